@@ -0,0 +1,73 @@
+//! Load pipeline benchmarks against generated documents
+//!
+//! Run with `cargo bench -p flow-writer-core`. Each section carries roughly 1KB
+//! of markdown, so the 10,000-section document is about 10MB of XML.
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use flow_writer_core::models::{ContextDocument, SECTION_TYPES};
+use flow_writer_core::parsers::parse_xml;
+use flow_writer_core::processors::{build_variable_map, resolve_section_tree};
+use flow_writer_core::serializers::serialize_xml;
+use flow_writer_core::validators::schema_validator::validate_schema;
+use std::hint::black_box;
+
+const SIZES: &[usize] = &[100, 1_000, 10_000];
+
+fn generate_document(section_count: usize) -> ContextDocument {
+    let paragraph = "The ${productName} team reviews **${goal}** every sprint; \
+        see [@ref-1] and the checklist below.\n\n- [ ] Draft\n- [ ] Review\n- [x] Ship\n\n";
+
+    (0..section_count)
+        .fold(
+            ContextDocument::builder()
+                .title("Benchmark Document")
+                .author("Bench")
+                .created("2025-10-09")
+                .variable("productName", "Flow Writer")
+                .variable("goal", "Ship v1"),
+            |builder, i| {
+                let content = format!("# Section {}\n\n{}", i, paragraph.repeat(5));
+                builder.section(format!("section-{}", i), SECTION_TYPES[i % SECTION_TYPES.len()], content)
+            },
+        )
+        .flow("```mermaid\nflowchart TD\n  A[Intent] --> B[Process]\n```")
+        .build()
+        .expect("generated document is valid")
+}
+
+fn bench_pipeline(c: &mut Criterion) {
+    let mut group = c.benchmark_group("document_pipeline");
+    group.sample_size(10);
+
+    for &size in SIZES {
+        let doc = generate_document(size);
+        let xml = serialize_xml(&doc);
+        group.throughput(Throughput::Bytes(xml.len() as u64));
+
+        group.bench_with_input(BenchmarkId::new("validate", size), &xml, |b, xml| {
+            b.iter(|| validate_schema(black_box(xml)).unwrap())
+        });
+
+        group.bench_with_input(BenchmarkId::new("parse", size), &xml, |b, xml| {
+            b.iter(|| parse_xml(black_box(xml)).unwrap())
+        });
+
+        group.bench_with_input(BenchmarkId::new("resolve", size), &doc, |b, doc| {
+            let var_map = build_variable_map(&doc.variables);
+            b.iter_batched(
+                || doc.sections.clone(),
+                |mut sections| resolve_section_tree(black_box(&mut sections), &var_map),
+                criterion::BatchSize::LargeInput,
+            )
+        });
+
+        group.bench_with_input(BenchmarkId::new("serialize", size), &doc, |b, doc| {
+            b.iter(|| serialize_xml(black_box(doc)))
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_pipeline);
+criterion_main!(benches);
@@ -0,0 +1,44 @@
+//! Micro-benchmark for the shared, lazily compiled regexes
+//!
+//! `compile_per_call` reproduces the old behaviour of building the Regex inside
+//! every call; `cached` is the library function using the shared static.
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use flow_writer_core::parsers::parse_mermaid;
+use flow_writer_core::processors::resolve_variables;
+use regex::Regex;
+use std::collections::HashMap;
+use std::hint::black_box;
+
+const CONTENT: &str = "# Intent\nUser: ${userName}\nGoal: ${goal}\nUnknown: ${missing}";
+
+const MERMAID: &str = "```mermaid\nflowchart TD\n  A[Intent] --> B[Evaluation]\n  B -->|cond: score >= 85| C[Process]\n  B -->|Retry| A\n  C --> D(Done)\n  click A \"#intent-1\" \"Jump to Intent\"\n```";
+
+fn resolve_compile_per_call(content: &str, variables: &HashMap<String, String>) -> String {
+    let re = Regex::new(r"\$\{([a-zA-Z_][a-zA-Z0-9_]*)\}").unwrap();
+    re.replace_all(content, |caps: &regex::Captures| {
+        variables.get(&caps[1]).cloned().unwrap_or_else(|| caps[0].to_string())
+    })
+    .into_owned()
+}
+
+fn bench_resolve_variables(c: &mut Criterion) {
+    let variables: HashMap<String, String> = [("userName", "Jeremy"), ("goal", "Ship v1")]
+        .into_iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect();
+
+    let mut group = c.benchmark_group("resolve_variables");
+    group.bench_function("compile_per_call", |b| {
+        b.iter(|| resolve_compile_per_call(black_box(CONTENT), &variables))
+    });
+    group.bench_function("cached", |b| b.iter(|| resolve_variables(black_box(CONTENT), &variables)));
+    group.finish();
+}
+
+fn bench_parse_mermaid(c: &mut Criterion) {
+    c.bench_function("parse_mermaid", |b| b.iter(|| parse_mermaid(black_box(MERMAID)).unwrap()));
+}
+
+criterion_group!(benches, bench_resolve_variables, bench_parse_mermaid);
+criterion_main!(benches);
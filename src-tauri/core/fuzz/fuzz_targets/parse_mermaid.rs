@@ -0,0 +1,14 @@
+#![no_main]
+
+use flow_writer_core::parsers::{extract_mermaid_from_markdown, parse_click_actions, parse_mermaid};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    let Ok(code) = std::str::from_utf8(data) else {
+        return;
+    };
+
+    let _ = extract_mermaid_from_markdown(code);
+    let _ = parse_mermaid(code);
+    let _ = parse_click_actions(code);
+});
@@ -0,0 +1,23 @@
+#![no_main]
+
+use flow_writer_core::parsers::parse_xml;
+use flow_writer_core::serializers::serialize_xml;
+use flow_writer_core::validators::schema_validator::validate_schema;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    let Ok(xml) = std::str::from_utf8(data) else {
+        return;
+    };
+
+    let _ = validate_schema(xml);
+
+    // Anything we accept must serialize to XML we accept again, unchanged
+    if let Ok(doc) = parse_xml(xml) {
+        let serialized = serialize_xml(&doc);
+        if validate_schema(xml).is_ok() {
+            assert!(validate_schema(&serialized).is_ok());
+        }
+        assert!(parse_xml(&serialized).is_ok());
+    }
+});
@@ -26,6 +26,21 @@ pub enum ContextError {
     #[error("Schema validation failed: {0}")]
     SchemaValidationError(String),
 
+    #[error("Section not found: {0}")]
+    SectionNotFound(String),
+
+    #[error("Invalid argument: {0}")]
+    InvalidArgument(String),
+
+    #[error("Reference error: {0}")]
+    ReferenceError(String),
+
+    #[error("Plugin error: {0}")]
+    PluginError(String),
+
+    #[error("Script error: {0}")]
+    ScriptError(String),
+
     #[error("IO error: {0}")]
     IoError(#[from] std::io::Error),
 
@@ -0,0 +1,557 @@
+use std::io::{Cursor, Write};
+use pulldown_cmark::{Event, HeadingLevel, Parser, Tag, TagEnd};
+use quick_xml::escape::escape;
+use zip::write::SimpleFileOptions;
+use zip::ZipWriter;
+use crate::error::{ContextError, Result};
+use crate::models::*;
+use super::flow_svg::{render_flow_png, PngImage};
+use super::section_exporter::markdown_options;
+
+/// Pixels per SVG unit when rasterizing the flow diagram, sharp enough for print
+const FLOW_IMAGE_SCALE: f32 = 2.0;
+
+/// Widest the flow image may be: the text width of a Letter page with 1" margins
+const MAX_IMAGE_WIDTH_EMU: u64 = 6 * 914_400;
+
+const EMU_PER_PIXEL: u64 = 9_525;
+
+/// Numbering instance shared by all bullet lists; ordered lists get their own so they restart at their start value
+const BULLET_NUM_ID: u32 = 1;
+
+/// Export the document as a Word (.docx) file
+///
+/// The title becomes the Title paragraph, markdown headings map to Word's
+/// built-in Heading styles, lists to Word numbering, task list items to
+/// checkbox content controls and tables to Word tables. The flow diagram is
+/// embedded as a PNG at the end. Expects variables to be resolved already,
+/// as in `flow_service::load_context_document`.
+pub fn export_docx(doc: &ContextDocument) -> Result<Vec<u8>> {
+    let mut writer = BodyWriter::default();
+    writer.paragraph("Title", &doc.meta.title);
+    if !doc.meta.description.is_empty() {
+        writer.paragraph("Subtitle", &doc.meta.description);
+    }
+    for section in &doc.sections {
+        writer.markdown(&section.content);
+        for child in &section.children {
+            writer.markdown(&child.content);
+        }
+    }
+
+    let flow_image = match &doc.flow_graph {
+        Some(flow) if !flow.parsed_graph.nodes.is_empty() => {
+            let image = render_flow_png(flow, FLOW_IMAGE_SCALE)?;
+            if let Some(title) = &flow.title {
+                writer.paragraph("Heading1", title);
+            }
+            writer.image(&image, &doc.meta.title);
+            Some(image)
+        }
+        _ => None,
+    };
+
+    write_package(doc, &writer, flow_image.as_ref())
+        .map_err(|e| ContextError::SerializationError(format!("DOCX: {}", e)))
+}
+
+fn write_package(doc: &ContextDocument, body: &BodyWriter, image: Option<&PngImage>) -> zip::result::ZipResult<Vec<u8>> {
+    let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+    let options = SimpleFileOptions::default();
+    let add = |zip: &mut ZipWriter<Cursor<Vec<u8>>>, name: &str, content: &[u8]| -> zip::result::ZipResult<()> {
+        zip.start_file(name, options)?;
+        zip.write_all(content)?;
+        Ok(())
+    };
+
+    add(&mut zip, "[Content_Types].xml", CONTENT_TYPES.as_bytes())?;
+    add(&mut zip, "_rels/.rels", PACKAGE_RELS.as_bytes())?;
+    add(&mut zip, "docProps/core.xml", core_properties(&doc.meta).as_bytes())?;
+    add(&mut zip, "word/_rels/document.xml.rels", document_rels(image.is_some()).as_bytes())?;
+    add(&mut zip, "word/styles.xml", STYLES.as_bytes())?;
+    add(&mut zip, "word/numbering.xml", body.numbering().as_bytes())?;
+    add(&mut zip, "word/document.xml", body.document().as_bytes())?;
+    if let Some(image) = image {
+        add(&mut zip, "word/media/flow.png", &image.data)?;
+    }
+    Ok(zip.finish()?.into_inner())
+}
+
+#[derive(Default)]
+struct Paragraph {
+    style: Option<&'static str>,
+    /// Numbering instance and level
+    numbering: Option<(u32, usize)>,
+    indent_level: Option<usize>,
+    runs: String,
+}
+
+/// Builds the `<w:body>` content from markdown events
+#[derive(Default)]
+struct BodyWriter {
+    body: String,
+    paragraph: Option<Paragraph>,
+    /// Numbering instance of each open list, innermost last
+    lists: Vec<u32>,
+    /// Start value of each ordered list's numbering instance, in instance order
+    ordered_starts: Vec<u64>,
+    bold: usize,
+    italic: usize,
+    strike: usize,
+    quote: usize,
+    in_code_block: bool,
+}
+
+impl BodyWriter {
+    fn paragraph(&mut self, style: &'static str, text: &str) {
+        self.start_paragraph(Some(style));
+        self.text_run(text);
+        self.flush();
+    }
+
+    fn markdown(&mut self, markdown: &str) {
+        for event in Parser::new_ext(markdown, markdown_options()) {
+            self.event(event);
+        }
+        self.flush();
+    }
+
+    fn event(&mut self, event: Event) {
+        match event {
+            Event::Start(Tag::Heading { level, .. }) => self.start_paragraph(Some(heading_style(level))),
+            Event::Start(Tag::Paragraph) => {
+                // The first paragraph of a loose list item continues the item's paragraph
+                let continues_item = self.paragraph.as_ref().is_some_and(|p| p.runs.is_empty() && p.numbering.is_some());
+                if !continues_item {
+                    let style = if self.quote > 0 { "Quote" } else { "Normal" };
+                    self.start_paragraph(Some(style));
+                }
+            }
+            Event::Start(Tag::CodeBlock(_)) => {
+                self.start_paragraph(Some("Code"));
+                self.in_code_block = true;
+            }
+            Event::End(TagEnd::CodeBlock) => {
+                self.in_code_block = false;
+                self.flush();
+            }
+            Event::End(TagEnd::Heading(_)) | Event::End(TagEnd::Paragraph) | Event::End(TagEnd::Item) => self.flush(),
+            Event::Start(Tag::BlockQuote(_)) => self.quote += 1,
+            Event::End(TagEnd::BlockQuote(_)) => self.quote -= 1,
+            Event::Start(Tag::List(start)) => {
+                self.flush();
+                let num_id = match start {
+                    Some(start) => {
+                        self.ordered_starts.push(start);
+                        BULLET_NUM_ID + self.ordered_starts.len() as u32
+                    }
+                    None => BULLET_NUM_ID,
+                };
+                self.lists.push(num_id);
+            }
+            Event::End(TagEnd::List(_)) => {
+                self.flush();
+                self.lists.pop();
+            }
+            Event::Start(Tag::Item) => {
+                self.start_paragraph(Some("ListParagraph"));
+                if let (Some(paragraph), Some(&num_id)) = (self.paragraph.as_mut(), self.lists.last()) {
+                    paragraph.numbering = Some((num_id, self.lists.len() - 1));
+                }
+            }
+            Event::TaskListMarker(checked) => {
+                let depth = self.lists.len().saturating_sub(1);
+                if let Some(paragraph) = self.paragraph.as_mut() {
+                    paragraph.numbering = None;
+                    paragraph.indent_level = Some(depth);
+                    paragraph.runs.push_str(&checkbox(checked));
+                    paragraph.runs.push_str(&run("", " "));
+                }
+            }
+            Event::Start(Tag::Table(_)) => {
+                self.flush();
+                self.body.push_str(
+                    "<w:tbl><w:tblPr><w:tblStyle w:val=\"TableGrid\"/><w:tblW w:w=\"0\" w:type=\"auto\"/></w:tblPr>",
+                );
+            }
+            Event::End(TagEnd::Table) => self.body.push_str("</w:tbl>"),
+            Event::Start(Tag::TableHead) => {
+                self.body.push_str("<w:tr><w:trPr><w:tblHeader/></w:trPr>");
+                self.bold += 1;
+            }
+            Event::End(TagEnd::TableHead) => {
+                self.body.push_str("</w:tr>");
+                self.bold -= 1;
+            }
+            Event::Start(Tag::TableRow) => self.body.push_str("<w:tr>"),
+            Event::End(TagEnd::TableRow) => self.body.push_str("</w:tr>"),
+            Event::Start(Tag::TableCell) => {
+                self.body.push_str("<w:tc>");
+                self.start_paragraph(None);
+            }
+            Event::End(TagEnd::TableCell) => {
+                self.flush();
+                self.body.push_str("</w:tc>");
+            }
+            Event::Start(Tag::Strong) => self.bold += 1,
+            Event::End(TagEnd::Strong) => self.bold -= 1,
+            Event::Start(Tag::Emphasis) => self.italic += 1,
+            Event::End(TagEnd::Emphasis) => self.italic -= 1,
+            Event::Start(Tag::Strikethrough) => self.strike += 1,
+            Event::End(TagEnd::Strikethrough) => self.strike -= 1,
+            Event::Text(text) => self.text_run(&text),
+            Event::Code(code) => {
+                let properties = format!("{}<w:rStyle w:val=\"CodeChar\"/>", self.run_properties());
+                self.push_run(&properties, &code);
+            }
+            Event::SoftBreak => self.text_run(" "),
+            Event::HardBreak => self.push_raw("<w:r><w:br/></w:r>"),
+            Event::Rule => {
+                self.flush();
+                self.body.push_str(
+                    "<w:p><w:pPr><w:pBdr><w:bottom w:val=\"single\" w:sz=\"6\" w:space=\"1\" w:color=\"auto\"/></w:pBdr></w:pPr></w:p>",
+                );
+            }
+            _ => {}
+        }
+    }
+
+    fn start_paragraph(&mut self, style: Option<&'static str>) {
+        self.flush();
+        self.paragraph = Some(Paragraph { style, ..Paragraph::default() });
+    }
+
+    /// Write out the open paragraph, if any
+    fn flush(&mut self) {
+        let Some(paragraph) = self.paragraph.take() else {
+            return;
+        };
+        let mut properties = String::new();
+        if let Some(style) = paragraph.style {
+            properties.push_str(&format!("<w:pStyle w:val=\"{}\"/>", style));
+        }
+        if let Some((num_id, level)) = paragraph.numbering {
+            properties.push_str(&format!("<w:numPr><w:ilvl w:val=\"{}\"/><w:numId w:val=\"{}\"/></w:numPr>", level, num_id));
+        }
+        if let Some(level) = paragraph.indent_level {
+            properties.push_str(&format!("<w:ind w:left=\"{}\"/>", 360 * (level + 1)));
+        }
+        self.body.push_str(&format!("<w:p><w:pPr>{}</w:pPr>{}</w:p>", properties, paragraph.runs));
+    }
+
+    fn run_properties(&self) -> String {
+        let mut properties = String::new();
+        if self.bold > 0 {
+            properties.push_str("<w:b/>");
+        }
+        if self.italic > 0 {
+            properties.push_str("<w:i/>");
+        }
+        if self.strike > 0 {
+            properties.push_str("<w:strike/>");
+        }
+        properties
+    }
+
+    fn text_run(&mut self, text: &str) {
+        if self.in_code_block {
+            // Keep code lines as line breaks within the one code paragraph
+            let text = text.strip_suffix('\n').unwrap_or(text);
+            for (index, line) in text.split('\n').enumerate() {
+                if index > 0 {
+                    self.push_raw("<w:r><w:br/></w:r>");
+                }
+                self.push_run("", line);
+            }
+        } else {
+            let properties = self.run_properties();
+            self.push_run(&properties, text);
+        }
+    }
+
+    fn push_run(&mut self, properties: &str, text: &str) {
+        self.push_raw(&run(properties, text));
+    }
+
+    /// Append to the open paragraph, opening a plain one for stray inline content
+    fn push_raw(&mut self, xml: &str) {
+        if self.paragraph.is_none() {
+            self.start_paragraph(None);
+        }
+        if let Some(paragraph) = self.paragraph.as_mut() {
+            paragraph.runs.push_str(xml);
+        }
+    }
+
+    /// A paragraph holding the image inline, scaled down to the text width
+    fn image(&mut self, image: &PngImage, description: &str) {
+        let mut width = u64::from(image.width) * EMU_PER_PIXEL / FLOW_IMAGE_SCALE as u64;
+        let mut height = u64::from(image.height) * EMU_PER_PIXEL / FLOW_IMAGE_SCALE as u64;
+        if width > MAX_IMAGE_WIDTH_EMU {
+            height = height * MAX_IMAGE_WIDTH_EMU / width;
+            width = MAX_IMAGE_WIDTH_EMU;
+        }
+        self.start_paragraph(Some("Normal"));
+        self.push_raw(&format!(
+            concat!(
+                "<w:r><w:drawing><wp:inline distT=\"0\" distB=\"0\" distL=\"0\" distR=\"0\">",
+                "<wp:extent cx=\"{w}\" cy=\"{h}\"/><wp:docPr id=\"1\" name=\"Flow\" descr=\"{descr}\"/>",
+                "<a:graphic xmlns:a=\"http://schemas.openxmlformats.org/drawingml/2006/main\">",
+                "<a:graphicData uri=\"http://schemas.openxmlformats.org/drawingml/2006/picture\">",
+                "<pic:pic xmlns:pic=\"http://schemas.openxmlformats.org/drawingml/2006/picture\">",
+                "<pic:nvPicPr><pic:cNvPr id=\"1\" name=\"flow.png\"/><pic:cNvPicPr/></pic:nvPicPr>",
+                "<pic:blipFill><a:blip r:embed=\"rIdFlow\"/><a:stretch><a:fillRect/></a:stretch></pic:blipFill>",
+                "<pic:spPr><a:xfrm><a:off x=\"0\" y=\"0\"/><a:ext cx=\"{w}\" cy=\"{h}\"/></a:xfrm>",
+                "<a:prstGeom prst=\"rect\"><a:avLst/></a:prstGeom></pic:spPr>",
+                "</pic:pic></a:graphicData></a:graphic></wp:inline></w:drawing></w:r>"
+            ),
+            w = width,
+            h = height,
+            descr = escape(format!("Flow diagram of {}", description).as_str())
+        ));
+        self.flush();
+    }
+
+    fn document(&self) -> String {
+        format!(
+            concat!(
+                "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\n",
+                "<w:document xmlns:w=\"http://schemas.openxmlformats.org/wordprocessingml/2006/main\" ",
+                "xmlns:r=\"http://schemas.openxmlformats.org/officeDocument/2006/relationships\" ",
+                "xmlns:wp=\"http://schemas.openxmlformats.org/drawingml/2006/wordprocessingDrawing\" ",
+                "xmlns:w14=\"http://schemas.microsoft.com/office/word/2010/wordml\" ",
+                "xmlns:mc=\"http://schemas.openxmlformats.org/markup-compatibility/2006\" mc:Ignorable=\"w14\">",
+                "<w:body>{}<w:sectPr><w:pgMar w:top=\"1440\" w:right=\"1440\" w:bottom=\"1440\" w:left=\"1440\" ",
+                "w:header=\"720\" w:footer=\"720\" w:gutter=\"0\"/></w:sectPr></w:body></w:document>"
+            ),
+            self.body
+        )
+    }
+
+    fn numbering(&self) -> String {
+        let mut xml = String::from(concat!(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\n",
+            "<w:numbering xmlns:w=\"http://schemas.openxmlformats.org/wordprocessingml/2006/main\">"
+        ));
+        xml.push_str(&abstract_numbering(0, "bullet", &["\u{2022}", "\u{25E6}", "\u{25AA}"]));
+        xml.push_str(&abstract_numbering(1, "decimal", &["%1.", "%2.", "%3."]));
+        xml.push_str(&format!("<w:num w:numId=\"{}\"><w:abstractNumId w:val=\"0\"/></w:num>", BULLET_NUM_ID));
+        for (index, start) in self.ordered_starts.iter().enumerate() {
+            xml.push_str(&format!(
+                "<w:num w:numId=\"{}\"><w:abstractNumId w:val=\"1\"/><w:lvlOverride w:ilvl=\"0\"><w:startOverride w:val=\"{}\"/></w:lvlOverride></w:num>",
+                BULLET_NUM_ID + 1 + index as u32,
+                start
+            ));
+        }
+        xml.push_str("</w:numbering>");
+        xml
+    }
+}
+
+fn heading_style(level: HeadingLevel) -> &'static str {
+    match level {
+        HeadingLevel::H1 => "Heading1",
+        HeadingLevel::H2 => "Heading2",
+        HeadingLevel::H3 => "Heading3",
+        _ => "Heading4",
+    }
+}
+
+fn run(properties: &str, text: &str) -> String {
+    format!(
+        "<w:r><w:rPr>{}</w:rPr><w:t xml:space=\"preserve\">{}</w:t></w:r>",
+        properties,
+        escape(text)
+    )
+}
+
+/// A checkbox content control that can be ticked in Word
+fn checkbox(checked: bool) -> String {
+    format!(
+        concat!(
+            "<w:sdt><w:sdtPr><w14:checkbox><w14:checked w14:val=\"{}\"/>",
+            "<w14:checkedState w14:val=\"2612\" w14:font=\"MS Gothic\"/>",
+            "<w14:uncheckedState w14:val=\"2610\" w14:font=\"MS Gothic\"/></w14:checkbox></w:sdtPr>",
+            "<w:sdtContent><w:r><w:rPr><w:rFonts w:ascii=\"MS Gothic\" w:eastAsia=\"MS Gothic\" w:hAnsi=\"MS Gothic\"/></w:rPr>",
+            "<w:t>{}</w:t></w:r></w:sdtContent></w:sdt>"
+        ),
+        u8::from(checked),
+        if checked { '\u{2612}' } else { '\u{2610}' }
+    )
+}
+
+fn abstract_numbering(id: u32, format: &str, level_texts: &[&str]) -> String {
+    let levels: String = level_texts
+        .iter()
+        .enumerate()
+        .map(|(level, text)| {
+            format!(
+                concat!(
+                    "<w:lvl w:ilvl=\"{level}\"><w:start w:val=\"1\"/><w:numFmt w:val=\"{format}\"/>",
+                    "<w:lvlText w:val=\"{text}\"/><w:lvlJc w:val=\"left\"/>",
+                    "<w:pPr><w:ind w:left=\"{left}\" w:hanging=\"360\"/></w:pPr></w:lvl>"
+                ),
+                level = level,
+                format = format,
+                text = text,
+                left = 720 * (level + 1)
+            )
+        })
+        .collect();
+    format!("<w:abstractNum w:abstractNumId=\"{}\">{}</w:abstractNum>", id, levels)
+}
+
+fn core_properties(meta: &MetaData) -> String {
+    format!(
+        concat!(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\n",
+            "<cp:coreProperties xmlns:cp=\"http://schemas.openxmlformats.org/package/2006/metadata/core-properties\" ",
+            "xmlns:dc=\"http://purl.org/dc/elements/1.1/\">",
+            "<dc:title>{}</dc:title><dc:creator>{}</dc:creator><dc:description>{}</dc:description>",
+            "<cp:keywords>{}</cp:keywords></cp:coreProperties>"
+        ),
+        escape(meta.title.as_str()),
+        escape(meta.author.as_str()),
+        escape(meta.description.as_str()),
+        escape(meta.tags.join(", ").as_str())
+    )
+}
+
+fn document_rels(with_image: bool) -> String {
+    let image = if with_image {
+        "<Relationship Id=\"rIdFlow\" Type=\"http://schemas.openxmlformats.org/officeDocument/2006/relationships/image\" Target=\"media/flow.png\"/>"
+    } else {
+        ""
+    };
+    format!(
+        concat!(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\n",
+            "<Relationships xmlns=\"http://schemas.openxmlformats.org/package/2006/relationships\">",
+            "<Relationship Id=\"rIdStyles\" Type=\"http://schemas.openxmlformats.org/officeDocument/2006/relationships/styles\" Target=\"styles.xml\"/>",
+            "<Relationship Id=\"rIdNumbering\" Type=\"http://schemas.openxmlformats.org/officeDocument/2006/relationships/numbering\" Target=\"numbering.xml\"/>",
+            "{}</Relationships>"
+        ),
+        image
+    )
+}
+
+const CONTENT_TYPES: &str = concat!(
+    "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\n",
+    "<Types xmlns=\"http://schemas.openxmlformats.org/package/2006/content-types\">",
+    "<Default Extension=\"rels\" ContentType=\"application/vnd.openxmlformats-package.relationships+xml\"/>",
+    "<Default Extension=\"xml\" ContentType=\"application/xml\"/>",
+    "<Default Extension=\"png\" ContentType=\"image/png\"/>",
+    "<Override PartName=\"/word/document.xml\" ContentType=\"application/vnd.openxmlformats-officedocument.wordprocessingml.document.main+xml\"/>",
+    "<Override PartName=\"/word/styles.xml\" ContentType=\"application/vnd.openxmlformats-officedocument.wordprocessingml.styles+xml\"/>",
+    "<Override PartName=\"/word/numbering.xml\" ContentType=\"application/vnd.openxmlformats-officedocument.wordprocessingml.numbering+xml\"/>",
+    "<Override PartName=\"/docProps/core.xml\" ContentType=\"application/vnd.openxmlformats-package.core-properties+xml\"/>",
+    "</Types>"
+);
+
+const PACKAGE_RELS: &str = concat!(
+    "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\n",
+    "<Relationships xmlns=\"http://schemas.openxmlformats.org/package/2006/relationships\">",
+    "<Relationship Id=\"rId1\" Type=\"http://schemas.openxmlformats.org/officeDocument/2006/relationships/officeDocument\" Target=\"word/document.xml\"/>",
+    "<Relationship Id=\"rId2\" Type=\"http://schemas.openxmlformats.org/package/2006/relationships/metadata/core-properties\" Target=\"docProps/core.xml\"/>",
+    "</Relationships>"
+);
+
+/// Styles named like Word's built-ins so they pick up the user's theme when restyled
+const STYLES: &str = concat!(
+    "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\n",
+    "<w:styles xmlns:w=\"http://schemas.openxmlformats.org/wordprocessingml/2006/main\">",
+    "<w:docDefaults><w:rPrDefault><w:rPr><w:rFonts w:ascii=\"Calibri\" w:hAnsi=\"Calibri\" w:eastAsia=\"Calibri\" w:cs=\"Calibri\"/>",
+    "<w:sz w:val=\"22\"/></w:rPr></w:rPrDefault><w:pPrDefault><w:pPr><w:spacing w:after=\"160\" w:line=\"259\" w:lineRule=\"auto\"/></w:pPr></w:pPrDefault></w:docDefaults>",
+    "<w:style w:type=\"paragraph\" w:default=\"1\" w:styleId=\"Normal\"><w:name w:val=\"Normal\"/><w:qFormat/></w:style>",
+    "<w:style w:type=\"paragraph\" w:styleId=\"Title\"><w:name w:val=\"Title\"/><w:basedOn w:val=\"Normal\"/><w:next w:val=\"Normal\"/><w:qFormat/>",
+    "<w:pPr><w:spacing w:after=\"240\"/></w:pPr><w:rPr><w:sz w:val=\"56\"/></w:rPr></w:style>",
+    "<w:style w:type=\"paragraph\" w:styleId=\"Subtitle\"><w:name w:val=\"Subtitle\"/><w:basedOn w:val=\"Normal\"/><w:next w:val=\"Normal\"/><w:qFormat/>",
+    "<w:rPr><w:color w:val=\"5A5A5A\"/><w:sz w:val=\"28\"/></w:rPr></w:style>",
+    "<w:style w:type=\"paragraph\" w:styleId=\"Heading1\"><w:name w:val=\"heading 1\"/><w:basedOn w:val=\"Normal\"/><w:next w:val=\"Normal\"/><w:qFormat/>",
+    "<w:pPr><w:keepNext/><w:spacing w:before=\"360\" w:after=\"120\"/><w:outlineLvl w:val=\"0\"/></w:pPr><w:rPr><w:b/><w:color w:val=\"2F5496\"/><w:sz w:val=\"36\"/></w:rPr></w:style>",
+    "<w:style w:type=\"paragraph\" w:styleId=\"Heading2\"><w:name w:val=\"heading 2\"/><w:basedOn w:val=\"Normal\"/><w:next w:val=\"Normal\"/><w:qFormat/>",
+    "<w:pPr><w:keepNext/><w:spacing w:before=\"240\" w:after=\"80\"/><w:outlineLvl w:val=\"1\"/></w:pPr><w:rPr><w:b/><w:color w:val=\"2F5496\"/><w:sz w:val=\"30\"/></w:rPr></w:style>",
+    "<w:style w:type=\"paragraph\" w:styleId=\"Heading3\"><w:name w:val=\"heading 3\"/><w:basedOn w:val=\"Normal\"/><w:next w:val=\"Normal\"/><w:qFormat/>",
+    "<w:pPr><w:keepNext/><w:spacing w:before=\"200\" w:after=\"60\"/><w:outlineLvl w:val=\"2\"/></w:pPr><w:rPr><w:b/><w:sz w:val=\"26\"/></w:rPr></w:style>",
+    "<w:style w:type=\"paragraph\" w:styleId=\"Heading4\"><w:name w:val=\"heading 4\"/><w:basedOn w:val=\"Normal\"/><w:next w:val=\"Normal\"/><w:qFormat/>",
+    "<w:pPr><w:keepNext/><w:outlineLvl w:val=\"3\"/></w:pPr><w:rPr><w:b/><w:i/></w:rPr></w:style>",
+    "<w:style w:type=\"paragraph\" w:styleId=\"ListParagraph\"><w:name w:val=\"List Paragraph\"/><w:basedOn w:val=\"Normal\"/><w:qFormat/>",
+    "<w:pPr><w:spacing w:after=\"40\"/><w:contextualSpacing/></w:pPr></w:style>",
+    "<w:style w:type=\"paragraph\" w:styleId=\"Quote\"><w:name w:val=\"Quote\"/><w:basedOn w:val=\"Normal\"/><w:qFormat/>",
+    "<w:pPr><w:ind w:left=\"720\"/></w:pPr><w:rPr><w:i/><w:color w:val=\"404040\"/></w:rPr></w:style>",
+    "<w:style w:type=\"paragraph\" w:styleId=\"Code\"><w:name w:val=\"Code\"/><w:basedOn w:val=\"Normal\"/>",
+    "<w:pPr><w:shd w:val=\"clear\" w:color=\"auto\" w:fill=\"F2F2F2\"/><w:spacing w:after=\"160\" w:line=\"240\" w:lineRule=\"auto\"/></w:pPr>",
+    "<w:rPr><w:rFonts w:ascii=\"Consolas\" w:hAnsi=\"Consolas\" w:cs=\"Consolas\"/><w:sz w:val=\"19\"/></w:rPr></w:style>",
+    "<w:style w:type=\"character\" w:styleId=\"CodeChar\"><w:name w:val=\"Code Char\"/>",
+    "<w:rPr><w:rFonts w:ascii=\"Consolas\" w:hAnsi=\"Consolas\" w:cs=\"Consolas\"/><w:shd w:val=\"clear\" w:color=\"auto\" w:fill=\"F2F2F2\"/></w:rPr></w:style>",
+    "<w:style w:type=\"table\" w:styleId=\"TableGrid\"><w:name w:val=\"Table Grid\"/><w:tblPr><w:tblBorders>",
+    "<w:top w:val=\"single\" w:sz=\"4\" w:space=\"0\" w:color=\"auto\"/><w:left w:val=\"single\" w:sz=\"4\" w:space=\"0\" w:color=\"auto\"/>",
+    "<w:bottom w:val=\"single\" w:sz=\"4\" w:space=\"0\" w:color=\"auto\"/><w:right w:val=\"single\" w:sz=\"4\" w:space=\"0\" w:color=\"auto\"/>",
+    "<w:insideH w:val=\"single\" w:sz=\"4\" w:space=\"0\" w:color=\"auto\"/><w:insideV w:val=\"single\" w:sz=\"4\" w:space=\"0\" w:color=\"auto\"/>",
+    "</w:tblBorders><w:tblCellMar><w:left w:w=\"108\" w:type=\"dxa\"/><w:right w:w=\"108\" w:type=\"dxa\"/></w:tblCellMar></w:tblPr></w:style>",
+    "</w:styles>"
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Read;
+
+    fn read_part(docx: &[u8], name: &str) -> String {
+        let mut archive = zip::ZipArchive::new(Cursor::new(docx)).unwrap();
+        let mut part = String::new();
+        archive.by_name(name).unwrap().read_to_string(&mut part).unwrap();
+        part
+    }
+
+    fn body(markdown: &str) -> String {
+        let mut writer = BodyWriter::default();
+        writer.markdown(markdown);
+        writer.body
+    }
+
+    #[test]
+    fn test_headings_lists_and_checkboxes() {
+        let xml = body("# Process\nSome **bold** text\n\n1. First\n2. Second\n\n- [x] Done\n- [ ] Todo\n- Plain");
+
+        assert!(xml.contains("<w:pStyle w:val=\"Heading1\"/></w:pPr><w:r><w:rPr></w:rPr><w:t xml:space=\"preserve\">Process</w:t>"));
+        assert!(xml.contains("<w:rPr><w:b/></w:rPr><w:t xml:space=\"preserve\">bold</w:t>"));
+        assert!(xml.contains("<w:numId w:val=\"2\"/>"), "ordered list gets its own numbering");
+        assert_eq!(xml.matches("<w14:checkbox>").count(), 2);
+        assert!(xml.contains("<w14:checked w14:val=\"1\"/>"));
+        assert!(xml.contains("<w14:checked w14:val=\"0\"/>"));
+        assert_eq!(xml.matches("<w:numId w:val=\"1\"/>").count(), 1, "only the plain item keeps its bullet");
+    }
+
+    #[test]
+    fn test_tables_and_code_blocks() {
+        let xml = body("| A | B |\n|---|---|\n| 1 | 2 |\n\n```\nline 1\nline 2\n```");
+
+        assert_eq!(xml.matches("<w:tc>").count(), 4);
+        assert!(xml.contains("<w:tblHeader/>"));
+        assert!(xml.contains("<w:pStyle w:val=\"Code\"/>"));
+        assert!(xml.contains("line 1</w:t></w:r><w:r><w:br/></w:r>"));
+    }
+
+    #[test]
+    fn test_package_with_flow_image() {
+        let mut doc = ContextDocument::builder()
+            .title("Launch & Plan")
+            .section("intent-1", "intent", "# Intent\nShip v1")
+            .flow("flowchart TD\n  A[Intent] --> B[Process]")
+            .build()
+            .unwrap();
+        crate::parsers::mermaid_parser::enrich_flow_graph(doc.flow_graph.as_mut().unwrap()).unwrap();
+
+        let docx = export_docx(&doc).unwrap();
+
+        let document = read_part(&docx, "word/document.xml");
+        assert!(document.contains("Launch &amp; Plan"));
+        assert!(document.contains("r:embed=\"rIdFlow\""));
+        assert!(read_part(&docx, "word/_rels/document.xml.rels").contains("media/flow.png"));
+        assert!(read_part(&docx, "docProps/core.xml").contains("<dc:title>Launch &amp; Plan</dc:title>"));
+        roxmltree::Document::parse(&document).unwrap();
+        roxmltree::Document::parse(&read_part(&docx, "word/numbering.xml")).unwrap();
+        roxmltree::Document::parse(STYLES).unwrap();
+    }
+}
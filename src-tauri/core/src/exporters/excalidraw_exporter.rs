@@ -0,0 +1,209 @@
+use serde_json::{json, Value};
+use crate::models::*;
+use super::flow_svg::{box_edge_point, node_positions, NODE_HEIGHT, NODE_WIDTH};
+
+const FONT_SIZE: f64 = 20.0;
+const EDGE_LABEL_FONT_SIZE: f64 = 16.0;
+const LINE_HEIGHT: f64 = 1.25;
+
+/// Convert the flow graph into an Excalidraw scene (`.excalidraw` JSON)
+///
+/// Nodes become shapes with their label bound as text, edges become arrows
+/// bound to both nodes so they follow when a node is dragged in Excalidraw.
+/// Positions come from the saved canvas layout, or a computed layout when
+/// the graph has not been arranged. Element IDs are derived from node IDs,
+/// so exporting the same graph twice gives the same scene.
+pub fn export_excalidraw(flow: &FlowGraph) -> Value {
+    let graph = &flow.parsed_graph;
+    let positions = node_positions(flow);
+    let mut elements = Vec::new();
+
+    for node in &graph.nodes {
+        let Some(position) = positions.get(&node.id) else {
+            continue;
+        };
+        let shape_id = node_element_id(&node.id);
+        let text_id = format!("{}-label", shape_id);
+        let arrows: Vec<Value> = graph
+            .edges
+            .iter()
+            .enumerate()
+            .filter(|(_, edge)| edge.from == node.id || edge.to == node.id)
+            .map(|(index, _)| json!({ "id": edge_element_id(index), "type": "arrow" }))
+            .collect();
+        let mut bound = vec![json!({ "id": text_id, "type": "text" })];
+        bound.extend(arrows);
+
+        let (kind, roundness) = shape(&node.node_type);
+        let mut shape = element(&shape_id, kind, position.x - NODE_WIDTH / 2.0, position.y - NODE_HEIGHT / 2.0, NODE_WIDTH, NODE_HEIGHT);
+        shape["roundness"] = roundness;
+        shape["backgroundColor"] = json!("#e7ebff");
+        shape["boundElements"] = json!(bound);
+        elements.push(shape);
+        elements.push(text(&text_id, &node.label, position, FONT_SIZE, Some(&shape_id)));
+    }
+
+    for (index, edge) in graph.edges.iter().enumerate() {
+        let (Some(from), Some(to)) = (positions.get(&edge.from), positions.get(&edge.to)) else {
+            continue;
+        };
+        let (x1, y1) = box_edge_point(from, to);
+        let (x2, y2) = box_edge_point(to, from);
+        let arrow_id = edge_element_id(index);
+
+        let mut arrow = element(&arrow_id, "arrow", x1, y1, (x2 - x1).abs(), (y2 - y1).abs());
+        arrow["points"] = json!([[0.0, 0.0], [x2 - x1, y2 - y1]]);
+        arrow["lastCommittedPoint"] = Value::Null;
+        arrow["startBinding"] = json!({ "elementId": node_element_id(&edge.from), "focus": 0, "gap": 1 });
+        arrow["endBinding"] = json!({ "elementId": node_element_id(&edge.to), "focus": 0, "gap": 1 });
+        arrow["startArrowhead"] = Value::Null;
+        arrow["endArrowhead"] = json!("arrow");
+        arrow["roundness"] = json!({ "type": 2 });
+
+        if let Some(label) = &edge.label {
+            let label_id = format!("{}-label", arrow_id);
+            arrow["boundElements"] = json!([{ "id": label_id, "type": "text" }]);
+            let middle = NodePosition { x: (x1 + x2) / 2.0, y: (y1 + y2) / 2.0 };
+            elements.push(arrow);
+            elements.push(text(&label_id, label, &middle, EDGE_LABEL_FONT_SIZE, Some(&arrow_id)));
+        } else {
+            elements.push(arrow);
+        }
+    }
+
+    json!({
+        "type": "excalidraw",
+        "version": 2,
+        "source": "flow-writer",
+        "elements": elements,
+        "appState": { "viewBackgroundColor": "#ffffff", "gridSize": null },
+        "files": {}
+    })
+}
+
+fn node_element_id(node_id: &str) -> String {
+    format!("node-{}", node_id)
+}
+
+fn edge_element_id(index: usize) -> String {
+    format!("edge-{}", index)
+}
+
+/// Excalidraw has rectangles, ellipses and diamonds; other Mermaid shapes map to the closest
+fn shape(node_type: &NodeType) -> (&'static str, Value) {
+    match node_type {
+        NodeType::Circle => ("ellipse", json!({ "type": 2 })),
+        NodeType::Rhombus => ("diamond", json!({ "type": 2 })),
+        NodeType::RoundEdges | NodeType::Stadium => ("rectangle", json!({ "type": 3 })),
+        _ => ("rectangle", Value::Null),
+    }
+}
+
+/// A text element centred on `center`, bound to its container
+fn text(id: &str, content: &str, center: &NodePosition, font_size: f64, container_id: Option<&str>) -> Value {
+    let lines: Vec<&str> = content.lines().collect();
+    let longest = lines.iter().map(|line| line.chars().count()).max().unwrap_or(0);
+    // Rough metrics; Excalidraw re-measures bound text when the scene is opened
+    let width = longest as f64 * font_size * 0.55;
+    let height = lines.len().max(1) as f64 * font_size * LINE_HEIGHT;
+
+    let mut text = element(id, "text", center.x - width / 2.0, center.y - height / 2.0, width, height);
+    text["text"] = json!(content);
+    text["originalText"] = json!(content);
+    text["fontSize"] = json!(font_size);
+    text["fontFamily"] = json!(1);
+    text["textAlign"] = json!("center");
+    text["verticalAlign"] = json!("middle");
+    text["containerId"] = json!(container_id);
+    text["lineHeight"] = json!(LINE_HEIGHT);
+    text["autoResize"] = json!(true);
+    text
+}
+
+/// Fields every Excalidraw element carries
+fn element(id: &str, kind: &str, x: f64, y: f64, width: f64, height: f64) -> Value {
+    json!({
+        "id": id,
+        "type": kind,
+        "x": x,
+        "y": y,
+        "width": width,
+        "height": height,
+        "angle": 0,
+        "strokeColor": "#1e1e1e",
+        "backgroundColor": "transparent",
+        "fillStyle": "solid",
+        "strokeWidth": 2,
+        "strokeStyle": "solid",
+        "roughness": 1,
+        "opacity": 100,
+        "groupIds": [],
+        "frameId": null,
+        "roundness": null,
+        "seed": seed(id),
+        "version": 1,
+        "versionNonce": seed(&format!("{}-nonce", id)),
+        "isDeleted": false,
+        "boundElements": null,
+        "updated": 1,
+        "link": null,
+        "locked": false
+    })
+}
+
+/// Stable per-element seed for Excalidraw's hand-drawn jitter (FNV-1a)
+fn seed(id: &str) -> u32 {
+    let hash = id.bytes().fold(0x811c_9dc5_u32, |hash, byte| (hash ^ u32::from(byte)).wrapping_mul(0x0100_0193));
+    hash & 0x7fff_ffff
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn flow() -> FlowGraph {
+        let mut doc = ContextDocument::builder()
+            .title("Flow")
+            .flow("flowchart TD\n  A[Intent] -->|then| B[Process]\n  B --> C(Review)")
+            .build()
+            .unwrap();
+        let mut flow = doc.flow_graph.take().unwrap();
+        crate::parsers::mermaid_parser::enrich_flow_graph(&mut flow).unwrap();
+        flow
+    }
+
+    #[test]
+    fn test_scene_structure() {
+        let scene = export_excalidraw(&flow());
+
+        assert_eq!(scene["type"], "excalidraw");
+        let elements = scene["elements"].as_array().unwrap();
+        let count = |kind: &str| elements.iter().filter(|e| e["type"] == kind).count();
+        assert_eq!(count("rectangle"), 3);
+        assert_eq!(count("arrow"), 2);
+        assert_eq!(count("text"), 4, "three node labels and one edge label");
+
+        let review = elements.iter().find(|e| e["id"] == "node-C").unwrap();
+        assert_eq!(review["roundness"]["type"], 3);
+        let label = elements.iter().find(|e| e["id"] == "node-A-label").unwrap();
+        assert_eq!(label["containerId"], "node-A");
+        assert_eq!(label["text"], "Intent");
+    }
+
+    #[test]
+    fn test_arrows_are_bound_to_nodes() {
+        let scene = export_excalidraw(&flow());
+        let elements = scene["elements"].as_array().unwrap();
+
+        let arrow = elements.iter().find(|e| e["id"] == "edge-0").unwrap();
+        assert_eq!(arrow["startBinding"]["elementId"], "node-A");
+        assert_eq!(arrow["endBinding"]["elementId"], "node-B");
+        let node_b = elements.iter().find(|e| e["id"] == "node-B").unwrap();
+        let bound: Vec<&str> = node_b["boundElements"].as_array().unwrap().iter().map(|b| b["id"].as_str().unwrap()).collect();
+        assert_eq!(bound, vec!["node-B-label", "edge-0", "edge-1"]);
+
+        // Top-down flow: the arrow points downwards from A to B
+        assert!(arrow["points"][1][1].as_f64().unwrap() > 0.0);
+        assert_eq!(export_excalidraw(&flow()), scene, "export is deterministic");
+    }
+}
@@ -0,0 +1,291 @@
+use std::collections::BTreeMap;
+use std::sync::LazyLock;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use crate::models::*;
+use crate::parsers::mermaid_parser;
+use super::flow_svg::node_positions;
+
+/// Value of `format` in every export, so tools can recognise the file
+pub const FLOW_JSON_FORMAT: &str = "flow-writer-flow";
+/// Bumped on breaking changes to the layout described in `knowledge-docs/schema/flow-graph.schema.json`
+pub const FLOW_JSON_VERSION: u32 = 1;
+
+/// `style A fill:#f9f,stroke:#333`
+static STYLE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"^style\s+(\w+)\s+(.+?);?$").unwrap());
+/// `classDef done fill:#cfc`
+static CLASS_DEF: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"^classDef\s+([\w-]+)\s+(.+?);?$").unwrap());
+/// `class A,B done`
+static CLASS: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"^class\s+([\w,\s]+?)\s+([\w-]+);?$").unwrap());
+/// `A:::done` shorthand on a node declaration
+static CLASS_SHORTHAND: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"(\w+)(?:\[[^\]]*\]|\([^)]*\))?:::([\w-]+)").unwrap());
+
+/// A flow graph as standalone JSON for analysis tools
+///
+/// Reading one back only needs `nodes`; everything else has defaults.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase", default)]
+pub struct FlowJson {
+    pub format: String,
+    pub format_version: u32,
+    pub id: String,
+    pub version: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    /// `TD`, `BT`, `LR` or `RL`
+    pub direction: String,
+    pub nodes: Vec<FlowJsonNode>,
+    pub edges: Vec<GraphEdge>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub subgraphs: Vec<FlowJsonSubgraph>,
+    pub node_refs: Vec<NodeReference>,
+    /// Whether node positions come from the saved canvas layout rather than a computed one
+    pub positions_saved: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub viewport: Option<FlowJsonViewport>,
+    /// `classDef` name -> CSS-like properties
+    pub class_defs: BTreeMap<String, BTreeMap<String, String>>,
+    /// The diagram as written, for tools that do want to render it
+    pub mermaid_code: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct FlowJsonNode {
+    pub id: String,
+    /// The ID when left out
+    #[serde(default)]
+    pub label: String,
+    #[serde(default = "default_shape")]
+    pub shape: NodeType,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub section_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<NodeMetadata>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub position: Option<NodePosition>,
+    /// Properties from `style` lines for this node
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub style: BTreeMap<String, String>,
+    /// Classes from `class` lines and `:::` shorthand, in diagram order
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub classes: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct FlowJsonSubgraph {
+    pub id: String,
+    /// The ID when left out
+    #[serde(default)]
+    pub title: String,
+    /// Nodes placed in the block itself, not in nested blocks
+    #[serde(default)]
+    pub node_ids: Vec<String>,
+    /// The enclosing subgraph, which must be listed earlier
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parent_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub section_id: Option<String>,
+}
+
+fn default_shape() -> NodeType {
+    NodeType::Rectangle
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct FlowJsonViewport {
+    pub zoom: f64,
+    pub pan_x: f64,
+    pub pan_y: f64,
+}
+
+/// Describe an enriched flow graph (nodes, edges, click links, positions and styling) as [`FlowJson`]
+///
+/// Node positions are the saved canvas layout when it places every node,
+/// else a computed layout, like the other flow exports.
+pub fn export_flow_json(flow: &FlowGraph) -> FlowJson {
+    let code = mermaid_parser::extract_mermaid_from_markdown(&flow.mermaid_code).unwrap_or_else(|_| flow.mermaid_code.clone());
+    let styling = Styling::parse(&code);
+    let positions = node_positions(flow);
+    let positions_saved = flow
+        .layout
+        .as_ref()
+        .is_some_and(|layout| flow.parsed_graph.nodes.iter().all(|n| layout.positions.contains_key(&n.id)));
+
+    let nodes = flow
+        .parsed_graph
+        .nodes
+        .iter()
+        .map(|node| FlowJsonNode {
+            id: node.id.clone(),
+            label: node.label.clone(),
+            shape: node.node_type.clone(),
+            section_id: node.ref_section_id.clone(),
+            metadata: node.metadata.clone(),
+            position: positions.get(&node.id).copied(),
+            style: styling.styles.get(&node.id).cloned().unwrap_or_default(),
+            classes: styling.classes.get(&node.id).cloned().unwrap_or_default(),
+        })
+        .collect();
+
+    let direction = match mermaid_parser::parse_direction(&code) {
+        FlowDirection::TopDown => "TD",
+        FlowDirection::BottomUp => "BT",
+        FlowDirection::LeftRight => "LR",
+        FlowDirection::RightLeft => "RL",
+    };
+
+    FlowJson {
+        format: FLOW_JSON_FORMAT.to_string(),
+        format_version: FLOW_JSON_VERSION,
+        id: flow.id.clone(),
+        version: flow.version.clone(),
+        title: flow.title.clone(),
+        direction: direction.to_string(),
+        nodes,
+        edges: flow.parsed_graph.edges.clone(),
+        subgraphs: flow
+            .parsed_graph
+            .subgraphs
+            .iter()
+            .map(|subgraph| FlowJsonSubgraph {
+                id: subgraph.id.clone(),
+                title: subgraph.title.clone(),
+                node_ids: subgraph.node_ids.clone(),
+                parent_id: subgraph.parent_id.clone(),
+                section_id: subgraph.ref_section_id.clone(),
+            })
+            .collect(),
+        node_refs: flow.node_refs.clone(),
+        positions_saved,
+        viewport: flow.layout.as_ref().map(|layout| FlowJsonViewport {
+            zoom: layout.zoom,
+            pan_x: layout.pan_x,
+            pan_y: layout.pan_y,
+        }),
+        class_defs: styling.class_defs,
+        mermaid_code: flow.mermaid_code.clone(),
+    }
+}
+
+/// `style`, `classDef` and `class` statements of a diagram
+#[derive(Default)]
+struct Styling {
+    styles: BTreeMap<String, BTreeMap<String, String>>,
+    class_defs: BTreeMap<String, BTreeMap<String, String>>,
+    classes: BTreeMap<String, Vec<String>>,
+}
+
+impl Styling {
+    fn parse(code: &str) -> Self {
+        let mut styling = Styling::default();
+        for line in code.lines().map(str::trim).filter(|line| !line.starts_with("%%")) {
+            if let Some(caps) = STYLE.captures(line) {
+                styling.styles.entry(caps[1].to_string()).or_default().extend(properties(&caps[2]));
+            } else if let Some(caps) = CLASS_DEF.captures(line) {
+                styling.class_defs.entry(caps[1].to_string()).or_default().extend(properties(&caps[2]));
+            } else if let Some(caps) = CLASS.captures(line) {
+                for node_id in caps[1].split(',').map(str::trim).filter(|id| !id.is_empty()) {
+                    styling.add_class(node_id, &caps[2]);
+                }
+            } else {
+                for caps in CLASS_SHORTHAND.captures_iter(line) {
+                    styling.add_class(&caps[1], &caps[2]);
+                }
+            }
+        }
+        styling
+    }
+
+    fn add_class(&mut self, node_id: &str, class: &str) {
+        let classes = self.classes.entry(node_id.to_string()).or_default();
+        if !classes.iter().any(|existing| existing == class) {
+            classes.push(class.to_string());
+        }
+    }
+}
+
+/// `fill:#f9f,stroke-width:2px` as a property map
+fn properties(list: &str) -> BTreeMap<String, String> {
+    list.split(',')
+        .filter_map(|property| property.split_once(':'))
+        .map(|(name, value)| (name.trim().to_string(), value.trim().to_string()))
+        .filter(|(name, _)| !name.is_empty())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn flow(code: &str) -> FlowGraph {
+        let mut flow = FlowGraph {
+            id: "flow-1".to_string(),
+            version: "1.0".to_string(),
+            title: Some("Release".to_string()),
+            mermaid_code: code.to_string(),
+            parsed_graph: GraphStructure { nodes: vec![], edges: vec![], subgraphs: vec![] },
+            node_refs: vec![],
+            layout: None,
+            node_metadata: BTreeMap::new(),
+        };
+        mermaid_parser::enrich_flow_graph(&mut flow).unwrap();
+        flow
+    }
+
+    #[test]
+    fn test_export_flow_json() {
+        let code = "flowchart LR\n  A[Plan]:::todo --> B(Build)\n  click A \"#intent-1\"\n  style B fill:#f9f,stroke-width:2px\n  classDef todo fill:#eee\n  class A,B done";
+        let exported = export_flow_json(&flow(code));
+
+        assert_eq!(exported.format, FLOW_JSON_FORMAT);
+        assert_eq!(exported.direction, "LR");
+        assert!(!exported.positions_saved);
+        assert!(exported.nodes.iter().all(|node| node.position.is_some()));
+        assert_eq!(exported.nodes[0].section_id.as_deref(), Some("intent-1"));
+        assert_eq!(exported.nodes[0].classes, vec!["todo", "done"]);
+        assert_eq!(exported.nodes[1].style["stroke-width"], "2px");
+        assert_eq!(exported.class_defs["todo"]["fill"], "#eee");
+        assert_eq!(exported.edges.len(), 1);
+
+        let json = serde_json::to_value(&exported).unwrap();
+        assert_eq!(json["formatVersion"], 1);
+        assert_eq!(json["nodes"][0]["sectionId"], "intent-1");
+        assert_eq!(json["nodes"][1]["shape"], "roundedges");
+        assert!(json["nodes"][1].get("classes").is_some());
+        assert!(json.get("subgraphs").is_none());
+    }
+
+    #[test]
+    fn test_export_subgraphs() {
+        let exported = export_flow_json(&flow("flowchart TD\n  subgraph build[Build]\n    A[Compile] --> B[Test]\n  end\n  click build \"#process-1\""));
+        assert_eq!(exported.subgraphs.len(), 1);
+        assert_eq!(exported.subgraphs[0].node_ids, vec!["A", "B"]);
+
+        let json = serde_json::to_value(&exported).unwrap();
+        assert_eq!(json["subgraphs"][0]["title"], "Build");
+        assert_eq!(json["subgraphs"][0]["sectionId"], "process-1");
+        assert!(json["subgraphs"][0].get("parentId").is_none());
+    }
+
+    #[test]
+    fn test_saved_layout_is_used() {
+        let mut flow = flow("flowchart TD\n  A[One] --> B[Two]");
+        flow.layout = Some(FlowLayout {
+            zoom: 1.5,
+            pan_x: 10.0,
+            pan_y: 0.0,
+            positions: BTreeMap::from([
+                ("A".to_string(), NodePosition { x: 1.0, y: 2.0 }),
+                ("B".to_string(), NodePosition { x: 3.0, y: 4.0 }),
+            ]),
+        });
+        let exported = export_flow_json(&flow);
+
+        assert!(exported.positions_saved);
+        assert_eq!(exported.nodes[1].position, Some(NodePosition { x: 3.0, y: 4.0 }));
+        assert_eq!(exported.viewport.unwrap().zoom, 1.5);
+    }
+}
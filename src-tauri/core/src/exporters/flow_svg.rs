@@ -0,0 +1,196 @@
+use std::collections::BTreeMap;
+use quick_xml::escape::escape;
+use resvg::{tiny_skia, usvg};
+use crate::error::{ContextError, Result};
+use crate::models::*;
+use crate::parsers::mermaid_parser::parse_direction;
+use crate::processors::auto_layout::compute_layout;
+
+pub(crate) const NODE_WIDTH: f64 = 150.0;
+pub(crate) const NODE_HEIGHT: f64 = 50.0;
+const PADDING: f64 = 20.0;
+
+/// Render the flow graph as a standalone SVG
+///
+/// Uses the layout saved from the canvas when it places every node, and a
+/// computed layout otherwise. The SVG has a `viewBox` and no fixed size, so
+/// it scales to the width of whatever contains it. Expects `parsed_graph`
+/// to be filled in, as `flow_service::process_flow_graph` does.
+pub fn render_flow_svg(flow: &FlowGraph) -> String {
+    let graph = &flow.parsed_graph;
+    let positions = node_positions(flow);
+
+    let (mut min_x, mut min_y, mut max_x, mut max_y) = (f64::MAX, f64::MAX, f64::MIN, f64::MIN);
+    for position in positions.values() {
+        min_x = min_x.min(position.x - NODE_WIDTH / 2.0);
+        min_y = min_y.min(position.y - NODE_HEIGHT / 2.0);
+        max_x = max_x.max(position.x + NODE_WIDTH / 2.0);
+        max_y = max_y.max(position.y + NODE_HEIGHT / 2.0);
+    }
+    if positions.is_empty() {
+        (min_x, min_y, max_x, max_y) = (0.0, 0.0, NODE_WIDTH, NODE_HEIGHT);
+    }
+
+    let mut svg = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" viewBox=\"{} {} {} {}\" font-family=\"sans-serif\" font-size=\"14\">\n",
+        min_x - PADDING,
+        min_y - PADDING,
+        max_x - min_x + 2.0 * PADDING,
+        max_y - min_y + 2.0 * PADDING
+    );
+    svg.push_str(
+        "  <defs><marker id=\"arrow\" viewBox=\"0 0 10 10\" refX=\"10\" refY=\"5\" markerWidth=\"8\" markerHeight=\"8\" orient=\"auto\">\
+         <path d=\"M0,0 L10,5 L0,10 z\" fill=\"#555\"/></marker></defs>\n",
+    );
+
+    for edge in &graph.edges {
+        let (Some(from), Some(to)) = (positions.get(&edge.from), positions.get(&edge.to)) else {
+            continue;
+        };
+        let (x1, y1) = box_edge_point(from, to);
+        let (x2, y2) = box_edge_point(to, from);
+        svg.push_str(&format!(
+            "  <line x1=\"{x1:.1}\" y1=\"{y1:.1}\" x2=\"{x2:.1}\" y2=\"{y2:.1}\" stroke=\"#555\" stroke-width=\"1.5\" marker-end=\"url(#arrow)\"/>\n"
+        ));
+        if let Some(label) = &edge.label {
+            svg.push_str(&format!(
+                "  <text x=\"{:.1}\" y=\"{:.1}\" text-anchor=\"middle\" font-size=\"12\" fill=\"#333\">{}</text>\n",
+                (x1 + x2) / 2.0,
+                (y1 + y2) / 2.0 - 4.0,
+                escape(label.as_str())
+            ));
+        }
+    }
+
+    for node in &graph.nodes {
+        if let Some(position) = positions.get(&node.id) {
+            svg.push_str(&node_shape(node, position));
+            svg.push_str(&format!(
+                "  <text x=\"{:.1}\" y=\"{:.1}\" text-anchor=\"middle\" dominant-baseline=\"middle\">{}</text>\n",
+                position.x,
+                position.y,
+                escape(node.label.as_str())
+            ));
+        }
+    }
+
+    svg.push_str("</svg>\n");
+    svg
+}
+
+fn node_shape(node: &GraphNode, p: &NodePosition) -> String {
+    let (w, h) = (NODE_WIDTH / 2.0, NODE_HEIGHT / 2.0);
+    let style = "fill=\"#f5f7ff\" stroke=\"#3b4cca\" stroke-width=\"1.5\"";
+    match node.node_type {
+        NodeType::Circle => format!("  <circle cx=\"{:.1}\" cy=\"{:.1}\" r=\"{:.1}\" {style}/>\n", p.x, p.y, h),
+        NodeType::Rhombus => format!(
+            "  <polygon points=\"{:.1},{:.1} {:.1},{:.1} {:.1},{:.1} {:.1},{:.1}\" {style}/>\n",
+            p.x, p.y - h, p.x + w, p.y, p.x, p.y + h, p.x - w, p.y
+        ),
+        NodeType::Hexagon => format!(
+            "  <polygon points=\"{:.1},{:.1} {:.1},{:.1} {:.1},{:.1} {:.1},{:.1} {:.1},{:.1} {:.1},{:.1}\" {style}/>\n",
+            p.x - w + 15.0, p.y - h, p.x + w - 15.0, p.y - h, p.x + w, p.y,
+            p.x + w - 15.0, p.y + h, p.x - w + 15.0, p.y + h, p.x - w, p.y
+        ),
+        _ => {
+            let radius = match node.node_type {
+                NodeType::RoundEdges => 10.0,
+                NodeType::Stadium => h,
+                _ => 0.0,
+            };
+            format!(
+                "  <rect x=\"{:.1}\" y=\"{:.1}\" width=\"{NODE_WIDTH}\" height=\"{NODE_HEIGHT}\" rx=\"{radius}\" {style}/>\n",
+                p.x - w,
+                p.y - h
+            )
+        }
+    }
+}
+
+/// Node centres from the layout saved on the canvas if it places every node, else a computed layout
+pub(crate) fn node_positions(flow: &FlowGraph) -> BTreeMap<String, NodePosition> {
+    let graph = &flow.parsed_graph;
+    match &flow.layout {
+        Some(layout) if graph.nodes.iter().all(|n| layout.positions.contains_key(&n.id)) => layout.positions.clone(),
+        _ => compute_layout(graph, parse_direction(&flow.mermaid_code)).positions,
+    }
+}
+
+/// A rendered raster image with its pixel size
+#[derive(Debug, Clone, PartialEq)]
+pub struct PngImage {
+    pub data: Vec<u8>,
+    pub width: u32,
+    pub height: u32,
+}
+
+/// Render the flow graph as a PNG, `scale` pixels per SVG unit, on a white background
+///
+/// For formats that cannot embed SVG. Labels use the system fonts.
+pub fn render_flow_png(flow: &FlowGraph, scale: f32) -> Result<PngImage> {
+    let mut options = usvg::Options::default();
+    options.fontdb_mut().load_system_fonts();
+    let tree = usvg::Tree::from_str(&render_flow_svg(flow), &options)
+        .map_err(|e| ContextError::SerializationError(format!("Flow SVG: {}", e)))?;
+
+    let size = tree
+        .size()
+        .to_int_size()
+        .scale_by(scale)
+        .ok_or_else(|| ContextError::InvalidArgument(format!("Invalid image scale {}", scale)))?;
+    let mut pixmap = tiny_skia::Pixmap::new(size.width(), size.height())
+        .ok_or_else(|| ContextError::SerializationError("Flow image is too large".to_string()))?;
+    pixmap.fill(tiny_skia::Color::WHITE);
+    resvg::render(&tree, tiny_skia::Transform::from_scale(scale, scale), &mut pixmap.as_mut());
+
+    let data = pixmap
+        .encode_png()
+        .map_err(|e| ContextError::SerializationError(format!("Flow PNG: {}", e)))?;
+    Ok(PngImage {
+        data,
+        width: size.width(),
+        height: size.height(),
+    })
+}
+
+/// Where the line from `from` towards `to` leaves the node box around `from`
+pub(crate) fn box_edge_point(from: &NodePosition, to: &NodePosition) -> (f64, f64) {
+    let (dx, dy) = (to.x - from.x, to.y - from.y);
+    if dx == 0.0 && dy == 0.0 {
+        return (from.x, from.y);
+    }
+    let scale_x = if dx != 0.0 { (NODE_WIDTH / 2.0) / dx.abs() } else { f64::MAX };
+    let scale_y = if dy != 0.0 { (NODE_HEIGHT / 2.0) / dy.abs() } else { f64::MAX };
+    let scale = scale_x.min(scale_y).min(1.0);
+    (from.x + dx * scale, from.y + dy * scale)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_renders_nodes_edges_and_labels() {
+        let doc = ContextDocument::builder()
+            .title("Flow")
+            .flow("flowchart LR\n  A[Intent & goals] -->|next| B[Decide]\n  B --> C[Done]")
+            .build()
+            .unwrap();
+
+        let mut flow = doc.flow_graph.unwrap();
+        crate::parsers::mermaid_parser::enrich_flow_graph(&mut flow).unwrap();
+
+        let svg = render_flow_svg(&flow);
+
+        assert!(svg.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\" viewBox="));
+        assert!(!svg.lines().next().unwrap().contains(" width="), "no fixed size so it scales");
+        assert_eq!(svg.matches("<line").count(), 2);
+        assert!(svg.contains("Intent &amp; goals"));
+        assert!(svg.contains(">next</text>"));
+        assert_eq!(svg.matches("<rect").count(), 3);
+
+        let png = render_flow_png(&flow, 2.0).unwrap();
+        assert!(png.data.starts_with(b"\x89PNG"));
+        assert!(png.width > png.height, "left-to-right flow is wide");
+    }
+}
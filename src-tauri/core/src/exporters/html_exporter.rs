@@ -0,0 +1,164 @@
+use pulldown_cmark::{html, CodeBlockKind, Event, Parser, Tag, TagEnd};
+use quick_xml::escape::escape;
+use serde::{Deserialize, Serialize};
+use crate::models::*;
+use crate::parsers::mermaid_parser::extract_mermaid_from_markdown;
+use super::section_exporter::markdown_options;
+
+/// Mermaid build loaded by exported pages to render `<pre class="mermaid">` blocks
+pub const MERMAID_MODULE_URL: &str = "https://cdn.jsdelivr.net/npm/mermaid@11/dist/mermaid.esm.min.mjs";
+
+/// Options for the shareable HTML export
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase", default)]
+pub struct HtmlOptions {
+    /// Date shown under the title; the caller supplies it so output is reproducible
+    pub date: String,
+    pub include_flow: bool,
+}
+
+/// Render the document as one standalone HTML page
+///
+/// Section markdown becomes HTML. The flow diagram, and any ```mermaid
+/// fence in section content, is embedded as a `<pre class="mermaid">` block
+/// that mermaid.js renders when the page is opened; without a network
+/// connection the diagram source stays readable. Click actions such as
+/// `#intent-1` jump to the section, whose element carries its ID. Expects
+/// variables to be resolved already, as in `flow_service::load_context_document`.
+pub fn export_html(doc: &ContextDocument, options: &HtmlOptions) -> String {
+    let title = escape(doc.meta.title.as_str());
+
+    let mut html = String::new();
+    html.push_str("<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n");
+    html.push_str("<meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">\n");
+    html.push_str(&format!("<title>{}</title>\n", title));
+    html.push_str(&format!("<style>\n{}</style>\n", HTML_CSS));
+    html.push_str("</head>\n<body>\n<main>\n");
+    html.push_str(&format!("<h1 class=\"document-title\">{}</h1>\n", title));
+    if !options.date.is_empty() {
+        html.push_str(&format!("<p class=\"date\">{}</p>\n", escape(options.date.as_str())));
+    }
+    if !doc.meta.description.is_empty() {
+        html.push_str(&format!("<p class=\"description\">{}</p>\n", escape(doc.meta.description.as_str())));
+    }
+
+    if options.include_flow {
+        if let Some(flow) = &doc.flow_graph {
+            html.push_str("<figure class=\"flow\">\n");
+            let code = extract_mermaid_from_markdown(&flow.mermaid_code).unwrap_or_else(|_| flow.mermaid_code.clone());
+            html.push_str(&mermaid_block(&code));
+            if let Some(flow_title) = &flow.title {
+                html.push_str(&format!("<figcaption>{}</figcaption>\n", escape(flow_title.as_str())));
+            }
+            html.push_str("</figure>\n");
+        }
+    }
+
+    for section in &doc.sections {
+        write_section(&mut html, section);
+    }
+
+    html.push_str("</main>\n");
+    if html.contains("<pre class=\"mermaid\">") {
+        html.push_str(&format!(
+            "<script type=\"module\">\nimport mermaid from \"{}\";\nmermaid.initialize({{ startOnLoad: true }});\n</script>\n",
+            MERMAID_MODULE_URL
+        ));
+    }
+    html.push_str("</body>\n</html>\n");
+    html
+}
+
+fn write_section(html: &mut String, section: &Section) {
+    html.push_str(&format!(
+        "<section class=\"section-{}\" id=\"{}\">\n",
+        escape(section.section_type.as_str()),
+        escape(section.id.as_str())
+    ));
+    html.push_str(&render_markdown(&section.content));
+    for child in &section.children {
+        write_section(html, child);
+    }
+    html.push_str("</section>\n");
+}
+
+/// Markdown as HTML, with ```mermaid fences as blocks for mermaid.js instead of code listings
+fn render_markdown(markdown: &str) -> String {
+    let mut events = Vec::new();
+    let mut mermaid: Option<String> = None;
+    for event in Parser::new_ext(markdown, markdown_options()) {
+        match (&mut mermaid, event) {
+            (None, Event::Start(Tag::CodeBlock(CodeBlockKind::Fenced(lang)))) if lang.trim().eq_ignore_ascii_case("mermaid") => {
+                mermaid = Some(String::new());
+            }
+            (Some(code), Event::Text(text)) => code.push_str(&text),
+            (Some(code), Event::End(TagEnd::CodeBlock)) => {
+                events.push(Event::Html(mermaid_block(code).into()));
+                mermaid = None;
+            }
+            (_, event) => events.push(event),
+        }
+    }
+    let mut output = String::new();
+    html::push_html(&mut output, events.into_iter());
+    output
+}
+
+fn mermaid_block(code: &str) -> String {
+    format!("<pre class=\"mermaid\">\n{}\n</pre>\n", escape(code.trim()))
+}
+
+const HTML_CSS: &str = r#"body { font-family: -apple-system, "Segoe UI", Helvetica, Arial, sans-serif; line-height: 1.55; color: #1d1d1f; margin: 0; }
+main { max-width: 820px; margin: 0 auto; padding: 40px 24px; }
+.document-title { margin-bottom: 4px; }
+.date, .description { color: #666; margin-top: 0; }
+section { margin-top: 32px; }
+section section { margin-left: 16px; padding-left: 16px; border-left: 3px solid #eee; }
+pre:not(.mermaid) { background: #f6f6f6; padding: 12px; overflow-x: auto; }
+pre.mermaid { text-align: center; background: none; }
+.flow { margin: 32px 0; }
+.flow figcaption { text-align: center; color: #666; font-size: 0.9em; }
+table { border-collapse: collapse; }
+td, th { border: 1px solid #ccc; padding: 4px 8px; }
+"#;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn document() -> ContextDocument {
+        let mut process = Section::new("process-1", "process", "# Process\n\n```mermaid\nsequenceDiagram\n  A->>B: go\n```\n\n```rust\nfn main() {}\n```");
+        process.children.push(Section::new("process-2", "process", "Step <one>"));
+        ContextDocument::builder()
+            .title("Launch <Plan>")
+            .section("intent-1", "intent", "# Intent\nGoal: **Ship v1**")
+            .add_section(process)
+            .flow("```mermaid\nflowchart TD\n  A[Intent] --> B[Process]\n  click A \"#intent-1\"\n```")
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_html_embeds_mermaid() {
+        let options = HtmlOptions { date: "2025-10-09".to_string(), include_flow: true };
+        let html = export_html(&document(), &options);
+
+        assert!(html.contains("<title>Launch &lt;Plan&gt;</title>"));
+        assert!(html.contains("<p class=\"date\">2025-10-09</p>"));
+        assert!(html.contains("<pre class=\"mermaid\">\nflowchart TD\n  A[Intent] --&gt; B[Process]\n  click A &quot;#intent-1&quot;\n</pre>"));
+        assert!(html.contains("<section class=\"section-intent\" id=\"intent-1\">\n<h1>Intent</h1>"));
+        assert!(html.contains("<strong>Ship v1</strong>"));
+        assert!(html.contains("<pre class=\"mermaid\">\nsequenceDiagram\n  A-&gt;&gt;B: go\n</pre>"));
+        assert!(html.contains("<code class=\"language-rust\">"));
+        assert!(html.contains("<section class=\"section-process\" id=\"process-2\">"));
+        assert_eq!(html.matches("import mermaid from").count(), 1);
+    }
+
+    #[test]
+    fn test_no_loader_without_diagrams() {
+        let doc = ContextDocument::builder().title("Plain").section("intent-1", "intent", "Text").build().unwrap();
+        let html = export_html(&doc, &HtmlOptions::default());
+        assert!(!html.contains("<script"));
+        assert!(!html.contains("class=\"date\""));
+    }
+}
@@ -0,0 +1,110 @@
+use chrono::{Days, NaiveDate};
+use crate::processors::calendar_events::{CalendarEvent, EventSource};
+
+/// Longest content line allowed by RFC 5545, in octets
+const MAX_LINE_OCTETS: usize = 75;
+
+/// Render events as an iCalendar (`.ics`) file of all-day events
+///
+/// UIDs combine the event key with `uid_namespace` (something stable per
+/// document, such as a hash of its path), so re-importing an updated export
+/// moves events instead of duplicating them. `timestamp` is the `DTSTAMP`
+/// in UTC basic format (`20251009T120000Z`).
+pub fn export_ics(title: &str, events: &[CalendarEvent], uid_namespace: &str, timestamp: &str) -> String {
+    let mut lines = vec![
+        "BEGIN:VCALENDAR".to_string(),
+        "VERSION:2.0".to_string(),
+        "PRODID:-//Flow Writer//Calendar Export//EN".to_string(),
+        "CALSCALE:GREGORIAN".to_string(),
+        format!("X-WR-CALNAME:{}", escape_text(title)),
+    ];
+
+    for event in events {
+        let Ok(date) = NaiveDate::parse_from_str(&event.date, "%Y-%m-%d") else {
+            continue;
+        };
+        let end = date.checked_add_days(Days::new(1)).unwrap_or(date);
+        let description = match (&event.source, &event.section_id) {
+            (EventSource::Milestone, Some(section_id)) => format!("Milestone in {} ({})", title, section_id),
+            _ => format!("{} in {}", event.key.trim_start_matches("var:"), title),
+        };
+
+        lines.push("BEGIN:VEVENT".to_string());
+        lines.push(format!("UID:{}-{}@flow-writer", uid_part(&event.key), uid_namespace));
+        lines.push(format!("DTSTAMP:{}", timestamp));
+        lines.push(format!("DTSTART;VALUE=DATE:{}", date.format("%Y%m%d")));
+        lines.push(format!("DTEND;VALUE=DATE:{}", end.format("%Y%m%d")));
+        lines.push(format!("SUMMARY:{}", escape_text(&event.summary)));
+        lines.push(format!("DESCRIPTION:{}", escape_text(&description)));
+        lines.push("TRANSP:TRANSPARENT".to_string());
+        lines.push("END:VEVENT".to_string());
+    }
+    lines.push("END:VCALENDAR".to_string());
+
+    lines.iter().map(|line| fold_line(line) + "\r\n").collect()
+}
+
+/// Escape TEXT values (RFC 5545 3.3.11)
+fn escape_text(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace("\r\n", "\\n")
+        .replace('\n', "\\n")
+}
+
+fn uid_part(key: &str) -> String {
+    key.chars().map(|c| if c.is_ascii_alphanumeric() || c == '-' { c } else { '-' }).collect()
+}
+
+/// Fold lines longer than 75 octets, continuing with a leading space, without splitting characters
+fn fold_line(line: &str) -> String {
+    let mut folded = String::new();
+    let mut length = 0;
+    for c in line.chars() {
+        if length + c.len_utf8() > MAX_LINE_OCTETS {
+            folded.push_str("\r\n ");
+            length = 1;
+        }
+        folded.push(c);
+        length += c.len_utf8();
+    }
+    folded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(key: &str, date: &str, summary: &str) -> CalendarEvent {
+        CalendarEvent {
+            key: key.to_string(),
+            date: date.to_string(),
+            summary: summary.to_string(),
+            source: EventSource::Variable,
+            section_id: None,
+        }
+    }
+
+    #[test]
+    fn test_all_day_events() {
+        let ics = export_ics("Launch; v1", &[event("var:deadline", "2025-12-31", "Deadline")], "abc123", "20251009T120000Z");
+
+        assert!(ics.starts_with("BEGIN:VCALENDAR\r\nVERSION:2.0\r\n"));
+        assert!(ics.contains("X-WR-CALNAME:Launch\\; v1\r\n"));
+        assert!(ics.contains("UID:var-deadline-abc123@flow-writer\r\n"));
+        assert!(ics.contains("DTSTART;VALUE=DATE:20251231\r\nDTEND;VALUE=DATE:20260101\r\n"));
+        assert!(ics.ends_with("END:VEVENT\r\nEND:VCALENDAR\r\n"));
+    }
+
+    #[test]
+    fn test_long_lines_are_folded() {
+        let summary = "ü".repeat(60);
+        let ics = export_ics("Plan", &[event("var:x", "2025-01-01", &summary)], "n", "20250101T000000Z");
+
+        for line in ics.split("\r\n") {
+            assert!(line.len() <= MAX_LINE_OCTETS, "{} octets", line.len());
+        }
+        assert!(ics.replace("\r\n ", "").contains(&format!("SUMMARY:{}", summary)));
+    }
+}
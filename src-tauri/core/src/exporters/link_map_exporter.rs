@@ -0,0 +1,338 @@
+use pulldown_cmark::{Event, HeadingLevel, Parser, Tag, TagEnd};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use crate::models::*;
+use crate::processors::flow_navigation::flatten;
+use crate::processors::portable_format::portable_path;
+use super::section_exporter::markdown_options;
+
+/// Value of `format` in every link map, so tools can recognise the file
+pub const LINK_MAP_FORMAT: &str = "flow-writer-link-map";
+/// Bumped on breaking changes to the link map layout
+pub const LINK_MAP_VERSION: u32 = 1;
+
+/// Extensions of files linked as documents rather than attachments
+const DOCUMENT_EXTENSIONS: &[&str] = &[".xml", ".cec"];
+
+/// Sections, headings, flow nodes and links of a document, for audits and publishing pipelines
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct LinkMap {
+    pub format: String,
+    pub format_version: u32,
+    pub title: String,
+    /// Every section at any depth, parents before their children
+    pub sections: Vec<LinkMapSection>,
+    pub nodes: Vec<LinkMapNode>,
+    pub links: Vec<LinkMapLink>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct LinkMapSection {
+    pub id: String,
+    pub section_type: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub parent_id: Option<String>,
+    pub headings: Vec<LinkMapHeading>,
+    /// Flow nodes whose click action opens the section
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub node_ids: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct LinkMapHeading {
+    pub level: u8,
+    pub text: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct LinkMapNode {
+    pub id: String,
+    pub label: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub section_id: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum LinkKind {
+    /// A section of the same document
+    Internal,
+    /// Another context document, optionally at a section
+    Document,
+    /// A local file other than a context document
+    Attachment,
+    /// A URL, `mailto:` address and the like
+    External,
+}
+
+/// Where a link is written
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum LinkSource {
+    /// A markdown link or image in section content
+    Markdown,
+    /// The section's refTarget attribute
+    RefTarget,
+    /// A node's click action
+    Click,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct LinkMapLink {
+    /// Section holding the link; unset for click actions
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub section_id: Option<String>,
+    /// Node holding the link, for click actions
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub node_id: Option<String>,
+    pub source: LinkSource,
+    pub kind: LinkKind,
+    /// As written; paths with `/` separators
+    pub target: String,
+    /// Link text or image alt text
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub text: Option<String>,
+    /// Internal links to a section that does not exist
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub broken: bool,
+}
+
+/// Build the link map of a document, expecting enriched flow graphs as from `flow_service`
+pub fn export_link_map(doc: &ContextDocument) -> LinkMap {
+    let section_ids: HashSet<&str> = flatten(&doc.sections).iter().map(|section| section.id.as_str()).collect();
+    let node_refs: &[NodeReference] = doc.flow_graph.as_ref().map(|flow| flow.node_refs.as_slice()).unwrap_or_default();
+
+    let mut sections = Vec::new();
+    let mut links = Vec::new();
+    collect_sections(&doc.sections, None, node_refs, &section_ids, &mut sections, &mut links);
+
+    let mut nodes = Vec::new();
+    if let Some(flow) = &doc.flow_graph {
+        nodes = flow
+            .parsed_graph
+            .nodes
+            .iter()
+            .map(|node| LinkMapNode {
+                id: node.id.clone(),
+                label: node.label.clone(),
+                section_id: node.ref_section_id.clone(),
+            })
+            .collect();
+        links.extend(node_refs.iter().map(|node_ref| LinkMapLink {
+            section_id: None,
+            node_id: Some(node_ref.node_id.clone()),
+            source: LinkSource::Click,
+            kind: LinkKind::Internal,
+            target: node_ref.click_action.clone(),
+            text: node_ref.tooltip.clone(),
+            broken: !section_ids.contains(node_ref.section_id.as_str()),
+        }));
+    }
+
+    LinkMap {
+        format: LINK_MAP_FORMAT.to_string(),
+        format_version: LINK_MAP_VERSION,
+        title: doc.meta.title.clone(),
+        sections,
+        nodes,
+        links,
+    }
+}
+
+fn collect_sections(
+    sections: &[Section],
+    parent_id: Option<&str>,
+    node_refs: &[NodeReference],
+    section_ids: &HashSet<&str>,
+    entries: &mut Vec<LinkMapSection>,
+    links: &mut Vec<LinkMapLink>,
+) {
+    for section in sections {
+        let (headings, markdown_links) = scan_markdown(&section.content);
+        entries.push(LinkMapSection {
+            id: section.id.clone(),
+            section_type: section.section_type.clone(),
+            parent_id: parent_id.map(str::to_string),
+            headings,
+            node_ids: node_refs
+                .iter()
+                .filter(|node_ref| node_ref.section_id == section.id)
+                .map(|node_ref| node_ref.node_id.clone())
+                .collect(),
+        });
+
+        for target in section.ref_target.iter().flat_map(|ref_target| ref_target.split_whitespace()) {
+            links.push(link(section, LinkSource::RefTarget, target, None, section_ids));
+        }
+        for (target, text) in markdown_links {
+            links.push(link(section, LinkSource::Markdown, &target, text, section_ids));
+        }
+
+        collect_sections(&section.children, Some(&section.id), node_refs, section_ids, entries, links);
+    }
+}
+
+fn link(section: &Section, source: LinkSource, target: &str, text: Option<String>, section_ids: &HashSet<&str>) -> LinkMapLink {
+    let kind = link_kind(target, source);
+    let local_id = match source {
+        LinkSource::RefTarget => target,
+        _ => target.strip_prefix('#').unwrap_or_default(),
+    };
+    LinkMapLink {
+        section_id: Some(section.id.clone()),
+        node_id: None,
+        source,
+        kind,
+        target: if matches!(kind, LinkKind::Document | LinkKind::Attachment) { portable_path(target) } else { target.to_string() },
+        text,
+        broken: kind == LinkKind::Internal && !section_ids.contains(local_id),
+    }
+}
+
+fn link_kind(target: &str, source: LinkSource) -> LinkKind {
+    let has_scheme = target.contains("://") || target.starts_with("//") || ["mailto:", "tel:", "data:"].iter().any(|scheme| target.starts_with(scheme));
+    if has_scheme {
+        return LinkKind::External;
+    }
+    if source == LinkSource::RefTarget {
+        return if target.contains('#') { LinkKind::Document } else { LinkKind::Internal };
+    }
+    if target.starts_with('#') {
+        return LinkKind::Internal;
+    }
+    let path = target.split(['#', '?']).next().unwrap_or_default().to_lowercase();
+    if DOCUMENT_EXTENSIONS.iter().any(|extension| path.ends_with(extension)) {
+        LinkKind::Document
+    } else {
+        LinkKind::Attachment
+    }
+}
+
+/// Headings and `(target, text)` of links and images, in content order
+fn scan_markdown(content: &str) -> (Vec<LinkMapHeading>, Vec<(String, Option<String>)>) {
+    let mut headings = Vec::new();
+    let mut links = Vec::new();
+    // Text collected for the heading or link being read
+    let mut text: Option<String> = None;
+    let mut heading_level = None;
+    let mut target = None;
+
+    for event in Parser::new_ext(content, markdown_options()) {
+        match event {
+            Event::Start(Tag::Heading { level, .. }) => {
+                heading_level = Some(level);
+                text = Some(String::new());
+            }
+            Event::End(TagEnd::Heading(_)) => {
+                if let (Some(level), Some(heading)) = (heading_level.take(), text.take()) {
+                    headings.push(LinkMapHeading { level: heading_level_number(level), text: heading.trim().to_string() });
+                }
+            }
+            Event::Start(Tag::Link { dest_url, .. }) | Event::Start(Tag::Image { dest_url, .. }) => {
+                target = Some(dest_url.to_string());
+                if heading_level.is_none() {
+                    text = Some(String::new());
+                }
+            }
+            Event::End(TagEnd::Link) | Event::End(TagEnd::Image) => {
+                if let Some(target) = target.take() {
+                    let link_text = if heading_level.is_none() { text.take() } else { None };
+                    links.push((target, link_text.filter(|text| !text.trim().is_empty())));
+                }
+            }
+            Event::Text(value) | Event::Code(value) => {
+                if let Some(text) = &mut text {
+                    text.push_str(&value);
+                }
+            }
+            _ => {}
+        }
+    }
+    (headings, links)
+}
+
+fn heading_level_number(level: HeadingLevel) -> u8 {
+    match level {
+        HeadingLevel::H1 => 1,
+        HeadingLevel::H2 => 2,
+        HeadingLevel::H3 => 3,
+        HeadingLevel::H4 => 4,
+        HeadingLevel::H5 => 5,
+        HeadingLevel::H6 => 6,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parsers::mermaid_parser;
+
+    fn document() -> ContextDocument {
+        let mut intent = Section::new(
+            "intent-1",
+            "intent",
+            "# Intent\n\nSee [the steps](#process-1), [gone](#old-1), [spec](docs\\spec.md) and [site](https://example.com).\n\n## Scope\n\n![logo](img/logo.png)",
+        );
+        intent.ref_target = Some("process-1 ../shared/brief.xml#intent-1".to_string());
+        intent.children.push(Section::new("intent-2", "intent", "### Detail\n[Brief](brief.cec#scope)"));
+        let mut doc = ContextDocument::builder()
+            .title("Plan")
+            .add_section(intent)
+            .section("process-1", "process", "No heading")
+            .flow("flowchart TD\n  A[Intent] --> B[Process]\n  click A \"#intent-1\" \"Open intent\"\n  click B \"#missing\"")
+            .build()
+            .unwrap();
+        mermaid_parser::enrich_flow_graph(doc.flow_graph.as_mut().unwrap()).unwrap();
+        doc
+    }
+
+    #[test]
+    fn test_sections_and_nodes() {
+        let map = export_link_map(&document());
+
+        assert_eq!(map.format, LINK_MAP_FORMAT);
+        let ids: Vec<_> = map.sections.iter().map(|s| (s.id.as_str(), s.parent_id.as_deref())).collect();
+        assert_eq!(ids, vec![("intent-1", None), ("intent-2", Some("intent-1")), ("process-1", None)]);
+        assert_eq!(
+            map.sections[0].headings,
+            vec![LinkMapHeading { level: 1, text: "Intent".to_string() }, LinkMapHeading { level: 2, text: "Scope".to_string() }]
+        );
+        assert_eq!(map.sections[0].node_ids, vec!["A"]);
+        assert!(map.sections[2].headings.is_empty());
+        assert_eq!(map.nodes.len(), 2);
+        assert_eq!(map.nodes[0].section_id.as_deref(), Some("intent-1"));
+    }
+
+    #[test]
+    fn test_links_are_classified() {
+        let map = export_link_map(&document());
+        let links: Vec<_> = map
+            .links
+            .iter()
+            .map(|l| (l.section_id.as_deref().or(l.node_id.as_deref()).unwrap(), l.source, l.kind, l.target.as_str(), l.broken))
+            .collect();
+
+        assert_eq!(
+            links,
+            vec![
+                ("intent-1", LinkSource::RefTarget, LinkKind::Internal, "process-1", false),
+                ("intent-1", LinkSource::RefTarget, LinkKind::Document, "../shared/brief.xml#intent-1", false),
+                ("intent-1", LinkSource::Markdown, LinkKind::Internal, "#process-1", false),
+                ("intent-1", LinkSource::Markdown, LinkKind::Internal, "#old-1", true),
+                ("intent-1", LinkSource::Markdown, LinkKind::Attachment, "docs/spec.md", false),
+                ("intent-1", LinkSource::Markdown, LinkKind::External, "https://example.com", false),
+                ("intent-1", LinkSource::Markdown, LinkKind::Attachment, "img/logo.png", false),
+                ("intent-2", LinkSource::Markdown, LinkKind::Document, "brief.cec#scope", false),
+                ("A", LinkSource::Click, LinkKind::Internal, "#intent-1", false),
+                ("B", LinkSource::Click, LinkKind::Internal, "#missing", true),
+            ]
+        );
+        assert_eq!(map.links[2].text.as_deref(), Some("the steps"));
+        assert_eq!(map.links[8].text.as_deref(), Some("Open intent"));
+        assert_eq!(map.links[6].text.as_deref(), Some("logo"));
+    }
+}
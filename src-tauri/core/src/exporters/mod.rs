@@ -0,0 +1,21 @@
+pub mod docx_exporter;
+pub mod excalidraw_exporter;
+pub mod flow_json_exporter;
+pub mod flow_svg;
+pub mod html_exporter;
+pub mod ics_exporter;
+pub mod link_map_exporter;
+pub mod print_exporter;
+pub mod reading_order_exporter;
+pub mod section_exporter;
+
+pub use docx_exporter::*;
+pub use excalidraw_exporter::*;
+pub use flow_json_exporter::*;
+pub use flow_svg::*;
+pub use html_exporter::*;
+pub use ics_exporter::*;
+pub use link_map_exporter::*;
+pub use print_exporter::*;
+pub use reading_order_exporter::*;
+pub use section_exporter::*;
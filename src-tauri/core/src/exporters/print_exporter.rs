@@ -0,0 +1,147 @@
+use quick_xml::escape::escape;
+use serde::{Deserialize, Serialize};
+use crate::models::*;
+use super::flow_svg::render_flow_svg;
+use super::section_exporter::markdown_to_html;
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum PageSize {
+    #[default]
+    A4,
+    Letter,
+}
+
+/// Options for the print export profile
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase", default)]
+pub struct PrintOptions {
+    pub page_size: PageSize,
+    /// Date shown in the header; the caller supplies it so output is reproducible
+    pub date: String,
+    pub include_flow: bool,
+}
+
+/// Render the document as paginated, print-optimized HTML
+///
+/// Each top-level section starts on a new page. The title and date repeat in
+/// a header and footer on every printed page, and the flow diagram is
+/// rendered as SVG scaled to the page width. Expects variables to be
+/// resolved already, as in `flow_service::load_context_document`.
+pub fn export_print_html(doc: &ContextDocument, options: &PrintOptions) -> String {
+    let title = escape(doc.meta.title.as_str());
+    let date = escape(options.date.as_str());
+    let page_size = match options.page_size {
+        PageSize::A4 => "A4",
+        PageSize::Letter => "letter",
+    };
+
+    let mut html = String::new();
+    html.push_str("<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n");
+    html.push_str(&format!("<title>{}</title>\n", title));
+    html.push_str(&format!("<style>\n{}</style>\n", PRINT_CSS.replace("{page_size}", page_size)));
+    html.push_str("</head>\n<body>\n");
+    html.push_str(&format!(
+        "<header class=\"page-header\"><span>{}</span><span>{}</span></header>\n",
+        title, date
+    ));
+    html.push_str(&format!(
+        "<footer class=\"page-footer\"><span>{}</span><span>{}</span></footer>\n",
+        title, date
+    ));
+
+    html.push_str("<main>\n");
+    html.push_str(&format!("<h1 class=\"document-title\">{}</h1>\n", title));
+    if !doc.meta.description.is_empty() {
+        html.push_str(&format!("<p class=\"description\">{}</p>\n", escape(doc.meta.description.as_str())));
+    }
+
+    for section in &doc.sections {
+        write_section(&mut html, section);
+    }
+
+    if options.include_flow {
+        if let Some(flow) = &doc.flow_graph {
+            html.push_str("<section class=\"page flow\">\n");
+            if let Some(flow_title) = &flow.title {
+                html.push_str(&format!("<h2>{}</h2>\n", escape(flow_title.as_str())));
+            }
+            html.push_str(&render_flow_svg(flow));
+            html.push_str("</section>\n");
+        }
+    }
+
+    html.push_str("</main>\n</body>\n</html>\n");
+    html
+}
+
+fn write_section(html: &mut String, section: &Section) {
+    html.push_str(&format!(
+        "<section class=\"page section-{}\" id=\"{}\">\n",
+        escape(section.section_type.as_str()),
+        escape(section.id.as_str())
+    ));
+    html.push_str(&markdown_to_html(&section.content));
+    for child in &section.children {
+        html.push_str(&format!("<div class=\"subsection\" id=\"{}\">\n", escape(child.id.as_str())));
+        html.push_str(&markdown_to_html(&child.content));
+        html.push_str("</div>\n");
+    }
+    html.push_str("</section>\n");
+}
+
+/// Header and footer are fixed so print engines repeat them on every page;
+/// the page margins leave room for them
+const PRINT_CSS: &str = r#"@page { size: {page_size}; margin: 25mm 18mm; }
+body { font-family: Georgia, "Times New Roman", serif; font-size: 11pt; line-height: 1.45; color: #111; margin: 0; }
+.page-header, .page-footer { position: fixed; left: 0; right: 0; display: flex; justify-content: space-between; font: 9pt sans-serif; color: #666; }
+.page-header { top: -15mm; border-bottom: 0.5pt solid #ccc; }
+.page-footer { bottom: -15mm; border-top: 0.5pt solid #ccc; }
+.document-title { font-size: 22pt; margin-top: 0; }
+.page + .page { break-before: page; page-break-before: always; }
+h1, h2, h3, h4 { break-after: avoid; page-break-after: avoid; }
+pre, table, figure, svg { break-inside: avoid; page-break-inside: avoid; }
+pre { white-space: pre-wrap; font-size: 9pt; background: #f6f6f6; padding: 6pt; }
+table { border-collapse: collapse; }
+td, th { border: 0.5pt solid #999; padding: 3pt 6pt; }
+.flow svg { width: 100%; height: auto; max-height: 230mm; }
+@media screen { body { max-width: 180mm; margin: 0 auto; padding: 20mm 0; } .page-header, .page-footer { position: static; } }
+"#;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn document() -> ContextDocument {
+        ContextDocument::builder()
+            .title("Launch <Plan>")
+            .section("intent-1", "intent", "# Intent\nGoal: Ship v1")
+            .section("process-1", "process", "# Process\n- [ ] Write docs")
+            .flow("flowchart TD\n  A[Intent] --> B[Process]")
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_print_html_structure() {
+        let options = PrintOptions {
+            page_size: PageSize::Letter,
+            date: "2025-10-09".to_string(),
+            include_flow: true,
+        };
+        let html = export_print_html(&document(), &options);
+
+        assert!(html.contains("@page { size: letter;"));
+        assert!(html.contains("<header class=\"page-header\"><span>Launch &lt;Plan&gt;</span><span>2025-10-09</span></header>"));
+        assert!(html.contains("Goal: Ship v1"));
+        assert_eq!(html.matches("<section class=\"page").count(), 3);
+        assert!(html.contains("<section class=\"page flow\">\n<svg"));
+    }
+
+    #[test]
+    fn test_flow_is_optional() {
+        let html = export_print_html(&document(), &PrintOptions::default());
+        assert!(!html.contains("<svg"));
+        assert!(html.contains("size: A4"));
+    }
+}
@@ -0,0 +1,232 @@
+use quick_xml::escape::escape;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use crate::models::*;
+use crate::processors::flow_traversal;
+use super::section_exporter::{export_section, ExportFormat};
+
+/// Order in which sections are exported
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum ReadingOrder {
+    /// Sections as they appear in the document
+    #[default]
+    Document,
+    /// Follow each path of the flow to its end before taking the next branch
+    DepthFirst,
+    /// Every node after all of its predecessors
+    Topological,
+}
+
+/// Options for the reading-order export
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ReadingOrderOptions {
+    pub order: ReadingOrder,
+    pub format: ExportFormat,
+    /// Render the label of the edge leading to a section as a note before it
+    pub transition_notes: bool,
+}
+
+/// Export the sections in the order the flow diagram reads
+///
+/// Nodes are visited from the ones without incoming edges, in diagram order;
+/// cycles are broken at the first node in diagram order that is still
+/// pending. Each node contributes the sections its click actions link to.
+/// Sections no node links to follow in document order, and documents without
+/// a flow graph export in document order. Expects variables to be resolved
+/// and the flow graph enriched, as in `flow_service::load_document_for_export`.
+pub fn export_reading_order(doc: &ContextDocument, options: &ReadingOrderOptions) -> String {
+    let sections = flatten(&doc.sections);
+    let mut parts: Vec<String> = Vec::new();
+    let mut exported: HashSet<&str> = HashSet::new();
+
+    if let (Some(flow), ReadingOrder::DepthFirst | ReadingOrder::Topological) = (&doc.flow_graph, options.order) {
+        let graph = &flow.parsed_graph;
+        let order = match options.order {
+            ReadingOrder::DepthFirst => flow_traversal::depth_first_order(graph),
+            _ => flow_traversal::topological_order(graph),
+        };
+
+        let mut visited: Vec<&str> = Vec::new();
+        for node_id in order {
+            // The most recently exported predecessor is the one the reader arrives from
+            let transition = visited.iter().rev().find_map(|from| {
+                graph
+                    .edges
+                    .iter()
+                    .find(|edge| edge.from == *from && edge.to == node_id)
+                    .and_then(|edge| edge.label.as_deref())
+            });
+            visited.push(node_id);
+
+            let mut pending_note = transition.filter(|_| options.transition_notes);
+            for node_ref in flow.node_refs.iter().filter(|node_ref| node_ref.node_id == node_id) {
+                let Some(section) = sections.iter().find(|s| s.id == node_ref.section_id) else {
+                    continue;
+                };
+                if !exported.insert(section.id.as_str()) {
+                    continue;
+                }
+                if let Some(label) = pending_note.take() {
+                    parts.push(transition_note(label, options.format));
+                }
+                parts.push(export_section(section, options.format));
+            }
+        }
+    }
+
+    for section in &sections {
+        if exported.insert(section.id.as_str()) {
+            parts.push(export_section(section, options.format));
+        }
+    }
+
+    parts
+        .iter()
+        .map(|part| part.trim())
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
+
+fn transition_note(label: &str, format: ExportFormat) -> String {
+    match format {
+        ExportFormat::Markdown => format!("> → {}", label),
+        ExportFormat::Html => format!("<p class=\"transition\">→ {}</p>", escape(label)),
+        ExportFormat::PlainText => format!("→ {}", label),
+    }
+}
+
+fn flatten(sections: &[Section]) -> Vec<&Section> {
+    sections
+        .iter()
+        .flat_map(|section| std::iter::once(section).chain(flatten(&section.children)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn node(id: &str) -> GraphNode {
+        GraphNode {
+            id: id.to_string(),
+            label: id.to_string(),
+            node_type: NodeType::Rectangle,
+            ref_section_id: None,
+            metadata: None,
+        }
+    }
+
+    fn edge(from: &str, to: &str, label: Option<&str>) -> GraphEdge {
+        GraphEdge {
+            from: from.to_string(),
+            to: to.to_string(),
+            label: label.map(str::to_string),
+            condition: None,
+        }
+    }
+
+    /// A -> B -> D and A -> C -> D, with sections listed in reverse
+    fn document() -> ContextDocument {
+        let mut doc = ContextDocument::builder()
+            .title("Plan")
+            .section("notes-1", "alternatives", "Notes")
+            .section("d", "process", "Ship")
+            .section("c", "process", "Review")
+            .section("b", "process", "Build")
+            .section("a", "intent", "Goal")
+            .build()
+            .unwrap();
+        doc.flow_graph = Some(FlowGraph {
+            id: "flow-1".to_string(),
+            version: "1.0".to_string(),
+            title: None,
+            mermaid_code: String::new(),
+            parsed_graph: GraphStructure {
+                nodes: vec![node("A"), node("B"), node("C"), node("D")],
+                edges: vec![
+                    edge("A", "B", Some("start")),
+                    edge("B", "D", None),
+                    edge("A", "C", Some("needs review")),
+                    edge("C", "D", Some("approved")),
+                ],
+                subgraphs: vec![],
+            },
+            node_refs: ["A", "B", "C", "D"]
+                .iter()
+                .map(|id| NodeReference {
+                    node_id: id.to_string(),
+                    section_id: id.to_lowercase(),
+                    click_action: String::new(),
+                    tooltip: None,
+                })
+                .collect(),
+            layout: None,
+            node_metadata: Default::default(),
+        });
+        doc
+    }
+
+    fn export(order: ReadingOrder, transition_notes: bool) -> String {
+        let options = ReadingOrderOptions {
+            order,
+            format: ExportFormat::Markdown,
+            transition_notes,
+        };
+        export_reading_order(&document(), &options)
+    }
+
+    #[test]
+    fn test_document_order() {
+        assert_eq!(export(ReadingOrder::Document, true), "Notes\n\nShip\n\nReview\n\nBuild\n\nGoal");
+    }
+
+    #[test]
+    fn test_depth_first_order() {
+        assert_eq!(export(ReadingOrder::DepthFirst, false), "Goal\n\nBuild\n\nShip\n\nReview\n\nNotes");
+    }
+
+    #[test]
+    fn test_topological_order_with_transitions() {
+        assert_eq!(
+            export(ReadingOrder::Topological, true),
+            "Goal\n\n> → start\n\nBuild\n\n> → needs review\n\nReview\n\n> → approved\n\nShip\n\nNotes"
+        );
+    }
+
+    #[test]
+    fn test_cycle_is_exported_once() {
+        let mut doc = document();
+        let flow = doc.flow_graph.as_mut().unwrap();
+        flow.parsed_graph.edges.push(edge("D", "A", Some("again")));
+        let options = ReadingOrderOptions {
+            order: ReadingOrder::Topological,
+            ..Default::default()
+        };
+
+        assert_eq!(export_reading_order(&doc, &options), "Goal\n\nBuild\n\nReview\n\nShip\n\nNotes");
+    }
+
+    #[test]
+    fn test_html_transition_is_escaped() {
+        let mut doc = document();
+        doc.flow_graph.as_mut().unwrap().parsed_graph.edges[0].label = Some("a < b".to_string());
+        let options = ReadingOrderOptions {
+            order: ReadingOrder::DepthFirst,
+            format: ExportFormat::Html,
+            transition_notes: true,
+        };
+
+        assert!(export_reading_order(&doc, &options).contains("<p class=\"transition\">→ a &lt; b</p>"));
+    }
+
+    #[test]
+    fn test_options_deserialization() {
+        let options: ReadingOrderOptions =
+            serde_json::from_str(r#"{"order":"depthFirst","format":"html","transitionNotes":true}"#).unwrap();
+        assert_eq!(options.order, ReadingOrder::DepthFirst);
+        assert_eq!(options.format, ExportFormat::Html);
+    }
+}
@@ -0,0 +1,116 @@
+use pulldown_cmark::{html, Event, Options, Parser, Tag, TagEnd};
+use serde::{Deserialize, Serialize};
+use crate::models::Section;
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    #[default]
+    Markdown,
+    Html,
+    #[serde(alias = "text")]
+    PlainText,
+}
+
+/// Render a single section's content in the requested format
+pub fn export_section(section: &Section, format: ExportFormat) -> String {
+    match format {
+        ExportFormat::Markdown => section.content.clone(),
+        ExportFormat::Html => markdown_to_html(&section.content),
+        ExportFormat::PlainText => markdown_to_plain_text(&section.content),
+    }
+}
+
+pub fn markdown_to_html(markdown: &str) -> String {
+    let parser = Parser::new_ext(markdown, markdown_options());
+    let mut output = String::new();
+    html::push_html(&mut output, parser);
+    output
+}
+
+/// Strip markdown syntax, keeping text with one line per block
+pub fn markdown_to_plain_text(markdown: &str) -> String {
+    let mut output = String::new();
+
+    for event in Parser::new_ext(markdown, markdown_options()) {
+        match event {
+            Event::Text(text) | Event::Code(text) => output.push_str(&text),
+            Event::SoftBreak | Event::HardBreak => output.push('\n'),
+            Event::TaskListMarker(checked) => output.push_str(if checked { "[x] " } else { "[ ] " }),
+            Event::Start(Tag::Item) => output.push_str("- "),
+            Event::End(TagEnd::Paragraph)
+            | Event::End(TagEnd::Heading(_))
+            | Event::End(TagEnd::Item)
+            | Event::End(TagEnd::CodeBlock)
+            | Event::End(TagEnd::TableRow)
+            | Event::End(TagEnd::TableHead)
+                if !output.ends_with('\n') =>
+            {
+                output.push('\n');
+            }
+            Event::End(TagEnd::TableCell) => output.push('\t'),
+            Event::Rule => output.push('\n'),
+            _ => {}
+        }
+    }
+
+    output.trim().to_string()
+}
+
+pub(crate) fn markdown_options() -> Options {
+    Options::ENABLE_TABLES | Options::ENABLE_STRIKETHROUGH | Options::ENABLE_TASKLISTS
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeMap;
+
+    fn section(content: &str) -> Section {
+        Section {
+            id: "intent-1".to_string(),
+            section_type: "intent".to_string(),
+            content: content.to_string(),
+            ref_target: None,
+            children: vec![],
+            budget: None,
+            transclusions: vec![],
+            tags: vec![],
+            translations: BTreeMap::new(),
+            sensitive: false,
+            encrypted: false,
+        }
+    }
+
+    #[test]
+    fn test_export_markdown_is_unchanged() {
+        let s = section("# Intent\n\nShip **v1**");
+        assert_eq!(export_section(&s, ExportFormat::Markdown), "# Intent\n\nShip **v1**");
+    }
+
+    #[test]
+    fn test_export_html() {
+        let s = section("# Intent\n\nShip **v1**");
+        let html = export_section(&s, ExportFormat::Html);
+
+        assert!(html.contains("<h1>Intent</h1>"));
+        assert!(html.contains("<strong>v1</strong>"));
+    }
+
+    #[test]
+    fn test_export_plain_text() {
+        let s = section("# Intent\n\nShip **v1** with `code`\n\n- one\n- [x] two");
+        let text = export_section(&s, ExportFormat::PlainText);
+
+        assert_eq!(text, "Intent\nShip v1 with code\n- one\n- [x] two");
+    }
+
+    #[test]
+    fn test_export_format_deserialization() {
+        let format: ExportFormat = serde_json::from_str(r#""plaintext""#).unwrap();
+        assert_eq!(format, ExportFormat::PlainText);
+
+        let format: ExportFormat = serde_json::from_str(r#""text""#).unwrap();
+        assert_eq!(format, ExportFormat::PlainText);
+    }
+}
@@ -0,0 +1,15 @@
+//! Core library for Flow Writer context documents
+//!
+//! Parses, validates, transforms and serializes context documents without any
+//! dependency on the Tauri shell, so the format can be reused from CLIs,
+//! servers and other front ends.
+
+pub mod error;
+pub mod exporters;
+pub mod models;
+pub mod parsers;
+pub mod plugins;
+pub mod processors;
+pub mod serializers;
+pub mod services;
+pub mod validators;
@@ -0,0 +1,322 @@
+use std::collections::{BTreeMap, HashSet};
+use crate::error::{ContextError, Result};
+use super::*;
+
+/// Application name written to `<app>` for documents created by this app
+pub const APP_NAME: &str = "CEC";
+
+/// Fluent builder for [`ContextDocument`]
+///
+/// ```ignore
+/// let doc = ContextDocument::builder()
+///     .title("Onboarding")
+///     .author("Jeremy")
+///     .variable("goal", "Ship v1")
+///     .section("intent-1", "intent", "# Intent\n${goal}")
+///     .flow("```mermaid\nflowchart TD\n  A[Intent]\n```")
+///     .build()?;
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct ContextDocumentBuilder {
+    title: String,
+    author: String,
+    created: String,
+    app_info: Option<AppInfo>,
+    tags: Vec<String>,
+    description: String,
+    extra: BTreeMap<String, String>,
+    variables: Vec<Variable>,
+    sections: Vec<Section>,
+    references: Vec<Reference>,
+    flow_graph: Option<FlowGraph>,
+}
+
+impl ContextDocument {
+    pub fn builder() -> ContextDocumentBuilder {
+        ContextDocumentBuilder::default()
+    }
+}
+
+impl Section {
+    /// Section with content only; optional fields left empty
+    pub fn new(id: impl Into<String>, section_type: impl Into<String>, content: impl Into<String>) -> Self {
+        Section {
+            id: id.into(),
+            section_type: section_type.into(),
+            content: content.into(),
+            ref_target: None,
+            tags: vec![],
+            translations: BTreeMap::new(),
+            children: vec![],
+            budget: None,
+            transclusions: vec![],
+            sensitive: false,
+            encrypted: false,
+        }
+    }
+}
+
+impl ContextDocumentBuilder {
+    pub fn title(mut self, title: impl Into<String>) -> Self {
+        self.title = title.into();
+        self
+    }
+
+    pub fn author(mut self, author: impl Into<String>) -> Self {
+        self.author = author.into();
+        self
+    }
+
+    pub fn created(mut self, created: impl Into<String>) -> Self {
+        self.created = created.into();
+        self
+    }
+
+    /// Override the `<app>` info (defaults to this application and version)
+    pub fn app(mut self, name: impl Into<String>, version: impl Into<String>) -> Self {
+        self.app_info = Some(AppInfo {
+            name: name.into(),
+            version: version.into(),
+        });
+        self
+    }
+
+    pub fn tag(mut self, tag: impl Into<String>) -> Self {
+        self.tags.push(tag.into());
+        self
+    }
+
+    pub fn description(mut self, description: impl Into<String>) -> Self {
+        self.description = description.into();
+        self
+    }
+
+    pub fn meta_field(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.extra.insert(name.into(), value.into());
+        self
+    }
+
+    /// Copy every metadata field, e.g. from an existing document
+    pub fn meta(mut self, meta: MetaData) -> Self {
+        self.title = meta.title;
+        self.author = meta.author;
+        self.created = meta.created;
+        self.app_info = Some(meta.app_info);
+        self.tags = meta.tags;
+        self.description = meta.description;
+        self.extra = meta.extra;
+        self
+    }
+
+    pub fn variable(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.variables.push(Variable {
+            name: name.into(),
+            value: value.into(),
+            description: None,
+            sensitive: false,
+            resolve_in: vec![],
+            due: None,
+        });
+        self
+    }
+
+    pub fn section(self, id: impl Into<String>, section_type: impl Into<String>, content: impl Into<String>) -> Self {
+        self.add_section(Section::new(id, section_type, content))
+    }
+
+    pub fn add_section(mut self, section: Section) -> Self {
+        self.sections.push(section);
+        self
+    }
+
+    pub fn reference(mut self, reference: Reference) -> Self {
+        self.references.push(reference);
+        self
+    }
+
+    /// Attach a flow diagram (`flow-1`, version 1.0) from mermaid code
+    pub fn flow(self, mermaid_code: impl Into<String>) -> Self {
+        self.flow_graph(FlowGraph {
+            id: "flow-1".to_string(),
+            version: "1.0".to_string(),
+            title: None,
+            mermaid_code: mermaid_code.into(),
+            parsed_graph: GraphStructure {
+                nodes: vec![],
+                edges: vec![],
+                subgraphs: vec![],
+            },
+            node_refs: vec![],
+            layout: None,
+            node_metadata: BTreeMap::new(),
+        })
+    }
+
+    pub fn flow_graph(mut self, flow: FlowGraph) -> Self {
+        self.flow_graph = Some(flow);
+        self
+    }
+
+    /// Validate and assemble the document
+    ///
+    /// Checks the rules the loader enforces (title present, valid and unique
+    /// section IDs and types, unique variable and reference names) and that
+    /// local refTarget IDs point at sections in the document.
+    pub fn build(self) -> Result<ContextDocument> {
+        if self.title.trim().is_empty() {
+            return Err(ContextError::MissingRequiredField("title".to_string()));
+        }
+
+        let mut section_ids = HashSet::new();
+        for section in &self.sections {
+            if section.id.trim().is_empty() {
+                return Err(ContextError::SchemaValidationError(
+                    "Section must have 'id' attribute".to_string(),
+                ));
+            }
+            if !SECTION_TYPES.contains(&section.section_type.as_str()) {
+                return Err(ContextError::SchemaValidationError(format!(
+                    "Section '{}' has invalid type '{}'. Allowed types: {}",
+                    section.id,
+                    section.section_type,
+                    SECTION_TYPES.join(", ")
+                )));
+            }
+            if !section_ids.insert(section.id.as_str()) {
+                return Err(ContextError::SchemaValidationError(format!(
+                    "Duplicate section ID '{}' found. Section IDs must be unique.",
+                    section.id
+                )));
+            }
+        }
+
+        for section in &self.sections {
+            let local_refs = section
+                .ref_target
+                .as_deref()
+                .unwrap_or("")
+                .split_whitespace()
+                .filter(|token| !token.contains('#'));
+            for target in local_refs {
+                if !section_ids.contains(target) {
+                    return Err(ContextError::ReferenceError(format!(
+                        "Section '{}' references missing section '{}'",
+                        section.id, target
+                    )));
+                }
+            }
+        }
+
+        let mut variable_names = HashSet::new();
+        for variable in &self.variables {
+            if !variable_names.insert(variable.name.as_str()) {
+                return Err(ContextError::InvalidArgument(format!(
+                    "Duplicate variable '{}'",
+                    variable.name
+                )));
+            }
+        }
+
+        let mut reference_ids = HashSet::new();
+        for reference in &self.references {
+            if !reference_ids.insert(reference.id.as_str()) {
+                return Err(ContextError::SchemaValidationError(format!(
+                    "Duplicate reference ID '{}' found. Reference IDs must be unique.",
+                    reference.id
+                )));
+            }
+        }
+
+        Ok(ContextDocument {
+            meta: MetaData {
+                title: self.title,
+                author: self.author,
+                created: self.created,
+                app_info: self.app_info.unwrap_or_else(|| AppInfo {
+                    name: APP_NAME.to_string(),
+                    version: env!("CARGO_PKG_VERSION").to_string(),
+                }),
+                tags: self.tags,
+                description: self.description,
+                extra: self.extra,
+            },
+            variables: self.variables,
+            sections: self.sections,
+            references: self.references,
+            flow_graph: self.flow_graph,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_build_document() {
+        let doc = ContextDocument::builder()
+            .title("Onboarding")
+            .author("Jeremy")
+            .created("2025-10-09")
+            .tag("docs")
+            .variable("goal", "Ship v1")
+            .section("intent-1", "intent", "# Intent\n${goal}")
+            .add_section(Section {
+                ref_target: Some("intent-1".to_string()),
+                ..Section::new("eval-1", "evaluation", "Criteria")
+            })
+            .flow("```mermaid\nflowchart TD\n  A[Intent]\n```")
+            .build()
+            .unwrap();
+
+        assert_eq!(doc.meta.title, "Onboarding");
+        assert_eq!(doc.meta.app_info.name, APP_NAME);
+        assert_eq!(doc.meta.tags, vec!["docs"]);
+        assert_eq!(doc.variables.len(), 1);
+        assert_eq!(doc.sections.len(), 2);
+        assert_eq!(doc.flow_graph.unwrap().id, "flow-1");
+    }
+
+    #[test]
+    fn test_build_requires_title() {
+        let result = ContextDocument::builder().author("Jeremy").build();
+
+        assert!(matches!(result, Err(ContextError::MissingRequiredField(field)) if field == "title"));
+    }
+
+    #[test]
+    fn test_build_rejects_invalid_sections() {
+        let duplicate = ContextDocument::builder()
+            .title("Doc")
+            .section("intent-1", "intent", "A")
+            .section("intent-1", "intent", "B")
+            .build();
+        assert!(duplicate.unwrap_err().to_string().contains("Duplicate section ID 'intent-1'"));
+
+        let bad_type = ContextDocument::builder()
+            .title("Doc")
+            .section("notes-1", "notes", "A")
+            .build();
+        assert!(bad_type.unwrap_err().to_string().contains("invalid type 'notes'"));
+
+        let dangling = ContextDocument::builder()
+            .title("Doc")
+            .add_section(Section {
+                ref_target: Some("missing-1 other.xml#intent-1".to_string()),
+                ..Section::new("intent-1", "intent", "A")
+            })
+            .build();
+        assert!(matches!(dangling, Err(ContextError::ReferenceError(msg)) if msg.contains("missing-1")));
+    }
+
+    #[test]
+    fn test_build_rejects_duplicate_variables() {
+        let result = ContextDocument::builder()
+            .title("Doc")
+            .variable("goal", "A")
+            .variable("goal", "B")
+            .build();
+
+        assert!(matches!(result, Err(ContextError::InvalidArgument(_))));
+    }
+}
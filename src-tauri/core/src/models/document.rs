@@ -0,0 +1,177 @@
+use bincode::{Decode, Encode};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use super::{Section, FlowGraph, Reference};
+
+#[derive(Debug, Clone, Serialize, Deserialize, Encode, Decode, PartialEq)]
+pub struct ContextDocument {
+    pub meta: MetaData,
+    pub variables: Vec<Variable>,
+    pub sections: Vec<Section>,
+    /// Bibliography entries from the optional `<references>` block
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub references: Vec<Reference>,
+    pub flow_graph: Option<FlowGraph>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Encode, Decode, PartialEq)]
+pub struct MetaData {
+    pub title: String,
+    pub author: String,
+    pub created: String,
+    pub app_info: AppInfo,
+    pub tags: Vec<String>,
+    pub description: String,
+    /// Custom fields from `<custom>` or unrecognized `<meta>` children
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub extra: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Encode, Decode, PartialEq)]
+pub struct AppInfo {
+    pub name: String,
+    pub version: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Encode, Decode, PartialEq)]
+pub struct Variable {
+    pub name: String,
+    pub value: String,
+    /// What the value should be, from the optional `description` attribute
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// Marked `sensitive="true"`; masked by redaction profiles on export
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub sensitive: bool,
+    /// Contexts the value is substituted in, from `resolveIn="export,assembly"`; all of them when empty.
+    /// Elsewhere the `${name}` reference is left as a placeholder
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub resolve_in: Vec<ResolutionContext>,
+    /// When the value has to be filled in or confirmed, from the `due` attribute (`YYYY-MM-DD`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub due: Option<String>,
+}
+
+/// Where resolved content is going, for variables that should only resolve in some places
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Encode, Decode, PartialEq, Eq, Hash)]
+#[serde(rename_all = "lowercase")]
+pub enum ResolutionContext {
+    /// Sections shown in the app
+    Preview,
+    /// Exported files
+    Export,
+    /// Assembled context for a model
+    Assembly,
+}
+
+impl ResolutionContext {
+    pub const ALL: [ResolutionContext; 3] = [ResolutionContext::Preview, ResolutionContext::Export, ResolutionContext::Assembly];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ResolutionContext::Preview => "preview",
+            ResolutionContext::Export => "export",
+            ResolutionContext::Assembly => "assembly",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<ResolutionContext> {
+        Self::ALL.into_iter().find(|context| context.as_str() == value.trim())
+    }
+}
+
+impl Variable {
+    /// Whether the value is substituted in `context`
+    pub fn resolves_in(&self, context: ResolutionContext) -> bool {
+        self.resolve_in.is_empty() || self.resolve_in.contains(&context)
+    }
+}
+
+fn is_false(value: &bool) -> bool {
+    !*value
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_variable_creation() {
+        let var = Variable {
+            name: "userName".to_string(),
+            value: "Jeremy".to_string(),
+            description: None,
+            sensitive: false,
+            resolve_in: vec![],
+            due: None,
+        };
+
+        assert_eq!(var.name, "userName");
+        assert_eq!(var.value, "Jeremy");
+    }
+
+    #[test]
+    fn test_app_info_serialization() {
+        let app_info = AppInfo {
+            name: "CEC".to_string(),
+            version: "0.1.0".to_string(),
+        };
+
+        let json = serde_json::to_string(&app_info).unwrap();
+        assert!(json.contains("CEC"));
+        assert!(json.contains("0.1.0"));
+    }
+
+    #[test]
+    fn test_metadata_creation() {
+        let meta = MetaData {
+            title: "Test Document".to_string(),
+            author: "Test Author".to_string(),
+            created: "2025-10-09".to_string(),
+            app_info: AppInfo {
+                name: "CEC".to_string(),
+                version: "0.1.0".to_string(),
+            },
+            tags: vec!["test".to_string(), "document".to_string()],
+            description: "A test document".to_string(),
+            extra: BTreeMap::new(),
+        };
+
+        assert_eq!(meta.title, "Test Document");
+        assert_eq!(meta.tags.len(), 2);
+    }
+
+    #[test]
+    fn test_context_document_structure() {
+        let doc = ContextDocument {
+            meta: MetaData {
+                title: "Test".to_string(),
+                author: "Author".to_string(),
+                created: "2025-10-09".to_string(),
+                app_info: AppInfo {
+                    name: "CEC".to_string(),
+                    version: "0.1.0".to_string(),
+                },
+                tags: vec![],
+                description: "Test".to_string(),
+                extra: BTreeMap::new(),
+            },
+            variables: vec![
+                Variable {
+                    name: "var1".to_string(),
+                    value: "value1".to_string(),
+                    description: None,
+                    sensitive: false,
+                    resolve_in: vec![],
+                    due: None,
+                }
+            ],
+            sections: vec![],
+            references: vec![],
+            flow_graph: None,
+        };
+
+        assert_eq!(doc.variables.len(), 1);
+        assert!(doc.flow_graph.is_none());
+    }
+}
@@ -0,0 +1,354 @@
+use bincode::{Decode, Encode};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+#[derive(Debug, Clone, Serialize, Deserialize, Encode, Decode, PartialEq)]
+pub struct FlowGraph {
+    pub id: String,
+    pub version: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    pub mermaid_code: String,
+    pub parsed_graph: GraphStructure,
+    pub node_refs: Vec<NodeReference>,
+    /// Node positions and viewport saved from the flow canvas
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub layout: Option<FlowLayout>,
+    /// Node ID -> owner, status and effort, stored in `<nodes>` and merged into `parsed_graph`
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub node_metadata: BTreeMap<String, NodeMetadata>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Encode, Decode, PartialEq)]
+pub struct FlowLayout {
+    pub zoom: f64,
+    pub pan_x: f64,
+    pub pan_y: f64,
+    /// Node ID -> canvas position
+    #[serde(default)]
+    pub positions: BTreeMap<String, NodePosition>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Encode, Decode, PartialEq)]
+pub struct NodePosition {
+    pub x: f64,
+    pub y: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Encode, Decode, PartialEq)]
+pub struct GraphStructure {
+    pub nodes: Vec<GraphNode>,
+    pub edges: Vec<GraphEdge>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub subgraphs: Vec<Subgraph>,
+}
+
+/// A `subgraph ... end` block grouping nodes of the diagram
+#[derive(Debug, Clone, Serialize, Deserialize, Encode, Decode, PartialEq)]
+pub struct Subgraph {
+    pub id: String,
+    /// The bracketed title, else the ID
+    pub title: String,
+    /// Nodes placed in this block itself; those of nested blocks belong to them
+    pub node_ids: Vec<String>,
+    /// The enclosing block, for nested subgraphs
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parent_id: Option<String>,
+    /// Section opened by a click action on the subgraph ID, as for nodes
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ref_section_id: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Encode, Decode, PartialEq)]
+pub struct GraphNode {
+    pub id: String,
+    pub label: String,
+    #[serde(rename = "nodeType")]
+    pub node_type: NodeType,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ref_section_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<NodeMetadata>,
+}
+
+/// Planning details attached to a flow node
+#[derive(Debug, Clone, Default, Serialize, Deserialize, Encode, Decode, PartialEq)]
+pub struct NodeMetadata {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub owner: Option<String>,
+    /// Free-form workflow state, e.g. "todo", "in-progress", "done"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub status: Option<String>,
+    /// Estimated effort in the document's own unit (hours, points, ...)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub estimated_effort: Option<f64>,
+}
+
+impl NodeMetadata {
+    pub fn is_empty(&self) -> bool {
+        self.owner.is_none() && self.status.is_none() && self.estimated_effort.is_none()
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Encode, Decode, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum NodeType {
+    Rectangle,
+    RoundEdges,
+    Stadium,
+    Subroutine,
+    Cylindrical,
+    Circle,
+    Asymmetric,
+    Rhombus,
+    Hexagon,
+    Parallelogram,
+    Trapezoid,
+}
+
+/// Direction declared in the Mermaid header (`flowchart TD`, `graph LR`, ...)
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Encode, Decode, PartialEq, Default)]
+pub enum FlowDirection {
+    #[default]
+    TopDown,
+    BottomUp,
+    LeftRight,
+    RightLeft,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Encode, Decode, PartialEq)]
+pub struct GraphEdge {
+    pub from: String,
+    pub to: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+    /// Structured form of labels written as `|cond: variable op value|`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub condition: Option<EdgeCondition>,
+}
+
+/// A branch condition comparing a document variable against a literal value
+#[derive(Debug, Clone, Serialize, Deserialize, Encode, Decode, PartialEq)]
+pub struct EdgeCondition {
+    /// Expression as written after `cond:`
+    pub expression: String,
+    pub variable: String,
+    pub operator: ConditionOperator,
+    pub value: String,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Encode, Decode, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum ConditionOperator {
+    Eq,
+    Ne,
+    Gt,
+    Ge,
+    Lt,
+    Le,
+}
+
+impl EdgeCondition {
+    /// Check the condition against a variable value
+    ///
+    /// Values that both parse as numbers are compared numerically, anything
+    /// else is compared as text. A missing variable never satisfies a condition.
+    pub fn evaluate(&self, actual: Option<&str>) -> bool {
+        let Some(actual) = actual else {
+            return false;
+        };
+
+        let ordering = match (actual.trim().parse::<f64>(), self.value.trim().parse::<f64>()) {
+            (Ok(a), Ok(b)) => a.partial_cmp(&b),
+            _ => Some(actual.cmp(self.value.as_str())),
+        };
+
+        match (self.operator, ordering) {
+            (ConditionOperator::Eq, Some(o)) => o.is_eq(),
+            (ConditionOperator::Ne, Some(o)) => o.is_ne(),
+            (ConditionOperator::Gt, Some(o)) => o.is_gt(),
+            (ConditionOperator::Ge, Some(o)) => o.is_ge(),
+            (ConditionOperator::Lt, Some(o)) => o.is_lt(),
+            (ConditionOperator::Le, Some(o)) => o.is_le(),
+            (_, None) => false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Encode, Decode, PartialEq)]
+pub struct NodeReference {
+    pub node_id: String,
+    pub section_id: String,
+    pub click_action: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tooltip: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_graph_node_creation() {
+        let node = GraphNode {
+            id: "A".to_string(),
+            label: "Intent".to_string(),
+            node_type: NodeType::Rectangle,
+            ref_section_id: Some("intent-1".to_string()),
+            metadata: None,
+        };
+
+        assert_eq!(node.id, "A");
+        assert_eq!(node.label, "Intent");
+        assert_eq!(node.ref_section_id, Some("intent-1".to_string()));
+    }
+
+    #[test]
+    fn test_graph_edge_creation() {
+        let edge = GraphEdge {
+            from: "A".to_string(),
+            to: "B".to_string(),
+            label: None,
+            condition: None,
+        };
+
+        assert_eq!(edge.from, "A");
+        assert_eq!(edge.to, "B");
+        assert!(edge.label.is_none());
+    }
+
+    #[test]
+    fn test_graph_edge_with_label() {
+        let edge = GraphEdge {
+            from: "C".to_string(),
+            to: "D".to_string(),
+            label: Some("Alt A".to_string()),
+            condition: None,
+        };
+
+        assert_eq!(edge.label, Some("Alt A".to_string()));
+    }
+
+    #[test]
+    fn test_graph_edge_condition_omitted_when_none() {
+        let edge = GraphEdge {
+            from: "C".to_string(),
+            to: "D".to_string(),
+            label: Some("Success".to_string()),
+            condition: None,
+        };
+
+        let json = serde_json::to_string(&edge).unwrap();
+        assert!(!json.contains("condition"));
+    }
+
+    #[test]
+    fn test_edge_condition_evaluate() {
+        let condition = |operator, value: &str| EdgeCondition {
+            expression: String::new(),
+            variable: "x".to_string(),
+            operator,
+            value: value.to_string(),
+        };
+
+        assert!(condition(ConditionOperator::Gt, "3").evaluate(Some("10")));
+        assert!(!condition(ConditionOperator::Gt, "3").evaluate(Some("3")));
+        assert!(condition(ConditionOperator::Ge, "3.0").evaluate(Some("3")));
+        assert!(condition(ConditionOperator::Eq, "prod").evaluate(Some("prod")));
+        assert!(condition(ConditionOperator::Ne, "prod").evaluate(Some("dev")));
+        assert!(!condition(ConditionOperator::Eq, "prod").evaluate(None));
+    }
+
+    #[test]
+    fn test_node_reference_creation() {
+        let node_ref = NodeReference {
+            node_id: "A".to_string(),
+            section_id: "intent-1".to_string(),
+            click_action: "#intent-1".to_string(),
+            tooltip: Some("Jump to Intent".to_string()),
+        };
+
+        assert_eq!(node_ref.node_id, "A");
+        assert_eq!(node_ref.click_action, "#intent-1");
+    }
+
+    #[test]
+    fn test_graph_structure_creation() {
+        let graph = GraphStructure {
+            nodes: vec![
+                GraphNode {
+                    id: "A".to_string(),
+                    label: "Intent".to_string(),
+                    node_type: NodeType::Rectangle,
+                    ref_section_id: Some("intent-1".to_string()),
+                    metadata: None,
+                },
+            ],
+            edges: vec![
+                GraphEdge {
+                    from: "A".to_string(),
+                    to: "B".to_string(),
+                    label: None,
+                    condition: None,
+                },
+            ],
+            subgraphs: vec![],
+        };
+
+        assert_eq!(graph.nodes.len(), 1);
+        assert_eq!(graph.edges.len(), 1);
+    }
+
+    #[test]
+    fn test_flow_graph_creation() {
+        let flow = FlowGraph {
+            id: "flow-1".to_string(),
+            version: "1.0".to_string(),
+            title: Some("Document Flow".to_string()),
+            mermaid_code: "flowchart TD\n  A[Intent] --> B[Evaluation]".to_string(),
+            parsed_graph: GraphStructure {
+                nodes: vec![],
+                edges: vec![],
+                subgraphs: vec![],
+            },
+            node_refs: vec![],
+            layout: None,
+            node_metadata: BTreeMap::new(),
+        };
+
+        assert_eq!(flow.id, "flow-1");
+        assert_eq!(flow.version, "1.0");
+        assert_eq!(flow.title, Some("Document Flow".to_string()));
+    }
+
+    #[test]
+    fn test_node_type_serialization() {
+        let node = GraphNode {
+            id: "A".to_string(),
+            label: "Test".to_string(),
+            node_type: NodeType::Rectangle,
+            ref_section_id: None,
+            metadata: None,
+        };
+
+        let json = serde_json::to_string(&node).unwrap();
+        // Should be camelCase in JSON
+        assert!(json.contains(r#""nodeType":"rectangle""#));
+    }
+
+    #[test]
+    fn test_flow_layout_serialization() {
+        let mut positions = BTreeMap::new();
+        positions.insert("A".to_string(), NodePosition { x: 10.0, y: 20.5 });
+
+        let layout = FlowLayout {
+            zoom: 1.5,
+            pan_x: 0.0,
+            pan_y: -40.0,
+            positions,
+        };
+
+        let json = serde_json::to_string(&layout).unwrap();
+        assert!(json.contains(r#""positions":{"A":{"x":10.0,"y":20.5}}"#));
+    }
+}
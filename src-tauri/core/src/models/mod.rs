@@ -1,7 +1,11 @@
 pub mod document;
 pub mod section;
 pub mod flow_graph;
+pub mod reference;
+pub mod builder;
 
 pub use document::*;
 pub use section::*;
 pub use flow_graph::*;
+pub use reference::*;
+pub use builder::*;
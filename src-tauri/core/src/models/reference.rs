@@ -0,0 +1,33 @@
+use bincode::{Decode, Encode};
+use serde::{Deserialize, Serialize};
+
+/// A bibliography entry from the `<references>` block, cited in content as `[@id]`
+#[derive(Debug, Clone, Serialize, Deserialize, Encode, Decode, PartialEq)]
+pub struct Reference {
+    pub id: String,
+    pub title: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    /// Full citation text (e.g. author, year, publisher)
+    #[serde(default)]
+    pub citation: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reference_serialization() {
+        let reference = Reference {
+            id: "smith-2024".to_string(),
+            title: "Context Engineering".to_string(),
+            url: None,
+            citation: "Smith, J. (2024).".to_string(),
+        };
+
+        let json = serde_json::to_string(&reference).unwrap();
+        assert!(json.contains(r#""id":"smith-2024""#));
+        assert!(!json.contains("url"));
+    }
+}
@@ -0,0 +1,361 @@
+use bincode::{Decode, Encode};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Section types allowed by the document schema
+pub const SECTION_TYPES: &[&str] = &["intent", "evaluation", "process", "alternatives"];
+
+#[derive(Debug, Clone, Serialize, Deserialize, Encode, Decode, PartialEq)]
+pub struct Section {
+    pub id: String,
+    #[serde(rename = "type")]
+    pub section_type: String,
+    pub content: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ref_target: Option<String>,
+    /// Comma-separated `tags` attribute, split and trimmed
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    /// Size limit for the resolved content, from the `budget` attribute
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub budget: Option<Budget>,
+    /// Language-specific variants from `<content lang="..">`, keyed by language code
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub translations: BTreeMap<String, String>,
+    #[serde(default)]
+    pub children: Vec<Section>,
+    /// Read-only content inlined from cross-document references
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub transclusions: Vec<Transclusion>,
+    /// Marked `sensitive="true"`; redacted with its subsections by redaction profiles on export
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub sensitive: bool,
+    /// Marked `encrypted="true"`; the content and translations hold ciphertext (see `section_encryption`)
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub encrypted: bool,
+}
+
+fn is_false(value: &bool) -> bool {
+    !*value
+}
+
+/// Size limit declared with `budget="500 tokens"` or `budget="2000 chars"`
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Encode, Decode, PartialEq, Eq)]
+pub struct Budget {
+    pub limit: usize,
+    pub unit: BudgetUnit,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Encode, Decode, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum BudgetUnit {
+    Tokens,
+    Chars,
+}
+
+/// A reference to a section in another document, written in refTarget as `path#section-id`
+#[derive(Debug, Clone, Serialize, Deserialize, Encode, Decode, PartialEq)]
+pub struct CrossDocumentRef {
+    pub path: String,
+    pub section_id: String,
+}
+
+/// Content inlined from a cross-document reference
+#[derive(Debug, Clone, Serialize, Deserialize, Encode, Decode, PartialEq)]
+pub struct Transclusion {
+    pub source_path: String,
+    pub section_id: String,
+    pub section_type: String,
+    pub content: String,
+    /// 1 for direct references, 2 for references of references, and so on
+    pub depth: usize,
+    pub read_only: bool,
+}
+
+impl CrossDocumentRef {
+    /// Parse a refTarget token; plain section IDs (no path before `#`) return None
+    pub fn parse(token: &str) -> Option<CrossDocumentRef> {
+        let (path, section_id) = token.split_once('#')?;
+        if path.is_empty() || section_id.is_empty() {
+            return None;
+        }
+
+        Some(CrossDocumentRef {
+            path: path.to_string(),
+            section_id: section_id.to_string(),
+        })
+    }
+}
+
+impl Budget {
+    /// Parse a budget attribute value; a bare number counts tokens
+    pub fn parse(value: &str) -> Option<Budget> {
+        let value = value.trim();
+        let digits_end = value.find(|c: char| !c.is_ascii_digit()).unwrap_or(value.len());
+        let limit = value[..digits_end].parse().ok()?;
+        let unit = match value[digits_end..].trim().to_ascii_lowercase().as_str() {
+            "" | "token" | "tokens" => BudgetUnit::Tokens,
+            "char" | "chars" | "characters" => BudgetUnit::Chars,
+            _ => return None,
+        };
+        Some(Budget { limit, unit })
+    }
+
+    /// Size of the text in this budget's unit
+    pub fn measure(&self, text: &str) -> usize {
+        match self.unit {
+            BudgetUnit::Tokens => estimate_tokens(text),
+            BudgetUnit::Chars => text.chars().count(),
+        }
+    }
+}
+
+impl std::fmt::Display for Budget {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let unit = match self.unit {
+            BudgetUnit::Tokens => "tokens",
+            BudgetUnit::Chars => "chars",
+        };
+        write!(f, "{} {}", self.limit, unit)
+    }
+}
+
+/// Rough token count for LLM prompts: about four characters per token
+///
+/// Tokenizers differ between models; this is the usual rule of thumb for
+/// English text and is only meant for budgeting.
+pub fn estimate_tokens(text: &str) -> usize {
+    text.chars().count().div_ceil(4)
+}
+
+impl Section {
+    /// Whether the section carries the tag (case-insensitive)
+    pub fn has_tag(&self, tag: &str) -> bool {
+        self.tags.iter().any(|t| t.eq_ignore_ascii_case(tag.trim()))
+    }
+
+    /// Content in the preferred language
+    ///
+    /// Falls back from an exact match (`de-AT`) to the primary language (`de`)
+    /// and finally to the default, untagged content. Matching is case-insensitive.
+    pub fn localized_content(&self, lang: &str) -> &str {
+        let lang = lang.trim();
+        let primary = lang.split(['-', '_']).next().unwrap_or(lang);
+
+        [lang, primary]
+            .iter()
+            .find_map(|candidate| {
+                self.translations
+                    .iter()
+                    .find(|(code, _)| code.eq_ignore_ascii_case(candidate))
+                    .map(|(_, content)| content.as_str())
+            })
+            .unwrap_or(&self.content)
+    }
+
+    /// Cross-document references listed in refTarget
+    pub fn cross_document_refs(&self) -> Vec<CrossDocumentRef> {
+        self.ref_target
+            .as_deref()
+            .unwrap_or("")
+            .split_whitespace()
+            .filter_map(CrossDocumentRef::parse)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_section_creation() {
+        let section = Section {
+            id: "intent-1".to_string(),
+            section_type: "intent".to_string(),
+            content: "# Intent\nTest content".to_string(),
+            ref_target: None,
+            children: vec![],
+            budget: None,
+            transclusions: vec![],
+            tags: vec![],
+            translations: BTreeMap::new(),
+            sensitive: false,
+            encrypted: false,
+        };
+
+        assert_eq!(section.id, "intent-1");
+        assert_eq!(section.section_type, "intent");
+        assert!(section.children.is_empty());
+    }
+
+    #[test]
+    fn test_section_with_children() {
+        let child = Section {
+            id: "alt-1".to_string(),
+            section_type: "alternatives".to_string(),
+            content: "Alternative content".to_string(),
+            ref_target: None,
+            children: vec![],
+            budget: None,
+            transclusions: vec![],
+            tags: vec![],
+            translations: BTreeMap::new(),
+            sensitive: false,
+            encrypted: false,
+        };
+
+        let parent = Section {
+            id: "proc-1".to_string(),
+            section_type: "process".to_string(),
+            content: "Process content".to_string(),
+            ref_target: Some("intent-1 eval-1".to_string()),
+            children: vec![child],
+            budget: None,
+            transclusions: vec![],
+            tags: vec![],
+            translations: BTreeMap::new(),
+            sensitive: false,
+            encrypted: false,
+        };
+
+        assert_eq!(parent.children.len(), 1);
+        assert_eq!(parent.ref_target, Some("intent-1 eval-1".to_string()));
+    }
+
+    #[test]
+    fn test_section_serialization() {
+        let section = Section {
+            id: "test-1".to_string(),
+            section_type: "test".to_string(),
+            content: "Test".to_string(),
+            ref_target: None,
+            children: vec![],
+            budget: None,
+            transclusions: vec![],
+            tags: vec![],
+            translations: BTreeMap::new(),
+            sensitive: false,
+            encrypted: false,
+        };
+
+        let json = serde_json::to_string(&section).unwrap();
+        // Check that "type" is used in JSON (not "section_type")
+        assert!(json.contains(r#""type":"test""#));
+        assert!(json.contains(r#""id":"test-1""#));
+    }
+
+    #[test]
+    fn test_section_ref_target_omitted_when_none() {
+        let section = Section {
+            id: "test-1".to_string(),
+            section_type: "test".to_string(),
+            content: "Test".to_string(),
+            ref_target: None,
+            children: vec![],
+            budget: None,
+            transclusions: vec![],
+            tags: vec![],
+            translations: BTreeMap::new(),
+            sensitive: false,
+            encrypted: false,
+        };
+
+        let json = serde_json::to_string(&section).unwrap();
+        // ref_target should be omitted when None
+        assert!(!json.contains("refTarget"));
+    }
+
+    #[test]
+    fn test_cross_document_ref_parse() {
+        let parsed = CrossDocumentRef::parse("shared/base.xml#intent-1").unwrap();
+        assert_eq!(parsed.path, "shared/base.xml");
+        assert_eq!(parsed.section_id, "intent-1");
+
+        assert!(CrossDocumentRef::parse("intent-1").is_none());
+        assert!(CrossDocumentRef::parse("#intent-1").is_none());
+        assert!(CrossDocumentRef::parse("base.xml#").is_none());
+    }
+
+    #[test]
+    fn test_section_cross_document_refs() {
+        let section = Section {
+            id: "proc-1".to_string(),
+            section_type: "process".to_string(),
+            content: "Process".to_string(),
+            ref_target: Some("intent-1 other.xml#eval-1".to_string()),
+            children: vec![],
+            budget: None,
+            transclusions: vec![],
+            tags: vec![],
+            translations: BTreeMap::new(),
+            sensitive: false,
+            encrypted: false,
+        };
+
+        let refs = section.cross_document_refs();
+        assert_eq!(refs.len(), 1);
+        assert_eq!(refs[0].path, "other.xml");
+        assert_eq!(refs[0].section_id, "eval-1");
+    }
+
+    #[test]
+    fn test_section_has_tag() {
+        let section = Section {
+            id: "intent-1".to_string(),
+            section_type: "intent".to_string(),
+            content: String::new(),
+            ref_target: None,
+            tags: vec!["Draft".to_string(), "backend".to_string()],
+            translations: BTreeMap::new(),
+            children: vec![],
+            budget: None,
+            transclusions: vec![],
+            sensitive: false,
+            encrypted: false,
+        };
+
+        assert!(section.has_tag("draft"));
+        assert!(section.has_tag(" backend "));
+        assert!(!section.has_tag("frontend"));
+    }
+
+    #[test]
+    fn test_localized_content_fallback() {
+        let mut translations = BTreeMap::new();
+        translations.insert("de".to_string(), "Absicht".to_string());
+        translations.insert("fr-CA".to_string(), "Intention (CA)".to_string());
+
+        let section = Section {
+            id: "intent-1".to_string(),
+            section_type: "intent".to_string(),
+            content: "Intent".to_string(),
+            ref_target: None,
+            tags: vec![],
+            translations,
+            children: vec![],
+            budget: None,
+            transclusions: vec![],
+            sensitive: false,
+            encrypted: false,
+        };
+
+        assert_eq!(section.localized_content("de"), "Absicht");
+        assert_eq!(section.localized_content("de-AT"), "Absicht");
+        assert_eq!(section.localized_content("FR-ca"), "Intention (CA)");
+        assert_eq!(section.localized_content("fr"), "Intent");
+        assert_eq!(section.localized_content("ja"), "Intent");
+    }
+
+    #[test]
+    fn test_budget_parse_and_measure() {
+        assert_eq!(Budget::parse("500"), Some(Budget { limit: 500, unit: BudgetUnit::Tokens }));
+        assert_eq!(Budget::parse(" 2000 Chars "), Some(Budget { limit: 2000, unit: BudgetUnit::Chars }));
+        assert_eq!(Budget::parse("10tokens").unwrap().to_string(), "10 tokens");
+        assert_eq!(Budget::parse("lots"), None);
+        assert_eq!(Budget::parse("10 words"), None);
+
+        assert_eq!(Budget::parse("10 chars").unwrap().measure("héllo"), 5);
+        assert_eq!(Budget::parse("10").unwrap().measure("123456789"), 3);
+    }
+}
@@ -0,0 +1,125 @@
+use crate::error::{ContextError, Result};
+use serde::{Deserialize, Serialize};
+
+const UTF8_BOM: &[u8] = &[0xEF, 0xBB, 0xBF];
+const UTF16_LE_BOM: &[u8] = &[0xFF, 0xFE];
+const UTF16_BE_BOM: &[u8] = &[0xFE, 0xFF];
+
+/// Something unusual about a file's raw bytes that was corrected before parsing
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum InputQuirk {
+    /// UTF-8 byte order mark at the start of the file
+    ByteOrderMark,
+    /// File was UTF-16 encoded and has been decoded
+    Utf16Encoding,
+    /// Whitespace before the XML declaration or root element
+    LeadingWhitespace,
+}
+
+impl InputQuirk {
+    /// Short identifier used in diagnostics
+    pub fn code(&self) -> &'static str {
+        match self {
+            InputQuirk::ByteOrderMark => "byte-order-mark",
+            InputQuirk::Utf16Encoding => "utf16-encoding",
+            InputQuirk::LeadingWhitespace => "leading-whitespace",
+        }
+    }
+
+    /// Human-readable warning shown when opening the document
+    pub fn message(&self) -> &'static str {
+        match self {
+            InputQuirk::ByteOrderMark => "File starts with a byte order mark; it will be removed on save",
+            InputQuirk::Utf16Encoding => "File is UTF-16 encoded; it will be saved as UTF-8",
+            InputQuirk::LeadingWhitespace => "File has whitespace before the XML content; it will be removed on save",
+        }
+    }
+}
+
+/// Decode raw file bytes into XML text the validator accepts
+///
+/// Strips a byte order mark (decoding UTF-16 when the mark says so) and any
+/// whitespace before the first `<`, which otherwise fails the XML declaration
+/// and root element checks. Each correction is reported as a quirk.
+pub fn decode_input(bytes: &[u8]) -> Result<(String, Vec<InputQuirk>)> {
+    let mut quirks = Vec::new();
+
+    let text = if let Some(rest) = bytes.strip_prefix(UTF8_BOM) {
+        quirks.push(InputQuirk::ByteOrderMark);
+        decode_utf8(rest)?
+    } else if let Some(rest) = bytes.strip_prefix(UTF16_LE_BOM) {
+        quirks.push(InputQuirk::Utf16Encoding);
+        decode_utf16(rest, u16::from_le_bytes)?
+    } else if let Some(rest) = bytes.strip_prefix(UTF16_BE_BOM) {
+        quirks.push(InputQuirk::Utf16Encoding);
+        decode_utf16(rest, u16::from_be_bytes)?
+    } else {
+        decode_utf8(bytes)?
+    };
+
+    let trimmed = text.trim_start();
+    if trimmed.len() == text.len() {
+        return Ok((text, quirks));
+    }
+    quirks.push(InputQuirk::LeadingWhitespace);
+    Ok((trimmed.to_string(), quirks))
+}
+
+fn decode_utf8(bytes: &[u8]) -> Result<String> {
+    String::from_utf8(bytes.to_vec()).map_err(|e| ContextError::InvalidXml(format!("File is not valid UTF-8: {}", e)))
+}
+
+fn decode_utf16(bytes: &[u8], to_unit: fn([u8; 2]) -> u16) -> Result<String> {
+    if !bytes.len().is_multiple_of(2) {
+        return Err(ContextError::InvalidXml("UTF-16 file has an odd number of bytes".to_string()));
+    }
+    let units = bytes.chunks_exact(2).map(|pair| to_unit([pair[0], pair[1]]));
+    char::decode_utf16(units)
+        .collect::<std::result::Result<String, _>>()
+        .map_err(|e| ContextError::InvalidXml(format!("File is not valid UTF-16: {}", e)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const XML: &str = "<?xml version=\"1.0\"?><context/>";
+
+    #[test]
+    fn test_clean_input_has_no_quirks() {
+        let (text, quirks) = decode_input(XML.as_bytes()).unwrap();
+        assert_eq!(text, XML);
+        assert!(quirks.is_empty());
+    }
+
+    #[test]
+    fn test_strips_utf8_bom_and_leading_whitespace() {
+        let mut bytes = UTF8_BOM.to_vec();
+        bytes.extend_from_slice(b"\r\n  ");
+        bytes.extend_from_slice(XML.as_bytes());
+
+        let (text, quirks) = decode_input(&bytes).unwrap();
+
+        assert_eq!(text, XML);
+        assert_eq!(quirks, vec![InputQuirk::ByteOrderMark, InputQuirk::LeadingWhitespace]);
+    }
+
+    #[test]
+    fn test_decodes_utf16() {
+        let mut le = UTF16_LE_BOM.to_vec();
+        let mut be = UTF16_BE_BOM.to_vec();
+        for unit in XML.encode_utf16() {
+            le.extend_from_slice(&unit.to_le_bytes());
+            be.extend_from_slice(&unit.to_be_bytes());
+        }
+
+        assert_eq!(decode_input(&le).unwrap(), (XML.to_string(), vec![InputQuirk::Utf16Encoding]));
+        assert_eq!(decode_input(&be).unwrap(), (XML.to_string(), vec![InputQuirk::Utf16Encoding]));
+    }
+
+    #[test]
+    fn test_rejects_invalid_utf8() {
+        assert!(matches!(decode_input(&[b'<', 0xFF, b'>']), Err(ContextError::InvalidXml(_))));
+    }
+}
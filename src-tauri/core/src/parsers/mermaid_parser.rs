@@ -0,0 +1,586 @@
+use regex::Regex;
+use std::borrow::Cow;
+use std::collections::HashSet;
+use std::sync::LazyLock;
+use crate::error::Result;
+use crate::models::*;
+
+// Compiled once and shared; flows are re-parsed on every load and edit
+static MERMAID_FENCE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"```mermaid\s*\n([\s\S]*?)\n```").unwrap());
+static FLOW_HEADER: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?m)^\s*(?:flowchart|graph)\s+(TB|TD|BT|LR|RL)\b").unwrap());
+static NODE_ID: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\w+").unwrap());
+/// Opening and closing delimiters of each node shape, matching a quoted (group 1) or bare (group 2) label;
+/// longer openings come first so `((` is not read as `(`
+static NODE_SHAPES: LazyLock<Vec<(Regex, NodeType)>> = LazyLock::new(|| {
+    [
+        ("(((", ")))", NodeType::Circle),
+        ("((", "))", NodeType::Circle),
+        ("([", "])", NodeType::Stadium),
+        ("(", ")", NodeType::RoundEdges),
+        ("[[", "]]", NodeType::Subroutine),
+        ("[(", ")]", NodeType::Cylindrical),
+        ("[/", "/]", NodeType::Parallelogram),
+        ("[\\", "\\]", NodeType::Parallelogram),
+        ("[/", "\\]", NodeType::Trapezoid),
+        ("[\\", "/]", NodeType::Trapezoid),
+        ("[", "]", NodeType::Rectangle),
+        ("{{", "}}", NodeType::Hexagon),
+        ("{", "}", NodeType::Rhombus),
+        (">", "]", NodeType::Asymmetric),
+    ]
+    .into_iter()
+    .map(|(open, close, node_type)| {
+        let end = regex::escape(&close[close.len() - 1..]);
+        let pattern = format!(r#"^{}(?:"([^"]*)"|([^"{}][^{}]*?)){}"#, regex::escape(open), end, end, regex::escape(close));
+        (Regex::new(&pattern).unwrap(), node_type)
+    })
+    .collect()
+});
+/// `subgraph id[Title]`, `subgraph id["Title"]`, `subgraph Title` or a bare `subgraph`
+static SUBGRAPH: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"^subgraph(?:\s+(.*))?$").unwrap());
+static SUBGRAPH_TITLE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r#"^(\w+)\s*\[(?:"([^"]*)"|([^\]]*))\]$"#).unwrap());
+/// Statements inside a subgraph that name nodes without placing them
+const NON_PLACING_KEYWORDS: &[&str] = &["click", "style", "class", "classDef", "linkStyle", "direction"];
+static EDGE_LABEL: LazyLock<Regex> = LazyLock::new(|| Regex::new(r#"\|(?:"[^"]*"|[^|]*)\|"#).unwrap());
+/// `from[label] --> |label| to`: the source's own label is skipped so dashes in it are not taken for the arrow,
+/// and the edge label may be quoted to contain pipes
+static EDGE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"^(\w+)\s*(?:\["[^"]*"\]|\[(?:[^"\]][^\]]*)?\]|\("[^"]*"\)|\((?:[^")][^)]*)?\))?[^\-\[\](){}]*-->\s*(?:\|(?:"([^"]*)"|([^|]*))\|)?\s*(\w+)"#)
+        .unwrap()
+});
+/// Mermaid entity codes: `#quot;`, `#124;`, ...
+static ENTITY: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"#(\d+|[a-zA-Z]+);").unwrap());
+static CONDITION_LABEL: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^\s*cond:\s*((?:\$\{)?([A-Za-z_][A-Za-z0-9_]*)\}?\s*(==|!=|>=|<=|=|>|<)\s*(.+?))\s*$").unwrap()
+});
+static CLICK_ACTION: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r#"click\s+(\w+)\s+"([^"]+)"\s*(?:"([^"]*)")?"#).unwrap());
+
+pub fn parse_mermaid(mermaid_code: &str) -> Result<GraphStructure> {
+    let clean_code = extract_mermaid_from_markdown(mermaid_code)?;
+
+    let nodes = parse_nodes(&clean_code)?;
+    let edges = parse_edges(&clean_code)?;
+    let subgraphs = parse_subgraphs(&clean_code, &nodes);
+
+    Ok(GraphStructure { nodes, edges, subgraphs })
+}
+
+pub fn extract_mermaid_from_markdown(content: &str) -> Result<String> {
+    // Extract content between ```mermaid and ```
+    if let Some(caps) = MERMAID_FENCE.captures(content) {
+        Ok(caps[1].to_string())
+    } else {
+        // If no markdown fence, assume it's pure mermaid code
+        Ok(content.to_string())
+    }
+}
+
+/// Read the flow direction from the `flowchart`/`graph` header, defaulting to top-down
+pub fn parse_direction(code: &str) -> FlowDirection {
+    match FLOW_HEADER.captures(code).as_ref().map(|caps| &caps[1]) {
+        Some("BT") => FlowDirection::BottomUp,
+        Some("LR") => FlowDirection::LeftRight,
+        Some("RL") => FlowDirection::RightLeft,
+        _ => FlowDirection::TopDown,
+    }
+}
+
+fn parse_nodes(code: &str) -> Result<Vec<GraphNode>> {
+    let mut nodes: Vec<GraphNode> = Vec::new();
+
+    for line in code.lines() {
+        let line = line.trim();
+        if line.starts_with("click ") || line.starts_with("%%") || SUBGRAPH.is_match(line) {
+            continue;
+        }
+
+        // Nodes declared with a shape, A[Label], B{Decide}, C((Done)) and so on, in document order;
+        // the first declaration wins
+        for (id, shape) in node_mentions(line) {
+            let Some((label, node_type)) = shape else {
+                continue;
+            };
+            if nodes.iter().any(|n| n.id == id) {
+                continue;
+            }
+            nodes.push(GraphNode {
+                id,
+                label,
+                node_type,
+                ref_section_id: None,
+                metadata: None,
+            });
+        }
+    }
+
+    Ok(nodes)
+}
+
+/// IDs written on a line, in order, each with the label and type it is declared with there, if any
+fn node_mentions(line: &str) -> Vec<(String, Option<(String, NodeType)>)> {
+    // Edge labels may contain bracket syntax that is not a node
+    let line = EDGE_LABEL.replace_all(line, "||");
+    let mut mentions = Vec::new();
+    let mut position = 0;
+    while let Some(id) = NODE_ID.find_at(&line, position) {
+        position = id.end();
+        let shape = node_shape(&line[id.end()..]).map(|(label, node_type, length)| {
+            position += length;
+            (label, node_type)
+        });
+        mentions.push((id.as_str().to_string(), shape));
+    }
+    mentions
+}
+
+/// Subgraphs in the order their blocks open, each with the nodes placed in it
+///
+/// A node belongs to the innermost block it is first mentioned in; mentions
+/// outside every block don't place it, as in Mermaid. Only nodes in `nodes`
+/// are placed.
+fn parse_subgraphs(code: &str, nodes: &[GraphNode]) -> Vec<Subgraph> {
+    let mut subgraphs: Vec<Subgraph> = Vec::new();
+    // Indices in `subgraphs` of the blocks around the current line, innermost last
+    let mut open: Vec<usize> = Vec::new();
+    let mut placed = HashSet::new();
+
+    for line in code.lines().map(str::trim) {
+        if let Some(caps) = SUBGRAPH.captures(line) {
+            let (mut id, title) = subgraph_header(caps.get(1).map_or("", |m| m.as_str()).trim(), subgraphs.len());
+            // IDs stay unique so nesting can't loop back on itself
+            if subgraphs.iter().any(|s| s.id == id) {
+                id = format!("subGraph{}", subgraphs.len());
+            }
+            let parent_id = open.last().map(|&index| subgraphs[index].id.clone());
+            subgraphs.push(Subgraph { id, title, node_ids: vec![], parent_id, ref_section_id: None });
+            open.push(subgraphs.len() - 1);
+            continue;
+        }
+        if line == "end" {
+            open.pop();
+            continue;
+        }
+        let Some(&current) = open.last() else {
+            continue;
+        };
+        let keyword = line.split_whitespace().next().unwrap_or_default();
+        if line.starts_with("%%") || NON_PLACING_KEYWORDS.contains(&keyword) {
+            continue;
+        }
+        for (id, _) in node_mentions(line) {
+            if nodes.iter().any(|n| n.id == id) && placed.insert(id.clone()) {
+                subgraphs[current].node_ids.push(id);
+            }
+        }
+    }
+
+    subgraphs
+}
+
+/// ID and title from what follows `subgraph`; a block with neither is numbered, as Mermaid does
+fn subgraph_header(header: &str, index: usize) -> (String, String) {
+    if let Some(caps) = SUBGRAPH_TITLE.captures(header) {
+        let title = caps.get(2).or(caps.get(3)).map_or("", |m| m.as_str().trim());
+        return (caps[1].to_string(), unescape_label(title));
+    }
+    let title = header.strip_prefix('"').and_then(|h| h.strip_suffix('"')).unwrap_or(header);
+    if title.is_empty() {
+        let id = format!("subGraph{}", index);
+        return (id.clone(), id);
+    }
+    (title.to_string(), unescape_label(title))
+}
+
+/// Label, type and length of the node shape that `text` starts with, e.g. `{Decide}` or `(["Done"])`
+fn node_shape(text: &str) -> Option<(String, NodeType, usize)> {
+    NODE_SHAPES.iter().find_map(|(pattern, node_type)| {
+        let caps = pattern.captures(text)?;
+        let label = caps.get(1).or(caps.get(2))?;
+        Some((unescape_label(label.as_str()), node_type.clone(), caps[0].len()))
+    })
+}
+
+/// The line without the shape and label of the node it starts with, which may hold dashes and brackets of its own
+fn strip_source_shape(line: &str) -> Cow<'_, str> {
+    let id_end = line.find(|c: char| !(c.is_alphanumeric() || c == '_')).unwrap_or(line.len());
+    match node_shape(&line[id_end..]) {
+        Some((_, _, length)) if id_end > 0 => Cow::Owned(format!("{}{}", &line[..id_end], &line[id_end + length..])),
+        _ => Cow::Borrowed(line),
+    }
+}
+
+fn parse_edges(code: &str) -> Result<Vec<GraphEdge>> {
+    let mut edges = Vec::new();
+
+    // A --> B, A[Label] --> B[Label], C -->|Alt A| D[Alternative A] or C -->|"a | b"| D
+    for line in code.lines() {
+        let line = line.trim();
+        if !line.contains("-->") {
+            continue;
+        }
+        if let Some(caps) = EDGE.captures(&strip_source_shape(line)) {
+            let label = caps.get(2).or(caps.get(3)).map(|m| unescape_label(m.as_str()));
+            edges.push(GraphEdge {
+                from: caps[1].to_string(),
+                to: caps[4].to_string(),
+                condition: label.as_deref().and_then(parse_condition),
+                label,
+            });
+        }
+    }
+
+    Ok(edges)
+}
+
+/// Decode Mermaid entity codes (`#quot;`, `#124;`, `#35;`) in a label
+///
+/// Unknown named entities are left as written. The inverse is
+/// `mermaid_serializer::escape_label`.
+pub fn unescape_label(label: &str) -> String {
+    ENTITY
+        .replace_all(label, |caps: &regex::Captures| {
+            let decoded = match &caps[1] {
+                "quot" => Some('"'),
+                "amp" => Some('&'),
+                "lt" => Some('<'),
+                "gt" => Some('>'),
+                "nbsp" => Some('\u{a0}'),
+                code => code.parse::<u32>().ok().and_then(char::from_u32),
+            };
+            decoded.map_or_else(|| caps[0].to_string(), String::from)
+        })
+        .into_owned()
+}
+
+/// Parse an edge label written as `cond: variable op value` (e.g. `cond: score >= 85`)
+///
+/// The variable may also be written as `${variable}` and the value may be quoted.
+/// Labels that do not follow the convention return None.
+pub fn parse_condition(label: &str) -> Option<EdgeCondition> {
+    let caps = CONDITION_LABEL.captures(label)?;
+
+    let operator = match &caps[3] {
+        "=" | "==" => ConditionOperator::Eq,
+        "!=" => ConditionOperator::Ne,
+        ">" => ConditionOperator::Gt,
+        ">=" => ConditionOperator::Ge,
+        "<" => ConditionOperator::Lt,
+        _ => ConditionOperator::Le,
+    };
+
+    let raw_value = &caps[4];
+    let value = ['"', '\'']
+        .iter()
+        .find_map(|q| raw_value.strip_prefix(*q).and_then(|v| v.strip_suffix(*q)))
+        .unwrap_or(raw_value);
+
+    Some(EdgeCondition {
+        expression: caps[1].to_string(),
+        variable: caps[2].to_string(),
+        operator,
+        value: value.to_string(),
+    })
+}
+
+pub fn parse_click_actions(code: &str) -> Result<Vec<NodeReference>> {
+    let mut node_refs = Vec::new();
+
+    // click A "#intent-1" "Jump to Intent"
+    for caps in CLICK_ACTION.captures_iter(code) {
+        let node_id = caps[1].to_string();
+        let click_action = caps[2].to_string();
+
+        // Extract section_id from click_action (e.g., "#intent-1" -> "intent-1")
+        let section_id = click_action.trim_start_matches('#').to_string();
+
+        let tooltip = caps.get(3).map(|m| unescape_label(m.as_str()));
+
+        node_refs.push(NodeReference {
+            node_id,
+            section_id,
+            click_action,
+            tooltip,
+        });
+    }
+
+    Ok(node_refs)
+}
+
+pub fn enrich_flow_graph(flow: &mut FlowGraph) -> Result<()> {
+    // Parse mermaid code
+    flow.parsed_graph = parse_mermaid(&flow.mermaid_code)?;
+
+    // Parse click actions
+    flow.node_refs = parse_click_actions(&flow.mermaid_code)?;
+
+    // Link node references to graph nodes, and to subgraphs clicked by their ID
+    for node_ref in &flow.node_refs {
+        if let Some(node) = flow.parsed_graph.nodes.iter_mut().find(|n| n.id == node_ref.node_id) {
+            node.ref_section_id = Some(node_ref.section_id.clone());
+        }
+        if let Some(subgraph) = flow.parsed_graph.subgraphs.iter_mut().find(|s| s.id == node_ref.node_id) {
+            subgraph.ref_section_id = Some(node_ref.section_id.clone());
+        }
+    }
+
+    // Attach metadata from <nodes>; entries for nodes no longer in the diagram are kept but unused
+    for node in &mut flow.parsed_graph.nodes {
+        node.metadata = flow.node_metadata.get(&node.id).cloned();
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract_mermaid_from_markdown() {
+        let content = r#"
+```mermaid
+flowchart TD
+  A[Intent] --> B[Evaluation]
+```
+        "#;
+
+        let result = extract_mermaid_from_markdown(content).unwrap();
+        assert!(result.contains("flowchart TD"));
+        assert!(result.contains("A[Intent]"));
+    }
+
+    #[test]
+    fn test_parse_rectangle_nodes() {
+        let code = "A[Intent] --> B[Evaluation]";
+        let nodes = parse_nodes(code).unwrap();
+
+        assert_eq!(nodes.len(), 2);
+        assert_eq!(nodes[0].id, "A");
+        assert_eq!(nodes[0].label, "Intent");
+        assert_eq!(nodes[1].id, "B");
+        assert_eq!(nodes[1].label, "Evaluation");
+    }
+
+    #[test]
+    fn test_parse_node_shapes() {
+        let code = r#"A{Decide?} --> B([Done])
+B --> C[[Sub]] & D[(Store)]
+E((Hub)) --> F{{"Prep {x}"}}
+G[/In/] --> H[\Out\]
+I[/Wide\] --> J[\Narrow/]
+K>Flag] --> L(((End)))
+M(Round) --> N[Rect (draft)]"#;
+        let nodes = parse_nodes(code).unwrap();
+
+        let shapes: Vec<_> = nodes.iter().map(|n| (n.id.as_str(), n.label.as_str(), n.node_type.clone())).collect();
+        assert_eq!(
+            shapes,
+            vec![
+                ("A", "Decide?", NodeType::Rhombus),
+                ("B", "Done", NodeType::Stadium),
+                ("C", "Sub", NodeType::Subroutine),
+                ("D", "Store", NodeType::Cylindrical),
+                ("E", "Hub", NodeType::Circle),
+                ("F", "Prep {x}", NodeType::Hexagon),
+                ("G", "In", NodeType::Parallelogram),
+                ("H", "Out", NodeType::Parallelogram),
+                ("I", "Wide", NodeType::Trapezoid),
+                ("J", "Narrow", NodeType::Trapezoid),
+                ("K", "Flag", NodeType::Asymmetric),
+                ("L", "End", NodeType::Circle),
+                ("M", "Round", NodeType::RoundEdges),
+                ("N", "Rect (draft)", NodeType::Rectangle),
+            ]
+        );
+
+        let edges = parse_edges(code).unwrap();
+        let pairs: Vec<_> = edges.iter().map(|e| (e.from.as_str(), e.to.as_str())).collect();
+        assert_eq!(pairs, vec![("A", "B"), ("B", "C"), ("E", "F"), ("G", "H"), ("I", "J"), ("K", "L"), ("M", "N")]);
+    }
+
+    #[test]
+    fn test_parse_simple_edges() {
+        let code = "A --> B\nB --> C";
+        let edges = parse_edges(code).unwrap();
+
+        assert_eq!(edges.len(), 2);
+        assert_eq!(edges[0].from, "A");
+        assert_eq!(edges[0].to, "B");
+        assert!(edges[0].label.is_none());
+    }
+
+    #[test]
+    fn test_parse_labeled_edges() {
+        let code = "C -->|Alt A| D";
+        let edges = parse_edges(code).unwrap();
+
+        assert_eq!(edges.len(), 1);
+        assert_eq!(edges[0].from, "C");
+        assert_eq!(edges[0].to, "D");
+        assert_eq!(edges[0].label, Some("Alt A".to_string()));
+    }
+
+    #[test]
+    fn test_parse_special_edge_labels() {
+        let code = r#"A -->|"yes | no"| B
+B -->|say #quot;go#quot;| C
+C -->|日本 → ünïcödé| D
+D[Pre-check] -->|ok (done)| E"#;
+        let edges = parse_edges(code).unwrap();
+
+        let labels: Vec<&str> = edges.iter().map(|e| e.label.as_deref().unwrap()).collect();
+        assert_eq!(labels, vec!["yes | no", r#"say "go""#, "日本 → ünïcödé", "ok (done)"]);
+        assert_eq!(edges[3].from, "D", "dashes in the source label are not the arrow");
+        assert_eq!(parse_nodes(code).unwrap().len(), 1, "edge label text is not a node");
+        assert!(parse_edges("N0[-->0]").unwrap().is_empty(), "arrows inside a node label are not edges");
+    }
+
+    #[test]
+    fn test_unescape_label() {
+        assert_eq!(unescape_label("#quot;a#quot; #124; #35;quot; #9829;"), "\"a\" | #quot; \u{2665}");
+        assert_eq!(unescape_label("#unknown; #12"), "#unknown; #12");
+    }
+
+    #[test]
+    fn test_parse_condition() {
+        let cond = parse_condition("cond: score >= 85").unwrap();
+        assert_eq!(cond.variable, "score");
+        assert_eq!(cond.operator, ConditionOperator::Ge);
+        assert_eq!(cond.value, "85");
+        assert_eq!(cond.expression, "score >= 85");
+
+        let cond = parse_condition("cond: ${env} == 'prod'").unwrap();
+        assert_eq!(cond.variable, "env");
+        assert_eq!(cond.operator, ConditionOperator::Eq);
+        assert_eq!(cond.value, "prod");
+
+        assert_eq!(parse_condition("cond:x<3").unwrap().operator, ConditionOperator::Lt);
+        assert!(parse_condition("Success").is_none());
+        assert!(parse_condition("cond: just words").is_none());
+    }
+
+    #[test]
+    fn test_parse_conditional_edge() {
+        let edges = parse_edges("C -->|cond: risk > 3| D[Escalate]").unwrap();
+
+        assert_eq!(edges[0].label, Some("cond: risk > 3".to_string()));
+        let cond = edges[0].condition.as_ref().unwrap();
+        assert_eq!(cond.variable, "risk");
+        assert_eq!(cond.operator, ConditionOperator::Gt);
+        assert_eq!(cond.value, "3");
+    }
+
+    #[test]
+    fn test_parse_click_actions() {
+        let code = r###"click A "#intent-1" "Jump to Intent""###;
+        let refs = parse_click_actions(code).unwrap();
+
+        assert_eq!(refs.len(), 1);
+        assert_eq!(refs[0].node_id, "A");
+        assert_eq!(refs[0].section_id, "intent-1");
+        assert_eq!(refs[0].click_action, "#intent-1");
+        assert_eq!(refs[0].tooltip, Some("Jump to Intent".to_string()));
+    }
+
+    #[test]
+    fn test_parse_direction() {
+        assert_eq!(parse_direction("flowchart TD\n  A --> B"), FlowDirection::TopDown);
+        assert_eq!(parse_direction("```mermaid\ngraph LR\n  A --> B\n```"), FlowDirection::LeftRight);
+        assert_eq!(parse_direction("flowchart BT"), FlowDirection::BottomUp);
+        assert_eq!(parse_direction("flowchart RL"), FlowDirection::RightLeft);
+        assert_eq!(parse_direction("A --> B"), FlowDirection::TopDown);
+    }
+
+    #[test]
+    fn test_parse_full_mermaid() {
+        let code = r#"
+```mermaid
+flowchart TD
+  A[Intent] --> B[Evaluation]
+  B --> C[Process]
+  C -->|Alt A| D[Alternative A]
+```
+        "#;
+
+        let graph = parse_mermaid(code).unwrap();
+
+        assert_eq!(graph.nodes.len(), 4);
+        assert_eq!(graph.edges.len(), 3);
+    }
+
+    #[test]
+    fn test_parse_subgraphs() {
+        let code = r##"flowchart LR
+  A[Plan] --> B[Build]
+  subgraph phase1 [Phase 1]
+    direction TB
+    B --> C[Test]
+    subgraph release["Release #quot;v1#quot;"]
+      D[Ship]
+    end
+    C --> D
+    style A fill:#f9f
+  end
+  subgraph Wrap up
+    E[Retro]
+  end
+  click phase1 "#process-1"
+"##;
+        let mut flow = FlowGraph {
+            id: "flow-1".to_string(),
+            version: "1.0".to_string(),
+            title: None,
+            mermaid_code: code.to_string(),
+            parsed_graph: GraphStructure { nodes: vec![], edges: vec![], subgraphs: vec![] },
+            node_refs: vec![],
+            layout: None,
+            node_metadata: Default::default(),
+        };
+        enrich_flow_graph(&mut flow).unwrap();
+
+        let graph = &flow.parsed_graph;
+        assert_eq!(graph.nodes.len(), 5, "subgraph titles are not nodes");
+        let groups: Vec<_> = graph
+            .subgraphs
+            .iter()
+            .map(|s| (s.id.as_str(), s.title.as_str(), s.node_ids.join(","), s.parent_id.as_deref()))
+            .collect();
+        assert_eq!(
+            groups,
+            vec![
+                ("phase1", "Phase 1", "B,C".to_string(), None),
+                ("release", r#"Release "v1""#, "D".to_string(), Some("phase1")),
+                ("Wrap up", "Wrap up", "E".to_string(), None),
+            ]
+        );
+        assert_eq!(graph.subgraphs[0].ref_section_id.as_deref(), Some("process-1"));
+        assert_eq!(graph.subgraphs[1].ref_section_id, None);
+    }
+
+    #[test]
+    fn test_enrich_merges_node_metadata() {
+        let mut flow = FlowGraph {
+            id: "flow-1".to_string(),
+            version: "1.0".to_string(),
+            title: None,
+            mermaid_code: "flowchart TD\n  A[Intent] --> B[Review]".to_string(),
+            parsed_graph: GraphStructure { nodes: vec![], edges: vec![], subgraphs: vec![] },
+            node_refs: vec![],
+            layout: None,
+            node_metadata: [(
+                "B".to_string(),
+                NodeMetadata { owner: Some("sam".to_string()), status: Some("todo".to_string()), estimated_effort: Some(2.5) },
+            )]
+            .into(),
+        };
+
+        enrich_flow_graph(&mut flow).unwrap();
+
+        assert!(flow.parsed_graph.nodes[0].metadata.is_none());
+        let metadata = flow.parsed_graph.nodes[1].metadata.as_ref().unwrap();
+        assert_eq!(metadata.owner.as_deref(), Some("sam"));
+        assert_eq!(metadata.estimated_effort, Some(2.5));
+    }
+}
@@ -0,0 +1,9 @@
+pub mod xml_parser;
+pub mod mermaid_parser;
+pub mod input_normalizer;
+pub mod salvage_parser;
+
+pub use xml_parser::*;
+pub use mermaid_parser::*;
+pub use input_normalizer::*;
+pub use salvage_parser::*;
@@ -0,0 +1,194 @@
+use std::collections::HashSet;
+use std::sync::LazyLock;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use crate::models::*;
+use crate::processors::section_import::unique_section_id;
+
+/// Title given to a recovered document whose `<title>` is unreadable
+pub const RECOVERED_TITLE: &str = "Recovered document";
+
+/// Type given to recovered sections whose `type` attribute is missing or invalid
+const FALLBACK_SECTION_TYPE: &str = "process";
+
+static SECTION_OPEN: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"<section\b([^>]*)>").unwrap());
+static ATTRIBUTE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r#"([\w:-]+)\s*=\s*(?:"([^"]*)"|'([^']*)')"#).unwrap());
+static TITLE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"(?s)<title>(.*?)</title>").unwrap());
+static VARIABLE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r#"(?s)<var\s+name\s*=\s*"([^"]+)"[^>]*>(.*?)</var>"#).unwrap());
+static CONTENT_OPEN: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"<content\b[^>]*>").unwrap());
+static DIAGRAM_OPEN: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"<diagram\b[^>]*>").unwrap());
+
+/// Whatever could be rescued from a file that does not parse
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SalvagedDocument {
+    pub document: ContextDocument,
+    /// Set when the document came from the tolerant scan rather than the parser
+    pub recovered: bool,
+    /// Why the regular parser rejected the file
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub parse_error: Option<String>,
+    /// What was guessed or dropped while recovering, one line each
+    pub notes: Vec<String>,
+}
+
+/// Recover sections, variables, the title and the flow diagram from damaged XML
+///
+/// Scans for `<section>` start tags and takes the raw text of the
+/// `<content>` that follows each one, up to `</content>`, the end of the
+/// CDATA block or the next section, whichever comes first. Everything else
+/// gets defaults. Missing or duplicate IDs and invalid types are replaced
+/// and reported in the notes.
+pub fn salvage_document(xml: &str) -> (ContextDocument, Vec<String>) {
+    let mut notes = Vec::new();
+
+    let title = TITLE
+        .captures(xml)
+        .map(|caps| unescape(caps[1].trim()))
+        .filter(|title| !title.is_empty())
+        .unwrap_or_else(|| {
+            notes.push("No readable title; using a placeholder".to_string());
+            RECOVERED_TITLE.to_string()
+        });
+
+    let mut builder = ContextDocument::builder().title(title);
+    let mut variable_names = HashSet::new();
+    for caps in VARIABLE.captures_iter(xml) {
+        if variable_names.insert(caps[1].to_string()) {
+            builder = builder.variable(&caps[1], unescape(caps[2].trim()));
+        }
+    }
+
+    let openings: Vec<_> = SECTION_OPEN.captures_iter(xml).collect();
+    let mut taken = HashSet::new();
+    for (index, caps) in openings.iter().enumerate() {
+        let start = caps.get(0).unwrap().end();
+        let end = openings.get(index + 1).map_or(xml.len(), |next| next.get(0).unwrap().start());
+        let end = xml[start..end].find("</sections>").map_or(end, |offset| start + offset);
+        let attributes = attributes(&caps[1]);
+        let attribute = |name: &str| attributes.iter().find(|(key, _)| key == name).map(|(_, value)| value.as_str());
+
+        let base_id = attribute("id").map(str::trim).filter(|id| !id.is_empty());
+        let id = unique_section_id(base_id.unwrap_or(&format!("recovered-{}", index + 1)), &taken);
+        match base_id {
+            None => notes.push(format!("Section {} had no ID; recovered as '{}'", index + 1, id)),
+            Some(base_id) if base_id != id => notes.push(format!("Duplicate section ID '{}' recovered as '{}'", base_id, id)),
+            _ => {}
+        }
+        taken.insert(id.clone());
+
+        let section_type = match attribute("type") {
+            Some(section_type) if SECTION_TYPES.contains(&section_type) => section_type,
+            _ => {
+                notes.push(format!("Section '{}' had no valid type; recovered as '{}'", id, FALLBACK_SECTION_TYPE));
+                FALLBACK_SECTION_TYPE
+            }
+        };
+        let content = element_text(&xml[start..end], &CONTENT_OPEN, "</content>").unwrap_or_else(|| {
+            notes.push(format!("Section '{}' had no readable content", id));
+            String::new()
+        });
+        builder = builder.add_section(Section::new(id, section_type, content));
+    }
+
+    let diagram_start = xml.find("<flow").unwrap_or(xml.len());
+    if let Some(diagram) = element_text(&xml[diagram_start..], &DIAGRAM_OPEN, "</diagram>") {
+        builder = builder.flow(diagram);
+    }
+
+    // Section IDs, types and variable names were made unique and valid above
+    let document = builder.build().expect("salvaged document is valid");
+    (document, notes)
+}
+
+/// Raw text of the first element opened by `open` in `region`, tolerating a missing close tag
+fn element_text(region: &str, open: &Regex, close: &str) -> Option<String> {
+    let rest = &region[open.find(region)?.end()..];
+    let trimmed = rest.trim_start();
+    let text = if let Some(cdata) = trimmed.strip_prefix("<![CDATA[") {
+        cdata.split_once("]]>").map_or(cdata, |(text, _)| text).to_string()
+    } else {
+        let raw = rest.split_once(close).map_or(rest, |(text, _)| text);
+        // Without a close tag the text runs into the next element
+        let raw = raw.split_once("</").map_or(raw, |(text, _)| text);
+        unescape(raw)
+    };
+    Some(text.trim().to_string())
+}
+
+fn attributes(tag: &str) -> Vec<(String, String)> {
+    ATTRIBUTE
+        .captures_iter(tag)
+        .map(|caps| {
+            let value = caps.get(2).or_else(|| caps.get(3)).map_or("", |m| m.as_str());
+            (caps[1].to_string(), unescape(value))
+        })
+        .collect()
+}
+
+fn unescape(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DAMAGED: &str = r#"<?xml version="1.0"?>
+<context version="1.0">
+    <meta>
+        <title>Launch &amp; Learn</title>
+        <author>Broken
+    </meta>
+    <variables><var name="goal">Ship v1</var></variables>
+    <sections>
+        <section id="intent-1" type="intent">
+            <content><![CDATA[
+# Intent
+Goal: ${goal}
+            ]]></content>
+        </section>
+        <section id="intent-1" type="mystery">
+            <content>Plain &lt;text&gt;
+        <section type="process">
+            <content><![CDATA[Half a CDATA block
+    </sections>
+    <flow id="flow-1" version="1.0">
+        <diagram><![CDATA[flowchart TD
+  A[Intent] --> B[Process]]]></diagram>
+"#;
+
+    #[test]
+    fn test_salvage_damaged_document() {
+        let (doc, notes) = salvage_document(DAMAGED);
+
+        assert_eq!(doc.meta.title, "Launch & Learn");
+        assert_eq!(doc.variables[0].value, "Ship v1");
+
+        let sections: Vec<_> = doc.sections.iter().map(|s| (s.id.as_str(), s.section_type.as_str())).collect();
+        assert_eq!(sections, vec![("intent-1", "intent"), ("intent-1-2", "process"), ("recovered-3", "process")]);
+        assert_eq!(doc.sections[0].content, "# Intent\nGoal: ${goal}");
+        assert_eq!(doc.sections[1].content, "Plain <text>");
+        assert_eq!(doc.sections[2].content, "Half a CDATA block");
+
+        assert!(doc.flow_graph.unwrap().mermaid_code.contains("A[Intent] --> B[Process]"));
+        assert_eq!(notes.len(), 3);
+        assert!(notes[0].contains("Duplicate section ID 'intent-1'"));
+    }
+
+    #[test]
+    fn test_salvage_garbage() {
+        let (doc, notes) = salvage_document("not xml at all");
+
+        assert_eq!(doc.meta.title, RECOVERED_TITLE);
+        assert!(doc.sections.is_empty());
+        assert!(doc.flow_graph.is_none());
+        assert_eq!(notes.len(), 1);
+    }
+}
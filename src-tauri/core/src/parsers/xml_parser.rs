@@ -0,0 +1,1003 @@
+use quick_xml::events::attributes::Attribute;
+use quick_xml::events::Event;
+use quick_xml::Reader;
+use crate::error::{ContextError, Result};
+use crate::models::*;
+use std::collections::BTreeMap;
+
+pub fn parse_xml(xml_content: &str) -> Result<ContextDocument> {
+    let mut reader = Reader::from_str(xml_content);
+    reader.config_mut().trim_text(true);
+
+    let mut meta: Option<MetaData> = None;
+    let mut variables: Vec<Variable> = Vec::new();
+    let mut sections: Vec<Section> = Vec::new();
+    let mut references: Vec<Reference> = Vec::new();
+    let mut flow_graph: Option<FlowGraph> = None;
+
+    let mut buf = Vec::new();
+
+    loop {
+        match reader.read_event_into(&mut buf) {
+            Ok(Event::Start(e)) => {
+                match e.name().as_ref() {
+                    b"meta" => {
+                        meta = Some(parse_meta(&mut reader)?);
+                    }
+                    b"variables" => {
+                        variables = parse_variables(&mut reader)?;
+                    }
+                    b"sections" => {
+                        sections = parse_sections(&mut reader)?;
+                    }
+                    b"references" => {
+                        references = parse_references(&mut reader)?;
+                    }
+                    b"flow" => {
+                        flow_graph = Some(parse_flow(&mut reader, &e)?);
+                    }
+                    _ => {}
+                }
+            }
+            Ok(Event::Eof) => break,
+            Err(e) => return Err(ContextError::InvalidXml(e.to_string())),
+            _ => {}
+        }
+        buf.clear();
+    }
+
+    let meta = meta.ok_or_else(|| ContextError::MissingRequiredField("meta".to_string()))?;
+
+    Ok(ContextDocument {
+        meta,
+        variables,
+        sections,
+        references,
+        flow_graph,
+    })
+}
+
+fn parse_meta(reader: &mut Reader<&[u8]>) -> Result<MetaData> {
+    let mut title = String::new();
+    let mut author = String::new();
+    let mut created = String::new();
+    let mut app_info: Option<AppInfo> = None;
+    let mut tags = Vec::new();
+    let mut description = String::new();
+    let mut extra = BTreeMap::new();
+
+    let mut buf = Vec::new();
+
+    loop {
+        match reader.read_event_into(&mut buf) {
+            Ok(Event::Empty(e)) if !KNOWN_META_ELEMENTS.contains(&e.name().as_ref()) => {
+                extra.insert(String::from_utf8_lossy(e.name().as_ref()).to_string(), String::new());
+            }
+            Ok(Event::Start(e)) | Ok(Event::Empty(e)) => {
+                let tag_name = e.name();
+                match tag_name.as_ref() {
+                    b"title" => title = read_text(reader, "title")?,
+                    b"author" => author = read_text(reader, "author")?,
+                    b"created" => created = read_text(reader, "created")?,
+                    b"app" => {
+                        let mut name = String::new();
+                        let mut version = String::new();
+                        for attr in e.attributes() {
+                            let attr = attr.map_err(|e| ContextError::InvalidXml(e.to_string()))?;
+                            match attr.key.as_ref() {
+                                b"name" => name = attribute_value(&attr)?,
+                                b"version" => version = attribute_value(&attr)?,
+                                _ => {}
+                            }
+                        }
+                        app_info = Some(AppInfo { name, version });
+                    }
+                    b"tags" => {
+                        tags = split_tags(&read_text(reader, "tags")?);
+                    }
+                    b"description" => description = read_text(reader, "description")?,
+                    b"custom" => extra.extend(parse_custom_fields(reader)?),
+                    other => {
+                        // Unknown meta children become custom fields
+                        let name = String::from_utf8_lossy(other).to_string();
+                        let value = read_text(reader, &name)?;
+                        extra.insert(name, value);
+                    }
+                }
+            }
+            Ok(Event::End(e)) if e.name().as_ref() == b"meta" => break,
+            Ok(Event::Eof) => break,
+            Err(e) => return Err(ContextError::InvalidXml(e.to_string())),
+            _ => {}
+        }
+        buf.clear();
+    }
+
+    let app_info = app_info.ok_or_else(|| ContextError::MissingRequiredField("app".to_string()))?;
+
+    Ok(MetaData {
+        title,
+        author,
+        created,
+        app_info,
+        tags,
+        description,
+        extra,
+    })
+}
+
+/// Standard meta children; anything else is collected into `MetaData::extra`
+const KNOWN_META_ELEMENTS: &[&[u8]] = &[b"title", b"author", b"created", b"app", b"tags", b"description", b"custom"];
+
+/// Parse `<custom><field name="...">value</field></custom>`
+fn parse_custom_fields(reader: &mut Reader<&[u8]>) -> Result<BTreeMap<String, String>> {
+    let mut fields = BTreeMap::new();
+    let mut buf = Vec::new();
+
+    loop {
+        match reader.read_event_into(&mut buf) {
+            Ok(Event::Start(e)) if e.name().as_ref() == b"field" => {
+                let name = read_name_attribute(&e)?;
+                let value = read_text(reader, "field")?;
+                fields.insert(name, value);
+            }
+            Ok(Event::Empty(e)) if e.name().as_ref() == b"field" => {
+                fields.insert(read_name_attribute(&e)?, String::new());
+            }
+            Ok(Event::End(e)) if e.name().as_ref() == b"custom" => break,
+            Ok(Event::Eof) => break,
+            Err(e) => return Err(ContextError::InvalidXml(e.to_string())),
+            _ => {}
+        }
+        buf.clear();
+    }
+
+    Ok(fields)
+}
+
+fn read_name_attribute(start_event: &quick_xml::events::BytesStart) -> Result<String> {
+    let mut name = String::new();
+    for attr in start_event.attributes() {
+        let attr = attr.map_err(|e| ContextError::InvalidXml(e.to_string()))?;
+        if attr.key.as_ref() == b"name" {
+            name = attribute_value(&attr)?;
+        }
+    }
+    Ok(name)
+}
+
+fn parse_variables(reader: &mut Reader<&[u8]>) -> Result<Vec<Variable>> {
+    let mut variables = Vec::new();
+    let mut buf = Vec::new();
+
+    loop {
+        match reader.read_event_into(&mut buf) {
+            Ok(Event::Start(e)) if e.name().as_ref() == b"var" => {
+                let mut variable = parse_var_attributes(&e)?;
+                variable.value = read_text(reader, "var")?;
+                variables.push(variable);
+            }
+            Ok(Event::Empty(e)) if e.name().as_ref() == b"var" => {
+                variables.push(parse_var_attributes(&e)?);
+            }
+            Ok(Event::End(e)) if e.name().as_ref() == b"variables" => break,
+            Ok(Event::Eof) => break,
+            Err(e) => return Err(ContextError::InvalidXml(e.to_string())),
+            _ => {}
+        }
+        buf.clear();
+    }
+
+    Ok(variables)
+}
+
+/// A `<var>` element without its value; an empty description counts as none
+fn parse_var_attributes(e: &quick_xml::events::BytesStart) -> Result<Variable> {
+    let mut variable = Variable {
+        name: String::new(),
+        value: String::new(),
+        description: None,
+        sensitive: false,
+        resolve_in: vec![],
+        due: None,
+    };
+    for attr in e.attributes() {
+        let attr = attr.map_err(|e| ContextError::InvalidXml(e.to_string()))?;
+        match attr.key.as_ref() {
+            b"name" => variable.name = attribute_value(&attr)?,
+            b"description" => variable.description = Some(attribute_value(&attr)?).filter(|d| !d.trim().is_empty()),
+            b"sensitive" => variable.sensitive = parse_flag(&attribute_value(&attr)?),
+            b"due" => variable.due = Some(attribute_value(&attr)?).filter(|d| !d.trim().is_empty()),
+            b"resolveIn" => {
+                variable.resolve_in = attribute_value(&attr)?.split(',').filter_map(ResolutionContext::parse).collect()
+            }
+            _ => {}
+        }
+    }
+    Ok(variable)
+}
+
+/// `xs:boolean` attribute value; anything but `true`/`1` is false
+fn parse_flag(value: &str) -> bool {
+    matches!(value.trim(), "true" | "1")
+}
+
+/// Split a comma-separated tag list, dropping empty entries
+fn split_tags(value: &str) -> Vec<String> {
+    value
+        .split(',')
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+        .collect()
+}
+
+fn parse_sections(reader: &mut Reader<&[u8]>) -> Result<Vec<Section>> {
+    let mut sections = Vec::new();
+    let mut buf = Vec::new();
+
+    loop {
+        match reader.read_event_into(&mut buf) {
+            Ok(Event::Start(e)) if e.name().as_ref() == b"section" => {
+                sections.push(parse_section(reader, &e)?);
+            }
+            Ok(Event::End(e)) if e.name().as_ref() == b"sections" => break,
+            Ok(Event::Eof) => break,
+            Err(e) => return Err(ContextError::InvalidXml(e.to_string())),
+            _ => {}
+        }
+        buf.clear();
+    }
+
+    Ok(sections)
+}
+
+fn parse_section(reader: &mut Reader<&[u8]>, start_event: &quick_xml::events::BytesStart) -> Result<Section> {
+    let mut id = String::new();
+    let mut section_type = String::new();
+    let mut ref_target: Option<String> = None;
+    let mut tags = Vec::new();
+    let mut budget = None;
+    let mut sensitive = false;
+    let mut encrypted = false;
+
+    for attr in start_event.attributes() {
+        let attr = attr.map_err(|e| ContextError::InvalidXml(e.to_string()))?;
+        match attr.key.as_ref() {
+            b"id" => id = attribute_value(&attr)?,
+            b"type" => section_type = attribute_value(&attr)?,
+            b"refTarget" => ref_target = Some(attribute_value(&attr)?),
+            b"tags" => tags = split_tags(&attribute_value(&attr)?),
+            b"budget" => {
+                let value = attribute_value(&attr)?;
+                budget = Some(Budget::parse(&value).ok_or_else(|| {
+                    ContextError::InvalidXml(format!("Invalid budget '{}' on section '{}'", value, id))
+                })?);
+            }
+            b"sensitive" => sensitive = parse_flag(&attribute_value(&attr)?),
+            b"encrypted" => encrypted = parse_flag(&attribute_value(&attr)?),
+            _ => {}
+        }
+    }
+
+    let mut content: Option<String> = None;
+    let mut translations = BTreeMap::new();
+    let mut children = Vec::new();
+    let mut buf = Vec::new();
+
+    loop {
+        match reader.read_event_into(&mut buf) {
+            Ok(Event::Start(e)) => {
+                match e.name().as_ref() {
+                    b"content" => {
+                        let lang = read_lang_attribute(&e)?;
+                        let text = read_cdata(reader, "content")?;
+                        match lang {
+                            Some(lang) => {
+                                translations.insert(lang, text);
+                            }
+                            None => content = Some(text),
+                        }
+                    }
+                    b"section" => {
+                        children.push(parse_section(reader, &e)?);
+                    }
+                    _ => {}
+                }
+            }
+            Ok(Event::End(e)) if e.name().as_ref() == b"section" => break,
+            Ok(Event::Eof) => break,
+            Err(e) => return Err(ContextError::InvalidXml(e.to_string())),
+            _ => {}
+        }
+        buf.clear();
+    }
+
+    // Sections with only language variants use the first one as default content
+    let content = content
+        .or_else(|| translations.values().next().cloned())
+        .unwrap_or_default();
+
+    Ok(Section {
+        id,
+        section_type,
+        content,
+        ref_target,
+        tags,
+        budget,
+        translations,
+        children,
+        transclusions: vec![],
+        sensitive,
+        encrypted,
+    })
+}
+
+fn read_lang_attribute(start_event: &quick_xml::events::BytesStart) -> Result<Option<String>> {
+    for attr in start_event.attributes() {
+        let attr = attr.map_err(|e| ContextError::InvalidXml(e.to_string()))?;
+        if attr.key.as_ref() == b"lang" {
+            let lang = attribute_value(&attr)?.trim().to_string();
+            return Ok(Some(lang).filter(|l| !l.is_empty()));
+        }
+    }
+    Ok(None)
+}
+
+fn parse_references(reader: &mut Reader<&[u8]>) -> Result<Vec<Reference>> {
+    let mut references = Vec::new();
+    let mut buf = Vec::new();
+
+    loop {
+        match reader.read_event_into(&mut buf) {
+            Ok(Event::Start(e)) if e.name().as_ref() == b"reference" => {
+                references.push(parse_reference(reader, &e)?);
+            }
+            Ok(Event::End(e)) if e.name().as_ref() == b"references" => break,
+            Ok(Event::Eof) => break,
+            Err(e) => return Err(ContextError::InvalidXml(e.to_string())),
+            _ => {}
+        }
+        buf.clear();
+    }
+
+    Ok(references)
+}
+
+fn parse_reference(reader: &mut Reader<&[u8]>, start_event: &quick_xml::events::BytesStart) -> Result<Reference> {
+    let mut id = String::new();
+    let mut url: Option<String> = None;
+
+    for attr in start_event.attributes() {
+        let attr = attr.map_err(|e| ContextError::InvalidXml(e.to_string()))?;
+        match attr.key.as_ref() {
+            b"id" => id = attribute_value(&attr)?,
+            b"url" => url = Some(attribute_value(&attr)?),
+            _ => {}
+        }
+    }
+
+    let mut title = String::new();
+    let mut citation = String::new();
+    let mut buf = Vec::new();
+
+    loop {
+        match reader.read_event_into(&mut buf) {
+            Ok(Event::Start(e)) => match e.name().as_ref() {
+                b"title" => title = read_text(reader, "title")?,
+                b"citation" => citation = read_cdata(reader, "citation")?,
+                _ => {}
+            },
+            Ok(Event::End(e)) if e.name().as_ref() == b"reference" => break,
+            Ok(Event::Eof) => break,
+            Err(e) => return Err(ContextError::InvalidXml(e.to_string())),
+            _ => {}
+        }
+        buf.clear();
+    }
+
+    Ok(Reference { id, title, url, citation })
+}
+
+fn parse_flow(reader: &mut Reader<&[u8]>, start_event: &quick_xml::events::BytesStart) -> Result<FlowGraph> {
+    let mut id = String::new();
+    let mut version = String::new();
+
+    for attr in start_event.attributes() {
+        let attr = attr.map_err(|e| ContextError::InvalidXml(e.to_string()))?;
+        match attr.key.as_ref() {
+            b"id" => id = attribute_value(&attr)?,
+            b"version" => version = attribute_value(&attr)?,
+            _ => {}
+        }
+    }
+
+    let mut title: Option<String> = None;
+    let mut mermaid_code = String::new();
+    let mut layout: Option<FlowLayout> = None;
+    let mut node_metadata = BTreeMap::new();
+    let mut buf = Vec::new();
+
+    loop {
+        match reader.read_event_into(&mut buf) {
+            Ok(Event::Start(e)) => {
+                match e.name().as_ref() {
+                    b"title" => {
+                        title = Some(read_text(reader, "title")?);
+                    }
+                    b"diagram" => {
+                        mermaid_code = read_cdata(reader, "diagram")?;
+                    }
+                    b"layout" => {
+                        let mut flow_layout = parse_layout_attributes(&e)?;
+                        flow_layout.positions = parse_layout_nodes(reader)?;
+                        layout = Some(flow_layout);
+                    }
+                    b"nodes" => {
+                        node_metadata = parse_node_metadata(reader)?;
+                    }
+                    _ => {}
+                }
+            }
+            Ok(Event::Empty(e)) if e.name().as_ref() == b"layout" => {
+                layout = Some(parse_layout_attributes(&e)?);
+            }
+            Ok(Event::End(e)) if e.name().as_ref() == b"flow" => break,
+            Ok(Event::Eof) => break,
+            Err(e) => return Err(ContextError::InvalidXml(e.to_string())),
+            _ => {}
+        }
+        buf.clear();
+    }
+
+    // For now, return empty parsed_graph and node_refs - will be populated by mermaid parser
+    Ok(FlowGraph {
+        id,
+        version,
+        title,
+        mermaid_code,
+        parsed_graph: GraphStructure {
+            nodes: vec![],
+            edges: vec![],
+            subgraphs: vec![],
+        },
+        node_refs: vec![],
+        layout,
+        node_metadata,
+    })
+}
+
+fn parse_layout_attributes(start_event: &quick_xml::events::BytesStart) -> Result<FlowLayout> {
+    let mut layout = FlowLayout {
+        zoom: 1.0,
+        pan_x: 0.0,
+        pan_y: 0.0,
+        positions: BTreeMap::new(),
+    };
+
+    for attr in start_event.attributes() {
+        let attr = attr.map_err(|e| ContextError::InvalidXml(e.to_string()))?;
+        match attr.key.as_ref() {
+            b"zoom" => layout.zoom = parse_number(&attr.value, "zoom")?,
+            b"panX" => layout.pan_x = parse_number(&attr.value, "panX")?,
+            b"panY" => layout.pan_y = parse_number(&attr.value, "panY")?,
+            _ => {}
+        }
+    }
+
+    Ok(layout)
+}
+
+fn parse_layout_nodes(reader: &mut Reader<&[u8]>) -> Result<BTreeMap<String, NodePosition>> {
+    let mut positions = BTreeMap::new();
+    let mut buf = Vec::new();
+
+    loop {
+        match reader.read_event_into(&mut buf) {
+            Ok(Event::Start(e)) | Ok(Event::Empty(e)) if e.name().as_ref() == b"node" => {
+                let mut id = String::new();
+                let mut position = NodePosition { x: 0.0, y: 0.0 };
+                for attr in e.attributes() {
+                    let attr = attr.map_err(|e| ContextError::InvalidXml(e.to_string()))?;
+                    match attr.key.as_ref() {
+                        b"id" => id = attribute_value(&attr)?,
+                        b"x" => position.x = parse_number(&attr.value, "x")?,
+                        b"y" => position.y = parse_number(&attr.value, "y")?,
+                        _ => {}
+                    }
+                }
+                positions.insert(id, position);
+            }
+            Ok(Event::End(e)) if e.name().as_ref() == b"layout" => break,
+            Ok(Event::Eof) => break,
+            Err(e) => return Err(ContextError::InvalidXml(e.to_string())),
+            _ => {}
+        }
+        buf.clear();
+    }
+
+    Ok(positions)
+}
+
+fn parse_node_metadata(reader: &mut Reader<&[u8]>) -> Result<BTreeMap<String, NodeMetadata>> {
+    let mut node_metadata = BTreeMap::new();
+    let mut buf = Vec::new();
+
+    loop {
+        match reader.read_event_into(&mut buf) {
+            Ok(Event::Start(e)) | Ok(Event::Empty(e)) if e.name().as_ref() == b"node" => {
+                let mut id = String::new();
+                let mut metadata = NodeMetadata::default();
+                for attr in e.attributes() {
+                    let attr = attr.map_err(|e| ContextError::InvalidXml(e.to_string()))?;
+                    match attr.key.as_ref() {
+                        b"id" => id = attribute_value(&attr)?,
+                        b"owner" => metadata.owner = Some(attribute_value(&attr)?).filter(|v| !v.is_empty()),
+                        b"status" => metadata.status = Some(attribute_value(&attr)?).filter(|v| !v.is_empty()),
+                        b"effort" => metadata.estimated_effort = Some(parse_number(&attr.value, "effort")?),
+                        _ => {}
+                    }
+                }
+                node_metadata.insert(id, metadata);
+            }
+            Ok(Event::End(e)) if e.name().as_ref() == b"nodes" => break,
+            Ok(Event::Eof) => break,
+            Err(e) => return Err(ContextError::InvalidXml(e.to_string())),
+            _ => {}
+        }
+        buf.clear();
+    }
+
+    Ok(node_metadata)
+}
+
+/// Attribute value with XML entities (`&amp;`, `&quot;`, ...) decoded
+fn attribute_value(attr: &Attribute) -> Result<String> {
+    attr.unescape_value()
+        .map(|value| value.into_owned())
+        .map_err(|e| ContextError::InvalidXml(e.to_string()))
+}
+
+fn parse_number(value: &[u8], attr_name: &str) -> Result<f64> {
+    String::from_utf8_lossy(value)
+        .trim()
+        .parse()
+        .map_err(|_| ContextError::InvalidXml(format!("Attribute '{}' must be a number", attr_name)))
+}
+
+/// Trim in place, avoiding a copy when there is nothing to trim
+fn trim_owned(mut text: String) -> String {
+    let end = text.trim_end().len();
+    text.truncate(end);
+    let start = text.len() - text.trim_start().len();
+    if start > 0 {
+        text.drain(..start);
+    }
+    text
+}
+
+fn read_text(reader: &mut Reader<&[u8]>, _tag_name: &str) -> Result<String> {
+    let mut buf = Vec::new();
+    let mut text = String::new();
+
+    loop {
+        match reader.read_event_into(&mut buf) {
+            Ok(Event::Text(e)) => {
+                text.push_str(&e.unescape().map_err(|e| ContextError::InvalidXml(e.to_string()))?);
+            }
+            Ok(Event::End(_)) => break,
+            Ok(Event::Eof) => break,
+            Err(e) => return Err(ContextError::InvalidXml(e.to_string())),
+            _ => {}
+        }
+        buf.clear();
+    }
+
+    Ok(trim_owned(text))
+}
+
+fn read_cdata(reader: &mut Reader<&[u8]>, _tag_name: &str) -> Result<String> {
+    let mut buf = Vec::new();
+    let mut text = String::new();
+
+    loop {
+        match reader.read_event_into(&mut buf) {
+            Ok(Event::CData(e)) => {
+                text.push_str(&String::from_utf8_lossy(&e));
+            }
+            Ok(Event::Text(e)) => {
+                text.push_str(&e.unescape().map_err(|e| ContextError::InvalidXml(e.to_string()))?);
+            }
+            Ok(Event::End(_)) => break,
+            Ok(Event::Eof) => break,
+            Err(e) => return Err(ContextError::InvalidXml(e.to_string())),
+            _ => {}
+        }
+        buf.clear();
+    }
+
+    Ok(trim_owned(text))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_simple_meta() {
+        let xml = r#"
+        <context version="1.0">
+            <meta>
+                <title>Test Doc</title>
+                <author>Test Author</author>
+                <created>2025-10-09</created>
+                <app name="CEC" version="0.1.0"/>
+                <tags>test, doc</tags>
+                <description>A test</description>
+            </meta>
+            <variables></variables>
+            <sections></sections>
+        </context>
+        "#;
+
+        let doc = parse_xml(xml).unwrap();
+        assert_eq!(doc.meta.title, "Test Doc");
+        assert_eq!(doc.meta.author, "Test Author");
+        assert_eq!(doc.meta.app_info.name, "CEC");
+        assert_eq!(doc.meta.tags.len(), 2);
+    }
+
+    #[test]
+    fn test_parse_variables() {
+        let xml = r#"
+        <context version="1.0">
+            <meta>
+                <title>Test</title>
+                <author>Author</author>
+                <created>2025-10-09</created>
+                <app name="CEC" version="0.1.0"/>
+                <tags>test</tags>
+                <description>Test</description>
+            </meta>
+            <variables>
+                <var name="userName">Jeremy</var>
+                <var name="goal" description="What to ship &amp; when">Ship v1</var>
+                <var name="owner" description=""/>
+            </variables>
+            <sections></sections>
+        </context>
+        "#;
+
+        let doc = parse_xml(xml).unwrap();
+        assert_eq!(doc.variables.len(), 3);
+        assert_eq!(doc.variables[0].name, "userName");
+        assert_eq!(doc.variables[0].value, "Jeremy");
+        assert_eq!(doc.variables[0].description, None);
+        assert_eq!(doc.variables[1].description.as_deref(), Some("What to ship & when"));
+        assert_eq!(doc.variables[2].description, None);
+    }
+
+    #[test]
+    fn test_parse_section_with_cdata() {
+        let xml = r#"
+        <context version="1.0">
+            <meta>
+                <title>Test</title>
+                <author>Author</author>
+                <created>2025-10-09</created>
+                <app name="CEC" version="0.1.0"/>
+                <tags>test</tags>
+                <description>Test</description>
+            </meta>
+            <variables></variables>
+            <sections>
+                <section id="test-1" type="intent">
+                    <content><![CDATA[
+# Intent
+This is test content
+                    ]]></content>
+                </section>
+            </sections>
+        </context>
+        "#;
+
+        let doc = parse_xml(xml).unwrap();
+        assert_eq!(doc.sections.len(), 1);
+        assert_eq!(doc.sections[0].id, "test-1");
+        assert_eq!(doc.sections[0].section_type, "intent");
+        assert!(doc.sections[0].content.contains("Intent"));
+    }
+
+    #[test]
+    fn test_parse_nested_sections() {
+        let xml = r#"
+        <context version="1.0">
+            <meta>
+                <title>Test</title>
+                <author>Author</author>
+                <created>2025-10-09</created>
+                <app name="CEC" version="0.1.0"/>
+                <tags>test</tags>
+                <description>Test</description>
+            </meta>
+            <variables></variables>
+            <sections>
+                <section id="parent-1" type="process">
+                    <content><![CDATA[Parent content]]></content>
+                    <section id="child-1" type="alternatives">
+                        <content><![CDATA[Child content]]></content>
+                    </section>
+                </section>
+            </sections>
+        </context>
+        "#;
+
+        let doc = parse_xml(xml).unwrap();
+        assert_eq!(doc.sections.len(), 1);
+        assert_eq!(doc.sections[0].children.len(), 1);
+        assert_eq!(doc.sections[0].children[0].id, "child-1");
+    }
+
+    #[test]
+    fn test_parse_flow() {
+        let xml = r#"
+        <context version="1.0">
+            <meta>
+                <title>Test</title>
+                <author>Author</author>
+                <created>2025-10-09</created>
+                <app name="CEC" version="0.1.0"/>
+                <tags>test</tags>
+                <description>Test</description>
+            </meta>
+            <variables></variables>
+            <sections></sections>
+            <flow id="flow-1" version="1.0">
+                <title>Document Flow</title>
+                <diagram><![CDATA[
+```mermaid
+flowchart TD
+  A[Intent] --> B[Evaluation]
+```
+                ]]></diagram>
+            </flow>
+        </context>
+        "#;
+
+        let doc = parse_xml(xml).unwrap();
+        assert!(doc.flow_graph.is_some());
+        let flow = doc.flow_graph.unwrap();
+        assert_eq!(flow.id, "flow-1");
+        assert_eq!(flow.version, "1.0");
+        assert_eq!(flow.title, Some("Document Flow".to_string()));
+        assert!(flow.mermaid_code.contains("mermaid"));
+    }
+
+    #[test]
+    fn test_parse_flow_layout() {
+        let xml = r#"
+        <context version="1.0">
+            <meta>
+                <title>Test</title>
+                <author>Author</author>
+                <created>2025-10-09</created>
+                <app name="CEC" version="0.1.0"/>
+                <tags>test</tags>
+                <description>Test</description>
+            </meta>
+            <variables></variables>
+            <sections></sections>
+            <flow id="flow-1" version="1.0">
+                <diagram><![CDATA[flowchart TD
+  A --> B]]></diagram>
+                <layout zoom="1.25" panX="-10" panY="30.5">
+                    <node id="A" x="0" y="0"/>
+                    <node id="B" x="120" y="80"/>
+                </layout>
+            </flow>
+        </context>
+        "#;
+
+        let doc = parse_xml(xml).unwrap();
+        let layout = doc.flow_graph.unwrap().layout.unwrap();
+        assert_eq!(layout.zoom, 1.25);
+        assert_eq!(layout.pan_x, -10.0);
+        assert_eq!(layout.pan_y, 30.5);
+        assert_eq!(layout.positions.len(), 2);
+        assert_eq!(layout.positions["B"], NodePosition { x: 120.0, y: 80.0 });
+    }
+
+    #[test]
+    fn test_parse_node_metadata() {
+        let xml = r#"
+        <context version="1.0">
+            <meta>
+                <title>Test</title>
+                <author>Author</author>
+                <created>2025-10-09</created>
+                <app name="CEC" version="0.1.0"/>
+                <tags>test</tags>
+                <description>Test</description>
+            </meta>
+            <variables></variables>
+            <sections></sections>
+            <flow id="flow-1" version="1.0">
+                <diagram><![CDATA[flowchart TD
+  A --> B]]></diagram>
+                <nodes>
+                    <node id="A" owner="sam" status="in-progress" effort="3"/>
+                    <node id="B" status=""/>
+                </nodes>
+            </flow>
+        </context>
+        "#;
+
+        let node_metadata = parse_xml(xml).unwrap().flow_graph.unwrap().node_metadata;
+        assert_eq!(node_metadata.len(), 2);
+        assert_eq!(node_metadata["A"].owner.as_deref(), Some("sam"));
+        assert_eq!(node_metadata["A"].status.as_deref(), Some("in-progress"));
+        assert_eq!(node_metadata["A"].estimated_effort, Some(3.0));
+        assert!(node_metadata["B"].is_empty());
+    }
+
+    #[test]
+    fn test_parse_flow_layout_invalid_number() {
+        let xml = r#"
+        <context version="1.0">
+            <meta>
+                <title>Test</title>
+                <author>Author</author>
+                <created>2025-10-09</created>
+                <app name="CEC" version="0.1.0"/>
+                <tags>test</tags>
+                <description>Test</description>
+            </meta>
+            <variables></variables>
+            <sections></sections>
+            <flow id="flow-1" version="1.0">
+                <diagram>flowchart TD</diagram>
+                <layout zoom="big"/>
+            </flow>
+        </context>
+        "#;
+
+        let result = parse_xml(xml);
+        assert!(result.unwrap_err().to_string().contains("'zoom' must be a number"));
+    }
+
+    #[test]
+    fn test_parse_meta_custom_fields() {
+        let xml = r#"
+        <context version="1.0">
+            <meta>
+                <title>Test</title>
+                <author>Author</author>
+                <created>2025-10-09</created>
+                <app name="CEC" version="0.1.0"/>
+                <tags>test</tags>
+                <description>Test</description>
+                <ownerTeam>Platform</ownerTeam>
+                <archived/>
+                <custom>
+                    <field name="projectCode">PX-42</field>
+                    <field name="reviewDate">2025-12-01</field>
+                    <field name="empty"/>
+                </custom>
+            </meta>
+            <variables></variables>
+            <sections></sections>
+        </context>
+        "#;
+
+        let doc = parse_xml(xml).unwrap();
+        let extra = &doc.meta.extra;
+        assert_eq!(extra.len(), 5);
+        assert_eq!(extra["projectCode"], "PX-42");
+        assert_eq!(extra["reviewDate"], "2025-12-01");
+        assert_eq!(extra["ownerTeam"], "Platform");
+        assert_eq!(extra["archived"], "");
+        assert_eq!(extra["empty"], "");
+        assert_eq!(doc.meta.description, "Test");
+    }
+
+    #[test]
+    fn test_parse_section_tags() {
+        let xml = r#"
+        <context version="1.0">
+            <meta>
+                <title>Test</title>
+                <author>Author</author>
+                <created>2025-10-09</created>
+                <app name="CEC" version="0.1.0"/>
+                <tags>test</tags>
+                <description>Test</description>
+            </meta>
+            <variables></variables>
+            <sections>
+                <section id="intent-1" type="intent" tags="backend, draft,">
+                    <content><![CDATA[Intent]]></content>
+                </section>
+                <section id="eval-1" type="evaluation">
+                    <content><![CDATA[Eval]]></content>
+                </section>
+            </sections>
+        </context>
+        "#;
+
+        let doc = parse_xml(xml).unwrap();
+        assert_eq!(doc.sections[0].tags, vec!["backend", "draft"]);
+        assert!(doc.sections[1].tags.is_empty());
+    }
+
+    #[test]
+    fn test_parse_localized_content() {
+        let xml = r#"
+        <context version="1.0">
+            <meta>
+                <title>Test</title>
+                <author>Author</author>
+                <created>2025-10-09</created>
+                <app name="CEC" version="0.1.0"/>
+                <tags>test</tags>
+                <description>Test</description>
+            </meta>
+            <variables></variables>
+            <sections>
+                <section id="intent-1" type="intent">
+                    <content><![CDATA[Intent]]></content>
+                    <content lang="de"><![CDATA[Absicht]]></content>
+                </section>
+                <section id="eval-1" type="evaluation">
+                    <content lang="en"><![CDATA[Evaluation]]></content>
+                    <content lang="fr"><![CDATA[Évaluation]]></content>
+                </section>
+            </sections>
+        </context>
+        "#;
+
+        let doc = parse_xml(xml).unwrap();
+        assert_eq!(doc.sections[0].content, "Intent");
+        assert_eq!(doc.sections[0].translations["de"], "Absicht");
+        assert_eq!(doc.sections[1].content, "Evaluation");
+        assert_eq!(doc.sections[1].translations.len(), 2);
+    }
+
+    #[test]
+    fn test_parse_references() {
+        let xml = r#"
+        <context version="1.0">
+            <meta>
+                <title>Test</title>
+                <author>Author</author>
+                <created>2025-10-09</created>
+                <app name="CEC" version="0.1.0"/>
+                <tags>test</tags>
+                <description>Test</description>
+            </meta>
+            <variables></variables>
+            <sections>
+                <section id="intent-1" type="intent">
+                    <content><![CDATA[As shown in [@smith-2024].]]></content>
+                </section>
+            </sections>
+            <references>
+                <reference id="smith-2024" url="https://example.com/paper">
+                    <title>Context Engineering</title>
+                    <citation><![CDATA[Smith, J. (2024). Context Engineering.]]></citation>
+                </reference>
+                <reference id="doe-2023">
+                    <title>Flows &amp; Graphs</title>
+                </reference>
+            </references>
+        </context>
+        "#;
+
+        let doc = parse_xml(xml).unwrap();
+        assert_eq!(doc.references.len(), 2);
+        assert_eq!(doc.references[0].id, "smith-2024");
+        assert_eq!(doc.references[0].url.as_deref(), Some("https://example.com/paper"));
+        assert_eq!(doc.references[0].citation, "Smith, J. (2024). Context Engineering.");
+        assert_eq!(doc.references[1].title, "Flows & Graphs");
+        assert!(doc.references[1].url.is_none());
+        assert!(doc.references[1].citation.is_empty());
+    }
+}
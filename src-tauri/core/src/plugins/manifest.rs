@@ -0,0 +1,114 @@
+use crate::error::{ContextError, Result};
+use serde::{Deserialize, Serialize};
+use std::path::{Component, Path};
+
+/// Manifest file expected in every plugin directory
+pub const MANIFEST_FILE: &str = "plugin.json";
+
+/// When a plugin's transform runs
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "lowercase")]
+pub enum PluginStage {
+    /// On the raw section content, before variables are resolved
+    Load,
+    /// On the resolved sections, right before they are joined into the context
+    Assembly,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct PluginManifest {
+    pub id: String,
+    pub name: String,
+    pub version: String,
+    #[serde(default)]
+    pub description: String,
+    /// WASM module, relative to the plugin directory
+    pub entry: String,
+    pub stages: Vec<PluginStage>,
+    /// Section types the transform applies to; empty for all
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub section_types: Vec<String>,
+}
+
+impl PluginManifest {
+    /// Read and validate `plugin.json` in `dir`
+    pub fn read(dir: &Path) -> Result<PluginManifest> {
+        let path = dir.join(MANIFEST_FILE);
+        let json = std::fs::read_to_string(&path)?;
+        let manifest: PluginManifest = serde_json::from_str(&json)
+            .map_err(|e| ContextError::PluginError(format!("{}: {}", path.display(), e)))?;
+        manifest.validate()?;
+        Ok(manifest)
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        let valid_id = !self.id.is_empty()
+            && self.id.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'));
+        if !valid_id {
+            return Err(ContextError::PluginError(format!("Invalid plugin ID '{}'", self.id)));
+        }
+        if self.stages.is_empty() {
+            return Err(ContextError::PluginError(format!("Plugin '{}' declares no stages", self.id)));
+        }
+
+        // The entry must stay inside the plugin directory
+        let entry = Path::new(&self.entry);
+        let inside = entry.components().all(|c| matches!(c, Component::Normal(_)));
+        if !inside || entry.extension().and_then(|ext| ext.to_str()) != Some("wasm") {
+            return Err(ContextError::PluginError(format!(
+                "Plugin '{}' entry must be a .wasm file inside the plugin directory",
+                self.id
+            )));
+        }
+        Ok(())
+    }
+
+    /// Whether the transform runs for sections of this type at this stage
+    pub fn applies_to(&self, stage: PluginStage, section_type: &str) -> bool {
+        self.stages.contains(&stage)
+            && (self.section_types.is_empty() || self.section_types.iter().any(|t| t == section_type))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn manifest(entry: &str) -> PluginManifest {
+        PluginManifest {
+            id: "macros".to_string(),
+            name: "Macros".to_string(),
+            version: "0.1.0".to_string(),
+            description: String::new(),
+            entry: entry.to_string(),
+            stages: vec![PluginStage::Load],
+            section_types: vec!["process".to_string()],
+        }
+    }
+
+    #[test]
+    fn test_validate_entry() {
+        assert!(manifest("plugin.wasm").validate().is_ok());
+        assert!(manifest("lib/plugin.wasm").validate().is_ok());
+        assert!(manifest("../plugin.wasm").validate().is_err());
+        assert!(manifest("/abs/plugin.wasm").validate().is_err());
+        assert!(manifest("plugin.so").validate().is_err());
+    }
+
+    #[test]
+    fn test_applies_to() {
+        let manifest = manifest("plugin.wasm");
+        assert!(manifest.applies_to(PluginStage::Load, "process"));
+        assert!(!manifest.applies_to(PluginStage::Load, "intent"));
+        assert!(!manifest.applies_to(PluginStage::Assembly, "process"));
+    }
+
+    #[test]
+    fn test_parse_manifest_json() {
+        let json = r#"{"id":"upper","name":"Upper","version":"1.0.0","entry":"upper.wasm","stages":["assembly"]}"#;
+        let manifest: PluginManifest = serde_json::from_str(json).unwrap();
+        assert_eq!(manifest.stages, vec![PluginStage::Assembly]);
+        assert!(manifest.section_types.is_empty());
+    }
+}
@@ -0,0 +1,20 @@
+//! Content processor plugins
+//!
+//! Plugins are WASM modules described by a `plugin.json` manifest. Enabled
+//! plugins are compiled into a [`ProcessorPipeline`] whose transforms run over
+//! section content when a document is loaded or assembled.
+//!
+//! Projects can also define rhai [`ScriptHooks`] that run on load, save and
+//! assembly with access to the whole document.
+
+pub mod manifest;
+pub mod pipeline;
+pub mod registry;
+pub mod script_hooks;
+pub mod wasm;
+
+pub use manifest::*;
+pub use pipeline::*;
+pub use registry::*;
+pub use script_hooks::*;
+pub use wasm::*;
@@ -0,0 +1,100 @@
+use crate::error::Result;
+use crate::models::Section;
+use super::manifest::PluginStage;
+use std::sync::Arc;
+
+/// A transform over section content contributed by a plugin
+pub trait ContentTransform: Send + Sync {
+    /// Plugin ID, used in error messages
+    fn id(&self) -> &str;
+
+    fn applies_to(&self, stage: PluginStage, section_type: &str) -> bool;
+
+    fn transform(&self, content: &str) -> Result<String>;
+}
+
+/// Ordered list of transforms run over every section
+#[derive(Clone, Default)]
+pub struct ProcessorPipeline {
+    transforms: Vec<Arc<dyn ContentTransform>>,
+}
+
+impl ProcessorPipeline {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn push(&mut self, transform: Arc<dyn ContentTransform>) {
+        self.transforms.push(transform);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.transforms.is_empty()
+    }
+
+    /// IDs of the transforms, in the order they run
+    pub fn ids(&self) -> Vec<String> {
+        self.transforms.iter().map(|t| t.id().to_string()).collect()
+    }
+
+    /// Run the stage's transforms over the section tree, in pipeline order
+    pub fn run(&self, stage: PluginStage, sections: &mut [Section]) -> Result<()> {
+        if self.is_empty() {
+            return Ok(());
+        }
+        for section in sections {
+            for transform in &self.transforms {
+                if transform.applies_to(stage, &section.section_type) {
+                    section.content = transform.transform(&section.content)?;
+                }
+            }
+            self.run(stage, &mut section.children)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::fmt::Debug for ProcessorPipeline {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ProcessorPipeline").field("transforms", &self.ids()).finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Suffix(&'static str, PluginStage);
+
+    impl ContentTransform for Suffix {
+        fn id(&self) -> &str {
+            self.0
+        }
+
+        fn applies_to(&self, stage: PluginStage, section_type: &str) -> bool {
+            stage == self.1 && section_type != "evaluation"
+        }
+
+        fn transform(&self, content: &str) -> Result<String> {
+            Ok(format!("{}+{}", content, self.0))
+        }
+    }
+
+    #[test]
+    fn test_runs_matching_transforms_in_order() {
+        let mut pipeline = ProcessorPipeline::new();
+        pipeline.push(Arc::new(Suffix("a", PluginStage::Load)));
+        pipeline.push(Arc::new(Suffix("b", PluginStage::Load)));
+        pipeline.push(Arc::new(Suffix("c", PluginStage::Assembly)));
+
+        let mut parent = Section::new("process-1", "process", "p");
+        parent.children.push(Section::new("evaluation-1", "evaluation", "e"));
+        let mut sections = vec![parent];
+
+        pipeline.run(PluginStage::Load, &mut sections).unwrap();
+
+        assert_eq!(sections[0].content, "p+a+b");
+        assert_eq!(sections[0].children[0].content, "e");
+        assert_eq!(pipeline.ids(), vec!["a", "b", "c"]);
+    }
+}
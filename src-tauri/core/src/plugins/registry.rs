@@ -0,0 +1,172 @@
+use crate::error::{ContextError, Result};
+use super::manifest::{PluginManifest, MANIFEST_FILE};
+use super::pipeline::ProcessorPipeline;
+use super::wasm::WasmTransform;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, LazyLock, RwLock};
+
+/// File in the plugins directory listing the enabled plugin IDs
+pub const ENABLED_FILE: &str = "enabled.json";
+
+/// A plugin found in the plugins directory
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct PluginInfo {
+    pub manifest: PluginManifest,
+    pub path: String,
+    pub enabled: bool,
+    /// Why the plugin could not be loaded, if it could not
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+static ACTIVE_PIPELINE: LazyLock<RwLock<Arc<ProcessorPipeline>>> =
+    LazyLock::new(|| RwLock::new(Arc::new(ProcessorPipeline::new())));
+
+/// Pipeline of the currently enabled plugins, used by load and assembly
+pub fn active_pipeline() -> Arc<ProcessorPipeline> {
+    ACTIVE_PIPELINE.read().unwrap().clone()
+}
+
+pub fn set_active_pipeline(pipeline: ProcessorPipeline) {
+    *ACTIVE_PIPELINE.write().unwrap() = Arc::new(pipeline);
+}
+
+/// Plugins in the subdirectories of `dir`, sorted by ID
+///
+/// A directory with a broken manifest is skipped rather than failing the
+/// listing, since one bad plugin should not hide the others.
+pub fn discover_plugins(dir: &Path) -> Result<Vec<PluginInfo>> {
+    let entries = match std::fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(vec![]),
+        Err(e) => return Err(e.into()),
+    };
+    let enabled = read_enabled(dir)?;
+
+    let mut plugins = Vec::new();
+    for entry in entries {
+        let path = entry?.path();
+        if !path.join(MANIFEST_FILE).is_file() {
+            continue;
+        }
+        if let Ok(manifest) = PluginManifest::read(&path) {
+            plugins.push(PluginInfo {
+                enabled: enabled.contains(&manifest.id),
+                manifest,
+                path: path.to_string_lossy().into_owned(),
+                error: None,
+            });
+        }
+    }
+    plugins.sort_by(|a, b| a.manifest.id.cmp(&b.manifest.id));
+    Ok(plugins)
+}
+
+/// Compile the enabled plugins in `dir` and make them the active pipeline
+pub fn load_plugins(dir: &Path) -> Result<Vec<PluginInfo>> {
+    let (plugins, pipeline) = build_pipeline(dir)?;
+    set_active_pipeline(pipeline);
+    Ok(plugins)
+}
+
+/// Compile the enabled plugins in `dir` into a pipeline, in ID order
+///
+/// Plugins that fail to load are reported with their error and left out.
+pub fn build_pipeline(dir: &Path) -> Result<(Vec<PluginInfo>, ProcessorPipeline)> {
+    let mut plugins = discover_plugins(dir)?;
+    let mut pipeline = ProcessorPipeline::new();
+
+    for plugin in plugins.iter_mut().filter(|p| p.enabled) {
+        let entry = PathBuf::from(&plugin.path).join(&plugin.manifest.entry);
+        let loaded = std::fs::read(&entry)
+            .map_err(ContextError::from)
+            .and_then(|wasm| WasmTransform::new(plugin.manifest.clone(), &wasm));
+        match loaded {
+            Ok(transform) => pipeline.push(Arc::new(transform)),
+            Err(e) => plugin.error = Some(e.to_string()),
+        }
+    }
+
+    Ok((plugins, pipeline))
+}
+
+/// Enable or disable a plugin; takes effect on the next [`load_plugins`]
+pub fn set_plugin_enabled(dir: &Path, id: &str, enabled: bool) -> Result<()> {
+    if !discover_plugins(dir)?.iter().any(|p| p.manifest.id == id) {
+        return Err(ContextError::InvalidArgument(format!("Unknown plugin '{}'", id)));
+    }
+
+    let mut ids = read_enabled(dir)?;
+    if enabled {
+        ids.insert(id.to_string());
+    } else {
+        ids.remove(id);
+    }
+    let json = serde_json::to_string_pretty(&ids).map_err(|e| ContextError::SerializationError(e.to_string()))?;
+    std::fs::write(dir.join(ENABLED_FILE), json)?;
+    Ok(())
+}
+
+fn read_enabled(dir: &Path) -> Result<BTreeSet<String>> {
+    let path = dir.join(ENABLED_FILE);
+    match std::fs::read_to_string(&path) {
+        Ok(json) => serde_json::from_str(&json)
+            .map_err(|e| ContextError::PluginError(format!("{}: {}", path.display(), e))),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(BTreeSet::new()),
+        Err(e) => Err(e.into()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::plugins::wasm::tests::{manifest, UPPERCASE_WAT};
+
+    fn install(dir: &Path, id: &str, wasm: Option<&[u8]>) {
+        let plugin_dir = dir.join(id);
+        std::fs::create_dir_all(&plugin_dir).unwrap();
+        std::fs::write(plugin_dir.join(MANIFEST_FILE), serde_json::to_string(&manifest(id)).unwrap()).unwrap();
+        if let Some(wasm) = wasm {
+            std::fs::write(plugin_dir.join("plugin.wasm"), wasm).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_discover_and_enable() {
+        let dir = tempfile::TempDir::new().unwrap();
+        install(dir.path(), "upper", Some(&wat::parse_str(UPPERCASE_WAT).unwrap()));
+        install(dir.path(), "broken", None);
+        std::fs::create_dir(dir.path().join("not-a-plugin")).unwrap();
+
+        let plugins = discover_plugins(dir.path()).unwrap();
+        let ids: Vec<&str> = plugins.iter().map(|p| p.manifest.id.as_str()).collect();
+        assert_eq!(ids, vec!["broken", "upper"]);
+        assert!(plugins.iter().all(|p| !p.enabled));
+
+        set_plugin_enabled(dir.path(), "upper", true).unwrap();
+        set_plugin_enabled(dir.path(), "broken", true).unwrap();
+        let (plugins, pipeline) = build_pipeline(dir.path()).unwrap();
+
+        assert!(plugins.iter().all(|p| p.enabled));
+        assert!(plugins[0].error.is_some());
+        assert!(plugins[1].error.is_none());
+        assert_eq!(pipeline.ids(), vec!["upper"]);
+
+        set_plugin_enabled(dir.path(), "broken", false).unwrap();
+        assert!(!discover_plugins(dir.path()).unwrap()[0].enabled);
+
+        assert!(matches!(
+            set_plugin_enabled(dir.path(), "missing", true),
+            Err(ContextError::InvalidArgument(_))
+        ));
+    }
+
+    #[test]
+    fn test_missing_directory_has_no_plugins() {
+        let dir = tempfile::TempDir::new().unwrap();
+        assert!(discover_plugins(&dir.path().join("plugins")).unwrap().is_empty());
+    }
+}
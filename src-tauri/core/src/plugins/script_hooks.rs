@@ -0,0 +1,333 @@
+use crate::error::{ContextError, Result};
+use crate::models::{ContextDocument, Section, Variable};
+use crate::processors::document_edits::{ensure_not_encrypted, find_section_mut};
+use rhai::module_resolvers::DummyModuleResolver;
+use rhai::{Array, Dynamic, Engine, Map, Scope, AST};
+use std::path::Path;
+
+/// Project script looked up next to the document
+pub const HOOKS_FILE: &str = "flow-writer.rhai";
+
+/// Operations a single hook call may run before it is stopped
+pub const MAX_OPERATIONS: u64 = 1_000_000;
+
+/// User-defined hooks from a project's rhai script
+///
+/// A script may define any of:
+///
+/// ```rhai
+/// fn on_load(doc) { doc.variables.owner = "team"; doc }
+/// fn on_save(doc) { if doc.title == "" { throw "Title is required" } }
+/// fn on_assemble(context, doc) { context + "\n\n-- " + doc.title }
+/// ```
+///
+/// Hooks see the document as a map (`title`, `author`, `description`,
+/// `tags`, `variables`, and `sections` with `id`, `type`, `content`, `tags`
+/// and nested sections as `children`). Metadata, variables and the content
+/// or tags of sections at any depth may be changed by returning the map;
+/// sections cannot be added, removed or renamed, and the content of an
+/// encrypted section (its ciphertext) cannot be changed. A hook
+/// that throws fails the operation, which is how `on_save` enforces
+/// conventions. Scripts cannot import modules or reach the file system, and
+/// each call is limited to [`MAX_OPERATIONS`].
+pub struct ScriptHooks {
+    engine: Engine,
+    ast: AST,
+}
+
+impl ScriptHooks {
+    pub fn compile(script: &str) -> Result<ScriptHooks> {
+        let engine = sandboxed_engine();
+        let ast = engine
+            .compile(script)
+            .map_err(|e| ContextError::ScriptError(format!("{}: {}", HOOKS_FILE, e)))?;
+        Ok(ScriptHooks { engine, ast })
+    }
+
+    /// Hooks of the project the document belongs to, if it has a script
+    pub fn for_document(file_path: &str) -> Result<Option<ScriptHooks>> {
+        let dir = Path::new(file_path).parent().unwrap_or(Path::new("."));
+        match std::fs::read_to_string(dir.join(HOOKS_FILE)) {
+            Ok(script) => ScriptHooks::compile(&script).map(Some),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Whether the script defines the hook function
+    pub fn defines(&self, hook: &str) -> bool {
+        self.ast.iter_functions().any(|f| f.name == hook)
+    }
+
+    pub fn on_load(&self, doc: &mut ContextDocument) -> Result<()> {
+        self.run_document_hook("on_load", doc)
+    }
+
+    pub fn on_save(&self, doc: &mut ContextDocument) -> Result<()> {
+        self.run_document_hook("on_save", doc)
+    }
+
+    /// Post-process the assembled context; returns it unchanged if the hook returns nothing
+    pub fn on_assemble(&self, doc: &ContextDocument, context: String) -> Result<String> {
+        if !self.defines("on_assemble") {
+            return Ok(context);
+        }
+        let result = self.call("on_assemble", (context.clone(), Dynamic::from_map(to_script(doc))))?;
+        if result.is_unit() {
+            return Ok(context);
+        }
+        result
+            .into_string()
+            .map_err(|t| ContextError::ScriptError(format!("on_assemble must return a string, not {}", t)))
+    }
+
+    fn run_document_hook(&self, hook: &str, doc: &mut ContextDocument) -> Result<()> {
+        if !self.defines(hook) {
+            return Ok(());
+        }
+        let result = self.call(hook, (Dynamic::from_map(to_script(doc)),))?;
+        if result.is_unit() {
+            return Ok(());
+        }
+        let map = result
+            .try_cast::<Map>()
+            .ok_or_else(|| ContextError::ScriptError(format!("{} must return the document map", hook)))?;
+        apply_script(doc, &map)
+    }
+
+    fn call(&self, hook: &str, args: impl rhai::FuncArgs) -> Result<Dynamic> {
+        self.engine
+            .call_fn::<Dynamic>(&mut Scope::new(), &self.ast, hook, args)
+            .map_err(|e| ContextError::ScriptError(format!("{}: {}", hook, e)))
+    }
+}
+
+fn sandboxed_engine() -> Engine {
+    let mut engine = Engine::new();
+    engine
+        .set_module_resolver(DummyModuleResolver::new())
+        .set_max_operations(MAX_OPERATIONS)
+        .set_max_call_levels(32)
+        .set_max_expr_depths(64, 32)
+        .set_max_string_size(10 * 1024 * 1024)
+        .set_max_array_size(100_000)
+        .set_max_map_size(100_000)
+        .disable_symbol("eval")
+        .on_print(|_| {})
+        .on_debug(|_, _, _| {});
+    engine
+}
+
+fn strings(values: &[String]) -> Array {
+    values.iter().map(|v| Dynamic::from(v.clone())).collect()
+}
+
+fn to_script(doc: &ContextDocument) -> Map {
+    let mut variables = Map::new();
+    for var in &doc.variables {
+        variables.insert(var.name.as_str().into(), var.value.clone().into());
+    }
+
+    let mut map = Map::new();
+    map.insert("title".into(), doc.meta.title.clone().into());
+    map.insert("author".into(), doc.meta.author.clone().into());
+    map.insert("description".into(), doc.meta.description.clone().into());
+    map.insert("tags".into(), strings(&doc.meta.tags).into());
+    map.insert("variables".into(), variables.into());
+    map.insert("sections".into(), section_maps(&doc.sections).into());
+    map
+}
+
+fn section_maps(sections: &[Section]) -> Array {
+    sections
+        .iter()
+        .map(|section| {
+            let mut map = Map::new();
+            map.insert("id".into(), section.id.clone().into());
+            map.insert("type".into(), section.section_type.clone().into());
+            map.insert("content".into(), section.content.clone().into());
+            map.insert("tags".into(), strings(&section.tags).into());
+            map.insert("children".into(), section_maps(&section.children).into());
+            Dynamic::from_map(map)
+        })
+        .collect()
+}
+
+/// Copy the editable fields of a hook's returned map back into the document
+fn apply_script(doc: &mut ContextDocument, map: &Map) -> Result<()> {
+    if let Some(title) = get_string(map, "title")? {
+        doc.meta.title = title;
+    }
+    if let Some(author) = get_string(map, "author")? {
+        doc.meta.author = author;
+    }
+    if let Some(description) = get_string(map, "description")? {
+        doc.meta.description = description;
+    }
+    if let Some(tags) = get_strings(map, "tags")? {
+        doc.meta.tags = tags;
+    }
+
+    if let Some(variables) = map.get("variables") {
+        let variables = variables
+            .read_lock::<Map>()
+            .ok_or_else(|| ContextError::ScriptError("variables must be a map".to_string()))?;
+        // Keep the document's order; new variables go at the end
+        doc.variables.retain(|var| variables.contains_key(var.name.as_str()));
+        for (name, value) in variables.iter() {
+            let value = value.to_string();
+            match doc.variables.iter_mut().find(|var| var.name == name.as_str()) {
+                Some(var) => var.value = value,
+                None => doc.variables.push(Variable { name: name.to_string(), value, description: None, sensitive: false, resolve_in: vec![], due: None }),
+            }
+        }
+    }
+
+    if let Some(sections) = map.get("sections") {
+        apply_sections(doc, sections, "sections")?;
+    }
+    Ok(())
+}
+
+/// Copy content and tags of section entries, and of their `children`, to the sections with their IDs
+fn apply_sections(doc: &mut ContextDocument, sections: &Dynamic, key: &str) -> Result<()> {
+    let sections = sections
+        .read_lock::<Array>()
+        .ok_or_else(|| ContextError::ScriptError(format!("{} must be an array", key)))?;
+    for entry in sections.iter() {
+        let Some(entry) = entry.read_lock::<Map>() else {
+            continue;
+        };
+        if let Some(id) = get_string(&entry, "id")? {
+            if let Some(section) = find_section_mut(&mut doc.sections, &id) {
+                if let Some(content) = get_string(&entry, "content")?.filter(|content| *content != section.content) {
+                    ensure_not_encrypted(section)?;
+                    section.content = content;
+                }
+                if let Some(tags) = get_strings(&entry, "tags")? {
+                    section.tags = tags;
+                }
+            }
+        }
+        if let Some(children) = entry.get("children") {
+            apply_sections(doc, children, "children")?;
+        }
+    }
+    Ok(())
+}
+
+fn get_string(map: &Map, key: &str) -> Result<Option<String>> {
+    match map.get(key) {
+        None => Ok(None),
+        Some(value) => value
+            .clone()
+            .into_string()
+            .map(Some)
+            .map_err(|t| ContextError::ScriptError(format!("{} must be a string, not {}", key, t))),
+    }
+}
+
+fn get_strings(map: &Map, key: &str) -> Result<Option<Vec<String>>> {
+    let Some(value) = map.get(key) else {
+        return Ok(None);
+    };
+    let array = value
+        .read_lock::<Array>()
+        .ok_or_else(|| ContextError::ScriptError(format!("{} must be an array", key)))?;
+    Ok(Some(array.iter().map(|v| v.to_string()).collect()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn document() -> ContextDocument {
+        ContextDocument::builder()
+            .title("Hooks")
+            .variable("goal", "Ship v1")
+            .section("intent-1", "intent", "Goal: ${goal}")
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_on_load_populates_variables_and_content() {
+        let hooks = ScriptHooks::compile(
+            r#"
+            fn on_load(doc) {
+                doc.variables.owner = "platform";
+                doc.sections[0].content += "\nOwner: ${owner}";
+                doc.sections[0].tags.push("reviewed");
+                doc
+            }
+            "#,
+        )
+        .unwrap();
+        let mut doc = document();
+
+        hooks.on_load(&mut doc).unwrap();
+
+        assert_eq!(doc.variables.len(), 2);
+        assert_eq!(doc.variables[1].name, "owner");
+        assert_eq!(doc.sections[0].content, "Goal: ${goal}\nOwner: ${owner}");
+        assert_eq!(doc.sections[0].tags, vec!["reviewed"]);
+    }
+
+    #[test]
+    fn test_hooks_see_and_edit_nested_sections() {
+        let hooks = ScriptHooks::compile(
+            r#"
+            fn on_save(doc) {
+                for child in doc.sections[0].children {
+                    if child.content == "" { throw "Section " + child.id + " is empty" }
+                }
+                doc.sections[0].children[0].content = "Filled in";
+                doc
+            }
+            "#,
+        )
+        .unwrap();
+        let mut doc = document();
+        doc.sections[0].children.push(Section::new("intent-2", "intent", ""));
+
+        let error = hooks.on_save(&mut doc).unwrap_err();
+        assert!(error.to_string().contains("Section intent-2 is empty"));
+
+        doc.sections[0].children[0].content = "Draft".to_string();
+        hooks.on_save(&mut doc).unwrap();
+        assert_eq!(doc.sections[0].children[0].content, "Filled in");
+    }
+
+    #[test]
+    fn test_on_save_can_reject() {
+        let hooks = ScriptHooks::compile(r#"fn on_save(doc) { if doc.author == "" { throw "Author is required" } }"#).unwrap();
+        let mut doc = document();
+
+        let error = hooks.on_save(&mut doc).unwrap_err();
+        assert!(error.to_string().contains("Author is required"));
+
+        doc.meta.author = "Jeremy".to_string();
+        assert!(hooks.on_save(&mut doc).is_ok());
+    }
+
+    #[test]
+    fn test_on_assemble_and_missing_hooks() {
+        let hooks = ScriptHooks::compile(r##"fn on_assemble(context, doc) { "# " + doc.title + "\n" + context }"##).unwrap();
+        let mut doc = document();
+
+        assert_eq!(hooks.on_assemble(&doc, "Body".to_string()).unwrap(), "# Hooks\nBody");
+        hooks.on_load(&mut doc).unwrap();
+        assert_eq!(doc, document());
+    }
+
+    #[test]
+    fn test_scripts_are_sandboxed() {
+        let runaway = ScriptHooks::compile("fn on_load(doc) { loop {} }").unwrap();
+        assert!(matches!(runaway.on_load(&mut document()), Err(ContextError::ScriptError(_))));
+
+        let importing = ScriptHooks::compile(r#"fn on_load(doc) { import "fs" as fs; doc }"#).unwrap();
+        assert!(importing.on_load(&mut document()).is_err());
+
+        assert!(ScriptHooks::compile(r#"fn on_load(doc) { eval("1") }"#).is_err());
+    }
+}
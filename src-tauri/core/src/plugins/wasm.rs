@@ -0,0 +1,204 @@
+use crate::error::{ContextError, Result};
+use super::manifest::{PluginManifest, PluginStage};
+use super::pipeline::ContentTransform;
+use wasmi::{Config, Engine, Linker, Module, Store, StoreLimits, StoreLimitsBuilder};
+
+/// Instructions a plugin may execute per section before it is stopped
+pub const FUEL_PER_CALL: u64 = 50_000_000;
+
+/// Linear memory a plugin may grow to
+pub const MAX_MEMORY_BYTES: usize = 64 * 1024 * 1024;
+
+/// Content transform implemented by a sandboxed WASM module
+///
+/// The module must export `memory`, `alloc(len: i32) -> i32` and
+/// `transform(ptr: i32, len: i32) -> i64`, which returns the output location
+/// packed as `ptr << 32 | len`. Content is passed as UTF-8.
+///
+/// Modules get no host functions at all, so a module that imports anything
+/// is rejected. Every call runs in a fresh instance with limited fuel and
+/// memory, so a plugin can neither keep state between sections nor hang the app.
+pub struct WasmTransform {
+    manifest: PluginManifest,
+    engine: Engine,
+    module: Module,
+}
+
+impl WasmTransform {
+    pub fn new(manifest: PluginManifest, wasm: &[u8]) -> Result<WasmTransform> {
+        let mut config = Config::default();
+        config.consume_fuel(true);
+        let engine = Engine::new(&config);
+        let module = Module::new(&engine, wasm).map_err(|e| plugin_error(&manifest, e))?;
+
+        if let Some(import) = module.imports().next() {
+            return Err(ContextError::PluginError(format!(
+                "Plugin '{}' imports '{}::{}'; plugins may not import host functions",
+                manifest.id,
+                import.module(),
+                import.name()
+            )));
+        }
+
+        Ok(WasmTransform { manifest, engine, module })
+    }
+
+    pub fn manifest(&self) -> &PluginManifest {
+        &self.manifest
+    }
+
+    fn call(&self, input: &str) -> std::result::Result<String, String> {
+        let limits = StoreLimitsBuilder::new().memory_size(MAX_MEMORY_BYTES).instances(1).build();
+        let mut store: Store<StoreLimits> = Store::new(&self.engine, limits);
+        store.limiter(|limits| limits);
+        store.set_fuel(FUEL_PER_CALL).map_err(|e| e.to_string())?;
+
+        let instance = Linker::<StoreLimits>::new(&self.engine)
+            .instantiate(&mut store, &self.module)
+            .and_then(|pre| pre.start(&mut store))
+            .map_err(|e| e.to_string())?;
+        let memory = instance
+            .get_memory(&store, "memory")
+            .ok_or("module does not export 'memory'")?;
+        let alloc = instance
+            .get_typed_func::<i32, i32>(&store, "alloc")
+            .map_err(|e| format!("alloc: {}", e))?;
+        let transform = instance
+            .get_typed_func::<(i32, i32), i64>(&store, "transform")
+            .map_err(|e| format!("transform: {}", e))?;
+
+        let len = i32::try_from(input.len()).map_err(|_| "content too large")?;
+        let ptr = alloc.call(&mut store, len).map_err(|e| e.to_string())?;
+        memory
+            .write(&mut store, ptr as u32 as usize, input.as_bytes())
+            .map_err(|e| e.to_string())?;
+
+        let packed = transform.call(&mut store, (ptr, len)).map_err(|e| e.to_string())? as u64;
+        let (out_ptr, out_len) = ((packed >> 32) as usize, (packed & 0xFFFF_FFFF) as usize);
+        // The length comes from the plugin; check it before allocating on the host
+        let in_bounds = out_ptr.checked_add(out_len).is_some_and(|end| end <= memory.data(&store).len());
+        if !in_bounds || out_len > MAX_MEMORY_BYTES {
+            return Err(format!("output of {} bytes at {} is outside the plugin's memory", out_len, out_ptr));
+        }
+        let mut output = vec![0; out_len];
+        memory.read(&store, out_ptr, &mut output).map_err(|e| e.to_string())?;
+
+        String::from_utf8(output).map_err(|_| "output is not valid UTF-8".to_string())
+    }
+}
+
+impl ContentTransform for WasmTransform {
+    fn id(&self) -> &str {
+        &self.manifest.id
+    }
+
+    fn applies_to(&self, stage: PluginStage, section_type: &str) -> bool {
+        self.manifest.applies_to(stage, section_type)
+    }
+
+    fn transform(&self, content: &str) -> Result<String> {
+        self.call(content).map_err(|e| plugin_error(&self.manifest, e))
+    }
+}
+
+fn plugin_error(manifest: &PluginManifest, error: impl std::fmt::Display) -> ContextError {
+    ContextError::PluginError(format!("{}: {}", manifest.id, error))
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    /// Uppercases ASCII letters in place
+    pub(crate) const UPPERCASE_WAT: &str = r#"
+        (module
+          (memory (export "memory") 1)
+          (func (export "alloc") (param $len i32) (result i32) (i32.const 1024))
+          (func (export "transform") (param $ptr i32) (param $len i32) (result i64)
+            (local $i i32) (local $c i32)
+            (block $done
+              (loop $next
+                (br_if $done (i32.ge_u (local.get $i) (local.get $len)))
+                (local.set $c (i32.load8_u (i32.add (local.get $ptr) (local.get $i))))
+                (if (i32.and (i32.ge_u (local.get $c) (i32.const 97)) (i32.le_u (local.get $c) (i32.const 122)))
+                  (then (i32.store8 (i32.add (local.get $ptr) (local.get $i)) (i32.sub (local.get $c) (i32.const 32)))))
+                (local.set $i (i32.add (local.get $i) (i32.const 1)))
+                (br $next)))
+            (i64.or
+              (i64.shl (i64.extend_i32_u (local.get $ptr)) (i64.const 32))
+              (i64.extend_i32_u (local.get $len)))))
+    "#;
+
+    pub(crate) fn manifest(id: &str) -> PluginManifest {
+        PluginManifest {
+            id: id.to_string(),
+            name: id.to_string(),
+            version: "0.1.0".to_string(),
+            description: String::new(),
+            entry: "plugin.wasm".to_string(),
+            stages: vec![PluginStage::Assembly],
+            section_types: vec![],
+        }
+    }
+
+    #[test]
+    fn test_runs_wasm_transform() {
+        let wasm = wat::parse_str(UPPERCASE_WAT).unwrap();
+        let transform = WasmTransform::new(manifest("upper"), &wasm).unwrap();
+
+        assert_eq!(transform.transform("Hello, wörld").unwrap(), "HELLO, WöRLD");
+        assert_eq!(transform.transform("").unwrap(), "");
+    }
+
+    #[test]
+    fn test_rejects_imports() {
+        let wasm = wat::parse_str(r#"(module (import "env" "read_file" (func)))"#).unwrap();
+        let error = WasmTransform::new(manifest("sneaky"), &wasm).err().unwrap();
+        assert!(error.to_string().contains("env::read_file"));
+    }
+
+    #[test]
+    fn test_endless_loop_runs_out_of_fuel() {
+        let wasm = wat::parse_str(
+            r#"(module
+                 (memory (export "memory") 1)
+                 (func (export "alloc") (param i32) (result i32) (i32.const 0))
+                 (func (export "transform") (param i32 i32) (result i64)
+                   (loop $forever (br $forever))
+                   (i64.const 0)))"#,
+        )
+        .unwrap();
+        let transform = WasmTransform::new(manifest("spin"), &wasm).unwrap();
+
+        assert!(matches!(transform.transform("x"), Err(ContextError::PluginError(_))));
+    }
+
+    #[test]
+    fn test_out_of_bounds_output_is_an_error() {
+        let wasm = wat::parse_str(
+            r#"(module
+                 (memory (export "memory") 1)
+                 (func (export "alloc") (param i32) (result i32) (i32.const 0))
+                 (func (export "transform") (param i32 i32) (result i64) (i64.const 0x0000FFFF00001000)))"#,
+        )
+        .unwrap();
+        let transform = WasmTransform::new(manifest("oob"), &wasm).unwrap();
+
+        assert!(transform.transform("x").is_err());
+    }
+
+    #[test]
+    fn test_oversized_output_length_is_rejected() {
+        let wasm = wat::parse_str(
+            r#"(module
+                 (memory (export "memory") 1)
+                 (func (export "alloc") (param i32) (result i32) (i32.const 0))
+                 (func (export "transform") (param i32 i32) (result i64) (i64.const 0x00000000FFFFFFFF)))"#,
+        )
+        .unwrap();
+        let transform = WasmTransform::new(manifest("huge"), &wasm).unwrap();
+
+        let error = transform.transform("x").unwrap_err();
+        assert!(error.to_string().contains("outside the plugin's memory"));
+    }
+}
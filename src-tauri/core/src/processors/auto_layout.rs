@@ -0,0 +1,288 @@
+use std::collections::{BTreeMap, HashMap};
+use crate::models::*;
+
+/// Distance between neighbouring nodes within a layer
+pub const NODE_SPACING: f64 = 180.0;
+/// Distance between consecutive layers
+pub const LAYER_SPACING: f64 = 120.0;
+
+const ORDERING_SWEEPS: usize = 4;
+
+/// Compute node positions with a layered (Sugiyama-style) layout
+///
+/// 1. Break cycles by reversing DFS back edges
+/// 2. Assign layers by longest path from the sources
+/// 3. Split edges spanning several layers with virtual nodes
+/// 4. Reduce crossings with alternating barycenter sweeps
+/// 5. Centre each layer and rotate the result to the flow direction
+///
+/// Nodes that only appear as edge endpoints are laid out as well. The result
+/// is normalized so the smallest coordinates are zero.
+pub fn compute_layout(graph: &GraphStructure, direction: FlowDirection) -> FlowLayout {
+    let mut ids: Vec<String> = Vec::new();
+    let mut index: HashMap<String, usize> = HashMap::new();
+    let node_ids = graph.nodes.iter().map(|n| n.id.as_str());
+    let edge_ids = graph.edges.iter().flat_map(|e| [e.from.as_str(), e.to.as_str()]);
+    for id in node_ids.chain(edge_ids) {
+        if !index.contains_key(id) {
+            index.insert(id.to_string(), ids.len());
+            ids.push(id.to_string());
+        }
+    }
+
+    let node_count = ids.len();
+    let mut edges: Vec<(usize, usize)> = Vec::new();
+    for edge in &graph.edges {
+        let pair = (index[&edge.from], index[&edge.to]);
+        if pair.0 != pair.1 && !edges.contains(&pair) {
+            edges.push(pair);
+        }
+    }
+
+    let edges = remove_cycles(node_count, &edges);
+    let node_layers = assign_layers(node_count, &edges);
+    let (layers, layered_edges) = insert_virtual_nodes(&node_layers, &edges);
+    let layers = order_layers(layers, &layered_edges);
+
+    let mut positions = BTreeMap::new();
+    for (layer_index, layer) in layers.iter().enumerate() {
+        let offset = (layer.len() as f64 - 1.0) / 2.0;
+        for (slot, &node) in layer.iter().enumerate() {
+            if node >= node_count {
+                continue;
+            }
+            let across = (slot as f64 - offset) * NODE_SPACING;
+            let along = layer_index as f64 * LAYER_SPACING;
+            let (x, y) = match direction {
+                FlowDirection::TopDown => (across, along),
+                FlowDirection::BottomUp => (across, -along),
+                FlowDirection::LeftRight => (along, across),
+                FlowDirection::RightLeft => (-along, across),
+            };
+            positions.insert(ids[node].clone(), NodePosition { x, y });
+        }
+    }
+
+    normalize(&mut positions);
+
+    FlowLayout {
+        zoom: 1.0,
+        pan_x: 0.0,
+        pan_y: 0.0,
+        positions,
+    }
+}
+
+/// Reverse edges that close a cycle so the graph becomes acyclic
+fn remove_cycles(node_count: usize, edges: &[(usize, usize)]) -> Vec<(usize, usize)> {
+    #[derive(Clone, Copy, PartialEq)]
+    enum State {
+        Unvisited,
+        Active,
+        Done,
+    }
+
+    fn visit(node: usize, edges: &[(usize, usize)], state: &mut [State], reversed: &mut Vec<bool>) {
+        state[node] = State::Active;
+        for (i, &(from, to)) in edges.iter().enumerate() {
+            if from != node {
+                continue;
+            }
+            match state[to] {
+                State::Unvisited => visit(to, edges, state, reversed),
+                State::Active => reversed[i] = true,
+                State::Done => {}
+            }
+        }
+        state[node] = State::Done;
+    }
+
+    let mut state = vec![State::Unvisited; node_count];
+    let mut reversed = vec![false; edges.len()];
+    for node in 0..node_count {
+        if state[node] == State::Unvisited {
+            visit(node, edges, &mut state, &mut reversed);
+        }
+    }
+
+    edges
+        .iter()
+        .zip(reversed)
+        .map(|(&(from, to), reverse)| if reverse { (to, from) } else { (from, to) })
+        .collect()
+}
+
+/// Longest-path layering: every node sits one layer below its deepest predecessor
+fn assign_layers(node_count: usize, edges: &[(usize, usize)]) -> Vec<usize> {
+    let mut in_degree = vec![0; node_count];
+    for &(_, to) in edges {
+        in_degree[to] += 1;
+    }
+
+    let mut queue: Vec<usize> = (0..node_count).filter(|&n| in_degree[n] == 0).collect();
+    let mut layers = vec![0; node_count];
+    let mut head = 0;
+
+    while head < queue.len() {
+        let node = queue[head];
+        head += 1;
+        for &(from, to) in edges {
+            if from == node {
+                layers[to] = layers[to].max(layers[node] + 1);
+                in_degree[to] -= 1;
+                if in_degree[to] == 0 {
+                    queue.push(to);
+                }
+            }
+        }
+    }
+
+    layers
+}
+
+/// Group nodes by layer, adding virtual nodes so every edge joins adjacent layers
+fn insert_virtual_nodes(node_layers: &[usize], edges: &[(usize, usize)]) -> (Vec<Vec<usize>>, Vec<(usize, usize)>) {
+    let layer_count = node_layers.iter().max().map_or(0, |max| max + 1);
+    let mut layers: Vec<Vec<usize>> = vec![Vec::new(); layer_count];
+    for (node, &layer) in node_layers.iter().enumerate() {
+        layers[layer].push(node);
+    }
+
+    let mut next_id = node_layers.len();
+    let mut layered_edges = Vec::new();
+    for &(from, to) in edges {
+        let mut previous = from;
+        for layer in layers.iter_mut().take(node_layers[to]).skip(node_layers[from] + 1) {
+            layer.push(next_id);
+            layered_edges.push((previous, next_id));
+            previous = next_id;
+            next_id += 1;
+        }
+        layered_edges.push((previous, to));
+    }
+
+    (layers, layered_edges)
+}
+
+/// Reorder nodes inside layers by the barycenter of their neighbours, sweeping down then up
+fn order_layers(mut layers: Vec<Vec<usize>>, edges: &[(usize, usize)]) -> Vec<Vec<usize>> {
+    for _ in 0..ORDERING_SWEEPS {
+        for i in 1..layers.len() {
+            let (fixed, free) = layers.split_at_mut(i);
+            reorder(&mut free[0], &fixed[i - 1], edges, false);
+        }
+        for i in (0..layers.len().saturating_sub(1)).rev() {
+            let (free, fixed) = layers.split_at_mut(i + 1);
+            reorder(&mut free[i], &fixed[0], edges, true);
+        }
+    }
+
+    layers
+}
+
+fn reorder(layer: &mut [usize], neighbour_layer: &[usize], edges: &[(usize, usize)], use_successors: bool) {
+    let slot: HashMap<usize, usize> = neighbour_layer.iter().enumerate().map(|(i, &n)| (n, i)).collect();
+
+    let mut keyed: Vec<(f64, usize)> = layer
+        .iter()
+        .enumerate()
+        .map(|(current, &node)| {
+            let neighbours: Vec<usize> = edges
+                .iter()
+                .filter_map(|&(from, to)| match use_successors {
+                    false if to == node => slot.get(&from).copied(),
+                    true if from == node => slot.get(&to).copied(),
+                    _ => None,
+                })
+                .collect();
+            let barycenter = if neighbours.is_empty() {
+                current as f64
+            } else {
+                neighbours.iter().sum::<usize>() as f64 / neighbours.len() as f64
+            };
+            (barycenter, node)
+        })
+        .collect();
+
+    keyed.sort_by(|a, b| a.0.total_cmp(&b.0));
+    for (target, (_, node)) in layer.iter_mut().zip(keyed) {
+        *target = node;
+    }
+}
+
+fn normalize(positions: &mut BTreeMap<String, NodePosition>) {
+    let min_x = positions.values().map(|p| p.x).fold(f64::INFINITY, f64::min);
+    let min_y = positions.values().map(|p| p.y).fold(f64::INFINITY, f64::min);
+
+    for position in positions.values_mut() {
+        position.x -= min_x;
+        position.y -= min_y;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parsers::mermaid_parser::parse_mermaid;
+
+    fn layout_of(code: &str, direction: FlowDirection) -> FlowLayout {
+        compute_layout(&parse_mermaid(code).unwrap(), direction)
+    }
+
+    #[test]
+    fn test_chain_top_down() {
+        let layout = layout_of("A[Intent] --> B[Eval]\nB --> C[Process]", FlowDirection::TopDown);
+
+        assert_eq!(layout.positions["A"], NodePosition { x: 0.0, y: 0.0 });
+        assert_eq!(layout.positions["B"], NodePosition { x: 0.0, y: LAYER_SPACING });
+        assert_eq!(layout.positions["C"], NodePosition { x: 0.0, y: 2.0 * LAYER_SPACING });
+    }
+
+    #[test]
+    fn test_chain_left_right_and_reversed() {
+        let lr = layout_of("A[Intent] --> B[Eval]", FlowDirection::LeftRight);
+        assert!(lr.positions["A"].x < lr.positions["B"].x);
+        assert_eq!(lr.positions["A"].y, lr.positions["B"].y);
+
+        let bt = layout_of("A[Intent] --> B[Eval]", FlowDirection::BottomUp);
+        assert!(bt.positions["A"].y > bt.positions["B"].y);
+
+        let rl = layout_of("A[Intent] --> B[Eval]", FlowDirection::RightLeft);
+        assert!(rl.positions["A"].x > rl.positions["B"].x);
+    }
+
+    #[test]
+    fn test_branches_share_layer() {
+        let layout = layout_of(
+            "A[Start] --> B[Left]\nA --> C[Right]\nB --> D[End]\nC --> D",
+            FlowDirection::TopDown,
+        );
+
+        assert_eq!(layout.positions["B"].y, layout.positions["C"].y);
+        assert_ne!(layout.positions["B"].x, layout.positions["C"].x);
+        assert_eq!(layout.positions["D"].y, 2.0 * LAYER_SPACING);
+    }
+
+    #[test]
+    fn test_cycle_is_laid_out() {
+        let layout = layout_of("A[Intent] --> B[Eval]\nB --> C[Process]\nC --> A", FlowDirection::TopDown);
+
+        assert_eq!(layout.positions.len(), 3);
+        assert!(layout.positions["A"].y < layout.positions["B"].y);
+        assert!(layout.positions["B"].y < layout.positions["C"].y);
+    }
+
+    #[test]
+    fn test_long_edge_keeps_layers() {
+        let layout = layout_of("A[Start] --> B[Mid]\nB --> C[End]\nA --> C", FlowDirection::TopDown);
+
+        assert_eq!(layout.positions["C"].y, 2.0 * LAYER_SPACING);
+        assert_eq!(layout.positions.len(), 3);
+    }
+
+    #[test]
+    fn test_empty_graph() {
+        let layout = compute_layout(&GraphStructure { nodes: vec![], edges: vec![], subgraphs: vec![] }, FlowDirection::TopDown);
+        assert!(layout.positions.is_empty());
+    }
+}
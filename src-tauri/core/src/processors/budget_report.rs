@@ -0,0 +1,71 @@
+use serde::{Deserialize, Serialize};
+use crate::models::*;
+use crate::processors::variable_resolver;
+
+/// How much of its budget a section's resolved content uses
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct BudgetUsage {
+    pub section_id: String,
+    pub budget: Budget,
+    /// Size of the resolved content in the budget's unit
+    pub used: usize,
+    pub over_budget: bool,
+}
+
+/// Budget usage of every section that declares a budget, in document order
+///
+/// Content is measured after variable resolution, since that is what ends up
+/// in the prompt.
+pub fn budget_report(doc: &ContextDocument) -> Vec<BudgetUsage> {
+    let var_map = variable_resolver::build_variable_map(&doc.variables);
+    let mut usage = Vec::new();
+    collect_usage(&doc.sections, &var_map, &mut usage);
+    usage
+}
+
+fn collect_usage(
+    sections: &[Section],
+    var_map: &std::collections::HashMap<String, String>,
+    usage: &mut Vec<BudgetUsage>,
+) {
+    for section in sections {
+        if let Some(budget) = section.budget {
+            let resolved = variable_resolver::resolve_variables(&section.content, var_map);
+            let used = budget.measure(&resolved);
+            usage.push(BudgetUsage {
+                section_id: section.id.clone(),
+                budget,
+                used,
+                over_budget: used > budget.limit,
+            });
+        }
+        collect_usage(&section.children, var_map, usage);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_measures_resolved_content() {
+        let mut doc = ContextDocument::builder()
+            .title("Budgets")
+            .variable("goal", "a much longer goal than the placeholder")
+            .section("intent-1", "intent", "Goal: ${goal}")
+            .section("process-1", "process", "Short")
+            .section("evaluation-1", "evaluation", "No budget")
+            .build()
+            .unwrap();
+        doc.sections[0].budget = Budget::parse("20 chars");
+        doc.sections[1].budget = Budget::parse("10 tokens");
+
+        let report = budget_report(&doc);
+
+        assert_eq!(report.len(), 2);
+        assert_eq!(report[0].used, "Goal: a much longer goal than the placeholder".len());
+        assert!(report[0].over_budget);
+        assert_eq!(report[1].used, 2);
+        assert!(!report[1].over_budget);
+    }
+}
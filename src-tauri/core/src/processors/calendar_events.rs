@@ -0,0 +1,155 @@
+use std::sync::LazyLock;
+use chrono::NaiveDate;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use crate::models::*;
+
+/// `Milestone: <summary>` lines, optionally in a list item or task, with the label in bold
+static MILESTONE_LINE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?im)^\s*(?:[-*+]\s+|\d+[.)]\s+)?(?:\[[ xX]\]\s+)?\**milestone\**\s*:\**\s*(.+?)\s*$").unwrap()
+});
+static ISO_DATE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\b(\d{4}-\d{2}-\d{2})\b").unwrap());
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum EventSource {
+    Variable,
+    Milestone,
+}
+
+/// An all-day date found in the document
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct CalendarEvent {
+    /// Stable within the document: `var:<name>` or `milestone:<section-id>:<n>`
+    pub key: String,
+    /// ISO date, `YYYY-MM-DD`
+    pub date: String,
+    pub summary: String,
+    pub source: EventSource,
+    /// Section the milestone was found in
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub section_id: Option<String>,
+}
+
+/// Collect dated variables and milestone markers, ordered by date
+///
+/// A variable is dated when its whole value is an ISO date (`2025-11-01`).
+/// A milestone is a line starting with `Milestone:` (also as a list item or
+/// task) that contains an ISO date; the rest of the line is the summary.
+/// Sections are expected to have variables resolved, so a milestone may
+/// use a dated variable: `- Milestone: Launch ${deadline}`.
+pub fn collect_calendar_events(doc: &ContextDocument) -> Vec<CalendarEvent> {
+    let mut events: Vec<CalendarEvent> = doc
+        .variables
+        .iter()
+        .filter_map(|var| {
+            let date = parse_date(var.value.trim())?;
+            Some(CalendarEvent {
+                key: format!("var:{}", var.name),
+                date,
+                summary: humanize(&var.name),
+                source: EventSource::Variable,
+                section_id: None,
+            })
+        })
+        .collect();
+
+    let mut stack: Vec<&Section> = doc.sections.iter().rev().collect();
+    while let Some(section) = stack.pop() {
+        let milestones = MILESTONE_LINE
+            .captures_iter(&section.content)
+            .filter_map(|caps| milestone(&caps[1]))
+            .enumerate();
+        for (index, (date, summary)) in milestones {
+            events.push(CalendarEvent {
+                key: format!("milestone:{}:{}", section.id, index + 1),
+                date,
+                summary,
+                source: EventSource::Milestone,
+                section_id: Some(section.id.clone()),
+            });
+        }
+        stack.extend(section.children.iter().rev());
+    }
+
+    // ISO dates sort chronologically as text; the sort is stable so ties keep document order
+    events.sort_by(|a, b| a.date.cmp(&b.date));
+    events
+}
+
+fn parse_date(value: &str) -> Option<String> {
+    NaiveDate::parse_from_str(value, "%Y-%m-%d").ok().map(|date| date.to_string())
+}
+
+/// Date and summary of a milestone line's text, if it has a valid date
+fn milestone(text: &str) -> Option<(String, String)> {
+    let found = ISO_DATE.find_iter(text).find_map(|m| parse_date(m.as_str()).map(|date| (m, date)))?;
+    let (matched, date) = found;
+    let summary = format!("{}{}", &text[..matched.start()], &text[matched.end()..])
+        .replace("()", "")
+        .replace("**", "");
+    let summary = summary.trim_matches(|c: char| c.is_whitespace() || matches!(c, '-' | '–' | '—' | ',' | ':' | '@'));
+    let summary = if summary.is_empty() { "Milestone" } else { summary };
+    Some((date, summary.to_string()))
+}
+
+/// `launchDate` or `launch_date` -> `Launch date`
+pub(crate) fn humanize(name: &str) -> String {
+    let mut words = String::new();
+    for (index, c) in name.chars().enumerate() {
+        if c == '_' || c == '-' {
+            words.push(' ');
+        } else if c.is_uppercase() && index > 0 {
+            words.push(' ');
+            words.extend(c.to_lowercase());
+        } else if index == 0 {
+            words.extend(c.to_uppercase());
+        } else {
+            words.push(c);
+        }
+    }
+    words
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_collects_dated_variables_and_milestones() {
+        let doc = ContextDocument::builder()
+            .title("Launch")
+            .variable("launchDate", "2025-11-01")
+            .variable("goal", "Ship v1 by 2025-11-01")
+            .variable("bogus", "2025-02-30")
+            .section(
+                "process-1",
+                "process",
+                "# Process\n- Milestone: Beta (2025-10-15)\n- [ ] **Milestone:** 2025-12-01 — GA\nMilestone: no date yet",
+            )
+            .build()
+            .unwrap();
+
+        let events = collect_calendar_events(&doc);
+
+        let summary: Vec<(&str, &str, &str)> =
+            events.iter().map(|e| (e.key.as_str(), e.date.as_str(), e.summary.as_str())).collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("milestone:process-1:1", "2025-10-15", "Beta"),
+                ("var:launchDate", "2025-11-01", "Launch date"),
+                ("milestone:process-1:2", "2025-12-01", "GA"),
+            ]
+        );
+        assert_eq!(events[0].section_id.as_deref(), Some("process-1"));
+    }
+
+    #[test]
+    fn test_humanize() {
+        assert_eq!(humanize("deadline"), "Deadline");
+        assert_eq!(humanize("launch_date"), "Launch date");
+        assert_eq!(humanize("betaLaunchDate"), "Beta launch date");
+    }
+}
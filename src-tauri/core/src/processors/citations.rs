@@ -0,0 +1,104 @@
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::sync::LazyLock;
+use crate::models::ContextDocument;
+
+static CITATION_PATTERN: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\[(@[^\[\]]+)\]").unwrap());
+
+/// A `[@ref-id]` citation that does not match any entry in `<references>`
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct UnresolvedCitation {
+    pub section_id: String,
+    pub reference_id: String,
+}
+
+/// Reference IDs cited in content, in order of first appearance
+///
+/// Supports single (`[@smith-2024]`) and grouped (`[@smith-2024; @doe-2023]`) citations.
+pub fn find_citations(content: &str) -> Vec<String> {
+    let mut ids: Vec<String> = Vec::new();
+
+    for caps in CITATION_PATTERN.captures_iter(content) {
+        for part in caps[1].split(';') {
+            let Some(id) = part.trim().strip_prefix('@') else {
+                continue;
+            };
+            if !id.is_empty() && !ids.iter().any(|existing| existing == id) {
+                ids.push(id.to_string());
+            }
+        }
+    }
+
+    ids
+}
+
+/// Citations in any section (including language variants) without a matching reference
+pub fn find_unresolved_citations(doc: &ContextDocument) -> Vec<UnresolvedCitation> {
+    let known: HashSet<&str> = doc.references.iter().map(|r| r.id.as_str()).collect();
+    let mut unresolved = Vec::new();
+
+    for section in &doc.sections {
+        let mut cited = find_citations(&section.content);
+        for content in section.translations.values() {
+            for id in find_citations(content) {
+                if !cited.contains(&id) {
+                    cited.push(id);
+                }
+            }
+        }
+
+        for reference_id in cited {
+            if !known.contains(reference_id.as_str()) {
+                unresolved.push(UnresolvedCitation {
+                    section_id: section.id.clone(),
+                    reference_id,
+                });
+            }
+        }
+    }
+
+    unresolved
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::Reference;
+
+    fn reference(id: &str) -> Reference {
+        Reference {
+            id: id.to_string(),
+            title: id.to_string(),
+            url: None,
+            citation: String::new(),
+        }
+    }
+
+    #[test]
+    fn test_find_citations() {
+        let content = "See [@smith-2024] and [@doe_2023; @smith-2024]. Not a [link](x) or [@].";
+
+        assert_eq!(find_citations(content), vec!["smith-2024", "doe_2023"]);
+        assert!(find_citations("Email me@example.com [not @cited]").is_empty());
+    }
+
+    #[test]
+    fn test_find_unresolved_citations() {
+        let doc = ContextDocument::builder()
+            .title("Test")
+            .section("intent-1", "intent", "Per [@smith-2024].")
+            .section("proc-1", "process", "Per [@smith-2024; @missing-1].")
+            .reference(reference("smith-2024"))
+            .build()
+            .unwrap();
+
+        assert_eq!(
+            find_unresolved_citations(&doc),
+            vec![UnresolvedCitation {
+                section_id: "proc-1".to_string(),
+                reference_id: "missing-1".to_string(),
+            }]
+        );
+    }
+}
@@ -0,0 +1,273 @@
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::sync::LazyLock;
+use crate::models::*;
+use crate::parsers::mermaid_parser;
+use crate::processors::content_summary;
+
+static CLICK_TARGET: LazyLock<Regex> = LazyLock::new(|| Regex::new(r##"(click\s+\w+\s+"#)([^"]+)""##).unwrap());
+
+/// Minimum similarity for a section to be suggested for a node
+pub const SUGGESTION_THRESHOLD: f64 = 0.5;
+
+/// A node-to-section link, written to Mermaid as `click <node> "#<section>" "<tooltip>"`
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ClickLink {
+    pub node_id: String,
+    pub section_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tooltip: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ClickSuggestion {
+    pub node_label: String,
+    /// Similarity between 0 and 1
+    pub score: f64,
+    pub link: ClickLink,
+}
+
+/// Suggest click actions for unlinked nodes by fuzzy-matching labels against section headings and IDs
+pub fn suggest_click_actions(flow: &FlowGraph, sections: &[Section]) -> Vec<ClickSuggestion> {
+    let mut suggestions = Vec::new();
+
+    for node in flow.parsed_graph.nodes.iter().filter(|n| n.ref_section_id.is_none()) {
+        let best = sections
+            .iter()
+            .map(|section| {
+                let heading = content_summary::first_heading(&section.content);
+                let score = [
+                    heading.as_deref().map(|h| similarity(&node.label, h)).unwrap_or(0.0),
+                    similarity(&node.label, &section.id),
+                    similarity(&node.label, &section.section_type),
+                ]
+                .into_iter()
+                .fold(0.0, f64::max);
+                (section, heading, score)
+            })
+            .max_by(|a, b| a.2.total_cmp(&b.2));
+
+        if let Some((section, heading, score)) = best {
+            if score >= SUGGESTION_THRESHOLD {
+                suggestions.push(ClickSuggestion {
+                    node_label: node.label.clone(),
+                    score,
+                    link: ClickLink {
+                        node_id: node.id.clone(),
+                        section_id: section.id.clone(),
+                        tooltip: heading.map(|h| format!("Jump to {}", h)),
+                    },
+                });
+            }
+        }
+    }
+
+    suggestions
+}
+
+/// Insert `click` lines into Mermaid code, skipping nodes that already have one
+///
+/// Lines go before the closing fence when the diagram is wrapped in a
+/// ```mermaid block, otherwise at the end.
+pub fn apply_click_actions(mermaid_code: &str, links: &[ClickLink]) -> String {
+    let existing: HashSet<String> = mermaid_parser::parse_click_actions(mermaid_code)
+        .unwrap_or_default()
+        .into_iter()
+        .map(|r| r.node_id)
+        .collect();
+
+    let new_lines: Vec<String> = links
+        .iter()
+        .filter(|link| !existing.contains(&link.node_id))
+        .map(|link| match &link.tooltip {
+            Some(tooltip) => format!(
+                "  click {} \"#{}\" \"{}\"",
+                link.node_id,
+                link.section_id,
+                tooltip.replace('"', "'")
+            ),
+            None => format!("  click {} \"#{}\"", link.node_id, link.section_id),
+        })
+        .collect();
+
+    if new_lines.is_empty() {
+        return mermaid_code.to_string();
+    }
+
+    let mut lines: Vec<String> = mermaid_code.lines().map(str::to_string).collect();
+    let fence_index = lines
+        .iter()
+        .rposition(|line| line.trim() == "```")
+        .filter(|_| mermaid_code.contains("```mermaid"));
+    let insert_at = fence_index.unwrap_or(lines.len());
+
+    lines.splice(insert_at..insert_at, new_lines);
+    lines.join("\n")
+}
+
+/// Point `click` actions at new section IDs (old ID -> new ID), leaving other lines untouched
+pub fn retarget_click_actions(mermaid_code: &str, renames: &HashMap<String, String>) -> String {
+    CLICK_TARGET
+        .replace_all(mermaid_code, |caps: &regex::Captures| {
+            let target = renames.get(&caps[2]).map(String::as_str).unwrap_or(&caps[2]);
+            format!("{}{}\"", &caps[1], target)
+        })
+        .into_owned()
+}
+
+/// Dice coefficient over character bigrams of the normalized strings
+fn similarity(a: &str, b: &str) -> f64 {
+    let a = normalize(a);
+    let b = normalize(b);
+    if a.is_empty() || b.is_empty() {
+        return 0.0;
+    }
+    if a == b {
+        return 1.0;
+    }
+
+    let a_bigrams = bigrams(&a);
+    let mut b_bigrams = bigrams(&b);
+    if a_bigrams.is_empty() || b_bigrams.is_empty() {
+        return 0.0;
+    }
+
+    let total = a_bigrams.len() + b_bigrams.len();
+    let mut shared = 0;
+    for bigram in &a_bigrams {
+        if let Some(pos) = b_bigrams.iter().position(|b| b == bigram) {
+            b_bigrams.swap_remove(pos);
+            shared += 1;
+        }
+    }
+
+    (2 * shared) as f64 / total as f64
+}
+
+/// Lowercase alphanumeric words joined by single spaces; separators like `-` and `_` become spaces
+fn normalize(s: &str) -> String {
+    s.to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn bigrams(s: &str) -> Vec<(char, char)> {
+    let chars: Vec<char> = s.chars().collect();
+    chars.windows(2).map(|w| (w[0], w[1])).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeMap;
+
+    fn section(id: &str, section_type: &str, content: &str) -> Section {
+        Section {
+            id: id.to_string(),
+            section_type: section_type.to_string(),
+            content: content.to_string(),
+            ref_target: None,
+            children: vec![],
+            budget: None,
+            transclusions: vec![],
+            tags: vec![],
+            translations: BTreeMap::new(),
+            sensitive: false,
+            encrypted: false,
+        }
+    }
+
+    fn flow(mermaid_code: &str) -> FlowGraph {
+        let mut flow = FlowGraph {
+            id: "flow-1".to_string(),
+            version: "1.0".to_string(),
+            title: None,
+            mermaid_code: mermaid_code.to_string(),
+            parsed_graph: GraphStructure {
+                nodes: vec![],
+                edges: vec![],
+                subgraphs: vec![],
+            },
+            node_refs: vec![],
+            layout: None,
+            node_metadata: Default::default(),
+        };
+        mermaid_parser::enrich_flow_graph(&mut flow).unwrap();
+        flow
+    }
+
+    #[test]
+    fn test_similarity() {
+        assert_eq!(similarity("Intent", "intent"), 1.0);
+        assert!(similarity("Evaluation", "evaluation-criteria") > 0.5);
+        assert!(similarity("Deployment", "Intent") < 0.3);
+    }
+
+    #[test]
+    fn test_suggest_click_actions_for_unlinked_nodes() {
+        let flow = flow("flowchart TD\n  A[Intent] --> B[Evaluation Criteria]\n  B --> C[Launch Party]\n  click A \"#intent-1\"");
+        let sections = vec![
+            section("intent-1", "intent", "# Intent"),
+            section("eval-1", "evaluation", "# Evaluation Criteria\n- fast"),
+        ];
+
+        let suggestions = suggest_click_actions(&flow, &sections);
+
+        assert_eq!(suggestions.len(), 1);
+        assert_eq!(suggestions[0].link.node_id, "B");
+        assert_eq!(suggestions[0].link.section_id, "eval-1");
+        assert_eq!(suggestions[0].score, 1.0);
+        assert_eq!(suggestions[0].link.tooltip, Some("Jump to Evaluation Criteria".to_string()));
+    }
+
+    #[test]
+    fn test_apply_click_actions_inside_fence() {
+        let code = "```mermaid\nflowchart TD\n  A[Intent] --> B[Eval]\n  click A \"#intent-1\"\n```";
+        let links = vec![
+            ClickLink {
+                node_id: "A".to_string(),
+                section_id: "other".to_string(),
+                tooltip: None,
+            },
+            ClickLink {
+                node_id: "B".to_string(),
+                section_id: "eval-1".to_string(),
+                tooltip: Some("Jump to \"Eval\"".to_string()),
+            },
+        ];
+
+        let updated = apply_click_actions(code, &links);
+
+        assert_eq!(
+            updated,
+            "```mermaid\nflowchart TD\n  A[Intent] --> B[Eval]\n  click A \"#intent-1\"\n  click B \"#eval-1\" \"Jump to 'Eval'\"\n```"
+        );
+        let refs = mermaid_parser::parse_click_actions(&updated).unwrap();
+        assert_eq!(refs.len(), 2);
+    }
+
+    #[test]
+    fn test_apply_click_actions_without_fence() {
+        let code = "flowchart TD\n  A --> B";
+        let links = vec![ClickLink {
+            node_id: "B".to_string(),
+            section_id: "eval-1".to_string(),
+            tooltip: None,
+        }];
+
+        assert_eq!(apply_click_actions(code, &links), "flowchart TD\n  A --> B\n  click B \"#eval-1\"");
+    }
+
+    #[test]
+    fn test_retarget_click_actions() {
+        let code = "graph TD\n  A --> B\n  click A \"#old\" \"Tip\"\n  click B \"#kept\"";
+        let renames = HashMap::from([("old".to_string(), "new".to_string())]);
+
+        let result = retarget_click_actions(code, &renames);
+
+        assert_eq!(result, "graph TD\n  A --> B\n  click A \"#new\" \"Tip\"\n  click B \"#kept\"");
+    }
+}
@@ -0,0 +1,129 @@
+use serde::{Deserialize, Serialize};
+use crate::error::{ContextError, Result};
+
+/// One `---`-separated block of section content
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ContentBlock {
+    pub index: usize,
+    pub content: String,
+}
+
+/// Split content into blocks at `---` separator lines
+///
+/// A separator is a line containing only `---` that follows a blank line (or
+/// starts the content) and sits outside fenced code. The blank-line rule keeps
+/// setext headings (`Title` underlined with `---`) intact.
+pub fn split_blocks(content: &str) -> Vec<ContentBlock> {
+    block_spans(content)
+        .into_iter()
+        .enumerate()
+        .map(|(index, (start, end))| ContentBlock {
+            index,
+            content: content[start..end].to_string(),
+        })
+        .collect()
+}
+
+/// Replace a single block, leaving the rest of the raw content byte-for-byte unchanged
+pub fn replace_block(content: &str, index: usize, new_block: &str) -> Result<String> {
+    let spans = block_spans(content);
+    let (start, end) = *spans.get(index).ok_or_else(|| {
+        ContextError::InvalidArgument(format!(
+            "Block index {} out of range (section has {} blocks)",
+            index,
+            spans.len()
+        ))
+    })?;
+
+    let new_block = new_block.trim_matches('\n');
+    if block_spans(new_block).len() > 1 {
+        return Err(ContextError::InvalidArgument(
+            "Block content must not contain a '---' separator".to_string(),
+        ));
+    }
+
+    Ok(format!("{}{}{}", &content[..start], new_block, &content[end..]))
+}
+
+/// Byte ranges of each block, excluding separators and surrounding blank lines
+fn block_spans(content: &str) -> Vec<(usize, usize)> {
+    let mut spans = Vec::new();
+    let mut block_start = 0;
+    let mut offset = 0;
+    let mut in_fence = false;
+    let mut previous_blank = true;
+
+    for line in content.split_inclusive('\n') {
+        let trimmed = line.trim();
+
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            in_fence = !in_fence;
+        } else if !in_fence && trimmed == "---" && previous_blank {
+            spans.push(trim_span(content, block_start, offset));
+            block_start = offset + line.len();
+        }
+
+        previous_blank = trimmed.is_empty();
+        offset += line.len();
+    }
+
+    spans.push(trim_span(content, block_start, content.len()));
+    spans
+}
+
+/// Narrow a range so it excludes leading and trailing blank lines
+fn trim_span(content: &str, start: usize, end: usize) -> (usize, usize) {
+    let slice = &content[start..end];
+    let trimmed_start = slice.len() - slice.trim_start_matches(['\n', '\r']).len();
+    let trimmed_end = slice.trim_end().len().max(trimmed_start);
+    (start + trimmed_start, start + trimmed_end)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_blocks() {
+        let content = "# Intent\nFirst part\n\n---\n\nSecond part\n\n---\nThird part";
+        let blocks = split_blocks(content);
+
+        assert_eq!(blocks.len(), 3);
+        assert_eq!(blocks[0].content, "# Intent\nFirst part");
+        assert_eq!(blocks[1].content, "Second part");
+        assert_eq!(blocks[2].index, 2);
+        assert_eq!(blocks[2].content, "Third part");
+    }
+
+    #[test]
+    fn test_split_ignores_setext_and_code_fences() {
+        let content = "Title\n---\n\n```\n\n---\n```";
+        let blocks = split_blocks(content);
+
+        assert_eq!(blocks.len(), 1);
+        assert_eq!(blocks[0].content, content);
+    }
+
+    #[test]
+    fn test_content_without_separators_is_one_block() {
+        assert_eq!(split_blocks("Just text").len(), 1);
+        assert_eq!(split_blocks("")[0].content, "");
+    }
+
+    #[test]
+    fn test_replace_block_preserves_other_blocks() {
+        let content = "First\n\n---\n\nSecond\n\n---\n\nThird";
+        let updated = replace_block(content, 1, "New second\nline").unwrap();
+
+        assert_eq!(updated, "First\n\n---\n\nNew second\nline\n\n---\n\nThird");
+        assert_eq!(split_blocks(&updated)[1].content, "New second\nline");
+    }
+
+    #[test]
+    fn test_replace_block_rejects_bad_input() {
+        let content = "First\n\n---\n\nSecond";
+
+        assert!(replace_block(content, 5, "x").is_err());
+        assert!(replace_block(content, 0, "a\n\n---\n\nb").is_err());
+    }
+}
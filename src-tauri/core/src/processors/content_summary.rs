@@ -0,0 +1,63 @@
+use crate::exporters::markdown_to_plain_text;
+
+/// Default length of content previews, in characters
+pub const DEFAULT_PREVIEW_LENGTH: usize = 160;
+
+/// Text of the first ATX heading (`# Title`) in markdown content
+pub fn first_heading(content: &str) -> Option<String> {
+    content
+        .lines()
+        .map(str::trim)
+        .find(|line| line.starts_with('#'))
+        .map(|line| line.trim_start_matches('#').trim().to_string())
+        .filter(|heading| !heading.is_empty())
+}
+
+/// Plain-text preview of content with the first heading removed and whitespace collapsed
+pub fn content_preview(content: &str, max_chars: usize) -> String {
+    let mut skipped_heading = false;
+    let body: Vec<&str> = content
+        .lines()
+        .filter(|line| {
+            if !skipped_heading && line.trim_start().starts_with('#') {
+                skipped_heading = true;
+                return false;
+            }
+            true
+        })
+        .collect();
+
+    let text = markdown_to_plain_text(&body.join("\n"));
+    let collapsed = text.split_whitespace().collect::<Vec<_>>().join(" ");
+
+    if collapsed.chars().count() <= max_chars {
+        collapsed
+    } else {
+        let truncated: String = collapsed.chars().take(max_chars).collect();
+        format!("{}…", truncated.trim_end())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_first_heading() {
+        assert_eq!(first_heading("\n# Intent\nBody"), Some("Intent".to_string()));
+        assert_eq!(first_heading("Body\n## Sub heading"), Some("Sub heading".to_string()));
+        assert_eq!(first_heading("No heading"), None);
+    }
+
+    #[test]
+    fn test_content_preview_skips_heading_and_markdown() {
+        let content = "# Intent\n\nWe aim to **ship**\n\n- fast\n- well";
+        assert_eq!(content_preview(content, 100), "We aim to ship - fast - well");
+    }
+
+    #[test]
+    fn test_content_preview_truncates() {
+        let preview = content_preview("one two three four", 8);
+        assert_eq!(preview, "one two…");
+    }
+}
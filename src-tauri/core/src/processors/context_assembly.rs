@@ -0,0 +1,75 @@
+use crate::models::Section;
+
+/// Concatenate section content (and any transcluded content) into a single context string
+///
+/// Sections are emitted in document order separated by blank lines. Transcluded
+/// content follows the section that references it, preceded by a source comment.
+pub fn assemble_sections(sections: &[Section]) -> String {
+    let mut parts: Vec<String> = Vec::new();
+
+    for section in sections {
+        let content = section.content.trim();
+        if !content.is_empty() {
+            parts.push(content.to_string());
+        }
+
+        for transclusion in &section.transclusions {
+            parts.push(format!(
+                "<!-- transcluded from {}#{} -->\n{}",
+                transclusion.source_path,
+                transclusion.section_id,
+                transclusion.content.trim()
+            ));
+        }
+    }
+
+    parts.join("\n\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeMap;
+    use crate::models::Transclusion;
+
+    fn section(id: &str, content: &str) -> Section {
+        Section {
+            id: id.to_string(),
+            section_type: "intent".to_string(),
+            content: content.to_string(),
+            ref_target: None,
+            tags: vec![],
+            translations: BTreeMap::new(),
+            children: vec![],
+            budget: None,
+            transclusions: vec![],
+            sensitive: false,
+            encrypted: false,
+        }
+    }
+
+    #[test]
+    fn test_assemble_sections() {
+        let sections = vec![section("a", "# Intent\n"), section("b", "  "), section("c", "Process")];
+
+        assert_eq!(assemble_sections(&sections), "# Intent\n\nProcess");
+    }
+
+    #[test]
+    fn test_assemble_sections_with_transclusions() {
+        let mut intent = section("a", "Intent");
+        intent.transclusions.push(Transclusion {
+            source_path: "other.xml".to_string(),
+            section_id: "shared-1".to_string(),
+            section_type: "process".to_string(),
+            content: "Shared steps".to_string(),
+            depth: 1,
+            read_only: true,
+        });
+
+        assert_eq!(
+            assemble_sections(&[intent]),
+            "Intent\n\n<!-- transcluded from other.xml#shared-1 -->\nShared steps"
+        );
+    }
+}
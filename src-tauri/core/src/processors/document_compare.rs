@@ -0,0 +1,352 @@
+use std::collections::{BTreeSet, HashMap};
+use serde::{Deserialize, Serialize};
+use similar::TextDiff;
+use crate::models::*;
+use crate::parsers::mermaid_parser;
+use super::content_summary::first_heading;
+use super::flow_navigation::flatten;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum DiffStatus {
+    Added,
+    Removed,
+    Modified,
+    Unchanged,
+}
+
+/// A value that differs between the two documents; None where it is missing
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct FieldChange {
+    /// Field name, e.g. `title`, `extra.client`, `goal.due` or a node ID
+    pub key: String,
+    pub before: Option<String>,
+    pub after: Option<String>,
+}
+
+/// One section, matched by ID, in both documents
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SectionComparison {
+    pub id: String,
+    pub status: DiffStatus,
+    /// Type in the second document, else in the first
+    pub section_type: String,
+    /// First heading, else the ID
+    pub title: String,
+    /// Raw content in each document
+    pub before: Option<String>,
+    pub after: Option<String>,
+    /// Attributes other than content that differ: `type`, `tags`, `refTarget`, `parent`, `translations.<lang>`, ...
+    pub changes: Vec<FieldChange>,
+    /// Unified diff of the content; empty when it is the same
+    pub diff: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct FlowComparison {
+    pub changed: bool,
+    pub nodes_added: Vec<String>,
+    pub nodes_removed: Vec<String>,
+    /// Nodes whose label or click target changed, keyed by node ID
+    pub nodes_changed: Vec<FieldChange>,
+    /// Edges as `A --> B` or `A -->|label| B`
+    pub edges_added: Vec<String>,
+    pub edges_removed: Vec<String>,
+    /// Unified diff of the Mermaid code; empty when it is the same
+    pub diff: String,
+}
+
+/// Differences between two documents, for a side-by-side compare screen
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct DocumentComparison {
+    pub identical: bool,
+    pub meta: Vec<FieldChange>,
+    pub variables: Vec<FieldChange>,
+    /// Every section of either document, at any depth, unchanged ones included
+    pub sections: Vec<SectionComparison>,
+    pub flow: FlowComparison,
+}
+
+/// Compare two documents part by part
+///
+/// Sections are matched by ID wherever they are nested and listed in the
+/// second document's order, with removed sections placed after the section
+/// they followed in the first. Content is compared raw, so expects
+/// documents as read, with `${...}` placeholders.
+pub fn compare_documents(before: &ContextDocument, after: &ContextDocument) -> DocumentComparison {
+    let meta = compare_meta(&before.meta, &after.meta);
+    let variables = compare_variables(&before.variables, &after.variables);
+    let sections = compare_sections(&before.sections, &after.sections);
+    let flow = compare_flows(before.flow_graph.as_ref(), after.flow_graph.as_ref());
+
+    let identical = meta.is_empty()
+        && variables.is_empty()
+        && sections.iter().all(|section| section.status == DiffStatus::Unchanged)
+        && !flow.changed
+        && before.references == after.references;
+    DocumentComparison { identical, meta, variables, sections, flow }
+}
+
+fn change(key: impl Into<String>, before: Option<String>, after: Option<String>) -> Option<FieldChange> {
+    (before != after).then(|| FieldChange { key: key.into(), before, after })
+}
+
+fn compare_meta(before: &MetaData, after: &MetaData) -> Vec<FieldChange> {
+    let fields = |meta: &MetaData| {
+        let mut fields = vec![
+            ("title".to_string(), meta.title.clone()),
+            ("author".to_string(), meta.author.clone()),
+            ("created".to_string(), meta.created.clone()),
+            ("description".to_string(), meta.description.clone()),
+            ("tags".to_string(), meta.tags.join(", ")),
+            ("app".to_string(), format!("{} {}", meta.app_info.name, meta.app_info.version)),
+        ];
+        fields.extend(meta.extra.iter().map(|(name, value)| (format!("extra.{}", name), value.clone())));
+        fields
+    };
+    compare_fields(fields(before), fields(after))
+}
+
+fn compare_variables(before: &[Variable], after: &[Variable]) -> Vec<FieldChange> {
+    let fields = |variables: &[Variable]| {
+        variables
+            .iter()
+            .flat_map(|variable| {
+                let mut fields = vec![(variable.name.clone(), variable.value.clone())];
+                fields.extend(variable.description.clone().map(|description| (format!("{}.description", variable.name), description)));
+                fields.extend(variable.due.clone().map(|due| (format!("{}.due", variable.name), due)));
+                fields
+            })
+            .collect()
+    };
+    compare_fields(fields(before), fields(after))
+}
+
+/// Changes between two lists of named values, in the order they first appear
+fn compare_fields(before: Vec<(String, String)>, after: Vec<(String, String)>) -> Vec<FieldChange> {
+    let before_values: HashMap<&str, &str> = before.iter().map(|(key, value)| (key.as_str(), value.as_str())).collect();
+    let after_values: HashMap<&str, &str> = after.iter().map(|(key, value)| (key.as_str(), value.as_str())).collect();
+    let mut seen = BTreeSet::new();
+    before
+        .iter()
+        .chain(&after)
+        .filter(|(key, _)| seen.insert(key.as_str()))
+        .filter_map(|(key, _)| {
+            let value = |values: &HashMap<&str, &str>| values.get(key.as_str()).map(|value| value.to_string());
+            change(key.as_str(), value(&before_values), value(&after_values))
+        })
+        .collect()
+}
+
+fn compare_sections(before: &[Section], after: &[Section]) -> Vec<SectionComparison> {
+    let before_sections = flatten(before);
+    let after_sections = flatten(after);
+    let before_by_id: HashMap<&str, &Section> = before_sections.iter().map(|section| (section.id.as_str(), *section)).collect();
+    let after_by_id: HashMap<&str, &Section> = after_sections.iter().map(|section| (section.id.as_str(), *section)).collect();
+    let before_parents = parents(before, None);
+    let after_parents = parents(after, None);
+
+    let mut order: Vec<&str> = after_sections.iter().map(|section| section.id.as_str()).collect();
+    for (index, section) in before_sections.iter().enumerate() {
+        if after_by_id.contains_key(section.id.as_str()) {
+            continue;
+        }
+        let position = before_sections[..index]
+            .iter()
+            .rev()
+            .find_map(|previous| order.iter().position(|id| *id == previous.id))
+            .map_or(0, |position| position + 1);
+        order.insert(position, &section.id);
+    }
+
+    order
+        .into_iter()
+        .map(|id| {
+            let old = before_by_id.get(id).copied();
+            let new = after_by_id.get(id).copied();
+            let changes = match (old, new) {
+                (Some(old), Some(new)) => {
+                    let mut changes: Vec<FieldChange> = [
+                        change("type", Some(old.section_type.clone()), Some(new.section_type.clone())),
+                        change("tags", Some(old.tags.join(", ")), Some(new.tags.join(", "))),
+                        change("refTarget", old.ref_target.clone(), new.ref_target.clone()),
+                        change("parent", before_parents[id].clone(), after_parents[id].clone()),
+                        change("sensitive", Some(old.sensitive.to_string()), Some(new.sensitive.to_string())),
+                        change("encrypted", Some(old.encrypted.to_string()), Some(new.encrypted.to_string())),
+                        change("budget", old.budget.map(|budget| budget.to_string()), new.budget.map(|budget| budget.to_string())),
+                    ]
+                    .into_iter()
+                    .flatten()
+                    .collect();
+                    let languages: BTreeSet<&String> = old.translations.keys().chain(new.translations.keys()).collect();
+                    changes.extend(languages.into_iter().filter_map(|language| {
+                        change(format!("translations.{}", language), old.translations.get(language).cloned(), new.translations.get(language).cloned())
+                    }));
+                    changes
+                }
+                _ => Vec::new(),
+            };
+            let status = match (old, new) {
+                (None, _) => DiffStatus::Added,
+                (_, None) => DiffStatus::Removed,
+                (Some(old), Some(new)) if old.content != new.content || !changes.is_empty() => DiffStatus::Modified,
+                _ => DiffStatus::Unchanged,
+            };
+            let before_content = old.map(|section| section.content.clone());
+            let after_content = new.map(|section| section.content.clone());
+            let shown = new.or(old).expect("section is in one of the documents");
+            SectionComparison {
+                id: id.to_string(),
+                status,
+                section_type: shown.section_type.clone(),
+                title: first_heading(&shown.content).unwrap_or_else(|| id.to_string()),
+                diff: text_diff(before_content.as_deref().unwrap_or_default(), after_content.as_deref().unwrap_or_default()),
+                before: before_content,
+                after: after_content,
+                changes,
+            }
+        })
+        .collect()
+}
+
+/// Parent ID of every section, None at the top level
+fn parents<'a>(sections: &'a [Section], parent: Option<&str>) -> HashMap<&'a str, Option<String>> {
+    let mut parents = HashMap::new();
+    for section in sections {
+        parents.insert(section.id.as_str(), parent.map(str::to_string));
+        parents.extend(self::parents(&section.children, Some(&section.id)));
+    }
+    parents
+}
+
+fn compare_flows(before: Option<&FlowGraph>, after: Option<&FlowGraph>) -> FlowComparison {
+    let code = |flow: Option<&FlowGraph>| flow.map(|flow| flow.mermaid_code.clone()).unwrap_or_default();
+    let (before_code, after_code) = (code(before), code(after));
+    if before_code == after_code {
+        return FlowComparison::default();
+    }
+
+    // Enriched, so nodes carry the sections they click through to; a diagram that does not parse has no nodes
+    let graph = |flow: Option<&FlowGraph>| {
+        let mut flow = flow.cloned()?;
+        mermaid_parser::enrich_flow_graph(&mut flow).ok()?;
+        Some(flow.parsed_graph)
+    };
+    let empty = || GraphStructure { nodes: vec![], edges: vec![], subgraphs: vec![] };
+    let (before_graph, after_graph) = (graph(before).unwrap_or_else(empty), graph(after).unwrap_or_else(empty));
+    let node_text = |node: &GraphNode| match &node.ref_section_id {
+        Some(section_id) => format!("{} (#{})", node.label, section_id),
+        None => node.label.clone(),
+    };
+    let before_nodes: HashMap<&str, String> = before_graph.nodes.iter().map(|node| (node.id.as_str(), node_text(node))).collect();
+    let after_nodes: HashMap<&str, String> = after_graph.nodes.iter().map(|node| (node.id.as_str(), node_text(node))).collect();
+    let edge_text = |edge: &GraphEdge| match &edge.label {
+        Some(label) => format!("{} -->|{}| {}", edge.from, label, edge.to),
+        None => format!("{} --> {}", edge.from, edge.to),
+    };
+    let before_edges: Vec<String> = before_graph.edges.iter().map(edge_text).collect();
+    let after_edges: Vec<String> = after_graph.edges.iter().map(edge_text).collect();
+
+    FlowComparison {
+        changed: true,
+        nodes_added: after_graph.nodes.iter().filter(|node| !before_nodes.contains_key(node.id.as_str())).map(|node| node.id.clone()).collect(),
+        nodes_removed: before_graph.nodes.iter().filter(|node| !after_nodes.contains_key(node.id.as_str())).map(|node| node.id.clone()).collect(),
+        nodes_changed: after_graph
+            .nodes
+            .iter()
+            .filter_map(|node| {
+                let before = before_nodes.get(node.id.as_str())?;
+                change(node.id.clone(), Some(before.clone()), Some(after_nodes[node.id.as_str()].clone()))
+            })
+            .collect(),
+        edges_added: after_edges.iter().filter(|edge| !before_edges.contains(edge)).cloned().collect(),
+        edges_removed: before_edges.iter().filter(|edge| !after_edges.contains(edge)).cloned().collect(),
+        diff: text_diff(&before_code, &after_code),
+    }
+}
+
+fn text_diff(before: &str, after: &str) -> String {
+    if before == after {
+        return String::new();
+    }
+    TextDiff::from_lines(before, after).unified_diff().header("before", "after").to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn document() -> ContextDocument {
+        let mut process = Section::new("process-1", "process", "# Process\n\n1. Build");
+        process.children.push(Section::new("process-2", "process", "# Test"));
+        ContextDocument::builder()
+            .title("Plan")
+            .author("Dana")
+            .variable("goal", "Ship v1")
+            .section("intent-1", "intent", "# Intent\nGoal: ${goal}")
+            .add_section(process)
+            .section("evaluation-1", "evaluation", "# Evaluation")
+            .flow("flowchart TD\n  A[Intent] --> B[Process]\n  click A \"#intent-1\"")
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_identical_documents() {
+        let comparison = compare_documents(&document(), &document());
+        assert!(comparison.identical);
+        assert_eq!(comparison.sections.len(), 4);
+        assert!(comparison.sections.iter().all(|section| section.diff.is_empty()));
+        assert_eq!(comparison.flow, FlowComparison::default());
+    }
+
+    #[test]
+    fn test_compare_each_part() {
+        let before = document();
+        let mut after = document();
+        after.meta.title = "Launch plan".to_string();
+        after.meta.extra.insert("client".to_string(), "Acme".to_string());
+        after.variables[0].value = "Ship v2".to_string();
+        after.sections[0].content = "# Intent\nGoal: ${goal}\nBy Friday".to_string();
+        after.sections[1].children.clear();
+        after.sections.remove(2);
+        after.sections.insert(1, Section::new("alternatives-1", "alternatives", "# Options"));
+        after.sections[2].tags.push("draft".to_string());
+        after.flow_graph.as_mut().unwrap().mermaid_code = "flowchart TD\n  A[Goal] --> C[Review]\n  click A \"#intent-1\"".to_string();
+
+        let comparison = compare_documents(&before, &after);
+        assert!(!comparison.identical);
+        assert_eq!(comparison.meta.len(), 2);
+        assert_eq!(comparison.meta[0], FieldChange { key: "title".to_string(), before: Some("Plan".to_string()), after: Some("Launch plan".to_string()) });
+        assert_eq!(comparison.meta[1].key, "extra.client");
+        assert_eq!(comparison.variables[0].after.as_deref(), Some("Ship v2"));
+
+        let summary: Vec<_> = comparison.sections.iter().map(|section| (section.id.as_str(), section.status)).collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("intent-1", DiffStatus::Modified),
+                ("alternatives-1", DiffStatus::Added),
+                ("process-1", DiffStatus::Modified),
+                ("process-2", DiffStatus::Removed),
+                ("evaluation-1", DiffStatus::Removed),
+            ]
+        );
+        assert!(comparison.sections[0].diff.contains("\n+By Friday\n"));
+        assert_eq!(comparison.sections[2].changes[0].key, "tags");
+        assert_eq!(comparison.sections[3].title, "Test");
+        assert_eq!(comparison.sections[3].after, None);
+
+        let flow = &comparison.flow;
+        assert_eq!(flow.nodes_added, vec!["C"]);
+        assert_eq!(flow.nodes_removed, vec!["B"]);
+        assert_eq!(flow.nodes_changed[0].after.as_deref(), Some("Goal (#intent-1)"));
+        assert_eq!(flow.edges_added, vec!["A --> C"]);
+        assert_eq!(flow.edges_removed, vec!["A --> B"]);
+        assert!(flow.diff.contains("-  A[Intent] --> B[Process]"));
+    }
+}
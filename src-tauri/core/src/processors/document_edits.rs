@@ -0,0 +1,351 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+use crate::error::{ContextError, Result};
+use crate::models::*;
+
+/// One operation of an `apply_edits` batch
+///
+/// Serialized with an `op` tag, e.g. `{"op": "delete_section", "id": "notes-1"}`.
+/// Sections are addressed by ID anywhere in the tree; `parent_id` places a
+/// section among the children of another one instead of at the top level.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum DocumentEdit {
+    /// Insert a new section at `index` (appended when missing)
+    CreateSection {
+        section: Section,
+        #[serde(default)]
+        parent_id: Option<String>,
+        #[serde(default)]
+        index: Option<usize>,
+    },
+    /// Change the given fields of a section; omitted fields are kept
+    UpdateSection {
+        id: String,
+        #[serde(default)]
+        section_type: Option<String>,
+        #[serde(default)]
+        content: Option<String>,
+        /// An empty string removes the refTarget
+        #[serde(default)]
+        ref_target: Option<String>,
+        #[serde(default)]
+        tags: Option<Vec<String>>,
+    },
+    /// Move a section (with its children) under `parent_id`, or to the top level
+    MoveSection {
+        id: String,
+        #[serde(default)]
+        parent_id: Option<String>,
+        #[serde(default)]
+        index: Option<usize>,
+    },
+    DeleteSection { id: String },
+    /// Add a variable or change its value
+    SetVariable { name: String, value: String },
+    DeleteVariable { name: String },
+    /// Change the given metadata fields; `None` values in `extra` remove custom fields
+    UpdateMeta {
+        #[serde(default)]
+        title: Option<String>,
+        #[serde(default)]
+        author: Option<String>,
+        #[serde(default)]
+        description: Option<String>,
+        #[serde(default)]
+        tags: Option<Vec<String>>,
+        #[serde(default)]
+        extra: BTreeMap<String, Option<String>>,
+    },
+}
+
+impl DocumentEdit {
+    /// Short description such as `delete_section notes-1`, for the change journal
+    pub fn describe(&self) -> String {
+        match self {
+            DocumentEdit::CreateSection { section, .. } => format!("create_section {}", section.id),
+            DocumentEdit::UpdateSection { id, .. } => format!("update_section {}", id),
+            DocumentEdit::MoveSection { id, .. } => format!("move_section {}", id),
+            DocumentEdit::DeleteSection { id } => format!("delete_section {}", id),
+            DocumentEdit::SetVariable { name, .. } => format!("set_variable {}", name),
+            DocumentEdit::DeleteVariable { name } => format!("delete_variable {}", name),
+            DocumentEdit::UpdateMeta { .. } => "update_meta".to_string(),
+        }
+    }
+}
+
+/// Apply a batch of edits in order
+///
+/// Stops at the first edit that fails, leaving `doc` partially edited; callers
+/// run this on a copy (see `document_store::update`) so a failed batch changes
+/// nothing. Errors name the position of the failing edit.
+pub fn apply_edits(doc: &mut ContextDocument, edits: &[DocumentEdit]) -> Result<()> {
+    for (index, edit) in edits.iter().enumerate() {
+        apply_edit(doc, edit).map_err(|e| ContextError::InvalidArgument(format!("Edit {} failed: {}", index + 1, e)))?;
+    }
+    Ok(())
+}
+
+fn apply_edit(doc: &mut ContextDocument, edit: &DocumentEdit) -> Result<()> {
+    match edit {
+        DocumentEdit::CreateSection { section, parent_id, index } => {
+            if find_section(&doc.sections, &section.id).is_some() {
+                return Err(ContextError::InvalidArgument(format!("Section '{}' already exists", section.id)));
+            }
+            insert_section(&mut doc.sections, section.clone(), parent_id.as_deref(), *index)
+        }
+        DocumentEdit::UpdateSection { id, section_type, content, ref_target, tags } => {
+            let section = find_section_mut(&mut doc.sections, id).ok_or_else(|| ContextError::SectionNotFound(id.clone()))?;
+            if let Some(section_type) = section_type {
+                section.section_type = section_type.clone();
+            }
+            if let Some(content) = content {
+                ensure_not_encrypted(section)?;
+                section.content = content.clone();
+            }
+            if let Some(ref_target) = ref_target {
+                section.ref_target = Some(ref_target.clone()).filter(|target| !target.trim().is_empty());
+            }
+            if let Some(tags) = tags {
+                section.tags = tags.clone();
+            }
+            Ok(())
+        }
+        DocumentEdit::MoveSection { id, parent_id, index } => {
+            if parent_id.as_deref().is_some_and(|parent| parent == id || find_in_subtree(&doc.sections, id, parent)) {
+                return Err(ContextError::InvalidArgument(format!("Cannot move section '{}' into itself", id)));
+            }
+            let section = remove_section(&mut doc.sections, id).ok_or_else(|| ContextError::SectionNotFound(id.clone()))?;
+            insert_section(&mut doc.sections, section, parent_id.as_deref(), *index)
+        }
+        DocumentEdit::DeleteSection { id } => {
+            remove_section(&mut doc.sections, id).ok_or_else(|| ContextError::SectionNotFound(id.clone()))?;
+            Ok(())
+        }
+        DocumentEdit::SetVariable { name, value } => {
+            match doc.variables.iter_mut().find(|v| &v.name == name) {
+                Some(variable) => variable.value = value.clone(),
+                None => doc.variables.push(Variable { name: name.clone(), value: value.clone(), description: None, sensitive: false, resolve_in: vec![], due: None }),
+            }
+            Ok(())
+        }
+        DocumentEdit::DeleteVariable { name } => {
+            let position = doc
+                .variables
+                .iter()
+                .position(|v| &v.name == name)
+                .ok_or_else(|| ContextError::InvalidArgument(format!("Variable '{}' not found", name)))?;
+            doc.variables.remove(position);
+            Ok(())
+        }
+        DocumentEdit::UpdateMeta { title, author, description, tags, extra } => {
+            if let Some(title) = title {
+                doc.meta.title = title.clone();
+            }
+            if let Some(author) = author {
+                doc.meta.author = author.clone();
+            }
+            if let Some(description) = description {
+                doc.meta.description = description.clone();
+            }
+            if let Some(tags) = tags {
+                doc.meta.tags = tags.clone();
+            }
+            for (name, value) in extra {
+                match value {
+                    Some(value) => doc.meta.extra.insert(name.clone(), value.clone()),
+                    None => doc.meta.extra.remove(name),
+                };
+            }
+            Ok(())
+        }
+    }
+}
+
+/// Overwrite the stored section with the same ID, at any depth, with `section`
+///
+/// Every field the section itself carries is replaced. Its children stay as
+/// stored, since they are sections of their own, and so do transclusions,
+/// which are only filled in on load. Encrypted sections are refused, as their
+/// loaded content is a placeholder rather than the ciphertext. Returns the
+/// section as now stored.
+pub fn replace_section(doc: &mut ContextDocument, section: &Section) -> Result<Section> {
+    let stored = find_section_mut(&mut doc.sections, &section.id).ok_or_else(|| ContextError::SectionNotFound(section.id.clone()))?;
+    ensure_not_encrypted(stored)?;
+    ensure_not_encrypted(section)?;
+    *stored = Section {
+        children: std::mem::take(&mut stored.children),
+        transclusions: std::mem::take(&mut stored.transclusions),
+        ..section.clone()
+    };
+    Ok(stored.clone())
+}
+
+/// Refuse to change the content of an encrypted section
+///
+/// Its stored content is the ciphertext envelope (or, once loaded, a
+/// placeholder), so any edit to it would make the section undecryptable.
+pub(crate) fn ensure_not_encrypted(section: &Section) -> Result<()> {
+    if section.encrypted {
+        return Err(ContextError::InvalidArgument(format!(
+            "Section '{}' is encrypted; decrypt it before editing its content",
+            section.id
+        )));
+    }
+    Ok(())
+}
+
+pub(crate) fn find_section<'a>(sections: &'a [Section], id: &str) -> Option<&'a Section> {
+    sections
+        .iter()
+        .find_map(|section| if section.id == id { Some(section) } else { find_section(&section.children, id) })
+}
+
+pub(crate) fn find_section_mut<'a>(sections: &'a mut [Section], id: &str) -> Option<&'a mut Section> {
+    for section in sections {
+        if section.id == id {
+            return Some(section);
+        }
+        if let Some(found) = find_section_mut(&mut section.children, id) {
+            return Some(found);
+        }
+    }
+    None
+}
+
+/// Add the IDs of the sections and all their descendants to `ids`
+pub(crate) fn collect_section_ids(sections: &[Section], ids: &mut HashSet<String>) {
+    for section in sections {
+        ids.insert(section.id.clone());
+        collect_section_ids(&section.children, ids);
+    }
+}
+
+/// Whether `descendant_id` is nested somewhere below section `id`
+fn find_in_subtree(sections: &[Section], id: &str, descendant_id: &str) -> bool {
+    find_section(sections, id).is_some_and(|section| find_section(&section.children, descendant_id).is_some())
+}
+
+pub(crate) fn remove_section(sections: &mut Vec<Section>, id: &str) -> Option<Section> {
+    if let Some(position) = sections.iter().position(|s| s.id == id) {
+        return Some(sections.remove(position));
+    }
+    sections.iter_mut().find_map(|section| remove_section(&mut section.children, id))
+}
+
+fn insert_section(sections: &mut Vec<Section>, section: Section, parent_id: Option<&str>, index: Option<usize>) -> Result<()> {
+    let siblings = match parent_id {
+        Some(parent_id) => {
+            &mut find_section_mut(sections, parent_id)
+                .ok_or_else(|| ContextError::SectionNotFound(parent_id.to_string()))?
+                .children
+        }
+        None => sections,
+    };
+    let index = index.unwrap_or(siblings.len()).min(siblings.len());
+    siblings.insert(index, section);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn document() -> ContextDocument {
+        ContextDocument::builder()
+            .title("Plan")
+            .variable("goal", "Ship v1")
+            .section("intent-1", "intent", "# Intent")
+            .section("process-1", "process", "# Process")
+            .build()
+            .unwrap()
+    }
+
+    fn ids(sections: &[Section]) -> Vec<&str> {
+        sections.iter().map(|s| s.id.as_str()).collect()
+    }
+
+    #[test]
+    fn test_section_edits() {
+        let mut doc = document();
+        let edits: Vec<DocumentEdit> = serde_json::from_str(
+            r##"[
+                {"op": "create_section", "section": {"id": "eval-1", "type": "evaluation", "content": "# Eval"}, "index": 1},
+                {"op": "update_section", "id": "intent-1", "content": "# Goal", "tags": ["core"]},
+                {"op": "move_section", "id": "process-1", "parent_id": "eval-1"},
+                {"op": "delete_section", "id": "intent-1"}
+            ]"##,
+        )
+        .unwrap();
+
+        apply_edits(&mut doc, &edits).unwrap();
+
+        assert_eq!(ids(&doc.sections), vec!["eval-1"]);
+        assert_eq!(ids(&doc.sections[0].children), vec!["process-1"]);
+    }
+
+    #[test]
+    fn test_variable_and_meta_edits() {
+        let mut doc = document();
+        doc.meta.extra.insert("stage".to_string(), "draft".to_string());
+        let edits = vec![
+            DocumentEdit::SetVariable { name: "goal".to_string(), value: "Ship v2".to_string() },
+            DocumentEdit::SetVariable { name: "owner".to_string(), value: "Sam".to_string() },
+            DocumentEdit::UpdateMeta {
+                title: Some("Roadmap".to_string()),
+                author: None,
+                description: None,
+                tags: None,
+                extra: [("stage".to_string(), None), ("team".to_string(), Some("Core".to_string()))].into(),
+            },
+        ];
+
+        apply_edits(&mut doc, &edits).unwrap();
+
+        assert_eq!(doc.variables[0].value, "Ship v2");
+        assert_eq!(doc.variables[1].name, "owner");
+        assert_eq!(doc.meta.title, "Roadmap");
+        assert_eq!(doc.meta.extra.keys().collect::<Vec<_>>(), vec!["team"]);
+    }
+
+    #[test]
+    fn test_replace_section_keeps_children() {
+        let mut doc = document();
+        doc.sections[1].children.push(Section::new("process-2", "process", "# Step"));
+        let mut section = Section::new("process-1", "process", "# Steps");
+        section.tags = vec!["core".to_string()];
+
+        let stored = replace_section(&mut doc, &section).unwrap();
+
+        assert_eq!(stored.content, "# Steps");
+        assert_eq!(stored.tags, vec!["core"]);
+        assert_eq!(ids(&doc.sections[1].children), vec!["process-2"]);
+        assert_eq!(doc.sections[0].content, "# Intent");
+
+        doc.sections[0].encrypted = true;
+        assert!(matches!(
+            replace_section(&mut doc, &Section::new("intent-1", "intent", "Plain")),
+            Err(ContextError::InvalidArgument(_))
+        ));
+        assert!(matches!(
+            replace_section(&mut doc, &Section::new("nope", "intent", "")),
+            Err(ContextError::SectionNotFound(_))
+        ));
+    }
+
+    #[test]
+    fn test_failing_edit_is_reported_with_position() {
+        let mut doc = document();
+        let edits = vec![
+            DocumentEdit::DeleteSection { id: "intent-1".to_string() },
+            DocumentEdit::MoveSection { id: "process-1".to_string(), parent_id: Some("process-1".to_string()), index: None },
+        ];
+
+        let error = apply_edits(&mut doc, &edits).unwrap_err();
+        assert!(error.to_string().contains("Edit 2 failed"), "{}", error);
+        assert!(matches!(
+            apply_edits(&mut document(), &[DocumentEdit::DeleteVariable { name: "nope".to_string() }]),
+            Err(ContextError::InvalidArgument(_))
+        ));
+    }
+}
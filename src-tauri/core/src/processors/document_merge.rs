@@ -0,0 +1,282 @@
+use std::collections::{HashMap, HashSet};
+use serde::{Deserialize, Serialize};
+use crate::models::*;
+
+/// Which version wins a conflict
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum MergeSide {
+    #[default]
+    Ours,
+    Theirs,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ConflictKind {
+    Meta,
+    Variable,
+    Section,
+    Reference,
+    Flow,
+}
+
+/// A part both versions changed differently
+///
+/// Texts are the part's main content (section content, variable value, ...);
+/// None means the part does not exist in that version.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct MergeConflict {
+    /// `kind:key`, e.g. `section:intent-1`; used to pick a side
+    pub id: String,
+    pub kind: ConflictKind,
+    pub key: String,
+    pub base: Option<String>,
+    pub ours: Option<String>,
+    pub theirs: Option<String>,
+    pub resolution: MergeSide,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct MergeResult {
+    pub document: ContextDocument,
+    pub conflicts: Vec<MergeConflict>,
+}
+
+/// Merge two versions of a document part by part
+///
+/// Meta, each variable, section, reference and the flow are merged
+/// separately: a part changed on one side only takes that change, and a part
+/// changed on both sides becomes a conflict resolved by `resolutions`
+/// (keyed by conflict ID, defaulting to ours). Without a `base` there is no
+/// way to tell an addition from a deletion, so parts missing on one side are
+/// kept, and every part that differs is a conflict.
+pub fn merge_documents(
+    base: Option<&ContextDocument>,
+    ours: &ContextDocument,
+    theirs: &ContextDocument,
+    resolutions: &HashMap<String, MergeSide>,
+) -> MergeResult {
+    let mut merger = Merger { resolutions, conflicts: Vec::new() };
+
+    let meta = merger.merge_value(
+        ConflictKind::Meta,
+        "meta",
+        base.map(|b| Some(&b.meta)),
+        Some(&ours.meta),
+        Some(&theirs.meta),
+        |m| m.title.clone(),
+    );
+    let variables = merger.merge_keyed(
+        ConflictKind::Variable,
+        base.map(|b| b.variables.as_slice()),
+        &ours.variables,
+        &theirs.variables,
+        |v| &v.name,
+        |v| v.value.clone(),
+    );
+    let sections = merger.merge_keyed(
+        ConflictKind::Section,
+        base.map(|b| b.sections.as_slice()),
+        &ours.sections,
+        &theirs.sections,
+        |s| &s.id,
+        |s| s.content.clone(),
+    );
+    let references = merger.merge_keyed(
+        ConflictKind::Reference,
+        base.map(|b| b.references.as_slice()),
+        &ours.references,
+        &theirs.references,
+        |r| &r.id,
+        |r| r.title.clone(),
+    );
+    let flow_graph = merger.merge_value(
+        ConflictKind::Flow,
+        "flow",
+        base.map(|b| b.flow_graph.as_ref()),
+        ours.flow_graph.as_ref(),
+        theirs.flow_graph.as_ref(),
+        |f| f.mermaid_code.clone(),
+    );
+
+    MergeResult {
+        document: ContextDocument {
+            meta: meta.unwrap_or_else(|| ours.meta.clone()),
+            variables,
+            sections,
+            references,
+            flow_graph,
+        },
+        conflicts: merger.conflicts,
+    }
+}
+
+struct Merger<'a> {
+    resolutions: &'a HashMap<String, MergeSide>,
+    conflicts: Vec<MergeConflict>,
+}
+
+impl Merger<'_> {
+    /// `base` is None when there is no base document, Some(None) when the base lacks the part
+    fn merge_value<T: Clone + PartialEq>(
+        &mut self,
+        kind: ConflictKind,
+        key: &str,
+        base: Option<Option<&T>>,
+        ours: Option<&T>,
+        theirs: Option<&T>,
+        describe: impl Fn(&T) -> String,
+    ) -> Option<T> {
+        if ours == theirs {
+            return ours.cloned();
+        }
+        match base {
+            Some(base) if base == ours => return theirs.cloned(),
+            Some(base) if base == theirs => return ours.cloned(),
+            None if ours.is_none() => return theirs.cloned(),
+            None if theirs.is_none() => return ours.cloned(),
+            _ => {}
+        }
+
+        let id = format!("{}:{}", kind_name(kind), key);
+        let resolution = self.resolutions.get(&id).copied().unwrap_or_default();
+        self.conflicts.push(MergeConflict {
+            id,
+            kind,
+            key: key.to_string(),
+            base: base.flatten().map(&describe),
+            ours: ours.map(&describe),
+            theirs: theirs.map(&describe),
+            resolution,
+        });
+        match resolution {
+            MergeSide::Ours => ours.cloned(),
+            MergeSide::Theirs => theirs.cloned(),
+        }
+    }
+
+    /// Merge lists of keyed items, keeping our order and slotting in their new items after their predecessor
+    fn merge_keyed<T: Clone + PartialEq>(
+        &mut self,
+        kind: ConflictKind,
+        base: Option<&[T]>,
+        ours: &[T],
+        theirs: &[T],
+        key: impl Fn(&T) -> &String,
+        describe: impl Fn(&T) -> String,
+    ) -> Vec<T> {
+        let find = |items: &[T], k: &str| items.iter().find(|item| key(item) == k).cloned();
+
+        let mut order: Vec<String> = ours.iter().map(|item| key(item).clone()).collect();
+        let mut seen: HashSet<String> = order.iter().cloned().collect();
+        for (index, item) in theirs.iter().enumerate() {
+            let k = key(item);
+            if seen.insert(k.clone()) {
+                let position = theirs[..index]
+                    .iter()
+                    .rev()
+                    .find_map(|prev| order.iter().position(|o| o == key(prev)))
+                    .map_or(0, |p| p + 1);
+                order.insert(position, k.clone());
+            }
+        }
+
+        order
+            .iter()
+            .filter_map(|k| {
+                let base_item = base.map(|b| find(b, k));
+                self.merge_value(
+                    kind,
+                    k,
+                    base_item.as_ref().map(Option::as_ref),
+                    find(ours, k).as_ref(),
+                    find(theirs, k).as_ref(),
+                    &describe,
+                )
+            })
+            .collect()
+    }
+}
+
+fn kind_name(kind: ConflictKind) -> &'static str {
+    match kind {
+        ConflictKind::Meta => "meta",
+        ConflictKind::Variable => "variable",
+        ConflictKind::Section => "section",
+        ConflictKind::Reference => "reference",
+        ConflictKind::Flow => "flow",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn base() -> ContextDocument {
+        ContextDocument::builder()
+            .title("Plan")
+            .variable("goal", "Ship v1")
+            .section("intent-1", "intent", "Intent")
+            .section("process-1", "process", "Process")
+            .section("evaluation-1", "evaluation", "Evaluate")
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_three_way_takes_one_sided_changes() {
+        let base = base();
+        let mut ours = base.clone();
+        ours.sections[0].content = "Our intent".to_string();
+        ours.sections.remove(2);
+        let mut theirs = base.clone();
+        theirs.sections[1].content = "Their process".to_string();
+        theirs.variables[0].value = "Ship v2".to_string();
+        theirs.sections.insert(2, Section::new("process-2", "process", "New step"));
+
+        let result = merge_documents(Some(&base), &ours, &theirs, &HashMap::new());
+
+        assert!(result.conflicts.is_empty());
+        let ids: Vec<&str> = result.document.sections.iter().map(|s| s.id.as_str()).collect();
+        assert_eq!(ids, vec!["intent-1", "process-1", "process-2"]);
+        assert_eq!(result.document.sections[0].content, "Our intent");
+        assert_eq!(result.document.sections[1].content, "Their process");
+        assert_eq!(result.document.variables[0].value, "Ship v2");
+    }
+
+    #[test]
+    fn test_conflicts_follow_resolutions() {
+        let base = base();
+        let mut ours = base.clone();
+        ours.sections[0].content = "Ours".to_string();
+        let mut theirs = base.clone();
+        theirs.sections[0].content = "Theirs".to_string();
+
+        let result = merge_documents(Some(&base), &ours, &theirs, &HashMap::new());
+        assert_eq!(result.conflicts.len(), 1);
+        assert_eq!(result.conflicts[0].id, "section:intent-1");
+        assert_eq!(result.conflicts[0].base.as_deref(), Some("Intent"));
+        assert_eq!(result.document.sections[0].content, "Ours");
+
+        let resolutions = HashMap::from([("section:intent-1".to_string(), MergeSide::Theirs)]);
+        let result = merge_documents(Some(&base), &ours, &theirs, &resolutions);
+        assert_eq!(result.document.sections[0].content, "Theirs");
+        assert_eq!(result.conflicts[0].resolution, MergeSide::Theirs);
+    }
+
+    #[test]
+    fn test_two_way_keeps_parts_from_both_sides() {
+        let mut ours = base();
+        ours.sections.remove(2);
+        ours.sections[0].content = "Ours".to_string();
+        let theirs = base();
+
+        let result = merge_documents(None, &ours, &theirs, &HashMap::new());
+
+        assert_eq!(result.document.sections.len(), 3);
+        assert_eq!(result.conflicts.len(), 1);
+        assert_eq!(result.conflicts[0].ours.as_deref(), Some("Ours"));
+    }
+}
@@ -0,0 +1,54 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use crate::models::*;
+
+/// Size of a document's content, for tracking progress over time
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct DocumentStats {
+    pub section_count: usize,
+    pub word_count: usize,
+    /// Estimated LLM tokens (see `estimate_tokens`)
+    pub token_count: usize,
+    /// Section type -> words in sections of that type
+    pub words_by_type: BTreeMap<String, usize>,
+}
+
+/// Count sections, words and tokens across the whole section tree
+///
+/// Pass resolved sections to count what ends up in the prompt.
+pub fn compute_stats(sections: &[Section]) -> DocumentStats {
+    let mut stats = DocumentStats::default();
+    add_sections(sections, &mut stats);
+    stats
+}
+
+fn add_sections(sections: &[Section], stats: &mut DocumentStats) {
+    for section in sections {
+        let words = section.content.split_whitespace().count();
+        stats.section_count += 1;
+        stats.word_count += words;
+        stats.token_count += estimate_tokens(&section.content);
+        *stats.words_by_type.entry(section.section_type.clone()).or_default() += words;
+        add_sections(&section.children, stats);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compute_stats() {
+        let mut process = Section::new("process-1", "process", "one two three");
+        process.children.push(Section::new("process-2", "process", "four five"));
+        let sections = vec![Section::new("intent-1", "intent", "Ship it"), process];
+
+        let stats = compute_stats(&sections);
+
+        assert_eq!(stats.section_count, 3);
+        assert_eq!(stats.word_count, 7);
+        assert_eq!(stats.words_by_type["process"], 5);
+        assert_eq!(stats.words_by_type["intent"], 2);
+        assert_eq!(stats.token_count, 2 + 4 + 3);
+    }
+}
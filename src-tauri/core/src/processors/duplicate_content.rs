@@ -0,0 +1,214 @@
+use serde::{Deserialize, Serialize};
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap, HashSet};
+use std::hash::{Hash, Hasher};
+use crate::models::*;
+use crate::processors::content_summary::{content_preview, DEFAULT_PREVIEW_LENGTH};
+use crate::processors::flow_navigation::flatten;
+
+/// Default share of shared word shingles for two paragraphs to count as duplicates
+pub const DEFAULT_DUPLICATE_THRESHOLD: f64 = 0.8;
+
+/// Words per shingle; shorter paragraphs are not compared
+const SHINGLE_SIZE: usize = 3;
+
+/// MinHash signature length, split into `BANDS` bands for candidate lookup
+const SIGNATURE_LENGTH: usize = 64;
+const BANDS: usize = 16;
+
+/// A paragraph of a section's content
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ParagraphRef {
+    pub section_id: String,
+    /// Position among the section's paragraphs, from 0
+    pub paragraph_index: usize,
+    pub preview: String,
+}
+
+/// Two paragraphs with (nearly) the same wording
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct DuplicateParagraphs {
+    pub first: ParagraphRef,
+    pub second: ParagraphRef,
+    /// Jaccard similarity of the paragraphs' word shingles, between 0 and 1
+    pub similarity: f64,
+}
+
+struct Paragraph {
+    section_id: String,
+    index: usize,
+    text: String,
+    shingles: HashSet<u64>,
+}
+
+/// Find near-duplicate paragraphs across all sections (nested ones included)
+///
+/// Paragraphs are compared as sets of word shingles, ignoring case and
+/// punctuation. MinHash signatures with banding pick the candidate pairs, and
+/// each candidate is then checked against `threshold` with its exact Jaccard
+/// similarity, so large documents are not compared pair by pair. Banding is
+/// tuned for thresholds of about 0.5 and above; lower ones may miss pairs.
+/// Pairs are sorted by similarity, most similar first.
+pub fn find_duplicate_paragraphs(sections: &[Section], threshold: f64) -> Vec<DuplicateParagraphs> {
+    let paragraphs: Vec<Paragraph> = flatten(sections)
+        .into_iter()
+        .flat_map(|section| {
+            paragraphs(&section.content)
+                .into_iter()
+                .enumerate()
+                .map(|(index, text)| Paragraph {
+                    section_id: section.id.clone(),
+                    index,
+                    shingles: shingles(&text),
+                    text,
+                })
+                .collect::<Vec<_>>()
+        })
+        .filter(|paragraph| !paragraph.shingles.is_empty())
+        .collect();
+
+    let rows = SIGNATURE_LENGTH / BANDS;
+    let mut buckets: HashMap<(usize, &[u64]), Vec<usize>> = HashMap::new();
+    let signatures: Vec<Vec<u64>> = paragraphs.iter().map(|paragraph| signature(&paragraph.shingles)).collect();
+    for (position, signature) in signatures.iter().enumerate() {
+        for (band, rows) in signature.chunks(rows).enumerate() {
+            buckets.entry((band, rows)).or_default().push(position);
+        }
+    }
+
+    let mut candidates: Vec<(usize, usize)> = buckets
+        .into_values()
+        .flat_map(|members| {
+            let mut pairs = Vec::new();
+            for (i, &a) in members.iter().enumerate() {
+                for &b in &members[i + 1..] {
+                    pairs.push((a, b));
+                }
+            }
+            pairs
+        })
+        .collect();
+    candidates.sort_unstable();
+    candidates.dedup();
+
+    let mut duplicates: Vec<DuplicateParagraphs> = candidates
+        .into_iter()
+        .filter_map(|(a, b)| {
+            let similarity = jaccard(&paragraphs[a].shingles, &paragraphs[b].shingles);
+            (similarity >= threshold).then(|| DuplicateParagraphs {
+                first: paragraph_ref(&paragraphs[a]),
+                second: paragraph_ref(&paragraphs[b]),
+                similarity,
+            })
+        })
+        .collect();
+    duplicates.sort_by(|a, b| b.similarity.total_cmp(&a.similarity));
+    duplicates
+}
+
+/// Paragraphs separated by blank lines; blank lines inside code fences do not split
+fn paragraphs(content: &str) -> Vec<String> {
+    let mut paragraphs = Vec::new();
+    let mut current: Vec<&str> = Vec::new();
+    let mut in_fence = false;
+    for line in content.lines() {
+        if line.trim_start().starts_with("```") {
+            in_fence = !in_fence;
+        }
+        if line.trim().is_empty() && !in_fence {
+            if !current.is_empty() {
+                paragraphs.push(current.join("\n"));
+                current.clear();
+            }
+        } else {
+            current.push(line);
+        }
+    }
+    if !current.is_empty() {
+        paragraphs.push(current.join("\n"));
+    }
+    paragraphs
+}
+
+fn shingles(text: &str) -> HashSet<u64> {
+    let words: Vec<String> = text
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+        .collect();
+    words.windows(SHINGLE_SIZE).map(|shingle| hash(0, &shingle)).collect()
+}
+
+fn signature(shingles: &HashSet<u64>) -> Vec<u64> {
+    (0..SIGNATURE_LENGTH as u64)
+        .map(|seed| shingles.iter().map(|shingle| hash(seed, shingle)).min().unwrap_or(u64::MAX))
+        .collect()
+}
+
+fn hash(seed: u64, value: &impl Hash) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    seed.hash(&mut hasher);
+    value.hash(&mut hasher);
+    hasher.finish()
+}
+
+fn jaccard(a: &HashSet<u64>, b: &HashSet<u64>) -> f64 {
+    let shared = a.intersection(b).count();
+    shared as f64 / (a.len() + b.len() - shared) as f64
+}
+
+fn paragraph_ref(paragraph: &Paragraph) -> ParagraphRef {
+    ParagraphRef {
+        section_id: paragraph.section_id.clone(),
+        paragraph_index: paragraph.index,
+        preview: content_preview(&paragraph.text, DEFAULT_PREVIEW_LENGTH),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SHARED: &str = "The assistant should always answer in plain English and cite the source document for every claim it makes.";
+
+    #[test]
+    fn test_finds_near_duplicates_across_sections() {
+        let mut parent = Section::new("intent-1", "intent", format!("# Intent\n\n{}\n\nSomething else entirely.", SHARED));
+        parent.children.push(Section::new("process-1", "process", "Unrelated steps to follow here, one after the other."));
+        let sections = vec![
+            parent,
+            Section::new(
+                "evaluation-1",
+                "evaluation",
+                format!("Intro line.\n\n{}", SHARED.replace("English", "English,")),
+            ),
+        ];
+
+        let duplicates = find_duplicate_paragraphs(&sections, DEFAULT_DUPLICATE_THRESHOLD);
+
+        assert_eq!(duplicates.len(), 1);
+        assert_eq!(duplicates[0].first.section_id, "intent-1");
+        assert_eq!(duplicates[0].first.paragraph_index, 1);
+        assert_eq!(duplicates[0].second.section_id, "evaluation-1");
+        assert_eq!(duplicates[0].second.paragraph_index, 1);
+        assert_eq!(duplicates[0].similarity, 1.0);
+    }
+
+    #[test]
+    fn test_threshold() {
+        let edited = SHARED.replace("every", "each");
+        let sections = vec![Section::new("intent-1", "intent", SHARED), Section::new("process-1", "process", &edited)];
+
+        assert!(find_duplicate_paragraphs(&sections, 0.95).is_empty());
+        let duplicates = find_duplicate_paragraphs(&sections, 0.6);
+        assert_eq!(duplicates.len(), 1);
+        assert!(duplicates[0].similarity < 1.0);
+    }
+
+    #[test]
+    fn test_code_fences_stay_one_paragraph() {
+        assert_eq!(paragraphs("One\n\n```\na\n\nb\n```\n\nTwo").len(), 3);
+    }
+}
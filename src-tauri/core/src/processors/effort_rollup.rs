@@ -0,0 +1,271 @@
+use serde::{Deserialize, Serialize};
+use crate::models::*;
+use crate::processors::flow_navigation;
+use crate::processors::section_frontmatter;
+
+/// Frontmatter keys read as a section's effort estimate, in order of preference
+pub const EFFORT_FRONTMATTER_KEYS: &[&str] = &["effort", "estimate", "estimatedEffort"];
+
+/// Path enumeration stops here so heavily branching diagrams stay responsive
+pub const MAX_PATHS: usize = 500;
+
+/// Where a node's estimate came from
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum EffortSource {
+    /// `estimatedEffort` in the node's metadata
+    Node,
+    /// An effort key in the frontmatter of the section the node links to
+    Frontmatter,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct NodeEffort {
+    pub node_id: String,
+    pub label: String,
+    pub effort: Option<f64>,
+    pub source: Option<EffortSource>,
+}
+
+/// One route from a start node to an end node
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct PathEffort {
+    pub node_ids: Vec<String>,
+    pub total: f64,
+    /// Nodes on the path without an estimate; the total is a lower bound when any are listed
+    pub unestimated: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SubgraphEffort {
+    /// Title of the subgraph
+    pub name: String,
+    /// Nodes inside the block, including those of nested subgraphs
+    pub node_ids: Vec<String>,
+    pub total: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct EffortRollup {
+    pub nodes: Vec<NodeEffort>,
+    pub paths: Vec<PathEffort>,
+    pub subgraphs: Vec<SubgraphEffort>,
+    /// Sum over every node, counting each once
+    pub total: f64,
+    /// The longest path by effort, the rough critical path
+    pub critical_path: Option<PathEffort>,
+    /// More than [`MAX_PATHS`] paths exist and only the first ones are listed
+    pub truncated: bool,
+}
+
+/// Sum effort estimates along each path of an enriched flow graph and per subgraph
+///
+/// A node's estimate is its `estimatedEffort` metadata, else the first
+/// [`EFFORT_FRONTMATTER_KEYS`] entry in the frontmatter of the section its
+/// click action links to. Paths run from nodes without incoming edges to
+/// nodes without outgoing edges; an edge back onto the path ends it, so
+/// cycles are counted once.
+pub fn effort_rollup(flow: &FlowGraph, sections: &[Section]) -> EffortRollup {
+    let flat = flow_navigation::flatten(sections);
+    let nodes: Vec<NodeEffort> = flow
+        .parsed_graph
+        .nodes
+        .iter()
+        .map(|node| {
+            let (effort, source) = node_effort(node, &flat);
+            NodeEffort { node_id: node.id.clone(), label: node.label.clone(), effort, source }
+        })
+        .collect();
+
+    let effort_of = |node_id: &str| nodes.iter().find(|node| node.node_id == node_id).and_then(|node| node.effort);
+    let summarize = |node_ids: Vec<String>| {
+        let total = node_ids.iter().filter_map(|id| effort_of(id)).sum();
+        let unestimated = node_ids.iter().filter(|id| effort_of(id).is_none()).cloned().collect();
+        PathEffort { node_ids, total, unestimated }
+    };
+
+    let (routes, truncated) = enumerate_paths(&flow.parsed_graph);
+    let paths: Vec<PathEffort> = routes.into_iter().map(summarize).collect();
+    let critical_path = paths
+        .iter()
+        .fold(None::<&PathEffort>, |best, path| match best {
+            Some(best) if best.total >= path.total => Some(best),
+            _ => Some(path),
+        })
+        .cloned();
+
+    let subgraphs = flow
+        .parsed_graph
+        .subgraphs
+        .iter()
+        .map(|subgraph| {
+            let node_ids = subgraph_members(&flow.parsed_graph.subgraphs, subgraph);
+            let total = node_ids.iter().filter_map(|id| effort_of(id)).sum();
+            SubgraphEffort { name: subgraph.title.clone(), node_ids, total }
+        })
+        .collect();
+
+    EffortRollup {
+        total: nodes.iter().filter_map(|node| node.effort).sum(),
+        nodes,
+        paths,
+        subgraphs,
+        critical_path,
+        truncated,
+    }
+}
+
+/// Parse an estimate such as `3`, `2.5` or `4h`; a trailing unit is ignored
+pub fn parse_effort(value: &str) -> Option<f64> {
+    let number = value.trim().trim_end_matches(|c: char| c.is_alphabetic()).trim();
+    number.parse::<f64>().ok().filter(|effort| effort.is_finite() && *effort >= 0.0)
+}
+
+fn node_effort(node: &GraphNode, sections: &[&Section]) -> (Option<f64>, Option<EffortSource>) {
+    if let Some(effort) = node.metadata.as_ref().and_then(|metadata| metadata.estimated_effort) {
+        return (Some(effort), Some(EffortSource::Node));
+    }
+    let frontmatter = node
+        .ref_section_id
+        .as_ref()
+        .and_then(|id| sections.iter().find(|section| &section.id == id))
+        .map(|section| section_frontmatter::section_frontmatter(section))
+        .unwrap_or_default();
+    let effort = EFFORT_FRONTMATTER_KEYS
+        .iter()
+        .find_map(|key| frontmatter.get(*key).and_then(|value| parse_effort(value)));
+    (effort, effort.map(|_| EffortSource::Frontmatter))
+}
+
+/// Every simple path from a start node to an end node, and whether the list was cut at [`MAX_PATHS`]
+///
+/// A diagram that is one big cycle starts from its first node.
+fn enumerate_paths(graph: &GraphStructure) -> (Vec<Vec<String>>, bool) {
+    let mut starts: Vec<&str> = graph
+        .nodes
+        .iter()
+        .filter(|node| !graph.edges.iter().any(|edge| edge.to == node.id))
+        .map(|node| node.id.as_str())
+        .collect();
+    if starts.is_empty() {
+        starts.extend(graph.nodes.first().map(|node| node.id.as_str()));
+    }
+
+    let mut paths = Vec::new();
+    let mut truncated = false;
+    for start in starts {
+        let mut path = vec![start];
+        extend_paths(graph, &mut path, &mut paths, &mut truncated);
+    }
+    (paths, truncated)
+}
+
+fn extend_paths<'a>(graph: &'a GraphStructure, path: &mut Vec<&'a str>, paths: &mut Vec<Vec<String>>, truncated: &mut bool) {
+    if paths.len() >= MAX_PATHS {
+        *truncated = true;
+        return;
+    }
+    let last = *path.last().unwrap();
+    let mut next: Vec<&str> = Vec::new();
+    for edge in graph.edges.iter().filter(|edge| edge.from == last) {
+        if !path.contains(&edge.to.as_str()) && !next.contains(&edge.to.as_str()) {
+            next.push(edge.to.as_str());
+        }
+    }
+
+    if next.is_empty() {
+        paths.push(path.iter().map(|id| id.to_string()).collect());
+        return;
+    }
+    for node_id in next {
+        path.push(node_id);
+        extend_paths(graph, path, paths, truncated);
+        path.pop();
+    }
+}
+
+/// Nodes of the subgraph, then those of its nested subgraphs
+fn subgraph_members(subgraphs: &[Subgraph], subgraph: &Subgraph) -> Vec<String> {
+    let mut members = subgraph.node_ids.clone();
+    for child in subgraphs.iter().filter(|child| child.parent_id.as_ref() == Some(&subgraph.id)) {
+        members.extend(subgraph_members(subgraphs, child));
+    }
+    members
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parsers::mermaid_parser::{self, parse_mermaid};
+
+    fn flow(code: &str, estimates: &[(&str, f64)]) -> FlowGraph {
+        let mut flow = FlowGraph {
+            id: "flow-1".to_string(),
+            version: "1.0".to_string(),
+            title: None,
+            mermaid_code: code.to_string(),
+            parsed_graph: GraphStructure { nodes: vec![], edges: vec![], subgraphs: vec![] },
+            node_refs: vec![],
+            layout: None,
+            node_metadata: Default::default(),
+        };
+        for (node_id, effort) in estimates {
+            let metadata = NodeMetadata { estimated_effort: Some(*effort), ..Default::default() };
+            flow.node_metadata.insert(node_id.to_string(), metadata);
+        }
+        mermaid_parser::enrich_flow_graph(&mut flow).unwrap();
+        flow
+    }
+
+    #[test]
+    fn test_paths_and_sources() {
+        let code = "flowchart TD\n  A[Plan] --> B[Build]\n  A --> C[Review]\n  B --> D[Ship]\n  C --> D\n  click C \"#process-1\"";
+        let sections = vec![Section::new("process-1", "process", "---\neffort: 1.5d\n---\nReview steps")];
+        let rollup = effort_rollup(&flow(code, &[("A", 1.0), ("B", 3.0)]), &sections);
+
+        assert_eq!(rollup.nodes[2].effort, Some(1.5));
+        assert_eq!(rollup.nodes[2].source, Some(EffortSource::Frontmatter));
+        assert_eq!(rollup.nodes[3].effort, None);
+        assert_eq!(rollup.total, 5.5);
+
+        let paths: Vec<_> = rollup.paths.iter().map(|path| (path.node_ids.join(""), path.total)).collect();
+        assert_eq!(paths, vec![("ABD".to_string(), 4.0), ("ACD".to_string(), 2.5)]);
+        assert_eq!(rollup.paths[0].unestimated, vec!["D"]);
+        assert_eq!(rollup.critical_path.unwrap().node_ids, vec!["A", "B", "D"]);
+        assert!(!rollup.truncated);
+    }
+
+    #[test]
+    fn test_cycles_end_the_path() {
+        let graph = parse_mermaid("flowchart TD\n  A[One] --> B[Two]\n  B --> C[Three]\n  C --> A").unwrap();
+        let (paths, truncated) = enumerate_paths(&graph);
+
+        assert_eq!(paths, vec![vec!["A", "B", "C"]]);
+        assert!(!truncated);
+    }
+
+    #[test]
+    fn test_subgraph_totals() {
+        let code = "flowchart LR\n  A[Plan] --> B[Build]\n  subgraph Delivery\n  B --> C[Test]\n  subgraph Release\n  D[Ship]\n  end\n  C --> D\n  end";
+        let rollup = effort_rollup(&flow(code, &[("A", 1.0), ("B", 2.0), ("C", 1.0), ("D", 0.5)]), &[]);
+
+        assert_eq!(rollup.subgraphs.len(), 2);
+        assert_eq!(rollup.subgraphs[0].name, "Delivery");
+        assert_eq!(rollup.subgraphs[0].node_ids, vec!["B", "C", "D"]);
+        assert_eq!(rollup.subgraphs[0].total, 3.5);
+        assert_eq!(rollup.subgraphs[1].name, "Release");
+        assert_eq!(rollup.subgraphs[1].total, 0.5);
+    }
+
+    #[test]
+    fn test_parse_effort() {
+        assert_eq!(parse_effort("3"), Some(3.0));
+        assert_eq!(parse_effort(" 2.5h "), Some(2.5));
+        assert_eq!(parse_effort("soon"), None);
+        assert_eq!(parse_effort("-1"), None);
+    }
+}
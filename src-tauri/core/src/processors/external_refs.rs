@@ -0,0 +1,93 @@
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::sync::LazyLock;
+use crate::models::*;
+use crate::processors::flow_navigation::flatten;
+use crate::processors::portable_format::portable_path;
+
+/// Markdown link or image target: `[text](target)` / `![alt](target "title")`
+pub(crate) static LINK_TARGET: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r#"!?\[[^\]]*\]\(\s*<?([^)\s>]+)>?(?:\s+"[^"]*")?\s*\)"#).unwrap());
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "lowercase")]
+pub enum DependencyKind {
+    /// Another document referenced as `path#section-id` in refTarget
+    Transclusion,
+    /// A local file linked or embedded from markdown content
+    Attachment,
+}
+
+/// Local files the sections depend on, in order of first appearance
+///
+/// Transclusions are the documents named in refTargets; attachments are
+/// relative or absolute markdown link and image targets, both with `/`
+/// separators. URLs, in-document anchors and `#fragment`/`?query` suffixes
+/// are left out. Only direct
+/// dependencies are listed, not those of transcluded documents.
+pub fn collect_external_refs(sections: &[Section]) -> Vec<(String, DependencyKind, Vec<String>)> {
+    let mut refs: Vec<(String, DependencyKind, Vec<String>)> = Vec::new();
+    let mut add = |target: String, kind: DependencyKind, section_id: &str| {
+        match refs.iter_mut().find(|(path, k, _)| *path == target && *k == kind) {
+            Some((_, _, section_ids)) => {
+                if !section_ids.iter().any(|id| id == section_id) {
+                    section_ids.push(section_id.to_string());
+                }
+            }
+            None => refs.push((target, kind, vec![section_id.to_string()])),
+        }
+    };
+
+    for section in flatten(sections) {
+        for reference in section.cross_document_refs() {
+            add(portable_path(&reference.path), DependencyKind::Transclusion, &section.id);
+        }
+        let contents = std::iter::once(&section.content).chain(section.translations.values());
+        for content in contents {
+            for caps in LINK_TARGET.captures_iter(content) {
+                if let Some(target) = local_target(&caps[1]) {
+                    add(target, DependencyKind::Attachment, &section.id);
+                }
+            }
+        }
+    }
+    refs
+}
+
+pub(crate) fn local_target(target: &str) -> Option<String> {
+    let is_remote = target.contains("://") || target.starts_with("//") || target.starts_with('#');
+    let has_scheme = ["mailto:", "data:", "tel:"].iter().any(|scheme| target.starts_with(scheme));
+    if is_remote || has_scheme {
+        return None;
+    }
+    let path = target.split(['#', '?']).next().unwrap_or_default();
+    Some(portable_path(path)).filter(|path| !path.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_collect_external_refs() {
+        let mut intent = Section::new(
+            "intent-1",
+            "intent",
+            "See [spec](docs/spec.md#scope), ![diagram](<img/flow.png> \"Flow\"), [site](https://example.com) and [below](#process-1)",
+        );
+        intent.ref_target = Some("process-1 shared.xml#intent-1".to_string());
+        let mut process = Section::new("process-1", "process", "Also [spec](docs/spec.md?raw=1)");
+        process.ref_target = Some("shared.xml#process-2".to_string());
+
+        let refs = collect_external_refs(&[intent, process]);
+
+        assert_eq!(
+            refs,
+            vec![
+                ("shared.xml".to_string(), DependencyKind::Transclusion, vec!["intent-1".to_string(), "process-1".to_string()]),
+                ("docs/spec.md".to_string(), DependencyKind::Attachment, vec!["intent-1".to_string(), "process-1".to_string()]),
+                ("img/flow.png".to_string(), DependencyKind::Attachment, vec!["intent-1".to_string()]),
+            ]
+        );
+    }
+}
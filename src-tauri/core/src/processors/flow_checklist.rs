@@ -0,0 +1,71 @@
+use regex::Regex;
+use std::collections::HashSet;
+use std::sync::LazyLock;
+use crate::models::*;
+use crate::processors::flow_traversal::topological_order;
+
+/// Hidden marker tying a task back to its flow node
+static NODE_MARKER: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"<!-- node:(\w+) -->").unwrap());
+
+/// Markdown tasks for the flow's nodes in topological order
+///
+/// Each task carries the node label, a link to the node's section when it
+/// has a click action, and a `<!-- node:ID -->` marker. Nodes that already
+/// have a task in `existing` are skipped, so running it again on the same
+/// section only adds tasks for new nodes and keeps ticked ones as they are.
+/// Expects an enriched flow graph.
+pub fn checklist_tasks(flow: &FlowGraph, existing: &str) -> Vec<String> {
+    let listed: HashSet<&str> = NODE_MARKER
+        .captures_iter(existing)
+        .map(|caps| caps.get(1).unwrap().as_str())
+        .collect();
+
+    topological_order(&flow.parsed_graph)
+        .into_iter()
+        .filter(|node_id| !listed.contains(node_id))
+        .filter_map(|node_id| flow.parsed_graph.nodes.iter().find(|node| node.id == node_id))
+        .map(|node| match &node.ref_section_id {
+            Some(section_id) => format!("- [ ] {} ([{}](#{})) <!-- node:{} -->", node.label, section_id, section_id, node.id),
+            None => format!("- [ ] {} <!-- node:{} -->", node.label, node.id),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parsers::mermaid_parser::parse_mermaid;
+
+    fn flow() -> FlowGraph {
+        let mut parsed_graph = parse_mermaid("flowchart TD\n  B[Build] --> C[Ship]\n  A[Plan] --> B").unwrap();
+        parsed_graph.nodes[0].ref_section_id = Some("process-1".to_string());
+        FlowGraph {
+            id: "flow-1".to_string(),
+            version: "1.0".to_string(),
+            title: None,
+            mermaid_code: String::new(),
+            parsed_graph,
+            node_refs: vec![],
+            layout: None,
+            node_metadata: Default::default(),
+        }
+    }
+
+    #[test]
+    fn test_checklist_tasks() {
+        assert_eq!(
+            checklist_tasks(&flow(), ""),
+            vec![
+                "- [ ] Plan <!-- node:A -->",
+                "- [ ] Build ([process-1](#process-1)) <!-- node:B -->",
+                "- [ ] Ship <!-- node:C -->",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_existing_tasks_are_skipped() {
+        let existing = "# Checklist\n\n- [x] Plan <!-- node:A -->";
+        assert_eq!(checklist_tasks(&flow(), existing).len(), 2);
+    }
+}
@@ -0,0 +1,271 @@
+use serde::{Deserialize, Serialize};
+use crate::models::*;
+use crate::processors::content_summary;
+
+/// Hover-card data for one flow node
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct NodeNavigation {
+    pub node_id: String,
+    pub label: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub section_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub section_type: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub heading: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub preview: Option<String>,
+}
+
+/// Build navigation entries for every node of an enriched flow graph
+///
+/// Nodes whose click action points at a section that does not exist keep the
+/// section ID but carry no heading or preview.
+pub fn build_flow_navigation(flow: &FlowGraph, sections: &[Section], preview_length: usize) -> Vec<NodeNavigation> {
+    flow.parsed_graph
+        .nodes
+        .iter()
+        .map(|node| {
+            let section = node
+                .ref_section_id
+                .as_ref()
+                .and_then(|id| sections.iter().find(|s| &s.id == id));
+
+            NodeNavigation {
+                node_id: node.id.clone(),
+                label: node.label.clone(),
+                section_id: node.ref_section_id.clone(),
+                section_type: section.map(|s| s.section_type.clone()),
+                heading: section.and_then(|s| content_summary::first_heading(&s.content)),
+                preview: section.map(|s| content_summary::content_preview(&s.content, preview_length)),
+            }
+        })
+        .collect()
+}
+
+/// How a section relates to the flow node in focus
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum FocusRelation {
+    /// The node's click action points at the section
+    Linked,
+    /// A linked section lists the section in its refTarget
+    Referenced,
+    /// The section lists a linked section in its refTarget
+    Referencing,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct FocusSection {
+    pub relation: FocusRelation,
+    pub section: Section,
+}
+
+/// Sections relevant to one flow node, for focus mode
+///
+/// Starts with the sections the node's click actions link to, followed by
+/// the sections they reference and the sections referencing them through
+/// refTarget. Nested sections are included; cross-document targets
+/// (`path#section-id`) are skipped. Each section appears once, under its
+/// closest relation.
+pub fn sections_for_node(flow: &FlowGraph, sections: &[Section], node_id: &str) -> Vec<FocusSection> {
+    let all = flatten(sections);
+    let linked: Vec<&str> = flow
+        .node_refs
+        .iter()
+        .filter(|node_ref| node_ref.node_id == node_id)
+        .map(|node_ref| node_ref.section_id.as_str())
+        .collect();
+
+    let mut focus: Vec<FocusSection> = Vec::new();
+    let mut add = |section: &Section, relation| {
+        if !focus.iter().any(|f| f.section.id == section.id) {
+            focus.push(FocusSection { relation, section: section.clone() });
+        }
+    };
+
+    let linked_sections: Vec<&Section> = linked
+        .iter()
+        .filter_map(|id| all.iter().copied().find(|s| s.id == *id))
+        .collect();
+    for section in &linked_sections {
+        add(section, FocusRelation::Linked);
+    }
+    for section in &linked_sections {
+        for target in local_ref_targets(section) {
+            if let Some(referenced) = all.iter().find(|s| s.id == target) {
+                add(referenced, FocusRelation::Referenced);
+            }
+        }
+    }
+    for section in &all {
+        if local_ref_targets(section).any(|target| linked.contains(&target)) {
+            add(section, FocusRelation::Referencing);
+        }
+    }
+
+    focus
+}
+
+/// The flow node a section belongs to in focus mode
+///
+/// A node whose click action links to the section wins; otherwise the first
+/// node (in diagram order) whose focus sections include it through refTarget.
+pub fn node_for_section<'a>(flow: &'a FlowGraph, sections: &[Section], section_id: &str) -> Option<&'a GraphNode> {
+    let nodes = &flow.parsed_graph.nodes;
+    let linked = flow
+        .node_refs
+        .iter()
+        .find(|node_ref| node_ref.section_id == section_id)
+        .and_then(|node_ref| nodes.iter().find(|node| node.id == node_ref.node_id));
+
+    linked.or_else(|| {
+        nodes.iter().find(|node| {
+            sections_for_node(flow, sections, &node.id)
+                .iter()
+                .any(|focus| focus.section.id == section_id)
+        })
+    })
+}
+
+/// Section IDs in the refTarget attribute that point into the same document
+pub(crate) fn local_ref_targets(section: &Section) -> impl Iterator<Item = &str> {
+    section
+        .ref_target
+        .as_deref()
+        .unwrap_or("")
+        .split_whitespace()
+        .filter(|target| !target.contains('#'))
+}
+
+pub(crate) fn flatten(sections: &[Section]) -> Vec<&Section> {
+    sections
+        .iter()
+        .flat_map(|section| std::iter::once(section).chain(flatten(&section.children)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeMap;
+
+    fn node(id: &str, label: &str, section: Option<&str>) -> GraphNode {
+        GraphNode {
+            id: id.to_string(),
+            label: label.to_string(),
+            node_type: NodeType::Rectangle,
+            ref_section_id: section.map(|s| s.to_string()),
+            metadata: None,
+        }
+    }
+
+    #[test]
+    fn test_build_flow_navigation() {
+        let flow = FlowGraph {
+            id: "flow-1".to_string(),
+            version: "1.0".to_string(),
+            title: None,
+            mermaid_code: String::new(),
+            parsed_graph: GraphStructure {
+                nodes: vec![
+                    node("A", "Intent", Some("intent-1")),
+                    node("B", "Missing", Some("gone")),
+                    node("C", "Unlinked", None),
+                ],
+                edges: vec![],
+                subgraphs: vec![],
+            },
+            node_refs: vec![],
+            layout: None,
+            node_metadata: Default::default(),
+        };
+        let sections = vec![Section {
+            id: "intent-1".to_string(),
+            section_type: "intent".to_string(),
+            content: "# Our Intent\nShip it".to_string(),
+            ref_target: None,
+            children: vec![],
+            budget: None,
+            transclusions: vec![],
+            tags: vec![],
+            translations: BTreeMap::new(),
+            sensitive: false,
+            encrypted: false,
+        }];
+
+        let nav = build_flow_navigation(&flow, &sections, 50);
+
+        assert_eq!(nav.len(), 3);
+        assert_eq!(nav[0].heading, Some("Our Intent".to_string()));
+        assert_eq!(nav[0].preview, Some("Ship it".to_string()));
+        assert_eq!(nav[0].section_type, Some("intent".to_string()));
+        assert_eq!(nav[1].section_id, Some("gone".to_string()));
+        assert!(nav[1].heading.is_none());
+        assert!(nav[2].section_id.is_none());
+    }
+
+    fn section(id: &str, ref_target: Option<&str>) -> Section {
+        Section {
+            ref_target: ref_target.map(str::to_string),
+            ..Section::new(id.to_string(), "process".to_string(), String::new())
+        }
+    }
+
+    fn focus_flow() -> FlowGraph {
+        let node_ref = |node: &str, section: &str| NodeReference {
+            node_id: node.to_string(),
+            section_id: section.to_string(),
+            click_action: format!("#{}", section),
+            tooltip: None,
+        };
+        FlowGraph {
+            id: "flow-1".to_string(),
+            version: "1.0".to_string(),
+            title: None,
+            mermaid_code: String::new(),
+            parsed_graph: GraphStructure {
+                nodes: vec![node("A", "Plan", Some("plan")), node("B", "Build", Some("build")), node("C", "Ship", None)],
+                edges: vec![],
+                subgraphs: vec![],
+            },
+            node_refs: vec![node_ref("A", "plan"), node_ref("B", "build")],
+            layout: None,
+            node_metadata: Default::default(),
+        }
+    }
+
+    fn focus_sections() -> Vec<Section> {
+        let mut build = section("build", Some("plan other.xml#shared"));
+        build.children = vec![section("build-notes", Some("build"))];
+        vec![section("plan", Some("risks")), build, section("risks", None), section("unrelated", None)]
+    }
+
+    #[test]
+    fn test_sections_for_node() {
+        let focus = sections_for_node(&focus_flow(), &focus_sections(), "A");
+        let summary: Vec<(&str, FocusRelation)> = focus.iter().map(|f| (f.section.id.as_str(), f.relation)).collect();
+
+        assert_eq!(
+            summary,
+            vec![
+                ("plan", FocusRelation::Linked),
+                ("risks", FocusRelation::Referenced),
+                ("build", FocusRelation::Referencing),
+            ]
+        );
+        assert!(sections_for_node(&focus_flow(), &focus_sections(), "C").is_empty());
+    }
+
+    #[test]
+    fn test_node_for_section() {
+        let flow = focus_flow();
+        let sections = focus_sections();
+        let node_id = |section_id| node_for_section(&flow, &sections, section_id).map(|n| n.id.as_str());
+
+        assert_eq!(node_id("build"), Some("B"), "direct links win over refTarget");
+        assert_eq!(node_id("risks"), Some("A"));
+        assert_eq!(node_id("build-notes"), Some("B"));
+        assert_eq!(node_id("unrelated"), None);
+    }
+}
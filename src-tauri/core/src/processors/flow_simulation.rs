@@ -0,0 +1,199 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use crate::error::{ContextError, Result};
+use crate::models::*;
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SimulationStep {
+    pub node_id: String,
+    pub label: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub section_id: Option<String>,
+    /// Label of the edge taken to reach this node
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub via_edge_label: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum SimulationOutcome {
+    /// Reached a node without outgoing edges
+    Completed,
+    /// Every outgoing edge was conditional and none matched
+    NoMatchingBranch,
+    /// The chosen branch leads back to a visited node; with fixed variables it would loop forever
+    CycleDetected,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SimulationResult {
+    pub steps: Vec<SimulationStep>,
+    pub outcome: SimulationOutcome,
+}
+
+/// Walk the flow from a start node, picking branches by evaluating edge conditions
+///
+/// At each node the first outgoing edge whose condition holds is taken; if no
+/// conditional edge matches, the first unconditional edge is the default
+/// branch. Without a `start_node`, the walk begins at the first node that has
+/// no incoming edges (or the first node when every node has one).
+pub fn simulate_flow(
+    flow: &FlowGraph,
+    variables: &HashMap<String, String>,
+    start_node: Option<&str>,
+) -> Result<SimulationResult> {
+    let graph = &flow.parsed_graph;
+
+    let start = match start_node {
+        Some(id) => graph
+            .nodes
+            .iter()
+            .find(|n| n.id == id)
+            .ok_or_else(|| ContextError::ReferenceError(format!("Start node '{}' not found in flow", id)))?,
+        None => match graph
+            .nodes
+            .iter()
+            .find(|n| !graph.edges.iter().any(|e| e.to == n.id))
+            .or_else(|| graph.nodes.first())
+        {
+            Some(node) => node,
+            None => {
+                return Ok(SimulationResult {
+                    steps: vec![],
+                    outcome: SimulationOutcome::Completed,
+                })
+            }
+        },
+    };
+
+    let mut steps = vec![step(flow, &start.id, None)];
+    let mut visited: HashSet<String> = HashSet::from([start.id.clone()]);
+    let mut current = start.id.clone();
+
+    loop {
+        let outgoing: Vec<&GraphEdge> = graph.edges.iter().filter(|e| e.from == current).collect();
+        if outgoing.is_empty() {
+            return Ok(SimulationResult {
+                steps,
+                outcome: SimulationOutcome::Completed,
+            });
+        }
+
+        let chosen = outgoing
+            .iter()
+            .find(|e| {
+                e.condition
+                    .as_ref()
+                    .is_some_and(|c| c.evaluate(variables.get(&c.variable).map(String::as_str)))
+            })
+            .or_else(|| outgoing.iter().find(|e| e.condition.is_none()));
+
+        let Some(edge) = chosen else {
+            return Ok(SimulationResult {
+                steps,
+                outcome: SimulationOutcome::NoMatchingBranch,
+            });
+        };
+
+        if !visited.insert(edge.to.clone()) {
+            return Ok(SimulationResult {
+                steps,
+                outcome: SimulationOutcome::CycleDetected,
+            });
+        }
+
+        steps.push(step(flow, &edge.to, edge.label.clone()));
+        current = edge.to.clone();
+    }
+}
+
+fn step(flow: &FlowGraph, node_id: &str, via_edge_label: Option<String>) -> SimulationStep {
+    let node = flow.parsed_graph.nodes.iter().find(|n| n.id == node_id);
+
+    SimulationStep {
+        node_id: node_id.to_string(),
+        label: node.map(|n| n.label.clone()).unwrap_or_else(|| node_id.to_string()),
+        section_id: node.and_then(|n| n.ref_section_id.clone()),
+        via_edge_label,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parsers::mermaid_parser;
+
+    fn flow(mermaid_code: &str) -> FlowGraph {
+        let mut flow = FlowGraph {
+            id: "flow-1".to_string(),
+            version: "1.0".to_string(),
+            title: None,
+            mermaid_code: mermaid_code.to_string(),
+            parsed_graph: GraphStructure {
+                nodes: vec![],
+                edges: vec![],
+                subgraphs: vec![],
+            },
+            node_refs: vec![],
+            layout: None,
+            node_metadata: Default::default(),
+        };
+        mermaid_parser::enrich_flow_graph(&mut flow).unwrap();
+        flow
+    }
+
+    fn vars(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+        pairs.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect()
+    }
+
+    const BRANCHING: &str = "flowchart TD
+  A[Intent] --> B[Review]
+  B -->|cond: score >= 85| C[Ship]
+  B -->|cond: score < 85| D[Rework]
+  click A \"#intent-1\"";
+
+    fn path(result: &SimulationResult) -> Vec<&str> {
+        result.steps.iter().map(|s| s.node_id.as_str()).collect()
+    }
+
+    #[test]
+    fn test_simulate_takes_matching_branch() {
+        let flow = flow(BRANCHING);
+
+        let result = simulate_flow(&flow, &vars(&[("score", "90")]), None).unwrap();
+        assert_eq!(path(&result), vec!["A", "B", "C"]);
+        assert_eq!(result.outcome, SimulationOutcome::Completed);
+        assert_eq!(result.steps[0].section_id, Some("intent-1".to_string()));
+        assert_eq!(result.steps[2].via_edge_label, Some("cond: score >= 85".to_string()));
+
+        let result = simulate_flow(&flow, &vars(&[("score", "40")]), None).unwrap();
+        assert_eq!(path(&result), vec!["A", "B", "D"]);
+    }
+
+    #[test]
+    fn test_simulate_no_matching_branch() {
+        let result = simulate_flow(&flow(BRANCHING), &HashMap::new(), None).unwrap();
+
+        assert_eq!(path(&result), vec!["A", "B"]);
+        assert_eq!(result.outcome, SimulationOutcome::NoMatchingBranch);
+    }
+
+    #[test]
+    fn test_simulate_default_branch_and_start_node() {
+        let flow = flow("A[Start] --> B[Check]\nB -->|cond: env = prod| C[Deploy]\nB -->|Otherwise| D[Stage]");
+
+        let result = simulate_flow(&flow, &vars(&[("env", "dev")]), Some("B")).unwrap();
+        assert_eq!(path(&result), vec!["B", "D"]);
+
+        assert!(simulate_flow(&flow, &HashMap::new(), Some("Z")).is_err());
+    }
+
+    #[test]
+    fn test_simulate_detects_cycle() {
+        let flow = flow("A[Start] --> B[Work]\nB --> C[Check]\nC --> B");
+
+        let result = simulate_flow(&flow, &HashMap::new(), Some("A")).unwrap();
+        assert_eq!(path(&result), vec!["A", "B", "C"]);
+        assert_eq!(result.outcome, SimulationOutcome::CycleDetected);
+    }
+}
@@ -0,0 +1,84 @@
+use crate::models::*;
+
+/// Node IDs in depth-first order
+///
+/// Starts from the nodes without incoming edges, in diagram order, and
+/// follows outgoing edges in the order they are written. Nodes only
+/// reachable through a cycle are visited from the first of them in diagram
+/// order.
+pub fn depth_first_order(graph: &GraphStructure) -> Vec<&str> {
+    let mut order: Vec<&str> = Vec::new();
+    for root in roots(graph) {
+        let mut stack = vec![root];
+        while let Some(node_id) = stack.pop() {
+            if order.contains(&node_id) {
+                continue;
+            }
+            order.push(node_id);
+            // Reversed so the first edge in the diagram is followed first
+            for edge in graph.edges.iter().rev().filter(|edge| edge.from == node_id) {
+                if !order.contains(&edge.to.as_str()) {
+                    stack.push(edge.to.as_str());
+                }
+            }
+        }
+    }
+    order
+}
+
+/// Node IDs with every node after all of its predecessors
+///
+/// Ties are broken by diagram order, and cycles at the first pending node in
+/// diagram order, so the result is stable for the same diagram.
+pub fn topological_order(graph: &GraphStructure) -> Vec<&str> {
+    let nodes: Vec<&str> = graph.nodes.iter().map(|node| node.id.as_str()).collect();
+    let mut order: Vec<&str> = Vec::new();
+
+    while order.len() < nodes.len() {
+        let ready = nodes.iter().copied().find(|node_id| {
+            !order.contains(node_id)
+                && graph
+                    .edges
+                    .iter()
+                    .filter(|edge| edge.to == *node_id)
+                    .all(|edge| order.contains(&edge.from.as_str()) || !nodes.contains(&edge.from.as_str()))
+        });
+        let next = ready.unwrap_or_else(|| {
+            // Only cycles remain
+            nodes.iter().copied().find(|node_id| !order.contains(node_id)).unwrap()
+        });
+        order.push(next);
+    }
+    order
+}
+
+/// Nodes without incoming edges first, then every node as a fallback root for cycles
+fn roots(graph: &GraphStructure) -> impl Iterator<Item = &str> {
+    let sources = graph
+        .nodes
+        .iter()
+        .filter(|node| !graph.edges.iter().any(|edge| edge.to == node.id));
+    sources.chain(graph.nodes.iter()).map(|node| node.id.as_str())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parsers::mermaid_parser::parse_mermaid;
+
+    #[test]
+    fn test_orders() {
+        let graph = parse_mermaid("flowchart TD\n  A[Start] --> B[Build]\n  B --> D[Ship]\n  A --> C[Review]\n  C --> D").unwrap();
+
+        assert_eq!(depth_first_order(&graph), vec!["A", "B", "D", "C"]);
+        assert_eq!(topological_order(&graph), vec!["A", "B", "C", "D"]);
+    }
+
+    #[test]
+    fn test_cycles_visit_every_node_once() {
+        let graph = parse_mermaid("flowchart TD\n  A[One] --> B[Two]\n  B --> A").unwrap();
+
+        assert_eq!(depth_first_order(&graph), vec!["A", "B"]);
+        assert_eq!(topological_order(&graph), vec!["A", "B"]);
+    }
+}
@@ -0,0 +1,623 @@
+use std::collections::{BTreeMap, HashSet};
+use serde::{Deserialize, Serialize};
+use crate::error::{ContextError, Result};
+use crate::exporters::flow_json_exporter::{FlowJson, FLOW_JSON_FORMAT, FLOW_JSON_VERSION};
+use crate::models::*;
+use crate::parsers::mermaid_parser;
+use crate::serializers::mermaid_serializer;
+
+/// Graph description formats `import_flow` accepts
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum GraphFormat {
+    /// The `export_flow_json` format
+    Json,
+    /// Graphviz DOT
+    Dot,
+}
+
+/// A graph read from another tool, ready to become the document's flow
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImportedGraph {
+    pub graph: GraphStructure,
+    pub direction: FlowDirection,
+    pub node_refs: Vec<NodeReference>,
+    /// Node positions (and viewport) when the source has them
+    pub layout: Option<FlowLayout>,
+    pub node_metadata: BTreeMap<String, NodeMetadata>,
+    /// `style`, `classDef` and `class` lines to append to the generated diagram
+    pub styling: Vec<String>,
+}
+
+impl ImportedGraph {
+    /// Mermaid code for the graph, fenced as stored in `<diagram>`
+    pub fn to_mermaid(&self) -> String {
+        let mut code = mermaid_serializer::generate_mermaid(&self.graph, self.direction, &self.node_refs);
+        for line in &self.styling {
+            code.push_str(&format!("    {}\n", line));
+        }
+        format!("```mermaid\n{}```", code)
+    }
+}
+
+pub fn import_graph(source: &str, format: GraphFormat) -> Result<ImportedGraph> {
+    let imported = match format {
+        GraphFormat::Json => parse_flow_json(source)?,
+        GraphFormat::Dot => parse_dot(source)?,
+    };
+    if imported.graph.nodes.is_empty() {
+        return Err(ContextError::InvalidArgument("The imported graph has no nodes".to_string()));
+    }
+    Ok(imported)
+}
+
+/// Read a graph exported by `export_flow_json` (or written to its schema by another tool)
+///
+/// Node and subgraph IDs must already be valid Mermaid IDs, and a subgraph's
+/// parent must be listed before it. Click links come from `nodeRefs`, else
+/// from each node's and subgraph's `sectionId`.
+pub fn parse_flow_json(source: &str) -> Result<ImportedGraph> {
+    let flow: FlowJson = serde_json::from_str(source)
+        .map_err(|e| ContextError::InvalidArgument(format!("Not a flow JSON document: {}", e)))?;
+    if !flow.format.is_empty() && flow.format != FLOW_JSON_FORMAT {
+        return Err(ContextError::InvalidArgument(format!("Unknown flow JSON format '{}'", flow.format)));
+    }
+    if flow.format_version > FLOW_JSON_VERSION {
+        return Err(ContextError::InvalidArgument(format!(
+            "Flow JSON version {} is newer than this app supports ({})",
+            flow.format_version, FLOW_JSON_VERSION
+        )));
+    }
+
+    let mut ids = HashSet::new();
+    for node in &flow.nodes {
+        if !is_mermaid_id(&node.id) {
+            return Err(ContextError::InvalidArgument(format!("Node ID '{}' is not a valid Mermaid ID", node.id)));
+        }
+        if !ids.insert(node.id.as_str()) {
+            return Err(ContextError::InvalidArgument(format!("Duplicate node ID '{}'", node.id)));
+        }
+    }
+    for edge in &flow.edges {
+        if let Some(missing) = [&edge.from, &edge.to].into_iter().find(|id| !ids.contains(id.as_str())) {
+            return Err(ContextError::InvalidArgument(format!("Edge refers to unknown node '{}'", missing)));
+        }
+    }
+
+    let mut subgraph_ids = HashSet::new();
+    let mut placed = HashSet::new();
+    for subgraph in &flow.subgraphs {
+        if !is_mermaid_id(&subgraph.id) {
+            return Err(ContextError::InvalidArgument(format!("Subgraph ID '{}' is not a valid Mermaid ID", subgraph.id)));
+        }
+        if ids.contains(subgraph.id.as_str()) || !subgraph_ids.insert(subgraph.id.as_str()) {
+            return Err(ContextError::InvalidArgument(format!("Duplicate subgraph ID '{}'", subgraph.id)));
+        }
+        if let Some(parent) = subgraph.parent_id.as_ref().filter(|parent| !subgraph_ids.contains(parent.as_str()) || *parent == &subgraph.id) {
+            return Err(ContextError::InvalidArgument(format!("Subgraph '{}' refers to unknown parent '{}'", subgraph.id, parent)));
+        }
+        for node_id in &subgraph.node_ids {
+            if !ids.contains(node_id.as_str()) {
+                return Err(ContextError::InvalidArgument(format!("Subgraph '{}' refers to unknown node '{}'", subgraph.id, node_id)));
+            }
+            if !placed.insert(node_id.as_str()) {
+                return Err(ContextError::InvalidArgument(format!("Node '{}' is in more than one subgraph", node_id)));
+            }
+        }
+    }
+
+    let nodes = flow
+        .nodes
+        .iter()
+        .map(|node| GraphNode {
+            id: node.id.clone(),
+            label: if node.label.is_empty() { node.id.clone() } else { node.label.clone() },
+            node_type: node.shape.clone(),
+            ref_section_id: None,
+            metadata: None,
+        })
+        .collect();
+    let edges = flow
+        .edges
+        .iter()
+        .map(|edge| GraphEdge {
+            condition: edge.label.as_deref().and_then(mermaid_parser::parse_condition),
+            ..edge.clone()
+        })
+        .collect();
+
+    let subgraphs = flow
+        .subgraphs
+        .iter()
+        .map(|subgraph| Subgraph {
+            id: subgraph.id.clone(),
+            title: if subgraph.title.is_empty() { subgraph.id.clone() } else { subgraph.title.clone() },
+            node_ids: subgraph.node_ids.clone(),
+            parent_id: subgraph.parent_id.clone(),
+            ref_section_id: None,
+        })
+        .collect();
+
+    let node_refs = if flow.node_refs.is_empty() {
+        let node_links = flow.nodes.iter().map(|node| (&node.id, &node.section_id));
+        let subgraph_links = flow.subgraphs.iter().map(|subgraph| (&subgraph.id, &subgraph.section_id));
+        node_links
+            .chain(subgraph_links)
+            .filter_map(|(id, section_id)| section_id.as_ref().map(|section_id| section_link(id, section_id)))
+            .collect()
+    } else {
+        flow.node_refs.clone()
+    };
+
+    let positions: BTreeMap<String, NodePosition> = flow
+        .nodes
+        .iter()
+        .filter_map(|node| node.position.map(|position| (node.id.clone(), position)))
+        .collect();
+    let layout = (!positions.is_empty() || flow.viewport.is_some()).then(|| {
+        let (zoom, pan_x, pan_y) = flow.viewport.as_ref().map_or((1.0, 0.0, 0.0), |v| (v.zoom, v.pan_x, v.pan_y));
+        FlowLayout { zoom, pan_x, pan_y, positions }
+    });
+
+    let mut styling = Vec::new();
+    for (name, properties) in &flow.class_defs {
+        styling.push(format!("classDef {} {}", name, property_list(properties)));
+    }
+    for node in &flow.nodes {
+        if !node.style.is_empty() {
+            styling.push(format!("style {} {}", node.id, property_list(&node.style)));
+        }
+        for class in &node.classes {
+            styling.push(format!("class {} {}", node.id, class));
+        }
+    }
+
+    Ok(ImportedGraph {
+        graph: GraphStructure { nodes, edges, subgraphs },
+        direction: direction_from_keyword(&flow.direction),
+        node_refs,
+        layout,
+        node_metadata: flow
+            .nodes
+            .iter()
+            .filter_map(|node| node.metadata.clone().filter(|m| !m.is_empty()).map(|m| (node.id.clone(), m)))
+            .collect(),
+        styling,
+    })
+}
+
+/// Read a Graphviz DOT graph
+///
+/// Supports node and edge statements (including `a -> b -> c` chains),
+/// `label`, `shape` and `href`/`URL` attributes (a `#section-id` link becomes
+/// a click action) and `rankdir`. Subgraphs are flattened and other
+/// attributes ignored. IDs that are not valid Mermaid IDs are renamed, keeping
+/// the original as the label.
+pub fn parse_dot(source: &str) -> Result<ImportedGraph> {
+    let tokens = tokenize_dot(source)?;
+    let mut parser = DotParser { tokens, position: 0, graph: ImportedGraph::empty(), ids: BTreeMap::new() };
+    parser.parse()?;
+    Ok(parser.graph)
+}
+
+impl ImportedGraph {
+    fn empty() -> Self {
+        ImportedGraph {
+            graph: GraphStructure { nodes: vec![], edges: vec![], subgraphs: vec![] },
+            direction: FlowDirection::TopDown,
+            node_refs: vec![],
+            layout: None,
+            node_metadata: BTreeMap::new(),
+            styling: vec![],
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Id(String),
+    Edge,
+    Open,
+    Close,
+    OpenAttributes,
+    CloseAttributes,
+    Equals,
+    Separator,
+}
+
+fn tokenize_dot(source: &str) -> Result<Vec<Token>> {
+    let chars: Vec<char> = source.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    let mut line_start = true;
+
+    while i < chars.len() {
+        let c = chars[i];
+        if c == '\n' {
+            line_start = true;
+            i += 1;
+            continue;
+        }
+        if c.is_whitespace() {
+            i += 1;
+            continue;
+        }
+        // `#` lines are preprocessor output; `//` and `/* */` are comments
+        if (c == '#' && line_start) || (c == '/' && chars.get(i + 1) == Some(&'/')) {
+            while i < chars.len() && chars[i] != '\n' {
+                i += 1;
+            }
+            continue;
+        }
+        line_start = false;
+        if c == '/' && chars.get(i + 1) == Some(&'*') {
+            i += 2;
+            while i < chars.len() && !(chars[i] == '*' && chars.get(i + 1) == Some(&'/')) {
+                i += 1;
+            }
+            i += 2;
+            continue;
+        }
+
+        match c {
+            '{' => tokens.push(Token::Open),
+            '}' => tokens.push(Token::Close),
+            '[' => tokens.push(Token::OpenAttributes),
+            ']' => tokens.push(Token::CloseAttributes),
+            '=' => tokens.push(Token::Equals),
+            ';' | ',' => tokens.push(Token::Separator),
+            '-' if matches!(chars.get(i + 1), Some('>') | Some('-')) => {
+                tokens.push(Token::Edge);
+                i += 1;
+            }
+            '"' => {
+                let mut text = String::new();
+                i += 1;
+                while i < chars.len() && chars[i] != '"' {
+                    if chars[i] == '\\' && chars.get(i + 1) == Some(&'"') {
+                        i += 1;
+                    } else if chars[i] == '\\' && chars.get(i + 1) == Some(&'\n') {
+                        i += 2;
+                        continue;
+                    }
+                    text.push(chars[i]);
+                    i += 1;
+                }
+                if i >= chars.len() {
+                    return Err(ContextError::InvalidArgument("Unterminated string in DOT source".to_string()));
+                }
+                tokens.push(Token::Id(text.replace("\\n", "\n")));
+            }
+            '<' => {
+                // HTML-like labels: keep the markup as text
+                let mut depth = 0;
+                let mut text = String::new();
+                while i < chars.len() {
+                    match chars[i] {
+                        '<' => depth += 1,
+                        '>' => depth -= 1,
+                        _ => {}
+                    }
+                    text.push(chars[i]);
+                    if depth == 0 {
+                        break;
+                    }
+                    i += 1;
+                }
+                tokens.push(Token::Id(text[1..text.len().saturating_sub(1)].to_string()));
+            }
+            _ if c.is_alphanumeric() || c == '_' || c == '.' || c == '-' => {
+                let mut text = String::new();
+                while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_' || chars[i] == '.' || (chars[i] == '-' && text.chars().all(|c| c.is_ascii_digit() || c == '.' || c == '-'))) {
+                    text.push(chars[i]);
+                    i += 1;
+                }
+                tokens.push(Token::Id(text));
+                continue;
+            }
+            other => {
+                return Err(ContextError::InvalidArgument(format!("Unexpected '{}' in DOT source", other)));
+            }
+        }
+        i += 1;
+    }
+    Ok(tokens)
+}
+
+struct DotParser {
+    tokens: Vec<Token>,
+    position: usize,
+    graph: ImportedGraph,
+    /// DOT ID -> Mermaid node ID
+    ids: BTreeMap<String, String>,
+}
+
+impl DotParser {
+    fn parse(&mut self) -> Result<()> {
+        // [strict] (graph | digraph) [ID] {
+        while let Some(Token::Id(word)) = self.peek() {
+            let word = word.to_lowercase();
+            self.position += 1;
+            if word == "graph" || word == "digraph" {
+                break;
+            }
+            if word != "strict" {
+                return Err(ContextError::InvalidArgument("DOT source must start with 'graph' or 'digraph'".to_string()));
+            }
+        }
+        if let Some(Token::Id(_)) = self.peek() {
+            self.position += 1;
+        }
+        if self.next() != Some(Token::Open) {
+            return Err(ContextError::InvalidArgument("Expected '{' after the DOT graph header".to_string()));
+        }
+
+        while let Some(token) = self.next() {
+            match token {
+                Token::Close | Token::Open | Token::Separator => {}
+                Token::Id(word) if word.eq_ignore_ascii_case("subgraph") => {
+                    if let Some(Token::Id(_)) = self.peek() {
+                        self.position += 1;
+                    }
+                }
+                Token::Id(word) if ["graph", "node", "edge"].contains(&word.to_lowercase().as_str()) && self.peek() == Some(&Token::OpenAttributes) => {
+                    let attributes = self.attributes()?;
+                    if word.eq_ignore_ascii_case("graph") {
+                        self.graph_attributes(&attributes);
+                    }
+                }
+                Token::Id(name) if self.peek() == Some(&Token::Equals) => {
+                    self.position += 1;
+                    let value = self.id()?;
+                    self.graph_attributes(&[(name, value)]);
+                }
+                Token::Id(first) => self.node_or_edges(first)?,
+                other => {
+                    return Err(ContextError::InvalidArgument(format!("Unexpected {:?} in DOT source", other)));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn node_or_edges(&mut self, first: String) -> Result<()> {
+        let mut chain = vec![first];
+        while self.peek() == Some(&Token::Edge) {
+            self.position += 1;
+            chain.push(self.id()?);
+        }
+        let attributes = if self.peek() == Some(&Token::OpenAttributes) { self.attributes()? } else { vec![] };
+        let attribute = |name: &str| attributes.iter().find(|(key, _)| key.eq_ignore_ascii_case(name)).map(|(_, value)| value.clone());
+
+        if chain.len() == 1 {
+            let node_id = self.node(&chain[0]);
+            let node = self.graph.graph.nodes.iter_mut().find(|node| node.id == node_id).unwrap();
+            if let Some(label) = attribute("label") {
+                node.label = label;
+            }
+            if let Some(shape) = attribute("shape") {
+                node.node_type = node_type(&shape);
+            }
+            if let Some(section_id) = attribute("href").or_else(|| attribute("URL")).and_then(|url| url.strip_prefix('#').map(str::to_string)) {
+                self.graph.node_refs.retain(|node_ref| node_ref.node_id != node_id);
+                self.graph.node_refs.push(section_link(&node_id, &section_id));
+            }
+            return Ok(());
+        }
+
+        let label = attribute("label");
+        for pair in chain.windows(2) {
+            let (from, to) = (self.node(&pair[0]), self.node(&pair[1]));
+            self.graph.graph.edges.push(GraphEdge {
+                from,
+                to,
+                condition: label.as_deref().and_then(mermaid_parser::parse_condition),
+                label: label.clone(),
+            });
+        }
+        Ok(())
+    }
+
+    /// The Mermaid ID of a DOT node, declaring it on first use
+    fn node(&mut self, dot_id: &str) -> String {
+        if let Some(id) = self.ids.get(dot_id) {
+            return id.clone();
+        }
+        let mut base: String = dot_id.chars().map(|c| if c.is_alphanumeric() || c == '_' { c } else { '_' }).collect();
+        if base.is_empty() || base.chars().all(|c| c == '_') {
+            base = format!("n{}", self.ids.len() + 1);
+        }
+        let taken: HashSet<&str> = self.ids.values().map(String::as_str).collect();
+        let id = (1..)
+            .map(|n| if n == 1 { base.clone() } else { format!("{}_{}", base, n) })
+            .find(|candidate| !taken.contains(candidate.as_str()))
+            .unwrap();
+
+        self.ids.insert(dot_id.to_string(), id.clone());
+        self.graph.graph.nodes.push(GraphNode {
+            id: id.clone(),
+            label: dot_id.to_string(),
+            node_type: NodeType::RoundEdges,
+            ref_section_id: None,
+            metadata: None,
+        });
+        id
+    }
+
+    fn graph_attributes(&mut self, attributes: &[(String, String)]) {
+        for (name, value) in attributes {
+            if name.eq_ignore_ascii_case("rankdir") {
+                self.graph.direction = direction_from_keyword(value);
+            }
+        }
+    }
+
+    fn attributes(&mut self) -> Result<Vec<(String, String)>> {
+        let mut attributes = Vec::new();
+        // Several `[...]` lists may follow each other
+        while self.peek() == Some(&Token::OpenAttributes) {
+            self.position += 1;
+            loop {
+                match self.next() {
+                    Some(Token::CloseAttributes) => break,
+                    Some(Token::Separator) => {}
+                    Some(Token::Id(name)) => {
+                        if self.peek() == Some(&Token::Equals) {
+                            self.position += 1;
+                            attributes.push((name, self.id()?));
+                        } else {
+                            attributes.push((name, "true".to_string()));
+                        }
+                    }
+                    _ => return Err(ContextError::InvalidArgument("Unterminated attribute list in DOT source".to_string())),
+                }
+            }
+        }
+        Ok(attributes)
+    }
+
+    fn id(&mut self) -> Result<String> {
+        match self.next() {
+            Some(Token::Id(id)) => Ok(id),
+            other => Err(ContextError::InvalidArgument(format!("Expected an ID in DOT source, found {:?}", other))),
+        }
+    }
+
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.position).cloned();
+        self.position += 1;
+        token
+    }
+}
+
+/// Closest Mermaid shape for a Graphviz shape; DOT's default ellipse becomes a rounded box
+fn node_type(shape: &str) -> NodeType {
+    match shape.to_lowercase().as_str() {
+        "box" | "rect" | "rectangle" | "square" | "plaintext" | "plain" | "none" | "note" | "tab" | "folder" => NodeType::Rectangle,
+        "circle" | "doublecircle" | "point" => NodeType::Circle,
+        "diamond" | "mdiamond" => NodeType::Rhombus,
+        "hexagon" => NodeType::Hexagon,
+        "cylinder" => NodeType::Cylindrical,
+        "parallelogram" => NodeType::Parallelogram,
+        "trapezium" | "invtrapezium" => NodeType::Trapezoid,
+        "component" | "box3d" => NodeType::Subroutine,
+        "cds" | "rarrow" | "larrow" => NodeType::Asymmetric,
+        _ => NodeType::RoundEdges,
+    }
+}
+
+fn direction_from_keyword(keyword: &str) -> FlowDirection {
+    match keyword.trim().to_uppercase().as_str() {
+        "BT" => FlowDirection::BottomUp,
+        "LR" => FlowDirection::LeftRight,
+        "RL" => FlowDirection::RightLeft,
+        _ => FlowDirection::TopDown,
+    }
+}
+
+fn is_mermaid_id(id: &str) -> bool {
+    !id.is_empty() && id.chars().all(|c| c.is_alphanumeric() || c == '_')
+}
+
+fn section_link(node_id: &str, section_id: &str) -> NodeReference {
+    NodeReference {
+        node_id: node_id.to_string(),
+        section_id: section_id.to_string(),
+        click_action: format!("#{}", section_id),
+        tooltip: None,
+    }
+}
+
+fn property_list(properties: &BTreeMap<String, String>) -> String {
+    properties.iter().map(|(name, value)| format!("{}:{}", name, value)).collect::<Vec<_>>().join(",")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::exporters::flow_json_exporter::export_flow_json;
+
+    #[test]
+    fn test_flow_json_round_trip() {
+        let code = "flowchart LR\n  A[Plan] --> B(Build)\n  subgraph ship[Ship]\n    B -->|cond: risk > 3| C[Review]\n  end\n  click A \"#intent-1\"\n  click ship \"#process-1\"\n  style B fill:#f9f";
+        let mut flow = FlowGraph {
+            id: "flow-1".to_string(),
+            version: "1.0".to_string(),
+            title: None,
+            mermaid_code: code.to_string(),
+            parsed_graph: GraphStructure { nodes: vec![], edges: vec![], subgraphs: vec![] },
+            node_refs: vec![],
+            layout: None,
+            node_metadata: BTreeMap::new(),
+        };
+        mermaid_parser::enrich_flow_graph(&mut flow).unwrap();
+        let json = serde_json::to_string(&export_flow_json(&flow)).unwrap();
+
+        let imported = import_graph(&json, GraphFormat::Json).unwrap();
+        assert_eq!(imported.direction, FlowDirection::LeftRight);
+        assert_eq!(imported.node_refs, flow.node_refs);
+        assert_eq!(imported.layout.unwrap().positions.len(), 3);
+        assert_eq!(imported.styling, vec!["style B fill:#f9f"]);
+
+        let reparsed = mermaid_parser::parse_mermaid(&ImportedGraph { layout: None, ..parse_flow_json(&json).unwrap() }.to_mermaid()).unwrap();
+        assert_eq!(reparsed.nodes, flow.parsed_graph.nodes.iter().map(|n| GraphNode { ref_section_id: None, ..n.clone() }).collect::<Vec<_>>());
+        assert_eq!(reparsed.edges, flow.parsed_graph.edges);
+        assert_eq!(reparsed.subgraphs[0].node_ids, vec!["B", "C"]);
+        assert_eq!(reparsed.subgraphs[0].title, "Ship");
+    }
+
+    #[test]
+    fn test_flow_json_minimal_and_invalid() {
+        let imported = import_graph(r#"{"nodes":[{"id":"A","sectionId":"intent-1"},{"id":"B"}],"edges":[{"from":"A","to":"B"}]}"#, GraphFormat::Json).unwrap();
+        assert_eq!(imported.graph.nodes[0].label, "A");
+        assert_eq!(imported.node_refs[0].click_action, "#intent-1");
+        assert!(imported.layout.is_none());
+
+        assert!(import_graph(r#"{"nodes":[{"id":"A B"}]}"#, GraphFormat::Json).is_err());
+        assert!(import_graph(r#"{"nodes":[{"id":"A"}],"edges":[{"from":"A","to":"Z"}]}"#, GraphFormat::Json).is_err());
+        assert!(import_graph(r#"{"nodes":[]}"#, GraphFormat::Json).is_err());
+        assert!(import_graph(r#"{"nodes":[{"id":"A"}],"subgraphs":[{"id":"g","nodeIds":["Z"]}]}"#, GraphFormat::Json).is_err());
+        assert!(import_graph(r#"{"nodes":[{"id":"A"}],"subgraphs":[{"id":"g","parentId":"h"},{"id":"h"}]}"#, GraphFormat::Json).is_err());
+        assert!(import_graph(r#"{"nodes":[{"id":"A"}],"subgraphs":[{"id":"g","nodeIds":["A"]},{"id":"h","nodeIds":["A"]}]}"#, GraphFormat::Json).is_err());
+    }
+
+    #[test]
+    fn test_parse_dot() {
+        let dot = r##"
+            // release plan
+            digraph release {
+                rankdir=LR;
+                node [shape=box];
+                start [label="Plan it", href="#intent-1"];
+                "build step" [shape=diamond];
+                start -> "build step" -> done [label="ok"];
+                subgraph cluster_qa { done; }
+                /* trailing comment */
+            }
+        "##;
+        let imported = import_graph(dot, GraphFormat::Dot).unwrap();
+
+        assert_eq!(imported.direction, FlowDirection::LeftRight);
+        let nodes: Vec<_> = imported.graph.nodes.iter().map(|n| (n.id.as_str(), n.label.as_str())).collect();
+        assert_eq!(nodes, vec![("start", "Plan it"), ("build_step", "build step"), ("done", "done")]);
+        assert_eq!(imported.graph.nodes[1].node_type, NodeType::Rhombus);
+        assert_eq!(imported.graph.edges.len(), 2);
+        assert_eq!(imported.graph.edges[1].label.as_deref(), Some("ok"));
+        assert_eq!(imported.node_refs[0].section_id, "intent-1");
+
+        let code = imported.to_mermaid();
+        assert!(code.starts_with("```mermaid\nflowchart LR\n"));
+        assert!(code.contains("click start \"#intent-1\""));
+    }
+
+    #[test]
+    fn test_parse_dot_errors() {
+        assert!(parse_dot("flowchart TD").is_err());
+        assert!(parse_dot("digraph { a [label=\"open }").is_err());
+        assert!(import_graph("digraph {}", GraphFormat::Dot).is_err());
+    }
+}
@@ -0,0 +1,164 @@
+use chrono::{Datelike, NaiveDate};
+use std::collections::HashMap;
+
+/// Variables every document can reference without declaring them
+pub const BUILTIN_VARIABLES: &[&str] = &["date.today", "date.iso", "date.year"];
+
+/// Date and number conventions of a locale
+///
+/// The default is the locale-neutral ISO style: `2025-10-09` and `1234.5`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LocaleFormat {
+    /// chrono format string for dates
+    pub date_pattern: &'static str,
+    pub decimal_separator: char,
+    /// Between groups of three integer digits; no grouping when unset
+    pub group_separator: Option<char>,
+}
+
+impl Default for LocaleFormat {
+    fn default() -> Self {
+        LocaleFormat::ISO
+    }
+}
+
+/// Keyed by lowercase `language` or `language-region`; the exact tag is tried before its language
+const LOCALES: &[(&str, LocaleFormat)] = &[
+    ("en", LocaleFormat::new("%m/%d/%Y", '.', Some(','))),
+    ("en-au", LocaleFormat::new("%d/%m/%Y", '.', Some(','))),
+    ("en-gb", LocaleFormat::new("%d/%m/%Y", '.', Some(','))),
+    ("en-ie", LocaleFormat::new("%d/%m/%Y", '.', Some(','))),
+    ("en-in", LocaleFormat::new("%d/%m/%Y", '.', Some(','))),
+    ("en-nz", LocaleFormat::new("%d/%m/%Y", '.', Some(','))),
+    ("de", LocaleFormat::new("%d.%m.%Y", ',', Some('.'))),
+    ("de-ch", LocaleFormat::new("%d.%m.%Y", '.', Some('\u{2019}'))),
+    ("es", LocaleFormat::new("%d/%m/%Y", ',', Some('.'))),
+    ("fr", LocaleFormat::new("%d/%m/%Y", ',', Some('\u{202f}'))),
+    ("it", LocaleFormat::new("%d/%m/%Y", ',', Some('.'))),
+    ("ja", LocaleFormat::new("%Y/%m/%d", '.', Some(','))),
+    ("ko", LocaleFormat::new("%Y. %m. %d.", '.', Some(','))),
+    ("nl", LocaleFormat::new("%d-%m-%Y", ',', Some('.'))),
+    ("pl", LocaleFormat::new("%d.%m.%Y", ',', Some('\u{a0}'))),
+    ("pt", LocaleFormat::new("%d/%m/%Y", ',', Some('.'))),
+    ("ru", LocaleFormat::new("%d.%m.%Y", ',', Some('\u{a0}'))),
+    ("sv", LocaleFormat::new("%Y-%m-%d", ',', Some('\u{a0}'))),
+    ("zh", LocaleFormat::new("%Y/%m/%d", '.', Some(','))),
+];
+
+impl LocaleFormat {
+    pub const ISO: LocaleFormat = LocaleFormat::new("%Y-%m-%d", '.', None);
+
+    const fn new(date_pattern: &'static str, decimal_separator: char, group_separator: Option<char>) -> Self {
+        LocaleFormat { date_pattern, decimal_separator, group_separator }
+    }
+
+    /// Conventions for a BCP 47 tag such as `de-DE` or `pt_BR`; ISO for unknown locales
+    pub fn for_locale(tag: &str) -> Self {
+        let tag = tag.trim().replace('_', "-").to_lowercase();
+        let mut parts = tag.split('-');
+        let language = parts.next().unwrap_or("");
+        let language_region = parts.next().map(|region| format!("{}-{}", language, region));
+
+        let lookup = |key: &str| LOCALES.iter().find(|(known, _)| *known == key).map(|(_, format)| *format);
+        language_region
+            .and_then(|key| lookup(&key))
+            .or_else(|| lookup(language))
+            .unwrap_or(LocaleFormat::ISO)
+    }
+
+    pub fn format_date(&self, date: NaiveDate) -> String {
+        date.format(self.date_pattern).to_string()
+    }
+
+    /// The number rounded to `decimals` places, with the locale's separators
+    pub fn format_number(&self, value: f64, decimals: usize) -> String {
+        let fixed = format!("{:.*}", decimals, value.abs());
+        let (integer, fraction) = fixed.split_once('.').unwrap_or((&fixed, ""));
+        let negative = value < 0.0 && fixed.chars().any(|c| c.is_ascii_digit() && c != '0');
+
+        let mut formatted = String::new();
+        if negative {
+            formatted.push('-');
+        }
+        formatted.push_str(&self.group_digits(integer));
+        if !fraction.is_empty() {
+            formatted.push(self.decimal_separator);
+            formatted.push_str(fraction);
+        }
+        formatted
+    }
+
+    pub fn format_integer(&self, value: i64) -> String {
+        let digits = value.unsigned_abs().to_string();
+        let sign = if value < 0 { "-" } else { "" };
+        format!("{}{}", sign, self.group_digits(&digits))
+    }
+
+    fn group_digits(&self, digits: &str) -> String {
+        let Some(separator) = self.group_separator else {
+            return digits.to_string();
+        };
+        let mut grouped = String::new();
+        for (i, digit) in digits.chars().enumerate() {
+            if i > 0 && (digits.len() - i).is_multiple_of(3) {
+                grouped.push(separator);
+            }
+            grouped.push(digit);
+        }
+        grouped
+    }
+}
+
+pub fn is_builtin_variable(name: &str) -> bool {
+    BUILTIN_VARIABLES.contains(&name)
+}
+
+/// Values of the built-in variables on `today`
+///
+/// `date.today` follows the locale; `date.iso` is always `YYYY-MM-DD`.
+pub fn builtin_variables(format: &LocaleFormat, today: NaiveDate) -> HashMap<String, String> {
+    HashMap::from([
+        ("date.today".to_string(), format.format_date(today)),
+        ("date.iso".to_string(), LocaleFormat::ISO.format_date(today)),
+        ("date.year".to_string(), today.year().to_string()),
+    ])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date() -> NaiveDate {
+        NaiveDate::from_ymd_opt(2025, 10, 9).unwrap()
+    }
+
+    #[test]
+    fn test_for_locale() {
+        assert_eq!(LocaleFormat::for_locale("en-US").format_date(date()), "10/09/2025");
+        assert_eq!(LocaleFormat::for_locale("en_GB").format_date(date()), "09/10/2025");
+        assert_eq!(LocaleFormat::for_locale("de-AT").format_date(date()), "09.10.2025");
+        assert_eq!(LocaleFormat::for_locale("DE").format_date(date()), "09.10.2025");
+        assert_eq!(LocaleFormat::for_locale("xx-YY"), LocaleFormat::ISO);
+        assert_eq!(LocaleFormat::default().format_date(date()), "2025-10-09");
+    }
+
+    #[test]
+    fn test_format_number() {
+        let de = LocaleFormat::for_locale("de-DE");
+        assert_eq!(de.format_number(1234567.891, 2), "1.234.567,89");
+        assert_eq!(de.format_integer(-1234), "-1.234");
+        assert_eq!(LocaleFormat::for_locale("en").format_number(999.5, 0), "1,000");
+        assert_eq!(LocaleFormat::ISO.format_number(-0.001, 2), "0.00");
+        assert_eq!(LocaleFormat::ISO.format_integer(1234567), "1234567");
+    }
+
+    #[test]
+    fn test_builtin_variables() {
+        let variables = builtin_variables(&LocaleFormat::for_locale("fr-FR"), date());
+
+        assert_eq!(variables["date.today"], "09/10/2025");
+        assert_eq!(variables["date.iso"], "2025-10-09");
+        assert_eq!(variables["date.year"], "2025");
+        assert!(variables.keys().all(|name| is_builtin_variable(name)));
+    }
+}
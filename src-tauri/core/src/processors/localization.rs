@@ -0,0 +1,53 @@
+use crate::models::Section;
+
+/// Replace each section's content with its variant in the preferred language
+///
+/// Sections without a matching translation keep their default content.
+/// See [`Section::localized_content`] for the fallback rules.
+pub fn localize_sections(sections: &mut [Section], lang: &str) {
+    for section in sections.iter_mut() {
+        let localized = section.localized_content(lang).to_string();
+        section.content = localized;
+        if !section.children.is_empty() {
+            localize_sections(&mut section.children, lang);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeMap;
+
+    fn section(id: &str, content: &str, translations: &[(&str, &str)]) -> Section {
+        Section {
+            id: id.to_string(),
+            section_type: "intent".to_string(),
+            content: content.to_string(),
+            ref_target: None,
+            tags: vec![],
+            translations: translations
+                .iter()
+                .map(|(lang, text)| (lang.to_string(), text.to_string()))
+                .collect::<BTreeMap<_, _>>(),
+            children: vec![],
+            budget: None,
+            transclusions: vec![],
+            sensitive: false,
+            encrypted: false,
+        }
+    }
+
+    #[test]
+    fn test_localize_sections() {
+        let mut sections = vec![
+            section("a", "Intent", &[("de", "Absicht")]),
+            section("b", "Process", &[("fr", "Processus")]),
+        ];
+
+        localize_sections(&mut sections, "de-CH");
+
+        assert_eq!(sections[0].content, "Absicht");
+        assert_eq!(sections[1].content, "Process");
+    }
+}
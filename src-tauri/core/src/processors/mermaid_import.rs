@@ -0,0 +1,130 @@
+use crate::error::{ContextError, Result};
+use crate::parsers::mermaid_parser;
+
+/// Pull a Mermaid flowchart out of pasted text, e.g. a chat answer
+///
+/// Takes the first ```` ```mermaid ```` (or `~~~mermaid`) fence, else the
+/// first plain fence holding a flowchart, else unfenced code from the first
+/// `flowchart`/`graph` header up to the next blank line. The result is
+/// dedented, validated and wrapped in a ```` ```mermaid ```` fence as stored
+/// in `<diagram>`.
+pub fn extract_pasted_mermaid(text: &str) -> Result<String> {
+    let text = text.replace("\r\n", "\n");
+    let lines: Vec<&str> = text.lines().collect();
+
+    let code = fenced_block(&lines, |info, _| info.eq_ignore_ascii_case("mermaid"))
+        .or_else(|| fenced_block(&lines, |info, code| info.is_empty() && is_flowchart(code)))
+        .or_else(|| unfenced_block(&lines))
+        .ok_or_else(|| ContextError::InvalidArgument("No Mermaid flowchart found in the pasted text".to_string()))?;
+
+    if !is_flowchart(&code) {
+        let kind = code.split_whitespace().next().unwrap_or("");
+        return Err(ContextError::InvalidArgument(format!(
+            "Only flowcharts can be imported, found '{}'",
+            kind
+        )));
+    }
+    let graph = mermaid_parser::parse_mermaid(&code)?;
+    if graph.nodes.is_empty() {
+        return Err(ContextError::InvalidArgument("The pasted flowchart has no nodes".to_string()));
+    }
+
+    Ok(format!("```mermaid\n{}\n```", code))
+}
+
+/// Body of the first fence whose info string and body pass `accept`
+fn fenced_block(lines: &[&str], accept: impl Fn(&str, &str) -> bool) -> Option<String> {
+    let mut iter = lines.iter().enumerate();
+    while let Some((start, line)) = iter.next() {
+        let trimmed = line.trim_start();
+        let Some(marker) = ["```", "~~~"].into_iter().find(|marker| trimmed.starts_with(marker)) else {
+            continue;
+        };
+        let info = trimmed.trim_start_matches(marker.chars().next().unwrap()).trim();
+        let end = lines[start + 1..]
+            .iter()
+            .position(|line| line.trim_start().starts_with(marker))
+            .map(|offset| start + 1 + offset)
+            .unwrap_or(lines.len());
+        let code = dedent(&lines[start + 1..end]);
+        if accept(info, &code) {
+            return Some(code);
+        }
+        // Skip the body so a fence inside it is not mistaken for an opening one
+        iter.nth(end - start - 1);
+    }
+    None
+}
+
+fn unfenced_block(lines: &[&str]) -> Option<String> {
+    let start = lines.iter().position(|line| is_flowchart(line))?;
+    let end = lines[start..]
+        .iter()
+        .position(|line| line.trim().is_empty())
+        .map(|offset| start + offset)
+        .unwrap_or(lines.len());
+    Some(dedent(&lines[start..end]))
+}
+
+fn is_flowchart(code: &str) -> bool {
+    code.lines()
+        .map(str::trim)
+        .find(|line| !line.is_empty() && !line.starts_with("%%"))
+        .and_then(|line| line.split_whitespace().next())
+        .is_some_and(|keyword| keyword == "flowchart" || keyword == "graph")
+}
+
+/// Remove the indentation shared by all non-blank lines, and surrounding blank lines
+fn dedent(lines: &[&str]) -> String {
+    let indent = lines
+        .iter()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| line.len() - line.trim_start().len())
+        .min()
+        .unwrap_or(0);
+    lines
+        .iter()
+        .map(|line| line.get(indent..).unwrap_or("").trim_end())
+        .collect::<Vec<_>>()
+        .join("\n")
+        .trim_matches('\n')
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract_fenced_mermaid() {
+        let pasted = "Sure! Here is the diagram:\n\n```bash\nflowchart TD\n```\n\n```Mermaid\r\n    flowchart LR\r\n      A[Start] --> B[End]\r\n```\n\nLet me know!";
+        assert_eq!(
+            extract_pasted_mermaid(pasted).unwrap(),
+            "```mermaid\nflowchart LR\n  A[Start] --> B[End]\n```"
+        );
+    }
+
+    #[test]
+    fn test_extract_plain_fence_and_unfenced_code() {
+        let fenced = "```\ngraph TD\n  A[One] --> B[Two]\n```";
+        assert_eq!(extract_pasted_mermaid(fenced).unwrap(), "```mermaid\ngraph TD\n  A[One] --> B[Two]\n```");
+
+        let unfenced = "Try this:\nflowchart TD\n  A[One] --> B[Two]\n\nThe arrow means 'then'.";
+        assert_eq!(
+            extract_pasted_mermaid(unfenced).unwrap(),
+            "```mermaid\nflowchart TD\n  A[One] --> B[Two]\n```"
+        );
+    }
+
+    #[test]
+    fn test_rejects_text_without_flowchart() {
+        let cases = [
+            "Just some prose",
+            "```mermaid\nsequenceDiagram\n  A->>B: hi\n```",
+            "```mermaid\nflowchart TD\n```",
+        ];
+        for case in cases {
+            assert!(matches!(extract_pasted_mermaid(case), Err(ContextError::InvalidArgument(_))), "{}", case);
+        }
+    }
+}
@@ -0,0 +1,87 @@
+pub mod auto_layout;
+pub mod budget_report;
+pub mod calendar_events;
+pub mod citations;
+pub mod click_suggestions;
+pub mod content_blocks;
+pub mod content_summary;
+pub mod context_assembly;
+pub mod document_compare;
+pub mod document_edits;
+pub mod document_merge;
+pub mod document_stats;
+pub mod duplicate_content;
+pub mod effort_rollup;
+pub mod external_refs;
+pub mod flow_checklist;
+pub mod flow_navigation;
+pub mod flow_simulation;
+pub mod flow_traversal;
+pub mod graph_import;
+pub mod locale_format;
+pub mod localization;
+pub mod mermaid_import;
+pub mod portable_format;
+pub mod redaction;
+pub mod reminders;
+pub mod section_dependencies;
+pub mod section_encryption;
+pub mod section_filter;
+pub mod section_frontmatter;
+pub mod section_import;
+pub mod section_index;
+pub mod section_merge;
+pub mod section_split;
+pub mod section_templates;
+pub mod section_type_inference;
+pub mod status_sync;
+pub mod tag_index;
+pub mod template_extraction;
+pub mod variable_resolver;
+pub mod variable_sheet;
+pub mod workspace_graph;
+pub mod workspace_index;
+
+pub use auto_layout::*;
+pub use budget_report::*;
+pub use calendar_events::*;
+pub use citations::*;
+pub use click_suggestions::*;
+pub use content_blocks::*;
+pub use content_summary::*;
+pub use context_assembly::*;
+pub use document_compare::*;
+pub use document_edits::*;
+pub use document_merge::*;
+pub use document_stats::*;
+pub use duplicate_content::*;
+pub use effort_rollup::*;
+pub use external_refs::*;
+pub use flow_checklist::*;
+pub use flow_navigation::*;
+pub use flow_simulation::*;
+pub use flow_traversal::*;
+pub use graph_import::*;
+pub use locale_format::*;
+pub use localization::*;
+pub use mermaid_import::*;
+pub use portable_format::*;
+pub use redaction::*;
+pub use reminders::*;
+pub use section_dependencies::*;
+pub use section_encryption::*;
+pub use section_filter::*;
+pub use section_frontmatter::*;
+pub use section_import::*;
+pub use section_index::*;
+pub use section_merge::*;
+pub use section_split::*;
+pub use section_templates::*;
+pub use section_type_inference::*;
+pub use status_sync::*;
+pub use tag_index::*;
+pub use template_extraction::*;
+pub use variable_resolver::*;
+pub use variable_sheet::*;
+pub use workspace_graph::*;
+pub use workspace_index::*;
@@ -0,0 +1,174 @@
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use crate::models::*;
+use crate::processors::external_refs::{local_target, LINK_TARGET};
+
+/// Line endings of saved document files
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum LineEnding {
+    #[default]
+    Lf,
+    Crlf,
+    /// CRLF on Windows, LF elsewhere
+    Native,
+}
+
+impl LineEnding {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LineEnding::Lf => "\n",
+            LineEnding::Crlf => "\r\n",
+            LineEnding::Native if cfg!(windows) => "\r\n",
+            LineEnding::Native => "\n",
+        }
+    }
+}
+
+/// Text with CRLF and lone CR line breaks turned into LF
+pub fn to_lf(text: &str) -> String {
+    text.replace("\r\n", "\n").replace('\r', "\n")
+}
+
+/// Text with every line break, whatever its kind, written as `line_ending`
+pub fn with_line_ending(text: &str, line_ending: LineEnding) -> String {
+    let text = to_lf(text);
+    match line_ending.as_str() {
+        "\n" => text,
+        ending => text.replace('\n', ending),
+    }
+}
+
+/// A stored path in the platform-neutral form: `/` separators only
+pub fn portable_path(path: &str) -> String {
+    path.replace('\\', "/")
+}
+
+/// A stored path, in either form, with this platform's separators
+pub fn platform_path(path: &str) -> PathBuf {
+    PathBuf::from(portable_path(path).replace('/', std::path::MAIN_SEPARATOR_STR))
+}
+
+/// Bring a document edited on any OS into the form it is saved in
+///
+/// Line breaks in section content, translations, variable values and the flow
+/// diagram become LF; the file's own line endings are applied when it is
+/// written. Document paths in refTargets and local markdown link and image
+/// targets get `/` separators. Returns whether anything changed.
+pub fn normalize_document(doc: &mut ContextDocument) -> bool {
+    let mut changed = normalize_sections(&mut doc.sections);
+    for var in &mut doc.variables {
+        changed |= set(&mut var.value, to_lf);
+    }
+    if let Some(flow) = &mut doc.flow_graph {
+        changed |= set(&mut flow.mermaid_code, to_lf);
+    }
+    changed
+}
+
+fn normalize_sections(sections: &mut [Section]) -> bool {
+    let mut changed = false;
+    for section in sections {
+        for content in std::iter::once(&mut section.content).chain(section.translations.values_mut()) {
+            changed |= set(content, |content| portable_links(&to_lf(content)));
+        }
+        if let Some(ref_target) = &mut section.ref_target {
+            changed |= set(ref_target, portable_ref_target);
+        }
+        changed |= normalize_sections(&mut section.children);
+    }
+    changed
+}
+
+/// Replace `value` with its normalized form, reporting whether it differed
+fn set(value: &mut String, normalize: impl FnOnce(&str) -> String) -> bool {
+    let normalized = normalize(value);
+    let changed = *value != normalized;
+    *value = normalized;
+    changed
+}
+
+/// refTarget tokens with the document path before `#` made portable
+fn portable_ref_target(ref_target: &str) -> String {
+    if !ref_target.contains('\\') {
+        return ref_target.to_string();
+    }
+    ref_target
+        .split_whitespace()
+        .map(|token| match token.split_once('#') {
+            Some((path, section_id)) if !path.is_empty() => format!("{}#{}", portable_path(path), section_id),
+            _ => token.to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Markdown with local link and image targets made portable; URLs are left alone
+fn portable_links(content: &str) -> String {
+    if !content.contains('\\') {
+        return content.to_string();
+    }
+    LINK_TARGET
+        .replace_all(content, |caps: &regex::Captures| {
+            let target = caps.get(1).unwrap();
+            if local_target(target.as_str()).is_none() {
+                return caps[0].to_string();
+            }
+            let whole = caps.get(0).unwrap();
+            let (start, end) = (target.start() - whole.start(), target.end() - whole.start());
+            format!("{}{}{}", &whole.as_str()[..start], portable_path(target.as_str()), &whole.as_str()[end..])
+        })
+        .into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_line_endings() {
+        assert_eq!(to_lf("a\r\nb\rc\nd"), "a\nb\nc\nd");
+        assert_eq!(with_line_ending("a\r\nb\nc", LineEnding::Crlf), "a\r\nb\r\nc");
+        assert_eq!(with_line_ending("a\r\nb\nc", LineEnding::Lf), "a\nb\nc");
+    }
+
+    #[test]
+    fn test_paths() {
+        assert_eq!(portable_path(r"..\shared\brief.xml"), "../shared/brief.xml");
+        let expected: PathBuf = ["..", "shared", "brief.xml"].iter().collect();
+        assert_eq!(platform_path(r"..\shared\brief.xml"), expected);
+        assert_eq!(platform_path("../shared/brief.xml"), expected);
+    }
+
+    #[test]
+    fn test_normalize_document() {
+        let mut intent = Section::new(
+            "intent-1",
+            "intent",
+            "# Intent\r\nSee [spec](docs\\spec.md#scope) and ![flow](img\\flow.png \"Flow\")\r\n[web](https://example.com/a\\b)",
+        );
+        intent.ref_target = Some("intent-2 ..\\shared\\brief.xml#intent-1".to_string());
+        intent.translations.insert("de".to_string(), "Siehe\r[Spez](docs\\spec.md)".to_string());
+        let mut doc = ContextDocument::builder()
+            .title("Plan")
+            .variable("owner", "Kim\r\nand Lee")
+            .add_section(intent)
+            .section("intent-2", "intent", "More")
+            .flow("flowchart TD\r\n  A --> B")
+            .build()
+            .unwrap();
+
+        assert!(normalize_document(&mut doc));
+        let section = &doc.sections[0];
+        assert_eq!(
+            section.content,
+            "# Intent\nSee [spec](docs/spec.md#scope) and ![flow](img/flow.png \"Flow\")\n[web](https://example.com/a\\b)"
+        );
+        assert_eq!(section.ref_target.as_deref(), Some("intent-2 ../shared/brief.xml#intent-1"));
+        assert_eq!(section.translations["de"], "Siehe\n[Spez](docs/spec.md)");
+        assert_eq!(doc.variables[0].value, "Kim\nand Lee");
+        assert_eq!(doc.flow_graph.as_ref().unwrap().mermaid_code, "flowchart TD\n  A --> B");
+
+        assert!(!normalize_document(&mut doc));
+    }
+}
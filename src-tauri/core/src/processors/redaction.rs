@@ -0,0 +1,202 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use crate::models::*;
+
+/// Replacement text when a profile does not set its own
+pub const DEFAULT_REDACTION_MASK: &str = "[REDACTED]";
+
+/// What happens to a section selected for redaction
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum SectionRedaction {
+    /// Keep the section, replacing its content with the mask
+    #[default]
+    Mask,
+    /// Leave the section out entirely
+    Remove,
+}
+
+/// Which variables and sections an assembly or export hides
+///
+/// The default profile redacts nothing, so internal exports are unchanged.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase", default)]
+pub struct RedactionProfile {
+    /// Mask the values of variables marked `sensitive="true"`
+    pub sensitive_variables: bool,
+    /// Redact sections marked `sensitive="true"`
+    pub sensitive_sections: bool,
+    /// Further variables to mask, by name
+    pub variables: Vec<String>,
+    /// Further sections to redact, by ID
+    pub sections: Vec<String>,
+    /// Redact sections carrying any of these tags
+    pub tags: Vec<String>,
+    pub section_mode: SectionRedaction,
+    pub mask: String,
+}
+
+impl Default for RedactionProfile {
+    fn default() -> Self {
+        RedactionProfile {
+            sensitive_variables: false,
+            sensitive_sections: false,
+            variables: Vec::new(),
+            sections: Vec::new(),
+            tags: Vec::new(),
+            section_mode: SectionRedaction::Mask,
+            mask: DEFAULT_REDACTION_MASK.to_string(),
+        }
+    }
+}
+
+impl RedactionProfile {
+    /// Everything marked sensitive is masked, for sharing with external partners
+    pub fn sensitive() -> Self {
+        RedactionProfile {
+            sensitive_variables: true,
+            sensitive_sections: true,
+            ..RedactionProfile::default()
+        }
+    }
+
+    pub fn masks_variable(&self, variable: &Variable) -> bool {
+        (self.sensitive_variables && variable.sensitive) || self.variables.contains(&variable.name)
+    }
+
+    /// Whether the section itself is selected; its subsections go with it
+    pub fn redacts_section(&self, section: &Section) -> bool {
+        (self.sensitive_sections && section.sensitive)
+            || self.sections.contains(&section.id)
+            || self.tags.iter().any(|tag| section.has_tag(tag))
+    }
+}
+
+/// Apply the profile to a document before its variables are resolved
+pub fn redact_document(doc: &mut ContextDocument, profile: &RedactionProfile) {
+    for variable in doc.variables.iter_mut().filter(|variable| profile.masks_variable(variable)) {
+        variable.value = profile.mask.clone();
+    }
+    redact_sections(&mut doc.sections, profile);
+}
+
+/// Mask or remove the sections the profile selects, at any depth
+pub fn redact_sections(sections: &mut Vec<Section>, profile: &RedactionProfile) {
+    if profile.section_mode == SectionRedaction::Remove {
+        sections.retain(|section| !profile.redacts_section(section));
+    }
+    for section in sections.iter_mut() {
+        if profile.redacts_section(section) {
+            mask_section(section, &profile.mask);
+        } else {
+            redact_sections(&mut section.children, profile);
+        }
+    }
+}
+
+/// Mask the values in a resolved variable map, including call-time overrides
+///
+/// Names the profile lists are masked whether or not the document declares them.
+pub fn redact_variable_map(var_map: &mut HashMap<String, String>, variables: &[Variable], profile: &RedactionProfile) {
+    let masked = variables
+        .iter()
+        .filter(|variable| profile.masks_variable(variable))
+        .map(|variable| &variable.name)
+        .chain(&profile.variables);
+    for name in masked {
+        if let Some(value) = var_map.get_mut(name) {
+            value.clone_from(&profile.mask);
+        }
+    }
+}
+
+fn mask_section(section: &mut Section, mask: &str) {
+    section.content = mask.to_string();
+    for content in section.translations.values_mut() {
+        *content = mask.to_string();
+    }
+    section.transclusions.clear();
+    for child in &mut section.children {
+        mask_section(child, mask);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn document() -> ContextDocument {
+        let mut secret = Section::new("process-1", "process", "Call ${apiKey}");
+        secret.sensitive = true;
+        secret.children.push(Section::new("process-2", "process", "Nested detail"));
+        let mut pricing = Section::new("alternatives-1", "alternatives", "Discount tiers");
+        pricing.tags = vec!["pricing".to_string()];
+
+        let mut doc = ContextDocument::builder()
+            .title("Partner brief")
+            .variable("apiKey", "sk-123")
+            .variable("goal", "Ship v1")
+            .section("intent-1", "intent", "Goal: ${goal}")
+            .add_section(secret)
+            .add_section(pricing)
+            .build()
+            .unwrap();
+        doc.variables[0].sensitive = true;
+        doc
+    }
+
+    #[test]
+    fn test_default_profile_redacts_nothing() {
+        let mut doc = document();
+        redact_document(&mut doc, &RedactionProfile::default());
+        assert_eq!(doc, document());
+    }
+
+    #[test]
+    fn test_sensitive_profile_masks_marked_content() {
+        let mut doc = document();
+        redact_document(&mut doc, &RedactionProfile::sensitive());
+
+        assert_eq!(doc.variables[0].value, DEFAULT_REDACTION_MASK);
+        assert_eq!(doc.variables[1].value, "Ship v1");
+        assert_eq!(doc.sections[1].content, DEFAULT_REDACTION_MASK);
+        assert_eq!(doc.sections[1].children[0].content, DEFAULT_REDACTION_MASK);
+        assert_eq!(doc.sections[2].content, "Discount tiers");
+    }
+
+    #[test]
+    fn test_remove_by_tag_and_id() {
+        let mut doc = document();
+        let profile = RedactionProfile {
+            tags: vec!["Pricing".to_string()],
+            sections: vec!["process-2".to_string()],
+            section_mode: SectionRedaction::Remove,
+            ..RedactionProfile::default()
+        };
+        redact_document(&mut doc, &profile);
+
+        let ids: Vec<&str> = doc.sections.iter().map(|section| section.id.as_str()).collect();
+        assert_eq!(ids, vec!["intent-1", "process-1"]);
+        assert!(doc.sections[1].children.is_empty());
+    }
+
+    #[test]
+    fn test_redact_variable_map() {
+        let doc = document();
+        let mut var_map = HashMap::from([
+            ("apiKey".to_string(), "override".to_string()),
+            ("token".to_string(), "abc".to_string()),
+            ("goal".to_string(), "Ship v1".to_string()),
+        ]);
+        let profile = RedactionProfile {
+            variables: vec!["token".to_string()],
+            mask: "***".to_string(),
+            ..RedactionProfile::sensitive()
+        };
+        redact_variable_map(&mut var_map, &doc.variables, &profile);
+
+        assert_eq!(var_map["apiKey"], "***");
+        assert_eq!(var_map["token"], "***");
+        assert_eq!(var_map["goal"], "Ship v1");
+    }
+}
@@ -0,0 +1,144 @@
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use crate::models::*;
+use crate::processors::calendar_events::humanize;
+use crate::processors::content_summary::first_heading;
+use crate::processors::flow_navigation::flatten;
+use crate::processors::section_frontmatter::section_frontmatter;
+
+/// Frontmatter key holding a section's due date
+pub const DUE_FRONTMATTER_KEY: &str = "due";
+
+/// Section statuses that mean the work is done, so its due date no longer needs a reminder
+pub const DONE_STATUSES: [&str; 2] = ["final", "done"];
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ReminderSource {
+    Section,
+    Variable,
+}
+
+/// Something in the document that is due soon or overdue
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Reminder {
+    /// Stable within the document: `section:<id>` or `var:<name>`
+    pub key: String,
+    /// ISO date, `YYYY-MM-DD`
+    pub due: String,
+    /// The section's first heading or ID, or the humanized variable name
+    pub title: String,
+    pub source: ReminderSource,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub section_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub variable: Option<String>,
+    /// Days from today to the due date; negative when overdue
+    pub days_left: i64,
+    pub overdue: bool,
+}
+
+/// Overdue items and those due within `within_days` of `today`, soonest first
+///
+/// Sections are due by a `due: YYYY-MM-DD` frontmatter field, at any depth,
+/// unless their `status` is one of [`DONE_STATUSES`]; variables by their
+/// `due` attribute. Dates that do not parse are ignored.
+pub fn collect_reminders(doc: &ContextDocument, today: NaiveDate, within_days: i64) -> Vec<Reminder> {
+    let mut reminders = Vec::new();
+
+    for section in flatten(&doc.sections) {
+        let frontmatter = section_frontmatter(section);
+        let done = frontmatter
+            .get("status")
+            .is_some_and(|status| DONE_STATUSES.contains(&status.trim().to_lowercase().as_str()));
+        let Some(due) = frontmatter.get(DUE_FRONTMATTER_KEY).and_then(|due| parse_date(due)) else {
+            continue;
+        };
+        if !done {
+            reminders.push(Reminder {
+                key: format!("section:{}", section.id),
+                due: due.to_string(),
+                title: first_heading(&section.content).unwrap_or_else(|| section.id.clone()),
+                source: ReminderSource::Section,
+                section_id: Some(section.id.clone()),
+                variable: None,
+                days_left: (due - today).num_days(),
+                overdue: due < today,
+            });
+        }
+    }
+
+    for var in &doc.variables {
+        let Some(due) = var.due.as_deref().and_then(parse_date) else {
+            continue;
+        };
+        reminders.push(Reminder {
+            key: format!("var:{}", var.name),
+            due: due.to_string(),
+            title: humanize(&var.name),
+            source: ReminderSource::Variable,
+            section_id: None,
+            variable: Some(var.name.clone()),
+            days_left: (due - today).num_days(),
+            overdue: due < today,
+        });
+    }
+
+    reminders.retain(|reminder| reminder.days_left <= within_days);
+    // Stable, so items due the same day keep document order
+    reminders.sort_by_key(|reminder| reminder.days_left);
+    reminders
+}
+
+fn parse_date(value: &str) -> Option<NaiveDate> {
+    NaiveDate::parse_from_str(value.trim(), "%Y-%m-%d").ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(value: &str) -> NaiveDate {
+        parse_date(value).unwrap()
+    }
+
+    fn document() -> ContextDocument {
+        let mut parent = Section::new("process-1", "process", "---\ndue: 2025-11-20\n---\n# Build\nSteps");
+        parent.children.push(Section::new("process-2", "process", "---\ndue: 2025-11-01\nstatus: Final\n---\nDone already"));
+        let mut doc = ContextDocument::builder()
+            .title("Plan")
+            .variable("launchDate", "TBD")
+            .add_section(Section::new("intent-1", "intent", "---\ndue: 2025-10-30\n---\nNo heading"))
+            .add_section(Section::new("evaluation-1", "evaluation", "---\ndue: soon\n---\n# Check"))
+            .add_section(parent)
+            .build()
+            .unwrap();
+        doc.variables[0].due = Some("2025-11-03".to_string());
+        doc
+    }
+
+    #[test]
+    fn test_collect_reminders() {
+        let reminders = collect_reminders(&document(), date("2025-11-02"), 7);
+
+        let keys: Vec<_> = reminders.iter().map(|r| r.key.as_str()).collect();
+        assert_eq!(keys, vec!["section:intent-1", "var:launchDate"]);
+        assert!(reminders[0].overdue);
+        assert_eq!(reminders[0].days_left, -3);
+        assert_eq!(reminders[0].title, "intent-1");
+        assert_eq!(reminders[1].title, "Launch date");
+        assert_eq!(reminders[1].days_left, 1);
+        assert!(!reminders[1].overdue);
+    }
+
+    #[test]
+    fn test_window_includes_later_items() {
+        let reminders = collect_reminders(&document(), date("2025-10-01"), 60);
+
+        let keys: Vec<_> = reminders.iter().map(|r| r.key.as_str()).collect();
+        assert_eq!(keys, vec!["section:intent-1", "var:launchDate", "section:process-1"]);
+        assert_eq!(reminders[2].title, "Build");
+        assert!(reminders.iter().all(|r| !r.overdue));
+    }
+}
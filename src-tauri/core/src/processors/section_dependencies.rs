@@ -0,0 +1,196 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+use crate::models::*;
+use crate::processors::flow_navigation::{flatten, local_ref_targets};
+
+/// What one section depends on through refTarget
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SectionDependencies {
+    pub section_id: String,
+    /// Sections listed in its refTarget
+    pub direct: Vec<String>,
+    /// Every section reachable through refTargets, direct ones included
+    pub transitive: Vec<String>,
+    /// Whether the section depends on itself through a chain of refTargets
+    pub in_cycle: bool,
+}
+
+/// A refTarget that contradicts the order of the flow diagram
+///
+/// `section_id` references `depends_on`, so `depends_on` should come first,
+/// but the diagram leads from the section's node to the other's node.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct OrderingConflict {
+    pub section_id: String,
+    pub depends_on: String,
+    pub node_id: String,
+    pub depends_on_node_id: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct DependencyReport {
+    pub sections: Vec<SectionDependencies>,
+    pub conflicts: Vec<OrderingConflict>,
+}
+
+/// Build the refTarget dependency graph of all sections (nested ones included)
+///
+/// Cross-document targets and targets that do not exist are ignored. With
+/// an enriched flow graph, direct dependencies are checked against the
+/// diagram: a conflict is reported when the section's node reaches the
+/// dependency's node but not the other way round.
+pub fn section_dependencies(sections: &[Section], flow: Option<&FlowGraph>) -> DependencyReport {
+    let all = flatten(sections);
+    let ids: HashSet<&str> = all.iter().map(|section| section.id.as_str()).collect();
+    let direct: BTreeMap<&str, Vec<&str>> = all
+        .iter()
+        .map(|section| {
+            let mut targets: Vec<&str> = Vec::new();
+            for target in local_ref_targets(section) {
+                if ids.contains(target) && !targets.contains(&target) {
+                    targets.push(target);
+                }
+            }
+            (section.id.as_str(), targets)
+        })
+        .collect();
+
+    let dependencies: Vec<SectionDependencies> = all
+        .iter()
+        .map(|section| {
+            let id = section.id.as_str();
+            let transitive = reachable(id, |from| direct.get(from).cloned().unwrap_or_default());
+            SectionDependencies {
+                section_id: id.to_string(),
+                direct: direct[id].iter().map(|s| s.to_string()).collect(),
+                in_cycle: transitive.contains(&id),
+                transitive: transitive.iter().map(|s| s.to_string()).collect(),
+            }
+        })
+        .collect();
+
+    let conflicts = match flow {
+        Some(flow) => ordering_conflicts(&direct, flow),
+        None => vec![],
+    };
+
+    DependencyReport {
+        sections: dependencies,
+        conflicts,
+    }
+}
+
+fn ordering_conflicts(direct: &BTreeMap<&str, Vec<&str>>, flow: &FlowGraph) -> Vec<OrderingConflict> {
+    let node_of = |section_id: &str| {
+        flow.node_refs
+            .iter()
+            .find(|node_ref| node_ref.section_id == section_id)
+            .map(|node_ref| node_ref.node_id.as_str())
+    };
+    let successors = |node_id: &str| {
+        flow.parsed_graph
+            .edges
+            .iter()
+            .filter(|edge| edge.from == node_id)
+            .map(|edge| edge.to.as_str())
+            .collect::<Vec<_>>()
+    };
+
+    let mut conflicts = Vec::new();
+    for (section_id, targets) in direct {
+        let Some(node_id) = node_of(section_id) else {
+            continue;
+        };
+        for target in targets {
+            let Some(target_node_id) = node_of(target) else {
+                continue;
+            };
+            if node_id == target_node_id {
+                continue;
+            }
+            let forward = reachable(node_id, successors).contains(&target_node_id);
+            let backward = reachable(target_node_id, successors).contains(&node_id);
+            if forward && !backward {
+                conflicts.push(OrderingConflict {
+                    section_id: section_id.to_string(),
+                    depends_on: target.to_string(),
+                    node_id: node_id.to_string(),
+                    depends_on_node_id: target_node_id.to_string(),
+                });
+            }
+        }
+    }
+    conflicts
+}
+
+/// IDs reachable from `start` in breadth-first order; includes `start` only through a cycle
+fn reachable<'a>(start: &'a str, next: impl Fn(&'a str) -> Vec<&'a str>) -> Vec<&'a str> {
+    let mut found: Vec<&str> = Vec::new();
+    let mut queue = next(start);
+    while !queue.is_empty() {
+        let current = queue.remove(0);
+        if found.contains(&current) {
+            continue;
+        }
+        found.push(current);
+        queue.extend(next(current));
+    }
+    found
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parsers::mermaid_parser::{parse_click_actions, parse_mermaid};
+
+    fn section(id: &str, ref_target: Option<&str>) -> Section {
+        let mut section = Section::new(id, "process", "");
+        section.ref_target = ref_target.map(str::to_string);
+        section
+    }
+
+    #[test]
+    fn test_transitive_dependencies() {
+        let mut parent = section("process-1", Some("process-2 other.xml#intent-1 missing"));
+        parent.children.push(section("process-2", Some("process-3")));
+        let sections = vec![parent, section("process-3", None), section("loop-a", Some("loop-b")), section("loop-b", Some("loop-a"))];
+
+        let report = section_dependencies(&sections, None);
+
+        assert_eq!(report.sections[0].direct, vec!["process-2"]);
+        assert_eq!(report.sections[0].transitive, vec!["process-2", "process-3"]);
+        assert!(!report.sections[0].in_cycle);
+        assert_eq!(report.sections[1].section_id, "process-2");
+        assert!(report.sections[3].in_cycle);
+        assert!(report.conflicts.is_empty());
+    }
+
+    #[test]
+    fn test_ordering_conflicts() {
+        let code = "flowchart TD\n  A[Plan] --> B[Build]\n  B --> C[Ship]\n  click A \"#plan\"\n  click B \"#build\"\n  click C \"#ship\"";
+        let flow = FlowGraph {
+            id: "flow-1".to_string(),
+            version: "1.0".to_string(),
+            title: None,
+            mermaid_code: code.to_string(),
+            parsed_graph: parse_mermaid(code).unwrap(),
+            node_refs: parse_click_actions(code).unwrap(),
+            layout: None,
+            node_metadata: Default::default(),
+        };
+        // Shipping builds on the plan (consistent); the plan relies on shipping (contradicts the flow)
+        let sections = vec![section("plan", Some("ship")), section("build", None), section("ship", Some("plan"))];
+
+        let report = section_dependencies(&sections, Some(&flow));
+
+        assert_eq!(
+            report.conflicts,
+            vec![OrderingConflict {
+                section_id: "plan".to_string(),
+                depends_on: "ship".to_string(),
+                node_id: "A".to_string(),
+                depends_on_node_id: "C".to_string(),
+            }]
+        );
+    }
+}
@@ -0,0 +1,193 @@
+use aes_gcm::aead::rand_core::RngCore;
+use aes_gcm::aead::{Aead, AeadCore, KeyInit, OsRng};
+use aes_gcm::{Aes256Gcm, Key, Nonce};
+use argon2::Argon2;
+use base64::{engine::general_purpose::STANDARD, Engine};
+use crate::error::{ContextError, Result};
+use crate::models::*;
+use crate::processors::document_edits::{find_section, find_section_mut};
+
+/// Shown in place of an encrypted section's content until it is unlocked
+pub const LOCKED_PLACEHOLDER: &str = "[Encrypted section]";
+
+/// First field of the ciphertext envelope, bumped if the scheme ever changes
+const ENVELOPE_VERSION: &str = "v1";
+
+const SALT_LENGTH: usize = 16;
+const NONCE_LENGTH: usize = 12;
+
+/// Encrypt text with a key derived from the passphrase (Argon2id, then AES-256-GCM)
+///
+/// The result is `v1:<salt>:<nonce>:<ciphertext>` in base64, safe to store in CDATA.
+/// Every call uses a fresh salt and nonce.
+pub fn encrypt_text(plaintext: &str, passphrase: &str) -> Result<String> {
+    let mut salt = [0u8; SALT_LENGTH];
+    OsRng.fill_bytes(&mut salt);
+    let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+    let ciphertext = cipher(passphrase, &salt)?
+        .encrypt(&nonce, plaintext.as_bytes())
+        .map_err(|_| ContextError::InvalidArgument("Could not encrypt the content".to_string()))?;
+
+    Ok([ENVELOPE_VERSION.to_string(), STANDARD.encode(salt), STANDARD.encode(nonce), STANDARD.encode(ciphertext)].join(":"))
+}
+
+/// Reverse [`encrypt_text`]; a wrong passphrase and tampered ciphertext both fail
+pub fn decrypt_text(envelope: &str, passphrase: &str) -> Result<String> {
+    let malformed = || ContextError::InvalidArgument("Encrypted content is malformed".to_string());
+    let [version, salt, nonce, ciphertext] = envelope.trim().split(':').collect::<Vec<_>>()[..] else {
+        return Err(malformed());
+    };
+    if version != ENVELOPE_VERSION {
+        return Err(ContextError::InvalidArgument(format!("Unsupported encryption version '{}'", version)));
+    }
+    let decode = |field: &str| STANDARD.decode(field).map_err(|_| malformed());
+    let (salt, nonce, ciphertext) = (decode(salt)?, decode(nonce)?, decode(ciphertext)?);
+    if nonce.len() != NONCE_LENGTH {
+        return Err(malformed());
+    }
+
+    let plaintext = cipher(passphrase, &salt)?
+        .decrypt(Nonce::from_slice(&nonce), ciphertext.as_ref())
+        .map_err(|_| ContextError::InvalidArgument("Wrong passphrase or corrupted content".to_string()))?;
+    String::from_utf8(plaintext).map_err(|_| malformed())
+}
+
+/// Encrypt the section's content and translations and mark it `encrypted`
+///
+/// Subsections are left alone; each section is encrypted on its own.
+pub fn encrypt_section(doc: &mut ContextDocument, section_id: &str, passphrase: &str) -> Result<()> {
+    if passphrase.is_empty() {
+        return Err(ContextError::InvalidArgument("The passphrase must not be empty".to_string()));
+    }
+    let section = find_section_mut(&mut doc.sections, section_id)
+        .ok_or_else(|| ContextError::SectionNotFound(section_id.to_string()))?;
+    if section.encrypted {
+        return Err(ContextError::InvalidArgument(format!("Section '{}' is already encrypted", section_id)));
+    }
+
+    section.content = encrypt_text(&section.content, passphrase)?;
+    for content in section.translations.values_mut() {
+        *content = encrypt_text(content, passphrase)?;
+    }
+    section.encrypted = true;
+    Ok(())
+}
+
+/// A decrypted copy of an encrypted section, leaving the document encrypted
+pub fn unlock_section(doc: &ContextDocument, section_id: &str, passphrase: &str) -> Result<Section> {
+    let mut section = find_section(&doc.sections, section_id)
+        .ok_or_else(|| ContextError::SectionNotFound(section_id.to_string()))?
+        .clone();
+    decrypt_in_place(&mut section, passphrase)?;
+    Ok(section)
+}
+
+/// Decrypt the section for good and drop its `encrypted` mark
+pub fn decrypt_section(doc: &mut ContextDocument, section_id: &str, passphrase: &str) -> Result<()> {
+    let section = find_section_mut(&mut doc.sections, section_id)
+        .ok_or_else(|| ContextError::SectionNotFound(section_id.to_string()))?;
+    let mut decrypted = section.clone();
+    decrypt_in_place(&mut decrypted, passphrase)?;
+    section.content = decrypted.content;
+    section.translations = decrypted.translations;
+    section.encrypted = false;
+    Ok(())
+}
+
+/// Put [`LOCKED_PLACEHOLDER`] in place of the ciphertext of encrypted sections, at any depth
+///
+/// Keeps ciphertext out of rendered and assembled output; translations of
+/// locked sections are dropped.
+pub fn lock_sections(sections: &mut [Section]) {
+    for section in sections {
+        if section.encrypted {
+            section.content = LOCKED_PLACEHOLDER.to_string();
+            section.translations.clear();
+        }
+        lock_sections(&mut section.children);
+    }
+}
+
+/// Decrypt the section's own content and translations; nothing changes if any of them fails
+fn decrypt_in_place(section: &mut Section, passphrase: &str) -> Result<()> {
+    if !section.encrypted {
+        return Err(ContextError::InvalidArgument(format!("Section '{}' is not encrypted", section.id)));
+    }
+    let content = decrypt_text(&section.content, passphrase)?;
+    let translations = section
+        .translations
+        .iter()
+        .map(|(lang, text)| Ok((lang.clone(), decrypt_text(text, passphrase)?)))
+        .collect::<Result<_>>()?;
+    section.content = content;
+    section.translations = translations;
+    Ok(())
+}
+
+fn cipher(passphrase: &str, salt: &[u8]) -> Result<Aes256Gcm> {
+    let mut key = [0u8; 32];
+    Argon2::default()
+        .hash_password_into(passphrase.as_bytes(), salt, &mut key)
+        .map_err(|e| ContextError::InvalidArgument(format!("Could not derive the key: {}", e)))?;
+    Ok(Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(&key)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn document() -> ContextDocument {
+        let mut secret = Section::new("process-1", "process", "Call ${contact} on 555-0100");
+        secret.translations.insert("de".to_string(), "Ruf ${contact} an".to_string());
+        ContextDocument::builder()
+            .title("Plan")
+            .section("intent-1", "intent", "Public goal")
+            .add_section(secret)
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let envelope = encrypt_text("secret", "correct horse").unwrap();
+
+        assert!(envelope.starts_with("v1:"));
+        assert_ne!(envelope, encrypt_text("secret", "correct horse").unwrap());
+        assert_eq!(decrypt_text(&envelope, "correct horse").unwrap(), "secret");
+        assert!(matches!(decrypt_text(&envelope, "wrong"), Err(ContextError::InvalidArgument(_))));
+        assert!(decrypt_text("v1:not:base64", "correct horse").is_err());
+    }
+
+    #[test]
+    fn test_encrypt_unlock_and_decrypt_section() {
+        let mut doc = document();
+        encrypt_section(&mut doc, "process-1", "pw").unwrap();
+
+        let stored = &doc.sections[1];
+        assert!(stored.encrypted);
+        assert!(!stored.content.contains("555-0100"));
+        assert!(!stored.translations["de"].contains("contact"));
+        assert!(encrypt_section(&mut doc, "process-1", "pw").is_err());
+
+        let unlocked = unlock_section(&doc, "process-1", "pw").unwrap();
+        assert_eq!(unlocked.content, "Call ${contact} on 555-0100");
+        assert_eq!(unlocked.translations["de"], "Ruf ${contact} an");
+        assert!(doc.sections[1].encrypted);
+
+        assert!(decrypt_section(&mut doc, "process-1", "nope").is_err());
+        assert!(doc.sections[1].encrypted);
+        decrypt_section(&mut doc, "process-1", "pw").unwrap();
+        assert_eq!(doc.sections[1], document().sections[1]);
+    }
+
+    #[test]
+    fn test_lock_sections() {
+        let mut doc = document();
+        encrypt_section(&mut doc, "process-1", "pw").unwrap();
+        lock_sections(&mut doc.sections);
+
+        assert_eq!(doc.sections[0].content, "Public goal");
+        assert_eq!(doc.sections[1].content, LOCKED_PLACEHOLDER);
+        assert!(doc.sections[1].translations.is_empty());
+    }
+}
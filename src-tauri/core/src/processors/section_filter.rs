@@ -0,0 +1,108 @@
+use serde::{Deserialize, Serialize};
+use crate::models::*;
+
+/// Which sections an assembly or export keeps, shared by every exporter
+///
+/// The default spec keeps everything.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase", default)]
+pub struct FilterSpec {
+    /// Keep only sections of these types; all types when empty
+    pub include_types: Vec<String>,
+    /// Leave out sections carrying any of these tags
+    pub exclude_tags: Vec<String>,
+    /// Deepest nesting level to keep, 0 being top-level sections; no limit when unset
+    pub max_depth: Option<usize>,
+}
+
+impl FilterSpec {
+    pub fn is_empty(&self) -> bool {
+        self.include_types.is_empty() && self.exclude_tags.is_empty() && self.max_depth.is_none()
+    }
+
+    /// Whether the section passes the type and tag rules (depth aside)
+    pub fn matches(&self, section: &Section) -> bool {
+        let type_included = self.include_types.is_empty()
+            || self
+                .include_types
+                .iter()
+                .any(|section_type| section_type.eq_ignore_ascii_case(&section.section_type));
+        type_included && !self.exclude_tags.iter().any(|tag| section.has_tag(tag))
+    }
+}
+
+/// Keep the sections the spec matches, at any depth
+///
+/// A section that is left out takes its subsections with it, so nothing
+/// nested under an excluded section reappears at a different level.
+pub fn filter_sections(sections: Vec<Section>, spec: &FilterSpec) -> Vec<Section> {
+    if spec.is_empty() {
+        return sections;
+    }
+    filter_level(sections, spec, 0)
+}
+
+fn filter_level(sections: Vec<Section>, spec: &FilterSpec, depth: usize) -> Vec<Section> {
+    sections
+        .into_iter()
+        .filter(|section| spec.matches(section))
+        .map(|mut section| {
+            section.children = match spec.max_depth {
+                Some(max_depth) if depth >= max_depth => vec![],
+                _ => filter_level(std::mem::take(&mut section.children), spec, depth + 1),
+            };
+            section
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sections() -> Vec<Section> {
+        let mut process = Section::new("process-1", "process", "Steps");
+        let mut nested = Section::new("process-2", "process", "Sub-steps");
+        nested.children.push(Section::new("process-3", "process", "Details"));
+        process.children.push(nested);
+        let mut draft = Section::new("evaluation-1", "evaluation", "Checks");
+        draft.tags = vec!["draft".to_string()];
+        process.children.push(draft);
+        vec![Section::new("intent-1", "intent", "Goal"), process]
+    }
+
+    fn ids(sections: &[Section]) -> Vec<&str> {
+        sections.iter().map(|section| section.id.as_str()).collect()
+    }
+
+    #[test]
+    fn test_empty_spec_keeps_everything() {
+        assert_eq!(filter_sections(sections(), &FilterSpec::default()), sections());
+    }
+
+    #[test]
+    fn test_include_types_and_exclude_tags() {
+        let spec = FilterSpec {
+            include_types: vec!["Process".to_string(), "evaluation".to_string()],
+            exclude_tags: vec!["DRAFT".to_string()],
+            max_depth: None,
+        };
+        let filtered = filter_sections(sections(), &spec);
+
+        assert_eq!(ids(&filtered), vec!["process-1"]);
+        assert_eq!(ids(&filtered[0].children), vec!["process-2"]);
+        assert_eq!(ids(&filtered[0].children[0].children), vec!["process-3"]);
+    }
+
+    #[test]
+    fn test_max_depth() {
+        let spec = FilterSpec { max_depth: Some(1), ..FilterSpec::default() };
+        let filtered = filter_sections(sections(), &spec);
+
+        assert_eq!(ids(&filtered[1].children), vec!["process-2", "evaluation-1"]);
+        assert!(filtered[1].children[0].children.is_empty());
+
+        let top_level = filter_sections(sections(), &FilterSpec { max_depth: Some(0), ..FilterSpec::default() });
+        assert!(top_level[1].children.is_empty());
+    }
+}
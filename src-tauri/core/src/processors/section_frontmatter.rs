@@ -0,0 +1,158 @@
+use std::collections::BTreeMap;
+use crate::models::*;
+
+/// Line opening a frontmatter block; `---` or `...` closes it
+const FENCE: &str = "---";
+
+/// Split YAML-style frontmatter off the top of section content
+///
+/// Only flat `key: value` lines are supported, with `#` comments and
+/// optional quotes around values. Returns None when the content does not
+/// start with a well-formed block, so a leading `---` rule stays content.
+pub fn split_frontmatter(content: &str) -> Option<(BTreeMap<String, String>, &str)> {
+    let rest = content.trim_start_matches(['\r', '\n']);
+    let (first, mut rest) = rest.split_once('\n')?;
+    if first.trim_end() != FENCE {
+        return None;
+    }
+
+    let mut fields = BTreeMap::new();
+    loop {
+        let (line, remaining) = rest.split_once('\n').unwrap_or((rest, ""));
+        let line = line.trim();
+        if line == FENCE || line == "..." {
+            return Some((fields, remaining.trim_start_matches(['\r', '\n'])));
+        }
+        if remaining.is_empty() && rest.is_empty() {
+            return None;
+        }
+        rest = remaining;
+
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let (key, value) = line.split_once(':')?;
+        let key = key.trim();
+        if key.is_empty() || !key.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-')) {
+            return None;
+        }
+        fields.insert(key.to_string(), unquote(value.trim()).to_string());
+    }
+}
+
+/// The section's frontmatter fields; empty without a frontmatter block
+pub fn section_frontmatter(section: &Section) -> BTreeMap<String, String> {
+    split_frontmatter(&section.content).map(|(fields, _)| fields).unwrap_or_default()
+}
+
+/// Set one frontmatter field, adding a block at the top when the content has none
+///
+/// Other lines of an existing block, comments included, are kept as written.
+pub fn set_frontmatter_field(content: &str, key: &str, value: &str) -> String {
+    let field = format!("{}: {}", key, value);
+    if split_frontmatter(content).is_none() {
+        return format!("{}\n{}\n{}\n{}", FENCE, field, FENCE, content);
+    }
+
+    let body = content.trim_start_matches(['\r', '\n']);
+    let mut lines: Vec<&str> = body.split('\n').collect();
+    // split_frontmatter found the closing fence, so there is one after the opening line
+    let close = lines.iter().skip(1).position(|line| matches!(line.trim(), FENCE | "...")).unwrap() + 1;
+    let existing = (1..close).find(|&i| lines[i].split_once(':').is_some_and(|(name, _)| name.trim() == key));
+    match existing {
+        Some(i) => lines[i] = &field,
+        None => lines.insert(close, &field),
+    }
+    lines.join("\n")
+}
+
+/// Frontmatter of every section that has some, at any depth, keyed by section ID
+pub fn collect_frontmatter(sections: &[Section]) -> BTreeMap<String, BTreeMap<String, String>> {
+    let mut collected = BTreeMap::new();
+    collect_into(sections, &mut collected);
+    collected
+}
+
+/// Remove frontmatter from content and translations, at any depth, for rendered and assembled output
+pub fn strip_frontmatter(sections: &mut [Section]) {
+    for section in sections {
+        for content in std::iter::once(&mut section.content).chain(section.translations.values_mut()) {
+            if let Some((_, body)) = split_frontmatter(content) {
+                *content = body.to_string();
+            }
+        }
+        strip_frontmatter(&mut section.children);
+    }
+}
+
+fn collect_into(sections: &[Section], collected: &mut BTreeMap<String, BTreeMap<String, String>>) {
+    for section in sections {
+        if let Some((fields, _)) = split_frontmatter(&section.content) {
+            collected.insert(section.id.clone(), fields);
+        }
+        collect_into(&section.children, collected);
+    }
+}
+
+fn unquote(value: &str) -> &str {
+    ['"', '\'']
+        .iter()
+        .find_map(|quote| value.strip_prefix(*quote)?.strip_suffix(*quote))
+        .unwrap_or(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ANNOTATED: &str = "---\nowner: Ana\ndue: 2025-11-01\n# not shown\nstatus: \"in review\"\n---\n\n# Plan\nSteps";
+
+    #[test]
+    fn test_split_frontmatter() {
+        let (fields, body) = split_frontmatter(ANNOTATED).unwrap();
+
+        assert_eq!(fields["owner"], "Ana");
+        assert_eq!(fields["due"], "2025-11-01");
+        assert_eq!(fields["status"], "in review");
+        assert_eq!(fields.len(), 3);
+        assert_eq!(body, "# Plan\nSteps");
+    }
+
+    #[test]
+    fn test_content_without_frontmatter_is_left_alone() {
+        assert!(split_frontmatter("# Plan\n---\nowner: Ana\n---").is_none());
+        assert!(split_frontmatter("---\nJust a rule and some text\n---").is_none());
+        assert!(split_frontmatter("---\nowner: Ana\nno closing fence").is_none());
+        assert_eq!(split_frontmatter("---\n---\nBody"), Some((BTreeMap::new(), "Body")));
+    }
+
+    #[test]
+    fn test_set_frontmatter_field() {
+        let updated = set_frontmatter_field(ANNOTATED, "status", "final");
+        assert_eq!(updated, ANNOTATED.replace("status: \"in review\"", "status: final"));
+
+        let added = set_frontmatter_field(ANNOTATED, "reviewer", "Kim");
+        assert_eq!(split_frontmatter(&added).unwrap().0["reviewer"], "Kim");
+        assert!(added.contains("# not shown\nstatus: \"in review\"\nreviewer: Kim\n---"));
+
+        assert_eq!(set_frontmatter_field("# Plan", "status", "draft"), "---\nstatus: draft\n---\n# Plan");
+    }
+
+    #[test]
+    fn test_collect_and_strip() {
+        let mut parent = Section::new("process-1", "process", "Steps");
+        let mut child = Section::new("process-2", "process", ANNOTATED);
+        child.translations.insert("de".to_string(), "---\nowner: Ana\n---\nSchritte".to_string());
+        parent.children.push(child);
+        let mut sections = vec![parent];
+
+        let collected = collect_frontmatter(&sections);
+        assert_eq!(collected.keys().collect::<Vec<_>>(), vec!["process-2"]);
+        assert_eq!(section_frontmatter(&sections[0]), BTreeMap::new());
+
+        strip_frontmatter(&mut sections);
+        assert_eq!(sections[0].content, "Steps");
+        assert_eq!(sections[0].children[0].content, "# Plan\nSteps");
+        assert_eq!(sections[0].children[0].translations["de"], "Schritte");
+    }
+}
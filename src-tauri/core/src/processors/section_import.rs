@@ -0,0 +1,270 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use crate::error::{ContextError, Result};
+use crate::models::*;
+use crate::processors::document_edits::{collect_section_ids, find_section};
+use crate::processors::flow_navigation::flatten;
+use crate::processors::variable_resolver;
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ImportedSection {
+    pub source_id: String,
+    pub new_id: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ImportResult {
+    /// Every copied section, children after their parent
+    pub imported: Vec<ImportedSection>,
+    pub variables_added: Vec<String>,
+}
+
+/// Copy sections, at any depth in `source` and with their children, to the end of `target`
+///
+/// Sections and their children keep their IDs unless the target already uses
+/// them anywhere, in which case a numeric suffix is appended. refTargets
+/// between imported sections follow the remapping. Each section may be named
+/// once, and not together with a section it is nested in. With
+/// `carry_variables`, variables referenced by the imported content that the
+/// target lacks are copied over; existing target values win.
+pub fn import_sections(
+    target: &mut ContextDocument,
+    source: &ContextDocument,
+    section_ids: &[String],
+    carry_variables: bool,
+) -> Result<ImportResult> {
+    let unique: HashSet<&String> = section_ids.iter().collect();
+    if unique.len() != section_ids.len() {
+        return Err(ContextError::InvalidArgument("Section IDs to import must be distinct".to_string()));
+    }
+    let mut selected = Vec::new();
+    for id in section_ids {
+        let section = find_section(&source.sections, id).ok_or_else(|| ContextError::SectionNotFound(id.clone()))?;
+        if let Some(nested) = section_ids.iter().find(|other| find_section(&section.children, other).is_some()) {
+            return Err(ContextError::InvalidArgument(format!("Section '{}' is nested in '{}' and is imported with it", nested, id)));
+        }
+        selected.push(section);
+    }
+
+    let mut taken = HashSet::new();
+    collect_section_ids(&target.sections, &mut taken);
+    let mut id_map: HashMap<String, String> = HashMap::new();
+    let mut imported = Vec::new();
+
+    for section in selected.iter().flat_map(|section| flatten(std::slice::from_ref(*section))) {
+        let new_id = unique_section_id(&section.id, &taken);
+        taken.insert(new_id.clone());
+        id_map.insert(section.id.clone(), new_id.clone());
+        imported.push(ImportedSection {
+            source_id: section.id.clone(),
+            new_id,
+        });
+    }
+
+    for section in &selected {
+        let mut copy = (*section).clone();
+        remap_ids(&mut copy, &id_map);
+        target.sections.push(copy);
+    }
+
+    let mut variables_added = Vec::new();
+    if carry_variables {
+        for section in selected.iter().flat_map(|section| flatten(std::slice::from_ref(*section))) {
+            for name in variable_resolver::find_variable_references(&section.content) {
+                if target.variables.iter().any(|v| v.name == name) {
+                    continue;
+                }
+                if let Some(var) = source.variables.iter().find(|v| v.name == name) {
+                    target.variables.push(var.clone());
+                    variables_added.push(name);
+                }
+            }
+        }
+    }
+
+    Ok(ImportResult {
+        imported,
+        variables_added,
+    })
+}
+
+/// Give the copied section and its children their new IDs, and point refTargets at them
+fn remap_ids(section: &mut Section, id_map: &HashMap<String, String>) {
+    section.id = id_map[&section.id].clone();
+    section.ref_target = section.ref_target.take().map(|targets| {
+        targets
+            .split_whitespace()
+            .map(|t| id_map.get(t).map(String::as_str).unwrap_or(t))
+            .collect::<Vec<_>>()
+            .join(" ")
+    });
+    section.transclusions.clear();
+    for child in &mut section.children {
+        remap_ids(child, id_map);
+    }
+}
+
+/// Return `base` if unused, otherwise the first free `base-N` for N >= 2
+pub fn unique_section_id(base: &str, taken: &HashSet<String>) -> String {
+    if !taken.contains(base) {
+        return base.to_string();
+    }
+
+    (2..)
+        .map(|n| format!("{}-{}", base, n))
+        .find(|candidate| !taken.contains(candidate))
+        .unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeMap;
+
+    fn section(id: &str, content: &str, ref_target: Option<&str>) -> Section {
+        Section {
+            id: id.to_string(),
+            section_type: "intent".to_string(),
+            content: content.to_string(),
+            ref_target: ref_target.map(|r| r.to_string()),
+            children: vec![],
+            budget: None,
+            transclusions: vec![],
+            tags: vec![],
+            translations: BTreeMap::new(),
+            sensitive: false,
+            encrypted: false,
+        }
+    }
+
+    fn variable(name: &str, value: &str) -> Variable {
+        Variable {
+            name: name.to_string(),
+            value: value.to_string(),
+            description: None,
+            sensitive: false,
+            resolve_in: vec![],
+            due: None,
+        }
+    }
+
+    fn document(variables: Vec<Variable>, sections: Vec<Section>) -> ContextDocument {
+        ContextDocument {
+            meta: MetaData {
+                title: "Test".to_string(),
+                author: "Author".to_string(),
+                created: "2025-10-09".to_string(),
+                app_info: AppInfo {
+                    name: "CEC".to_string(),
+                    version: "0.1.0".to_string(),
+                },
+                tags: vec![],
+                description: "Test".to_string(),
+                extra: BTreeMap::new(),
+            },
+            variables,
+            sections,
+            references: vec![],
+            flow_graph: None,
+        }
+    }
+
+    #[test]
+    fn test_unique_section_id() {
+        let taken: HashSet<String> = ["intent-1", "intent-1-2"].iter().map(|s| s.to_string()).collect();
+
+        assert_eq!(unique_section_id("eval-1", &taken), "eval-1");
+        assert_eq!(unique_section_id("intent-1", &taken), "intent-1-3");
+    }
+
+    #[test]
+    fn test_import_remaps_colliding_ids() {
+        let mut target = document(vec![], vec![section("intent-1", "Target", None)]);
+        let source = document(
+            vec![],
+            vec![
+                section("intent-1", "Source intent", None),
+                section("proc-1", "Source process", Some("intent-1 other")),
+            ],
+        );
+
+        let ids = vec!["intent-1".to_string(), "proc-1".to_string()];
+        let result = import_sections(&mut target, &source, &ids, false).unwrap();
+
+        assert_eq!(result.imported[0].new_id, "intent-1-2");
+        assert_eq!(result.imported[1].new_id, "proc-1");
+        assert_eq!(target.sections.len(), 3);
+        assert_eq!(target.sections[1].content, "Source intent");
+        assert_eq!(target.sections[2].ref_target, Some("intent-1-2 other".to_string()));
+    }
+
+    #[test]
+    fn test_import_nested_sections_with_children() {
+        let mut nested = section("intent-2", "Target child", None);
+        nested.children.push(section("intent-3", "Target grandchild", None));
+        let mut parent = section("intent-1", "Target", None);
+        parent.children.push(nested);
+        let mut target = document(vec![], vec![parent]);
+
+        let mut child = section("intent-3", "Source child", Some("intent-2"));
+        child.children.push(section("proc-1", "Source grandchild", None));
+        let mut picked = section("intent-2", "Source", None);
+        picked.children.push(child);
+        let mut outer = section("outer-1", "Outer", None);
+        outer.children.push(picked);
+        let source = document(vec![], vec![outer]);
+
+        let result = import_sections(&mut target, &source, &["intent-2".to_string()], false).unwrap();
+
+        let new_ids: Vec<&str> = result.imported.iter().map(|i| i.new_id.as_str()).collect();
+        assert_eq!(new_ids, vec!["intent-2-2", "intent-3-2", "proc-1"]);
+        let copy = &target.sections[1];
+        assert_eq!(copy.id, "intent-2-2");
+        assert_eq!(copy.children[0].id, "intent-3-2");
+        assert_eq!(copy.children[0].ref_target.as_deref(), Some("intent-2-2"));
+        assert_eq!(copy.children[0].children[0].id, "proc-1");
+    }
+
+    #[test]
+    fn test_import_rejects_repeated_and_nested_selections() {
+        let mut child = section("proc-1", "Child", None);
+        child.children.push(section("proc-2", "Grandchild", None));
+        let mut parent = section("intent-1", "Parent", None);
+        parent.children.push(child);
+        let source = document(vec![], vec![parent]);
+        let mut target = document(vec![], vec![]);
+
+        let repeated = import_sections(&mut target, &source, &["proc-1".to_string(), "proc-1".to_string()], false);
+        assert!(matches!(repeated, Err(ContextError::InvalidArgument(_))));
+        let nested = import_sections(&mut target, &source, &["intent-1".to_string(), "proc-2".to_string()], false);
+        assert!(matches!(nested, Err(ContextError::InvalidArgument(_))));
+        assert!(target.sections.is_empty());
+    }
+
+    #[test]
+    fn test_import_carries_referenced_variables() {
+        let mut target = document(vec![variable("goal", "Target goal")], vec![]);
+        let source = document(
+            vec![variable("goal", "Source goal"), variable("user", "Jeremy"), variable("unused", "x")],
+            vec![section("intent-1", "${goal} for ${user}", None)],
+        );
+
+        let result = import_sections(&mut target, &source, &["intent-1".to_string()], true).unwrap();
+
+        assert_eq!(result.variables_added, vec!["user".to_string()]);
+        assert_eq!(target.variables.len(), 2);
+        assert_eq!(target.variables[0].value, "Target goal");
+    }
+
+    #[test]
+    fn test_import_missing_section_leaves_target_untouched() {
+        let mut target = document(vec![], vec![]);
+        let source = document(vec![], vec![section("intent-1", "Content", None)]);
+
+        let ids = vec!["intent-1".to_string(), "missing".to_string()];
+        let result = import_sections(&mut target, &source, &ids, true);
+
+        assert!(matches!(result, Err(ContextError::SectionNotFound(_))));
+        assert!(target.sections.is_empty());
+    }
+}
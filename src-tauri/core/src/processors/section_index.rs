@@ -0,0 +1,142 @@
+use std::collections::HashSet;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use crate::models::*;
+use crate::processors::flow_navigation::flatten;
+use crate::processors::section_filter::{self, FilterSpec};
+
+/// Size and fingerprint of one section's content
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SectionIndexEntry {
+    pub id: String,
+    pub section_type: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub parent_id: Option<String>,
+    /// SHA-256 of the content, hex encoded
+    pub content_hash: String,
+    pub byte_length: usize,
+    pub word_count: usize,
+}
+
+/// Which part of the section index to return
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase", default)]
+pub struct SectionIndexQuery {
+    /// Entries to skip, counted after filtering
+    pub offset: usize,
+    /// Most entries to return; all remaining when unset
+    pub limit: Option<usize>,
+    /// Types, tags and depth to keep, as for exports
+    pub filter: FilterSpec,
+    /// Keep sections whose ID or content contains this text, ignoring case
+    pub search: Option<String>,
+}
+
+/// One page of the section index
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SectionIndexPage {
+    pub entries: Vec<SectionIndexEntry>,
+    /// Entries matching the filter and search across all pages
+    pub total: usize,
+    pub offset: usize,
+}
+
+/// Index every section of the tree, parents before their children
+///
+/// Meant to be cheap to poll: comparing hashes with a previous index tells
+/// which sections to reload after a change event. Pass resolved sections so
+/// variable edits show up as content changes.
+pub fn build_section_index(sections: &[Section]) -> Vec<SectionIndexEntry> {
+    let mut entries = Vec::new();
+    collect_entries(sections, None, &mut entries);
+    entries
+}
+
+/// Filter the index and return the requested page
+///
+/// Entries keep document order, so the same query returns the same page
+/// until the document changes. A section left out by the filter takes its
+/// subsections with it; the search matches each section on its own.
+pub fn query_section_index(sections: &[Section], query: &SectionIndexQuery) -> SectionIndexPage {
+    let filtered = section_filter::filter_sections(sections.to_vec(), &query.filter);
+    let mut entries = build_section_index(&filtered);
+
+    if let Some(needle) = query.search.as_deref().map(str::trim).filter(|s| !s.is_empty()) {
+        let needle = needle.to_lowercase();
+        let matching: HashSet<&str> = flatten(&filtered)
+            .into_iter()
+            .filter(|section| {
+                section.id.to_lowercase().contains(&needle) || section.content.to_lowercase().contains(&needle)
+            })
+            .map(|section| section.id.as_str())
+            .collect();
+        entries.retain(|entry| matching.contains(entry.id.as_str()));
+    }
+
+    let total = entries.len();
+    let entries = entries
+        .into_iter()
+        .skip(query.offset)
+        .take(query.limit.unwrap_or(usize::MAX))
+        .collect();
+    SectionIndexPage { entries, total, offset: query.offset }
+}
+
+fn collect_entries(sections: &[Section], parent_id: Option<&str>, entries: &mut Vec<SectionIndexEntry>) {
+    for section in sections {
+        entries.push(SectionIndexEntry {
+            id: section.id.clone(),
+            section_type: section.section_type.clone(),
+            parent_id: parent_id.map(str::to_string),
+            content_hash: format!("{:x}", Sha256::digest(section.content.as_bytes())),
+            byte_length: section.content.len(),
+            word_count: section.content.split_whitespace().count(),
+        });
+        collect_entries(&section.children, Some(&section.id), entries);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_build_section_index() {
+        let mut parent = Section::new("process-1", "process", "# Steps\nDo the thing");
+        parent.children.push(Section::new("process-2", "process", "Größe"));
+        let sections = vec![Section::new("intent-1", "intent", "# Intent"), parent];
+
+        let index = build_section_index(&sections);
+
+        let ids: Vec<&str> = index.iter().map(|e| e.id.as_str()).collect();
+        assert_eq!(ids, vec!["intent-1", "process-1", "process-2"]);
+        assert_eq!(index[1].word_count, 5);
+        assert_eq!(index[2].parent_id.as_deref(), Some("process-1"));
+        assert_eq!(index[2].byte_length, 7);
+        assert_eq!(index[0].content_hash.len(), 64);
+        assert_ne!(index[1].content_hash, index[2].content_hash);
+    }
+
+    #[test]
+    fn test_query_section_index() {
+        let sections: Vec<Section> = (1..=25)
+            .map(|n| Section::new(format!("process-{}", n), if n % 5 == 0 { "evaluation" } else { "process" }, format!("Step {}", n)))
+            .collect();
+
+        let page = query_section_index(&sections, &SectionIndexQuery { offset: 10, limit: Some(10), ..Default::default() });
+        assert_eq!(page.total, 25);
+        assert_eq!(page.entries.first().unwrap().id, "process-11");
+        assert_eq!(page.entries.len(), 10);
+
+        let last = query_section_index(&sections, &SectionIndexQuery { offset: 20, limit: Some(10), ..Default::default() });
+        assert_eq!(last.entries.len(), 5);
+
+        let filter = FilterSpec { include_types: vec!["evaluation".to_string()], ..Default::default() };
+        let evaluations = query_section_index(&sections, &SectionIndexQuery { filter, ..Default::default() });
+        assert_eq!(evaluations.total, 5);
+
+        let search = query_section_index(&sections, &SectionIndexQuery { search: Some("STEP 2".to_string()), ..Default::default() });
+        let ids: Vec<&str> = search.entries.iter().map(|e| e.id.as_str()).collect();
+        assert_eq!(ids, vec!["process-2", "process-20", "process-21", "process-22", "process-23", "process-24", "process-25"]);
+    }
+}
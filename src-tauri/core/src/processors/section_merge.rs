@@ -0,0 +1,280 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+use crate::error::{ContextError, Result};
+use crate::models::*;
+use crate::processors::click_suggestions::retarget_click_actions;
+use crate::processors::document_edits::{ensure_not_encrypted, find_section, find_section_mut, remove_section};
+
+/// Separator placed between merged contents when none is given
+pub const DEFAULT_MERGE_SEPARATOR: &str = "\n\n";
+
+/// Merge sections, at any depth, into the first of `ids`, in the order given
+///
+/// Content is joined with `separator`. Headings of the later sections are
+/// demoted so none of them outranks the first section's top heading, keeping
+/// the merged outline under it. Tags and refTargets are combined, language
+/// variants are joined per language, and children of the removed sections
+/// move to the end of the kept one. RefTargets and flow click actions that
+/// pointed at a removed section now point at the kept one. A section can't be
+/// merged with one nested inside it, and encrypted sections can't be merged.
+pub fn merge_sections(doc: &mut ContextDocument, ids: &[String], separator: &str) -> Result<()> {
+    if ids.len() < 2 {
+        return Err(ContextError::InvalidArgument(
+            "At least two sections are needed to merge".to_string(),
+        ));
+    }
+    let unique: HashSet<&String> = ids.iter().collect();
+    if unique.len() != ids.len() {
+        return Err(ContextError::InvalidArgument("Section IDs to merge must be distinct".to_string()));
+    }
+
+    for id in ids {
+        let section = find_section(&doc.sections, id).ok_or_else(|| ContextError::SectionNotFound(id.clone()))?;
+        ensure_not_encrypted(section)?;
+        if let Some(nested) = ids.iter().find(|other| find_section(&section.children, other).is_some()) {
+            return Err(ContextError::InvalidArgument(format!("Section '{}' is nested in '{}' and can't be merged with it", nested, id)));
+        }
+    }
+    let mut merged: Vec<Section> = vec![find_section(&doc.sections, &ids[0]).unwrap().clone()];
+    for id in &ids[1..] {
+        merged.push(remove_section(&mut doc.sections, id).unwrap());
+    }
+
+    let kept_id = ids[0].clone();
+    let renames: HashMap<String, String> = ids[1..].iter().map(|id| (id.clone(), kept_id.clone())).collect();
+
+    let top_level = top_heading_level(&merged[0].content).unwrap_or(0);
+    let mut content = merged[0].content.clone();
+    let mut translations: BTreeMap<String, String> = merged[0].translations.clone();
+    let mut tags = merged[0].tags.clone();
+    let mut ref_targets: Vec<String> = ref_tokens(&merged[0]);
+
+    for section in &merged[1..] {
+        content.push_str(separator);
+        content.push_str(&demote_headings(&section.content, top_level));
+
+        for (lang, text) in &section.translations {
+            let demoted = demote_headings(text, top_level);
+            translations
+                .entry(lang.clone())
+                .and_modify(|existing| {
+                    existing.push_str(separator);
+                    existing.push_str(&demoted);
+                })
+                .or_insert(demoted);
+        }
+        for tag in &section.tags {
+            if !tags.iter().any(|t| t.eq_ignore_ascii_case(tag)) {
+                tags.push(tag.clone());
+            }
+        }
+        ref_targets.extend(ref_tokens(section));
+    }
+
+    retarget_refs(&mut doc.sections, &renames);
+    let kept = find_section_mut(&mut doc.sections, &kept_id).unwrap();
+    kept.content = content;
+    kept.translations = translations;
+    kept.tags = tags;
+    kept.ref_target = join_ref_targets(ref_targets, &renames, &kept_id);
+    for section in &mut merged[1..] {
+        kept.children.append(&mut section.children);
+    }
+
+    if let Some(flow) = doc.flow_graph.as_mut() {
+        flow.mermaid_code = retarget_click_actions(&flow.mermaid_code, &renames);
+    }
+
+    Ok(())
+}
+
+/// Point refTargets of every section, at any depth, at the kept section instead of removed ones
+fn retarget_refs(sections: &mut [Section], renames: &HashMap<String, String>) {
+    for section in sections {
+        if section.ref_target.is_some() {
+            section.ref_target = join_ref_targets(ref_tokens(section), renames, &section.id);
+        }
+        retarget_refs(&mut section.children, renames);
+    }
+}
+
+fn ref_tokens(section: &Section) -> Vec<String> {
+    section
+        .ref_target
+        .as_deref()
+        .unwrap_or("")
+        .split_whitespace()
+        .map(str::to_string)
+        .collect()
+}
+
+/// Rename, de-duplicate and drop self-references, preserving order
+fn join_ref_targets(tokens: Vec<String>, renames: &HashMap<String, String>, own_id: &str) -> Option<String> {
+    let mut seen = HashSet::new();
+    let tokens: Vec<String> = tokens
+        .into_iter()
+        .map(|token| renames.get(&token).cloned().unwrap_or(token))
+        .filter(|token| token != own_id && seen.insert(token.clone()))
+        .collect();
+
+    (!tokens.is_empty()).then(|| tokens.join(" "))
+}
+
+/// Lowest ATX heading level (1 for `#`) outside fenced code
+fn top_heading_level(content: &str) -> Option<usize> {
+    headings(content).map(|(level, _)| level).min()
+}
+
+/// (level, line index) of each ATX heading outside fenced code
+fn headings(content: &str) -> impl Iterator<Item = (usize, usize)> + '_ {
+    let mut in_fence = false;
+    content.lines().enumerate().filter_map(move |(index, line)| {
+        let trimmed = line.trim_start();
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            in_fence = !in_fence;
+            return None;
+        }
+        if in_fence {
+            return None;
+        }
+        let level = trimmed.chars().take_while(|&c| c == '#').count();
+        let is_heading = (1..=6).contains(&level)
+            && trimmed[level..].chars().next().is_none_or(char::is_whitespace);
+        is_heading.then_some((level, index))
+    })
+}
+
+/// Demote headings so the highest one sits one level below `parent_level` (capped at 6)
+fn demote_headings(content: &str, parent_level: usize) -> String {
+    let Some(top) = top_heading_level(content) else {
+        return content.to_string();
+    };
+    let shift = (parent_level + 1).saturating_sub(top);
+    if shift == 0 {
+        return content.to_string();
+    }
+
+    let heading_lines: HashMap<usize, usize> = headings(content).map(|(level, index)| (index, level)).collect();
+    content
+        .lines()
+        .enumerate()
+        .map(|(index, line)| match heading_lines.get(&index) {
+            Some(&level) => {
+                let trimmed = line.trim_start();
+                let new_level = (level + shift).min(6);
+                format!("{}{}", "#".repeat(new_level), &trimmed[level..])
+            }
+            None => line.to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn document() -> ContextDocument {
+        let mut doc = ContextDocument::builder()
+            .title("Merge")
+            .section("process-1", "process", "# Process\nStep one")
+            .section("process-2", "process", "# More\n## Detail\nStep two")
+            .section("process-3", "process", "Step three")
+            .section("evaluation-1", "evaluation", "Evaluate")
+            .flow("graph TD\n  A --> B\n  click A \"#process-1\"\n  click B \"#process-2\"")
+            .build()
+            .unwrap();
+        doc.sections[1].tags = vec!["draft".to_string()];
+        doc.sections[1].ref_target = Some("process-1 evaluation-1".to_string());
+        doc.sections[3].ref_target = Some("process-2 process-3".to_string());
+        doc
+    }
+
+    fn ids(values: &[&str]) -> Vec<String> {
+        values.iter().map(|v| v.to_string()).collect()
+    }
+
+    #[test]
+    fn test_merges_into_first_section_with_demoted_headings() {
+        let mut doc = document();
+
+        merge_sections(&mut doc, &ids(&["process-1", "process-2", "process-3"]), "\n\n---\n\n").unwrap();
+
+        let section_ids: Vec<&str> = doc.sections.iter().map(|s| s.id.as_str()).collect();
+        assert_eq!(section_ids, vec!["process-1", "evaluation-1"]);
+        assert_eq!(
+            doc.sections[0].content,
+            "# Process\nStep one\n\n---\n\n## More\n### Detail\nStep two\n\n---\n\nStep three"
+        );
+        assert_eq!(doc.sections[0].tags, vec!["draft"]);
+    }
+
+    #[test]
+    fn test_updates_ref_targets_and_click_actions() {
+        let mut doc = document();
+
+        merge_sections(&mut doc, &ids(&["process-1", "process-2", "process-3"]), DEFAULT_MERGE_SEPARATOR).unwrap();
+
+        // The self-reference from process-2 is dropped, its other target kept
+        assert_eq!(doc.sections[0].ref_target.as_deref(), Some("evaluation-1"));
+        assert_eq!(doc.sections[1].ref_target.as_deref(), Some("process-1"));
+        let mermaid = &doc.flow_graph.as_ref().unwrap().mermaid_code;
+        assert!(mermaid.contains("click B \"#process-1\""));
+        assert!(!mermaid.contains("process-2"));
+    }
+
+    #[test]
+    fn test_nested_sections_and_children_are_kept() {
+        let mut doc = document();
+        let mut nested = Section::new("process-1a", "process", "Nested");
+        nested.ref_target = Some("process-2".to_string());
+        doc.sections[0].children.push(nested);
+        doc.sections[1].children.push(Section::new("process-2a", "process", "Sub step"));
+
+        merge_sections(&mut doc, &ids(&["process-1", "process-2"]), "\n").unwrap();
+
+        let children: Vec<&str> = doc.sections[0].children.iter().map(|s| s.id.as_str()).collect();
+        assert_eq!(children, vec!["process-1a", "process-2a"]);
+        assert_eq!(doc.sections[0].children[0].ref_target.as_deref(), Some("process-1"));
+        assert_eq!(doc.sections[2].ref_target.as_deref(), Some("process-1 process-3"));
+
+        // A nested section merges into a top-level one
+        merge_sections(&mut doc, &ids(&["process-3", "process-2a"]), "\n").unwrap();
+        assert_eq!(doc.sections[0].children.len(), 1);
+        assert_eq!(doc.sections[1].content, "Step three\nSub step");
+
+        assert!(matches!(
+            merge_sections(&mut doc, &ids(&["process-1", "process-1a"]), "\n"),
+            Err(ContextError::InvalidArgument(_))
+        ));
+    }
+
+    #[test]
+    fn test_headings_in_fences_are_not_demoted() {
+        assert_eq!(demote_headings("# A\n```\n# code\n```", 1), "## A\n```\n# code\n```");
+        assert_eq!(demote_headings("###### Deep\n# Top", 1), "###### Deep\n## Top");
+        assert_eq!(demote_headings("#hashtag", 1), "#hashtag");
+    }
+
+    #[test]
+    fn test_rejects_invalid_selection() {
+        let mut doc = document();
+
+        assert!(matches!(merge_sections(&mut doc, &ids(&["process-1"]), "\n"), Err(ContextError::InvalidArgument(_))));
+        assert!(matches!(
+            merge_sections(&mut doc, &ids(&["process-1", "process-1"]), "\n"),
+            Err(ContextError::InvalidArgument(_))
+        ));
+        assert!(matches!(
+            merge_sections(&mut doc, &ids(&["process-1", "nope"]), "\n"),
+            Err(ContextError::SectionNotFound(_))
+        ));
+
+        doc.sections[1].encrypted = true;
+        let before = doc.clone();
+        assert!(matches!(
+            merge_sections(&mut doc, &ids(&["process-1", "process-2"]), "\n"),
+            Err(ContextError::InvalidArgument(_))
+        ));
+        assert_eq!(doc, before);
+    }
+}
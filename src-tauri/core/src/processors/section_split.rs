@@ -0,0 +1,198 @@
+use std::collections::HashSet;
+use crate::error::{ContextError, Result};
+use crate::models::*;
+use crate::processors::document_edits::{collect_section_ids, ensure_not_encrypted};
+use crate::processors::section_import::unique_section_id;
+
+/// Split a section, at any depth, into several at ATX headings of exactly `heading_level`
+///
+/// Content before the first such heading stays in the original section (which
+/// keeps its ID, refTarget and translations); when the content starts with a
+/// heading, the first chunk stays there instead. Each further chunk becomes a
+/// new section of the same type and tags, inserted after the original among
+/// its siblings in order, with an ID generated from its heading and unique
+/// across the whole document. Headings inside fenced code
+/// are ignored. Encrypted sections are refused. Returns the IDs of the new sections.
+pub fn split_section(doc: &mut ContextDocument, section_id: &str, heading_level: usize) -> Result<Vec<String>> {
+    if !(1..=6).contains(&heading_level) {
+        return Err(ContextError::InvalidArgument(format!(
+            "Heading level must be between 1 and 6, got {}",
+            heading_level
+        )));
+    }
+
+    let mut taken = HashSet::new();
+    collect_section_ids(&doc.sections, &mut taken);
+    let (siblings, position) =
+        sibling_list(&mut doc.sections, section_id).ok_or_else(|| ContextError::SectionNotFound(section_id.to_string()))?;
+
+    ensure_not_encrypted(&siblings[position])?;
+    let chunks = split_at_headings(&siblings[position].content, heading_level);
+    if chunks.len() < 2 {
+        return Err(ContextError::InvalidArgument(format!(
+            "Section '{}' has no level {} headings to split at",
+            section_id, heading_level
+        )));
+    }
+
+    let original = &siblings[position];
+    let mut new_sections = Vec::new();
+
+    for chunk in &chunks[1..] {
+        let base = heading_id(&chunk.heading).unwrap_or_else(|| format!("{}-part", section_id));
+        let id = unique_section_id(&base, &taken);
+        taken.insert(id.clone());
+
+        new_sections.push(Section {
+            tags: original.tags.clone(),
+            ..Section::new(id, original.section_type.clone(), chunk.content.clone())
+        });
+    }
+
+    siblings[position].content = chunks[0].content.clone();
+    let new_ids = new_sections.iter().map(|s| s.id.clone()).collect();
+    siblings.splice(position + 1..position + 1, new_sections);
+
+    Ok(new_ids)
+}
+
+/// The list holding the section, at any depth, and its position there
+fn sibling_list<'a>(sections: &'a mut Vec<Section>, id: &str) -> Option<(&'a mut Vec<Section>, usize)> {
+    if let Some(position) = sections.iter().position(|s| s.id == id) {
+        return Some((sections, position));
+    }
+    sections.iter_mut().find_map(|section| sibling_list(&mut section.children, id))
+}
+
+struct Chunk {
+    heading: String,
+    content: String,
+}
+
+/// Content chunks, each starting at a heading of the given level (except a leading preamble)
+fn split_at_headings(content: &str, level: usize) -> Vec<Chunk> {
+    let marker = format!("{} ", "#".repeat(level));
+    let mut chunks: Vec<Chunk> = Vec::new();
+    let mut current = Chunk { heading: String::new(), content: String::new() };
+    let mut in_fence = false;
+
+    for line in content.split_inclusive('\n') {
+        let trimmed = line.trim();
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            in_fence = !in_fence;
+        }
+
+        let is_heading = !in_fence && (line.starts_with(&marker) || line.trim_end() == marker.trim_end());
+        if is_heading {
+            if !current.content.trim().is_empty() {
+                chunks.push(current);
+            }
+            current = Chunk {
+                heading: line.trim_start_matches('#').trim().to_string(),
+                content: String::new(),
+            };
+        }
+        current.content.push_str(line);
+    }
+
+    if !current.content.trim().is_empty() || chunks.is_empty() {
+        chunks.push(current);
+    }
+
+    for chunk in &mut chunks {
+        chunk.content = chunk.content.trim().to_string();
+    }
+    chunks
+}
+
+/// Section ID derived from heading text: lowercase ASCII words joined by hyphens
+pub fn heading_id(heading: &str) -> Option<String> {
+    let words: Vec<String> = heading
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_ascii_lowercase)
+        .collect();
+
+    (!words.is_empty()).then(|| words.join("-"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn document(content: &str) -> ContextDocument {
+        ContextDocument::builder()
+            .title("Split")
+            .section("process-1", "process", content)
+            .section("evaluation-1", "evaluation", "Evaluate")
+            .build()
+            .unwrap()
+    }
+
+    fn ids(doc: &ContextDocument) -> Vec<&str> {
+        doc.sections.iter().map(|s| s.id.as_str()).collect()
+    }
+
+    #[test]
+    fn test_splits_at_headings_keeping_preamble() {
+        let mut doc = document("# Process\nIntro\n\n## Gather Data\nCollect\n\n## Analyse (v2)\nThink\n### Detail\nKept");
+
+        let new_ids = split_section(&mut doc, "process-1", 2).unwrap();
+
+        assert_eq!(new_ids, vec!["gather-data", "analyse-v2"]);
+        assert_eq!(ids(&doc), vec!["process-1", "gather-data", "analyse-v2", "evaluation-1"]);
+        assert_eq!(doc.sections[0].content, "# Process\nIntro");
+        assert_eq!(doc.sections[1].content, "## Gather Data\nCollect");
+        assert_eq!(doc.sections[2].content, "## Analyse (v2)\nThink\n### Detail\nKept");
+        assert_eq!(doc.sections[2].section_type, "process");
+    }
+
+    #[test]
+    fn test_leading_heading_stays_in_original() {
+        let mut doc = document("## One\nA\n## Two\nB");
+
+        split_section(&mut doc, "process-1", 2).unwrap();
+
+        assert_eq!(ids(&doc), vec!["process-1", "two", "evaluation-1"]);
+        assert_eq!(doc.sections[0].content, "## One\nA");
+    }
+
+    #[test]
+    fn test_generated_ids_avoid_collisions_and_fences() {
+        let mut doc = document("Intro\n## Evaluation 1\nA\n```\n## not a heading\n```\n## Evaluation 1\nB");
+
+        let new_ids = split_section(&mut doc, "process-1", 2).unwrap();
+
+        assert_eq!(new_ids, vec!["evaluation-1-2", "evaluation-1-3"]);
+        assert!(doc.sections[1].content.contains("## not a heading"));
+    }
+
+    #[test]
+    fn test_splits_nested_section_among_its_siblings() {
+        let mut doc = document("# Process");
+        doc.sections[0].children.push(Section::new("process-2", "process", "Intro\n## Evaluation 1\nA"));
+        doc.sections[0].children.push(Section::new("process-3", "process", "Last"));
+
+        let new_ids = split_section(&mut doc, "process-2", 2).unwrap();
+
+        // evaluation-1 is taken at the top level
+        assert_eq!(new_ids, vec!["evaluation-1-2"]);
+        assert_eq!(ids(&doc), vec!["process-1", "evaluation-1"]);
+        let children: Vec<&str> = doc.sections[0].children.iter().map(|s| s.id.as_str()).collect();
+        assert_eq!(children, vec!["process-2", "evaluation-1-2", "process-3"]);
+        assert_eq!(doc.sections[0].children[0].content, "Intro");
+
+        // process-3 is taken by a nested section
+        doc.sections[0].content = "# Process\n## Process 3\nMoved".to_string();
+        assert_eq!(split_section(&mut doc, "process-1", 2).unwrap(), vec!["process-3-2"]);
+    }
+
+    #[test]
+    fn test_rejects_unsplittable_sections() {
+        let mut doc = document("# Only a title\nBody");
+
+        assert!(matches!(split_section(&mut doc, "process-1", 2), Err(ContextError::InvalidArgument(_))));
+        assert!(matches!(split_section(&mut doc, "process-1", 7), Err(ContextError::InvalidArgument(_))));
+        assert!(matches!(split_section(&mut doc, "missing", 2), Err(ContextError::SectionNotFound(_))));
+    }
+}
@@ -0,0 +1,154 @@
+use std::collections::BTreeMap;
+use crate::error::Result;
+use crate::models::*;
+use crate::processors::document_edits::DocumentEdit;
+
+/// Prefix of the custom meta fields holding a document's own templates, e.g. `template.evaluation`
+pub const TEMPLATE_FIELD_PREFIX: &str = "template.";
+
+/// Skeleton a new section of this type starts with when nothing else is configured
+pub fn builtin_template(section_type: &str) -> Option<&'static str> {
+    match section_type {
+        "intent" => Some("# Intent\n\n## Goal\n\n## Success criteria\n\n- "),
+        "evaluation" => Some("# Evaluation\n\n| Criterion | Weight | Notes |\n| --- | --- | --- |\n|  |  |  |"),
+        "process" => Some("# Process\n\n1. "),
+        "alternatives" => Some("# Alternatives\n\n| Option | Pros | Cons |\n| --- | --- | --- |\n|  |  |  |"),
+        _ => None,
+    }
+}
+
+/// Content for a new section of `section_type`
+///
+/// The document's `template.<type>` custom field wins over `configured` (the
+/// `sectionTemplates` setting), which wins over the built-in skeleton. An
+/// empty template at any level means the section starts blank.
+pub fn section_template(meta: &MetaData, configured: &BTreeMap<String, String>, section_type: &str) -> Option<String> {
+    meta.extra
+        .get(&format!("{}{}", TEMPLATE_FIELD_PREFIX, section_type))
+        .or_else(|| configured.get(section_type))
+        .cloned()
+        .or_else(|| builtin_template(section_type).map(str::to_string))
+        .filter(|template| !template.trim().is_empty())
+}
+
+/// Sections of a new document, in order; each starts from its template
+pub const SKELETON_SECTION_TYPES: [&str; 4] = ["intent", "evaluation", "process", "alternatives"];
+
+/// A new document with one `<type>-1` section of each of [`SKELETON_SECTION_TYPES`]
+///
+/// Sections start from [`section_template`] with `configured` templates.
+/// With `include_flow` the sections are linked in order by a flowchart whose
+/// nodes click through to them.
+pub fn document_skeleton(
+    title: &str,
+    author: &str,
+    created: &str,
+    configured: &BTreeMap<String, String>,
+    include_flow: bool,
+) -> Result<ContextDocument> {
+    let mut builder = ContextDocument::builder().title(title).author(author).created(created);
+    let meta = builder.clone().build()?.meta;
+    for section_type in SKELETON_SECTION_TYPES {
+        let content = section_template(&meta, configured, section_type).unwrap_or_default();
+        builder = builder.section(format!("{}-1", section_type), section_type, content);
+    }
+
+    if include_flow {
+        let nodes: Vec<(char, &str)> = ('A'..).zip(SKELETON_SECTION_TYPES).collect();
+        let mut flow = String::from("flowchart TD");
+        for (index, (node, section_type)) in nodes.iter().enumerate() {
+            let label = format!("{}{}", section_type[..1].to_uppercase(), &section_type[1..]);
+            match index {
+                0 => flow.push_str(&format!("\n  {}[{}]", node, label)),
+                _ => flow.push_str(&format!("\n  {} --> {}[{}]", nodes[index - 1].0, node, label)),
+            }
+        }
+        for (node, section_type) in &nodes {
+            flow.push_str(&format!("\n  click {} \"#{}-1\"", node, section_type));
+        }
+        builder = builder.flow(flow);
+    }
+    builder.build()
+}
+
+/// The edits with blank `create_section` content replaced by the template of the section's type
+pub fn with_section_templates(meta: &MetaData, configured: &BTreeMap<String, String>, edits: &[DocumentEdit]) -> Vec<DocumentEdit> {
+    edits
+        .iter()
+        .map(|edit| match edit {
+            DocumentEdit::CreateSection { section, parent_id, index } if section.content.trim().is_empty() => {
+                let mut section = section.clone();
+                if let Some(template) = section_template(meta, configured, &section.section_type) {
+                    section.content = template;
+                }
+                DocumentEdit::CreateSection { section, parent_id: parent_id.clone(), index: *index }
+            }
+            _ => edit.clone(),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn create(id: &str, section_type: &str, content: &str) -> DocumentEdit {
+        DocumentEdit::CreateSection { section: Section::new(id, section_type, content), parent_id: None, index: None }
+    }
+
+    fn content(edit: &DocumentEdit) -> &str {
+        match edit {
+            DocumentEdit::CreateSection { section, .. } => &section.content,
+            _ => panic!("not a create_section edit"),
+        }
+    }
+
+    #[test]
+    fn test_template_precedence() {
+        let mut meta = ContextDocument::builder().title("Plan").build().unwrap().meta;
+        let mut configured = BTreeMap::new();
+        assert!(section_template(&meta, &configured, "evaluation").unwrap().contains("| Criterion |"));
+
+        configured.insert("evaluation".to_string(), "# Scorecard".to_string());
+        configured.insert("process".to_string(), String::new());
+        assert_eq!(section_template(&meta, &configured, "evaluation").as_deref(), Some("# Scorecard"));
+        assert_eq!(section_template(&meta, &configured, "process"), None);
+
+        meta.extra.insert("template.evaluation".to_string(), "# Review\n\n- [ ] Risk".to_string());
+        assert_eq!(section_template(&meta, &configured, "evaluation").as_deref(), Some("# Review\n\n- [ ] Risk"));
+    }
+
+    #[test]
+    fn test_document_skeleton() {
+        let mut configured = BTreeMap::new();
+        configured.insert("alternatives".to_string(), String::new());
+        let doc = document_skeleton("Launch", "Dana", "2025-10-09", &configured, true).unwrap();
+
+        assert_eq!(doc.meta.author, "Dana");
+        let ids: Vec<_> = doc.sections.iter().map(|s| s.id.as_str()).collect();
+        assert_eq!(ids, vec!["intent-1", "evaluation-1", "process-1", "alternatives-1"]);
+        assert!(doc.sections[0].content.starts_with("# Intent"));
+        assert_eq!(doc.sections[3].content, "");
+        assert_eq!(
+            doc.flow_graph.unwrap().mermaid_code,
+            "flowchart TD\n  A[Intent]\n  A --> B[Evaluation]\n  B --> C[Process]\n  C --> D[Alternatives]\n  \
+             click A \"#intent-1\"\n  click B \"#evaluation-1\"\n  click C \"#process-1\"\n  click D \"#alternatives-1\""
+        );
+        assert!(document_skeleton("Launch", "", "", &configured, false).unwrap().flow_graph.is_none());
+    }
+
+    #[test]
+    fn test_only_blank_created_sections_get_templates() {
+        let meta = ContextDocument::builder().title("Plan").build().unwrap().meta;
+        let edits = vec![
+            create("intent-2", "intent", "  \n"),
+            create("process-2", "process", "# Written"),
+            DocumentEdit::DeleteSection { id: "intent-1".to_string() },
+        ];
+        let filled = with_section_templates(&meta, &BTreeMap::new(), &edits);
+
+        assert!(content(&filled[0]).starts_with("# Intent\n\n## Goal"));
+        assert_eq!(content(&filled[1]), "# Written");
+        assert_eq!(filled[2], edits[2]);
+    }
+}
@@ -0,0 +1,169 @@
+use serde::{Deserialize, Serialize};
+use crate::models::*;
+use crate::processors::content_summary;
+
+/// Keywords that hint at each section type
+const KEYWORDS: &[(&str, &[&str])] = &[
+    (
+        "intent",
+        &["intent", "goal", "goals", "purpose", "objective", "objectives", "aim", "why", "vision", "mission", "outcome", "problem", "motivation", "context"],
+    ),
+    (
+        "evaluation",
+        &["evaluation", "evaluate", "criteria", "metric", "metrics", "measure", "test", "tests", "verify", "validate", "review", "success", "acceptance", "quality", "kpi", "score", "check", "checks"],
+    ),
+    (
+        "process",
+        &["process", "step", "steps", "workflow", "procedure", "how", "implement", "implementation", "build", "execute", "plan", "task", "tasks", "first", "then", "next", "pipeline", "method"],
+    ),
+    (
+        "alternatives",
+        &["alternative", "alternatives", "option", "options", "instead", "tradeoff", "tradeoffs", "trade-off", "versus", "vs", "compare", "comparison", "fallback", "otherwise", "approaches"],
+    ),
+];
+
+/// A title keyword counts as much as this many content keywords
+const TITLE_WEIGHT: usize = 3;
+
+/// Suggested section type with the keywords that led to it
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct TypeSuggestion {
+    pub section_type: String,
+    /// Share of all keyword hits that point at this type, between 0 and 1
+    pub confidence: f64,
+    pub keywords: Vec<String>,
+}
+
+/// A suggested type for a flow node that has no section yet
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct NodeTypeSuggestion {
+    pub node_id: String,
+    pub label: String,
+    pub suggestion: TypeSuggestion,
+}
+
+/// Suggest a section type from a title (heading or node label) and content
+///
+/// Counts keywords per type, with title hits weighted higher; None when no
+/// keyword matches. Ties go to the type listed first in `SECTION_TYPES`.
+pub fn infer_section_type(title: &str, content: &str) -> Option<TypeSuggestion> {
+    let title_words = words(title);
+    let content_words = words(content);
+
+    let scores: Vec<(&str, usize, Vec<String>)> = KEYWORDS
+        .iter()
+        .map(|(section_type, keywords)| {
+            let mut matched: Vec<String> = Vec::new();
+            let mut score = 0;
+            for (words, weight) in [(&title_words, TITLE_WEIGHT), (&content_words, 1)] {
+                for word in words.iter().filter(|word| keywords.contains(&word.as_str())) {
+                    score += weight;
+                    if !matched.contains(word) {
+                        matched.push(word.clone());
+                    }
+                }
+            }
+            (*section_type, score, matched)
+        })
+        .collect();
+
+    let total: usize = scores.iter().map(|(_, score, _)| score).sum();
+    let best = scores
+        .into_iter()
+        .fold(None, |best: Option<(&str, usize, Vec<String>)>, candidate| match best {
+            Some(best) if best.1 >= candidate.1 => Some(best),
+            _ => Some(candidate),
+        })?;
+    if best.1 == 0 {
+        return None;
+    }
+
+    Some(TypeSuggestion {
+        section_type: best.0.to_string(),
+        confidence: best.1 as f64 / total as f64,
+        keywords: best.2,
+    })
+}
+
+/// Suggest a section type for every node without a click action, from its label
+pub fn suggest_node_section_types(flow: &FlowGraph) -> Vec<NodeTypeSuggestion> {
+    flow.parsed_graph
+        .nodes
+        .iter()
+        .filter(|node| node.ref_section_id.is_none())
+        .filter_map(|node| {
+            infer_section_type(&node.label, "").map(|suggestion| NodeTypeSuggestion {
+                node_id: node.id.clone(),
+                label: node.label.clone(),
+                suggestion,
+            })
+        })
+        .collect()
+}
+
+/// Suggest a type for a section from its first heading and content
+pub fn infer_type_of_section(section: &Section) -> Option<TypeSuggestion> {
+    let heading = content_summary::first_heading(&section.content).unwrap_or_default();
+    infer_section_type(&heading, &section.content)
+}
+
+fn words(text: &str) -> Vec<String> {
+    text.split(|c: char| !c.is_alphanumeric() && c != '-')
+        .map(|word| word.trim_matches('-').to_lowercase())
+        .filter(|word| !word.is_empty())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parsers::mermaid_parser::parse_mermaid;
+
+    #[test]
+    fn test_infer_section_type() {
+        let suggestion = infer_section_type("Success criteria", "Answers must be accurate.").unwrap();
+        assert_eq!(suggestion.section_type, "evaluation");
+        assert_eq!(suggestion.keywords, vec!["success", "criteria"]);
+        assert_eq!(suggestion.confidence, 1.0);
+
+        let suggestion = infer_section_type("Options", "First try A, then compare it with B").unwrap();
+        assert_eq!(suggestion.section_type, "alternatives");
+        assert!(suggestion.confidence < 1.0);
+
+        assert!(infer_section_type("Notes", "Lorem ipsum").is_none());
+    }
+
+    #[test]
+    fn test_title_outweighs_content() {
+        let suggestion = infer_section_type("Our goal", "Steps: build it, then test it").unwrap();
+        assert_eq!(suggestion.section_type, "intent");
+    }
+
+    #[test]
+    fn test_infer_type_of_section() {
+        let section = Section::new("notes-1", "process", "# Why we do this\nThe purpose is clarity");
+        assert_eq!(infer_type_of_section(&section).unwrap().section_type, "intent");
+    }
+
+    #[test]
+    fn test_suggest_node_section_types() {
+        let mut parsed_graph = parse_mermaid("flowchart TD\n  A[Define goal] --> B[Run tests]\n  B --> C[Misc]").unwrap();
+        parsed_graph.nodes[0].ref_section_id = Some("intent-1".to_string());
+        let flow = FlowGraph {
+            id: "flow-1".to_string(),
+            version: "1.0".to_string(),
+            title: None,
+            mermaid_code: String::new(),
+            parsed_graph,
+            node_refs: vec![],
+            layout: None,
+            node_metadata: Default::default(),
+        };
+
+        let suggestions = suggest_node_section_types(&flow);
+
+        assert_eq!(suggestions.len(), 1);
+        assert_eq!(suggestions[0].node_id, "B");
+        assert_eq!(suggestions[0].suggestion.section_type, "evaluation");
+    }
+}
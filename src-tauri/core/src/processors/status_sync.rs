@@ -0,0 +1,301 @@
+use std::collections::{BTreeMap, HashMap};
+use std::sync::LazyLock;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use crate::models::*;
+use crate::parsers::mermaid_parser;
+use crate::processors::document_edits::{find_section, find_section_mut};
+use crate::processors::flow_navigation::flatten;
+use crate::processors::section_frontmatter::{section_frontmatter, set_frontmatter_field};
+
+/// Section statuses mirrored on the flow, each with the `classDef` added for it
+pub const STATUS_CLASSES: [(&str, &str); 3] = [
+    ("draft", "fill:#fff4e0,stroke:#d9a441"),
+    ("review", "fill:#e6f0ff,stroke:#4a7fd0"),
+    ("final", "fill:#e5f5e5,stroke:#3f9a3f"),
+];
+
+/// Prefix of the class names, so `status-draft` marks a draft node
+const CLASS_PREFIX: &str = "status-";
+
+/// `class A,B status-draft`
+static STATUS_CLASS: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"^(\s*)class\s+([\w,\s]+?)\s+status-([\w-]+);?\s*$").unwrap());
+
+/// Which side of a node↔section link a status was copied from
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum StatusSource {
+    Section,
+    Node,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct StatusChange {
+    pub node_id: String,
+    pub section_id: String,
+    pub status: String,
+    /// The side the status was copied from
+    pub source: StatusSource,
+}
+
+/// Keep the `status` frontmatter of linked sections and the `status-*` classes of their nodes in step
+///
+/// A node is linked to a section by its click action. When the two disagree,
+/// the side that changed since `previous` (the last saved document) wins,
+/// and the section wins a tie. Only `draft`, `review` and `final` are
+/// mirrored; clearing one side does not clear the other. Node classes are
+/// read from and written as `class` lines, with a `classDef` per status.
+/// Encrypted sections are left out, as their content is ciphertext.
+pub fn sync_status(doc: &mut ContextDocument, previous: Option<&ContextDocument>) -> Vec<StatusChange> {
+    let Some(flow) = &doc.flow_graph else {
+        return Vec::new();
+    };
+    let links = linked_nodes(&flow.mermaid_code);
+    let current_nodes = node_statuses(&flow.mermaid_code);
+    let current_sections = section_statuses(&doc.sections);
+    let previous_flow = previous.and_then(|previous| previous.flow_graph.as_ref());
+    let previous_nodes = previous_flow.map(|flow| node_statuses(&flow.mermaid_code)).unwrap_or_default();
+    let previous_sections = previous.map(|previous| section_statuses(&previous.sections)).unwrap_or_default();
+
+    let mut changes = Vec::new();
+    for (section_id, node_ids) in &links {
+        if find_section(&doc.sections, section_id).is_some_and(|section| section.encrypted) {
+            continue;
+        }
+        let section_status = current_sections.get(section_id);
+        let section_changed = previous.is_some() && section_status != previous_sections.get(section_id);
+
+        // A node changed on the canvas updates the section unless the section changed too
+        let node_update = (!section_changed || section_status.is_none())
+            .then(|| {
+                node_ids.iter().find_map(|node_id| {
+                    let status = current_nodes.get(node_id)?;
+                    let changed = previous.is_some() && Some(status) != previous_nodes.get(node_id);
+                    (changed || section_status.is_none()).then_some((node_id, status))
+                })
+            })
+            .flatten()
+            .filter(|(_, status)| Some(*status) != section_status);
+
+        if let Some((node_id, status)) = node_update {
+            changes.push(StatusChange {
+                node_id: node_id.clone(),
+                section_id: section_id.clone(),
+                status: status.clone(),
+                source: StatusSource::Node,
+            });
+            // The section's other nodes follow it
+            for other in node_ids.iter().filter(|other| *other != node_id && current_nodes.get(*other) != Some(status)) {
+                changes.push(StatusChange {
+                    node_id: other.clone(),
+                    section_id: section_id.clone(),
+                    status: status.clone(),
+                    source: StatusSource::Section,
+                });
+            }
+            continue;
+        }
+
+        let Some(section_status) = section_status else { continue };
+        for node_id in node_ids.iter().filter(|node_id| current_nodes.get(*node_id) != Some(section_status)) {
+            changes.push(StatusChange {
+                node_id: node_id.clone(),
+                section_id: section_id.clone(),
+                status: section_status.clone(),
+                source: StatusSource::Section,
+            });
+        }
+    }
+
+    apply_changes(doc, &changes);
+    changes
+}
+
+fn apply_changes(doc: &mut ContextDocument, changes: &[StatusChange]) {
+    for change in changes.iter().filter(|change| change.source == StatusSource::Node) {
+        if let Some(section) = find_section_mut(&mut doc.sections, &change.section_id).filter(|section| !section.encrypted) {
+            section.content = set_frontmatter_field(&section.content, "status", &change.status);
+        }
+    }
+
+    let node_classes: BTreeMap<&str, &str> = changes
+        .iter()
+        .filter(|change| change.source == StatusSource::Section)
+        .map(|change| (change.node_id.as_str(), change.status.as_str()))
+        .collect();
+    if let (Some(flow), false) = (&mut doc.flow_graph, node_classes.is_empty()) {
+        flow.mermaid_code = set_node_classes(&flow.mermaid_code, &node_classes);
+    }
+}
+
+/// Replace the status class of the given nodes, adding any missing `classDef`
+fn set_node_classes(mermaid_code: &str, node_classes: &BTreeMap<&str, &str>) -> String {
+    let mut lines: Vec<String> = Vec::new();
+    for line in mermaid_code.lines() {
+        let Some(caps) = STATUS_CLASS.captures(line) else {
+            lines.push(line.to_string());
+            continue;
+        };
+        let kept: Vec<&str> = caps[2]
+            .split(',')
+            .map(str::trim)
+            .filter(|id| !id.is_empty() && !node_classes.contains_key(id))
+            .collect();
+        if !kept.is_empty() {
+            lines.push(format!("{}class {} {}{}", &caps[1], kept.join(","), CLASS_PREFIX, &caps[3]));
+        }
+    }
+
+    let mut new_lines = Vec::new();
+    for (status, style) in STATUS_CLASSES {
+        let used = node_classes.values().any(|class| *class == status);
+        let defined = lines.iter().any(|line| {
+            line.trim().strip_prefix("classDef ").is_some_and(|rest| rest.split_whitespace().next() == Some(&format!("{}{}", CLASS_PREFIX, status)))
+        });
+        if used && !defined {
+            new_lines.push(format!("  classDef {}{} {}", CLASS_PREFIX, status, style));
+        }
+    }
+    for (node_id, status) in node_classes {
+        new_lines.push(format!("  class {} {}{}", node_id, CLASS_PREFIX, status));
+    }
+
+    let fence_index = lines
+        .iter()
+        .rposition(|line| line.trim() == "```")
+        .filter(|_| mermaid_code.contains("```mermaid"));
+    let insert_at = fence_index.unwrap_or(lines.len());
+    lines.splice(insert_at..insert_at, new_lines);
+    lines.join("\n")
+}
+
+/// Section ID -> IDs of the nodes whose click action links to it, for nodes in the diagram
+fn linked_nodes(mermaid_code: &str) -> BTreeMap<String, Vec<String>> {
+    let node_ids: Vec<String> = mermaid_parser::parse_mermaid(mermaid_code)
+        .map(|graph| graph.nodes.into_iter().map(|node| node.id).collect())
+        .unwrap_or_default();
+    let mut links: BTreeMap<String, Vec<String>> = BTreeMap::new();
+    for node_ref in mermaid_parser::parse_click_actions(mermaid_code).unwrap_or_default() {
+        if node_ids.contains(&node_ref.node_id) {
+            let nodes = links.entry(node_ref.section_id).or_default();
+            if !nodes.contains(&node_ref.node_id) {
+                nodes.push(node_ref.node_id);
+            }
+        }
+    }
+    links
+}
+
+/// Node ID -> status from `class ... status-*` lines; the last line for a node wins
+fn node_statuses(mermaid_code: &str) -> HashMap<String, String> {
+    let mut statuses = HashMap::new();
+    for caps in mermaid_code.lines().filter_map(|line| STATUS_CLASS.captures(line)) {
+        if !is_status(&caps[3]) {
+            continue;
+        }
+        for node_id in caps[2].split(',').map(str::trim).filter(|id| !id.is_empty()) {
+            statuses.insert(node_id.to_string(), caps[3].to_string());
+        }
+    }
+    statuses
+}
+
+/// Section ID -> mirrored `status` frontmatter value, at any depth, leaving out encrypted sections
+fn section_statuses(sections: &[Section]) -> HashMap<String, String> {
+    flatten(sections)
+        .into_iter()
+        .filter(|section| !section.encrypted)
+        .filter_map(|section| {
+            let status = section_frontmatter(section).get("status")?.trim().to_lowercase();
+            is_status(&status).then(|| (section.id.clone(), status))
+        })
+        .collect()
+}
+
+fn is_status(status: &str) -> bool {
+    STATUS_CLASSES.iter().any(|(name, _)| *name == status)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn doc(intent_content: &str, diagram: &str) -> ContextDocument {
+        ContextDocument::builder()
+            .title("Status")
+            .add_section(Section::new("intent-1", "intent", intent_content))
+            .add_section(Section::new("process-1", "process", "Steps"))
+            .flow(diagram)
+            .build()
+            .unwrap()
+    }
+
+    const DIAGRAM: &str = "```mermaid\nflowchart TD\n  A[Intent] --> B[Process]\n  click A \"#intent-1\"\n  click B \"#process-1\"\n```";
+
+    fn diagram(doc: &ContextDocument) -> &str {
+        &doc.flow_graph.as_ref().unwrap().mermaid_code
+    }
+
+    #[test]
+    fn test_section_status_becomes_node_class() {
+        let mut doc = doc("---\nstatus: Review\n---\n# Intent", DIAGRAM);
+        let changes = sync_status(&mut doc, None);
+
+        assert_eq!(changes.len(), 1);
+        assert_eq!((changes[0].node_id.as_str(), changes[0].source), ("A", StatusSource::Section));
+        assert!(diagram(&doc).ends_with("  classDef status-review fill:#e6f0ff,stroke:#4a7fd0\n  class A status-review\n```"));
+        assert!(mermaid_parser::parse_mermaid(diagram(&doc)).is_ok());
+
+        // Already in step
+        assert!(sync_status(&mut doc, None).is_empty());
+    }
+
+    #[test]
+    fn test_changed_node_updates_section() {
+        let saved = doc("---\nstatus: draft\n---\n# Intent", &DIAGRAM.replace("\n```", "\n  class A status-draft\n```"));
+        let mut doc = saved.clone();
+        let flow = doc.flow_graph.as_mut().unwrap();
+        flow.mermaid_code = flow.mermaid_code.replace("class A status-draft", "class A status-final");
+
+        let changes = sync_status(&mut doc, Some(&saved));
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].source, StatusSource::Node);
+        assert_eq!(doc.sections[0].content, "---\nstatus: final\n---\n# Intent");
+    }
+
+    #[test]
+    fn test_encrypted_sections_are_left_alone() {
+        let mut saved = doc("v1:c2FsdA==:bm9uY2U=:Y2lwaGVy", &DIAGRAM.replace("\n```", "\n  class A status-draft\n```"));
+        saved.sections[0].encrypted = true;
+        let mut doc = saved.clone();
+        let flow = doc.flow_graph.as_mut().unwrap();
+        flow.mermaid_code = flow.mermaid_code.replace("class A status-draft", "class A status-final");
+
+        assert!(sync_status(&mut doc, Some(&saved)).is_empty());
+        assert_eq!(doc.sections[0].content, saved.sections[0].content);
+    }
+
+    #[test]
+    fn test_changed_section_wins() {
+        let saved = doc("---\nstatus: draft\n---\n# Intent", &DIAGRAM.replace("\n```", "\n  class A,B status-draft\n```"));
+        let mut doc = saved.clone();
+        doc.sections[0].content = "---\nstatus: final\n---\n# Intent".to_string();
+
+        let changes = sync_status(&mut doc, Some(&saved));
+        assert_eq!(changes.len(), 2);
+        // process-1 had no status, so it takes its node's
+        assert_eq!(doc.sections[1].content, "---\nstatus: draft\n---\nSteps");
+        assert!(diagram(&doc).contains("  class B status-draft\n"));
+        assert!(diagram(&doc).contains("  class A status-final\n"));
+        assert!(diagram(&doc).contains("classDef status-final"));
+        assert!(!diagram(&doc).contains("classDef status-draft"));
+    }
+
+    #[test]
+    fn test_unlinked_and_unknown_statuses_are_ignored() {
+        let mut doc = doc("---\nstatus: blocked\n---\n# Intent", "flowchart TD\n  A[Intent]\n  class A status-odd");
+        assert!(sync_status(&mut doc, None).is_empty());
+        assert_eq!(diagram(&doc), "flowchart TD\n  A[Intent]\n  class A status-odd");
+    }
+}
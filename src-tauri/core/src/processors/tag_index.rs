@@ -0,0 +1,114 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use crate::models::{ContextDocument, Section};
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct TagUsage {
+    pub tag: String,
+    /// Number of sections carrying the tag
+    pub section_count: usize,
+    /// Whether the tag is also listed in the document metadata
+    pub in_meta: bool,
+}
+
+/// All tags used in the document, most used first
+///
+/// Tags are matched case-insensitively; the first spelling encountered is reported.
+pub fn collect_tags(doc: &ContextDocument) -> Vec<TagUsage> {
+    let mut usage: BTreeMap<String, TagUsage> = BTreeMap::new();
+
+    for tag in &doc.meta.tags {
+        usage
+            .entry(tag.to_lowercase())
+            .or_insert_with(|| new_usage(tag))
+            .in_meta = true;
+    }
+
+    for section in &doc.sections {
+        for tag in &section.tags {
+            usage
+                .entry(tag.to_lowercase())
+                .or_insert_with(|| new_usage(tag))
+                .section_count += 1;
+        }
+    }
+
+    let mut tags: Vec<TagUsage> = usage.into_values().collect();
+    tags.sort_by_key(|t| std::cmp::Reverse(t.section_count));
+    tags
+}
+
+fn new_usage(tag: &str) -> TagUsage {
+    TagUsage {
+        tag: tag.to_string(),
+        section_count: 0,
+        in_meta: false,
+    }
+}
+
+/// Keep sections carrying at least one of the tags; an empty tag list keeps everything
+pub fn filter_sections_by_tags(sections: Vec<Section>, tags: &[String]) -> Vec<Section> {
+    if tags.is_empty() {
+        return sections;
+    }
+
+    sections
+        .into_iter()
+        .filter(|section| tags.iter().any(|tag| section.has_tag(tag)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn section(id: &str, tags: &[&str]) -> Section {
+        Section {
+            tags: tags.iter().map(|t| t.to_string()).collect(),
+            ..Section::new(id, "intent", "")
+        }
+    }
+
+    fn document(sections: Vec<Section>) -> ContextDocument {
+        sections
+            .into_iter()
+            .fold(ContextDocument::builder().title("Test").tag("backend").tag("docs"), |builder, section| {
+                builder.add_section(section)
+            })
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_collect_tags() {
+        let doc = document(vec![
+            section("a", &["draft", "Backend"]),
+            section("b", &["draft"]),
+        ]);
+
+        let tags = collect_tags(&doc);
+
+        assert_eq!(tags.len(), 3);
+        assert_eq!(tags[0].tag, "draft");
+        assert_eq!(tags[0].section_count, 2);
+        assert!(!tags[0].in_meta);
+
+        let backend = tags.iter().find(|t| t.tag == "backend").unwrap();
+        assert_eq!(backend.section_count, 1);
+        assert!(backend.in_meta);
+
+        let docs = tags.iter().find(|t| t.tag == "docs").unwrap();
+        assert_eq!(docs.section_count, 0);
+    }
+
+    #[test]
+    fn test_filter_sections_by_tags() {
+        let sections = vec![section("a", &["draft"]), section("b", &["final"]), section("c", &[])];
+
+        let filtered = filter_sections_by_tags(sections.clone(), &["DRAFT".to_string()]);
+        assert_eq!(filtered.len(), 1);
+        assert_eq!(filtered[0].id, "a");
+
+        assert_eq!(filter_sections_by_tags(sections, &[]).len(), 3);
+    }
+}
@@ -0,0 +1,90 @@
+use crate::models::*;
+
+/// Reduce a document to a reusable skeleton
+///
+/// Keeps section IDs, types, nesting, tags, budgets and refTargets, the
+/// headings of each section's content, variable names (with empty values)
+/// and the flow diagram with its click actions and layout. Prose, translations,
+/// transclusions, references, node metadata, the author and custom metadata
+/// fields are dropped. Expects raw (unresolved) content, so `${var}`
+/// placeholders inside headings survive.
+pub fn extract_template(doc: &ContextDocument, name: &str) -> ContextDocument {
+    let mut template = doc.clone();
+
+    template.meta.title = name.to_string();
+    template.meta.author = String::new();
+    template.meta.extra.clear();
+    for variable in &mut template.variables {
+        variable.value = String::new();
+    }
+    strip_sections(&mut template.sections);
+    template.references.clear();
+    if let Some(flow) = &mut template.flow_graph {
+        flow.node_metadata.clear();
+        for node in &mut flow.parsed_graph.nodes {
+            node.metadata = None;
+        }
+    }
+
+    template
+}
+
+fn strip_sections(sections: &mut [Section]) {
+    for section in sections {
+        section.content = headings(&section.content);
+        section.translations.clear();
+        section.transclusions.clear();
+        strip_sections(&mut section.children);
+    }
+}
+
+/// Markdown heading lines, skipping `#` lines inside fenced code blocks
+fn headings(content: &str) -> String {
+    let mut in_fence = false;
+    content
+        .lines()
+        .map(str::trim)
+        .filter(|line| {
+            if line.starts_with("```") || line.starts_with("~~~") {
+                in_fence = !in_fence;
+                return false;
+            }
+            !in_fence && line.starts_with('#')
+        })
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract_template() {
+        let mut process = Section::new("process-1", "process", "# Steps\n\nDo ${task}\n\n```sh\n# not a heading\n```\n\n## Checks\nAll green");
+        process.children.push(Section::new("process-2", "process", "Details only"));
+        process.translations.insert("de".to_string(), "# Schritte".to_string());
+        let mut doc = ContextDocument::builder()
+            .title("Launch plan")
+            .author("Sam")
+            .variable("task", "ship v2")
+            .section("intent-1", "intent", "# Intent for ${task}\nShip it")
+            .build()
+            .unwrap();
+        doc.sections.push(process);
+        doc.meta.extra.insert("client".to_string(), "Acme".to_string());
+
+        let template = extract_template(&doc, "Launch");
+
+        assert_eq!(template.meta.title, "Launch");
+        assert_eq!(template.meta.author, "");
+        assert!(template.meta.extra.is_empty());
+        assert_eq!(template.variables[0].name, "task");
+        assert_eq!(template.variables[0].value, "");
+        assert_eq!(template.sections[0].content, "# Intent for ${task}");
+        assert_eq!(template.sections[1].content, "# Steps\n\n## Checks");
+        assert!(template.sections[1].translations.is_empty());
+        assert_eq!(template.sections[1].children[0].id, "process-2");
+        assert_eq!(template.sections[1].children[0].content, "");
+    }
+}
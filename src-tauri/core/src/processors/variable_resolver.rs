@@ -1,6 +1,13 @@
 use regex::Regex;
+use std::borrow::Cow;
 use std::collections::HashMap;
-use crate::models::{Variable, Section};
+use std::sync::LazyLock;
+use crate::models::{ResolutionContext, Variable, Section};
+
+/// `${name}` references, including dotted built-ins like `${date.today}`; compiled once and shared by every call
+static VARIABLE_PATTERN: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"\$\{([a-zA-Z_][a-zA-Z0-9_]*(?:\.[a-zA-Z_][a-zA-Z0-9_]*)*)\}").unwrap()
+});
 
 pub fn build_variable_map(variables: &[Variable]) -> HashMap<String, String> {
     variables.iter()
@@ -8,20 +15,51 @@ pub fn build_variable_map(variables: &[Variable]) -> HashMap<String, String> {
         .collect()
 }
 
+/// Like [`build_variable_map`], leaving out variables whose `resolveIn` excludes `context`
+pub fn build_variable_map_for(variables: &[Variable], context: ResolutionContext) -> HashMap<String, String> {
+    variables.iter()
+        .filter(|v| v.resolves_in(context))
+        .map(|v| (v.name.clone(), v.value.clone()))
+        .collect()
+}
+
 pub fn resolve_variables(content: &str, variables: &HashMap<String, String>) -> String {
-    let re = Regex::new(r"\$\{([a-zA-Z_][a-zA-Z0-9_]*)\}").unwrap();
+    resolve_variables_borrowed(content, variables).into_owned()
+}
 
-    re.replace_all(content, |caps: &regex::Captures| {
+/// Resolve without allocating when the content has no variable references
+fn resolve_variables_borrowed<'a>(content: &'a str, variables: &HashMap<String, String>) -> Cow<'a, str> {
+    VARIABLE_PATTERN.replace_all(content, |caps: &regex::Captures| {
         let var_name = &caps[1];
         variables.get(var_name)
-            .map(|v| v.clone())
+            .cloned()
             .unwrap_or_else(|| caps[0].to_string())  // Keep original if variable not found
-    }).to_string()
+    })
+}
+
+/// Names of all `${var}` references in content, in order of first appearance
+pub fn find_variable_references(content: &str) -> Vec<String> {
+    let mut names: Vec<String> = Vec::new();
+
+    for caps in VARIABLE_PATTERN.captures_iter(content) {
+        if !names.iter().any(|n| n == &caps[1]) {
+            names.push(caps[1].to_string());
+        }
+    }
+
+    names
 }
 
 pub fn resolve_section_tree(sections: &mut [Section], var_map: &HashMap<String, String>) {
     for section in sections.iter_mut() {
-        section.content = resolve_variables(&section.content, var_map);
+        if let Cow::Owned(resolved) = resolve_variables_borrowed(&section.content, var_map) {
+            section.content = resolved;
+        }
+        for content in section.translations.values_mut() {
+            if let Cow::Owned(resolved) = resolve_variables_borrowed(content, var_map) {
+                *content = resolved;
+            }
+        }
         if !section.children.is_empty() {
             resolve_section_tree(&mut section.children, var_map);
         }
@@ -31,6 +69,7 @@ pub fn resolve_section_tree(sections: &mut [Section], var_map: &HashMap<String,
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::collections::BTreeMap;
 
     #[test]
     fn test_build_variable_map() {
@@ -38,10 +77,18 @@ mod tests {
             Variable {
                 name: "userName".to_string(),
                 value: "Jeremy".to_string(),
+                description: None,
+                sensitive: false,
+                resolve_in: vec![],
+                due: None,
             },
             Variable {
                 name: "goal".to_string(),
                 value: "Ship v1".to_string(),
+                description: None,
+                sensitive: false,
+                resolve_in: vec![],
+                due: None,
             },
         ];
 
@@ -52,6 +99,32 @@ mod tests {
         assert_eq!(map.len(), 2);
     }
 
+    #[test]
+    fn test_build_variable_map_for_context() {
+        let variables = vec![
+            Variable {
+                name: "apiKey".to_string(),
+                value: "secret".to_string(),
+                description: None,
+                sensitive: false,
+                resolve_in: vec![ResolutionContext::Export],
+                due: None,
+            },
+            Variable {
+                name: "goal".to_string(),
+                value: "Ship v1".to_string(),
+                description: None,
+                sensitive: false,
+                resolve_in: vec![],
+                due: None,
+            },
+        ];
+
+        let preview = build_variable_map_for(&variables, ResolutionContext::Preview);
+        assert_eq!(resolve_variables("${apiKey} / ${goal}", &preview), "${apiKey} / Ship v1");
+        assert_eq!(build_variable_map_for(&variables, ResolutionContext::Export).len(), 2);
+    }
+
     #[test]
     fn test_resolve_variables_simple() {
         let mut vars = HashMap::new();
@@ -107,6 +180,23 @@ mod tests {
         assert_eq!(result, "No variables here");
     }
 
+    #[test]
+    fn test_find_variable_references() {
+        let content = "${goal} for ${userName}, then ${goal} again. Not $goal or ${1bad}";
+        let names = find_variable_references(content);
+
+        assert_eq!(names, vec!["goal".to_string(), "userName".to_string()]);
+    }
+
+    #[test]
+    fn test_dotted_names() {
+        let mut vars = HashMap::new();
+        vars.insert("date.today".to_string(), "09.10.2025".to_string());
+
+        assert_eq!(resolve_variables("As of ${date.today}, ${date.}", &vars), "As of 09.10.2025, ${date.}");
+        assert_eq!(find_variable_references("${date.today} ${a.b.c} ${.x}"), vec!["date.today", "a.b.c"]);
+    }
+
     #[test]
     fn test_resolve_section_tree_single() {
         let mut vars = HashMap::new();
@@ -119,6 +209,12 @@ mod tests {
                 content: "Hello ${userName}".to_string(),
                 ref_target: None,
                 children: vec![],
+                budget: None,
+                transclusions: vec![],
+                tags: vec![],
+                translations: BTreeMap::new(),
+                sensitive: false,
+                encrypted: false,
             }
         ];
 
@@ -145,8 +241,20 @@ mod tests {
                         content: "For ${goal}".to_string(),
                         ref_target: None,
                         children: vec![],
+                        budget: None,
+                        transclusions: vec![],
+                        tags: vec![],
+                        translations: BTreeMap::new(),
+                        sensitive: false,
+                        encrypted: false,
                     }
                 ],
+                budget: None,
+                transclusions: vec![],
+                tags: vec![],
+                translations: BTreeMap::new(),
+                sensitive: false,
+                encrypted: false,
             }
         ];
 
@@ -0,0 +1,239 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use crate::error::{ContextError, Result};
+use crate::models::*;
+use crate::processors::locale_format::is_builtin_variable;
+use crate::processors::variable_resolver::find_variable_references;
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum SheetFormat {
+    #[default]
+    Json,
+    Markdown,
+}
+
+/// One row of a variable sheet
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct VariableSheetEntry {
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub value: String,
+    /// IDs of the sections whose content references the variable
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub used_in: Vec<String>,
+}
+
+/// List the variables to fill in: the declared ones in document order, then
+/// the ones referenced in content without being declared (with empty values)
+///
+/// Pass raw (unresolved) sections so the references can be found.
+pub fn build_variable_sheet(doc: &ContextDocument) -> Vec<VariableSheetEntry> {
+    let mut references: Vec<(String, Vec<String>)> = Vec::new();
+    collect_references(&doc.sections, &mut references);
+    let used_in = |name: &str| {
+        references
+            .iter()
+            .filter(|(_, names)| names.iter().any(|n| n == name))
+            .map(|(id, _)| id.clone())
+            .collect::<Vec<_>>()
+    };
+
+    let mut entries: Vec<VariableSheetEntry> = doc
+        .variables
+        .iter()
+        .map(|variable| VariableSheetEntry {
+            name: variable.name.clone(),
+            description: variable.description.clone(),
+            value: variable.value.clone(),
+            used_in: used_in(&variable.name),
+        })
+        .collect();
+
+    for (_, names) in &references {
+        for name in names {
+            if !is_builtin_variable(name) && !entries.iter().any(|entry| &entry.name == name) {
+                entries.push(VariableSheetEntry {
+                    name: name.clone(),
+                    description: None,
+                    value: String::new(),
+                    used_in: used_in(name),
+                });
+            }
+        }
+    }
+
+    entries
+}
+
+fn collect_references(sections: &[Section], references: &mut Vec<(String, Vec<String>)>) {
+    for section in sections {
+        let names = find_variable_references(&section.content);
+        if !names.is_empty() {
+            references.push((section.id.clone(), names));
+        }
+        collect_references(&section.children, references);
+    }
+}
+
+/// Render the sheet as pretty-printed JSON or as a markdown table
+pub fn render_variable_sheet(entries: &[VariableSheetEntry], format: SheetFormat) -> Result<String> {
+    match format {
+        SheetFormat::Json => {
+            serde_json::to_string_pretty(entries).map_err(|e| ContextError::SerializationError(e.to_string()))
+        }
+        SheetFormat::Markdown => {
+            let mut table = String::from("| Variable | Description | Value |\n| --- | --- | --- |\n");
+            for entry in entries {
+                table.push_str(&format!(
+                    "| {} | {} | {} |\n",
+                    escape_cell(&entry.name),
+                    escape_cell(entry.description.as_deref().unwrap_or("")),
+                    escape_cell(&entry.value)
+                ));
+            }
+            Ok(table)
+        }
+    }
+}
+
+/// Read a filled-in sheet back
+///
+/// Accepts the JSON array written by `render_variable_sheet`, a JSON object
+/// mapping names to values, or a markdown table with `Variable` and `Value`
+/// columns (other columns are ignored).
+pub fn parse_variable_sheet(sheet: &str) -> Result<Vec<VariableSheetEntry>> {
+    let trimmed = sheet.trim_start();
+    if trimmed.starts_with('[') {
+        return serde_json::from_str(sheet).map_err(|e| ContextError::InvalidArgument(format!("Invalid variable sheet: {}", e)));
+    }
+    if trimmed.starts_with('{') {
+        let values: BTreeMap<String, String> =
+            serde_json::from_str(sheet).map_err(|e| ContextError::InvalidArgument(format!("Invalid variable sheet: {}", e)))?;
+        return Ok(values
+            .into_iter()
+            .map(|(name, value)| VariableSheetEntry { name, description: None, value, used_in: vec![] })
+            .collect());
+    }
+    parse_markdown_table(sheet)
+}
+
+fn parse_markdown_table(sheet: &str) -> Result<Vec<VariableSheetEntry>> {
+    let mut lines = sheet.lines().map(str::trim).filter(|line| line.starts_with('|'));
+    let header = lines
+        .next()
+        .map(split_row)
+        .ok_or_else(|| ContextError::InvalidArgument("Variable sheet has no table".to_string()))?;
+    let column = |title: &str| {
+        header
+            .iter()
+            .position(|cell| cell.eq_ignore_ascii_case(title))
+            .ok_or_else(|| ContextError::InvalidArgument(format!("Variable sheet has no '{}' column", title)))
+    };
+    let (name_column, value_column) = (column("Variable")?, column("Value")?);
+
+    let mut entries = Vec::new();
+    for line in lines {
+        let cells = split_row(line);
+        if cells.iter().all(|cell| !cell.is_empty() && cell.chars().all(|c| matches!(c, '-' | ':'))) {
+            continue;
+        }
+        let name = cells.get(name_column).cloned().unwrap_or_default();
+        if name.is_empty() {
+            continue;
+        }
+        entries.push(VariableSheetEntry {
+            name,
+            description: None,
+            value: cells.get(value_column).cloned().unwrap_or_default(),
+            used_in: vec![],
+        });
+    }
+    Ok(entries)
+}
+
+fn escape_cell(text: &str) -> String {
+    text.replace('|', "\\|").replace('\n', "<br>")
+}
+
+/// Cells of a `| a | b |` row, with `\|` and `<br>` unescaped
+fn split_row(line: &str) -> Vec<String> {
+    let line = line.strip_prefix('|').unwrap_or(line);
+    let mut cells = Vec::new();
+    let mut cell = String::new();
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' if chars.peek() == Some(&'|') => {
+                cell.push('|');
+                chars.next();
+            }
+            '|' => cells.push(std::mem::take(&mut cell)),
+            _ => cell.push(c),
+        }
+    }
+    if !cell.trim().is_empty() {
+        cells.push(cell);
+    }
+    cells.iter().map(|cell| cell.trim().replace("<br>", "\n")).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn document() -> ContextDocument {
+        let mut doc = ContextDocument::builder()
+            .title("Plan")
+            .variable("goal", "Ship | launch")
+            .variable("owner", "")
+            .section("intent-1", "intent", "Goal: ${goal} for ${team}")
+            .section("process-1", "process", "Owner: ${owner}, goal ${goal} by ${date.year}")
+            .build()
+            .unwrap();
+        doc.variables[1].description = Some("Who signs off".to_string());
+        doc
+    }
+
+    #[test]
+    fn test_build_variable_sheet() {
+        let sheet = build_variable_sheet(&document());
+
+        let names: Vec<&str> = sheet.iter().map(|e| e.name.as_str()).collect();
+        assert_eq!(names, vec!["goal", "owner", "team"]);
+        assert_eq!(sheet[0].used_in, vec!["intent-1".to_string(), "process-1".to_string()]);
+        assert_eq!(sheet[1].description.as_deref(), Some("Who signs off"));
+        assert_eq!(sheet[2].value, "");
+    }
+
+    #[test]
+    fn test_markdown_round_trip() {
+        let mut sheet = build_variable_sheet(&document());
+        sheet[2].value = "Core\nPlatform".to_string();
+        let markdown = render_variable_sheet(&sheet, SheetFormat::Markdown).unwrap();
+        assert!(markdown.contains("| goal |  | Ship \\| launch |\n"));
+
+        let parsed = parse_variable_sheet(&markdown).unwrap();
+        let values: Vec<(&str, &str)> = parsed.iter().map(|e| (e.name.as_str(), e.value.as_str())).collect();
+        assert_eq!(values, vec![("goal", "Ship | launch"), ("owner", ""), ("team", "Core\nPlatform")]);
+    }
+
+    #[test]
+    fn test_parse_json_sheets() {
+        let sheet = build_variable_sheet(&document());
+        let json = render_variable_sheet(&sheet, SheetFormat::Json).unwrap();
+        assert_eq!(parse_variable_sheet(&json).unwrap(), sheet);
+
+        let parsed = parse_variable_sheet(r#"{"team": "Core"}"#).unwrap();
+        assert_eq!((parsed[0].name.as_str(), parsed[0].value.as_str()), ("team", "Core"));
+    }
+
+    #[test]
+    fn test_parse_rejects_sheet_without_columns() {
+        assert!(matches!(parse_variable_sheet("no table here"), Err(ContextError::InvalidArgument(_))));
+        assert!(matches!(parse_variable_sheet("| Name | Value |\n| - | - |"), Err(ContextError::InvalidArgument(_))));
+    }
+}
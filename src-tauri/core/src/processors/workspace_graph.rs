@@ -0,0 +1,159 @@
+use serde::{Deserialize, Serialize};
+use std::path::{Component, Path, PathBuf};
+use crate::models::*;
+use crate::processors::external_refs::{collect_external_refs, DependencyKind};
+use crate::processors::portable_format::platform_path;
+
+/// How the documents of a workspace reference each other
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkspaceGraph {
+    pub nodes: Vec<WorkspaceNode>,
+    pub edges: Vec<WorkspaceEdge>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkspaceNode {
+    pub file_path: String,
+    pub title: String,
+}
+
+/// One document referencing another, through refTargets or markdown links
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkspaceEdge {
+    pub from: String,
+    pub to: String,
+    /// `transclusion` for refTargets, `attachment` for markdown links
+    pub kind: DependencyKind,
+    /// Sections of `from` holding the reference
+    pub section_ids: Vec<String>,
+}
+
+impl WorkspaceGraph {
+    /// Edges pointing at the document, i.e. the documents that reference it
+    pub fn backlinks(&self, file_path: &str) -> Vec<&WorkspaceEdge> {
+        let target = normalize(Path::new(file_path));
+        self.edges
+            .iter()
+            .filter(|edge| normalize(Path::new(&edge.to)) == target)
+            .collect()
+    }
+}
+
+/// Build the graph of `(file path, document)` pairs
+///
+/// Reference targets are resolved against the referencing document's folder
+/// and compared lexically (`.` and `..` folded), so the file paths should be
+/// given in one consistent form. References to files outside the workspace
+/// and self-references are left out.
+pub fn build_workspace_graph(documents: &[(String, ContextDocument)]) -> WorkspaceGraph {
+    let paths: Vec<PathBuf> = documents.iter().map(|(file_path, _)| normalize(Path::new(file_path))).collect();
+
+    let mut edges = Vec::new();
+    for ((file_path, doc), path) in documents.iter().zip(&paths) {
+        let base_dir = path.parent().unwrap_or(Path::new(""));
+        for (target, kind, section_ids) in collect_external_refs(&doc.sections) {
+            let target = normalize(&base_dir.join(platform_path(&target)));
+            let Some(index) = paths.iter().position(|candidate| *candidate == target) else {
+                continue;
+            };
+            if target == *path {
+                continue;
+            }
+            edges.push(WorkspaceEdge {
+                from: file_path.clone(),
+                to: documents[index].0.clone(),
+                kind,
+                section_ids,
+            });
+        }
+    }
+
+    WorkspaceGraph {
+        nodes: documents
+            .iter()
+            .map(|(file_path, doc)| WorkspaceNode {
+                file_path: file_path.clone(),
+                title: doc.meta.title.clone(),
+            })
+            .collect(),
+        edges,
+    }
+}
+
+/// Fold `.` and `..` components without touching the file system
+fn normalize(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                if !normalized.pop() {
+                    normalized.push("..");
+                }
+            }
+            other => normalized.push(other),
+        }
+    }
+    normalized
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn document(title: &str, sections: Vec<Section>) -> ContextDocument {
+        sections
+            .into_iter()
+            .fold(ContextDocument::builder().title(title), |builder, section| builder.add_section(section))
+            .build()
+            .unwrap()
+    }
+
+    fn workspace() -> Vec<(String, ContextDocument)> {
+        let mut transcluding = Section::new("process-1", "process", "See [the brief](../brief.xml#intent-1)");
+        transcluding.ref_target = Some("../brief.xml#intent-1 ./plan.xml#process-2 other.xml#x".to_string());
+        vec![
+            ("ws/brief.xml".to_string(), document("Brief", vec![Section::new("intent-1", "intent", "Goal")])),
+            ("ws/plans/plan.xml".to_string(), document("Plan", vec![transcluding])),
+            (
+                "ws/notes.xml".to_string(),
+                document("Notes", vec![Section::new("intent-1", "intent", "[plan](plans/plan.xml) [web](https://x.io)")]),
+            ),
+        ]
+    }
+
+    #[test]
+    fn test_build_workspace_graph() {
+        let graph = build_workspace_graph(&workspace());
+
+        assert_eq!(graph.nodes.len(), 3);
+        assert_eq!(graph.nodes[1].title, "Plan");
+        let edges: Vec<(&str, &str, DependencyKind)> = graph
+            .edges
+            .iter()
+            .map(|edge| (edge.from.as_str(), edge.to.as_str(), edge.kind))
+            .collect();
+        assert_eq!(
+            edges,
+            vec![
+                ("ws/plans/plan.xml", "ws/brief.xml", DependencyKind::Transclusion),
+                ("ws/plans/plan.xml", "ws/brief.xml", DependencyKind::Attachment),
+                ("ws/notes.xml", "ws/plans/plan.xml", DependencyKind::Attachment),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_backlinks() {
+        let graph = build_workspace_graph(&workspace());
+
+        let backlinks = graph.backlinks("ws/./plans/plan.xml");
+        assert_eq!(backlinks.len(), 1);
+        assert_eq!(backlinks[0].from, "ws/notes.xml");
+        assert_eq!(backlinks[0].section_ids, vec!["intent-1"]);
+        assert!(graph.backlinks("ws/notes.xml").is_empty());
+    }
+}
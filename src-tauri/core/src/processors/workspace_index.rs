@@ -0,0 +1,175 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use crate::models::*;
+use crate::processors::flow_navigation::flatten;
+use crate::processors::variable_resolver::find_variable_references;
+
+/// Tags and variable names of every document in a workspace
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkspaceIndex {
+    pub documents: Vec<IndexedDocument>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct IndexedDocument {
+    pub file_path: String,
+    pub title: String,
+    /// Keyed by lowercase tag
+    pub tags: BTreeMap<String, TagLocation>,
+    /// Keyed by variable name
+    pub variables: BTreeMap<String, VariableLocation>,
+}
+
+/// Where a tag appears in one document
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct TagLocation {
+    /// Listed in the document metadata
+    pub in_meta: bool,
+    pub section_ids: Vec<String>,
+}
+
+/// Where a variable is declared and used in one document
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct VariableLocation {
+    /// Value of the `<var>` declaration; None when the variable is only referenced
+    pub value: Option<String>,
+    /// Sections with a `${name}` reference
+    pub section_ids: Vec<String>,
+}
+
+/// A document found by a workspace query, with what matched in it
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkspaceMatch<T> {
+    pub file_path: String,
+    pub title: String,
+    #[serde(flatten)]
+    pub location: T,
+}
+
+impl WorkspaceIndex {
+    /// Index `(file path, document)` pairs, keeping their order
+    pub fn build(documents: &[(String, ContextDocument)]) -> Self {
+        WorkspaceIndex {
+            documents: documents
+                .iter()
+                .map(|(file_path, doc)| index_document(file_path, doc))
+                .collect(),
+        }
+    }
+
+    /// Documents carrying the tag in their metadata or on any section (case-insensitive)
+    pub fn documents_by_tag(&self, tag: &str) -> Vec<WorkspaceMatch<TagLocation>> {
+        let key = tag.trim().to_lowercase();
+        self.matches(|doc| doc.tags.get(&key))
+    }
+
+    /// Documents declaring or referencing the variable
+    pub fn variable_usages(&self, name: &str) -> Vec<WorkspaceMatch<VariableLocation>> {
+        self.matches(|doc| doc.variables.get(name.trim()))
+    }
+
+    fn matches<'a, T: Clone + 'a>(&'a self, find: impl Fn(&'a IndexedDocument) -> Option<&'a T>) -> Vec<WorkspaceMatch<T>> {
+        self.documents
+            .iter()
+            .filter_map(|doc| {
+                find(doc).map(|location| WorkspaceMatch {
+                    file_path: doc.file_path.clone(),
+                    title: doc.title.clone(),
+                    location: location.clone(),
+                })
+            })
+            .collect()
+    }
+}
+
+fn index_document(file_path: &str, doc: &ContextDocument) -> IndexedDocument {
+    let mut tags: BTreeMap<String, TagLocation> = BTreeMap::new();
+    let mut variables: BTreeMap<String, VariableLocation> = BTreeMap::new();
+
+    for tag in &doc.meta.tags {
+        tags.entry(tag.to_lowercase()).or_default().in_meta = true;
+    }
+    for variable in &doc.variables {
+        variables.entry(variable.name.clone()).or_default().value = Some(variable.value.clone());
+    }
+
+    for section in flatten(&doc.sections) {
+        for tag in &section.tags {
+            push_unique(&mut tags.entry(tag.to_lowercase()).or_default().section_ids, &section.id);
+        }
+        let contents = std::iter::once(&section.content).chain(section.translations.values());
+        for name in contents.flat_map(|content| find_variable_references(content)) {
+            push_unique(&mut variables.entry(name).or_default().section_ids, &section.id);
+        }
+    }
+
+    IndexedDocument {
+        file_path: file_path.to_string(),
+        title: doc.meta.title.clone(),
+        tags,
+        variables,
+    }
+}
+
+fn push_unique(ids: &mut Vec<String>, id: &str) {
+    if !ids.iter().any(|existing| existing == id) {
+        ids.push(id.to_string());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn workspace() -> WorkspaceIndex {
+        let mut tagged = Section::new("process-1", "process", "Deploy for ${client} in ${region}");
+        tagged.tags = vec!["Backend".to_string()];
+        let onboarding = ContextDocument::builder()
+            .title("Onboarding")
+            .tag("docs")
+            .variable("client", "Acme")
+            .section("intent-1", "intent", "Welcome ${client}")
+            .add_section(tagged)
+            .build()
+            .unwrap();
+        let release = ContextDocument::builder()
+            .title("Release")
+            .tag("backend")
+            .section("intent-1", "intent", "Ship to ${region}")
+            .build()
+            .unwrap();
+
+        WorkspaceIndex::build(&[("a.xml".to_string(), onboarding), ("b.xml".to_string(), release)])
+    }
+
+    #[test]
+    fn test_documents_by_tag() {
+        let matches = workspace().documents_by_tag("BACKEND");
+
+        assert_eq!(matches.len(), 2);
+        assert_eq!(matches[0].file_path, "a.xml");
+        assert_eq!(matches[0].location, TagLocation { in_meta: false, section_ids: vec!["process-1".to_string()] });
+        assert_eq!(matches[1].location, TagLocation { in_meta: true, section_ids: vec![] });
+        assert!(workspace().documents_by_tag("missing").is_empty());
+    }
+
+    #[test]
+    fn test_variable_usages() {
+        let index = workspace();
+
+        let client = index.variable_usages("client");
+        assert_eq!(client.len(), 1);
+        assert_eq!(client[0].location.value.as_deref(), Some("Acme"));
+        assert_eq!(client[0].location.section_ids, vec!["intent-1", "process-1"]);
+
+        let region = index.variable_usages("region");
+        assert_eq!(region.len(), 2);
+        assert_eq!(region[1].title, "Release");
+        assert_eq!(region[1].location.value, None);
+    }
+}
@@ -0,0 +1,121 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use crate::error::{ContextError, Result};
+use crate::models::*;
+
+/// Value of `format` in every JSON document, so tools can recognise the file
+pub const JSON_DOCUMENT_FORMAT: &str = "flow-writer-document";
+/// Bumped on breaking changes to the JSON layout of [`ContextDocument`]
+pub const JSON_DOCUMENT_VERSION: u32 = 1;
+
+/// A context document wrapped with its format, as written by [`to_json`]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+struct JsonDocument {
+    format: String,
+    format_version: u32,
+    document: ContextDocument,
+}
+
+/// Serialize a context document to pretty-printed JSON
+///
+/// Field names are those of the models. Derived data (parsed graph, node
+/// refs, transclusions) is written as it is in `doc`, so callers wanting it
+/// pass an enriched document.
+pub fn to_json(doc: &ContextDocument) -> Result<String> {
+    let wrapped = JsonDocument {
+        format: JSON_DOCUMENT_FORMAT.to_string(),
+        format_version: JSON_DOCUMENT_VERSION,
+        document: doc.clone(),
+    };
+    serde_json::to_string_pretty(&wrapped).map_err(|e| ContextError::SerializationError(e.to_string()))
+}
+
+/// Parse a document written by [`to_json`] or by another tool in the same layout
+///
+/// `parsedGraph`, `node_refs` and transclusions may be left out; whatever is
+/// given is dropped, as with a parsed XML file, and rebuilt on load.
+pub fn from_json(json: &str) -> Result<ContextDocument> {
+    let mut value: Value = serde_json::from_str(json).map_err(|e| ContextError::SerializationError(e.to_string()))?;
+
+    let format = value.get("format").and_then(Value::as_str);
+    if format != Some(JSON_DOCUMENT_FORMAT) {
+        return Err(ContextError::InvalidArgument(format!("Not a flow-writer JSON document; expected format \"{}\"", JSON_DOCUMENT_FORMAT)));
+    }
+    let version = value.get("formatVersion").and_then(Value::as_u64).unwrap_or_default();
+    if version == 0 || version > JSON_DOCUMENT_VERSION as u64 {
+        return Err(ContextError::InvalidArgument(format!("Unsupported JSON document version {}", version)));
+    }
+
+    let mut document = value
+        .get_mut("document")
+        .map(Value::take)
+        .ok_or_else(|| ContextError::MissingRequiredField("document".to_string()))?;
+    if let Some(flow) = document.get_mut("flow_graph").and_then(Value::as_object_mut) {
+        flow.insert("parsed_graph".to_string(), json!({ "nodes": [], "edges": [] }));
+        flow.insert("node_refs".to_string(), json!([]));
+    }
+
+    let mut doc: ContextDocument = serde_json::from_value(document).map_err(|e| ContextError::SerializationError(e.to_string()))?;
+    clear_transclusions(&mut doc.sections);
+    Ok(doc)
+}
+
+fn clear_transclusions(sections: &mut [Section]) {
+    for section in sections {
+        section.transclusions.clear();
+        clear_transclusions(&mut section.children);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parsers::mermaid_parser;
+
+    fn document() -> ContextDocument {
+        let mut intent = Section::new("intent-1", "intent", "# Intent\nUser: ${userName}");
+        intent.children.push(Section::new("intent-2", "intent", "Details"));
+        ContextDocument::builder()
+            .title("Plan")
+            .variable("userName", "Jeremy")
+            .add_section(intent)
+            .flow("flowchart TD\n  A[Intent] --> B[Process]\n  click A \"#intent-1\"")
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let doc = document();
+        let json = to_json(&doc).unwrap();
+
+        assert_eq!(from_json(&json).unwrap(), doc);
+        let value: Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(value["format"], JSON_DOCUMENT_FORMAT);
+        assert_eq!(value["document"]["sections"][0]["type"], "intent");
+    }
+
+    #[test]
+    fn test_derived_flow_data_is_optional_and_dropped() {
+        let mut enriched = document();
+        mermaid_parser::enrich_flow_graph(enriched.flow_graph.as_mut().unwrap()).unwrap();
+        assert_eq!(from_json(&to_json(&enriched).unwrap()).unwrap(), document());
+
+        let mut value: Value = serde_json::from_str(&to_json(&document()).unwrap()).unwrap();
+        let flow = value["document"]["flow_graph"].as_object_mut().unwrap();
+        flow.remove("parsed_graph");
+        flow.remove("node_refs");
+        assert_eq!(from_json(&value.to_string()).unwrap(), document());
+    }
+
+    #[test]
+    fn test_rejects_other_formats() {
+        assert!(matches!(from_json(r#"{"nodes": []}"#), Err(ContextError::InvalidArgument(_))));
+        assert!(matches!(
+            from_json(r#"{"format": "flow-writer-document", "formatVersion": 9, "document": {}}"#),
+            Err(ContextError::InvalidArgument(_))
+        ));
+        assert!(matches!(from_json("<context/>"), Err(ContextError::SerializationError(_))));
+    }
+}
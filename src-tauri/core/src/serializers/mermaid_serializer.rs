@@ -0,0 +1,258 @@
+use std::collections::HashSet;
+use crate::models::*;
+
+/// Generate Mermaid flowchart code for a graph
+///
+/// Nodes are declared first, those of subgraphs inside their blocks, then
+/// edges, then click actions, so the output parses back into the same
+/// `GraphStructure` and node references. Labels are quoted and escaped only
+/// when they need it.
+pub fn generate_mermaid(graph: &GraphStructure, direction: FlowDirection, node_refs: &[NodeReference]) -> String {
+    let mut code = format!("flowchart {}\n", direction_keyword(direction));
+
+    let grouped: HashSet<&str> = graph.subgraphs.iter().flat_map(|s| s.node_ids.iter().map(String::as_str)).collect();
+    for node in graph.nodes.iter().filter(|node| !grouped.contains(node.id.as_str())) {
+        write_node(&mut code, node, 1);
+    }
+    // A parent missing from the graph leaves its subgraph at the top level
+    let is_top_level = |subgraph: &&Subgraph| subgraph.parent_id.as_ref().is_none_or(|parent| !graph.subgraphs.iter().any(|s| &s.id == parent));
+    for subgraph in graph.subgraphs.iter().filter(is_top_level) {
+        write_subgraph(&mut code, graph, subgraph, 1);
+    }
+    for edge in &graph.edges {
+        match &edge.label {
+            Some(label) => code.push_str(&format!("    {} -->|{}| {}\n", edge.from, edge_label(label), edge.to)),
+            None => code.push_str(&format!("    {} --> {}\n", edge.from, edge.to)),
+        }
+    }
+    for node_ref in node_refs {
+        code.push_str(&format!("    click {} \"{}\"", node_ref.node_id, escape_label(&node_ref.click_action)));
+        if let Some(tooltip) = &node_ref.tooltip {
+            code.push_str(&format!(" \"{}\"", escape_label(tooltip)));
+        }
+        code.push('\n');
+    }
+
+    code
+}
+
+fn write_node(code: &mut String, node: &GraphNode, depth: usize) {
+    let (open, close) = shape_delimiters(&node.node_type);
+    code.push_str(&format!("{}{}{}{}{}\n", "    ".repeat(depth), node.id, open, node_label(&node.label), close));
+}
+
+fn write_subgraph(code: &mut String, graph: &GraphStructure, subgraph: &Subgraph, depth: usize) {
+    let indent = "    ".repeat(depth);
+    if subgraph.title == subgraph.id {
+        code.push_str(&format!("{}subgraph {}\n", indent, subgraph.id));
+    } else {
+        code.push_str(&format!("{}subgraph {}[{}]\n", indent, subgraph.id, node_label(&subgraph.title)));
+    }
+    for node in subgraph.node_ids.iter().filter_map(|id| graph.nodes.iter().find(|node| &node.id == id)) {
+        write_node(code, node, depth + 1);
+    }
+    for child in graph.subgraphs.iter().filter(|s| s.parent_id.as_ref() == Some(&subgraph.id)) {
+        write_subgraph(code, graph, child, depth + 1);
+    }
+    code.push_str(&format!("{}end\n", indent));
+}
+
+/// Escape characters that would end or break a quoted Mermaid label
+///
+/// Quotes and pipes become entity codes (`#quot;`, `#124;`), and a `#` that
+/// would otherwise read as the start of an entity becomes `#35;`. Everything
+/// else, including unicode, is kept as written. The inverse is
+/// `mermaid_parser::unescape_label`.
+pub fn escape_label(label: &str) -> String {
+    let mut escaped = String::with_capacity(label.len());
+    for (index, c) in label.char_indices() {
+        match c {
+            '"' => escaped.push_str("#quot;"),
+            '|' => escaped.push_str("#124;"),
+            '#' if looks_like_entity(&label[index + 1..]) => escaped.push_str("#35;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Whether text after a `#` reads as the rest of an entity code, e.g. `quot;` or `35;`
+fn looks_like_entity(rest: &str) -> bool {
+    let name_len = rest.chars().take_while(char::is_ascii_alphanumeric).count();
+    name_len > 0 && rest[name_len..].starts_with(';')
+}
+
+/// Whether a label must be quoted: it contains shape, link, edge or entity
+/// syntax, has surrounding whitespace, or is empty
+fn needs_quotes(label: &str) -> bool {
+    label.is_empty()
+        || label.trim() != label
+        || label.contains("--")
+        || label.contains(['"', '|', '[', ']', '(', ')', '{', '}', '#', ';', '\n'])
+}
+
+fn node_label(label: &str) -> String {
+    if needs_quotes(label) {
+        format!("\"{}\"", escape_label(label))
+    } else {
+        label.to_string()
+    }
+}
+
+fn edge_label(label: &str) -> String {
+    // Edge labels are only delimited by pipes, so brackets may stay bare
+    if label.is_empty() || label.trim() != label || label.contains(['"', '|', '#', ';']) {
+        format!("\"{}\"", escape_label(label))
+    } else {
+        label.to_string()
+    }
+}
+
+fn direction_keyword(direction: FlowDirection) -> &'static str {
+    match direction {
+        FlowDirection::TopDown => "TD",
+        FlowDirection::BottomUp => "BT",
+        FlowDirection::LeftRight => "LR",
+        FlowDirection::RightLeft => "RL",
+    }
+}
+
+fn shape_delimiters(node_type: &NodeType) -> (&'static str, &'static str) {
+    match node_type {
+        NodeType::Rectangle => ("[", "]"),
+        NodeType::RoundEdges => ("(", ")"),
+        NodeType::Stadium => ("([", "])"),
+        NodeType::Subroutine => ("[[", "]]"),
+        NodeType::Cylindrical => ("[(", ")]"),
+        NodeType::Circle => ("((", "))"),
+        NodeType::Asymmetric => (">", "]"),
+        NodeType::Rhombus => ("{", "}"),
+        NodeType::Hexagon => ("{{", "}}"),
+        NodeType::Parallelogram => ("[/", "/]"),
+        NodeType::Trapezoid => ("[/", "\\]"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parsers::mermaid_parser::{parse_click_actions, parse_direction, parse_mermaid, unescape_label};
+
+    fn node(id: &str, label: &str, node_type: NodeType) -> GraphNode {
+        GraphNode {
+            id: id.to_string(),
+            label: label.to_string(),
+            node_type,
+            ref_section_id: None,
+            metadata: None,
+        }
+    }
+
+    fn edge(from: &str, to: &str, label: Option<&str>) -> GraphEdge {
+        GraphEdge {
+            from: from.to_string(),
+            to: to.to_string(),
+            label: label.map(String::from),
+            condition: label.and_then(crate::parsers::mermaid_parser::parse_condition),
+        }
+    }
+
+    #[test]
+    fn test_escape_label() {
+        assert_eq!(escape_label(r#"say "hi" | bye"#), "say #quot;hi#quot; #124; bye");
+        assert_eq!(escape_label("Issue #12 and #quot;"), "Issue #12 and #35;quot;");
+        assert_eq!(escape_label("Größe → 日本"), "Größe → 日本");
+
+        for label in [r#"a "b" | c"#, "#35; literal", "#quot;", "ends with #", "🚀 launch"] {
+            assert_eq!(unescape_label(&escape_label(label)), label);
+        }
+    }
+
+    #[test]
+    fn test_special_labels_round_trip() {
+        let graph = GraphStructure {
+            nodes: vec![
+                node("A", "Intent [draft] --> review", NodeType::Rectangle),
+                node("B", r#"Check "quality""#, NodeType::Rectangle),
+                node("C", "Größe prüfen", NodeType::RoundEdges),
+            ],
+            edges: vec![
+                edge("A", "B", Some("yes | no")),
+                edge("B", "C", Some(r#"say "go""#)),
+                edge("C", "A", Some("cond: score >= 85")),
+                edge("A", "C", Some("日本 → ünïcödé")),
+                edge("B", "A", None),
+            ],
+            subgraphs: vec![],
+        };
+
+        let code = generate_mermaid(&graph, FlowDirection::LeftRight, &[]);
+
+        assert!(code.contains(r#"A -->|"yes #124; no"| B"#), "{}", code);
+        assert_eq!(parse_mermaid(&code).unwrap(), graph);
+        assert_eq!(parse_direction(&code), FlowDirection::LeftRight);
+    }
+
+    #[test]
+    fn test_shapes_round_trip() {
+        let node_types = [
+            NodeType::Rectangle,
+            NodeType::RoundEdges,
+            NodeType::Stadium,
+            NodeType::Subroutine,
+            NodeType::Cylindrical,
+            NodeType::Circle,
+            NodeType::Asymmetric,
+            NodeType::Rhombus,
+            NodeType::Hexagon,
+            NodeType::Parallelogram,
+            NodeType::Trapezoid,
+        ];
+        let graph = GraphStructure {
+            nodes: node_types.into_iter().enumerate().map(|(i, node_type)| node(&format!("N{}", i), "Step (one)", node_type)).collect(),
+            edges: vec![edge("N7", "N8", Some("yes"))],
+            subgraphs: vec![],
+        };
+
+        let code = generate_mermaid(&graph, FlowDirection::TopDown, &[]);
+
+        assert!(code.contains(r#"N7{"Step (one)"}"#), "{}", code);
+        assert_eq!(parse_mermaid(&code).unwrap(), graph);
+    }
+
+    #[test]
+    fn test_subgraphs_round_trip() {
+        let subgraph = |id: &str, title: &str, node_ids: &[&str], parent_id: Option<&str>| Subgraph {
+            id: id.to_string(),
+            title: title.to_string(),
+            node_ids: node_ids.iter().map(|id| id.to_string()).collect(),
+            parent_id: parent_id.map(str::to_string),
+            ref_section_id: None,
+        };
+        let graph = GraphStructure {
+            nodes: vec![node("A", "Plan", NodeType::Rectangle), node("B", "Build", NodeType::Rectangle), node("C", "Ship", NodeType::Stadium)],
+            edges: vec![edge("A", "B", None), edge("B", "C", None)],
+            subgraphs: vec![subgraph("phase1", "Phase [1]", &["B"], None), subgraph("release", "release", &["C"], Some("phase1"))],
+        };
+
+        let code = generate_mermaid(&graph, FlowDirection::TopDown, &[]);
+
+        assert!(code.contains("    subgraph phase1[\"Phase [1]\"]\n        B[Build]\n        subgraph release\n            C([Ship])\n        end\n    end\n"), "{}", code);
+        assert_eq!(parse_mermaid(&code).unwrap(), graph);
+    }
+
+    #[test]
+    fn test_click_actions_round_trip() {
+        let node_refs = vec![NodeReference {
+            node_id: "A".to_string(),
+            section_id: "intent-1".to_string(),
+            click_action: "#intent-1".to_string(),
+            tooltip: Some(r#"Open "Intent""#.to_string()),
+        }];
+        let graph = GraphStructure { nodes: vec![node("A", "Intent", NodeType::Rectangle)], edges: vec![], subgraphs: vec![] };
+
+        let code = generate_mermaid(&graph, FlowDirection::TopDown, &node_refs);
+
+        assert_eq!(parse_click_actions(&code).unwrap(), node_refs);
+    }
+}
@@ -0,0 +1,6 @@
+pub mod json_serializer;
+pub mod mermaid_serializer;
+pub mod xml_serializer;
+
+pub use mermaid_serializer::*;
+pub use xml_serializer::*;
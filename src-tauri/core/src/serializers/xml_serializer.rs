@@ -0,0 +1,410 @@
+use quick_xml::escape::escape;
+use crate::models::*;
+use std::collections::BTreeMap;
+
+/// Format version written to the root `<context>` element
+pub const DOCUMENT_VERSION: &str = "1.0";
+
+/// Serialize a context document to XML
+///
+/// Section content and flow diagrams are written as CDATA so markdown and
+/// mermaid survive untouched. Derived data (parsed graph, node refs) is not
+/// written; it is rebuilt from the diagram on load.
+pub fn serialize_xml(doc: &ContextDocument) -> String {
+    let mut xml = String::new();
+
+    xml.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    xml.push_str(&format!("<context version=\"{}\">\n", DOCUMENT_VERSION));
+
+    write_meta(&mut xml, &doc.meta);
+    xml.push('\n');
+    write_variables(&mut xml, &doc.variables);
+    xml.push('\n');
+    write_sections(&mut xml, &doc.sections);
+
+    if !doc.references.is_empty() {
+        xml.push('\n');
+        write_references(&mut xml, &doc.references);
+    }
+
+    if let Some(flow) = &doc.flow_graph {
+        xml.push('\n');
+        write_flow(&mut xml, flow);
+    }
+
+    xml.push_str("</context>\n");
+    xml
+}
+
+fn write_meta(xml: &mut String, meta: &MetaData) {
+    xml.push_str("  <meta>\n");
+    write_text_element(xml, 4, "title", &meta.title);
+    write_text_element(xml, 4, "author", &meta.author);
+    write_text_element(xml, 4, "created", &meta.created);
+    xml.push_str(&format!(
+        "    <app name=\"{}\" version=\"{}\"/>\n",
+        escape(meta.app_info.name.as_str()),
+        escape(meta.app_info.version.as_str())
+    ));
+    write_text_element(xml, 4, "tags", &meta.tags.join(", "));
+    write_text_element(xml, 4, "description", &meta.description);
+    if !meta.extra.is_empty() {
+        xml.push_str("    <custom>\n");
+        for (name, value) in &meta.extra {
+            xml.push_str(&format!(
+                "      <field name=\"{}\">{}</field>\n",
+                escape(name.as_str()),
+                escape(value.as_str())
+            ));
+        }
+        xml.push_str("    </custom>\n");
+    }
+    xml.push_str("  </meta>\n");
+}
+
+fn write_variables(xml: &mut String, variables: &[Variable]) {
+    xml.push_str("  <variables>\n");
+    for var in variables {
+        let description = var
+            .description
+            .as_ref()
+            .map(|d| format!(" description=\"{}\"", escape(d.as_str())))
+            .unwrap_or_default();
+        let sensitive = if var.sensitive { " sensitive=\"true\"" } else { "" };
+        let resolve_in = if var.resolve_in.is_empty() {
+            String::new()
+        } else {
+            let contexts: Vec<&str> = var.resolve_in.iter().map(|context| context.as_str()).collect();
+            format!(" resolveIn=\"{}\"", contexts.join(","))
+        };
+        let due = var
+            .due
+            .as_ref()
+            .map(|due| format!(" due=\"{}\"", escape(due.as_str())))
+            .unwrap_or_default();
+        xml.push_str(&format!(
+            "    <var name=\"{}\"{}{}{}{}>{}</var>\n",
+            escape(var.name.as_str()),
+            description,
+            sensitive,
+            resolve_in,
+            due,
+            escape(var.value.as_str())
+        ));
+    }
+    xml.push_str("  </variables>\n");
+}
+
+fn write_sections(xml: &mut String, sections: &[Section]) {
+    xml.push_str("  <sections>\n");
+    for section in sections {
+        write_section(xml, section);
+    }
+    xml.push_str("  </sections>\n");
+}
+
+fn write_section(xml: &mut String, section: &Section) {
+    xml.push_str(&format!(
+        "    <section id=\"{}\" type=\"{}\"",
+        escape(section.id.as_str()),
+        escape(section.section_type.as_str())
+    ));
+    if let Some(ref_target) = &section.ref_target {
+        xml.push_str(&format!(" refTarget=\"{}\"", escape(ref_target.as_str())));
+    }
+    if !section.tags.is_empty() {
+        xml.push_str(&format!(" tags=\"{}\"", escape(section.tags.join(", ").as_str())));
+    }
+    if let Some(budget) = &section.budget {
+        xml.push_str(&format!(" budget=\"{}\"", budget));
+    }
+    if section.sensitive {
+        xml.push_str(" sensitive=\"true\"");
+    }
+    if section.encrypted {
+        xml.push_str(" encrypted=\"true\"");
+    }
+    xml.push_str(">\n");
+
+    xml.push_str("      <content>");
+    write_cdata(xml, &section.content);
+    xml.push_str("</content>\n");
+    for (lang, content) in &section.translations {
+        xml.push_str(&format!("      <content lang=\"{}\">", escape(lang.as_str())));
+        write_cdata(xml, content);
+        xml.push_str("</content>\n");
+    }
+
+    xml.push_str("    </section>\n");
+}
+
+fn write_references(xml: &mut String, references: &[Reference]) {
+    xml.push_str("  <references>\n");
+    for reference in references {
+        xml.push_str(&format!("    <reference id=\"{}\"", escape(reference.id.as_str())));
+        if let Some(url) = &reference.url {
+            xml.push_str(&format!(" url=\"{}\"", escape(url.as_str())));
+        }
+        xml.push_str(">\n");
+        write_text_element(xml, 6, "title", &reference.title);
+        if !reference.citation.is_empty() {
+            xml.push_str("      <citation>");
+            write_cdata(xml, &reference.citation);
+            xml.push_str("</citation>\n");
+        }
+        xml.push_str("    </reference>\n");
+    }
+    xml.push_str("  </references>\n");
+}
+
+fn write_flow(xml: &mut String, flow: &FlowGraph) {
+    xml.push_str(&format!(
+        "  <flow id=\"{}\" version=\"{}\">\n",
+        escape(flow.id.as_str()),
+        escape(flow.version.as_str())
+    ));
+    if let Some(title) = &flow.title {
+        write_text_element(xml, 4, "title", title);
+    }
+    xml.push_str("    <diagram>");
+    write_cdata(xml, &flow.mermaid_code);
+    xml.push_str("</diagram>\n");
+    if let Some(layout) = &flow.layout {
+        write_layout(xml, layout);
+    }
+    if !flow.node_metadata.is_empty() {
+        write_node_metadata(xml, &flow.node_metadata);
+    }
+    xml.push_str("  </flow>\n");
+}
+
+fn write_layout(xml: &mut String, layout: &FlowLayout) {
+    let attributes = format!(
+        "zoom=\"{}\" panX=\"{}\" panY=\"{}\"",
+        layout.zoom, layout.pan_x, layout.pan_y
+    );
+
+    if layout.positions.is_empty() {
+        xml.push_str(&format!("    <layout {}/>\n", attributes));
+        return;
+    }
+
+    xml.push_str(&format!("    <layout {}>\n", attributes));
+    for (node_id, position) in &layout.positions {
+        xml.push_str(&format!(
+            "      <node id=\"{}\" x=\"{}\" y=\"{}\"/>\n",
+            escape(node_id.as_str()),
+            position.x,
+            position.y
+        ));
+    }
+    xml.push_str("    </layout>\n");
+}
+
+fn write_node_metadata(xml: &mut String, node_metadata: &BTreeMap<String, NodeMetadata>) {
+    xml.push_str("    <nodes>\n");
+    for (node_id, metadata) in node_metadata {
+        xml.push_str(&format!("      <node id=\"{}\"", escape(node_id.as_str())));
+        if let Some(owner) = &metadata.owner {
+            xml.push_str(&format!(" owner=\"{}\"", escape(owner.as_str())));
+        }
+        if let Some(status) = &metadata.status {
+            xml.push_str(&format!(" status=\"{}\"", escape(status.as_str())));
+        }
+        if let Some(effort) = metadata.estimated_effort {
+            xml.push_str(&format!(" effort=\"{}\"", effort));
+        }
+        xml.push_str("/>\n");
+    }
+    xml.push_str("    </nodes>\n");
+}
+
+fn write_text_element(xml: &mut String, indent: usize, tag: &str, text: &str) {
+    xml.push_str(&format!(
+        "{}<{tag}>{}</{tag}>\n",
+        " ".repeat(indent),
+        escape(text)
+    ));
+}
+
+/// Write text as CDATA, splitting any `]]>` so it cannot terminate the block early
+fn write_cdata(xml: &mut String, text: &str) {
+    let escaped = text.replace("]]>", "]]]]><![CDATA[>");
+    xml.push_str(&format!("<![CDATA[\n{}\n]]>", escaped));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parsers::parse_xml;
+    use crate::validators::schema_validator::validate_schema;
+    use std::collections::BTreeMap;
+
+    fn sample_document() -> ContextDocument {
+        ContextDocument {
+            meta: MetaData {
+                title: "Plans & <Goals>".to_string(),
+                author: "Author".to_string(),
+                created: "2025-10-09".to_string(),
+                app_info: AppInfo {
+                    name: "CEC".to_string(),
+                    version: "0.1.0".to_string(),
+                },
+                tags: vec!["test".to_string(), "doc".to_string()],
+                description: "A \"quoted\" description".to_string(),
+                extra: std::collections::BTreeMap::new(),
+            },
+            variables: vec![Variable {
+                name: "goal".to_string(),
+                value: "Ship v1 & more".to_string(),
+                description: None,
+                sensitive: true,
+                resolve_in: vec![ResolutionContext::Export],
+                due: Some("2025-11-01".to_string()),
+            }],
+            sections: vec![
+                Section {
+                    id: "intent-1".to_string(),
+                    section_type: "intent".to_string(),
+                    content: "# Intent\n\nWe aim to **${goal}**".to_string(),
+                    ref_target: None,
+                    children: vec![],
+                    budget: None,
+                    transclusions: vec![],
+                    tags: vec![],
+                    translations: BTreeMap::from([("de".to_string(), "Wir wollen **${goal}**".to_string())]),
+                    sensitive: false,
+                    encrypted: false,
+                },
+                Section {
+                    id: "proc-1".to_string(),
+                    section_type: "process".to_string(),
+                    content: "Tricky ]]> sequence".to_string(),
+                    ref_target: Some("intent-1".to_string()),
+                    children: vec![],
+                    budget: Some(Budget { limit: 200, unit: BudgetUnit::Chars }),
+                    transclusions: vec![],
+                    tags: vec!["draft".to_string(), "backend".to_string()],
+                    translations: BTreeMap::new(),
+                    sensitive: true,
+                    encrypted: true,
+                },
+            ],
+            references: vec![],
+            flow_graph: Some(FlowGraph {
+                id: "flow-1".to_string(),
+                version: "1.0".to_string(),
+                title: Some("Flow".to_string()),
+                mermaid_code: "```mermaid\nflowchart TD\n  A[Intent] --> B[Process]\n```".to_string(),
+                parsed_graph: GraphStructure {
+                    nodes: vec![],
+                    edges: vec![],
+                    subgraphs: vec![],
+                },
+                node_refs: vec![],
+                layout: None,
+                node_metadata: BTreeMap::new(),
+            }),
+        }
+    }
+
+    #[test]
+    fn test_serialized_document_passes_validation() {
+        let xml = serialize_xml(&sample_document());
+        assert!(validate_schema(&xml).is_ok());
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let doc = sample_document();
+        let parsed = parse_xml(&serialize_xml(&doc)).unwrap();
+
+        assert_eq!(parsed, doc);
+    }
+
+    #[test]
+    fn test_escapes_text_and_attributes() {
+        let xml = serialize_xml(&sample_document());
+
+        assert!(xml.contains("<title>Plans &amp; &lt;Goals&gt;</title>"));
+        assert!(xml.contains("<var name=\"goal\" sensitive=\"true\" resolveIn=\"export\" due=\"2025-11-01\">Ship v1 &amp; more</var>"));
+    }
+
+    #[test]
+    fn test_round_trip_with_layout() {
+        let mut doc = sample_document();
+        let mut positions = std::collections::BTreeMap::new();
+        positions.insert("A".to_string(), NodePosition { x: 12.5, y: -3.0 });
+        positions.insert("B".to_string(), NodePosition { x: 200.0, y: 40.0 });
+        doc.flow_graph.as_mut().unwrap().layout = Some(FlowLayout {
+            zoom: 0.75,
+            pan_x: 100.0,
+            pan_y: 0.0,
+            positions,
+        });
+
+        let xml = serialize_xml(&doc);
+        assert!(xml.contains(r#"<layout zoom="0.75" panX="100" panY="0">"#));
+        assert_eq!(parse_xml(&xml).unwrap(), doc);
+    }
+
+    #[test]
+    fn test_round_trip_with_node_metadata() {
+        let mut doc = sample_document();
+        let flow = doc.flow_graph.as_mut().unwrap();
+        flow.node_metadata.insert(
+            "A".to_string(),
+            NodeMetadata { owner: Some("Kim & Lee".to_string()), status: Some("done".to_string()), estimated_effort: Some(1.5) },
+        );
+        flow.node_metadata.insert("B".to_string(), NodeMetadata { status: Some("todo".to_string()), ..Default::default() });
+
+        let xml = serialize_xml(&doc);
+        assert!(xml.contains(r#"<node id="A" owner="Kim &amp; Lee" status="done" effort="1.5"/>"#));
+        assert_eq!(parse_xml(&xml).unwrap(), doc);
+        assert!(validate_schema(&xml).is_ok());
+    }
+
+    #[test]
+    fn test_round_trip_with_custom_meta_fields() {
+        let mut doc = sample_document();
+        doc.meta.extra.insert("projectCode".to_string(), "PX & 42".to_string());
+        doc.meta.extra.insert("owner team".to_string(), "Platform".to_string());
+
+        let xml = serialize_xml(&doc);
+        assert!(xml.contains(r#"<field name="projectCode">PX &amp; 42</field>"#));
+        assert_eq!(parse_xml(&xml).unwrap(), doc);
+    }
+
+    #[test]
+    fn test_round_trip_with_references() {
+        let mut doc = sample_document();
+        doc.references = vec![
+            Reference {
+                id: "smith-2024".to_string(),
+                title: "Context & Flow".to_string(),
+                url: Some("https://example.com/?a=1&b=2".to_string()),
+                citation: "Smith, J. (2024).".to_string(),
+            },
+            Reference {
+                id: "doe-2023".to_string(),
+                title: "Graphs".to_string(),
+                url: None,
+                citation: String::new(),
+            },
+        ];
+
+        let xml = serialize_xml(&doc);
+        assert!(validate_schema(&xml).is_ok());
+        assert_eq!(parse_xml(&xml).unwrap(), doc);
+    }
+
+    #[test]
+    fn test_document_without_flow() {
+        let mut doc = sample_document();
+        doc.flow_graph = None;
+
+        let xml = serialize_xml(&doc);
+        assert!(!xml.contains("<flow"));
+        assert_eq!(parse_xml(&xml).unwrap(), doc);
+    }
+}
@@ -0,0 +1,209 @@
+use crate::error::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::sync::{LazyLock, RwLock};
+use std::time::{Duration, SystemTime};
+
+/// Environment file read next to the working directory
+pub const DEFAULT_ENV_FILE: &str = ".env";
+
+/// Document opened when the app starts
+pub const DOC_PATH_VAR: &str = "FLOW_WRITER_DOC_PATH";
+
+/// Overrides the default documents directory
+pub const DOCS_DIR_VAR: &str = "FLOW_WRITER_DOCS_DIR";
+
+/// Set to `1`/`true` to reload the config when the env file changes
+pub const WATCH_ENV_VAR: &str = "FLOW_WRITER_WATCH_ENV";
+
+/// Prefix of the variables that make up the app config
+pub const ENV_PREFIX: &str = "FLOW_WRITER_";
+
+/// Settings driven by environment variables
+///
+/// Values come from the env file, with variables set in the process
+/// environment taking precedence, as dotenv does.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct AppConfig {
+    pub doc_path: Option<String>,
+    pub docs_dir: Option<String>,
+    pub watch_env: bool,
+    /// Every `FLOW_WRITER_*` variable, including the ones above
+    pub settings: BTreeMap<String, String>,
+}
+
+static CONFIG: LazyLock<RwLock<AppConfig>> = LazyLock::new(|| RwLock::new(AppConfig::default()));
+
+impl AppConfig {
+    fn from_settings(settings: BTreeMap<String, String>) -> AppConfig {
+        let non_empty = |key: &str| settings.get(key).filter(|v| !v.trim().is_empty()).cloned();
+        let doc_path = non_empty(DOC_PATH_VAR);
+        let docs_dir = non_empty(DOCS_DIR_VAR);
+        let watch_env = settings
+            .get(WATCH_ENV_VAR)
+            .is_some_and(|v| matches!(v.trim().to_ascii_lowercase().as_str(), "1" | "true" | "yes" | "on"));
+        AppConfig { doc_path, docs_dir, watch_env, settings }
+    }
+}
+
+/// Parse `KEY=value` lines of an env file
+///
+/// Supports `#` comments, an optional `export ` prefix and single- or
+/// double-quoted values. Lines without `=` are ignored.
+pub fn parse_env_file(text: &str) -> Vec<(String, String)> {
+    text.lines()
+        .filter_map(|line| {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                return None;
+            }
+            let line = line.strip_prefix("export ").unwrap_or(line);
+            let (key, value) = line.split_once('=')?;
+            let key = key.trim();
+            if key.is_empty() {
+                return None;
+            }
+            Some((key.to_string(), unquote(value.trim())))
+        })
+        .collect()
+}
+
+fn unquote(value: &str) -> String {
+    for quote in ['"', '\''] {
+        if value.len() >= 2 && value.starts_with(quote) && value.ends_with(quote) {
+            return value[1..value.len() - 1].to_string();
+        }
+    }
+    // Unquoted values may carry a trailing comment
+    match value.find(" #") {
+        Some(index) => value[..index].trim_end().to_string(),
+        None => value.to_string(),
+    }
+}
+
+/// Build the config from an env file and the process environment
+///
+/// A missing env file is not an error.
+pub fn load_config(env_file: &Path) -> Result<AppConfig> {
+    let mut settings = BTreeMap::new();
+    match std::fs::read_to_string(env_file) {
+        Ok(text) => settings.extend(parse_env_file(&text).into_iter().filter(|(key, _)| key.starts_with(ENV_PREFIX))),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+        Err(e) => return Err(e.into()),
+    }
+    settings.extend(std::env::vars().filter(|(key, _)| key.starts_with(ENV_PREFIX)));
+    Ok(AppConfig::from_settings(settings))
+}
+
+/// The config as of the last load
+pub fn current_config() -> AppConfig {
+    CONFIG.read().unwrap().clone()
+}
+
+/// Re-read the env file and environment; returns the config and whether it changed
+pub fn reload_config(env_file: &Path) -> Result<(AppConfig, bool)> {
+    let config = load_config(env_file)?;
+    let mut current = CONFIG.write().unwrap();
+    let changed = *current != config;
+    *current = config.clone();
+    Ok((config, changed))
+}
+
+/// Poll the env file and call `on_change` with the new config whenever it changes
+///
+/// Runs until the task is dropped. Read errors are skipped; the next poll retries.
+pub async fn watch_config(env_file: &Path, interval: Duration, on_change: impl Fn(AppConfig)) {
+    let mut last_modified = modified(env_file);
+    let mut ticker = tokio::time::interval(interval);
+    loop {
+        ticker.tick().await;
+        let modified = modified(env_file);
+        if modified == last_modified {
+            continue;
+        }
+        last_modified = modified;
+        if let Ok((config, true)) = reload_config(env_file) {
+            on_change(config);
+        }
+    }
+}
+
+fn modified(path: &Path) -> Option<SystemTime> {
+    std::fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_env_file() {
+        let parsed = parse_env_file(
+            "# comment\nFLOW_WRITER_DOC_PATH=docs/a.xml\nexport QUOTED=\"a # b\"\nSINGLE='x'\nTRAILING=value # note\nbroken line\n",
+        );
+
+        assert_eq!(
+            parsed,
+            vec![
+                ("FLOW_WRITER_DOC_PATH".to_string(), "docs/a.xml".to_string()),
+                ("QUOTED".to_string(), "a # b".to_string()),
+                ("SINGLE".to_string(), "x".to_string()),
+                ("TRAILING".to_string(), "value".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_load_config_from_env_file() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let env_file = dir.path().join(".env");
+        std::fs::write(
+            &env_file,
+            "FLOW_WRITER_TEST_ONLY_DOC=x\nFLOW_WRITER_WATCH_ENV=true\nUNRELATED=1\n",
+        )
+        .unwrap();
+
+        let config = load_config(&env_file).unwrap();
+
+        assert!(config.watch_env);
+        assert_eq!(config.settings.get("FLOW_WRITER_TEST_ONLY_DOC").map(String::as_str), Some("x"));
+        assert!(!config.settings.contains_key("UNRELATED"));
+    }
+
+    #[test]
+    fn test_reload_reports_changes() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let env_file = dir.path().join(".env");
+        std::fs::write(&env_file, "FLOW_WRITER_TEST_RELOAD=1\n").unwrap();
+
+        reload_config(&env_file).unwrap();
+        let (_, changed) = reload_config(&env_file).unwrap();
+        assert!(!changed);
+
+        std::fs::write(&env_file, "FLOW_WRITER_TEST_RELOAD=2\n").unwrap();
+        let (config, changed) = reload_config(&env_file).unwrap();
+        assert!(changed);
+        assert_eq!(current_config(), config);
+    }
+
+    #[test]
+    fn test_missing_env_file_is_empty_config() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let config = load_config(&dir.path().join(".env")).unwrap();
+        assert!(config.settings.keys().all(|key| key.starts_with(ENV_PREFIX)));
+    }
+
+    #[test]
+    fn test_config_from_settings() {
+        let settings = BTreeMap::from([
+            (DOC_PATH_VAR.to_string(), "  ".to_string()),
+            (WATCH_ENV_VAR.to_string(), "On".to_string()),
+        ]);
+        let config = AppConfig::from_settings(settings);
+
+        assert_eq!(config.doc_path, None);
+        assert!(config.watch_env);
+    }
+}
@@ -0,0 +1,252 @@
+use crate::error::{ContextError, Result};
+use crate::processors::variable_resolver;
+use crate::services::binary_cache::content_hash;
+use crate::services::flow_service::{self, LoadOptions};
+use serde::{Deserialize, Serialize};
+use similar::TextDiff;
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+use tokio::fs;
+
+/// Folder next to the document that holds its assembly snapshots
+pub const HISTORY_DIR: &str = ".history";
+
+/// The exact context given to an LLM, with what it was assembled from
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct AssemblySnapshot {
+    pub id: String,
+    /// RFC 3339 timestamp
+    pub created_at: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+    /// SHA-256 of `output`
+    pub hash: String,
+    /// Variable values used, after call-time overrides
+    pub variables: BTreeMap<String, String>,
+    pub options: LoadOptions,
+    pub output: String,
+}
+
+/// Snapshot listing entry without the (potentially large) output
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SnapshotSummary {
+    pub id: String,
+    pub created_at: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+    pub hash: String,
+    pub size: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct VariableChange {
+    pub name: String,
+    pub before: Option<String>,
+    pub after: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SnapshotComparison {
+    pub identical: bool,
+    pub variable_changes: Vec<VariableChange>,
+    /// Unified diff of the outputs; empty when identical
+    pub diff: String,
+}
+
+/// `<document dir>/.history/<document file name>/`
+pub fn history_dir(file_path: &str) -> PathBuf {
+    let path = Path::new(file_path);
+    let file_name = path.file_name().map(|n| n.to_string_lossy().into_owned()).unwrap_or_default();
+    path.parent().unwrap_or(Path::new(".")).join(HISTORY_DIR).join(file_name)
+}
+
+/// Assemble the context and store the exact output in the document's history folder
+pub async fn save_assembly_snapshot(
+    file_path: &str,
+    options: &LoadOptions,
+    overrides: &HashMap<String, String>,
+    label: Option<String>,
+) -> Result<AssemblySnapshot> {
+    let output = flow_service::assemble_context(file_path, options, overrides).await?;
+    let doc = flow_service::read_context_document(file_path).await?;
+
+    let mut variables: BTreeMap<String, String> =
+        variable_resolver::build_variable_map(&doc.variables).into_iter().collect();
+    variables.extend(overrides.iter().map(|(k, v)| (k.clone(), v.clone())));
+
+    let now = chrono::Utc::now();
+    let hash = content_hash(output.as_bytes());
+    let snapshot = AssemblySnapshot {
+        id: format!("{}-{}", now.format("%Y%m%dT%H%M%S%.3fZ"), &hash[..8]),
+        created_at: now.to_rfc3339(),
+        label,
+        hash,
+        variables,
+        options: options.clone(),
+        output,
+    };
+
+    let dir = history_dir(file_path);
+    fs::create_dir_all(&dir).await?;
+    let json = serde_json::to_string_pretty(&snapshot).map_err(|e| ContextError::SerializationError(e.to_string()))?;
+    fs::write(dir.join(format!("{}.json", snapshot.id)), json).await?;
+
+    Ok(snapshot)
+}
+
+/// Snapshots of the document, newest first
+pub async fn list_assembly_snapshots(file_path: &str) -> Result<Vec<SnapshotSummary>> {
+    let dir = history_dir(file_path);
+    let mut entries = match fs::read_dir(&dir).await {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(vec![]),
+        Err(e) => return Err(e.into()),
+    };
+
+    let mut summaries = Vec::new();
+    while let Some(entry) = entries.next_entry().await? {
+        let path = entry.path();
+        if path.extension().and_then(|ext| ext.to_str()) != Some("json") {
+            continue;
+        }
+        let snapshot = read_snapshot_file(&path).await?;
+        summaries.push(SnapshotSummary {
+            size: snapshot.output.len(),
+            id: snapshot.id,
+            created_at: snapshot.created_at,
+            label: snapshot.label,
+            hash: snapshot.hash,
+        });
+    }
+
+    // IDs start with a sortable timestamp
+    summaries.sort_by(|a, b| b.id.cmp(&a.id));
+    Ok(summaries)
+}
+
+/// Load one snapshot with its full output
+pub async fn load_assembly_snapshot(file_path: &str, snapshot_id: &str) -> Result<AssemblySnapshot> {
+    if snapshot_id.contains(['/', '\\']) || snapshot_id.starts_with('.') {
+        return Err(ContextError::InvalidArgument(format!("Invalid snapshot ID '{}'", snapshot_id)));
+    }
+    let path = history_dir(file_path).join(format!("{}.json", snapshot_id));
+    if !fs::try_exists(&path).await? {
+        return Err(ContextError::FileNotFound(format!("Snapshot '{}'", snapshot_id)));
+    }
+    read_snapshot_file(&path).await
+}
+
+/// Compare two snapshots' variables and output
+pub async fn compare_assembly_snapshots(file_path: &str, before_id: &str, after_id: &str) -> Result<SnapshotComparison> {
+    let before = load_assembly_snapshot(file_path, before_id).await?;
+    let after = load_assembly_snapshot(file_path, after_id).await?;
+    Ok(compare_snapshots(&before, &after))
+}
+
+pub fn compare_snapshots(before: &AssemblySnapshot, after: &AssemblySnapshot) -> SnapshotComparison {
+    let mut names: Vec<&String> = before.variables.keys().chain(after.variables.keys()).collect();
+    names.sort();
+    names.dedup();
+
+    let variable_changes = names
+        .into_iter()
+        .filter(|name| before.variables.get(*name) != after.variables.get(*name))
+        .map(|name| VariableChange {
+            name: name.clone(),
+            before: before.variables.get(name).cloned(),
+            after: after.variables.get(name).cloned(),
+        })
+        .collect();
+
+    let identical = before.hash == after.hash;
+    let diff = if identical {
+        String::new()
+    } else {
+        TextDiff::from_lines(&before.output, &after.output)
+            .unified_diff()
+            .header(&before.id, &after.id)
+            .to_string()
+    };
+
+    SnapshotComparison { identical, variable_changes, diff }
+}
+
+async fn read_snapshot_file(path: &Path) -> Result<AssemblySnapshot> {
+    let json = fs::read_to_string(path).await?;
+    serde_json::from_str(&json)
+        .map_err(|e| ContextError::SerializationError(format!("{}: {}", path.display(), e)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::ContextDocument;
+    use crate::serializers::xml_serializer::serialize_xml;
+
+    fn write_document(dir: &Path) -> String {
+        let doc = ContextDocument::builder()
+            .title("History")
+            .variable("goal", "Ship v1")
+            .section("intent-1", "intent", "Goal: ${goal}")
+            .build()
+            .unwrap();
+        let path = dir.join("doc.xml");
+        std::fs::write(&path, serialize_xml(&doc)).unwrap();
+        path.to_str().unwrap().to_string()
+    }
+
+    #[tokio::test]
+    async fn test_save_list_and_compare_snapshots() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = write_document(dir.path());
+        let options = LoadOptions::default();
+
+        let first = save_assembly_snapshot(&path, &options, &HashMap::new(), Some("baseline".to_string()))
+            .await
+            .unwrap();
+        // IDs have millisecond resolution
+        tokio::time::sleep(std::time::Duration::from_millis(5)).await;
+        let overrides = HashMap::from([("goal".to_string(), "Ship v2".to_string())]);
+        let second = save_assembly_snapshot(&path, &options, &overrides, None).await.unwrap();
+
+        assert_eq!(first.hash, content_hash(first.output.as_bytes()));
+        assert!(history_dir(&path).join(format!("{}.json", first.id)).exists());
+
+        let listed = list_assembly_snapshots(&path).await.unwrap();
+        let ids: Vec<&str> = listed.iter().map(|s| s.id.as_str()).collect();
+        assert_eq!(ids, vec![second.id.as_str(), first.id.as_str()]);
+        assert_eq!(listed[1].label.as_deref(), Some("baseline"));
+
+        let comparison = compare_assembly_snapshots(&path, &first.id, &second.id).await.unwrap();
+        assert!(!comparison.identical);
+        assert_eq!(
+            comparison.variable_changes,
+            vec![VariableChange {
+                name: "goal".to_string(),
+                before: Some("Ship v1".to_string()),
+                after: Some("Ship v2".to_string()),
+            }]
+        );
+        assert!(comparison.diff.contains("-Goal: Ship v1"));
+        assert!(comparison.diff.contains("+Goal: Ship v2"));
+
+        crate::services::document_store::close(&path);
+    }
+
+    #[tokio::test]
+    async fn test_missing_history_and_invalid_ids() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("doc.xml");
+        let path = path.to_str().unwrap();
+
+        assert!(list_assembly_snapshots(path).await.unwrap().is_empty());
+        assert!(matches!(
+            load_assembly_snapshot(path, "../secrets").await,
+            Err(ContextError::InvalidArgument(_))
+        ));
+        assert!(matches!(
+            load_assembly_snapshot(path, "20250101T000000.000Z-deadbeef").await,
+            Err(ContextError::FileNotFound(_))
+        ));
+    }
+}
@@ -0,0 +1,187 @@
+use crate::error::{ContextError, Result};
+use crate::models::ContextDocument;
+use crate::services::change_journal::{self, JournalOperation};
+use crate::services::{document_store, flow_service, settings};
+use chrono::{DateTime, NaiveDateTime, SubsecRound, Utc};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use tokio::fs;
+
+/// Folder next to the document that holds its backups
+pub const BACKUP_FOLDER: &str = ".flow-writer-backups";
+
+/// Part of a backup's file name between the document's stem and extension, in UTC
+const TIMESTAMP_FORMAT: &str = "%Y%m%dT%H%M%S%3f";
+
+/// A copy of the document as it was on disk before a save
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct BackupInfo {
+    /// File name of the backup, e.g. `plan.20251009T140312123.xml`; pass it to `restore_backup`
+    pub id: String,
+    pub file_path: String,
+    /// RFC 3339 timestamp of the save that replaced this version
+    pub created_at: String,
+    pub size: u64,
+}
+
+pub fn backup_dir(file_path: &str) -> PathBuf {
+    Path::new(file_path).parent().unwrap_or(Path::new(".")).join(BACKUP_FOLDER)
+}
+
+/// Copy the file as it is now into the backup folder, before it is overwritten
+///
+/// Does nothing when the `maxBackups` setting is 0, when there is no file yet,
+/// or when the newest backup already holds the same bytes, so repeated
+/// autosaves of an unchanged file add nothing. Only the newest `maxBackups`
+/// backups of the document are kept.
+pub async fn backup_before_save(file_path: &str) -> Result<Option<BackupInfo>> {
+    let max_backups = settings::current_settings().max_backups as usize;
+    if max_backups == 0 {
+        return Ok(None);
+    }
+    let current = match fs::read(file_path).await {
+        Ok(current) => current,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(e.into()),
+    };
+
+    let mut backups = list_backups(file_path).await?;
+    let unchanged = match backups.first() {
+        Some(newest) => fs::read(&newest.file_path).await.is_ok_and(|bytes| bytes == current),
+        None => false,
+    };
+    if unchanged {
+        return Ok(None);
+    }
+
+    let dir = backup_dir(file_path);
+    fs::create_dir_all(&dir).await?;
+    // Names must stay unique when two saves fall in the same millisecond
+    let mut now = Utc::now().trunc_subsecs(3);
+    while fs::try_exists(dir.join(backup_name(file_path, &now.format(TIMESTAMP_FORMAT).to_string()))).await? {
+        now += chrono::Duration::milliseconds(1);
+    }
+    let id = backup_name(file_path, &now.format(TIMESTAMP_FORMAT).to_string());
+    let path = dir.join(&id);
+    let temp_path = path.with_extension("tmp");
+    fs::write(&temp_path, &current).await?;
+    fs::rename(&temp_path, &path).await?;
+
+    let backup = BackupInfo {
+        id,
+        file_path: path.to_string_lossy().into_owned(),
+        created_at: now.to_rfc3339(),
+        size: current.len() as u64,
+    };
+    backups.insert(0, backup.clone());
+    for old in backups.iter().skip(max_backups) {
+        fs::remove_file(&old.file_path).await?;
+    }
+    Ok(Some(backup))
+}
+
+/// Backups of the document, newest first
+pub async fn list_backups(file_path: &str) -> Result<Vec<BackupInfo>> {
+    let mut read_dir = match fs::read_dir(backup_dir(file_path)).await {
+        Ok(read_dir) => read_dir,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(vec![]),
+        Err(e) => return Err(e.into()),
+    };
+
+    let mut backups = Vec::new();
+    while let Some(entry) = read_dir.next_entry().await? {
+        let id = entry.file_name().to_string_lossy().into_owned();
+        let Some(created_at) = backup_time(file_path, &id) else {
+            continue;
+        };
+        backups.push(BackupInfo {
+            id,
+            file_path: entry.path().to_string_lossy().into_owned(),
+            created_at: created_at.to_rfc3339(),
+            size: entry.metadata().await?.len(),
+        });
+    }
+    backups.sort_by(|a, b| b.id.cmp(&a.id));
+    Ok(backups)
+}
+
+/// Put a backup back in place of the document and save it
+///
+/// The backup must parse; unsaved edits are discarded. The version it
+/// replaces is backed up in turn, so a restore can itself be undone.
+pub async fn restore_backup(file_path: &str, backup_id: &str) -> Result<ContextDocument> {
+    let backup = list_backups(file_path)
+        .await?
+        .into_iter()
+        .find(|backup| backup.id == backup_id)
+        .ok_or_else(|| ContextError::FileNotFound(format!("No backup '{}' of {}", backup_id, file_path)))?;
+    let xml_content = fs::read_to_string(&backup.file_path).await?;
+    let doc = flow_service::parse_context_document(&xml_content)?;
+
+    document_store::replace(file_path, doc.clone())?;
+    flow_service::save_document(file_path).await?;
+    change_journal::record(file_path, JournalOperation::RestoreBackup, Some(backup.id)).await;
+    Ok(doc)
+}
+
+/// `<stem>.<timestamp>.<extension>` of the document
+fn backup_name(file_path: &str, timestamp: &str) -> String {
+    let path = Path::new(file_path);
+    let stem = path.file_stem().map(|stem| stem.to_string_lossy()).unwrap_or_default();
+    match path.extension() {
+        Some(extension) => format!("{}.{}.{}", stem, timestamp, extension.to_string_lossy()),
+        None => format!("{}.{}", stem, timestamp),
+    }
+}
+
+/// When the backup was taken, if `name` is a backup of this document
+fn backup_time(file_path: &str, name: &str) -> Option<DateTime<Utc>> {
+    let pattern = backup_name(file_path, "\0");
+    let (prefix, suffix) = pattern.split_once('\0')?;
+    let timestamp = name.strip_prefix(prefix)?.strip_suffix(suffix)?;
+    NaiveDateTime::parse_from_str(timestamp, TIMESTAMP_FORMAT).ok().map(|time| time.and_utc())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn save_version(file_path: &str, title: &str) {
+        let doc = ContextDocument::builder().title(title).section("intent-1", "intent", "# Intent").build().unwrap();
+        std::fs::write(file_path, crate::serializers::xml_serializer::serialize_xml(&doc)).unwrap();
+    }
+
+    #[test]
+    fn test_backup_names() {
+        assert_eq!(backup_name("/docs/plan.xml", "20251009T140312123"), "plan.20251009T140312123.xml");
+        assert!(backup_time("/docs/plan.xml", "plan.20251009T140312123.xml").is_some());
+        assert!(backup_time("/docs/plan.xml", "plan.v2.20251009T140312123.xml").is_none());
+        assert!(backup_time("/docs/plan.xml", "plan.20251009T140312123.cec").is_none());
+        assert!(backup_time("/docs/plan.xml", "plan.xml").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_backups_are_taken_once_per_version_and_restored() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("plan.xml");
+        let file_path = path.to_str().unwrap();
+
+        assert_eq!(backup_before_save(file_path).await.unwrap(), None);
+        save_version(file_path, "First");
+        let first = backup_before_save(file_path).await.unwrap().unwrap();
+        assert_eq!(backup_before_save(file_path).await.unwrap(), None);
+        save_version(file_path, "Second");
+        backup_before_save(file_path).await.unwrap().unwrap();
+
+        let backups = list_backups(file_path).await.unwrap();
+        assert_eq!(backups.len(), 2);
+        assert_eq!(backups[1], first);
+
+        let doc = restore_backup(file_path, &first.id).await.unwrap();
+        assert_eq!(doc.meta.title, "First");
+        assert!(std::fs::read_to_string(file_path).unwrap().contains("<title>First</title>"));
+        assert!(matches!(restore_backup(file_path, "../plan.xml").await, Err(ContextError::FileNotFound(_))));
+        flow_service::close_document(file_path);
+    }
+}
@@ -0,0 +1,204 @@
+use crate::error::{ContextError, Result};
+use crate::models::ContextDocument;
+use crate::parsers::input_normalizer;
+use crate::services::flow_service;
+use sha2::{Digest, Sha256};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use tokio::fs;
+
+/// Written at the start of every cache file; bump whenever the models or the
+/// parser change what a document parses to, so stale entries are re-parsed
+pub const CACHE_FORMAT_VERSION: u32 = 10;
+
+const CACHE_EXTENSION: &str = "bin";
+
+/// On-disk cache of parsed documents, keyed by a hash of the file content
+///
+/// Reopening a large workspace only decodes the cached binary for files whose
+/// content has not changed instead of validating and parsing their XML again.
+/// Writing the cache is best-effort: a cache that cannot be written only costs
+/// a re-parse next time.
+#[derive(Debug, Clone)]
+pub struct BinaryCache {
+    dir: PathBuf,
+}
+
+/// A parsed document along with the content hash it is cached under
+#[derive(Debug, Clone)]
+pub struct CachedLoad {
+    pub hash: String,
+    pub document: ContextDocument,
+    pub from_cache: bool,
+}
+
+impl BinaryCache {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        BinaryCache { dir: dir.into() }
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Load a document, decoding it from the cache when the file content is unchanged
+    pub async fn load(&self, file_path: &str) -> Result<CachedLoad> {
+        let bytes = fs::read(file_path).await?;
+        let hash = content_hash(&bytes);
+
+        if let Some(document) = self.read_entry(&hash).await {
+            return Ok(CachedLoad { hash, document, from_cache: true });
+        }
+
+        let (xml_content, _) = input_normalizer::decode_input(&bytes)?;
+        let document = flow_service::parse_context_document(&xml_content)?;
+        // Best-effort, see the type docs
+        let _ = self.write_entry(&hash, &document).await;
+
+        Ok(CachedLoad { hash, document, from_cache: false })
+    }
+
+    /// Delete cache entries whose hash is not in `keep`; returns how many were removed
+    pub async fn prune(&self, keep: &HashSet<String>) -> Result<usize> {
+        let mut entries = match fs::read_dir(&self.dir).await {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(0),
+            Err(e) => return Err(e.into()),
+        };
+
+        let mut removed = 0;
+        while let Some(entry) = entries.next_entry().await? {
+            let path = entry.path();
+            if path.extension().and_then(|ext| ext.to_str()) != Some(CACHE_EXTENSION) {
+                continue;
+            }
+            let is_live = path
+                .file_stem()
+                .and_then(|stem| stem.to_str())
+                .is_some_and(|stem| keep.contains(stem));
+            if !is_live {
+                fs::remove_file(&path).await?;
+                removed += 1;
+            }
+        }
+        Ok(removed)
+    }
+
+    fn entry_path(&self, hash: &str) -> PathBuf {
+        self.dir.join(format!("{}.{}", hash, CACHE_EXTENSION))
+    }
+
+    /// Decode a cache entry; missing, corrupt and outdated entries are all misses
+    async fn read_entry(&self, hash: &str) -> Option<ContextDocument> {
+        let bytes = fs::read(self.entry_path(hash)).await.ok()?;
+        let config = bincode::config::standard();
+
+        let (version, offset): (u32, usize) = bincode::decode_from_slice(&bytes, config).ok()?;
+        if version != CACHE_FORMAT_VERSION {
+            return None;
+        }
+        let (document, _): (ContextDocument, usize) = bincode::decode_from_slice(&bytes[offset..], config).ok()?;
+        Some(document)
+    }
+
+    async fn write_entry(&self, hash: &str, document: &ContextDocument) -> Result<()> {
+        let bytes = bincode::encode_to_vec((CACHE_FORMAT_VERSION, document), bincode::config::standard())
+            .map_err(|e| ContextError::SerializationError(e.to_string()))?;
+
+        fs::create_dir_all(&self.dir).await?;
+        // Write then rename so a concurrent reader never sees a partial entry
+        let path = self.entry_path(hash);
+        let tmp_path = path.with_extension("tmp");
+        fs::write(&tmp_path, bytes).await?;
+        fs::rename(&tmp_path, &path).await?;
+        Ok(())
+    }
+}
+
+/// Hex-encoded SHA-256 of the file content
+pub fn content_hash(bytes: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(bytes))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::serializers::xml_serializer::serialize_xml;
+
+    fn write_document(dir: &Path, name: &str, title: &str) -> String {
+        let doc = ContextDocument::builder()
+            .title(title)
+            .section("intent-1", "intent", "Goal: ${goal}")
+            .variable("goal", "Ship v1")
+            .tag("draft")
+            .flow("graph TD\n    A[Start] --> B[End]\n    click A \"#intent-1\"")
+            .build()
+            .unwrap();
+        let path = dir.join(name);
+        std::fs::write(&path, serialize_xml(&doc)).unwrap();
+        path.to_str().unwrap().to_string()
+    }
+
+    #[tokio::test]
+    async fn test_second_load_is_served_from_cache() {
+        let docs_dir = tempfile::TempDir::new().unwrap();
+        let cache_dir = tempfile::TempDir::new().unwrap();
+        let cache = BinaryCache::new(cache_dir.path());
+        let path = write_document(docs_dir.path(), "doc.xml", "Cached");
+
+        let first = cache.load(&path).await.unwrap();
+        assert!(!first.from_cache);
+
+        let second = cache.load(&path).await.unwrap();
+        assert!(second.from_cache);
+        assert_eq!(second.hash, first.hash);
+        assert_eq!(second.document, first.document);
+    }
+
+    #[tokio::test]
+    async fn test_changed_content_misses_cache() {
+        let docs_dir = tempfile::TempDir::new().unwrap();
+        let cache_dir = tempfile::TempDir::new().unwrap();
+        let cache = BinaryCache::new(cache_dir.path());
+        let path = write_document(docs_dir.path(), "doc.xml", "Before");
+        cache.load(&path).await.unwrap();
+
+        write_document(docs_dir.path(), "doc.xml", "After");
+        let reloaded = cache.load(&path).await.unwrap();
+
+        assert!(!reloaded.from_cache);
+        assert_eq!(reloaded.document.meta.title, "After");
+    }
+
+    #[tokio::test]
+    async fn test_outdated_or_corrupt_entries_are_reparsed() {
+        let docs_dir = tempfile::TempDir::new().unwrap();
+        let cache_dir = tempfile::TempDir::new().unwrap();
+        let cache = BinaryCache::new(cache_dir.path());
+        let path = write_document(docs_dir.path(), "doc.xml", "Doc");
+        let hash = cache.load(&path).await.unwrap().hash;
+
+        let outdated = bincode::encode_to_vec(CACHE_FORMAT_VERSION + 1, bincode::config::standard()).unwrap();
+        std::fs::write(cache.entry_path(&hash), outdated).unwrap();
+        assert!(!cache.load(&path).await.unwrap().from_cache);
+
+        std::fs::write(cache.entry_path(&hash), b"garbage").unwrap();
+        let reloaded = cache.load(&path).await.unwrap();
+        assert!(!reloaded.from_cache);
+        assert_eq!(reloaded.document.meta.title, "Doc");
+    }
+
+    #[tokio::test]
+    async fn test_prune_keeps_live_entries() {
+        let docs_dir = tempfile::TempDir::new().unwrap();
+        let cache_dir = tempfile::TempDir::new().unwrap();
+        let cache = BinaryCache::new(cache_dir.path());
+        let kept = cache.load(&write_document(docs_dir.path(), "a.xml", "A")).await.unwrap();
+        cache.load(&write_document(docs_dir.path(), "b.xml", "B")).await.unwrap();
+
+        let removed = cache.prune(&HashSet::from([kept.hash.clone()])).await.unwrap();
+
+        assert_eq!(removed, 1);
+        assert!(cache.entry_path(&kept.hash).exists());
+    }
+}
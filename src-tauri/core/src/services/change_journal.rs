@@ -0,0 +1,245 @@
+use crate::error::{ContextError, Result};
+use crate::services::assembly_history::history_dir;
+use crate::services::{document_store, settings};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use tokio::fs;
+use tokio::io::AsyncWriteExt;
+
+/// Journal file inside the document's history folder
+pub const JOURNAL_FILE: &str = "journal.jsonl";
+
+/// Kind of mutation recorded in the journal
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum JournalOperation {
+    ApplyEdits,
+    SaveMetadata,
+    UpdateSection,
+    UpdateSectionBlock,
+    InsertSnippet,
+    ImportSections,
+    SplitSection,
+    MergeSections,
+    MergeConflictCopy,
+    ApplyClickActions,
+    ImportMermaid,
+    ImportFlow,
+    ImportJson,
+    CreateDocument,
+    RestoreBackup,
+    GenerateChecklist,
+    SaveFlowLayout,
+    SaveFlowGraph,
+    SetNodeMetadata,
+    ApplyFixes,
+    RecoverDocument,
+    EditRawXml,
+    EncryptSection,
+    DecryptSection,
+    QuickCapture,
+    Save,
+}
+
+/// One line of the journal
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct JournalEntry {
+    /// RFC 3339 timestamp of the (last) mutation
+    pub timestamp: String,
+    pub operation: JournalOperation,
+    /// What was changed, e.g. a section or node ID
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub subject: Option<String>,
+    /// `Name <email>` of the author profile in the settings at the time
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub author: Option<String>,
+    /// Number of consecutive identical mutations folded into this entry by compaction
+    #[serde(default = "one", skip_serializing_if = "is_one")]
+    pub count: usize,
+}
+
+fn one() -> usize {
+    1
+}
+
+fn is_one(count: &usize) -> bool {
+    *count == 1
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct JournalCompaction {
+    pub entries_before: usize,
+    pub entries_after: usize,
+}
+
+pub fn journal_path(file_path: &str) -> PathBuf {
+    history_dir(file_path).join(JOURNAL_FILE)
+}
+
+/// Append a mutation to the document's journal when the `changeJournal` setting is on
+///
+/// The entry is stamped with the `author` setting, when set.
+///
+/// The mutation has already happened, so a journal that cannot be written is
+/// reported on stderr rather than failing the command.
+pub async fn record(file_path: &str, operation: JournalOperation, subject: Option<String>) {
+    let settings = settings::current_settings();
+    if !settings.change_journal || document_store::is_scratch(file_path) {
+        return;
+    }
+    let entry = JournalEntry {
+        timestamp: chrono::Utc::now().to_rfc3339(),
+        operation,
+        subject,
+        author: settings.author.signature(),
+        count: 1,
+    };
+    if let Err(e) = append_entry(file_path, &entry).await {
+        eprintln!("Failed to write change journal for {}: {}", file_path, e);
+    }
+}
+
+/// Append one entry to the journal, creating the file if needed
+pub async fn append_entry(file_path: &str, entry: &JournalEntry) -> Result<()> {
+    let path = journal_path(file_path);
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).await?;
+    }
+    let mut line = serde_json::to_string(entry).map_err(|e| ContextError::SerializationError(e.to_string()))?;
+    line.push('\n');
+
+    let mut file = fs::OpenOptions::new().create(true).append(true).open(&path).await?;
+    file.write_all(line.as_bytes()).await?;
+    // tokio hands the write to a background task; wait for it before returning
+    file.flush().await?;
+    Ok(())
+}
+
+/// Journal entries, oldest first; empty when nothing was recorded
+///
+/// A line cut short by a crash while appending is skipped.
+pub async fn read_journal(file_path: &str) -> Result<Vec<JournalEntry>> {
+    let content = match fs::read_to_string(journal_path(file_path)).await {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(vec![]),
+        Err(e) => return Err(e.into()),
+    };
+    Ok(content
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect())
+}
+
+/// Fold runs of the same operation on the same subject, by the same author, into one entry
+///
+/// Bursts such as repeated layout saves or block edits become a single entry
+/// carrying the latest timestamp and the number of mutations it stands for.
+/// The journal is rewritten through a temporary file, so it is never left
+/// half-written.
+pub async fn compact_journal(file_path: &str) -> Result<JournalCompaction> {
+    let entries = read_journal(file_path).await?;
+    let entries_before = entries.len();
+    let compacted = compact_entries(entries);
+
+    if entries_before > 0 {
+        let mut content = String::new();
+        for entry in &compacted {
+            content.push_str(&serde_json::to_string(entry).map_err(|e| ContextError::SerializationError(e.to_string()))?);
+            content.push('\n');
+        }
+        let path = journal_path(file_path);
+        let temp_path = path.with_extension("jsonl.tmp");
+        fs::write(&temp_path, content).await?;
+        fs::rename(&temp_path, &path).await?;
+    }
+
+    Ok(JournalCompaction {
+        entries_before,
+        entries_after: compacted.len(),
+    })
+}
+
+pub fn compact_entries(entries: Vec<JournalEntry>) -> Vec<JournalEntry> {
+    let mut compacted: Vec<JournalEntry> = Vec::with_capacity(entries.len());
+    for entry in entries {
+        match compacted.last_mut() {
+            Some(last) if last.operation == entry.operation && last.subject == entry.subject && last.author == entry.author => {
+                last.count += entry.count;
+                last.timestamp = entry.timestamp;
+            }
+            _ => compacted.push(entry),
+        }
+    }
+    compacted
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(timestamp: &str, operation: JournalOperation, subject: Option<&str>) -> JournalEntry {
+        JournalEntry {
+            timestamp: timestamp.to_string(),
+            operation,
+            subject: subject.map(str::to_string),
+            author: None,
+            count: 1,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_append_read_and_compact() {
+        let dir = tempfile::tempdir().unwrap();
+        let file_path = dir.path().join("plan.xml");
+        let file_path = file_path.to_str().unwrap();
+
+        assert!(read_journal(file_path).await.unwrap().is_empty());
+
+        let entries = vec![
+            entry("2025-10-01T10:00:00Z", JournalOperation::SaveFlowLayout, None),
+            entry("2025-10-01T10:00:05Z", JournalOperation::SaveFlowLayout, None),
+            entry("2025-10-01T10:01:00Z", JournalOperation::UpdateSectionBlock, Some("intent-1")),
+            entry("2025-10-01T10:02:00Z", JournalOperation::UpdateSectionBlock, Some("process-1")),
+            entry("2025-10-01T10:03:00Z", JournalOperation::Save, None),
+        ];
+        for entry in &entries {
+            append_entry(file_path, entry).await.unwrap();
+        }
+        assert_eq!(read_journal(file_path).await.unwrap(), entries);
+
+        let compaction = compact_journal(file_path).await.unwrap();
+        assert_eq!(compaction, JournalCompaction { entries_before: 5, entries_after: 4 });
+
+        let compacted = read_journal(file_path).await.unwrap();
+        assert_eq!(compacted[0].count, 2);
+        assert_eq!(compacted[0].timestamp, "2025-10-01T10:00:05Z");
+        assert_eq!(compacted[1].subject.as_deref(), Some("intent-1"));
+    }
+
+    #[tokio::test]
+    async fn test_truncated_line_is_skipped() {
+        let dir = tempfile::tempdir().unwrap();
+        let file_path = dir.path().join("plan.xml");
+        let file_path = file_path.to_str().unwrap();
+
+        append_entry(file_path, &entry("2025-10-01T10:00:00Z", JournalOperation::Save, None)).await.unwrap();
+        let mut content = std::fs::read_to_string(journal_path(file_path)).unwrap();
+        content.push_str(r#"{"timestamp":"2025-10-01T1"#);
+        std::fs::write(journal_path(file_path), content).unwrap();
+
+        assert_eq!(read_journal(file_path).await.unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_entry_format() {
+        let json = serde_json::to_string(&entry("2025-10-01T10:00:00Z", JournalOperation::MergeSections, Some("a, b"))).unwrap();
+        assert_eq!(json, r#"{"timestamp":"2025-10-01T10:00:00Z","operation":"merge_sections","subject":"a, b"}"#);
+
+        let mut stamped = entry("2025-10-01T10:00:00Z", JournalOperation::Save, None);
+        stamped.author = Some("Dana <dana@example.com>".to_string());
+        let json = serde_json::to_string(&stamped).unwrap();
+        assert_eq!(json, r#"{"timestamp":"2025-10-01T10:00:00Z","operation":"save","author":"Dana <dana@example.com>"}"#);
+        assert_eq!(compact_entries(vec![entry("2025-10-01T10:00:00Z", JournalOperation::Save, None), stamped]).len(), 2);
+    }
+}
@@ -0,0 +1,119 @@
+use crate::error::Result;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// Sync service that produced a conflict copy, recognised by its file name
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum SyncProvider {
+    /// `plan (conflicted copy 2025-01-31).xml`, `plan (Jo's conflicted copy 2025-01-31).xml`
+    Dropbox,
+    /// `plan-LAPTOP-1A2B.xml` (the other computer's name)
+    OneDrive,
+    /// `plan.sync-conflict-20250131-101500-ABCDEFG.xml`
+    Syncthing,
+    /// `plan 2.xml`
+    ICloud,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ConflictCopy {
+    pub file_path: String,
+    pub provider: SyncProvider,
+    /// Seconds since the Unix epoch
+    pub modified: Option<u64>,
+}
+
+/// Conflict copies of the document in its folder, newest first
+pub async fn find_conflict_copies(file_path: &str) -> Result<Vec<ConflictCopy>> {
+    let path = Path::new(file_path);
+    let dir = path.parent().filter(|d| !d.as_os_str().is_empty()).unwrap_or(Path::new("."));
+    let (Some(stem), Some(extension)) = (path.file_stem(), path.extension()) else {
+        return Ok(vec![]);
+    };
+    let patterns = conflict_patterns(&stem.to_string_lossy(), &extension.to_string_lossy());
+
+    let mut copies = Vec::new();
+    let mut entries = tokio::fs::read_dir(dir).await?;
+    while let Some(entry) = entries.next_entry().await? {
+        let name = entry.file_name().to_string_lossy().into_owned();
+        let Some(provider) = patterns.iter().find(|(_, re)| re.is_match(&name)).map(|(p, _)| *p) else {
+            continue;
+        };
+        let metadata = entry.metadata().await?;
+        if !metadata.is_file() {
+            continue;
+        }
+        let modified = metadata
+            .modified()
+            .ok()
+            .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+            .map(|d| d.as_secs());
+        copies.push(ConflictCopy {
+            file_path: entry.path().to_string_lossy().into_owned(),
+            provider,
+            modified,
+        });
+    }
+
+    copies.sort_by(|a, b| b.modified.cmp(&a.modified).then_with(|| a.file_path.cmp(&b.file_path)));
+    Ok(copies)
+}
+
+fn conflict_patterns(stem: &str, extension: &str) -> Vec<(SyncProvider, Regex)> {
+    let stem = regex::escape(stem);
+    let extension = regex::escape(extension);
+    [
+        (SyncProvider::Dropbox, format!(r"^{stem} \([^)]*conflicted copy[^)]*\)\.{extension}$")),
+        (SyncProvider::Syncthing, format!(r"^{stem}\.sync-conflict-\d{{8}}-\d{{6}}(-[A-Z0-9]+)?\.{extension}$")),
+        (SyncProvider::OneDrive, format!(r"^{stem}-[A-Z0-9][A-Z0-9-]*\.{extension}$")),
+        (SyncProvider::ICloud, format!(r"^{stem} \d+\.{extension}$")),
+    ]
+    .into_iter()
+    .map(|(provider, pattern)| (provider, Regex::new(&pattern).expect("valid conflict pattern")))
+    .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_finds_conflict_copies_of_the_document_only() {
+        let dir = tempfile::TempDir::new().unwrap();
+        for name in [
+            "plan.xml",
+            "plan (conflicted copy 2025-01-31).xml",
+            "plan (Jo's conflicted copy 2025-02-01).xml",
+            "plan.sync-conflict-20250131-101500-ABCDEFG.xml",
+            "plan-LAPTOP-1A2B.xml",
+            "plan 2.xml",
+            "plan-notes.xml",
+            "planning (conflicted copy 2025-01-31).xml",
+            "plan (conflicted copy 2025-01-31).txt",
+        ] {
+            std::fs::write(dir.path().join(name), "").unwrap();
+        }
+        let path = dir.path().join("plan.xml");
+
+        let copies = find_conflict_copies(path.to_str().unwrap()).await.unwrap();
+
+        let mut found: Vec<(String, SyncProvider)> = copies
+            .iter()
+            .map(|c| (Path::new(&c.file_path).file_name().unwrap().to_string_lossy().into_owned(), c.provider))
+            .collect();
+        found.sort_by(|a, b| a.0.cmp(&b.0));
+        assert_eq!(
+            found,
+            vec![
+                ("plan (Jo's conflicted copy 2025-02-01).xml".to_string(), SyncProvider::Dropbox),
+                ("plan (conflicted copy 2025-01-31).xml".to_string(), SyncProvider::Dropbox),
+                ("plan 2.xml".to_string(), SyncProvider::ICloud),
+                ("plan-LAPTOP-1A2B.xml".to_string(), SyncProvider::OneDrive),
+                ("plan.sync-conflict-20250131-101500-ABCDEFG.xml".to_string(), SyncProvider::Syncthing),
+            ]
+        );
+    }
+}
@@ -0,0 +1,147 @@
+use crate::error::Result;
+use crate::models::ContextDocument;
+use crate::serializers::xml_serializer::serialize_xml;
+use std::path::{Path, PathBuf};
+
+/// Folder created under the user's documents directory
+pub const DEFAULT_DOCS_FOLDER: &str = "Flow Writer";
+
+/// File name of the example document written on first run
+pub const EXAMPLE_DOCUMENT: &str = "getting-started.xml";
+
+/// Extensions of context document files; `cec` is the one registered with the OS
+pub const DOCUMENT_EXTENSIONS: &[&str] = &["xml", "cec"];
+
+/// Whether the path has one of [`DOCUMENT_EXTENSIONS`] (case-insensitive)
+pub fn is_document_path(path: &Path) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| DOCUMENT_EXTENSIONS.iter().any(|known| known.eq_ignore_ascii_case(ext)))
+}
+
+/// Document the app was launched with, as when the OS opens a file with it
+///
+/// `args` excludes the program name. Flags are skipped; `file://` URLs are
+/// accepted since some launchers pass those.
+pub fn document_from_args(args: impl IntoIterator<Item = String>) -> Option<PathBuf> {
+    args.into_iter()
+        .filter(|arg| !arg.starts_with('-'))
+        .map(|arg| match arg.strip_prefix("file://") {
+            Some(path) => PathBuf::from(path),
+            None => PathBuf::from(arg),
+        })
+        .find(|path| is_document_path(path))
+}
+
+/// Create the documents directory with an example document if it does not exist yet
+///
+/// Returns whether the directory was created. An existing directory is left
+/// untouched, even if the user deleted the example.
+pub async fn ensure_documents_dir(dir: &Path) -> Result<bool> {
+    if tokio::fs::try_exists(dir).await? {
+        return Ok(false);
+    }
+    tokio::fs::create_dir_all(dir).await?;
+    tokio::fs::write(dir.join(EXAMPLE_DOCUMENT), serialize_xml(&example_document()?)).await?;
+    Ok(true)
+}
+
+/// Context documents directly inside `dir`, sorted by file name
+pub async fn list_documents(dir: &Path) -> Result<Vec<PathBuf>> {
+    let mut entries = match tokio::fs::read_dir(dir).await {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(vec![]),
+        Err(e) => return Err(e.into()),
+    };
+
+    let mut documents = Vec::new();
+    while let Some(entry) = entries.next_entry().await? {
+        let path = entry.path();
+        if is_document_path(&path) && entry.file_type().await?.is_file() {
+            documents.push(path);
+        }
+    }
+    documents.sort();
+    Ok(documents)
+}
+
+/// Document to open at startup: the configured path, else the first document in `dir`
+pub async fn default_document_path(configured: Option<&str>, dir: &Path) -> Result<Option<PathBuf>> {
+    if let Some(path) = configured {
+        return Ok(Some(PathBuf::from(path)));
+    }
+    ensure_documents_dir(dir).await?;
+    let documents = list_documents(dir).await?;
+    let example = dir.join(EXAMPLE_DOCUMENT);
+    Ok(documents.iter().find(|p| **p == example).or(documents.first()).cloned())
+}
+
+fn example_document() -> Result<ContextDocument> {
+    ContextDocument::builder()
+        .title("Getting Started")
+        .description("An example context document")
+        .variable("goal", "describe what you want the model to do")
+        .section(
+            "intent-1",
+            "intent",
+            "# Intent\n\nState the goal: ${goal}.\n\nVariables like `${goal}` are filled in when the context is assembled.",
+        )
+        .section("process-1", "process", "# Process\n\n1. Gather the inputs\n2. Work through the steps\n3. Check the result")
+        .section("evaluation-1", "evaluation", "# Evaluation\n\nHow will you know the output is good?")
+        .flow(
+            "```mermaid\nflowchart TD\n  A[Intent] --> B[Process]\n  B --> C[Evaluation]\n  click A \"#intent-1\"\n  click B \"#process-1\"\n  click C \"#evaluation-1\"\n```",
+        )
+        .build()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parsers::parse_xml;
+
+    #[tokio::test]
+    async fn test_scaffolds_directory_once() {
+        let root = tempfile::TempDir::new().unwrap();
+        let dir = root.path().join(DEFAULT_DOCS_FOLDER);
+
+        assert!(ensure_documents_dir(&dir).await.unwrap());
+        let example = std::fs::read_to_string(dir.join(EXAMPLE_DOCUMENT)).unwrap();
+        assert_eq!(parse_xml(&example).unwrap().sections.len(), 3);
+
+        std::fs::remove_file(dir.join(EXAMPLE_DOCUMENT)).unwrap();
+        assert!(!ensure_documents_dir(&dir).await.unwrap());
+        assert!(list_documents(&dir).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_default_document_path() {
+        let root = tempfile::TempDir::new().unwrap();
+        let dir = root.path().join("docs");
+
+        let path = default_document_path(None, &dir).await.unwrap();
+        assert_eq!(path, Some(dir.join(EXAMPLE_DOCUMENT)));
+
+        std::fs::write(dir.join("a.xml"), "").unwrap();
+        std::fs::write(dir.join("b.CEC"), "").unwrap();
+        std::fs::write(dir.join("notes.txt"), "").unwrap();
+        assert_eq!(
+            list_documents(&dir).await.unwrap(),
+            vec![dir.join("a.xml"), dir.join("b.CEC"), dir.join(EXAMPLE_DOCUMENT)]
+        );
+
+        let configured = default_document_path(Some("/tmp/x.xml"), &dir).await.unwrap();
+        assert_eq!(configured, Some(PathBuf::from("/tmp/x.xml")));
+    }
+
+    #[test]
+    fn test_document_from_args() {
+        let args = |list: &[&str]| list.iter().map(|arg| arg.to_string()).collect::<Vec<_>>();
+
+        assert_eq!(
+            document_from_args(args(&["--flag", "notes.txt", "/home/me/plan.cec"])),
+            Some(PathBuf::from("/home/me/plan.cec"))
+        );
+        assert_eq!(document_from_args(args(&["file:///tmp/a.XML"])), Some(PathBuf::from("/tmp/a.XML")));
+        assert_eq!(document_from_args(args(&["-psn_0_123"])), None);
+    }
+}
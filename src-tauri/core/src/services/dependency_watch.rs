@@ -0,0 +1,155 @@
+use crate::error::Result;
+use crate::processors::external_refs::{collect_external_refs, DependencyKind};
+use crate::processors::portable_format::platform_path;
+use crate::services::flow_service;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+/// A file outside the document that some of its sections depend on
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ExternalDependency {
+    /// Resolved against the document's folder
+    pub path: String,
+    pub kind: DependencyKind,
+    pub section_ids: Vec<String>,
+    pub exists: bool,
+    /// Last modification time (RFC 3339), when the file exists
+    pub modified: Option<String>,
+}
+
+/// An external file that changed while being watched
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct DependencyChange {
+    pub document_path: String,
+    pub dependency: ExternalDependency,
+    /// Stale-content warning to show next to the affected sections
+    pub warning: String,
+}
+
+/// External files the document's sections depend on, with their current state
+pub async fn external_dependencies(file_path: &str) -> Result<Vec<ExternalDependency>> {
+    let doc = flow_service::read_context_document(file_path).await?;
+    let base_dir = Path::new(file_path).parent().map(Path::to_path_buf).unwrap_or_default();
+
+    Ok(collect_external_refs(&doc.sections)
+        .into_iter()
+        .map(|(target, kind, section_ids)| {
+            let path = base_dir.join(platform_path(&target));
+            let modified = modified(&path);
+            ExternalDependency {
+                path: path.to_string_lossy().to_string(),
+                kind,
+                section_ids,
+                exists: path.exists(),
+                modified: modified.map(|time| chrono::DateTime::<chrono::Utc>::from(time).to_rfc3339()),
+            }
+        })
+        .collect())
+}
+
+/// Poll the document's external files and call `on_change` for each one that changes
+///
+/// The dependency list is re-read on every poll, so references added while
+/// watching are picked up; a file seen for the first time is not reported.
+/// Runs until the task is dropped. Read errors are skipped; the next poll retries.
+pub async fn watch_dependencies(file_path: &str, interval: Duration, on_change: impl Fn(DependencyChange)) {
+    let mut last_seen: HashMap<PathBuf, Option<SystemTime>> = HashMap::new();
+    let mut ticker = tokio::time::interval(interval);
+    loop {
+        ticker.tick().await;
+        let Ok(dependencies) = external_dependencies(file_path).await else {
+            continue;
+        };
+        for change in detect_changes(file_path, dependencies, &mut last_seen) {
+            on_change(change);
+        }
+    }
+}
+
+fn detect_changes(
+    file_path: &str,
+    dependencies: Vec<ExternalDependency>,
+    last_seen: &mut HashMap<PathBuf, Option<SystemTime>>,
+) -> Vec<DependencyChange> {
+    let mut changes = Vec::new();
+    for dependency in dependencies {
+        let path = PathBuf::from(&dependency.path);
+        let modified = modified(&path);
+        match last_seen.insert(path, modified) {
+            Some(previous) if previous != modified => {
+                let warning = stale_warning(&dependency);
+                changes.push(DependencyChange {
+                    document_path: file_path.to_string(),
+                    dependency,
+                    warning,
+                });
+            }
+            _ => {}
+        }
+    }
+    changes
+}
+
+fn stale_warning(dependency: &ExternalDependency) -> String {
+    let sections = dependency.section_ids.join(", ");
+    match (dependency.kind, dependency.exists) {
+        (DependencyKind::Transclusion, true) => {
+            format!("{} changed; transcluded content in {} may be out of date", dependency.path, sections)
+        }
+        (DependencyKind::Transclusion, false) => {
+            format!("{} was removed; transcluded content in {} can no longer be refreshed", dependency.path, sections)
+        }
+        (DependencyKind::Attachment, true) => format!("{} changed; it is linked from {}", dependency.path, sections),
+        (DependencyKind::Attachment, false) => format!("{} was removed; it is linked from {}", dependency.path, sections),
+    }
+}
+
+fn modified(path: &Path) -> Option<SystemTime> {
+    std::fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::*;
+    use std::io::Write;
+    use tempfile::TempDir;
+
+    #[tokio::test]
+    async fn test_external_dependencies_and_changes() {
+        let dir = TempDir::new().unwrap();
+        let notes = dir.path().join("notes.md");
+        std::fs::write(&notes, "v1").unwrap();
+        let doc = ContextDocument::builder()
+            .title("Main")
+            .section("intent-1", "intent", "[notes](notes.md) and [gone](missing.pdf)")
+            .build()
+            .unwrap();
+        let file_path = dir.path().join("main.xml");
+        flow_service::save_context_document(file_path.to_str().unwrap(), &doc).await.unwrap();
+        let file_path = file_path.to_str().unwrap();
+
+        let dependencies = external_dependencies(file_path).await.unwrap();
+        assert_eq!(dependencies.len(), 2);
+        assert!(dependencies[0].exists && dependencies[0].modified.is_some());
+        assert!(!dependencies[1].exists);
+
+        let mut last_seen = HashMap::new();
+        assert!(detect_changes(file_path, dependencies.clone(), &mut last_seen).is_empty());
+
+        let later = SystemTime::now() + Duration::from_secs(5);
+        let mut file = std::fs::OpenOptions::new().append(true).open(&notes).unwrap();
+        file.write_all(b" v2").unwrap();
+        file.set_modified(later).unwrap();
+
+        let changes = detect_changes(file_path, dependencies, &mut last_seen);
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].dependency.kind, DependencyKind::Attachment);
+        assert!(changes[0].warning.contains("linked from intent-1"));
+        flow_service::close_document(file_path);
+    }
+}
@@ -0,0 +1,670 @@
+use crate::error::{ContextError, Result};
+use crate::models::ContextDocument;
+use crate::parsers::InputQuirk;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, LazyLock, Mutex};
+use std::time::SystemTime;
+use tokio::fs;
+
+/// In-memory copies of documents, keyed by file path
+///
+/// The first read of a path parses it from disk; later reads and edits work on the
+/// cached copy. Edits mark the document dirty and only [`save`] writes it back. A
+/// clean copy is dropped and re-read when the file changes on disk. Scratch
+/// documents (see [`create_scratch`]) live here only, under a handle in place of a path.
+static DOCUMENTS: LazyLock<Mutex<HashMap<PathBuf, CachedDocument>>> = LazyLock::new(Default::default);
+
+/// Documents opened read-only; edits to them are refused
+static READ_ONLY: LazyLock<Mutex<HashSet<PathBuf>>> = LazyLock::new(Default::default);
+/// Folders whose documents are all read-only, e.g. the managed copies of downloaded documents
+static READ_ONLY_DIRS: Mutex<Vec<PathBuf>> = Mutex::new(Vec::new());
+/// Write locks of documents, see [`lock`]
+static WRITE_LOCKS: LazyLock<Mutex<HashMap<PathBuf, Arc<tokio::sync::Mutex<()>>>>> = LazyLock::new(Default::default);
+/// Prefix of the handles of scratch documents, e.g. `scratch:3`
+pub const SCRATCH_PREFIX: &str = "scratch:";
+/// Source of scratch document numbers
+static LAST_SCRATCH: AtomicU64 = AtomicU64::new(0);
+/// Prefix of the IDs handed out by [`open`], e.g. `doc:3`
+pub const DOCUMENT_ID_PREFIX: &str = "doc:";
+/// Paths (or scratch handles) of the documents opened by ID
+static DOCUMENT_IDS: LazyLock<Mutex<HashMap<String, PathBuf>>> = LazyLock::new(Default::default);
+/// Source of document ID numbers
+static LAST_DOCUMENT_ID: AtomicU64 = AtomicU64::new(0);
+/// Source of document versions, shared by all documents so a version is never reused after a reload
+static LAST_VERSION: AtomicU64 = AtomicU64::new(0);
+
+/// Exclusive write access to one document, released when dropped
+pub type DocumentLock = tokio::sync::OwnedMutexGuard<()>;
+
+struct CachedDocument {
+    doc: ContextDocument,
+    /// Corrected when the file was read; cleared once it is saved normalized
+    quirks: Vec<InputQuirk>,
+    dirty: bool,
+    /// Bumped on every edit so a save racing an edit does not mark it clean
+    revision: u64,
+    /// Modification time and length of the file when it was last read or written
+    fingerprint: Option<(SystemTime, u64)>,
+    changes: ChangeLog,
+}
+
+/// Which parts of a cached document changed at which version
+///
+/// Versions increase with every edit. Parts are tracked from the version the
+/// document was read at; anything older needs a full reload.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChangeLog {
+    /// Version of the document as it is now
+    pub version: u64,
+    /// Version the document was read from disk at
+    pub baseline: u64,
+    /// Last change of each top-level section (including its subsections) since the baseline
+    pub sections: HashMap<String, u64>,
+    /// Top-level sections removed since the baseline, with the version they were removed at
+    pub removed: HashMap<String, u64>,
+    pub section_order: u64,
+    pub meta: u64,
+    pub variables: u64,
+    pub flow: u64,
+}
+
+impl ChangeLog {
+    fn new() -> Self {
+        let version = next_version();
+        ChangeLog {
+            version,
+            baseline: version,
+            sections: HashMap::new(),
+            removed: HashMap::new(),
+            section_order: version,
+            meta: version,
+            variables: version,
+            flow: version,
+        }
+    }
+
+    /// Record the differences between two versions of the document
+    fn record(&mut self, before: &ContextDocument, after: &ContextDocument) {
+        let version = next_version();
+        self.version = version;
+
+        for section in &after.sections {
+            if before.sections.iter().find(|s| s.id == section.id) != Some(section) {
+                self.sections.insert(section.id.clone(), version);
+                self.removed.remove(&section.id);
+            }
+        }
+        for section in &before.sections {
+            if !after.sections.iter().any(|s| s.id == section.id) {
+                self.sections.remove(&section.id);
+                self.removed.insert(section.id.clone(), version);
+            }
+        }
+        if !before.sections.iter().map(|s| &s.id).eq(after.sections.iter().map(|s| &s.id)) {
+            self.section_order = version;
+        }
+        if before.meta != after.meta {
+            self.meta = version;
+        }
+        if before.variables != after.variables {
+            self.variables = version;
+        }
+        if before.flow_graph != after.flow_graph {
+            self.flow = version;
+        }
+    }
+}
+
+fn next_version() -> u64 {
+    LAST_VERSION.fetch_add(1, Ordering::Relaxed) + 1
+}
+
+async fn file_fingerprint(path: &Path) -> Option<(SystemTime, u64)> {
+    let metadata = fs::metadata(path).await.ok()?;
+    Some((metadata.modified().ok()?, metadata.len()))
+}
+
+fn documents() -> std::sync::MutexGuard<'static, HashMap<PathBuf, CachedDocument>> {
+    // A panic while holding the lock cannot leave a half-applied edit (edits
+    // run on the cached value only after their closure succeeds), so recover
+    DOCUMENTS.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+/// Load the document into the cache unless an up-to-date copy is already there
+async fn ensure_loaded(file_path: &str) -> Result<()> {
+    let path = PathBuf::from(file_path);
+    if is_scratch(file_path) {
+        return match documents().contains_key(&path) {
+            true => Ok(()),
+            false => Err(ContextError::FileNotFound(format!("Scratch document {} was closed", file_path))),
+        };
+    }
+    let fingerprint = file_fingerprint(&path).await;
+
+    {
+        let mut docs = documents();
+        if let Some(cached) = docs.get(&path) {
+            if cached.dirty || cached.fingerprint == fingerprint {
+                return Ok(());
+            }
+            docs.remove(&path);
+        }
+    }
+
+    let (doc, quirks) = super::flow_service::read_document_from_disk(file_path).await?;
+    documents().entry(path).or_insert(CachedDocument {
+        doc,
+        quirks,
+        dirty: false,
+        revision: 0,
+        fingerprint,
+        changes: ChangeLog::new(),
+    });
+    Ok(())
+}
+
+/// Current (possibly unsaved) version of the document, unresolved
+pub async fn get(file_path: &str) -> Result<ContextDocument> {
+    ensure_loaded(file_path).await?;
+    let docs = documents();
+    let cached = docs
+        .get(Path::new(file_path))
+        .expect("document loaded by ensure_loaded");
+    Ok(cached.doc.clone())
+}
+
+/// Mark the document read-only (or editable again)
+pub fn set_read_only(file_path: &str, read_only: bool) {
+    let mut paths = READ_ONLY.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+    if read_only {
+        paths.insert(PathBuf::from(file_path));
+    } else {
+        paths.remove(Path::new(file_path));
+    }
+}
+
+/// Treat every document in `dir`, or below it, as read-only for the rest of the run
+///
+/// Unlike [`set_read_only`] this covers documents that are not open yet, so
+/// the app registers such folders at startup.
+pub fn set_read_only_dir(dir: &Path) {
+    let mut dirs = READ_ONLY_DIRS.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+    if !dirs.iter().any(|known| known == dir) {
+        dirs.push(dir.to_path_buf());
+    }
+}
+
+pub fn is_read_only(file_path: &str) -> bool {
+    let path = Path::new(file_path);
+    let in_read_only_dir = READ_ONLY_DIRS
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .iter()
+        .any(|dir| path.starts_with(dir));
+    in_read_only_dir || READ_ONLY.lock().unwrap_or_else(|poisoned| poisoned.into_inner()).contains(path)
+}
+
+/// Refuse changes to a read-only document
+pub fn ensure_writable(file_path: &str) -> Result<()> {
+    if is_read_only(file_path) {
+        return Err(ContextError::InvalidArgument(format!(
+            "{} is open read-only; fork it to make changes",
+            file_path
+        )));
+    }
+    Ok(())
+}
+
+/// Wait for exclusive write access to the document
+///
+/// Mutating commands hold the lock across their whole read-edit-save
+/// sequence, so an autosave racing a manual save, or two edits that roll back
+/// on failure, run one after the other instead of interleaving. Reads do not
+/// take the lock and proceed concurrently. The lock is not reentrant: code
+/// holding it must not call anything that takes it again.
+pub async fn lock(file_path: &str) -> DocumentLock {
+    let lock = WRITE_LOCKS
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .entry(PathBuf::from(file_path))
+        .or_default()
+        .clone();
+    lock.lock_owned().await
+}
+
+/// Apply an edit to the cached document and mark it dirty
+///
+/// The edit runs on a copy which replaces the cached document only if the edit
+/// succeeds, so a failed edit never leaves a partial change behind. Read-only
+/// documents refuse every edit.
+pub async fn update<T>(file_path: &str, edit: impl FnOnce(&mut ContextDocument) -> Result<T>) -> Result<T> {
+    ensure_writable(file_path)?;
+    ensure_loaded(file_path).await?;
+    let mut docs = documents();
+    let cached = docs
+        .get_mut(Path::new(file_path))
+        .expect("document loaded by ensure_loaded");
+
+    let mut doc = cached.doc.clone();
+    let value = edit(&mut doc)?;
+
+    cached.changes.record(&cached.doc, &doc);
+    cached.doc = doc;
+    cached.dirty = true;
+    cached.revision += 1;
+    Ok(value)
+}
+
+/// Replace the cached document wholesale, e.g. after repairing a file that did not load
+///
+/// The new document is an unsaved edit just like one made through [`update`].
+pub fn replace(file_path: &str, doc: ContextDocument) -> Result<()> {
+    ensure_writable(file_path)?;
+    let mut docs = documents();
+    match docs.get_mut(Path::new(file_path)) {
+        Some(cached) => {
+            cached.changes.record(&cached.doc, &doc);
+            cached.doc = doc;
+            cached.dirty = true;
+            cached.revision += 1;
+        }
+        None => {
+            docs.insert(
+                PathBuf::from(file_path),
+                CachedDocument {
+                    doc,
+                    quirks: Vec::new(),
+                    dirty: true,
+                    revision: 1,
+                    fingerprint: None,
+                    changes: ChangeLog::new(),
+                },
+            );
+        }
+    }
+    Ok(())
+}
+
+/// Write the cached document to disk; no-op for documents that are not cached
+pub async fn save(file_path: &str) -> Result<()> {
+    if is_scratch(file_path) {
+        return Err(ContextError::InvalidArgument(format!("{} has no file yet", file_path)));
+    }
+    let path = PathBuf::from(file_path);
+    let (doc, revision) = match documents().get(&path) {
+        Some(cached) => (cached.doc.clone(), cached.revision),
+        None => return Ok(()),
+    };
+
+    super::flow_service::save_context_document(file_path, &doc).await?;
+    let fingerprint = file_fingerprint(&path).await;
+
+    if let Some(cached) = documents().get_mut(&path) {
+        cached.fingerprint = fingerprint;
+        cached.quirks.clear();
+        if cached.revision == revision {
+            cached.dirty = false;
+        }
+    }
+    Ok(())
+}
+
+/// Input quirks corrected when the document was last read from disk
+pub async fn load_warnings(file_path: &str) -> Result<Vec<InputQuirk>> {
+    ensure_loaded(file_path).await?;
+    let docs = documents();
+    let cached = docs
+        .get(Path::new(file_path))
+        .expect("document loaded by ensure_loaded");
+    Ok(cached.quirks.clone())
+}
+
+/// Versions at which the parts of the document last changed
+pub async fn change_log(file_path: &str) -> Result<ChangeLog> {
+    ensure_loaded(file_path).await?;
+    let docs = documents();
+    let cached = docs
+        .get(Path::new(file_path))
+        .expect("document loaded by ensure_loaded");
+    Ok(cached.changes.clone())
+}
+
+fn document_ids() -> std::sync::MutexGuard<'static, HashMap<String, PathBuf>> {
+    DOCUMENT_IDS.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+/// Load the document and return an ID that stands for it in place of its path
+///
+/// Opening the same path again returns the same ID. The ID follows the
+/// document to a new path when it is saved elsewhere (see [`rebind`]) and
+/// stops working when the document is closed.
+pub async fn open(file_path: &str) -> Result<String> {
+    ensure_loaded(file_path).await?;
+    let mut ids = document_ids();
+    if let Some((id, _)) = ids.iter().find(|(_, path)| path.as_path() == Path::new(file_path)) {
+        return Ok(id.clone());
+    }
+    let id = format!("{}{}", DOCUMENT_ID_PREFIX, LAST_DOCUMENT_ID.fetch_add(1, Ordering::Relaxed) + 1);
+    ids.insert(id.clone(), PathBuf::from(file_path));
+    Ok(id)
+}
+
+/// The path, or scratch handle, behind a document ID from [`open`]
+///
+/// Anything that is not a document ID is taken to be a path already and
+/// returned as is, so callers can accept either.
+pub fn resolve(document: &str) -> Result<String> {
+    if !document.starts_with(DOCUMENT_ID_PREFIX) {
+        return Ok(document.to_string());
+    }
+    document_ids()
+        .get(document)
+        .map(|path| path.to_string_lossy().into_owned())
+        .ok_or_else(|| ContextError::InvalidArgument(format!("Unknown document ID '{}'; it may have been closed", document)))
+}
+
+/// Add a document that has no file yet and return its handle
+///
+/// The handle stands in for the file path in every call until [`rebind`]
+/// gives the document a path. The document counts as unsaved throughout.
+pub fn create_scratch(doc: ContextDocument) -> String {
+    let handle = format!("{}{}", SCRATCH_PREFIX, LAST_SCRATCH.fetch_add(1, Ordering::Relaxed) + 1);
+    documents().insert(
+        PathBuf::from(&handle),
+        CachedDocument {
+            doc,
+            quirks: Vec::new(),
+            dirty: true,
+            revision: 1,
+            fingerprint: None,
+            changes: ChangeLog::new(),
+        },
+    );
+    handle
+}
+
+/// Whether `file_path` is the handle of a scratch document rather than a path
+pub fn is_scratch(file_path: &str) -> bool {
+    file_path.starts_with(SCRATCH_PREFIX)
+}
+
+/// Move the cached document from `file_path` to `new_path`, as an unsaved edit there
+///
+/// Refused when `new_path` has unsaved edits of its own; a clean copy cached
+/// there is replaced. IDs of the document from [`open`] move with it.
+pub fn rebind(file_path: &str, new_path: &str) -> Result<()> {
+    ensure_writable(new_path)?;
+    let mut docs = documents();
+    if docs.get(Path::new(new_path)).is_some_and(|cached| cached.dirty) {
+        return Err(ContextError::InvalidArgument(format!("{} is open with unsaved changes", new_path)));
+    }
+    let mut cached = docs
+        .remove(Path::new(file_path))
+        .ok_or_else(|| ContextError::FileNotFound(format!("{} is not open", file_path)))?;
+    cached.dirty = true;
+    cached.revision += 1;
+    cached.fingerprint = None;
+    docs.insert(PathBuf::from(new_path), cached);
+    for path in document_ids().values_mut().filter(|path| path.as_path() == Path::new(file_path)) {
+        *path = PathBuf::from(new_path);
+    }
+    Ok(())
+}
+
+/// Whether the document has edits that have not been saved
+pub fn is_dirty(file_path: &str) -> bool {
+    documents()
+        .get(Path::new(file_path))
+        .is_some_and(|cached| cached.dirty)
+}
+
+/// Drop the cached copy, discarding unsaved edits; returns whether there were any
+pub fn close(file_path: &str) -> bool {
+    // Keep the lock while a command still holds or waits for it
+    WRITE_LOCKS
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .retain(|path, lock| path != Path::new(file_path) || Arc::strong_count(lock) > 1);
+    document_ids().retain(|_, path| path != Path::new(file_path));
+    documents()
+        .remove(Path::new(file_path))
+        .is_some_and(|cached| cached.dirty)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::*;
+    use crate::serializers::xml_serializer::serialize_xml;
+
+    fn write_document(dir: &tempfile::TempDir, title: &str) -> String {
+        let doc = ContextDocument::builder()
+            .title(title)
+            .section("intent-1", "intent", "Intent")
+            .build()
+            .unwrap();
+        let path = dir.path().join("doc.xml");
+        std::fs::write(&path, serialize_xml(&doc)).unwrap();
+        path.to_str().unwrap().to_string()
+    }
+
+    #[tokio::test]
+    async fn test_edits_stay_in_memory_until_save() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = write_document(&dir, "Original");
+        let on_disk = std::fs::read_to_string(&path).unwrap();
+
+        update(&path, |doc| {
+            doc.meta.title = "Edited".to_string();
+            Ok(())
+        })
+        .await
+        .unwrap();
+
+        assert!(is_dirty(&path));
+        assert_eq!(get(&path).await.unwrap().meta.title, "Edited");
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), on_disk);
+
+        save(&path).await.unwrap();
+
+        assert!(!is_dirty(&path));
+        assert!(std::fs::read_to_string(&path).unwrap().contains("Edited"));
+        close(&path);
+    }
+
+    #[tokio::test]
+    async fn test_failed_edit_leaves_document_unchanged() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = write_document(&dir, "Original");
+
+        let result: Result<()> = update(&path, |doc| {
+            doc.meta.title = "Half-applied".to_string();
+            Err(crate::error::ContextError::InvalidArgument("nope".to_string()))
+        })
+        .await;
+
+        assert!(result.is_err());
+        assert!(!is_dirty(&path));
+        assert_eq!(get(&path).await.unwrap().meta.title, "Original");
+        close(&path);
+    }
+
+    #[tokio::test]
+    async fn test_clean_copy_reloads_after_external_change() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = write_document(&dir, "Original");
+        assert_eq!(get(&path).await.unwrap().meta.title, "Original");
+
+        write_document(&dir, "Changed elsewhere");
+        assert_eq!(get(&path).await.unwrap().meta.title, "Changed elsewhere");
+        close(&path);
+    }
+
+    #[tokio::test]
+    async fn test_bom_prefixed_file_opens_with_warning() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = write_document(&dir, "Exported");
+        let xml = std::fs::read_to_string(&path).unwrap();
+        std::fs::write(&path, format!("\u{FEFF}\n  {}", xml)).unwrap();
+
+        assert_eq!(get(&path).await.unwrap().meta.title, "Exported");
+        assert_eq!(
+            load_warnings(&path).await.unwrap(),
+            vec![InputQuirk::ByteOrderMark, InputQuirk::LeadingWhitespace]
+        );
+
+        update(&path, |_| Ok(())).await.unwrap();
+        save(&path).await.unwrap();
+        assert!(load_warnings(&path).await.unwrap().is_empty());
+        assert!(std::fs::read_to_string(&path).unwrap().starts_with('<'));
+        close(&path);
+    }
+
+    #[tokio::test]
+    async fn test_change_log_tracks_edited_parts() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = write_document(&dir, "Original");
+        let loaded = change_log(&path).await.unwrap();
+
+        update(&path, |doc| {
+            doc.sections[0].content = "Edited".to_string();
+            doc.sections.push(Section::new("process-1", "process", "Steps"));
+            Ok(())
+        })
+        .await
+        .unwrap();
+        let edited = change_log(&path).await.unwrap();
+        assert!(edited.version > loaded.version);
+        assert_eq!(edited.sections["intent-1"], edited.version);
+        assert_eq!(edited.sections["process-1"], edited.version);
+        assert_eq!(edited.section_order, edited.version);
+        assert_eq!(edited.meta, loaded.baseline);
+
+        update(&path, |doc| {
+            doc.sections.retain(|s| s.id != "process-1");
+            Ok(())
+        })
+        .await
+        .unwrap();
+        let removed = change_log(&path).await.unwrap();
+        assert_eq!(removed.removed["process-1"], removed.version);
+        assert!(!removed.sections.contains_key("process-1"));
+        assert_eq!(removed.sections["intent-1"], edited.version);
+
+        close(&path);
+        assert!(change_log(&path).await.unwrap().baseline > removed.version);
+        close(&path);
+    }
+
+    #[tokio::test]
+    async fn test_close_discards_unsaved_edits() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = write_document(&dir, "Original");
+
+        update(&path, |doc| {
+            doc.meta.title = "Edited".to_string();
+            Ok(())
+        })
+        .await
+        .unwrap();
+
+        assert!(close(&path));
+        assert_eq!(get(&path).await.unwrap().meta.title, "Original");
+        close(&path);
+    }
+
+    #[tokio::test]
+    async fn test_lock_serializes_writers_but_not_readers() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = write_document(&dir, "Original");
+        let other = dir.path().join("other.xml").to_string_lossy().into_owned();
+
+        let guard = lock(&path).await;
+        let writer = tokio::spawn({
+            let path = path.clone();
+            async move {
+                let _guard = lock(&path).await;
+                update(&path, |doc| {
+                    doc.meta.title = "Second".to_string();
+                    Ok(())
+                })
+                .await
+            }
+        });
+        tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+        assert!(!writer.is_finished());
+
+        // Reads and other documents are not held up
+        assert_eq!(get(&path).await.unwrap().meta.title, "Original");
+        drop(lock(&other).await);
+        update(&path, |doc| {
+            doc.meta.title = "First".to_string();
+            Ok(())
+        })
+        .await
+        .unwrap();
+
+        drop(guard);
+        writer.await.unwrap().unwrap();
+        assert_eq!(get(&path).await.unwrap().meta.title, "Second");
+        close(&path);
+        assert!(!WRITE_LOCKS.lock().unwrap().contains_key(Path::new(&path)));
+    }
+
+    #[tokio::test]
+    async fn test_scratch_document_rebinds_to_a_path() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("new.xml").to_string_lossy().into_owned();
+        let doc = ContextDocument::builder().title("Untitled").build().unwrap();
+
+        let handle = create_scratch(doc);
+        assert!(is_scratch(&handle));
+        assert!(is_dirty(&handle));
+        update(&handle, |doc| {
+            doc.meta.title = "Notes".to_string();
+            Ok(())
+        })
+        .await
+        .unwrap();
+        assert!(matches!(save(&handle).await, Err(ContextError::InvalidArgument(_))));
+
+        rebind(&handle, &path).unwrap();
+        assert!(matches!(get(&handle).await, Err(ContextError::FileNotFound(_))));
+        save(&path).await.unwrap();
+        assert!(!is_dirty(&path));
+        assert!(std::fs::read_to_string(&path).unwrap().contains("<title>Notes</title>"));
+
+        let other = create_scratch(get(&path).await.unwrap());
+        update(&path, |doc| {
+            doc.meta.title = "Edited".to_string();
+            Ok(())
+        })
+        .await
+        .unwrap();
+        assert!(matches!(rebind(&other, &path), Err(ContextError::InvalidArgument(_))));
+        close(&other);
+        close(&path);
+    }
+
+    #[tokio::test]
+    async fn test_document_ids_follow_the_document() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = write_document(&dir, "Original");
+        let moved = dir.path().join("moved.xml").to_string_lossy().into_owned();
+
+        let id = open(&path).await.unwrap();
+        assert!(id.starts_with(DOCUMENT_ID_PREFIX));
+        assert_eq!(open(&path).await.unwrap(), id);
+        assert_eq!(resolve(&id).unwrap(), path);
+        assert_eq!(resolve(&path).unwrap(), path);
+        assert!(open(&dir.path().join("missing.xml").to_string_lossy()).await.is_err());
+
+        rebind(&path, &moved).unwrap();
+        assert_eq!(resolve(&id).unwrap(), moved);
+
+        close(&moved);
+        assert!(matches!(resolve(&id), Err(ContextError::InvalidArgument(_))));
+    }
+}
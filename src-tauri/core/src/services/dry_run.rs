@@ -0,0 +1,89 @@
+use crate::error::Result;
+use crate::models::ContextDocument;
+use crate::serializers::xml_serializer;
+use crate::validators::schema_validator;
+use serde::{Deserialize, Serialize};
+use similar::TextDiff;
+
+/// What a mutating command would write, computed without applying it
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct DryRunPreview {
+    pub changed: bool,
+    /// Unified diff from the current XML to the would-be XML; empty when unchanged
+    pub diff: String,
+    /// The would-be document as it would be serialized
+    pub xml: String,
+}
+
+/// Result of a command that supports `dry_run`
+///
+/// Untagged, so commands called without `dry_run` keep returning their usual value.
+#[derive(Debug, Clone, Serialize, PartialEq)]
+#[serde(untagged)]
+pub enum Mutation<T> {
+    Applied(T),
+    Preview(DryRunPreview),
+}
+
+/// Compare the current XML with the would-be document
+///
+/// Fails with the same schema validation error the real command would hit
+/// when saving, so confirmation dialogs can surface it before anything changes.
+pub fn preview(before_xml: &str, after: &ContextDocument) -> Result<DryRunPreview> {
+    let xml = xml_serializer::serialize_xml(after);
+    schema_validator::validate_schema(&xml)?;
+
+    let changed = before_xml != xml;
+    let diff = if changed {
+        TextDiff::from_lines(before_xml, &xml)
+            .unified_diff()
+            .header("current", "preview")
+            .to_string()
+    } else {
+        String::new()
+    };
+
+    Ok(DryRunPreview { changed, diff, xml })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn document() -> ContextDocument {
+        ContextDocument::builder()
+            .title("Plan")
+            .section("intent-1", "intent", "# Intent")
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_preview_diff() {
+        let doc = document();
+        let before = xml_serializer::serialize_xml(&doc);
+        assert!(!preview(&before, &doc).unwrap().changed);
+
+        let mut edited = doc.clone();
+        edited.sections[0].content = "# Goal".to_string();
+        let result = preview(&before, &edited).unwrap();
+
+        assert!(result.changed);
+        assert!(result.diff.starts_with("--- current\n+++ preview\n"));
+        assert!(result.diff.contains("\n-# Intent\n+# Goal\n"), "{}", result.diff);
+        assert!(result.xml.contains("# Goal"));
+    }
+
+    #[test]
+    fn test_preview_rejects_invalid_document() {
+        let mut edited = document();
+        edited.sections[0].section_type = "notes".to_string();
+        assert!(preview("", &edited).is_err());
+    }
+
+    #[test]
+    fn test_mutation_serializes_untagged() {
+        let applied: Mutation<Vec<String>> = Mutation::Applied(vec!["a".to_string()]);
+        assert_eq!(serde_json::to_string(&applied).unwrap(), r#"["a"]"#);
+    }
+}
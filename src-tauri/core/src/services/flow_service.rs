@@ -0,0 +1,3108 @@
+use crate::error::{ContextError, Result};
+use crate::exporters::{docx_exporter, excalidraw_exporter, flow_json_exporter, html_exporter::{self, HtmlOptions}, ics_exporter, link_map_exporter, print_exporter, reading_order_exporter, section_exporter, ExportFormat, PageSize, PrintOptions, ReadingOrderOptions};
+use crate::models::*;
+use crate::parsers::{input_normalizer::{self, InputQuirk}, salvage_parser::{self, SalvagedDocument}, xml_parser, mermaid_parser};
+use crate::plugins::{self, PluginStage, ScriptHooks};
+use crate::processors::{
+    auto_layout, budget_report, calendar_events, citations, click_suggestions, content_blocks, content_summary, context_assembly, document_compare::{self, DocumentComparison}, document_edits, document_merge, document_stats,
+    duplicate_content, effort_rollup, flow_checklist, flow_navigation, flow_simulation, graph_import::{self, GraphFormat}, localization, mermaid_import, portable_format, redaction, section_dependencies, section_encryption, section_filter, section_frontmatter, section_import, section_index, section_merge, section_split, section_templates, section_type_inference, status_sync, tag_index, template_extraction, variable_resolver,
+    variable_sheet, workspace_graph, workspace_index,
+};
+use crate::processors::document_merge::{MergeResult, MergeSide};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::Path;
+use crate::serializers::{json_serializer, xml_serializer};
+use crate::services::conflict_copies::{self, ConflictCopy};
+use crate::services::change_journal::{self, JournalOperation};
+use crate::services::{formatting, settings, snippets::{self, Snippet}, stats_history, template_library::{self, TemplateInfo}};
+use crate::services::{backup_service, binary_cache::{self, BinaryCache}, document_store, dry_run::{self, DryRunPreview}, section_activity, transclusion_service, usage_metrics};
+use crate::validators::{auto_fix, flow_connectivity};
+use crate::validators::publish_check::{self, Diagnostic, PublishReport, Severity};
+use crate::validators::schema_validator;
+use serde::{Deserialize, Serialize};
+use tokio::fs;
+
+/// Optional behaviour for the load commands
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct LoadOptions {
+    /// Inline sections referenced from other documents (`path#section-id` in refTarget)
+    pub transclude: bool,
+    pub max_transclusion_depth: usize,
+    /// Only return sections carrying at least one of these tags (all sections when empty)
+    pub tags: Vec<String>,
+    /// Preferred content language (e.g. `de` or `de-AT`); default content when unset
+    pub language: Option<String>,
+    /// Section types, excluded tags and nesting depth to keep, applied after `tags`
+    pub filter: section_filter::FilterSpec,
+    /// Variables and sections of this document to mask or leave out; nothing by default
+    pub redaction: redaction::RedactionProfile,
+    /// Where the content is going, for variables limited with `resolveIn`; when unset,
+    /// preview for the load commands and assembly for `assemble_context`
+    pub context: Option<ResolutionContext>,
+}
+
+/// Summary of one document in a workspace listing
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct WorkspaceDocument {
+    pub file_path: String,
+    pub meta: MetaData,
+    pub section_count: usize,
+    /// Content hash the parsed document is cached under
+    pub hash: String,
+}
+
+/// Parts of a document that changed after a version, so the frontend can sync without reloading everything
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct DocumentChanges {
+    /// Version to ask about next time
+    pub version: u64,
+    /// The version asked about is older than what the backend tracks (the document
+    /// was reloaded or closed since), so everything is included
+    pub full: bool,
+    /// Added or changed top-level sections, resolved like `load_sections`; all of them when variables changed
+    pub sections: Vec<Section>,
+    pub removed_section_ids: Vec<String>,
+    /// IDs of the top-level sections in document order, when it changed
+    pub section_order: Option<Vec<String>>,
+    pub meta: Option<MetaData>,
+    /// The processed flow graph, when it changed
+    pub flow: Option<FlowGraph>,
+    /// The flow changed by being removed
+    pub flow_removed: bool,
+}
+
+impl Default for LoadOptions {
+    fn default() -> Self {
+        LoadOptions {
+            transclude: false,
+            max_transclusion_depth: transclusion_service::DEFAULT_MAX_TRANSCLUSION_DEPTH,
+            tags: Vec::new(),
+            language: None,
+            filter: section_filter::FilterSpec::default(),
+            redaction: redaction::RedactionProfile::default(),
+            context: None,
+        }
+    }
+}
+
+/// Current version of the document without resolving variables, for editing
+///
+/// Served from the in-memory document store, so unsaved edits are included.
+pub async fn read_context_document(file_path: &str) -> Result<ContextDocument> {
+    document_store::get(file_path).await
+}
+
+/// Read, validate and parse the document from disk, bypassing the document store
+///
+/// Also returns the input quirks (byte order mark, leading whitespace) that were
+/// corrected so they can be reported as warnings.
+pub async fn read_document_from_disk(file_path: &str) -> Result<(ContextDocument, Vec<InputQuirk>)> {
+    let bytes = fs::read(file_path).await?;
+    let (xml_content, quirks) = input_normalizer::decode_input(&bytes)?;
+    let mut doc = parse_context_document(&xml_content)?;
+    if let Some(hooks) = ScriptHooks::for_document(file_path)? {
+        hooks.on_load(&mut doc)?;
+    }
+    Ok((doc, quirks))
+}
+
+/// Validate and parse document XML
+pub fn parse_context_document(xml_content: &str) -> Result<ContextDocument> {
+    // Validate schema before parsing
+    schema_validator::validate_schema(xml_content)?;
+
+    xml_parser::parse_xml(xml_content)
+}
+
+/// Serialize context document to XML, validate it, and write it to disk
+pub async fn save_context_document(file_path: &str, doc: &ContextDocument) -> Result<()> {
+    let xml_content = portable_format::with_line_ending(&xml_serializer::serialize_xml(doc), settings::current_settings().line_endings);
+
+    // Never write a document we would refuse to load
+    schema_validator::validate_schema(&xml_content)?;
+    document_store::ensure_writable(file_path)?;
+
+    fs::write(file_path, xml_content).await?;
+    Ok(())
+}
+
+/// Load and parse context document from XML file, resolved for display in the app
+pub async fn load_context_document(file_path: &str) -> Result<ContextDocument> {
+    load_resolved_document(file_path, ResolutionContext::Preview).await
+}
+
+async fn load_resolved_document(file_path: &str, context: ResolutionContext) -> Result<ContextDocument> {
+    let mut doc = read_context_document(file_path).await?;
+    section_encryption::lock_sections(&mut doc.sections);
+    section_frontmatter::strip_frontmatter(&mut doc.sections);
+
+    // Resolve variables in sections
+    let var_map = variable_map(&doc, context);
+    variable_resolver::resolve_section_tree(&mut doc.sections, &var_map);
+
+    Ok(doc)
+}
+
+/// The document's variables that resolve in `context` over the built-in ones, so a declared `date.today` wins
+fn variable_map(doc: &ContextDocument, context: ResolutionContext) -> HashMap<String, String> {
+    let mut var_map = formatting::builtin_variables(doc);
+    var_map.extend(variable_resolver::build_variable_map_for(&doc.variables, context));
+    var_map
+}
+
+/// Process flow graph by parsing mermaid code and enriching with click actions
+pub async fn process_flow_graph(mut flow: FlowGraph) -> Result<FlowGraph> {
+    // Enrich flow graph with parsed mermaid structure
+    mermaid_parser::enrich_flow_graph(&mut flow)?;
+
+    Ok(flow)
+}
+
+/// Load context document and return sections (synchronously accessible)
+pub async fn load_sections(file_path: &str) -> Result<Vec<Section>> {
+    load_sections_with_options(file_path, &LoadOptions::default()).await
+}
+
+/// Load sections, applying the given load options
+pub async fn load_sections_with_options(file_path: &str, options: &LoadOptions) -> Result<Vec<Section>> {
+    load_sections_with_overrides(file_path, options, &HashMap::new(), ResolutionContext::Preview).await
+}
+
+/// Load sections, resolving variables with `overrides` taking precedence over document values
+///
+/// Variables resolve for `options.context`, else `default_context`. The
+/// document itself is not modified.
+async fn load_sections_with_overrides(
+    file_path: &str,
+    options: &LoadOptions,
+    overrides: &HashMap<String, String>,
+    default_context: ResolutionContext,
+) -> Result<Vec<Section>> {
+    let mut doc = read_context_document(file_path).await?;
+    section_encryption::lock_sections(&mut doc.sections);
+    section_frontmatter::strip_frontmatter(&mut doc.sections);
+    plugins::active_pipeline().run(PluginStage::Load, &mut doc.sections)?;
+    redaction::redact_sections(&mut doc.sections, &options.redaction);
+
+    let mut var_map = variable_map(&doc, options.context.unwrap_or(default_context));
+    var_map.extend(overrides.iter().map(|(k, v)| (k.clone(), v.clone())));
+    redaction::redact_variable_map(&mut var_map, &doc.variables, &options.redaction);
+    variable_resolver::resolve_section_tree(&mut doc.sections, &var_map);
+
+    let sections = tag_index::filter_sections_by_tags(doc.sections, &options.tags);
+    let mut sections = section_filter::filter_sections(sections, &options.filter);
+
+    if let Some(lang) = &options.language {
+        localization::localize_sections(&mut sections, lang);
+    }
+
+    if options.transclude {
+        transclusion_service::transclude_sections(
+            &mut sections,
+            file_path,
+            options.max_transclusion_depth,
+        )
+        .await?;
+    }
+
+    Ok(sections)
+}
+
+/// What changed in the document after `since`, a version returned by an earlier call (0 for everything)
+pub async fn get_changes_since(file_path: &str, since: u64) -> Result<DocumentChanges> {
+    let log = document_store::change_log(file_path).await?;
+    let full = since < log.baseline;
+    let changed = |version: u64| full || version > since;
+
+    let every_section = changed(log.variables);
+    let sections = load_sections(file_path)
+        .await?
+        .into_iter()
+        .filter(|section| every_section || log.sections.get(&section.id).is_some_and(|version| *version > since))
+        .collect();
+    let mut removed_section_ids: Vec<String> = log
+        .removed
+        .iter()
+        .filter(|(_, version)| !full && **version > since)
+        .map(|(id, _)| id.clone())
+        .collect();
+    removed_section_ids.sort();
+
+    let doc = read_context_document(file_path).await?;
+    let (flow, flow_removed) = match doc.flow_graph {
+        Some(flow) if changed(log.flow) => (Some(process_flow_graph(flow).await?), false),
+        None => (None, changed(log.flow)),
+        Some(_) => (None, false),
+    };
+
+    Ok(DocumentChanges {
+        version: log.version,
+        full,
+        sections,
+        removed_section_ids,
+        section_order: changed(log.section_order).then(|| doc.sections.iter().map(|s| s.id.clone()).collect()),
+        meta: changed(log.meta).then_some(doc.meta),
+        flow,
+        flow_removed,
+    })
+}
+
+/// Content hash and length of every section (resolved), to detect which ones changed
+pub async fn get_section_index(file_path: &str) -> Result<Vec<section_index::SectionIndexEntry>> {
+    let doc = load_context_document(file_path).await?;
+    Ok(section_index::build_section_index(&doc.sections))
+}
+
+/// A filtered page of the section index, for list virtualization over large documents
+pub async fn get_section_index_page(file_path: &str, query: &section_index::SectionIndexQuery) -> Result<section_index::SectionIndexPage> {
+    let doc = load_context_document(file_path).await?;
+    Ok(section_index::query_section_index(&doc.sections, query))
+}
+
+/// Near-duplicate paragraphs across the document's resolved sections
+pub async fn find_duplicate_paragraphs(file_path: &str, threshold: f64) -> Result<Vec<duplicate_content::DuplicateParagraphs>> {
+    let doc = load_context_document(file_path).await?;
+    Ok(duplicate_content::find_duplicate_paragraphs(&doc.sections, threshold))
+}
+
+/// refTarget dependencies of every section with their transitive closure, and refTargets
+/// that contradict the order of the flow diagram
+pub async fn get_section_dependencies(file_path: &str) -> Result<section_dependencies::DependencyReport> {
+    let doc = read_context_document(file_path).await?;
+    let flow = match doc.flow_graph.clone() {
+        Some(flow) => Some(process_flow_graph(flow).await?),
+        None => None,
+    };
+    Ok(section_dependencies::section_dependencies(&doc.sections, flow.as_ref()))
+}
+
+/// Assemble the (optionally filtered and transcluded) sections into a single context string
+///
+/// Variable `overrides` supplied at call time win over the document's values,
+/// so one document can be compiled for different users or goals without editing it.
+pub async fn assemble_context(
+    file_path: &str,
+    options: &LoadOptions,
+    overrides: &HashMap<String, String>,
+) -> Result<String> {
+    let mut sections = load_sections_with_overrides(file_path, options, overrides, ResolutionContext::Assembly).await?;
+    plugins::active_pipeline().run(PluginStage::Assembly, &mut sections)?;
+    let context = context_assembly::assemble_sections(&sections);
+
+    match ScriptHooks::for_document(file_path)? {
+        Some(hooks) => hooks.on_assemble(&read_context_document(file_path).await?, context),
+        None => Ok(context),
+    }
+}
+
+/// Frontmatter fields (owner, due date, status, ...) of the sections that have them, keyed by section ID
+///
+/// Loaded and assembled sections have the frontmatter stripped; this is where it can be read.
+pub async fn get_section_frontmatter(file_path: &str) -> Result<BTreeMap<String, BTreeMap<String, String>>> {
+    let doc = read_context_document(file_path).await?;
+    Ok(section_frontmatter::collect_frontmatter(&doc.sections))
+}
+
+/// Load the bibliography entries from the `<references>` block
+pub async fn load_references(file_path: &str) -> Result<Vec<Reference>> {
+    let doc = read_context_document(file_path).await?;
+    Ok(doc.references)
+}
+
+/// Find `[@ref-id]` citations that do not resolve to a reference
+pub async fn validate_citations(file_path: &str) -> Result<Vec<citations::UnresolvedCitation>> {
+    let doc = read_context_document(file_path).await?;
+    Ok(citations::find_unresolved_citations(&doc))
+}
+
+/// List every tag used in the document with usage counts
+pub async fn get_tags(file_path: &str) -> Result<Vec<tag_index::TagUsage>> {
+    let doc = read_context_document(file_path).await?;
+    Ok(tag_index::collect_tags(&doc))
+}
+
+/// Budget usage of every section that declares a `budget`, measured on resolved content
+pub async fn get_budget_report(file_path: &str) -> Result<Vec<budget_report::BudgetUsage>> {
+    let doc = read_context_document(file_path).await?;
+    Ok(budget_report::budget_report(&doc))
+}
+
+/// Load context document and return flow graph (processed asynchronously)
+pub async fn load_flow_graph(file_path: &str) -> Result<Option<FlowGraph>> {
+    let doc = load_context_document(file_path).await?;
+
+    if let Some(flow) = doc.flow_graph {
+        let processed_flow = process_flow_graph(flow).await?;
+        Ok(Some(processed_flow))
+    } else {
+        Ok(None)
+    }
+}
+
+/// Hover-card data linking each flow node to its section heading and preview
+pub async fn get_flow_navigation(file_path: &str) -> Result<Vec<flow_navigation::NodeNavigation>> {
+    let doc = load_context_document(file_path).await?;
+
+    match doc.flow_graph {
+        Some(flow) => {
+            let flow = process_flow_graph(flow).await?;
+            Ok(flow_navigation::build_flow_navigation(
+                &flow,
+                &doc.sections,
+                content_summary::DEFAULT_PREVIEW_LENGTH,
+            ))
+        }
+        None => Ok(vec![]),
+    }
+}
+
+/// Sections relevant to a flow node (its linked sections and their refTarget neighbours), for focus mode
+pub async fn get_sections_for_node(file_path: &str, node_id: &str) -> Result<Vec<flow_navigation::FocusSection>> {
+    let doc = load_context_document(file_path).await?;
+
+    match doc.flow_graph {
+        Some(flow) => {
+            let flow = process_flow_graph(flow).await?;
+            if !flow.parsed_graph.nodes.iter().any(|node| node.id == node_id) {
+                return Err(ContextError::InvalidArgument(format!("Node '{}' is not in the flow diagram", node_id)));
+            }
+            Ok(flow_navigation::sections_for_node(&flow, &doc.sections, node_id))
+        }
+        None => Ok(vec![]),
+    }
+}
+
+/// The flow node a section belongs to in focus mode, if any
+pub async fn get_node_for_section(file_path: &str, section_id: &str) -> Result<Option<GraphNode>> {
+    let doc = load_context_document(file_path).await?;
+
+    match doc.flow_graph {
+        Some(flow) => {
+            let flow = process_flow_graph(flow).await?;
+            Ok(flow_navigation::node_for_section(&flow, &doc.sections, section_id).cloned())
+        }
+        None => Ok(None),
+    }
+}
+
+/// Suggest click actions for flow nodes that are not linked to any section
+pub async fn suggest_click_actions(file_path: &str) -> Result<Vec<click_suggestions::ClickSuggestion>> {
+    let doc = load_context_document(file_path).await?;
+
+    match doc.flow_graph {
+        Some(flow) => {
+            let flow = process_flow_graph(flow).await?;
+            Ok(click_suggestions::suggest_click_actions(&flow, &doc.sections))
+        }
+        None => Ok(vec![]),
+    }
+}
+
+/// Suggest section types for flow nodes that are not linked to a section yet
+pub async fn suggest_node_section_types(file_path: &str) -> Result<Vec<section_type_inference::NodeTypeSuggestion>> {
+    match load_flow_graph(file_path).await? {
+        Some(flow) => Ok(section_type_inference::suggest_node_section_types(&flow)),
+        None => Ok(vec![]),
+    }
+}
+
+/// Write click actions into the document's Mermaid code and return the re-processed flow graph
+pub async fn apply_click_actions(file_path: &str, links: &[click_suggestions::ClickLink]) -> Result<FlowGraph> {
+    let updated = document_store::update(file_path, |doc| {
+        let flow = doc
+            .flow_graph
+            .as_mut()
+            .ok_or_else(|| ContextError::MissingRequiredField("flow".to_string()))?;
+        flow.mermaid_code = click_suggestions::apply_click_actions(&flow.mermaid_code, links);
+        Ok(flow.clone())
+    })
+    .await?;
+    let node_ids: Vec<&str> = links.iter().map(|link| link.node_id.as_str()).collect();
+    change_journal::record(file_path, JournalOperation::ApplyClickActions, Some(node_ids.join(", "))).await;
+
+    process_flow_graph(updated).await
+}
+
+/// Preview `apply_click_actions` as a diff of the in-memory document
+pub async fn preview_click_actions(file_path: &str, links: &[click_suggestions::ClickLink]) -> Result<DryRunPreview> {
+    preview_update(file_path, |doc| {
+        let flow = doc
+            .flow_graph
+            .as_mut()
+            .ok_or_else(|| ContextError::MissingRequiredField("flow".to_string()))?;
+        flow.mermaid_code = click_suggestions::apply_click_actions(&flow.mermaid_code, links);
+        Ok(())
+    })
+    .await
+}
+
+/// Replace the flow diagram (or add a `<flow>`) with a Mermaid flowchart found in pasted text
+///
+/// The flow keeps its ID, title and the layout positions and node metadata
+/// of nodes that are still in the diagram. Stays in memory until saved.
+pub async fn import_mermaid_from_text(file_path: &str, text: &str) -> Result<FlowGraph> {
+    let mermaid_code = mermaid_import::extract_pasted_mermaid(text)?;
+    let updated = document_store::update(file_path, |doc| replace_diagram(doc, mermaid_code)).await?;
+    change_journal::record(file_path, JournalOperation::ImportMermaid, None).await;
+
+    process_flow_graph(updated).await
+}
+
+/// Preview `import_mermaid_from_text` as a diff of the in-memory document
+pub async fn preview_mermaid_import(file_path: &str, text: &str) -> Result<DryRunPreview> {
+    let mermaid_code = mermaid_import::extract_pasted_mermaid(text)?;
+    preview_update(file_path, |doc| replace_diagram(doc, mermaid_code).map(|_| ())).await
+}
+
+/// Replace the flow with a graph from another tool, given as flow JSON or Graphviz DOT
+///
+/// The graph is written out as Mermaid. Positions and node metadata in the
+/// source replace the saved ones.
+pub async fn import_flow(file_path: &str, source: &str, format: GraphFormat) -> Result<FlowGraph> {
+    let imported = graph_import::import_graph(source, format)?;
+    let updated = document_store::update(file_path, |doc| {
+        replace_diagram(doc, imported.to_mermaid())?;
+        let flow = doc.flow_graph.as_mut().expect("replace_diagram creates the flow");
+        if imported.layout.is_some() {
+            flow.layout = imported.layout.clone();
+        }
+        flow.node_metadata.extend(imported.node_metadata.clone());
+        Ok(flow.clone())
+    })
+    .await?;
+    change_journal::record(file_path, JournalOperation::ImportFlow, None).await;
+
+    process_flow_graph(updated).await
+}
+
+fn replace_diagram(doc: &mut ContextDocument, mermaid_code: String) -> Result<FlowGraph> {
+    let node_ids: HashSet<String> = mermaid_parser::parse_mermaid(&mermaid_code)?
+        .nodes
+        .into_iter()
+        .map(|node| node.id)
+        .collect();
+    let flow = doc.flow_graph.get_or_insert_with(|| FlowGraph {
+        id: "flow-1".to_string(),
+        version: "1.0".to_string(),
+        title: None,
+        mermaid_code: String::new(),
+        parsed_graph: GraphStructure { nodes: vec![], edges: vec![], subgraphs: vec![] },
+        node_refs: vec![],
+        layout: None,
+        node_metadata: Default::default(),
+    });
+    flow.mermaid_code = mermaid_code;
+    if let Some(layout) = &mut flow.layout {
+        layout.positions.retain(|id, _| node_ids.contains(id));
+    }
+    flow.node_metadata.retain(|id, _| node_ids.contains(id));
+    Ok(flow.clone())
+}
+
+/// Check Mermaid code regenerated by the canvas against the current diagram without saving it
+pub async fn check_flow_graph(file_path: &str, mermaid_code: &str) -> Result<Vec<Diagnostic>> {
+    let doc = read_context_document(file_path).await?;
+    flow_connectivity::check_flow_update(doc.flow_graph.as_ref().map(|flow| flow.mermaid_code.as_str()), mermaid_code)
+}
+
+/// Replace the flow diagram with Mermaid code regenerated by the canvas
+///
+/// Refuses a diagram without nodes or one that drops the click action of a
+/// previously linked section, unless `force` is set; `check_flow_graph`
+/// lists the problems up front. Keeps layout positions and node metadata
+/// like `import_mermaid_from_text`. Stays in memory until saved.
+pub async fn save_flow_graph(file_path: &str, mermaid_code: &str, force: bool) -> Result<FlowGraph> {
+    let updated = document_store::update(file_path, |doc| {
+        let previous = doc.flow_graph.as_ref().map(|flow| flow.mermaid_code.as_str());
+        let errors: Vec<String> = flow_connectivity::check_flow_update(previous, mermaid_code)?
+            .into_iter()
+            .filter(|diagnostic| diagnostic.severity == Severity::Error)
+            .map(|diagnostic| diagnostic.message)
+            .collect();
+        if !errors.is_empty() && !force {
+            return Err(ContextError::ValidationError(errors.join("; ")));
+        }
+        replace_diagram(doc, mermaid_code.to_string())
+    })
+    .await?;
+    change_journal::record(file_path, JournalOperation::SaveFlowGraph, None).await;
+
+    process_flow_graph(updated).await
+}
+
+/// Turn the flow into a markdown task list, one task per node in topological order
+///
+/// The tasks are appended to `section_id`, or to a new process section
+/// (`section_id` or `process-checklist`) when it does not exist. Running it
+/// again only adds tasks for nodes added to the diagram since. An encrypted
+/// section is refused. Stays in memory until saved; returns the checklist section.
+pub async fn generate_checklist_from_flow(file_path: &str, section_id: Option<&str>) -> Result<Section> {
+    let flow = load_flow_graph(file_path)
+        .await?
+        .ok_or_else(|| ContextError::MissingRequiredField("flow".to_string()))?;
+
+    let section = document_store::update(file_path, |doc| {
+        let existing = section_id.and_then(|id| document_edits::find_section_mut(&mut doc.sections, id));
+        if let Some(section) = existing {
+            document_edits::ensure_not_encrypted(section)?;
+            let tasks = flow_checklist::checklist_tasks(&flow, &section.content);
+            if !tasks.is_empty() {
+                section.content = format!("{}\n\n{}", section.content.trim_end(), tasks.join("\n"));
+            }
+            return Ok(section.clone());
+        }
+
+        let mut taken = HashSet::new();
+        document_edits::collect_section_ids(&doc.sections, &mut taken);
+        let id = section_id
+            .map(str::to_string)
+            .unwrap_or_else(|| section_import::unique_section_id("process-checklist", &taken));
+        let tasks = flow_checklist::checklist_tasks(&flow, "");
+        let section = Section::new(id, "process", format!("# Checklist\n\n{}", tasks.join("\n")));
+        doc.sections.push(section.clone());
+        Ok(section)
+    })
+    .await?;
+    change_journal::record(file_path, JournalOperation::GenerateChecklist, Some(section.id.clone())).await;
+    Ok(section)
+}
+
+/// Load the saved canvas layout of the flow, if any
+pub async fn load_flow_layout(file_path: &str) -> Result<Option<FlowLayout>> {
+    let doc = read_context_document(file_path).await?;
+    Ok(doc.flow_graph.and_then(|flow| flow.layout))
+}
+
+/// Compute an automatic layered layout for the flow graph (not saved)
+pub async fn compute_flow_layout(file_path: &str) -> Result<Option<FlowLayout>> {
+    let doc = read_context_document(file_path).await?;
+
+    match doc.flow_graph {
+        Some(flow) => {
+            let flow = process_flow_graph(flow).await?;
+            let direction = mermaid_parser::parse_direction(&flow.mermaid_code);
+            Ok(Some(auto_layout::compute_layout(&flow.parsed_graph, direction)))
+        }
+        None => Ok(None),
+    }
+}
+
+/// Store the canvas layout on the document's flow
+pub async fn save_flow_layout(file_path: &str, layout: FlowLayout) -> Result<()> {
+    document_store::update(file_path, |doc| {
+        let flow = doc
+            .flow_graph
+            .as_mut()
+            .ok_or_else(|| ContextError::MissingRequiredField("flow".to_string()))?;
+        flow.layout = Some(layout);
+        Ok(())
+    })
+    .await?;
+    change_journal::record(file_path, JournalOperation::SaveFlowLayout, None).await;
+    Ok(())
+}
+
+/// Set a node's owner, status and effort and return the re-processed flow graph
+///
+/// Empty metadata removes the node's entry from `<nodes>`.
+pub async fn set_node_metadata(file_path: &str, node_id: &str, metadata: NodeMetadata) -> Result<FlowGraph> {
+    let updated = document_store::update(file_path, |doc| {
+        let flow = doc
+            .flow_graph
+            .as_mut()
+            .ok_or_else(|| ContextError::MissingRequiredField("flow".to_string()))?;
+        if !mermaid_parser::parse_mermaid(&flow.mermaid_code)?.nodes.iter().any(|node| node.id == node_id) {
+            return Err(ContextError::InvalidArgument(format!("Node '{}' is not in the flow diagram", node_id)));
+        }
+
+        if metadata.is_empty() {
+            flow.node_metadata.remove(node_id);
+        } else {
+            flow.node_metadata.insert(node_id.to_string(), metadata);
+        }
+        Ok(flow.clone())
+    })
+    .await?;
+    change_journal::record(file_path, JournalOperation::SetNodeMetadata, Some(node_id.to_string())).await;
+
+    process_flow_graph(updated).await
+}
+
+/// Walk the flow choosing branches from the document variables, with optional per-call overrides
+pub async fn simulate_flow(
+    file_path: &str,
+    start_node: Option<&str>,
+    overrides: &HashMap<String, String>,
+) -> Result<flow_simulation::SimulationResult> {
+    let doc = read_context_document(file_path).await?;
+    let mut var_map = variable_map(&doc, ResolutionContext::Preview);
+    var_map.extend(overrides.iter().map(|(k, v)| (k.clone(), v.clone())));
+
+    let flow = doc
+        .flow_graph
+        .ok_or_else(|| ContextError::MissingRequiredField("flow".to_string()))?;
+    let flow = process_flow_graph(flow).await?;
+
+    flow_simulation::simulate_flow(&flow, &var_map, start_node)
+}
+
+/// Effort estimates summed along each path of the flow and per subgraph, for rough project planning
+pub async fn get_effort_rollup(file_path: &str) -> Result<effort_rollup::EffortRollup> {
+    let doc = read_context_document(file_path).await?;
+    let flow = doc
+        .flow_graph
+        .ok_or_else(|| ContextError::MissingRequiredField("flow".to_string()))?;
+    let flow = process_flow_graph(flow).await?;
+
+    Ok(effort_rollup::effort_rollup(&flow, &doc.sections))
+}
+
+/// Get metadata from context document
+pub async fn load_metadata(file_path: &str) -> Result<MetaData> {
+    let doc = load_context_document(file_path).await?;
+    Ok(doc.meta)
+}
+
+/// Export one section with variables resolved and the filter and redaction profile applied
+///
+/// A section the filter leaves out is reported as not found.
+pub async fn export_section(
+    file_path: &str,
+    section_id: &str,
+    format: ExportFormat,
+    filter: &section_filter::FilterSpec,
+    redaction: &redaction::RedactionProfile,
+) -> Result<String> {
+    let doc = load_filtered_document(file_path, filter, redaction).await?;
+
+    let section = document_edits::find_section(&doc.sections, section_id)
+        .ok_or_else(|| ContextError::SectionNotFound(section_id.to_string()))?;
+
+    let exported = section_exporter::export_section(section, format);
+    usage_metrics::record_export("section").await;
+    Ok(exported)
+}
+
+/// The document with sections filtered and the redaction profile applied, then variables resolved
+async fn load_filtered_document(
+    file_path: &str,
+    filter: &section_filter::FilterSpec,
+    redaction: &redaction::RedactionProfile,
+) -> Result<ContextDocument> {
+    let mut doc = read_context_document(file_path).await?;
+    section_encryption::lock_sections(&mut doc.sections);
+    section_frontmatter::strip_frontmatter(&mut doc.sections);
+    doc.sections = section_filter::filter_sections(std::mem::take(&mut doc.sections), filter);
+    redaction::redact_document(&mut doc, redaction);
+
+    let var_map = variable_map(&doc, ResolutionContext::Export);
+    variable_resolver::resolve_section_tree(&mut doc.sections, &var_map);
+    Ok(doc)
+}
+
+/// The filtered, redacted and resolved document with the flow graph parsed, as whole-document exports need it
+async fn load_document_for_export(
+    file_path: &str,
+    filter: &section_filter::FilterSpec,
+    redaction: &redaction::RedactionProfile,
+) -> Result<ContextDocument> {
+    let mut doc = load_filtered_document(file_path, filter, redaction).await?;
+    if let Some(flow) = doc.flow_graph.take() {
+        doc.flow_graph = Some(process_flow_graph(flow).await?);
+    }
+    Ok(doc)
+}
+
+/// Export the whole document as paginated HTML for printing, dated today in the document's locale
+pub async fn export_print_html(
+    file_path: &str,
+    page_size: PageSize,
+    filter: &section_filter::FilterSpec,
+    redaction: &redaction::RedactionProfile,
+) -> Result<String> {
+    let doc = load_document_for_export(file_path, filter, redaction).await?;
+
+    let options = PrintOptions {
+        page_size,
+        date: formatting::today(&doc),
+        include_flow: true,
+    };
+    let html = print_exporter::export_print_html(&doc, &options);
+    usage_metrics::record_export("print_html").await;
+    Ok(html)
+}
+
+/// Export the document as one standalone HTML page with the flow embedded for mermaid.js, dated today
+pub async fn export_html(file_path: &str, filter: &section_filter::FilterSpec, redaction: &redaction::RedactionProfile) -> Result<String> {
+    let doc = load_document_for_export(file_path, filter, redaction).await?;
+
+    let options = HtmlOptions {
+        date: formatting::today(&doc),
+        include_flow: true,
+    };
+    let html = html_exporter::export_html(&doc, &options);
+    usage_metrics::record_export("html").await;
+    Ok(html)
+}
+
+/// Strip the document to its skeleton and add it to the template library in `templates_dir`
+///
+/// A template saved earlier under the same name is replaced.
+pub async fn save_as_template(file_path: &str, templates_dir: &Path, template_name: &str) -> Result<TemplateInfo> {
+    let doc = read_context_document(file_path).await?;
+    let mut template = template_extraction::extract_template(&doc, template_name);
+    template.meta.created = chrono::Local::now().format("%Y-%m-%d").to_string();
+    template_library::save_template(templates_dir, &template).await
+}
+
+/// Create a new document at `file_path` and return it
+///
+/// With `template_name` the document starts from that template in
+/// `templates_dir`, keeping its sections, variables and flow. Otherwise it has
+/// an intent, evaluation, process and alternatives section filled from the
+/// section templates, plus, with `include_flow`, a flow linking them. An
+/// empty `author` is taken from the `author` setting. An existing file is
+/// never overwritten.
+pub async fn create_document(
+    file_path: &str,
+    title: &str,
+    author: &str,
+    template_name: Option<&str>,
+    templates_dir: &Path,
+    include_flow: bool,
+) -> Result<ContextDocument> {
+    if fs::try_exists(file_path).await? {
+        return Err(ContextError::InvalidArgument(format!("{} already exists", file_path)));
+    }
+    let doc = new_document(title, author, template_name, templates_dir, include_flow).await?;
+
+    if let Some(dir) = Path::new(file_path).parent() {
+        fs::create_dir_all(dir).await?;
+    }
+    save_context_document(file_path, &doc).await?;
+    change_journal::record(file_path, JournalOperation::CreateDocument, None).await;
+    Ok(doc)
+}
+
+/// Create a new document in memory only and return its scratch handle
+///
+/// The document starts as with `create_document`. Every command taking a
+/// file path accepts the handle; edits stay in memory, and commands that
+/// save as they edit leave the changes unsaved. Give the document a file
+/// with `save_document_as`.
+pub async fn create_scratch_document(
+    title: &str,
+    author: &str,
+    template_name: Option<&str>,
+    templates_dir: &Path,
+    include_flow: bool,
+) -> Result<String> {
+    let doc = new_document(title, author, template_name, templates_dir, include_flow).await?;
+    Ok(document_store::create_scratch(doc))
+}
+
+async fn new_document(title: &str, author: &str, template_name: Option<&str>, templates_dir: &Path, include_flow: bool) -> Result<ContextDocument> {
+    if title.trim().is_empty() {
+        return Err(ContextError::MissingRequiredField("title".to_string()));
+    }
+    let settings = settings::current_settings();
+    let author = if author.trim().is_empty() { settings.author.name.trim() } else { author };
+    let created = chrono::Local::now().format("%Y-%m-%d").to_string();
+    match template_name {
+        Some(template_name) => {
+            let template = fs::read_to_string(template_library::template_path(templates_dir, template_name)?).await?;
+            let mut doc = parse_context_document(&template)?;
+            doc.meta.title = title.to_string();
+            doc.meta.author = author.to_string();
+            doc.meta.created = created;
+            Ok(doc)
+        }
+        None => section_templates::document_skeleton(title, author, &created, &settings.section_templates, include_flow),
+    }
+}
+
+/// Export the sections in the order the flow graph reads rather than document order
+pub async fn export_reading_order(
+    file_path: &str,
+    options: &ReadingOrderOptions,
+    filter: &section_filter::FilterSpec,
+    redaction: &redaction::RedactionProfile,
+) -> Result<String> {
+    let doc = load_document_for_export(file_path, filter, redaction).await?;
+    let exported = reading_order_exporter::export_reading_order(&doc, options);
+    usage_metrics::record_export("reading_order").await;
+    Ok(exported)
+}
+
+/// Export the flow graph as an Excalidraw scene (`.excalidraw` JSON)
+pub async fn export_excalidraw(file_path: &str) -> Result<String> {
+    let doc = load_document_for_export(file_path, &Default::default(), &Default::default()).await?;
+    let flow = doc
+        .flow_graph
+        .ok_or_else(|| ContextError::InvalidArgument(format!("{} has no flow graph", file_path)))?;
+
+    let json = serde_json::to_string_pretty(&excalidraw_exporter::export_excalidraw(&flow))
+        .map_err(|e| ContextError::SerializationError(e.to_string()))?;
+    usage_metrics::record_export("excalidraw").await;
+    Ok(json)
+}
+
+/// Export the enriched flow graph as standalone JSON (see `flow-graph.schema.json`) for analysis tools
+pub async fn export_flow_json(file_path: &str) -> Result<String> {
+    let doc = load_document_for_export(file_path, &Default::default(), &Default::default()).await?;
+    let flow = doc
+        .flow_graph
+        .ok_or_else(|| ContextError::InvalidArgument(format!("{} has no flow graph", file_path)))?;
+
+    let json = serde_json::to_string_pretty(&flow_json_exporter::export_flow_json(&flow))
+        .map_err(|e| ContextError::SerializationError(e.to_string()))?;
+    usage_metrics::record_export("flow_json").await;
+    Ok(json)
+}
+
+/// Export the sections, headings, flow nodes and links of the document as JSON, for audits and publishing pipelines
+pub async fn export_link_map(file_path: &str) -> Result<String> {
+    let doc = load_document_for_export(file_path, &Default::default(), &Default::default()).await?;
+    let json = serde_json::to_string_pretty(&link_map_exporter::export_link_map(&doc)).map_err(|e| ContextError::SerializationError(e.to_string()))?;
+    usage_metrics::record_export("link_map").await;
+    Ok(json)
+}
+
+/// Export the document as JSON, so other tools can read it without an XML parser
+///
+/// Content is raw, with `${...}` placeholders, as in the XML file; the flow
+/// carries its parsed nodes and edges. Unsaved edits are included.
+pub async fn export_json(file_path: &str) -> Result<String> {
+    let mut doc = read_context_document(file_path).await?;
+    if let Some(flow) = doc.flow_graph.take() {
+        doc.flow_graph = Some(process_flow_graph(flow).await?);
+    }
+    let json = json_serializer::to_json(&doc)?;
+    usage_metrics::record_export("json").await;
+    Ok(json)
+}
+
+/// Replace the document with one given as JSON, in the layout `export_json` writes
+///
+/// The document is refused when it would not pass schema validation as XML.
+/// It replaces the in-memory copy as an unsaved edit and is returned for
+/// review; save it with `save_document` or discard it with `close_document`.
+pub async fn import_json(file_path: &str, json: &str) -> Result<ContextDocument> {
+    let doc = json_serializer::from_json(json)?;
+    schema_validator::validate_schema(&xml_serializer::serialize_xml(&doc))?;
+
+    document_store::replace(file_path, doc.clone())?;
+    change_journal::record(file_path, JournalOperation::ImportJson, None).await;
+    Ok(doc)
+}
+
+/// Compare two documents, or two versions of one, for a side-by-side review
+///
+/// Both are read as stored, with unsaved edits and `${...}` placeholders.
+pub async fn compare_documents(path_a: &str, path_b: &str) -> Result<DocumentComparison> {
+    let before = read_context_document(path_a).await?;
+    let after = read_context_document(path_b).await?;
+    Ok(document_compare::compare_documents(&before, &after))
+}
+
+/// Export dated variables and milestones as an iCalendar file
+pub async fn export_calendar(file_path: &str) -> Result<String> {
+    let doc = load_resolved_document(file_path, ResolutionContext::Export).await?;
+    let events = calendar_events::collect_calendar_events(&doc);
+
+    // Event UIDs stay the same across exports of this document
+    let namespace = &binary_cache::content_hash(file_path.as_bytes())[..12];
+    let timestamp = chrono::Utc::now().format("%Y%m%dT%H%M%SZ").to_string();
+    let ics = ics_exporter::export_ics(&doc.meta.title, &events, namespace, &timestamp);
+    usage_metrics::record_export("calendar").await;
+    Ok(ics)
+}
+
+/// Export the whole document as a Word file at `destination`
+pub async fn export_docx(
+    file_path: &str,
+    destination: &str,
+    filter: &section_filter::FilterSpec,
+    redaction: &redaction::RedactionProfile,
+) -> Result<()> {
+    let doc = load_document_for_export(file_path, filter, redaction).await?;
+    let bytes = tokio::task::spawn_blocking(move || docx_exporter::export_docx(&doc))
+        .await
+        .map_err(|e| ContextError::AsyncError(e.to_string()))??;
+    fs::write(destination, bytes).await?;
+    usage_metrics::record_export("docx").await;
+    Ok(())
+}
+
+/// Split a section's raw (unresolved) content into `---`-separated blocks
+pub async fn get_section_blocks(file_path: &str, section_id: &str) -> Result<Vec<content_blocks::ContentBlock>> {
+    let doc = read_context_document(file_path).await?;
+
+    let section = document_edits::find_section(&doc.sections, section_id)
+        .ok_or_else(|| ContextError::SectionNotFound(section_id.to_string()))?;
+
+    Ok(content_blocks::split_blocks(&section.content))
+}
+
+/// Replace one block of a section's content; returns the section's updated blocks
+///
+/// Encrypted sections are refused.
+pub async fn update_section_block(
+    file_path: &str,
+    section_id: &str,
+    index: usize,
+    content: &str,
+) -> Result<Vec<content_blocks::ContentBlock>> {
+    let blocks = document_store::update(file_path, |doc| {
+        let section = document_edits::find_section_mut(&mut doc.sections, section_id)
+            .ok_or_else(|| ContextError::SectionNotFound(section_id.to_string()))?;
+        document_edits::ensure_not_encrypted(section)?;
+        section.content = content_blocks::replace_block(&section.content, index, content)?;
+        Ok(content_blocks::split_blocks(&section.content))
+    })
+    .await?;
+    change_journal::record(file_path, JournalOperation::UpdateSectionBlock, Some(section_id.to_string())).await;
+    Ok(blocks)
+}
+
+/// Insert a snippet into a section, resolving its placeholders at insert time
+///
+/// Placeholders take the document's variable values, with `overrides` winning.
+/// Encrypted sections are refused. Returns the section's new (raw) content.
+pub async fn insert_snippet(
+    file_path: &str,
+    section_id: &str,
+    snippet: &Snippet,
+    overrides: &HashMap<String, String>,
+    position: Option<usize>,
+) -> Result<String> {
+    let content = document_store::update(file_path, |doc| {
+        let mut variables = variable_map(doc, ResolutionContext::Preview);
+        variables.extend(overrides.iter().map(|(k, v)| (k.clone(), v.clone())));
+        let section = document_edits::find_section_mut(&mut doc.sections, section_id)
+            .ok_or_else(|| ContextError::SectionNotFound(section_id.to_string()))?;
+        document_edits::ensure_not_encrypted(section)?;
+        section.content = snippets::insert_snippet(&section.content, snippet, &variables, position)?;
+        Ok(section.content.clone())
+    })
+    .await?;
+    change_journal::record(file_path, JournalOperation::InsertSnippet, Some(section_id.to_string())).await;
+    Ok(content)
+}
+
+/// Copy sections from the source document into the target document
+pub async fn import_sections(
+    target_path: &str,
+    source_path: &str,
+    section_ids: &[String],
+    carry_variables: bool,
+) -> Result<section_import::ImportResult> {
+    let source = read_context_document(source_path).await?;
+
+    let result = document_store::update(target_path, |target| {
+        section_import::import_sections(target, &source, section_ids, carry_variables)
+    })
+    .await?;
+    let new_ids: Vec<&str> = result.imported.iter().map(|section| section.new_id.as_str()).collect();
+    change_journal::record(target_path, JournalOperation::ImportSections, Some(new_ids.join(", "))).await;
+    Ok(result)
+}
+
+/// Break a section into several at headings of the given level; returns the new section IDs
+pub async fn split_section(file_path: &str, section_id: &str, heading_level: usize) -> Result<Vec<String>> {
+    let new_ids = document_store::update(file_path, |doc| section_split::split_section(doc, section_id, heading_level)).await?;
+    change_journal::record(file_path, JournalOperation::SplitSection, Some(section_id.to_string())).await;
+    Ok(new_ids)
+}
+
+/// Encrypt a section's content with a passphrase, leaving the rest of the document readable
+///
+/// Loaded and exported sections show a placeholder in place of the ciphertext.
+pub async fn encrypt_section(file_path: &str, section_id: &str, passphrase: &str) -> Result<()> {
+    document_store::update(file_path, |doc| section_encryption::encrypt_section(doc, section_id, passphrase)).await?;
+    change_journal::record(file_path, JournalOperation::EncryptSection, Some(section_id.to_string())).await;
+    Ok(())
+}
+
+/// The decrypted section for viewing; the document stays encrypted
+pub async fn unlock_section(file_path: &str, section_id: &str, passphrase: &str) -> Result<Section> {
+    let doc = read_context_document(file_path).await?;
+    section_encryption::unlock_section(&doc, section_id, passphrase)
+}
+
+/// Store an encrypted section's content as plain text again
+pub async fn decrypt_section(file_path: &str, section_id: &str, passphrase: &str) -> Result<()> {
+    document_store::update(file_path, |doc| section_encryption::decrypt_section(doc, section_id, passphrase)).await?;
+    change_journal::record(file_path, JournalOperation::DecryptSection, Some(section_id.to_string())).await;
+    Ok(())
+}
+
+/// Merge sections into the first of `ids`, retargeting refTargets and click actions at removed IDs
+pub async fn merge_sections(file_path: &str, ids: &[String], separator: Option<&str>) -> Result<()> {
+    let separator = separator.unwrap_or(section_merge::DEFAULT_MERGE_SEPARATOR);
+    document_store::update(file_path, |doc| section_merge::merge_sections(doc, ids, separator)).await?;
+    change_journal::record(file_path, JournalOperation::MergeSections, Some(ids.join(", "))).await;
+    Ok(())
+}
+
+/// Preview `merge_sections` as a diff of the in-memory document
+pub async fn preview_merge_sections(file_path: &str, ids: &[String], separator: Option<&str>) -> Result<DryRunPreview> {
+    let separator = separator.unwrap_or(section_merge::DEFAULT_MERGE_SEPARATOR);
+    preview_update(file_path, |doc| section_merge::merge_sections(doc, ids, separator)).await
+}
+
+/// Sync-service conflict copies (Dropbox, OneDrive, ...) next to the document, newest first
+pub async fn list_conflict_copies(file_path: &str) -> Result<Vec<ConflictCopy>> {
+    conflict_copies::find_conflict_copies(file_path).await
+}
+
+/// Merge a conflict copy into the current document without applying it
+///
+/// `base_path` names a common ancestor (e.g. a backup) for a true three-way
+/// merge; without one every differing part is reported as a conflict.
+pub async fn preview_conflict_merge(
+    file_path: &str,
+    copy_path: &str,
+    base_path: Option<&str>,
+    resolutions: &HashMap<String, MergeSide>,
+) -> Result<MergeResult> {
+    let ours = read_context_document(file_path).await?;
+    let (theirs, _) = read_document_from_disk(copy_path).await?;
+    let base = match base_path {
+        Some(base_path) => Some(read_document_from_disk(base_path).await?.0),
+        None => None,
+    };
+    Ok(document_merge::merge_documents(base.as_ref(), &ours, &theirs, resolutions))
+}
+
+/// Merge a conflict copy into the document as an unsaved edit, resolving conflicts per `resolutions`
+pub async fn merge_conflict_copy(
+    file_path: &str,
+    copy_path: &str,
+    base_path: Option<&str>,
+    resolutions: &HashMap<String, MergeSide>,
+) -> Result<MergeResult> {
+    let result = preview_conflict_merge(file_path, copy_path, base_path, resolutions).await?;
+    document_store::update(file_path, |doc| {
+        *doc = result.document.clone();
+        Ok(())
+    })
+    .await?;
+    change_journal::record(file_path, JournalOperation::MergeConflictCopy, Some(copy_path.to_string())).await;
+    Ok(result)
+}
+
+/// Preview `merge_conflict_copy` as a diff of the in-memory document
+pub async fn preview_merge_conflict_copy(
+    file_path: &str,
+    copy_path: &str,
+    base_path: Option<&str>,
+    resolutions: &HashMap<String, MergeSide>,
+) -> Result<DryRunPreview> {
+    let result = preview_conflict_merge(file_path, copy_path, base_path, resolutions).await?;
+    preview_update(file_path, |doc| {
+        *doc = result.document;
+        Ok(())
+    })
+    .await
+}
+
+/// Apply a batch of edits as one transaction and save the document
+///
+/// Either every edit applies and the document is written once, or the
+/// in-memory document is left as it was and nothing is written. The batch is
+/// rejected up front when the edited document would fail schema validation.
+/// Sections created blank start from the template of their type (see `section_templates`).
+pub async fn apply_edits(file_path: &str, edits: &[document_edits::DocumentEdit]) -> Result<ContextDocument> {
+    let previous = document_store::get(file_path).await?;
+    let edited = document_store::update(file_path, |doc| {
+        let edits = section_templates::with_section_templates(&doc.meta, &settings::current_settings().section_templates, edits);
+        document_edits::apply_edits(doc, &edits)?;
+        schema_validator::validate_schema(&xml_serializer::serialize_xml(doc))?;
+        Ok(doc.clone())
+    })
+    .await?;
+
+    if let Err(e) = write_document(file_path).await {
+        document_store::replace(file_path, previous)?;
+        return Err(e);
+    }
+    let descriptions: Vec<String> = edits.iter().map(|edit| edit.describe()).collect();
+    change_journal::record(file_path, JournalOperation::ApplyEdits, Some(descriptions.join(", "))).await;
+    record_daily_stats(file_path).await;
+    Ok(edited)
+}
+
+/// Replace one section, found by ID at any depth, and save the document
+///
+/// Only that section changes; its children and the rest of the document stay
+/// as stored, so a frontend that has loaded part of the document can save its
+/// edits. `section` carries raw content, with `${...}` placeholders and
+/// frontmatter, not the resolved content `load_sections` returns. On failure
+/// nothing is written and the in-memory document is left as it was.
+pub async fn update_section(file_path: &str, section: &Section) -> Result<Section> {
+    let previous = document_store::get(file_path).await?;
+    let updated = document_store::update(file_path, |doc| {
+        let updated = document_edits::replace_section(doc, section)?;
+        schema_validator::validate_schema(&xml_serializer::serialize_xml(doc))?;
+        Ok(updated)
+    })
+    .await?;
+
+    if let Err(e) = write_document(file_path).await {
+        document_store::replace(file_path, previous)?;
+        return Err(e);
+    }
+    change_journal::record(file_path, JournalOperation::UpdateSection, Some(section.id.clone())).await;
+    record_daily_stats(file_path).await;
+    Ok(updated)
+}
+
+/// Preview `update_section` as a diff against the file on disk
+pub async fn preview_update_section(file_path: &str, section: &Section) -> Result<DryRunPreview> {
+    let mut doc = document_store::get(file_path).await?;
+    document_edits::replace_section(&mut doc, section)?;
+    dry_run::preview(&disk_xml(file_path).await, &doc)
+}
+
+/// Preview `apply_edits` as a diff against the file on disk, which is what it would write
+pub async fn preview_edits(file_path: &str, edits: &[document_edits::DocumentEdit]) -> Result<DryRunPreview> {
+    let mut doc = document_store::get(file_path).await?;
+    let edits = section_templates::with_section_templates(&doc.meta, &settings::current_settings().section_templates, edits);
+    document_edits::apply_edits(&mut doc, &edits)?;
+    dry_run::preview(&disk_xml(file_path).await, &doc)
+}
+
+/// Render a "fill these in" sheet of the document's variables with their descriptions and current values
+pub async fn get_variable_sheet(file_path: &str, format: variable_sheet::SheetFormat) -> Result<String> {
+    let doc = read_context_document(file_path).await?;
+    variable_sheet::render_variable_sheet(&variable_sheet::build_variable_sheet(&doc), format)
+}
+
+/// Set the variables from a filled-in sheet (JSON or markdown table) and save once
+///
+/// Blank values are skipped, so a partly filled sheet leaves the other variables as they are.
+pub async fn apply_variable_sheet(file_path: &str, sheet: &str) -> Result<ContextDocument> {
+    let edits: Vec<document_edits::DocumentEdit> = variable_sheet::parse_variable_sheet(sheet)?
+        .into_iter()
+        .filter(|entry| !entry.value.trim().is_empty())
+        .map(|entry| document_edits::DocumentEdit::SetVariable { name: entry.name, value: entry.value })
+        .collect();
+    apply_edits(file_path, &edits).await
+}
+
+/// Replace the document metadata (including custom fields)
+pub async fn save_metadata(file_path: &str, meta: MetaData) -> Result<()> {
+    document_store::update(file_path, |doc| {
+        doc.meta = meta;
+        Ok(())
+    })
+    .await?;
+    change_journal::record(file_path, JournalOperation::SaveMetadata, None).await;
+    Ok(())
+}
+
+/// Write the in-memory document, including all unsaved edits, to disk
+///
+/// A project `on_save` hook runs on unsaved edits first and can amend or reject them.
+pub async fn save_document(file_path: &str) -> Result<()> {
+    if document_store::is_scratch(file_path) {
+        return Err(ContextError::InvalidArgument(format!("{} has no file yet; save it with save_document_as", file_path)));
+    }
+    write_document(file_path).await?;
+    change_journal::record(file_path, JournalOperation::Save, None).await;
+    record_daily_stats(file_path).await;
+    Ok(())
+}
+
+/// Write the document to `new_path` and keep editing it there
+///
+/// This is how a scratch document gets its file. For a document that already
+/// has one, the old file stays as last saved. Unsaved edits to a document
+/// open at `new_path` are not discarded; close it first.
+pub async fn save_document_as(file_path: &str, new_path: &str) -> Result<()> {
+    if document_store::is_scratch(new_path) {
+        return Err(ContextError::InvalidArgument(format!("{} is not a file path", new_path)));
+    }
+    document_store::get(file_path).await?;
+    document_store::rebind(file_path, new_path)?;
+    if let Err(e) = save_document(new_path).await {
+        document_store::rebind(new_path, file_path)?;
+        return Err(e);
+    }
+    Ok(())
+}
+
+/// Update today's entry in the stats history when the `statsHistory` setting is on
+///
+/// Runs after a successful save, so failures are reported on stderr only.
+async fn record_daily_stats(file_path: &str) {
+    if !settings::current_settings().stats_history || document_store::is_scratch(file_path) {
+        return;
+    }
+    let result = async {
+        let doc = load_context_document(file_path).await?;
+        let date = chrono::Local::now().format("%Y-%m-%d").to_string();
+        stats_history::record_daily_stats(file_path, &date, document_stats::compute_stats(&doc.sections)).await
+    }
+    .await;
+    if let Err(e) = result {
+        eprintln!("Failed to record stats history for {}: {}", file_path, e);
+    }
+}
+
+async fn write_document(file_path: &str) -> Result<()> {
+    // Scratch documents have nowhere to go until `save_document_as`
+    if document_store::is_scratch(file_path) {
+        return Ok(());
+    }
+    if let Some(hooks) = ScriptHooks::for_document(file_path)? {
+        if hooks.defines("on_save") && is_document_dirty(file_path) {
+            document_store::update(file_path, |doc| hooks.on_save(doc)).await?;
+        }
+    }
+    if settings::current_settings().status_sync && is_document_dirty(file_path) {
+        let saved = saved_document(file_path).await;
+        document_store::update(file_path, |doc| Ok(status_sync::sync_status(doc, saved.as_ref()))).await?;
+    }
+    if portable_format::normalize_document(&mut document_store::get(file_path).await?) {
+        document_store::update(file_path, |doc| Ok(portable_format::normalize_document(doc))).await?;
+    }
+    let edited = if settings::current_settings().section_activity {
+        section_activity::edited_sections(saved_document(file_path).await.as_ref(), &document_store::get(file_path).await?)
+    } else {
+        Vec::new()
+    };
+    if let Err(e) = backup_service::backup_before_save(file_path).await {
+        eprintln!("Failed to back up {} before saving: {}", file_path, e);
+    }
+    document_store::save(file_path).await?;
+    usage_metrics::record_save().await;
+    if let Err(e) = section_activity::record_edits(file_path, &edited).await {
+        eprintln!("Failed to record section activity for {}: {}", file_path, e);
+    }
+    Ok(())
+}
+
+/// The document as last written to disk, if it parses
+async fn saved_document(file_path: &str) -> Option<ContextDocument> {
+    xml_parser::parse_xml(&disk_xml(file_path).await).ok()
+}
+
+/// Preview `save_document`: what would be written, as a diff against the file on disk
+///
+/// The project `on_save` hook, status sync and line ending and path normalization run on a
+/// copy, so their amendments are included.
+pub async fn preview_save(file_path: &str) -> Result<DryRunPreview> {
+    let mut doc = document_store::get(file_path).await?;
+    if let Some(hooks) = ScriptHooks::for_document(file_path)? {
+        if hooks.defines("on_save") && is_document_dirty(file_path) {
+            hooks.on_save(&mut doc)?;
+        }
+    }
+    if settings::current_settings().status_sync && is_document_dirty(file_path) {
+        status_sync::sync_status(&mut doc, saved_document(file_path).await.as_ref());
+    }
+    portable_format::normalize_document(&mut doc);
+    dry_run::preview(&disk_xml(file_path).await, &doc)
+}
+
+/// Current file contents, or nothing for a document not yet written
+async fn disk_xml(file_path: &str) -> String {
+    fs::read_to_string(file_path).await.unwrap_or_default()
+}
+
+/// Run an edit on a copy of the in-memory document and diff the result
+async fn preview_update(file_path: &str, edit: impl FnOnce(&mut ContextDocument) -> Result<()>) -> Result<DryRunPreview> {
+    let before = document_store::get(file_path).await?;
+    let mut after = before.clone();
+    edit(&mut after)?;
+    dry_run::preview(&xml_serializer::serialize_xml(&before), &after)
+}
+
+/// Drop the in-memory document; returns whether unsaved edits were discarded
+pub fn close_document(file_path: &str) -> bool {
+    document_store::close(file_path)
+}
+
+/// Open the document and return the ID that stands for it in place of its path
+pub async fn open_document(file_path: &str) -> Result<String> {
+    document_store::open(file_path).await
+}
+
+/// Whether the document has edits that have not been saved
+pub fn is_document_dirty(file_path: &str) -> bool {
+    document_store::is_dirty(file_path)
+}
+
+/// Run every validator in strict mode and report whether the document is ready to publish
+///
+/// Covers the current in-memory version, so unsaved edits are checked too. A
+/// file that fails to load is reported as not ready rather than as an error.
+pub async fn publish_check(file_path: &str) -> Result<PublishReport> {
+    let (xml_content, doc) = current_xml(file_path).await?;
+    let fixable = auto_fix::find_fixable(&xml_content);
+
+    let doc = match doc {
+        Ok(doc) => doc,
+        Err(e) => {
+            let mut diagnostics = vec![Diagnostic::new("schema", "schema", "document", Severity::Error, e.to_string())];
+            diagnostics.extend(fixable);
+            let report = PublishReport::from_diagnostics(diagnostics);
+            usage_metrics::record_validation_errors(report.error_count).await;
+            return Ok(report);
+        }
+    };
+
+    let mut diagnostics = Vec::new();
+
+    if let Err(e) = schema_validator::validate_schema(&xml_content) {
+        diagnostics.push(Diagnostic::new("schema", "schema", "document", Severity::Error, e.to_string()));
+    }
+    for quirk in document_store::load_warnings(file_path).await? {
+        diagnostics.push(Diagnostic::new("schema", "input-quirk", quirk.code(), Severity::Warning, quirk.message()));
+    }
+
+    diagnostics.extend(publish_check::check_document(&doc));
+
+    // Cross-document references, from sections at any depth, must point at files that exist
+    let base_dir = Path::new(file_path).parent().unwrap_or(Path::new("."));
+    for section in flow_navigation::flatten(&doc.sections) {
+        for reference in section.cross_document_refs() {
+            if !fs::try_exists(base_dir.join(portable_format::platform_path(&reference.path))).await.unwrap_or(false) {
+                diagnostics.push(
+                    Diagnostic::new(
+                        "refs",
+                        "missing-document",
+                        &format!("{}:{}", section.id, reference.path),
+                        Severity::Error,
+                        format!("Section '{}' references missing document '{}'", section.id, reference.path),
+                    )
+                    .in_section(&section.id),
+                );
+            }
+        }
+    }
+
+    // Problems the auto-fixer can repair replace their plain counterparts
+    for fix in fixable {
+        match diagnostics.iter_mut().find(|d| d.id == fix.id) {
+            Some(existing) => *existing = fix,
+            None => diagnostics.push(fix),
+        }
+    }
+
+    let report = PublishReport::from_diagnostics(diagnostics);
+    usage_metrics::record_validation_errors(report.error_count).await;
+    Ok(report)
+}
+
+/// Apply deterministic repairs for the selected fixable diagnostics
+///
+/// Works on the raw file when it does not load, so documents broken by the
+/// fixable problems can be recovered. The repaired document replaces the
+/// in-memory copy as an unsaved edit and is returned for review; save it with
+/// `save_document` or discard it with `close_document`.
+pub async fn apply_fixes(file_path: &str, diagnostic_ids: &[String]) -> Result<ContextDocument> {
+    let (xml_content, _) = current_xml(file_path).await?;
+    let fixed = auto_fix::apply_fixes(&xml_content, diagnostic_ids)?;
+    let doc = parse_context_document(&fixed)?;
+
+    document_store::replace(file_path, doc.clone())?;
+    change_journal::record(file_path, JournalOperation::ApplyFixes, Some(diagnostic_ids.join(", "))).await;
+    Ok(doc)
+}
+
+/// Open a document even when it does not parse, salvaging what a tolerant scan can recover
+///
+/// A document that loads normally is returned as is. Otherwise the raw
+/// section content, variables, title and diagram are recovered with default
+/// metadata and flagged as `recovered`. The recovered document replaces the
+/// in-memory copy as an unsaved edit; save it with `save_document` or
+/// discard it with `close_document`.
+pub async fn open_document_safe_mode(file_path: &str) -> Result<SalvagedDocument> {
+    let parse_error = match read_context_document(file_path).await {
+        Ok(document) => {
+            return Ok(SalvagedDocument { document, recovered: false, parse_error: None, notes: vec![] });
+        }
+        Err(e) => e.to_string(),
+    };
+
+    let bytes = fs::read(file_path).await?;
+    let xml_content = match input_normalizer::decode_input(&bytes) {
+        Ok((xml_content, _)) => xml_content,
+        Err(_) => String::from_utf8_lossy(&bytes).into_owned(),
+    };
+    let (document, notes) = salvage_parser::salvage_document(&xml_content);
+
+    document_store::replace(file_path, document.clone())?;
+    change_journal::record(file_path, JournalOperation::RecoverDocument, None).await;
+    Ok(SalvagedDocument { document, recovered: true, parse_error: Some(parse_error), notes })
+}
+
+/// Preview `apply_fixes` as a diff of the current XML
+pub async fn preview_fixes(file_path: &str, diagnostic_ids: &[String]) -> Result<DryRunPreview> {
+    let (xml_content, _) = current_xml(file_path).await?;
+    let fixed = auto_fix::apply_fixes(&xml_content, diagnostic_ids)?;
+    let doc = parse_context_document(&fixed)?;
+    dry_run::preview(&xml_content, &doc)
+}
+
+/// XML text of the document for a source view
+///
+/// Includes unsaved edits; a document that fails to load is returned as the raw file.
+pub async fn get_raw_document(file_path: &str) -> Result<String> {
+    let (xml_content, _) = current_xml(file_path).await?;
+    Ok(xml_content)
+}
+
+/// Replace the document with edited XML text and save it
+///
+/// The text is validated and parsed like a file being opened, so invalid XML
+/// is refused before anything changes. Returns the parsed document.
+pub async fn save_raw_document(file_path: &str, xml_content: &str) -> Result<ContextDocument> {
+    let (xml_content, _) = input_normalizer::decode_input(xml_content.as_bytes())?;
+    let doc = parse_context_document(&xml_content)?;
+
+    document_store::replace(file_path, doc.clone())?;
+    change_journal::record(file_path, JournalOperation::EditRawXml, None).await;
+    save_document(file_path).await?;
+    Ok(doc)
+}
+
+/// XML of the current document along with the parsed document, or why it fails to load
+///
+/// Loaded documents are serialized from memory so unsaved edits are included;
+/// otherwise the raw file is returned so its problems can be inspected.
+async fn current_xml(file_path: &str) -> Result<(String, Result<ContextDocument>)> {
+    match read_context_document(file_path).await {
+        Ok(doc) => Ok((xml_serializer::serialize_xml(&doc), Ok(doc))),
+        Err(e @ ContextError::IoError(_)) => Err(e),
+        Err(e) => {
+            let bytes = fs::read(file_path).await?;
+            let (xml_content, _) = input_normalizer::decode_input(&bytes)?;
+            Ok((xml_content, Err(e)))
+        }
+    }
+}
+
+/// Input quirks corrected when the document was read from disk, as warnings
+pub async fn get_load_warnings(file_path: &str) -> Result<Vec<InputQuirk>> {
+    document_store::load_warnings(file_path).await
+}
+
+/// Load metadata of many documents, re-parsing only files changed since they were cached
+///
+/// Cache entries for content no longer present in `file_paths` are pruned.
+pub async fn load_workspace_metadata(file_paths: &[String], cache_dir: &Path) -> Result<Vec<WorkspaceDocument>> {
+    let cache = BinaryCache::new(cache_dir);
+    let mut documents = Vec::with_capacity(file_paths.len());
+
+    for file_path in file_paths {
+        let loaded = cache.load(file_path).await?;
+        documents.push(WorkspaceDocument {
+            file_path: file_path.clone(),
+            section_count: loaded.document.sections.len(),
+            meta: loaded.document.meta,
+            hash: loaded.hash,
+        });
+    }
+
+    let live: HashSet<String> = documents.iter().map(|d| d.hash.clone()).collect();
+    cache.prune(&live).await?;
+
+    Ok(documents)
+}
+
+/// Index tags and variable names of the workspace documents, reusing the parsed-document cache
+pub async fn build_workspace_index(file_paths: &[String], cache_dir: &Path) -> Result<workspace_index::WorkspaceIndex> {
+    let documents = load_workspace_documents(file_paths, cache_dir).await?;
+    Ok(workspace_index::WorkspaceIndex::build(&documents))
+}
+
+/// Documents of the workspace as nodes and the references between them as edges
+pub async fn build_workspace_graph(file_paths: &[String], cache_dir: &Path) -> Result<workspace_graph::WorkspaceGraph> {
+    let documents = load_workspace_documents(file_paths, cache_dir).await?;
+    Ok(workspace_graph::build_workspace_graph(&documents))
+}
+
+async fn load_workspace_documents(file_paths: &[String], cache_dir: &Path) -> Result<Vec<(String, ContextDocument)>> {
+    let cache = BinaryCache::new(cache_dir);
+    let mut documents = Vec::with_capacity(file_paths.len());
+    for file_path in file_paths {
+        documents.push((file_path.clone(), cache.load(file_path).await?.document));
+    }
+    Ok(documents)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio;
+    use std::io::Write;
+    use tempfile::NamedTempFile;
+
+    fn create_test_xml() -> String {
+        r#"
+<context version="1.0">
+    <meta>
+        <title>Test Document</title>
+        <author>Test Author</author>
+        <created>2025-10-09</created>
+        <app name="CEC" version="0.1.0"/>
+        <tags>test, doc</tags>
+        <description>A test document</description>
+    </meta>
+    <variables>
+        <var name="userName">Jeremy</var>
+        <var name="goal">Ship v1</var>
+    </variables>
+    <sections>
+        <section id="intent-1" type="intent">
+            <content><![CDATA[
+# Intent
+User: ${userName}
+Goal: ${goal}
+            ]]></content>
+        </section>
+    </sections>
+    <flow id="flow-1" version="1.0">
+        <title>Test Flow</title>
+        <diagram><![CDATA[
+```mermaid
+flowchart TD
+  A[Intent] --> B[Evaluation]
+  B --> C[Process]
+```
+        ]]></diagram>
+    </flow>
+</context>
+        "#.to_string()
+    }
+
+    /// Handle of an unsaved document with `intent-2` nested under `intent-1`
+    ///
+    /// Files can't nest sections, so the document is only built in memory.
+    fn create_nested_scratch() -> String {
+        let mut intent = Section::new("intent-1", "intent", "# Intent");
+        intent.children.push(Section::new("intent-2", "intent", "## Detail\nFor ${userName}\n\n---\n\nSecond block"));
+        let doc = ContextDocument::builder()
+            .title("Nested")
+            .variable("userName", "Jeremy")
+            .add_section(intent)
+            .flow("flowchart TD\n  A[Intent] --> B[Detail]\n  click B \"#intent-2\"")
+            .build()
+            .unwrap();
+        document_store::create_scratch(doc)
+    }
+
+    #[tokio::test]
+    async fn test_load_context_document() {
+        let xml_content = create_test_xml();
+        let mut temp_file = NamedTempFile::new().unwrap();
+        temp_file.write_all(xml_content.as_bytes()).unwrap();
+        let file_path = temp_file.path().to_str().unwrap();
+
+        let doc = load_context_document(file_path).await.unwrap();
+
+        assert_eq!(doc.meta.title, "Test Document");
+        assert_eq!(doc.meta.author, "Test Author");
+        assert_eq!(doc.variables.len(), 2);
+        assert_eq!(doc.sections.len(), 1);
+        assert!(doc.flow_graph.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_load_sections() {
+        let xml_content = create_test_xml();
+        let mut temp_file = NamedTempFile::new().unwrap();
+        temp_file.write_all(xml_content.as_bytes()).unwrap();
+        let file_path = temp_file.path().to_str().unwrap();
+
+        let sections = load_sections(file_path).await.unwrap();
+
+        assert_eq!(sections.len(), 1);
+        assert_eq!(sections[0].id, "intent-1");
+        // Variables should be resolved
+        assert!(sections[0].content.contains("Jeremy"));
+        assert!(sections[0].content.contains("Ship v1"));
+    }
+
+    #[tokio::test]
+    async fn test_load_sections_with_transclusion() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let shared_xml = create_test_xml();
+        std::fs::write(dir.path().join("shared.xml"), &shared_xml).unwrap();
+
+        let main_xml = shared_xml
+            .replace(r#"id="intent-1" type="intent""#, r#"id="proc-1" type="process" refTarget="shared.xml#intent-1""#);
+        let main_path = dir.path().join("main.xml");
+        std::fs::write(&main_path, main_xml).unwrap();
+        let file_path = main_path.to_str().unwrap();
+
+        let plain = load_sections(file_path).await.unwrap();
+        assert!(plain[0].transclusions.is_empty());
+
+        let options = LoadOptions { transclude: true, ..LoadOptions::default() };
+        let sections = load_sections_with_options(file_path, &options).await.unwrap();
+        assert_eq!(sections[0].transclusions.len(), 1);
+        assert_eq!(sections[0].transclusions[0].section_id, "intent-1");
+        assert!(sections[0].transclusions[0].content.contains("Jeremy"));
+    }
+
+    #[tokio::test]
+    async fn test_tag_filtering_and_get_tags() {
+        let xml_content = create_test_xml().replace(
+            r#"<section id="intent-1" type="intent">"#,
+            r#"<section id="intent-1" type="intent" tags="draft">"#,
+        );
+        let mut temp_file = NamedTempFile::new().unwrap();
+        temp_file.write_all(xml_content.as_bytes()).unwrap();
+        let file_path = temp_file.path().to_str().unwrap();
+
+        let tags = get_tags(file_path).await.unwrap();
+        let draft = tags.iter().find(|t| t.tag == "draft").unwrap();
+        assert_eq!(draft.section_count, 1);
+
+        let options = LoadOptions { tags: vec!["draft".to_string()], ..LoadOptions::default() };
+        assert_eq!(load_sections_with_options(file_path, &options).await.unwrap().len(), 1);
+        assert!(assemble_context(file_path, &options, &HashMap::new()).await.unwrap().contains("Jeremy"));
+
+        let options = LoadOptions { tags: vec!["final".to_string()], ..LoadOptions::default() };
+        assert!(load_sections_with_options(file_path, &options).await.unwrap().is_empty());
+        assert_eq!(assemble_context(file_path, &options, &HashMap::new()).await.unwrap(), "");
+    }
+
+    #[tokio::test]
+    async fn test_filter_spec_in_assembly_and_export() {
+        let mut temp_file = NamedTempFile::new().unwrap();
+        temp_file.write_all(create_test_xml().as_bytes()).unwrap();
+        let file_path = temp_file.path().to_str().unwrap();
+        let filter = section_filter::FilterSpec {
+            include_types: vec!["process".to_string()],
+            ..section_filter::FilterSpec::default()
+        };
+
+        let options = LoadOptions { filter: filter.clone(), ..LoadOptions::default() };
+        assert_eq!(assemble_context(file_path, &options, &HashMap::new()).await.unwrap(), "");
+
+        let options = ReadingOrderOptions::default();
+        let exported = export_reading_order(file_path, &options, &filter, &Default::default()).await.unwrap();
+        assert!(!exported.contains("Jeremy"));
+        let result = export_section(file_path, "intent-1", ExportFormat::Markdown, &filter, &Default::default()).await;
+        assert!(matches!(result, Err(ContextError::SectionNotFound(_))));
+    }
+
+    #[tokio::test]
+    async fn test_assemble_context_with_overrides() {
+        let mut temp_file = NamedTempFile::new().unwrap();
+        temp_file.write_all(create_test_xml().as_bytes()).unwrap();
+        let file_path = temp_file.path().to_str().unwrap();
+
+        let overrides = HashMap::from([("userName".to_string(), "Ada".to_string())]);
+        let assembled = assemble_context(file_path, &LoadOptions::default(), &overrides).await.unwrap();
+
+        assert!(assembled.contains("User: Ada"));
+        assert!(assembled.contains("Goal: Ship v1"));
+        // The document keeps its own value
+        assert!(load_sections(file_path).await.unwrap()[0].content.contains("User: Jeremy"));
+    }
+
+    #[tokio::test]
+    async fn test_redaction_in_assembly_and_export() {
+        let xml_content = create_test_xml().replace(r#"<var name="userName">"#, r#"<var name="userName" sensitive="true">"#);
+        let mut temp_file = NamedTempFile::new().unwrap();
+        temp_file.write_all(xml_content.as_bytes()).unwrap();
+        let file_path = temp_file.path().to_str().unwrap();
+
+        let options = LoadOptions { redaction: redaction::RedactionProfile::sensitive(), ..LoadOptions::default() };
+        let overrides = HashMap::from([("userName".to_string(), "Ada".to_string())]);
+        let assembled = assemble_context(file_path, &options, &overrides).await.unwrap();
+        assert!(assembled.contains("User: [REDACTED]"));
+        assert!(assembled.contains("Goal: Ship v1"));
+
+        let profile = redaction::RedactionProfile {
+            sections: vec!["intent-1".to_string()],
+            ..redaction::RedactionProfile::default()
+        };
+        let markdown = export_section(file_path, "intent-1", ExportFormat::Markdown, &Default::default(), &profile).await.unwrap();
+        assert_eq!(markdown, "[REDACTED]");
+        assert!(export_print_html(file_path, PageSize::A4, &Default::default(), &Default::default()).await.unwrap().contains("Jeremy"));
+    }
+
+    #[tokio::test]
+    async fn test_variable_resolution_contexts() {
+        let xml_content = create_test_xml().replace(r#"<var name="userName">"#, r#"<var name="userName" resolveIn="export,assembly">"#);
+        let mut temp_file = NamedTempFile::new().unwrap();
+        temp_file.write_all(xml_content.as_bytes()).unwrap();
+        let file_path = temp_file.path().to_str().unwrap();
+
+        assert!(load_sections(file_path).await.unwrap()[0].content.contains("User: ${userName}"));
+        let assembled = assemble_context(file_path, &LoadOptions::default(), &HashMap::new()).await.unwrap();
+        assert!(assembled.contains("User: Jeremy"));
+        let preview_options = LoadOptions { context: Some(ResolutionContext::Preview), ..LoadOptions::default() };
+        let assembled = assemble_context(file_path, &preview_options, &HashMap::new()).await.unwrap();
+        assert!(assembled.contains("User: ${userName}"));
+        assert!(export_print_html(file_path, PageSize::A4, &Default::default(), &Default::default()).await.unwrap().contains("Jeremy"));
+
+        let variables = read_context_document(file_path).await.unwrap().variables;
+        assert_eq!(variables[0].resolve_in, vec![ResolutionContext::Export, ResolutionContext::Assembly]);
+        close_document(file_path);
+    }
+
+    #[tokio::test]
+    async fn test_load_sections_with_language() {
+        let xml_content = create_test_xml().replace(
+            "</content>",
+            "</content>\n            <content lang=\"de\"><![CDATA[Hallo ${userName}]]></content>",
+        );
+        let mut temp_file = NamedTempFile::new().unwrap();
+        temp_file.write_all(xml_content.as_bytes()).unwrap();
+        let file_path = temp_file.path().to_str().unwrap();
+
+        let default = load_sections(file_path).await.unwrap();
+        assert!(default[0].content.contains("User: Jeremy"));
+
+        let options = LoadOptions { language: Some("de-DE".to_string()), ..LoadOptions::default() };
+        let sections = load_sections_with_options(file_path, &options).await.unwrap();
+        assert_eq!(sections[0].content, "Hallo Jeremy");
+
+        let options = LoadOptions { language: Some("ja".to_string()), ..LoadOptions::default() };
+        let sections = load_sections_with_options(file_path, &options).await.unwrap();
+        assert!(sections[0].content.contains("User: Jeremy"));
+    }
+
+    #[tokio::test]
+    async fn test_references_and_citation_validation() {
+        let xml_content = create_test_xml()
+            .replace("Goal: ${goal}", "Goal: ${goal} [@smith-2024; @missing-1]")
+            .replace(
+                "</sections>",
+                r#"</sections>
+    <references>
+        <reference id="smith-2024" url="https://example.com">
+            <title>Context Engineering</title>
+        </reference>
+    </references>"#,
+            );
+        let mut temp_file = NamedTempFile::new().unwrap();
+        temp_file.write_all(xml_content.as_bytes()).unwrap();
+        let file_path = temp_file.path().to_str().unwrap();
+
+        let references = load_references(file_path).await.unwrap();
+        assert_eq!(references.len(), 1);
+        assert_eq!(references[0].title, "Context Engineering");
+
+        let unresolved = validate_citations(file_path).await.unwrap();
+        assert_eq!(unresolved.len(), 1);
+        assert_eq!(unresolved[0].section_id, "intent-1");
+        assert_eq!(unresolved[0].reference_id, "missing-1");
+    }
+
+    #[tokio::test]
+    async fn test_load_metadata() {
+        let xml_content = create_test_xml();
+        let mut temp_file = NamedTempFile::new().unwrap();
+        temp_file.write_all(xml_content.as_bytes()).unwrap();
+        let file_path = temp_file.path().to_str().unwrap();
+
+        let meta = load_metadata(file_path).await.unwrap();
+
+        assert_eq!(meta.title, "Test Document");
+        assert_eq!(meta.author, "Test Author");
+        assert_eq!(meta.app_info.name, "CEC");
+        assert_eq!(meta.tags.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_save_metadata_with_custom_fields() {
+        let xml_content = create_test_xml();
+        let mut temp_file = NamedTempFile::new().unwrap();
+        temp_file.write_all(xml_content.as_bytes()).unwrap();
+        let file_path = temp_file.path().to_str().unwrap();
+
+        let mut meta = load_metadata(file_path).await.unwrap();
+        assert!(meta.extra.is_empty());
+
+        meta.title = "Renamed".to_string();
+        meta.extra.insert("ownerTeam".to_string(), "Platform".to_string());
+        save_metadata(file_path, meta).await.unwrap();
+
+        let meta = load_metadata(file_path).await.unwrap();
+        assert_eq!(meta.title, "Renamed");
+        assert_eq!(meta.extra["ownerTeam"], "Platform");
+        assert_eq!(load_sections(file_path).await.unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_load_flow_graph() {
+        let xml_content = create_test_xml();
+        let mut temp_file = NamedTempFile::new().unwrap();
+        temp_file.write_all(xml_content.as_bytes()).unwrap();
+        let file_path = temp_file.path().to_str().unwrap();
+
+        let flow = load_flow_graph(file_path).await.unwrap();
+
+        assert!(flow.is_some());
+        let flow = flow.unwrap();
+        assert_eq!(flow.id, "flow-1");
+        assert_eq!(flow.title, Some("Test Flow".to_string()));
+
+        // Should be parsed and enriched
+        assert_eq!(flow.parsed_graph.nodes.len(), 3);
+        assert_eq!(flow.parsed_graph.edges.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_load_commands_share_cached_document() {
+        let mut temp_file = NamedTempFile::new().unwrap();
+        temp_file.write_all(create_test_xml().as_bytes()).unwrap();
+        let file_path = temp_file.path().to_str().unwrap();
+
+        // One in-memory copy backs sections, metadata and flow alike
+        document_store::update(file_path, |doc| {
+            doc.meta.title = "Cached".to_string();
+            doc.sections[0].content = "# Cached".to_string();
+            let flow = doc.flow_graph.as_mut().unwrap();
+            flow.mermaid_code = flow.mermaid_code.replace("\n  B --> C[Process]", "");
+            Ok(())
+        })
+        .await
+        .unwrap();
+        assert_eq!(load_metadata(file_path).await.unwrap().title, "Cached");
+        assert_eq!(load_sections(file_path).await.unwrap()[0].content, "# Cached");
+        assert_eq!(load_flow_graph(file_path).await.unwrap().unwrap().parsed_graph.nodes.len(), 2);
+
+        // A clean copy is re-read once the file changes on disk
+        close_document(file_path);
+        assert_eq!(load_metadata(file_path).await.unwrap().title, "Test Document");
+        std::fs::write(file_path, create_test_xml().replace("Test Document", "Changed on disk")).unwrap();
+        assert_eq!(load_metadata(file_path).await.unwrap().title, "Changed on disk");
+        assert_eq!(load_sections(file_path).await.unwrap()[0].id, "intent-1");
+        assert_eq!(load_flow_graph(file_path).await.unwrap().unwrap().parsed_graph.nodes.len(), 3);
+        close_document(file_path);
+    }
+
+    #[tokio::test]
+    async fn test_get_flow_navigation() {
+        let xml_content = create_test_xml().replace(
+            "B --> C[Process]",
+            "B --> C[Process]\n  click A \"#intent-1\"",
+        );
+        let mut temp_file = NamedTempFile::new().unwrap();
+        temp_file.write_all(xml_content.as_bytes()).unwrap();
+        let file_path = temp_file.path().to_str().unwrap();
+
+        let nav = get_flow_navigation(file_path).await.unwrap();
+
+        assert_eq!(nav.len(), 3);
+        let node_a = nav.iter().find(|n| n.node_id == "A").unwrap();
+        assert_eq!(node_a.section_id, Some("intent-1".to_string()));
+        assert_eq!(node_a.heading, Some("Intent".to_string()));
+        assert!(node_a.preview.as_ref().unwrap().contains("User: Jeremy"));
+    }
+
+    #[tokio::test]
+    async fn test_suggest_and_apply_click_actions() {
+        let xml_content = create_test_xml();
+        let mut temp_file = NamedTempFile::new().unwrap();
+        temp_file.write_all(xml_content.as_bytes()).unwrap();
+        let file_path = temp_file.path().to_str().unwrap();
+
+        let suggestions = suggest_click_actions(file_path).await.unwrap();
+        assert_eq!(suggestions.len(), 1);
+        assert_eq!(suggestions[0].link.node_id, "A");
+        assert_eq!(suggestions[0].link.section_id, "intent-1");
+
+        let links: Vec<_> = suggestions.into_iter().map(|s| s.link).collect();
+        let flow = apply_click_actions(file_path, &links).await.unwrap();
+        assert_eq!(flow.node_refs.len(), 1);
+
+        let reloaded = load_flow_graph(file_path).await.unwrap().unwrap();
+        let node_a = reloaded.parsed_graph.nodes.iter().find(|n| n.id == "A").unwrap();
+        assert_eq!(node_a.ref_section_id, Some("intent-1".to_string()));
+        assert!(suggest_click_actions(file_path).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_generate_checklist_from_flow() {
+        let xml_content = create_test_xml().replace("B --> C[Process]", "B --> C[Process]\n  click A \"#intent-1\"");
+        let mut temp_file = NamedTempFile::new().unwrap();
+        temp_file.write_all(xml_content.as_bytes()).unwrap();
+        let file_path = temp_file.path().to_str().unwrap();
+
+        let section = generate_checklist_from_flow(file_path, None).await.unwrap();
+        assert_eq!(section.id, "process-checklist");
+        assert_eq!(section.section_type, "process");
+        assert!(section.content.starts_with("# Checklist\n\n- [ ] Intent ([intent-1](#intent-1)) <!-- node:A -->\n"));
+
+        let again = generate_checklist_from_flow(file_path, Some("process-checklist")).await.unwrap();
+        assert_eq!(again.content, section.content);
+
+        let appended = generate_checklist_from_flow(file_path, Some("intent-1")).await.unwrap();
+        assert!(appended.content.contains("Goal: ${goal}\n\n- [ ] Intent"));
+        assert_eq!(read_context_document(file_path).await.unwrap().sections.len(), 2);
+        close_document(file_path);
+    }
+
+    #[tokio::test]
+    async fn test_generate_checklist_into_nested_section() {
+        let handle = create_nested_scratch();
+
+        let section = generate_checklist_from_flow(&handle, Some("intent-2")).await.unwrap();
+        assert!(section.content.starts_with("## Detail"));
+        assert!(section.content.contains("- [ ] Detail"));
+        let doc = read_context_document(&handle).await.unwrap();
+        assert_eq!(doc.sections.len(), 1);
+        assert_eq!(doc.sections[0].children[0].content, section.content);
+
+        document_store::update(&handle, |doc| {
+            doc.sections[0].children[0].encrypted = true;
+            Ok(())
+        })
+        .await
+        .unwrap();
+        let result = generate_checklist_from_flow(&handle, Some("intent-2")).await;
+        assert!(matches!(result, Err(ContextError::InvalidArgument(_))));
+        close_document(&handle);
+    }
+
+    #[tokio::test]
+    async fn test_import_mermaid_from_text() {
+        let xml_content = create_test_xml();
+        let mut temp_file = NamedTempFile::new().unwrap();
+        temp_file.write_all(xml_content.as_bytes()).unwrap();
+        let file_path = temp_file.path().to_str().unwrap();
+        let pasted = "Here you go:\n\n```mermaid\nflowchart LR\n  A[Intent] --> D[Done]\n```";
+
+        let preview = preview_mermaid_import(file_path, pasted).await.unwrap();
+        assert!(preview.changed);
+        assert!(preview.diff.contains("+  A[Intent] --> D[Done]"), "{}", preview.diff);
+        assert!(!is_document_dirty(file_path));
+
+        let flow = import_mermaid_from_text(file_path, pasted).await.unwrap();
+        assert_eq!(flow.title.as_deref(), Some("Test Flow"));
+        let ids: Vec<&str> = flow.parsed_graph.nodes.iter().map(|n| n.id.as_str()).collect();
+        assert_eq!(ids, vec!["A", "D"]);
+
+        let result = import_mermaid_from_text(file_path, "no diagram here").await;
+        assert!(matches!(result, Err(ContextError::InvalidArgument(_))));
+        close_document(file_path);
+    }
+
+    #[tokio::test]
+    async fn test_import_flow() {
+        let xml_content = create_test_xml();
+        let mut temp_file = NamedTempFile::new().unwrap();
+        temp_file.write_all(xml_content.as_bytes()).unwrap();
+        let file_path = temp_file.path().to_str().unwrap();
+
+        let dot = "digraph { rankdir=LR; start [label=\"Start\", href=\"#intent-1\"]; start -> finish }";
+        let flow = import_flow(file_path, dot, GraphFormat::Dot).await.unwrap();
+        let ids: Vec<&str> = flow.parsed_graph.nodes.iter().map(|n| n.id.as_str()).collect();
+        assert_eq!(ids, vec!["start", "finish"]);
+        assert_eq!(flow.parsed_graph.nodes[0].ref_section_id.as_deref(), Some("intent-1"));
+        assert!(flow.mermaid_code.contains("flowchart LR"));
+        assert!(is_document_dirty(file_path));
+
+        let json = r#"{"nodes":[{"id":"A","label":"Plan","position":{"x":5,"y":6},"metadata":{"owner":"sam"}}],"edges":[]}"#;
+        let flow = import_flow(file_path, json, GraphFormat::Json).await.unwrap();
+        assert_eq!(flow.layout.unwrap().positions["A"], NodePosition { x: 5.0, y: 6.0 });
+        assert_eq!(flow.parsed_graph.nodes[0].metadata.as_ref().unwrap().owner.as_deref(), Some("sam"));
+
+        let result = import_flow(file_path, "graph {}", GraphFormat::Dot).await;
+        assert!(matches!(result, Err(ContextError::InvalidArgument(_))));
+        close_document(file_path);
+    }
+
+    #[tokio::test]
+    async fn test_save_and_load_flow_layout() {
+        let xml_content = create_test_xml();
+        let mut temp_file = NamedTempFile::new().unwrap();
+        temp_file.write_all(xml_content.as_bytes()).unwrap();
+        let file_path = temp_file.path().to_str().unwrap();
+
+        assert!(load_flow_layout(file_path).await.unwrap().is_none());
+
+        let mut positions = std::collections::BTreeMap::new();
+        positions.insert("A".to_string(), NodePosition { x: 40.0, y: 60.0 });
+        let layout = FlowLayout {
+            zoom: 2.0,
+            pan_x: 5.0,
+            pan_y: 5.0,
+            positions,
+        };
+        save_flow_layout(file_path, layout.clone()).await.unwrap();
+
+        assert_eq!(load_flow_layout(file_path).await.unwrap(), Some(layout.clone()));
+        let flow = load_flow_graph(file_path).await.unwrap().unwrap();
+        assert_eq!(flow.layout, Some(layout));
+        assert_eq!(flow.parsed_graph.nodes.len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_focus_mode_lookups() {
+        let xml_content = create_test_xml().replace("B --> C[Process]", "B --> C[Process]\n  click A \"#intent-1\"");
+        let mut temp_file = NamedTempFile::new().unwrap();
+        temp_file.write_all(xml_content.as_bytes()).unwrap();
+        let file_path = temp_file.path().to_str().unwrap();
+
+        let focus = get_sections_for_node(file_path, "A").await.unwrap();
+        assert_eq!(focus[0].section.id, "intent-1");
+        assert_eq!(focus[0].relation, flow_navigation::FocusRelation::Linked);
+
+        let node = get_node_for_section(file_path, "intent-1").await.unwrap().unwrap();
+        assert_eq!(node.id, "A");
+        assert!(get_node_for_section(file_path, "missing").await.unwrap().is_none());
+        assert!(matches!(
+            get_sections_for_node(file_path, "Z").await,
+            Err(ContextError::InvalidArgument(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_variable_sheet_round_trip() {
+        let xml_content = create_test_xml();
+        let mut temp_file = NamedTempFile::new().unwrap();
+        temp_file.write_all(xml_content.as_bytes()).unwrap();
+        let file_path = temp_file.path().to_str().unwrap();
+
+        let sheet = get_variable_sheet(file_path, variable_sheet::SheetFormat::Markdown).await.unwrap();
+        assert!(sheet.contains("| goal |  | Ship v1 |"));
+
+        let filled = sheet.replace("| goal |  | Ship v1 |", "| goal |  | Ship v2 |").replace("Jeremy", "");
+        let doc = apply_variable_sheet(file_path, &filled).await.unwrap();
+        assert_eq!(doc.variables[0].value, "Jeremy");
+        assert_eq!(doc.variables[1].value, "Ship v2");
+
+        close_document(file_path);
+        assert!(std::fs::read_to_string(file_path).unwrap().contains("<var name=\"goal\">Ship v2</var>"));
+    }
+
+    #[tokio::test]
+    async fn test_apply_edits_is_all_or_nothing() {
+        use document_edits::DocumentEdit;
+
+        let xml_content = create_test_xml();
+        let mut temp_file = NamedTempFile::new().unwrap();
+        temp_file.write_all(xml_content.as_bytes()).unwrap();
+        let file_path = temp_file.path().to_str().unwrap();
+
+        let failing = vec![
+            DocumentEdit::SetVariable { name: "goal".to_string(), value: "Ship v2".to_string() },
+            DocumentEdit::DeleteSection { id: "missing".to_string() },
+        ];
+        assert!(apply_edits(file_path, &failing).await.is_err());
+        assert!(!is_document_dirty(file_path));
+        assert_eq!(read_context_document(file_path).await.unwrap().variables[1].value, "Ship v1");
+
+        let invalid_type = vec![DocumentEdit::CreateSection {
+            section: Section::new("notes-1", "notes", "# Notes"),
+            parent_id: None,
+            index: None,
+        }];
+        assert!(matches!(
+            apply_edits(file_path, &invalid_type).await,
+            Err(ContextError::SchemaValidationError(_))
+        ));
+
+        let edits = vec![
+            DocumentEdit::SetVariable { name: "goal".to_string(), value: "Ship v2".to_string() },
+            DocumentEdit::CreateSection {
+                section: Section::new("process-1", "process", "# Process"),
+                parent_id: None,
+                index: Some(0),
+            },
+            DocumentEdit::CreateSection {
+                section: Section::new("evaluation-1", "evaluation", ""),
+                parent_id: None,
+                index: None,
+            },
+        ];
+        let doc = apply_edits(file_path, &edits).await.unwrap();
+        assert_eq!(doc.sections[0].id, "process-1");
+        assert_eq!(doc.sections[0].content, "# Process");
+        assert!(doc.sections[2].content.starts_with("# Evaluation\n\n| Criterion |"));
+        assert!(!is_document_dirty(file_path));
+
+        close_document(file_path);
+        let saved = read_context_document(file_path).await.unwrap();
+        assert_eq!(saved.sections.len(), 3);
+        assert_eq!(saved.variables[1].value, "Ship v2");
+        close_document(file_path);
+    }
+
+    #[tokio::test]
+    async fn test_update_section_saves_only_that_section() {
+        let xml_content = create_test_xml();
+        let mut temp_file = NamedTempFile::new().unwrap();
+        temp_file.write_all(xml_content.as_bytes()).unwrap();
+        let file_path = temp_file.path().to_str().unwrap();
+
+        let mut section = Section::new("intent-1", "intent", "# Intent\nOwner: ${userName}");
+        section.tags = vec!["core".to_string()];
+        let preview = preview_update_section(file_path, &section).await.unwrap();
+        assert!(preview.changed);
+        assert!(!is_document_dirty(file_path));
+
+        let updated = update_section(file_path, &section).await.unwrap();
+        assert_eq!(updated.tags, vec!["core"]);
+        assert!(!is_document_dirty(file_path));
+
+        close_document(file_path);
+        let saved = read_context_document(file_path).await.unwrap();
+        assert_eq!(saved.sections[0].content, "# Intent\nOwner: ${userName}");
+        assert_eq!(saved.variables.len(), 2);
+        assert!(saved.flow_graph.is_some());
+
+        section.section_type = "notes".to_string();
+        assert!(matches!(update_section(file_path, &section).await, Err(ContextError::SchemaValidationError(_))));
+        assert_eq!(read_context_document(file_path).await.unwrap().sections[0].section_type, "intent");
+        assert!(matches!(
+            update_section(file_path, &Section::new("missing", "intent", "")).await,
+            Err(ContextError::SectionNotFound(_))
+        ));
+        close_document(file_path);
+    }
+
+    #[tokio::test]
+    async fn test_dry_run_previews_leave_document_untouched() {
+        use document_edits::DocumentEdit;
+
+        let xml_content = create_test_xml();
+        let mut temp_file = NamedTempFile::new().unwrap();
+        temp_file.write_all(xml_content.as_bytes()).unwrap();
+        let file_path = temp_file.path().to_str().unwrap();
+
+        let edits = vec![DocumentEdit::SetVariable { name: "goal".to_string(), value: "Ship v2".to_string() }];
+        let preview = preview_edits(file_path, &edits).await.unwrap();
+        assert!(preview.changed);
+        assert!(preview.diff.contains(r#"+    <var name="goal">Ship v2</var>"#), "{}", preview.diff);
+        assert!(!is_document_dirty(file_path));
+
+        let links = vec![click_suggestions::ClickLink { node_id: "A".to_string(), section_id: "intent-1".to_string(), tooltip: None }];
+        assert!(preview_click_actions(file_path, &links).await.unwrap().diff.contains("click A"));
+        assert!(preview_merge_sections(file_path, &["intent-1".to_string(), "missing".to_string()], None).await.is_err());
+        assert!(!is_document_dirty(file_path));
+
+        save_metadata(file_path, MetaData { title: "Renamed".to_string(), ..read_context_document(file_path).await.unwrap().meta })
+            .await
+            .unwrap();
+        let preview = preview_save(file_path).await.unwrap();
+        assert!(preview.diff.contains("+    <title>Renamed</title>"), "{}", preview.diff);
+        assert_eq!(std::fs::read_to_string(file_path).unwrap(), xml_content);
+        close_document(file_path);
+    }
+
+    #[tokio::test]
+    async fn test_section_index_tracks_variable_changes() {
+        let xml_content = create_test_xml();
+        let mut temp_file = NamedTempFile::new().unwrap();
+        temp_file.write_all(xml_content.as_bytes()).unwrap();
+        let file_path = temp_file.path().to_str().unwrap();
+
+        let before = get_section_index(file_path).await.unwrap();
+        assert_eq!(before.len(), 1);
+        assert_eq!(before[0].word_count, 7);
+
+        apply_edits(file_path, &[document_edits::DocumentEdit::SetVariable { name: "goal".to_string(), value: "Ship v2".to_string() }])
+            .await
+            .unwrap();
+        let after = get_section_index(file_path).await.unwrap();
+        assert_ne!(before[0].content_hash, after[0].content_hash);
+        close_document(file_path);
+    }
+
+    #[tokio::test]
+    async fn test_get_section_dependencies() {
+        let xml_content = create_test_xml()
+            .replace(r#"<section id="intent-1" type="intent">"#, r#"<section id="intent-1" type="intent" refTarget="process-1">"#)
+            .replace("</sections>", r#"<section id="process-1" type="process"><content>Steps</content></section></sections>"#)
+            .replace("B --> C[Process]", "B --> C[Process]\n  click A \"#intent-1\"\n  click C \"#process-1\"");
+        let mut temp_file = NamedTempFile::new().unwrap();
+        temp_file.write_all(xml_content.as_bytes()).unwrap();
+        let file_path = temp_file.path().to_str().unwrap();
+
+        let report = get_section_dependencies(file_path).await.unwrap();
+        assert_eq!(report.sections[0].direct, vec!["process-1"]);
+        assert_eq!(report.conflicts.len(), 1);
+        assert_eq!(report.conflicts[0].depends_on_node_id, "C");
+    }
+
+    #[tokio::test]
+    async fn test_save_records_daily_stats() {
+        let dir = tempfile::tempdir().unwrap();
+        let file_path = dir.path().join("stats.xml");
+        std::fs::write(&file_path, create_test_xml()).unwrap();
+        let file_path = file_path.to_str().unwrap();
+
+        save_document(file_path).await.unwrap();
+
+        let history = stats_history::read_stats_history(file_path).await.unwrap();
+        assert_eq!(history.len(), 1);
+        assert_eq!(history[0].date, chrono::Local::now().format("%Y-%m-%d").to_string());
+        assert_eq!(history[0].stats.words_by_type["intent"], 7);
+        close_document(file_path);
+    }
+
+    #[tokio::test]
+    async fn test_save_normalizes_line_endings_and_paths() {
+        let xml_content = create_test_xml()
+            .replace("Goal: ${goal}", r"Goal: ${goal}, see [spec](docs\spec.md)")
+            .replace(r#"type="intent">"#, r#"type="intent" refTarget="..\shared\brief.xml#intent-1">"#)
+            .replace('\n', "\r\n");
+        let mut temp_file = NamedTempFile::new().unwrap();
+        temp_file.write_all(xml_content.as_bytes()).unwrap();
+        let file_path = temp_file.path().to_str().unwrap();
+
+        save_document(file_path).await.unwrap();
+
+        let saved = std::fs::read_to_string(file_path).unwrap();
+        assert!(!saved.contains('\r'));
+        assert!(saved.contains("[spec](docs/spec.md)"));
+        assert!(saved.contains(r#"refTarget="../shared/brief.xml#intent-1""#));
+        close_document(file_path);
+    }
+
+    #[tokio::test]
+    async fn test_set_node_metadata() {
+        let xml_content = create_test_xml();
+        let mut temp_file = NamedTempFile::new().unwrap();
+        temp_file.write_all(xml_content.as_bytes()).unwrap();
+        let file_path = temp_file.path().to_str().unwrap();
+
+        let metadata = NodeMetadata {
+            owner: Some("sam".to_string()),
+            status: Some("review".to_string()),
+            estimated_effort: Some(4.0),
+        };
+        let flow = set_node_metadata(file_path, "B", metadata.clone()).await.unwrap();
+        let node_b = flow.parsed_graph.nodes.iter().find(|n| n.id == "B").unwrap();
+        assert_eq!(node_b.metadata, Some(metadata.clone()));
+
+        save_document(file_path).await.unwrap();
+        close_document(file_path);
+        let reloaded = load_flow_graph(file_path).await.unwrap().unwrap();
+        assert_eq!(reloaded.node_metadata["B"], metadata);
+
+        let cleared = set_node_metadata(file_path, "B", NodeMetadata::default()).await.unwrap();
+        assert!(cleared.node_metadata.is_empty());
+        assert!(matches!(
+            set_node_metadata(file_path, "Z", NodeMetadata::default()).await,
+            Err(ContextError::InvalidArgument(_))
+        ));
+        close_document(file_path);
+    }
+
+    #[tokio::test]
+    async fn test_compute_flow_layout() {
+        let xml_content = create_test_xml();
+        let mut temp_file = NamedTempFile::new().unwrap();
+        temp_file.write_all(xml_content.as_bytes()).unwrap();
+        let file_path = temp_file.path().to_str().unwrap();
+
+        let layout = compute_flow_layout(file_path).await.unwrap().unwrap();
+
+        assert_eq!(layout.positions.len(), 3);
+        assert!(layout.positions["A"].y < layout.positions["B"].y);
+        assert!(layout.positions["B"].y < layout.positions["C"].y);
+        assert!(load_flow_layout(file_path).await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_simulate_flow() {
+        let xml_content = create_test_xml()
+            .replace(r#"<var name="goal">Ship v1</var>"#, r#"<var name="goal">Ship v1</var><var name="risk">2</var>"#)
+            .replace("B --> C[Process]", "B -->|cond: risk > 3| C[Process]\n  B -->|cond: risk <= 3| D[Ship]");
+        let mut temp_file = NamedTempFile::new().unwrap();
+        temp_file.write_all(xml_content.as_bytes()).unwrap();
+        let file_path = temp_file.path().to_str().unwrap();
+
+        let result = simulate_flow(file_path, None, &HashMap::new()).await.unwrap();
+        let path: Vec<_> = result.steps.iter().map(|s| s.node_id.as_str()).collect();
+        assert_eq!(path, vec!["A", "B", "D"]);
+
+        let overrides = HashMap::from([("risk".to_string(), "5".to_string())]);
+        let result = simulate_flow(file_path, None, &overrides).await.unwrap();
+        assert_eq!(result.steps.last().unwrap().node_id, "C");
+        assert_eq!(result.outcome, flow_simulation::SimulationOutcome::Completed);
+    }
+
+    #[tokio::test]
+    async fn test_save_flow_graph() {
+        let xml_content = create_test_xml();
+        let mut temp_file = NamedTempFile::new().unwrap();
+        temp_file.write_all(xml_content.as_bytes()).unwrap();
+        let file_path = temp_file.path().to_str().unwrap();
+
+        let linked = "flowchart TD\n  A[Intent] --> B[Evaluation]\n  click A \"#intent-1\"";
+        let flow = save_flow_graph(file_path, linked, false).await.unwrap();
+        assert_eq!(flow.node_refs.len(), 1);
+
+        let unlinked = "flowchart TD\n  A[Intent] --> B[Evaluation]";
+        let diagnostics = check_flow_graph(file_path, unlinked).await.unwrap();
+        assert_eq!(diagnostics[0].id, "flow-lost-link:intent-1");
+        assert!(matches!(
+            save_flow_graph(file_path, unlinked, false).await,
+            Err(ContextError::ValidationError(_))
+        ));
+        assert!(matches!(
+            save_flow_graph(file_path, "flowchart TD", false).await,
+            Err(ContextError::ValidationError(_))
+        ));
+        assert_eq!(load_flow_graph(file_path).await.unwrap().unwrap().mermaid_code, linked);
+
+        let flow = save_flow_graph(file_path, unlinked, true).await.unwrap();
+        assert!(flow.node_refs.is_empty());
+        close_document(file_path);
+    }
+
+    #[tokio::test]
+    async fn test_open_document_safe_mode() {
+        let mut temp_file = NamedTempFile::new().unwrap();
+        temp_file.write_all(create_test_xml().as_bytes()).unwrap();
+        let file_path = temp_file.path().to_str().unwrap();
+        let opened = open_document_safe_mode(file_path).await.unwrap();
+        assert!(!opened.recovered);
+        assert_eq!(opened.document.meta.title, "Test Document");
+        close_document(file_path);
+
+        let damaged = create_test_xml().replace("</sections>", "<sections>");
+        let mut temp_file = NamedTempFile::new().unwrap();
+        temp_file.write_all(damaged.as_bytes()).unwrap();
+        let file_path = temp_file.path().to_str().unwrap();
+        assert!(load_context_document(file_path).await.is_err());
+
+        let opened = open_document_safe_mode(file_path).await.unwrap();
+        assert!(opened.recovered);
+        assert!(opened.parse_error.is_some());
+        assert_eq!(opened.document.sections[0].id, "intent-1");
+        assert!(opened.document.sections[0].content.contains("Goal: ${goal}"));
+        assert_eq!(read_context_document(file_path).await.unwrap(), opened.document);
+        close_document(file_path);
+    }
+
+    #[tokio::test]
+    async fn test_raw_document_round_trip() {
+        let mut temp_file = NamedTempFile::new().unwrap();
+        temp_file.write_all(create_test_xml().as_bytes()).unwrap();
+        let file_path = temp_file.path().to_str().unwrap();
+
+        let raw = get_raw_document(file_path).await.unwrap();
+        assert!(raw.contains("<title>Test Document</title>"));
+
+        let broken = raw.replace("</sections>", "");
+        assert!(save_raw_document(file_path, &broken).await.is_err());
+        assert_eq!(load_metadata(file_path).await.unwrap().title, "Test Document");
+
+        let edited = raw.replace("<title>Test Document</title>", "<title>Edited</title>");
+        let doc = save_raw_document(file_path, &edited).await.unwrap();
+        assert_eq!(doc.meta.title, "Edited");
+        close_document(file_path);
+        assert_eq!(load_metadata(file_path).await.unwrap().title, "Edited");
+        close_document(file_path);
+    }
+
+    #[tokio::test]
+    async fn test_get_changes_since() {
+        let mut temp_file = NamedTempFile::new().unwrap();
+        temp_file.write_all(create_test_xml().as_bytes()).unwrap();
+        let file_path = temp_file.path().to_str().unwrap();
+
+        let initial = get_changes_since(file_path, 0).await.unwrap();
+        assert!(initial.full);
+        assert_eq!(initial.sections.len(), 1);
+        assert!(initial.meta.is_some() && initial.flow.is_some());
+
+        document_store::update(file_path, |doc| {
+            doc.sections.push(Section::new("process-1", "process", "Steps"));
+            Ok(())
+        })
+        .await
+        .unwrap();
+        let added = get_changes_since(file_path, initial.version).await.unwrap();
+        assert!(!added.full);
+        assert_eq!(added.sections.iter().map(|s| s.id.as_str()).collect::<Vec<_>>(), vec!["process-1"]);
+        assert_eq!(added.section_order, Some(vec!["intent-1".to_string(), "process-1".to_string()]));
+        assert!(added.meta.is_none() && added.flow.is_none() && !added.flow_removed);
+
+        document_store::update(file_path, |doc| {
+            doc.sections.retain(|s| s.id != "process-1");
+            doc.flow_graph = None;
+            Ok(())
+        })
+        .await
+        .unwrap();
+        let removed = get_changes_since(file_path, added.version).await.unwrap();
+        assert!(removed.sections.is_empty());
+        assert_eq!(removed.removed_section_ids, vec!["process-1"]);
+        assert!(removed.flow_removed);
+
+        let unchanged = get_changes_since(file_path, removed.version).await.unwrap();
+        assert_eq!(unchanged.version, removed.version);
+        assert!(unchanged.sections.is_empty() && unchanged.section_order.is_none() && !unchanged.flow_removed);
+        close_document(file_path);
+    }
+
+    #[tokio::test]
+    async fn test_get_effort_rollup() {
+        let xml_content = create_test_xml();
+        let mut temp_file = NamedTempFile::new().unwrap();
+        temp_file.write_all(xml_content.as_bytes()).unwrap();
+        let file_path = temp_file.path().to_str().unwrap();
+
+        for (node_id, effort) in [("A", 1.0), ("C", 2.5)] {
+            let metadata = NodeMetadata { estimated_effort: Some(effort), ..Default::default() };
+            set_node_metadata(file_path, node_id, metadata).await.unwrap();
+        }
+
+        let rollup = get_effort_rollup(file_path).await.unwrap();
+        assert_eq!(rollup.total, 3.5);
+        assert_eq!(rollup.paths.len(), 1);
+        assert_eq!(rollup.paths[0].node_ids, vec!["A", "B", "C"]);
+        assert_eq!(rollup.paths[0].unestimated, vec!["B"]);
+        close_document(file_path);
+    }
+
+    #[tokio::test]
+    async fn test_process_flow_graph() {
+        let mermaid_code = r###"
+```mermaid
+flowchart TD
+  A[Start] --> B[End]
+  click A "#section-1" "Go to section"
+```
+            "###;
+
+        let flow = FlowGraph {
+            id: "test-flow".to_string(),
+            version: "1.0".to_string(),
+            title: Some("Test".to_string()),
+            mermaid_code: mermaid_code.to_string(),
+            parsed_graph: GraphStructure {
+                nodes: vec![],
+                edges: vec![],
+                subgraphs: vec![],
+            },
+            node_refs: vec![],
+            layout: None,
+            node_metadata: Default::default(),
+        };
+
+        let processed = process_flow_graph(flow).await.unwrap();
+
+        assert_eq!(processed.parsed_graph.nodes.len(), 2);
+        assert_eq!(processed.parsed_graph.edges.len(), 1);
+        assert_eq!(processed.node_refs.len(), 1);
+        assert_eq!(processed.node_refs[0].node_id, "A");
+        assert_eq!(processed.node_refs[0].section_id, "section-1");
+    }
+
+    #[tokio::test]
+    async fn test_load_document_without_flow() {
+        let xml_content = r#"
+<context version="1.0">
+    <meta>
+        <title>No Flow Document</title>
+        <author>Test Author</author>
+        <created>2025-10-09</created>
+        <app name="CEC" version="0.1.0"/>
+        <tags>test</tags>
+        <description>Document without flow</description>
+    </meta>
+    <variables></variables>
+    <sections>
+        <section id="test-1" type="intent">
+            <content><![CDATA[Test content]]></content>
+        </section>
+    </sections>
+</context>
+        "#;
+
+        let mut temp_file = NamedTempFile::new().unwrap();
+        temp_file.write_all(xml_content.as_bytes()).unwrap();
+        let file_path = temp_file.path().to_str().unwrap();
+
+        let flow = load_flow_graph(file_path).await.unwrap();
+        assert!(flow.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_export_section() {
+        let xml_content = create_test_xml();
+        let mut temp_file = NamedTempFile::new().unwrap();
+        temp_file.write_all(xml_content.as_bytes()).unwrap();
+        let file_path = temp_file.path().to_str().unwrap();
+
+        let html = export_section(file_path, "intent-1", ExportFormat::Html, &Default::default(), &Default::default()).await.unwrap();
+        assert!(html.contains("<h1>Intent</h1>"));
+        assert!(html.contains("User: Jeremy"));
+
+        let result = export_section(file_path, "missing", ExportFormat::Markdown, &Default::default(), &Default::default()).await;
+        assert!(matches!(result, Err(ContextError::SectionNotFound(_))));
+    }
+
+    #[tokio::test]
+    async fn test_export_nested_section() {
+        let handle = create_nested_scratch();
+
+        let markdown = export_section(&handle, "intent-2", ExportFormat::Markdown, &Default::default(), &Default::default()).await.unwrap();
+        assert!(markdown.contains("For Jeremy"));
+        close_document(&handle);
+    }
+
+    #[tokio::test]
+    async fn test_save_as_template() {
+        let xml_content = create_test_xml();
+        let mut temp_file = NamedTempFile::new().unwrap();
+        temp_file.write_all(xml_content.as_bytes()).unwrap();
+        let file_path = temp_file.path().to_str().unwrap();
+        let library = tempfile::tempdir().unwrap();
+
+        let info = save_as_template(file_path, library.path(), "Weekly Review").await.unwrap();
+        assert_eq!(info.variables, vec!["userName".to_string(), "goal".to_string()]);
+
+        let template = read_context_document(&info.file_path).await.unwrap();
+        assert_eq!(template.meta.title, "Weekly Review");
+        assert_eq!(template.sections[0].content, "# Intent");
+        assert!(template.variables.iter().all(|v| v.value.is_empty()));
+        assert!(template.flow_graph.unwrap().mermaid_code.contains("A[Intent] --> B[Evaluation]"));
+    }
+
+    #[tokio::test]
+    async fn test_create_document() {
+        let dir = tempfile::tempdir().unwrap();
+        let library = tempfile::tempdir().unwrap();
+        let file_path = dir.path().join("plans").join("launch.xml");
+        let file_path = file_path.to_str().unwrap();
+
+        let doc = create_document(file_path, "Launch", "Dana", None, library.path(), true).await.unwrap();
+        assert_eq!(doc.sections.len(), 4);
+        let loaded = read_context_document(file_path).await.unwrap();
+        assert_eq!(loaded.meta.author, "Dana");
+        assert_eq!(loaded.sections[3].id, "alternatives-1");
+        assert_eq!(load_flow_graph(file_path).await.unwrap().unwrap().node_refs.len(), 4);
+        assert!(matches!(
+            create_document(file_path, "Again", "", None, library.path(), false).await,
+            Err(ContextError::InvalidArgument(_))
+        ));
+        close_document(file_path);
+
+        save_as_template(file_path, library.path(), "Launch Plan").await.unwrap();
+        let from_template = dir.path().join("next.xml");
+        let from_template = from_template.to_str().unwrap();
+        let doc = create_document(from_template, "Next launch", "Sam", Some("Launch Plan"), library.path(), false).await.unwrap();
+        assert_eq!(doc.meta.title, "Next launch");
+        assert_eq!(doc.sections[0].content, "# Intent\n\n## Goal\n\n## Success criteria");
+        assert!(doc.flow_graph.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_scratch_document_first_save() {
+        let dir = tempfile::tempdir().unwrap();
+        let handle = create_scratch_document("Untitled", "", None, dir.path(), false).await.unwrap();
+
+        let mut section = Section::new("intent-1", "intent", "# Intent\nDraft");
+        update_section(&handle, &section).await.unwrap();
+        assert_eq!(read_context_document(&handle).await.unwrap().sections[0].content, "# Intent\nDraft");
+        assert!(is_document_dirty(&handle));
+        assert!(matches!(save_document(&handle).await, Err(ContextError::InvalidArgument(_))));
+
+        let file_path = dir.path().join("draft.xml");
+        let file_path = file_path.to_str().unwrap();
+        save_document_as(&handle, file_path).await.unwrap();
+        assert!(!is_document_dirty(file_path));
+        assert!(read_context_document(&handle).await.is_err());
+
+        section.content = "# Intent\nFinal".to_string();
+        update_section(file_path, &section).await.unwrap();
+        close_document(file_path);
+        assert_eq!(read_context_document(file_path).await.unwrap().sections[0].content, "# Intent\nFinal");
+        close_document(file_path);
+    }
+
+    #[tokio::test]
+    async fn test_compare_documents() {
+        let xml_content = create_test_xml();
+        let mut file_a = NamedTempFile::new().unwrap();
+        file_a.write_all(xml_content.as_bytes()).unwrap();
+        let mut file_b = NamedTempFile::new().unwrap();
+        file_b.write_all(xml_content.replace("Goal: ${goal}", "Goal: ${goal}\nBy Friday").as_bytes()).unwrap();
+        let (path_a, path_b) = (file_a.path().to_str().unwrap(), file_b.path().to_str().unwrap());
+
+        let comparison = compare_documents(path_a, path_b).await.unwrap();
+        assert!(!comparison.identical);
+        assert!(comparison.meta.is_empty());
+        assert_eq!(comparison.sections[0].status, document_compare::DiffStatus::Modified);
+        assert!(comparison.sections[0].diff.contains("+By Friday"));
+        assert!(compare_documents(path_a, path_a).await.unwrap().identical);
+        close_document(path_a);
+        close_document(path_b);
+    }
+
+    #[tokio::test]
+    async fn test_export_print_html() {
+        let xml_content = create_test_xml();
+        let mut temp_file = NamedTempFile::new().unwrap();
+        temp_file.write_all(xml_content.as_bytes()).unwrap();
+        let file_path = temp_file.path().to_str().unwrap();
+
+        let html = export_print_html(file_path, PageSize::A4, &Default::default(), &Default::default()).await.unwrap();
+        assert!(html.contains("User: Jeremy"));
+        assert!(html.contains("<section class=\"page flow\">"));
+        assert!(html.contains("<line"));
+    }
+
+    #[tokio::test]
+    async fn test_builtin_date_variables_follow_document_locale() {
+        let xml_content = create_test_xml()
+            .replace("<tags>test, doc</tags>", "<tags>test, doc</tags>\n        <locale>en-GB</locale>")
+            .replace("Goal: ${goal}", "Goal: ${goal} by ${date.year}, as of ${date.today}");
+        let mut temp_file = NamedTempFile::new().unwrap();
+        temp_file.write_all(xml_content.as_bytes()).unwrap();
+        let file_path = temp_file.path().to_str().unwrap();
+
+        let today = chrono::Local::now().date_naive();
+        let doc = load_context_document(file_path).await.unwrap();
+        assert!(doc.sections[0]
+            .content
+            .contains(&format!("by {}, as of {}", today.format("%Y"), today.format("%d/%m/%Y"))));
+
+        let html = export_print_html(file_path, PageSize::A4, &Default::default(), &Default::default()).await.unwrap();
+        assert!(html.contains(&today.format("%d/%m/%Y").to_string()));
+        close_document(file_path);
+    }
+
+    #[tokio::test]
+    async fn test_export_excalidraw() {
+        let xml_content = create_test_xml();
+        let mut temp_file = NamedTempFile::new().unwrap();
+        temp_file.write_all(xml_content.as_bytes()).unwrap();
+        let file_path = temp_file.path().to_str().unwrap();
+
+        let scene: serde_json::Value = serde_json::from_str(&export_excalidraw(file_path).await.unwrap()).unwrap();
+        assert_eq!(scene["type"], "excalidraw");
+        assert!(scene["elements"].as_array().unwrap().iter().any(|e| e["type"] == "arrow"));
+    }
+
+    #[tokio::test]
+    async fn test_export_html() {
+        let xml_content = create_test_xml();
+        let mut temp_file = NamedTempFile::new().unwrap();
+        temp_file.write_all(xml_content.as_bytes()).unwrap();
+        let file_path = temp_file.path().to_str().unwrap();
+
+        let html = export_html(file_path, &Default::default(), &Default::default()).await.unwrap();
+        assert!(html.contains("<pre class=\"mermaid\">\nflowchart TD\n  A[Intent] --&gt; B[Evaluation]"));
+        assert!(html.contains("User: Jeremy"));
+        assert!(html.contains("import mermaid from"));
+        close_document(file_path);
+    }
+
+    #[tokio::test]
+    async fn test_export_link_map() {
+        let xml_content = create_test_xml().replace("B --> C[Process]", "B --> C[Process]\n  click A \"#intent-1\"");
+        let mut temp_file = NamedTempFile::new().unwrap();
+        temp_file.write_all(xml_content.as_bytes()).unwrap();
+        let file_path = temp_file.path().to_str().unwrap();
+
+        let json: serde_json::Value = serde_json::from_str(&export_link_map(file_path).await.unwrap()).unwrap();
+        assert_eq!(json["format"], "flow-writer-link-map");
+        assert_eq!(json["sections"][0]["headings"][0]["text"], "Intent");
+        assert_eq!(json["sections"][0]["nodeIds"][0], "A");
+        assert_eq!(json["nodes"].as_array().unwrap().len(), 3);
+        assert_eq!(json["links"][0]["source"], "click");
+        close_document(file_path);
+    }
+
+    #[tokio::test]
+    async fn test_json_export_import_round_trip() {
+        let xml_content = create_test_xml();
+        let mut temp_file = NamedTempFile::new().unwrap();
+        temp_file.write_all(xml_content.as_bytes()).unwrap();
+        let file_path = temp_file.path().to_str().unwrap();
+
+        let json = export_json(file_path).await.unwrap();
+        let mut value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(value["format"], json_serializer::JSON_DOCUMENT_FORMAT);
+        assert_eq!(value["document"]["flow_graph"]["parsed_graph"]["nodes"].as_array().unwrap().len(), 3);
+        assert!(value["document"]["sections"][0]["content"].as_str().unwrap().contains("${goal}"));
+
+        value["document"]["meta"]["title"] = "From JSON".into();
+        let imported = import_json(file_path, &value.to_string()).await.unwrap();
+        assert_eq!(imported.meta.title, "From JSON");
+        assert!(is_document_dirty(file_path));
+        assert_eq!(read_context_document(file_path).await.unwrap(), imported);
+
+        value["document"]["sections"][0]["type"] = "unknown".into();
+        assert!(import_json(file_path, &value.to_string()).await.is_err());
+        close_document(file_path);
+    }
+
+    #[tokio::test]
+    async fn test_export_flow_json() {
+        let xml_content = create_test_xml();
+        let mut temp_file = NamedTempFile::new().unwrap();
+        temp_file.write_all(xml_content.as_bytes()).unwrap();
+        let file_path = temp_file.path().to_str().unwrap();
+
+        let exported: serde_json::Value = serde_json::from_str(&export_flow_json(file_path).await.unwrap()).unwrap();
+        assert_eq!(exported["format"], flow_json_exporter::FLOW_JSON_FORMAT);
+        assert_eq!(exported["nodes"].as_array().unwrap().len(), 3);
+        assert_eq!(exported["edges"][0]["from"], "A");
+    }
+
+    #[tokio::test]
+    async fn test_export_calendar() {
+        let xml_content = create_test_xml().replace("Goal: ${goal}", "Goal: ${goal}\n- Milestone: Beta 2025-10-15");
+        let mut temp_file = NamedTempFile::new().unwrap();
+        temp_file.write_all(xml_content.as_bytes()).unwrap();
+        let file_path = temp_file.path().to_str().unwrap();
+
+        let ics = export_calendar(file_path).await.unwrap();
+        assert!(ics.contains("DTSTART;VALUE=DATE:20251015"));
+        assert!(ics.contains("SUMMARY:Beta"));
+
+        let uids = |ics: &str| ics.lines().filter(|l| l.starts_with("UID:")).map(String::from).collect::<Vec<_>>();
+        assert_eq!(uids(&ics), uids(&export_calendar(file_path).await.unwrap()));
+    }
+
+    #[tokio::test]
+    async fn test_export_docx() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let file_path = dir.path().join("doc.xml");
+        std::fs::write(&file_path, create_test_xml()).unwrap();
+        let destination = dir.path().join("doc.docx");
+
+        export_docx(file_path.to_str().unwrap(), destination.to_str().unwrap(), &Default::default(), &Default::default()).await.unwrap();
+
+        let bytes = std::fs::read(&destination).unwrap();
+        assert!(bytes.starts_with(b"PK"));
+        close_document(file_path.to_str().unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_section_blocks() {
+        let xml_content = create_test_xml().replace("User: ${userName}", "User: ${userName}\n\n---\n\nSecond block");
+        let mut temp_file = NamedTempFile::new().unwrap();
+        temp_file.write_all(xml_content.as_bytes()).unwrap();
+        let file_path = temp_file.path().to_str().unwrap();
+
+        let blocks = get_section_blocks(file_path, "intent-1").await.unwrap();
+        assert_eq!(blocks.len(), 2);
+        assert!(blocks[0].content.contains("${userName}"));
+
+        let blocks = update_section_block(file_path, "intent-1", 1, "Edited ${goal}").await.unwrap();
+        assert_eq!(blocks[1].content, "Edited ${goal}");
+
+        let sections = load_sections(file_path).await.unwrap();
+        assert!(sections[0].content.contains("User: Jeremy"));
+        assert!(sections[0].content.ends_with("Edited Ship v1"));
+
+        // The edit lives in memory until the document is saved
+        assert!(is_document_dirty(file_path));
+        assert!(!std::fs::read_to_string(file_path).unwrap().contains("Edited ${goal}"));
+
+        save_document(file_path).await.unwrap();
+        assert!(!is_document_dirty(file_path));
+        assert!(std::fs::read_to_string(file_path).unwrap().contains("Edited ${goal}"));
+        close_document(file_path);
+    }
+
+    #[tokio::test]
+    async fn test_nested_section_blocks() {
+        let handle = create_nested_scratch();
+
+        let blocks = get_section_blocks(&handle, "intent-2").await.unwrap();
+        assert_eq!(blocks.len(), 2);
+        let blocks = update_section_block(&handle, "intent-2", 1, "Edited").await.unwrap();
+        assert_eq!(blocks[1].content, "Edited");
+        assert!(read_context_document(&handle).await.unwrap().sections[0].children[0].content.ends_with("Edited"));
+
+        document_store::update(&handle, |doc| {
+            doc.sections[0].children[0].encrypted = true;
+            Ok(())
+        })
+        .await
+        .unwrap();
+        let result = update_section_block(&handle, "intent-2", 0, "Plain").await;
+        assert!(matches!(result, Err(ContextError::InvalidArgument(_))));
+        assert!(read_context_document(&handle).await.unwrap().sections[0].children[0].content.ends_with("Edited"));
+        close_document(&handle);
+    }
+
+    #[tokio::test]
+    async fn test_content_mutators_refuse_encrypted_sections() {
+        let handle = create_nested_scratch();
+        document_store::update(&handle, |doc| {
+            doc.sections.push(Section::new("intent-3", "intent", "# More"));
+            Ok(())
+        })
+        .await
+        .unwrap();
+        let original = read_context_document(&handle).await.unwrap().sections[0].children[0].content.clone();
+        encrypt_section(&handle, "intent-2", "pw").await.unwrap();
+        let snippet = Snippet { name: "note".to_string(), description: None, content: "Note".to_string() };
+
+        let refused = [
+            update_section_block(&handle, "intent-2", 0, "Plain").await.err(),
+            insert_snippet(&handle, "intent-2", &snippet, &HashMap::new(), None).await.err(),
+            generate_checklist_from_flow(&handle, Some("intent-2")).await.err(),
+            merge_sections(&handle, &["intent-3".to_string(), "intent-2".to_string()], None).await.err(),
+            split_section(&handle, "intent-2", 2).await.err(),
+        ];
+        assert!(refused.iter().all(|e| matches!(e, Some(ContextError::InvalidArgument(_)))), "{:?}", refused);
+
+        let mut doc = read_context_document(&handle).await.unwrap();
+        doc.flow_graph.as_mut().unwrap().mermaid_code.push_str("\n  class B status-final");
+        assert!(status_sync::sync_status(&mut doc, None).is_empty());
+        let hooks = ScriptHooks::compile(r#"fn on_save(doc) { doc.sections[0].children[0].content += "!"; doc }"#).unwrap();
+        assert!(hooks.on_save(&mut doc).is_err());
+
+        assert_eq!(unlock_section(&handle, "intent-2", "pw").await.unwrap().content, original);
+        close_document(&handle);
+    }
+
+    #[tokio::test]
+    async fn test_insert_snippet() {
+        let xml_content = create_test_xml();
+        let mut temp_file = NamedTempFile::new().unwrap();
+        temp_file.write_all(xml_content.as_bytes()).unwrap();
+        let file_path = temp_file.path().to_str().unwrap();
+        let snippet = Snippet {
+            name: "sign-off".to_string(),
+            description: None,
+            content: "Signed off by ${userName} (${role})".to_string(),
+        };
+
+        let overrides = HashMap::from([("role".to_string(), "lead".to_string())]);
+        let content = insert_snippet(file_path, "intent-1", &snippet, &overrides, None).await.unwrap();
+        assert!(content.ends_with("Goal: ${goal}\n\nSigned off by Jeremy (lead)"));
+        assert!(is_document_dirty(file_path));
+
+        let result = insert_snippet(file_path, "missing", &snippet, &overrides, None).await;
+        assert!(matches!(result, Err(ContextError::SectionNotFound(_))));
+        close_document(file_path);
+
+        let handle = create_nested_scratch();
+        let content = insert_snippet(&handle, "intent-2", &snippet, &overrides, None).await.unwrap();
+        assert!(content.ends_with("Signed off by Jeremy (lead)"));
+
+        document_store::update(&handle, |doc| {
+            doc.sections[0].children[0].encrypted = true;
+            Ok(())
+        })
+        .await
+        .unwrap();
+        let result = insert_snippet(&handle, "intent-2", &snippet, &overrides, None).await;
+        assert!(matches!(result, Err(ContextError::InvalidArgument(_))));
+        close_document(&handle);
+    }
+
+    #[tokio::test]
+    async fn test_load_workspace_metadata() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let cache_dir = dir.path().join("cache");
+        let first = dir.path().join("first.xml");
+        let second = dir.path().join("second.xml");
+        std::fs::write(&first, create_test_xml()).unwrap();
+        std::fs::write(&second, create_test_xml().replace("Test Document", "Second Document")).unwrap();
+        let paths = vec![first.to_str().unwrap().to_string(), second.to_str().unwrap().to_string()];
+
+        let documents = load_workspace_metadata(&paths, &cache_dir).await.unwrap();
+        assert_eq!(documents.len(), 2);
+        assert_eq!(documents[1].meta.title, "Second Document");
+        assert_eq!(documents[0].section_count, 1);
+
+        // Dropping a document from the workspace prunes its cache entry
+        load_workspace_metadata(&paths[..1], &cache_dir).await.unwrap();
+        assert_eq!(std::fs::read_dir(&cache_dir).unwrap().count(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_build_workspace_index() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let first = dir.path().join("first.xml");
+        let second = dir.path().join("second.xml");
+        std::fs::write(&first, create_test_xml()).unwrap();
+        std::fs::write(&second, create_test_xml().replace("<tags>test, doc</tags>", "<tags>other</tags>")).unwrap();
+        let paths = vec![first.to_str().unwrap().to_string(), second.to_str().unwrap().to_string()];
+
+        let index = build_workspace_index(&paths, &dir.path().join("cache")).await.unwrap();
+        assert_eq!(index.documents_by_tag("doc").len(), 1);
+        assert_eq!(index.variable_usages("goal").len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_publish_check() {
+        let mut temp_file = NamedTempFile::new().unwrap();
+        temp_file.write_all(create_test_xml().as_bytes()).unwrap();
+        let file_path = temp_file.path().to_str().unwrap();
+
+        let report = publish_check(file_path).await.unwrap();
+        assert!(report.diagnostics.iter().all(|d| d.code != "schema"));
+
+        let mut broken = NamedTempFile::new().unwrap();
+        broken.write_all(create_test_xml().replace("<sections>", "<sectionz>").as_bytes()).unwrap();
+
+        let report = publish_check(broken.path().to_str().unwrap()).await.unwrap();
+        assert!(!report.ready);
+        assert_eq!(report.diagnostics[0].check, "schema");
+
+        let handle = create_nested_scratch();
+        document_store::update(&handle, |doc| {
+            doc.sections[0].children[0].ref_target = Some("missing-plan.xml#intent-1".to_string());
+            Ok(())
+        })
+        .await
+        .unwrap();
+        let report = publish_check(&handle).await.unwrap();
+        let missing = report.diagnostics.iter().find(|d| d.code == "missing-document").unwrap();
+        assert!(missing.message.contains("Section 'intent-2'"));
+        close_document(&handle);
+    }
+
+    #[tokio::test]
+    async fn test_apply_fixes_recovers_broken_document() {
+        let xml_content = create_test_xml()
+            .replace("<created>2025-10-09</created>", "<created>2025/10/9</created>")
+            .replace("</sections>", r#"<section id="intent-1" type="process"><content>Also</content></section></sections>"#);
+        let mut temp_file = NamedTempFile::new().unwrap();
+        temp_file.write_all(xml_content.as_bytes()).unwrap();
+        let file_path = temp_file.path().to_str().unwrap();
+
+        let report = publish_check(file_path).await.unwrap();
+        let fixable: Vec<String> = report.diagnostics.iter().filter(|d| d.fixable).map(|d| d.id.clone()).collect();
+        assert_eq!(fixable, vec!["duplicate-section-id:intent-1", "invalid-date:created"]);
+
+        let doc = apply_fixes(file_path, &fixable).await.unwrap();
+        assert_eq!(doc.sections[1].id, "intent-1-2");
+        assert_eq!(doc.meta.created, "2025-10-09");
+
+        // The repair is an unsaved edit until the document is saved
+        assert!(is_document_dirty(file_path));
+        assert!(std::fs::read_to_string(file_path).unwrap().contains("2025/10/9"));
+        close_document(file_path);
+    }
+
+    #[tokio::test]
+    async fn test_split_section() {
+        let xml_content = create_test_xml().replace("Goal: ${goal}", "## Goal\n${goal}\n\n## Next Steps\nShip it");
+        let mut temp_file = NamedTempFile::new().unwrap();
+        temp_file.write_all(xml_content.as_bytes()).unwrap();
+        let file_path = temp_file.path().to_str().unwrap();
+
+        let new_ids = split_section(file_path, "intent-1", 2).await.unwrap();
+        assert_eq!(new_ids, vec!["goal", "next-steps"]);
+
+        let sections = load_sections(file_path).await.unwrap();
+        assert_eq!(sections.len(), 3);
+        assert_eq!(sections[1].content, "## Goal\nShip v1");
+        close_document(file_path);
+    }
+
+    #[tokio::test]
+    async fn test_section_frontmatter_is_stripped_from_output() {
+        let xml_content = create_test_xml().replace("# Intent\n", "---\nowner: ${userName}\nstatus: draft\n---\n# Intent\n");
+        let mut temp_file = NamedTempFile::new().unwrap();
+        temp_file.write_all(xml_content.as_bytes()).unwrap();
+        let file_path = temp_file.path().to_str().unwrap();
+
+        let frontmatter = get_section_frontmatter(file_path).await.unwrap();
+        assert_eq!(frontmatter["intent-1"]["status"], "draft");
+        assert_eq!(frontmatter["intent-1"]["owner"], "${userName}");
+
+        let sections = load_sections(file_path).await.unwrap();
+        assert!(sections[0].content.starts_with("# Intent"));
+        let context = assemble_context(file_path, &LoadOptions::default(), &HashMap::new()).await.unwrap();
+        assert!(!context.contains("status: draft"));
+        close_document(file_path);
+    }
+
+    #[tokio::test]
+    async fn test_encrypted_section_is_locked_until_unlocked() {
+        let xml_content = create_test_xml();
+        let mut temp_file = NamedTempFile::new().unwrap();
+        temp_file.write_all(xml_content.as_bytes()).unwrap();
+        let file_path = temp_file.path().to_str().unwrap();
+
+        encrypt_section(file_path, "intent-1", "pw").await.unwrap();
+        save_document(file_path).await.unwrap();
+        assert!(std::fs::read_to_string(file_path).unwrap().contains("encrypted=\"true\""));
+        assert!(!std::fs::read_to_string(file_path).unwrap().contains("User: ${userName}"));
+
+        let sections = load_sections(file_path).await.unwrap();
+        assert_eq!(sections[0].content, section_encryption::LOCKED_PLACEHOLDER);
+        let unlocked = unlock_section(file_path, "intent-1", "pw").await.unwrap();
+        assert!(unlocked.content.contains("User: ${userName}"));
+        assert!(unlock_section(file_path, "intent-1", "wrong").await.is_err());
+
+        decrypt_section(file_path, "intent-1", "pw").await.unwrap();
+        assert!(load_sections(file_path).await.unwrap()[0].content.contains("User: Jeremy"));
+        close_document(file_path);
+    }
+
+    #[tokio::test]
+    async fn test_merge_conflict_copy() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let file_path = dir.path().join("plan.xml");
+        let copy_path = dir.path().join("plan (conflicted copy 2025-10-10).xml");
+        std::fs::write(&file_path, create_test_xml()).unwrap();
+        std::fs::write(&copy_path, create_test_xml().replace("Goal: ${goal}", "Goal: ${goal} soon")).unwrap();
+        let file_path = file_path.to_str().unwrap();
+        let copy_path = copy_path.to_str().unwrap();
+
+        let copies = list_conflict_copies(file_path).await.unwrap();
+        assert_eq!(copies.len(), 1);
+        assert_eq!(copies[0].file_path, copy_path);
+
+        // With the saved file as base, the copy's edit merges cleanly
+        let result = merge_conflict_copy(file_path, copy_path, Some(file_path), &HashMap::new()).await.unwrap();
+        assert!(result.conflicts.is_empty());
+        let doc = read_context_document(file_path).await.unwrap();
+        assert!(doc.sections[0].content.contains("Goal: ${goal} soon"));
+        assert!(is_document_dirty(file_path));
+
+        close_document(file_path);
+    }
+
+    #[tokio::test]
+    async fn test_import_sections() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let source_path = dir.path().join("source.xml");
+        let target_path = dir.path().join("target.xml");
+        std::fs::write(&source_path, create_test_xml()).unwrap();
+        std::fs::write(&target_path, create_test_xml().replace(r#"<var name="goal">Ship v1</var>"#, "")).unwrap();
+
+        let result = import_sections(
+            target_path.to_str().unwrap(),
+            source_path.to_str().unwrap(),
+            &["intent-1".to_string()],
+            true,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(result.imported[0].new_id, "intent-1-2");
+        assert_eq!(result.variables_added, vec!["goal".to_string()]);
+
+        let target = read_context_document(target_path.to_str().unwrap()).await.unwrap();
+        assert_eq!(target.sections.len(), 2);
+        assert!(target.sections[1].content.contains("${goal}"));
+        assert_eq!(target.flow_graph.unwrap().id, "flow-1");
+    }
+
+    #[tokio::test]
+    async fn test_load_nonexistent_file() {
+        let result = load_context_document("/nonexistent/file.xml").await;
+        assert!(result.is_err());
+
+        if let Err(e) = result {
+            match e {
+                ContextError::IoError(_) => {},
+                _ => panic!("Expected IoError, got: {:?}", e),
+            }
+        }
+    }
+}
@@ -0,0 +1,52 @@
+use crate::models::ContextDocument;
+use crate::processors::locale_format::{self, LocaleFormat};
+use crate::services::settings;
+use std::collections::HashMap;
+
+/// Custom meta field that sets a document's locale, overriding the `locale` setting
+pub const LOCALE_META_FIELD: &str = "locale";
+
+/// Locale for the document's rendered output: its `locale` meta field, else the `locale` setting
+pub fn document_locale(doc: &ContextDocument) -> Option<String> {
+    doc.meta
+        .extra
+        .get(LOCALE_META_FIELD)
+        .map(|locale| locale.trim())
+        .filter(|locale| !locale.is_empty())
+        .map(str::to_string)
+        .or_else(|| settings::current_settings().locale)
+}
+
+/// Date and number conventions for the document; ISO when no locale is set
+pub fn document_format(doc: &ContextDocument) -> LocaleFormat {
+    document_locale(doc)
+        .map(|locale| LocaleFormat::for_locale(&locale))
+        .unwrap_or_default()
+}
+
+/// Built-in variables such as `${date.today}` as of now, in the document's locale
+pub fn builtin_variables(doc: &ContextDocument) -> HashMap<String, String> {
+    locale_format::builtin_variables(&document_format(doc), chrono::Local::now().date_naive())
+}
+
+/// Today's date in the document's locale, for dating exports
+pub fn today(doc: &ContextDocument) -> String {
+    document_format(doc).format_date(chrono::Local::now().date_naive())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_meta_field_sets_locale() {
+        let mut doc = ContextDocument::builder().title("Plan").build().unwrap();
+        doc.meta.extra.insert(LOCALE_META_FIELD.to_string(), " de-DE ".to_string());
+
+        assert_eq!(document_locale(&doc).as_deref(), Some("de-DE"));
+        assert_eq!(document_format(&doc), LocaleFormat::for_locale("de"));
+        let today = chrono::Local::now().date_naive();
+        assert_eq!(builtin_variables(&doc)["date.today"], today.format("%d.%m.%Y").to_string());
+        assert_eq!(builtin_variables(&doc)["date.iso"], today.format("%Y-%m-%d").to_string());
+    }
+}
@@ -0,0 +1,26 @@
+pub mod app_config;
+pub mod assembly_history;
+pub mod backup_service;
+pub mod binary_cache;
+pub mod change_journal;
+pub mod conflict_copies;
+pub mod default_documents;
+pub mod dependency_watch;
+pub mod document_store;
+pub mod dry_run;
+pub mod flow_service;
+pub mod formatting;
+pub mod quick_capture;
+pub mod reminders;
+pub mod remote_documents;
+pub mod section_activity;
+pub mod settings;
+pub mod snippets;
+pub mod stats_history;
+pub mod template_library;
+pub mod transclusion_service;
+pub mod trash;
+pub mod usage_metrics;
+
+pub use flow_service::*;
+pub use transclusion_service::*;
@@ -0,0 +1,173 @@
+use crate::error::{ContextError, Result};
+use crate::models::*;
+use crate::processors::document_edits::{ensure_not_encrypted, find_section_mut};
+use crate::processors::portable_format::platform_path;
+use crate::services::change_journal::{self, JournalOperation};
+use crate::services::{document_store, flow_service};
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// Inbox document in the documents folder, used when the `inbox` setting is unset
+pub const DEFAULT_INBOX_DOCUMENT: &str = "inbox.xml";
+
+/// Section notes go to when the inbox setting names only a document
+pub const INBOX_SECTION_ID: &str = "inbox";
+
+const TIMESTAMP_FORMAT: &str = "%Y-%m-%d %H:%M";
+
+/// Where a captured note was stored
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct CapturedNote {
+    pub file_path: String,
+    pub section_id: String,
+    /// Local time of the capture, `YYYY-MM-DD HH:MM`
+    pub timestamp: String,
+}
+
+/// The inbox document and section named by the `inbox` setting
+///
+/// The setting is `path#section-id`, or a bare path for the document's
+/// [`INBOX_SECTION_ID`] section; relative paths are in `documents_dir`.
+/// Unset, the inbox is [`DEFAULT_INBOX_DOCUMENT`] in `documents_dir`.
+pub fn inbox_target(setting: Option<&str>, documents_dir: &Path) -> (PathBuf, String) {
+    let (path, section_id) = match setting.map(str::trim).filter(|setting| !setting.is_empty()) {
+        Some(setting) => match setting.rsplit_once('#') {
+            Some((path, section_id)) if !section_id.is_empty() => (path, section_id),
+            _ => (setting.trim_end_matches('#'), INBOX_SECTION_ID),
+        },
+        None => (DEFAULT_INBOX_DOCUMENT, INBOX_SECTION_ID),
+    };
+    (documents_dir.join(platform_path(path)), section_id.to_string())
+}
+
+/// Append a timestamped note to the inbox section and save the inbox document
+///
+/// The document is created, and the section added, when missing. Unsaved
+/// edits to an inbox document that is open in the editor are saved with the
+/// note.
+pub async fn quick_capture(file_path: &str, section_id: &str, text: &str) -> Result<CapturedNote> {
+    if text.trim().is_empty() {
+        return Err(ContextError::InvalidArgument("Nothing to capture".to_string()));
+    }
+    if !tokio::fs::try_exists(file_path).await? {
+        create_inbox(file_path, section_id).await?;
+    }
+
+    let now = chrono::Local::now().naive_local();
+    document_store::update(file_path, |doc| {
+        let section = match find_section_mut(&mut doc.sections, section_id) {
+            Some(section) => section,
+            None => {
+                doc.sections.push(inbox_section(section_id));
+                doc.sections.last_mut().unwrap()
+            }
+        };
+        ensure_not_encrypted(section)?;
+        section.content = append_note(&section.content, &format_note(text, now));
+        Ok(())
+    })
+    .await?;
+    flow_service::save_document(file_path).await?;
+    change_journal::record(file_path, JournalOperation::QuickCapture, Some(section_id.to_string())).await;
+
+    Ok(CapturedNote {
+        file_path: file_path.to_string(),
+        section_id: section_id.to_string(),
+        timestamp: now.format(TIMESTAMP_FORMAT).to_string(),
+    })
+}
+
+/// A list item `- **2025-10-09 14:03** text`, with later lines of the text indented under it
+pub fn format_note(text: &str, timestamp: NaiveDateTime) -> String {
+    let mut lines = text.trim().lines();
+    let mut note = format!("- **{}** {}", timestamp.format(TIMESTAMP_FORMAT), lines.next().unwrap_or_default());
+    for line in lines {
+        note.push('\n');
+        if !line.trim().is_empty() {
+            note.push_str("  ");
+            note.push_str(line);
+        }
+    }
+    note
+}
+
+/// Content with the note added at the end, in the same list as a preceding note
+pub fn append_note(content: &str, note: &str) -> String {
+    let content = content.trim_end();
+    if content.is_empty() {
+        return note.to_string();
+    }
+    let in_list = content.lines().last().is_some_and(|line| line.starts_with("- ") || line.starts_with("  "));
+    format!("{}{}{}", content, if in_list { "\n" } else { "\n\n" }, note)
+}
+
+fn inbox_section(section_id: &str) -> Section {
+    Section::new(section_id, "intent", "# Inbox")
+}
+
+async fn create_inbox(file_path: &str, section_id: &str) -> Result<()> {
+    if let Some(dir) = Path::new(file_path).parent() {
+        tokio::fs::create_dir_all(dir).await?;
+    }
+    let doc = ContextDocument::builder()
+        .title("Inbox")
+        .description("Notes captured with the quick capture shortcut, to be sorted into documents")
+        .add_section(inbox_section(section_id))
+        .build()?;
+    flow_service::save_context_document(file_path, &doc).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(timestamp: &str) -> NaiveDateTime {
+        NaiveDateTime::parse_from_str(timestamp, TIMESTAMP_FORMAT).unwrap()
+    }
+
+    #[test]
+    fn test_notes_join_one_list() {
+        let first = format_note("  Call the vendor ", at("2025-10-09 14:03"));
+        assert_eq!(first, "- **2025-10-09 14:03** Call the vendor");
+
+        let content = append_note("# Inbox\n", &first);
+        assert_eq!(content, "# Inbox\n\n- **2025-10-09 14:03** Call the vendor");
+
+        let second = format_note("Idea:\n\nsplit the flow", at("2025-10-09 15:30"));
+        assert_eq!(
+            append_note(&content, &second),
+            "# Inbox\n\n- **2025-10-09 14:03** Call the vendor\n- **2025-10-09 15:30** Idea:\n\n  split the flow"
+        );
+    }
+
+    #[test]
+    fn test_inbox_target() {
+        let dir = Path::new("/docs");
+        assert_eq!(inbox_target(None, dir), (dir.join("inbox.xml"), "inbox".to_string()));
+        assert_eq!(inbox_target(Some("plans/ideas.xml#later"), dir), (dir.join("plans/ideas.xml"), "later".to_string()));
+        assert_eq!(inbox_target(Some("/notes/inbox.cec"), dir), (PathBuf::from("/notes/inbox.cec"), "inbox".to_string()));
+    }
+
+    #[tokio::test]
+    async fn test_capture_creates_and_appends_to_inbox() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("notes").join("inbox.xml");
+        let file_path = path.to_str().unwrap();
+
+        let captured = quick_capture(file_path, "inbox", "First idea").await.unwrap();
+        assert_eq!(captured.section_id, "inbox");
+        quick_capture(file_path, "later", "Second idea").await.unwrap();
+        assert!(matches!(quick_capture(file_path, "inbox", "  ").await, Err(ContextError::InvalidArgument(_))));
+
+        flow_service::close_document(file_path);
+        let doc = flow_service::read_context_document(file_path).await.unwrap();
+        assert_eq!(doc.meta.title, "Inbox");
+        assert_eq!(doc.sections.len(), 2);
+        assert!(doc.sections[0].content.starts_with("# Inbox\n\n- **"));
+        assert!(doc.sections[0].content.ends_with("** First idea"));
+        assert!(doc.sections[1].content.ends_with("** Second idea"));
+        flow_service::close_document(file_path);
+    }
+}
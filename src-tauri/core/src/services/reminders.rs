@@ -0,0 +1,129 @@
+use crate::error::Result;
+use crate::processors::reminders::{self, Reminder};
+use crate::services::binary_cache::BinaryCache;
+use crate::services::flow_service;
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// How many days ahead `get_reminders` looks when the caller does not say
+pub const DEFAULT_REMINDER_WINDOW_DAYS: i64 = 7;
+
+/// A document with overdue items, as reported at startup
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct OverdueDocument {
+    pub file_path: String,
+    pub title: String,
+    pub reminders: Vec<Reminder>,
+}
+
+/// Overdue items and those due in the next `within_days` days (local time), soonest first
+///
+/// Reads the in-memory document, so unsaved due dates count.
+pub async fn get_reminders(file_path: &str, within_days: Option<i64>) -> Result<Vec<Reminder>> {
+    let doc = flow_service::read_context_document(file_path).await?;
+    let within_days = within_days.unwrap_or(DEFAULT_REMINDER_WINDOW_DAYS);
+    Ok(reminders::collect_reminders(&doc, today(), within_days))
+}
+
+/// The documents among `file_paths` with overdue items, for a notice when the app starts
+///
+/// Uses the parsed-document cache like the workspace listing. Documents
+/// that fail to load are skipped; the check should never block startup.
+pub async fn find_overdue(file_paths: &[String], cache_dir: &Path, today: NaiveDate) -> Vec<OverdueDocument> {
+    let cache = BinaryCache::new(cache_dir);
+    let mut overdue = Vec::new();
+    for file_path in file_paths {
+        let doc = match cache.load(file_path).await {
+            Ok(loaded) => loaded.document,
+            Err(e) => {
+                eprintln!("Skipping {} in the overdue check: {}", file_path, e);
+                continue;
+            }
+        };
+        let reminders: Vec<Reminder> = reminders::collect_reminders(&doc, today, 0)
+            .into_iter()
+            .filter(|reminder| reminder.overdue)
+            .collect();
+        if !reminders.is_empty() {
+            overdue.push(OverdueDocument {
+                file_path: file_path.clone(),
+                title: doc.meta.title,
+                reminders,
+            });
+        }
+    }
+    overdue
+}
+
+pub fn today() -> NaiveDate {
+    chrono::Local::now().date_naive()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use tempfile::{NamedTempFile, TempDir};
+
+    fn document(title: &str, due: &str) -> NamedTempFile {
+        let xml = format!(
+            r#"<context version="1.0">
+    <meta>
+        <title>{}</title>
+        <author>Author</author>
+        <created>2025-10-09</created>
+        <app name="CEC" version="0.1.0"/>
+        <tags>test</tags>
+        <description>Test</description>
+    </meta>
+    <variables><var name="budgetOwner" due="{}">Kim</var></variables>
+    <sections>
+        <section id="intent-1" type="intent">
+            <content><![CDATA[---
+due: 2030-01-01
+---
+# Intent]]></content>
+        </section>
+    </sections>
+</context>"#,
+            title, due
+        );
+        let mut file = NamedTempFile::new().unwrap();
+        file.write_all(xml.as_bytes()).unwrap();
+        file
+    }
+
+    #[tokio::test]
+    async fn test_get_reminders() {
+        let file = document("Plan", "2000-01-01");
+        let file_path = file.path().to_str().unwrap();
+
+        let reminders = get_reminders(file_path, None).await.unwrap();
+        assert_eq!(reminders.len(), 1);
+        assert_eq!(reminders[0].variable.as_deref(), Some("budgetOwner"));
+        assert!(reminders[0].overdue);
+
+        let all = get_reminders(file_path, Some(365 * 100)).await.unwrap();
+        assert_eq!(all.len(), 2);
+        flow_service::close_document(file_path);
+    }
+
+    #[tokio::test]
+    async fn test_find_overdue() {
+        let cache_dir = TempDir::new().unwrap();
+        let late = document("Late", "2025-10-01");
+        let on_time = document("On time", "2025-12-01");
+        let paths = vec![
+            late.path().to_str().unwrap().to_string(),
+            on_time.path().to_str().unwrap().to_string(),
+            "/nonexistent/plan.xml".to_string(),
+        ];
+
+        let overdue = find_overdue(&paths, cache_dir.path(), NaiveDate::from_ymd_opt(2025, 11, 1).unwrap()).await;
+        assert_eq!(overdue.len(), 1);
+        assert_eq!(overdue[0].title, "Late");
+        assert_eq!(overdue[0].reminders[0].key, "var:budgetOwner");
+    }
+}
@@ -0,0 +1,218 @@
+use crate::error::{ContextError, Result};
+use crate::parsers::input_normalizer;
+use crate::services::binary_cache::content_hash;
+use crate::services::{default_documents, document_store, flow_service};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use tokio::fs;
+
+/// Folder of the managed copies of downloaded documents, inside the app data directory
+pub const REMOTE_FOLDER: &str = "remote";
+
+/// Largest document accepted from a URL
+pub const MAX_REMOTE_DOCUMENT_BYTES: usize = 5 * 1024 * 1024;
+
+/// Content types accepted from a URL; raw file hosts often serve XML as plain text
+pub const ACCEPTED_CONTENT_TYPES: &[&str] = &["application/xml", "text/xml", "text/plain", "application/octet-stream"];
+
+/// A document downloaded into the managed remote-documents folder
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct RemoteDocument {
+    pub url: String,
+    pub file_path: String,
+    /// SHA-256 of the downloaded bytes
+    pub hash: String,
+    pub read_only: bool,
+}
+
+/// Only plain https URLs with a host are fetched
+pub fn validate_url(url: &str) -> Result<()> {
+    let rest = url
+        .strip_prefix("https://")
+        .ok_or_else(|| ContextError::InvalidArgument(format!("Only https URLs can be opened: {}", url)))?;
+    let host = rest.split(['/', '?', '#']).next().unwrap_or("");
+    if host.is_empty() || host.contains('@') || url.chars().any(char::is_whitespace) {
+        return Err(ContextError::InvalidArgument(format!("Invalid URL: {}", url)));
+    }
+    Ok(())
+}
+
+/// Check the `Content-Type` header; a missing header is accepted
+pub fn check_content_type(content_type: Option<&str>) -> Result<()> {
+    let Some(content_type) = content_type else {
+        return Ok(());
+    };
+    let mime = content_type.split(';').next().unwrap_or("").trim().to_ascii_lowercase();
+    if ACCEPTED_CONTENT_TYPES.contains(&mime.as_str()) || mime.ends_with("+xml") {
+        Ok(())
+    } else {
+        Err(ContextError::InvalidArgument(format!("Unexpected content type '{}'", content_type)))
+    }
+}
+
+/// Check, store and open a downloaded document read-only
+///
+/// The bytes must be a valid context document. The copy is named after the
+/// URL's file name and content hash, so downloading the same content again
+/// reuses it while a changed document gets a new file. Every document in
+/// `dir` is read-only; only a fork can be edited.
+pub async fn store_remote_document(dir: &Path, url: &str, bytes: &[u8]) -> Result<RemoteDocument> {
+    validate_url(url)?;
+    if bytes.len() > MAX_REMOTE_DOCUMENT_BYTES {
+        return Err(ContextError::InvalidArgument(format!(
+            "Document is larger than {} bytes",
+            MAX_REMOTE_DOCUMENT_BYTES
+        )));
+    }
+    let (xml_content, _) = input_normalizer::decode_input(bytes)?;
+    flow_service::parse_context_document(&xml_content)?;
+
+    let hash = content_hash(bytes);
+    let path = dir.join(format!("{}-{}", &hash[..12], file_name(url)));
+    fs::create_dir_all(dir).await?;
+    if !fs::try_exists(&path).await? {
+        fs::write(&path, bytes).await?;
+    }
+
+    document_store::set_read_only_dir(dir);
+    Ok(RemoteDocument {
+        url: url.to_string(),
+        file_path: path.to_string_lossy().into_owned(),
+        hash,
+        read_only: true,
+    })
+}
+
+/// Copy a downloaded document to `destination` as an editable local document
+///
+/// An existing file at `destination` is never overwritten.
+pub async fn fork_remote_document(file_path: &str, destination: &str) -> Result<String> {
+    if fs::try_exists(destination).await? {
+        return Err(ContextError::InvalidArgument(format!("{} already exists", destination)));
+    }
+    if let Some(parent) = Path::new(destination).parent() {
+        fs::create_dir_all(parent).await?;
+    }
+    fs::copy(file_path, destination).await?;
+    Ok(destination.to_string())
+}
+
+/// Last path segment of the URL as a safe file name, keeping a document
+/// extension such as `.cec` and defaulting to `.xml`
+fn file_name(url: &str) -> String {
+    let path = url.split(['?', '#']).next().unwrap_or(url);
+    let last = path.rsplit('/').next().unwrap_or("");
+    let (stem, extension) = match last.rsplit_once('.') {
+        Some((stem, ext)) if default_documents::is_document_path(Path::new(last)) => (stem, ext.to_lowercase()),
+        _ => (last, "xml".to_string()),
+    };
+    let stem: String = stem
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.') { c } else { '_' })
+        .collect();
+    let stem = stem.trim_matches('.');
+    format!("{}.{}", if stem.is_empty() { "document" } else { stem }, extension)
+}
+
+/// Where a fork goes when no destination is given: the original file name in `dir`
+pub fn default_fork_path(file_path: &str, dir: &Path) -> PathBuf {
+    let name = Path::new(file_path)
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    // Drop the content-hash prefix added by `store_remote_document`
+    let name = match name.split_once('-') {
+        Some((prefix, rest)) if prefix.len() == 12 && prefix.chars().all(|c| c.is_ascii_hexdigit()) => rest.to_string(),
+        _ => name,
+    };
+    dir.join(name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::ContextDocument;
+    use crate::serializers::xml_serializer::serialize_xml;
+
+    fn document_bytes() -> Vec<u8> {
+        let doc = ContextDocument::builder()
+            .title("Shared")
+            .section("intent-1", "intent", "Shared intent")
+            .build()
+            .unwrap();
+        serialize_xml(&doc).into_bytes()
+    }
+
+    #[test]
+    fn test_validate_url() {
+        assert!(validate_url("https://example.com/docs/a.xml").is_ok());
+        assert!(validate_url("http://example.com/a.xml").is_err());
+        assert!(validate_url("file:///etc/passwd").is_err());
+        assert!(validate_url("https:///a.xml").is_err());
+        assert!(validate_url("https://user@example.com/a.xml").is_err());
+    }
+
+    #[test]
+    fn test_check_content_type() {
+        assert!(check_content_type(Some("application/xml; charset=utf-8")).is_ok());
+        assert!(check_content_type(Some("Text/Plain")).is_ok());
+        assert!(check_content_type(Some("application/atom+xml")).is_ok());
+        assert!(check_content_type(None).is_ok());
+        assert!(check_content_type(Some("text/html")).is_err());
+    }
+
+    #[test]
+    fn test_file_name() {
+        assert_eq!(file_name("https://example.com/docs/plan.xml?raw=1"), "plan.xml");
+        assert_eq!(file_name("https://example.com/"), "document.xml");
+        assert_eq!(file_name("https://example.com/a b/../x y"), "x_y.xml");
+        assert_eq!(file_name("https://example.com/shared/plan.CEC"), "plan.cec");
+    }
+
+    #[tokio::test]
+    async fn test_store_opens_read_only_and_fork_is_editable() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let remote = store_remote_document(&dir.path().join(REMOTE_FOLDER), "https://example.com/shared.xml", &document_bytes())
+            .await
+            .unwrap();
+
+        assert!(remote.file_path.ends_with("-shared.xml"));
+        assert_eq!(default_fork_path(&remote.file_path, dir.path()), dir.path().join("shared.xml"));
+        let edit = document_store::update(&remote.file_path, |doc| {
+            doc.meta.title = "Changed".to_string();
+            Ok(())
+        })
+        .await;
+        assert!(matches!(edit, Err(ContextError::InvalidArgument(_))));
+        // Still read-only once the store has forgotten the document, e.g. after a restart
+        document_store::close(&remote.file_path);
+        let doc = flow_service::read_document_from_disk(&remote.file_path).await.unwrap().0;
+        assert!(flow_service::save_context_document(&remote.file_path, &doc).await.is_err());
+        assert!(document_store::is_read_only(dir.path().join(REMOTE_FOLDER).join("other.xml").to_str().unwrap()));
+
+        let fork = dir.path().join("mine").join("shared.xml");
+        let fork = fork_remote_document(&remote.file_path, fork.to_str().unwrap()).await.unwrap();
+        document_store::update(&fork, |doc| {
+            doc.meta.title = "Mine".to_string();
+            Ok(())
+        })
+        .await
+        .unwrap();
+        assert!(fork_remote_document(&remote.file_path, &fork).await.is_err());
+
+        document_store::close(&fork);
+        document_store::close(&remote.file_path);
+    }
+
+    #[tokio::test]
+    async fn test_rejects_invalid_downloads() {
+        let dir = tempfile::TempDir::new().unwrap();
+
+        let not_a_document = store_remote_document(dir.path(), "https://example.com/a.xml", b"<html></html>").await;
+        assert!(not_a_document.is_err());
+
+        let too_large = vec![b' '; MAX_REMOTE_DOCUMENT_BYTES + 1];
+        assert!(store_remote_document(dir.path(), "https://example.com/a.xml", &too_large).await.is_err());
+    }
+}
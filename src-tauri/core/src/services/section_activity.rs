@@ -0,0 +1,277 @@
+use crate::error::{ContextError, Result};
+use crate::models::*;
+use crate::processors::content_summary::first_heading;
+use crate::processors::flow_navigation::flatten;
+use crate::services::assembly_history::history_dir;
+use crate::services::{document_store, flow_service, settings};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use tokio::fs;
+
+/// Activity file inside the document's history folder
+pub const ACTIVITY_FILE: &str = "activity.json";
+
+/// Days without an edit after which `get_section_activity` calls a section stale, unless told otherwise
+pub const DEFAULT_STALE_AFTER_DAYS: i64 = 90;
+
+/// How often one section was viewed and edited, as stored in the activity file
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase", default)]
+pub struct SectionCounters {
+    pub views: u64,
+    pub edits: u64,
+    /// RFC 3339 timestamps
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_viewed: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_edited: Option<String>,
+    /// `Name <email>` from the `author` setting at the last edit
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_edited_by: Option<String>,
+}
+
+/// Counters by section ID
+pub type ActivityLog = BTreeMap<String, SectionCounters>;
+
+/// One section's activity, for a heat map of the canvas
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SectionActivity {
+    pub section_id: String,
+    /// The section's first heading, else its ID
+    pub title: String,
+    pub views: u64,
+    pub edits: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_viewed: Option<String>,
+    /// Last recorded edit, else the file's modification time
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_modified: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_edited_by: Option<String>,
+    /// Views plus edits relative to the busiest section, from 0 to 1
+    pub heat: f64,
+    pub stale: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub warning: Option<String>,
+}
+
+pub fn activity_path(file_path: &str) -> PathBuf {
+    history_dir(file_path).join(ACTIVITY_FILE)
+}
+
+/// Count a view of the section when the `sectionActivity` setting is on
+pub async fn record_view(file_path: &str, section_id: &str) -> Result<()> {
+    if !settings::current_settings().section_activity {
+        return Ok(());
+    }
+    let now = Utc::now().to_rfc3339();
+    update_log(file_path, |log| {
+        let counters = log.entry(section_id.to_string()).or_default();
+        counters.views += 1;
+        counters.last_viewed = Some(now);
+    })
+    .await
+}
+
+/// Count an edit of each of the sections, e.g. those changed by a save, stamped with the `author` setting
+pub async fn record_edits(file_path: &str, section_ids: &[String]) -> Result<()> {
+    if section_ids.is_empty() {
+        return Ok(());
+    }
+    let now = Utc::now().to_rfc3339();
+    let author = settings::current_settings().author.signature();
+    update_log(file_path, |log| {
+        for section_id in section_ids {
+            let counters = log.entry(section_id.clone()).or_default();
+            counters.edits += 1;
+            counters.last_edited = Some(now.clone());
+            counters.last_edited_by = author.clone();
+        }
+    })
+    .await
+}
+
+/// Recorded counters; empty when nothing was recorded yet
+pub async fn read_activity(file_path: &str) -> Result<ActivityLog> {
+    match fs::read_to_string(activity_path(file_path)).await {
+        Ok(json) => serde_json::from_str(&json).map_err(|e| ContextError::SerializationError(e.to_string())),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(ActivityLog::new()),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Activity of every section in the document, at any depth, in document order
+///
+/// Sections not edited for `stale_after_days` days (default
+/// [`DEFAULT_STALE_AFTER_DAYS`]) are stale. Sections without a recorded edit
+/// are judged by the file's modification time.
+pub async fn get_section_activity(file_path: &str, stale_after_days: Option<i64>) -> Result<Vec<SectionActivity>> {
+    let doc = flow_service::read_context_document(file_path).await?;
+    let log = read_activity(file_path).await?;
+    let file_modified = fs::metadata(file_path)
+        .await
+        .and_then(|metadata| metadata.modified())
+        .ok()
+        .map(DateTime::<Utc>::from);
+    Ok(summarize(&doc, &log, file_modified, Utc::now(), stale_after_days.unwrap_or(DEFAULT_STALE_AFTER_DAYS)))
+}
+
+/// IDs of the sections whose content, translations or type differ from `previous`, or that are new
+pub fn edited_sections(previous: Option<&ContextDocument>, doc: &ContextDocument) -> Vec<String> {
+    let previous: BTreeMap<&str, &Section> = previous
+        .map(|previous| flatten(&previous.sections).into_iter().map(|section| (section.id.as_str(), section)).collect())
+        .unwrap_or_default();
+    flatten(&doc.sections)
+        .into_iter()
+        .filter(|section| {
+            previous.get(section.id.as_str()).is_none_or(|before| {
+                before.content != section.content || before.translations != section.translations || before.section_type != section.section_type
+            })
+        })
+        .map(|section| section.id.clone())
+        .collect()
+}
+
+fn summarize(
+    doc: &ContextDocument,
+    log: &ActivityLog,
+    file_modified: Option<DateTime<Utc>>,
+    now: DateTime<Utc>,
+    stale_after_days: i64,
+) -> Vec<SectionActivity> {
+    let sections = flatten(&doc.sections);
+    let busiest = sections
+        .iter()
+        .filter_map(|section| log.get(&section.id))
+        .map(|counters| counters.views + counters.edits)
+        .max()
+        .unwrap_or(0);
+
+    sections
+        .into_iter()
+        .map(|section| {
+            let counters = log.get(&section.id).cloned().unwrap_or_default();
+            let last_modified = counters
+                .last_edited
+                .as_deref()
+                .and_then(|timestamp| DateTime::parse_from_rfc3339(timestamp).ok())
+                .map(|timestamp| timestamp.with_timezone(&Utc))
+                .or(file_modified);
+            let idle_days = last_modified.map(|modified| (now - modified).num_days());
+            let stale = idle_days.is_some_and(|days| days >= stale_after_days);
+            SectionActivity {
+                section_id: section.id.clone(),
+                title: first_heading(&section.content).unwrap_or_else(|| section.id.clone()),
+                views: counters.views,
+                edits: counters.edits,
+                last_viewed: counters.last_viewed,
+                last_modified: last_modified.map(|modified| modified.to_rfc3339()),
+                last_edited_by: counters.last_edited_by,
+                heat: if busiest == 0 { 0.0 } else { (counters.views + counters.edits) as f64 / busiest as f64 },
+                stale,
+                warning: stale.then(|| format!("Not edited in {} days", idle_days.unwrap_or_default())),
+            }
+        })
+        .collect()
+}
+
+async fn update_log(file_path: &str, change: impl FnOnce(&mut ActivityLog)) -> Result<()> {
+    if document_store::is_scratch(file_path) {
+        return Ok(());
+    }
+    let mut log = read_activity(file_path).await?;
+    change(&mut log);
+    let json = serde_json::to_string_pretty(&log).map_err(|e| ContextError::SerializationError(e.to_string()))?;
+
+    let path = activity_path(file_path);
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).await?;
+    }
+    let temp_path = path.with_extension("json.tmp");
+    fs::write(&temp_path, json).await?;
+    fs::rename(&temp_path, &path).await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn document() -> ContextDocument {
+        let mut process = Section::new("process-1", "process", "# Build");
+        process.children.push(Section::new("process-2", "process", "Steps"));
+        ContextDocument::builder()
+            .title("Plan")
+            .section("intent-1", "intent", "# Intent")
+            .add_section(process)
+            .build()
+            .unwrap()
+    }
+
+    fn at(timestamp: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(timestamp).unwrap().with_timezone(&Utc)
+    }
+
+    #[test]
+    fn test_summarize_heat_and_staleness() {
+        let mut log = ActivityLog::new();
+        log.insert(
+            "intent-1".to_string(),
+            SectionCounters {
+                views: 6,
+                edits: 2,
+                last_edited: Some("2025-10-01T09:00:00Z".to_string()),
+                last_edited_by: Some("Dana <dana@example.com>".to_string()),
+                ..Default::default()
+            },
+        );
+        log.insert("process-2".to_string(), SectionCounters { views: 2, ..Default::default() });
+        log.insert("deleted-1".to_string(), SectionCounters { views: 50, ..Default::default() });
+
+        let activity = summarize(&document(), &log, Some(at("2025-01-01T00:00:00Z")), at("2025-10-31T00:00:00Z"), 90);
+
+        let ids: Vec<_> = activity.iter().map(|a| a.section_id.as_str()).collect();
+        assert_eq!(ids, vec!["intent-1", "process-1", "process-2"]);
+        assert_eq!(activity[0].heat, 1.0);
+        assert_eq!(activity[2].heat, 0.25);
+        assert_eq!(activity[1].title, "Build");
+        assert!(!activity[0].stale);
+        assert!(activity[1].stale);
+        assert_eq!(activity[1].warning.as_deref(), Some("Not edited in 303 days"));
+        assert_eq!(activity[1].last_modified.as_deref(), Some("2025-01-01T00:00:00+00:00"));
+        assert_eq!(activity[0].last_edited_by.as_deref(), Some("Dana <dana@example.com>"));
+        assert_eq!(activity[1].last_edited_by, None);
+    }
+
+    #[test]
+    fn test_edited_sections() {
+        let previous = document();
+        let mut doc = previous.clone();
+        doc.sections[1].children[0].content = "More steps".to_string();
+        doc.sections.push(Section::new("evaluation-1", "evaluation", ""));
+        doc.meta.title = "Renamed".to_string();
+
+        assert_eq!(edited_sections(Some(&previous), &doc), vec!["process-2", "evaluation-1"]);
+        assert_eq!(edited_sections(None, &doc).len(), 4);
+    }
+
+    #[tokio::test]
+    async fn test_record_edits_accumulates() {
+        let dir = tempfile::tempdir().unwrap();
+        let file_path = dir.path().join("plan.xml");
+        let file_path = file_path.to_str().unwrap();
+
+        assert!(read_activity(file_path).await.unwrap().is_empty());
+        record_edits(file_path, &["intent-1".to_string()]).await.unwrap();
+        record_edits(file_path, &["intent-1".to_string(), "process-1".to_string()]).await.unwrap();
+
+        let log = read_activity(file_path).await.unwrap();
+        assert_eq!(log["intent-1"].edits, 2);
+        assert_eq!(log["process-1"].edits, 1);
+        assert!(log["intent-1"].last_edited.is_some());
+        assert_eq!(log["intent-1"].views, 0);
+    }
+}
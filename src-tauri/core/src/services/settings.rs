@@ -0,0 +1,295 @@
+use crate::error::{ContextError, Result};
+use crate::models::SECTION_TYPES;
+use crate::processors::portable_format::LineEnding;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::sync::{LazyLock, RwLock};
+
+/// File name of the settings store inside the app data directory
+pub const SETTINGS_FILE: &str = "settings.json";
+
+/// Allowed range for the editor font size, in pixels
+pub const FONT_SIZE_RANGE: std::ops::RangeInclusive<u32> = 8..=48;
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Theme {
+    #[default]
+    System,
+    Light,
+    Dark,
+}
+
+/// How strictly documents are validated while editing
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ValidationStrictness {
+    /// Only problems that prevent loading
+    Lenient,
+    #[default]
+    Standard,
+    /// Everything `publish_check` reports
+    Strict,
+}
+
+/// Who is editing, for new documents and the records of edits
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct AuthorProfile {
+    pub name: String,
+    pub email: String,
+}
+
+impl AuthorProfile {
+    /// `Name <email>`, or whichever of the two is set; `None` for an empty profile
+    pub fn signature(&self) -> Option<String> {
+        match (self.name.trim(), self.email.trim()) {
+            ("", "") => None,
+            (name, "") => Some(name.to_string()),
+            ("", email) => Some(format!("<{}>", email)),
+            (name, email) => Some(format!("{} <{}>", name, email)),
+        }
+    }
+}
+
+/// User settings persisted in the app data directory
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase", default, deny_unknown_fields)]
+pub struct Settings {
+    pub theme: Theme,
+    pub editor_font_size: u32,
+    pub autosave: bool,
+    pub validation_strictness: ValidationStrictness,
+    /// Record every mutation in a journal next to the document (see `change_journal`)
+    pub change_journal: bool,
+    /// Keep a daily snapshot of document stats, updated on save (see `stats_history`)
+    pub stats_history: bool,
+    /// Mirror the `status` frontmatter of linked sections as node classes, and back, on save (see `status_sync`)
+    pub status_sync: bool,
+    /// Locale of dates and numbers in rendered output, such as `de-DE`; ISO formats when unset.
+    /// A document's `locale` meta field takes precedence (see `formatting`)
+    pub locale: Option<String>,
+    /// Content of new sections by section type, replacing the built-in skeletons; an empty value
+    /// keeps new sections of that type blank. A document's `template.<type>` field wins (see `section_templates`)
+    pub section_templates: BTreeMap<String, String>,
+    /// Line endings of saved documents: `lf`, `crlf` or `native`. Content is kept with LF
+    /// and document paths with `/` whatever this says (see `portable_format`)
+    pub line_endings: LineEnding,
+    /// Where `quick_capture` puts notes: `path#section-id`, or a document path for its `inbox`
+    /// section; relative to the documents folder, which holds `inbox.xml` when unset (see `quick_capture`)
+    pub inbox: Option<String>,
+    /// Count section views and edits in the document's history folder, for a heat map of the canvas (see `section_activity`)
+    pub section_activity: bool,
+    /// Copies of the previous version kept in `.flow-writer-backups` next to each document on save;
+    /// 0 turns backups off (see `backup_service`)
+    pub max_backups: u32,
+    /// Author of new documents created without one, also stamped on journal entries and
+    /// section edits (see `change_journal` and `section_activity`)
+    pub author: AuthorProfile,
+    /// Count saves, exports and publish check errors per day in the app data directory, for
+    /// `export_metrics`; nothing leaves the machine (see `usage_metrics`)
+    pub usage_metrics: bool,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Settings {
+            theme: Theme::System,
+            editor_font_size: 14,
+            autosave: true,
+            validation_strictness: ValidationStrictness::Standard,
+            change_journal: false,
+            stats_history: true,
+            status_sync: false,
+            locale: None,
+            section_templates: BTreeMap::new(),
+            line_endings: LineEnding::Lf,
+            inbox: None,
+            section_activity: false,
+            max_backups: 20,
+            author: AuthorProfile::default(),
+            usage_metrics: false,
+        }
+    }
+}
+
+impl Settings {
+    fn validate(&self) -> Result<()> {
+        if !FONT_SIZE_RANGE.contains(&self.editor_font_size) {
+            return Err(ContextError::InvalidArgument(format!(
+                "editorFontSize must be between {} and {}",
+                FONT_SIZE_RANGE.start(),
+                FONT_SIZE_RANGE.end()
+            )));
+        }
+        if let Some(locale) = &self.locale {
+            let well_formed = !locale.is_empty() && locale.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_'));
+            if !well_formed {
+                return Err(ContextError::InvalidArgument(format!("locale '{}' is not a language tag like de-DE", locale)));
+            }
+        }
+        if self.inbox.as_ref().is_some_and(|inbox| inbox.trim().trim_matches('#').is_empty()) {
+            return Err(ContextError::InvalidArgument("inbox must name a document".to_string()));
+        }
+        if self.author.name.contains(['<', '>', '\n']) {
+            return Err(ContextError::InvalidArgument("author.name must not contain '<', '>' or line breaks".to_string()));
+        }
+        let email = self.author.email.trim();
+        if !email.is_empty() && (email.contains(char::is_whitespace) || email.contains(['<', '>']) || !email.contains('@')) {
+            return Err(ContextError::InvalidArgument(format!("author.email '{}' is not an email address", email)));
+        }
+        if let Some(section_type) = self.section_templates.keys().find(|t| !SECTION_TYPES.contains(&t.as_str())) {
+            return Err(ContextError::InvalidArgument(format!(
+                "sectionTemplates has unknown section type '{}'. Allowed types: {}",
+                section_type,
+                SECTION_TYPES.join(", ")
+            )));
+        }
+        Ok(())
+    }
+}
+
+static SETTINGS: LazyLock<RwLock<Settings>> = LazyLock::new(|| RwLock::new(Settings::default()));
+
+/// Settings as of the last load or change, for backend code that depends on them
+pub fn current_settings() -> Settings {
+    SETTINGS.read().unwrap().clone()
+}
+
+/// Read the settings file; a missing file gives the defaults
+///
+/// Keys missing from the file take their default value.
+pub async fn load_settings(path: &Path) -> Result<Settings> {
+    let settings = match tokio::fs::read_to_string(path).await {
+        Ok(json) => serde_json::from_str::<Settings>(&json)
+            .map_err(|e| ContextError::SerializationError(format!("{}: {}", path.display(), e)))?,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Settings::default(),
+        Err(e) => return Err(e.into()),
+    };
+    *SETTINGS.write().unwrap() = settings.clone();
+    Ok(settings)
+}
+
+/// Value of one setting by its camelCase key
+pub async fn get_setting(path: &Path, key: &str) -> Result<Value> {
+    let settings = to_object(&load_settings(path).await?)?;
+    settings
+        .get(key)
+        .cloned()
+        .ok_or_else(|| ContextError::InvalidArgument(format!("Unknown setting '{}'", key)))
+}
+
+/// Change one setting, checking the value against the schema, and persist
+pub async fn set_setting(path: &Path, key: &str, value: Value) -> Result<Settings> {
+    let mut object = to_object(&load_settings(path).await?)?;
+    if !object.contains_key(key) {
+        return Err(ContextError::InvalidArgument(format!("Unknown setting '{}'", key)));
+    }
+    object.insert(key.to_string(), value);
+
+    let settings: Settings = serde_json::from_value(Value::Object(object))
+        .map_err(|e| ContextError::InvalidArgument(format!("Invalid value for '{}': {}", key, e)))?;
+    settings.validate()?;
+
+    if let Some(dir) = path.parent() {
+        tokio::fs::create_dir_all(dir).await?;
+    }
+    let json = serde_json::to_string_pretty(&settings).map_err(|e| ContextError::SerializationError(e.to_string()))?;
+    let tmp = path.with_extension("json.tmp");
+    tokio::fs::write(&tmp, json).await?;
+    tokio::fs::rename(&tmp, path).await?;
+
+    *SETTINGS.write().unwrap() = settings.clone();
+    Ok(settings)
+}
+
+fn to_object(settings: &Settings) -> Result<serde_json::Map<String, Value>> {
+    match serde_json::to_value(settings) {
+        Ok(Value::Object(object)) => Ok(object),
+        Ok(_) => unreachable!("settings serialize to an object"),
+        Err(e) => Err(ContextError::SerializationError(e.to_string())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[tokio::test]
+    async fn test_defaults_when_missing() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join(SETTINGS_FILE);
+
+        assert_eq!(load_settings(&path).await.unwrap(), Settings::default());
+        assert_eq!(get_setting(&path, "editorFontSize").await.unwrap(), json!(14));
+        assert!(matches!(get_setting(&path, "nope").await, Err(ContextError::InvalidArgument(_))));
+    }
+
+    #[tokio::test]
+    async fn test_set_setting_persists() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("data").join(SETTINGS_FILE);
+
+        set_setting(&path, "theme", json!("dark")).await.unwrap();
+        set_setting(&path, "locale", json!("de-DE")).await.unwrap();
+        let settings = set_setting(&path, "autosave", json!(false)).await.unwrap();
+
+        assert_eq!(settings.theme, Theme::Dark);
+        assert!(!settings.autosave);
+        assert_eq!(settings.locale.as_deref(), Some("de-DE"));
+        assert_eq!(load_settings(&path).await.unwrap(), settings);
+        assert_eq!(get_setting(&path, "theme").await.unwrap(), json!("dark"));
+    }
+
+    #[tokio::test]
+    async fn test_set_setting_rejects_invalid_values() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join(SETTINGS_FILE);
+
+        for (key, value) in [
+            ("theme", json!("neon")),
+            ("editorFontSize", json!("big")),
+            ("editorFontSize", json!(200)),
+            ("validationStrictness", json!(true)),
+            ("locale", json!("de DE")),
+            ("sectionTemplates", json!({"summary": "# Summary"})),
+            ("lineEndings", json!("cr")),
+            ("inbox", json!(" ")),
+            ("maxBackups", json!(-1)),
+            ("author", json!({"name": "Dana <x>"})),
+            ("author", json!({"email": "dana"})),
+            ("author", json!({"nickname": "D"})),
+            ("unknown", json!(1)),
+        ] {
+            assert!(
+                matches!(set_setting(&path, key, value).await, Err(ContextError::InvalidArgument(_))),
+                "{} should be rejected",
+                key
+            );
+        }
+        assert!(!path.exists());
+    }
+
+    #[tokio::test]
+    async fn test_partial_file_uses_defaults() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join(SETTINGS_FILE);
+        std::fs::write(&path, r#"{"theme":"light"}"#).unwrap();
+
+        let settings = load_settings(&path).await.unwrap();
+        assert_eq!(settings.theme, Theme::Light);
+        assert_eq!(settings.editor_font_size, 14);
+    }
+
+    #[test]
+    fn test_author_signature() {
+        let author = |name: &str, email: &str| AuthorProfile { name: name.to_string(), email: email.to_string() };
+        assert_eq!(author("", " ").signature(), None);
+        assert_eq!(author("Dana", "").signature().as_deref(), Some("Dana"));
+        assert_eq!(author("", "dana@example.com").signature().as_deref(), Some("<dana@example.com>"));
+        assert_eq!(author(" Dana ", "dana@example.com").signature().as_deref(), Some("Dana <dana@example.com>"));
+    }
+}
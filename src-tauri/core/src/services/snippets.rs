@@ -0,0 +1,157 @@
+use crate::error::{ContextError, Result};
+use crate::processors::variable_resolver::resolve_variables;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+
+/// File name of the snippet library inside the app data directory
+pub const SNIPPETS_FILE: &str = "snippets.json";
+
+/// A reusable content fragment, e.g. boilerplate evaluation criteria
+///
+/// `${var}` placeholders in the content are resolved when it is inserted.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Snippet {
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    pub content: String,
+}
+
+/// Read the snippet library; a missing file gives an empty library
+pub async fn load_snippets(path: &Path) -> Result<Vec<Snippet>> {
+    match tokio::fs::read_to_string(path).await {
+        Ok(json) => serde_json::from_str(&json)
+            .map_err(|e| ContextError::SerializationError(format!("{}: {}", path.display(), e))),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(vec![]),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Snippet by name
+pub async fn find_snippet(path: &Path, name: &str) -> Result<Snippet> {
+    load_snippets(path)
+        .await?
+        .into_iter()
+        .find(|snippet| snippet.name == name)
+        .ok_or_else(|| ContextError::InvalidArgument(format!("Unknown snippet '{}'", name)))
+}
+
+/// Add a snippet, replacing one with the same name, and persist
+pub async fn save_snippet(path: &Path, snippet: Snippet) -> Result<Vec<Snippet>> {
+    if snippet.name.trim().is_empty() {
+        return Err(ContextError::InvalidArgument("Snippet name cannot be empty".to_string()));
+    }
+    let mut snippets = load_snippets(path).await?;
+    match snippets.iter_mut().find(|existing| existing.name == snippet.name) {
+        Some(existing) => *existing = snippet,
+        None => snippets.push(snippet),
+    }
+    write_snippets(path, &snippets).await?;
+    Ok(snippets)
+}
+
+/// Remove a snippet by name and persist
+pub async fn delete_snippet(path: &Path, name: &str) -> Result<Vec<Snippet>> {
+    let mut snippets = load_snippets(path).await?;
+    let count = snippets.len();
+    snippets.retain(|snippet| snippet.name != name);
+    if snippets.len() == count {
+        return Err(ContextError::InvalidArgument(format!("Unknown snippet '{}'", name)));
+    }
+    write_snippets(path, &snippets).await?;
+    Ok(snippets)
+}
+
+async fn write_snippets(path: &Path, snippets: &[Snippet]) -> Result<()> {
+    if let Some(dir) = path.parent() {
+        tokio::fs::create_dir_all(dir).await?;
+    }
+    let json = serde_json::to_string_pretty(snippets).map_err(|e| ContextError::SerializationError(e.to_string()))?;
+    let tmp = path.with_extension("json.tmp");
+    tokio::fs::write(&tmp, json).await?;
+    tokio::fs::rename(&tmp, path).await?;
+    Ok(())
+}
+
+/// Insert a snippet into section content
+///
+/// Placeholders are resolved against `variables`; ones without a value stay
+/// as `${var}`. Without a `position` the snippet is appended as a new
+/// paragraph, otherwise it is inserted as-is at that byte offset.
+pub fn insert_snippet(
+    content: &str,
+    snippet: &Snippet,
+    variables: &HashMap<String, String>,
+    position: Option<usize>,
+) -> Result<String> {
+    let text = resolve_variables(&snippet.content, variables);
+    match position {
+        None => {
+            let existing = content.trim_end();
+            if existing.is_empty() {
+                Ok(text)
+            } else {
+                Ok(format!("{}\n\n{}", existing, text))
+            }
+        }
+        Some(position) if content.is_char_boundary(position) => {
+            Ok(format!("{}{}{}", &content[..position], text, &content[position..]))
+        }
+        Some(position) => Err(ContextError::InvalidArgument(format!(
+            "Position {} is outside the section content",
+            position
+        ))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn snippet(name: &str, content: &str) -> Snippet {
+        Snippet {
+            name: name.to_string(),
+            description: None,
+            content: content.to_string(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_save_find_and_delete() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("data").join(SNIPPETS_FILE);
+        assert!(load_snippets(&path).await.unwrap().is_empty());
+
+        save_snippet(&path, snippet("criteria", "- Accurate")).await.unwrap();
+        save_snippet(&path, snippet("steps", "1. Plan")).await.unwrap();
+        let snippets = save_snippet(&path, snippet("criteria", "- Accurate\n- Concise")).await.unwrap();
+
+        assert_eq!(snippets.len(), 2);
+        assert_eq!(find_snippet(&path, "criteria").await.unwrap().content, "- Accurate\n- Concise");
+        assert!(matches!(save_snippet(&path, snippet(" ", "x")).await, Err(ContextError::InvalidArgument(_))));
+
+        assert_eq!(delete_snippet(&path, "steps").await.unwrap().len(), 1);
+        assert!(matches!(delete_snippet(&path, "steps").await, Err(ContextError::InvalidArgument(_))));
+        assert!(matches!(find_snippet(&path, "steps").await, Err(ContextError::InvalidArgument(_))));
+    }
+
+    #[test]
+    fn test_insert_snippet() {
+        let snippet = snippet("criteria", "Reviewed by ${owner} for ${audience}");
+        let variables = HashMap::from([("owner".to_string(), "Sam".to_string())]);
+
+        assert_eq!(
+            insert_snippet("# Evaluation\n", &snippet, &variables, None).unwrap(),
+            "# Evaluation\n\nReviewed by Sam for ${audience}"
+        );
+        assert_eq!(insert_snippet("", &snippet, &variables, None).unwrap(), "Reviewed by Sam for ${audience}");
+        assert_eq!(
+            insert_snippet("ab", &snippet, &HashMap::new(), Some(1)).unwrap(),
+            "aReviewed by ${owner} for ${audience}b"
+        );
+        assert!(insert_snippet("é", &snippet, &variables, Some(1)).is_err());
+        assert!(insert_snippet("ab", &snippet, &variables, Some(3)).is_err());
+    }
+}
@@ -0,0 +1,101 @@
+use crate::error::{ContextError, Result};
+use crate::processors::document_stats::DocumentStats;
+use crate::services::assembly_history::history_dir;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use tokio::fs;
+
+/// Stats history file inside the document's history folder, one JSON object per line
+pub const STATS_FILE: &str = "stats.jsonl";
+
+/// Document stats as of the last save on one day
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct DailyStats {
+    /// `YYYY-MM-DD`, local time
+    pub date: String,
+    #[serde(flatten)]
+    pub stats: DocumentStats,
+}
+
+pub fn stats_path(file_path: &str) -> PathBuf {
+    history_dir(file_path).join(STATS_FILE)
+}
+
+/// Store the stats for `date`, replacing an earlier snapshot of the same day
+///
+/// Keeps one entry per day, so the file grows with the number of days the
+/// document was worked on rather than the number of saves.
+pub async fn record_daily_stats(file_path: &str, date: &str, stats: DocumentStats) -> Result<()> {
+    let mut history = read_stats_history(file_path).await?;
+    history.retain(|entry| entry.date != date);
+    history.push(DailyStats {
+        date: date.to_string(),
+        stats,
+    });
+    history.sort_by(|a, b| a.date.cmp(&b.date));
+
+    let mut content = String::new();
+    for entry in &history {
+        content.push_str(&serde_json::to_string(entry).map_err(|e| ContextError::SerializationError(e.to_string()))?);
+        content.push('\n');
+    }
+
+    let path = stats_path(file_path);
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).await?;
+    }
+    let temp_path = path.with_extension("jsonl.tmp");
+    fs::write(&temp_path, content).await?;
+    fs::rename(&temp_path, &path).await?;
+    Ok(())
+}
+
+/// Daily stats snapshots, oldest first
+pub async fn read_stats_history(file_path: &str) -> Result<Vec<DailyStats>> {
+    let content = match fs::read_to_string(stats_path(file_path)).await {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(vec![]),
+        Err(e) => return Err(e.into()),
+    };
+    content
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| serde_json::from_str(line).map_err(|e| ContextError::SerializationError(e.to_string())))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stats(word_count: usize) -> DocumentStats {
+        DocumentStats {
+            word_count,
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_one_snapshot_per_day() {
+        let dir = tempfile::tempdir().unwrap();
+        let file_path = dir.path().join("plan.xml");
+        let file_path = file_path.to_str().unwrap();
+
+        assert!(read_stats_history(file_path).await.unwrap().is_empty());
+
+        record_daily_stats(file_path, "2025-10-02", stats(120)).await.unwrap();
+        record_daily_stats(file_path, "2025-10-01", stats(80)).await.unwrap();
+        record_daily_stats(file_path, "2025-10-02", stats(150)).await.unwrap();
+
+        let history = read_stats_history(file_path).await.unwrap();
+        let days: Vec<(&str, usize)> = history.iter().map(|d| (d.date.as_str(), d.stats.word_count)).collect();
+        assert_eq!(days, vec![("2025-10-01", 80), ("2025-10-02", 150)]);
+    }
+
+    #[test]
+    fn test_entry_is_flat() {
+        let json = serde_json::to_value(DailyStats { date: "2025-10-01".to_string(), stats: stats(3) }).unwrap();
+        assert_eq!(json["date"], "2025-10-01");
+        assert_eq!(json["word_count"], 3);
+    }
+}
@@ -0,0 +1,108 @@
+use crate::error::{ContextError, Result};
+use crate::models::ContextDocument;
+use crate::serializers::xml_serializer::serialize_xml;
+use crate::services::{default_documents, flow_service};
+use crate::validators::schema_validator;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use tokio::fs;
+
+/// Folder under the app data directory holding the template library
+pub const TEMPLATES_FOLDER: &str = "templates";
+
+/// A template in the library
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct TemplateInfo {
+    pub name: String,
+    pub file_path: String,
+    pub description: String,
+    pub section_count: usize,
+    pub variables: Vec<String>,
+}
+
+impl TemplateInfo {
+    fn new(path: &Path, template: &ContextDocument) -> TemplateInfo {
+        TemplateInfo {
+            name: template.meta.title.clone(),
+            file_path: path.to_string_lossy().into_owned(),
+            description: template.meta.description.clone(),
+            section_count: template.sections.len(),
+            variables: template.variables.iter().map(|v| v.name.clone()).collect(),
+        }
+    }
+}
+
+/// Write a template into the library, replacing one saved under the same name
+pub async fn save_template(dir: &Path, template: &ContextDocument) -> Result<TemplateInfo> {
+    let path = template_path(dir, &template.meta.title)?;
+    let xml = serialize_xml(template);
+    schema_validator::validate_schema(&xml)?;
+
+    fs::create_dir_all(dir).await?;
+    let temp_path = path.with_extension("xml.tmp");
+    fs::write(&temp_path, xml).await?;
+    fs::rename(&temp_path, &path).await?;
+    Ok(TemplateInfo::new(&path, template))
+}
+
+/// Templates in the library, sorted by file name
+///
+/// Files that do not parse as context documents are skipped.
+pub async fn list_templates(dir: &Path) -> Result<Vec<TemplateInfo>> {
+    let mut templates = Vec::new();
+    for path in default_documents::list_documents(dir).await? {
+        let content = fs::read_to_string(&path).await?;
+        if let Ok(template) = flow_service::parse_context_document(&content) {
+            templates.push(TemplateInfo::new(&path, &template));
+        }
+    }
+    Ok(templates)
+}
+
+/// `.xml` file in the library for a template name
+pub fn template_path(dir: &Path, name: &str) -> Result<PathBuf> {
+    let stem: String = name
+        .trim()
+        .chars()
+        .map(|c| if c.is_alphanumeric() || matches!(c, '-' | '_') { c.to_ascii_lowercase() } else { '-' })
+        .collect();
+    let stem = stem.trim_matches('-');
+    if stem.is_empty() {
+        return Err(ContextError::InvalidArgument(format!("Invalid template name '{}'", name)));
+    }
+    Ok(dir.join(format!("{}.xml", stem)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_template_path() {
+        let dir = Path::new("/lib");
+        assert_eq!(template_path(dir, "Sprint Plan").unwrap(), dir.join("sprint-plan.xml"));
+        assert_eq!(template_path(dir, "../etc/passwd").unwrap(), dir.join("etc-passwd.xml"));
+        assert!(matches!(template_path(dir, " ./ "), Err(ContextError::InvalidArgument(_))));
+    }
+
+    #[tokio::test]
+    async fn test_save_and_list_templates() {
+        let dir = tempfile::tempdir().unwrap();
+        assert!(list_templates(dir.path()).await.unwrap().is_empty());
+
+        let template = ContextDocument::builder()
+            .title("Sprint Plan")
+            .variable("team", "")
+            .section("intent-1", "intent", "# Intent")
+            .build()
+            .unwrap();
+        let info = save_template(dir.path(), &template).await.unwrap();
+        save_template(dir.path(), &template).await.unwrap();
+        std::fs::write(dir.path().join("broken.xml"), "<context>").unwrap();
+
+        assert_eq!(info.name, "Sprint Plan");
+        assert_eq!(info.variables, vec!["team".to_string()]);
+        assert_eq!(list_templates(dir.path()).await.unwrap(), vec![info]);
+    }
+}
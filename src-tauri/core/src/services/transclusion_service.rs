@@ -0,0 +1,252 @@
+use crate::error::{ContextError, Result};
+use crate::models::*;
+use crate::processors::portable_format::platform_path;
+use crate::services::flow_service;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// Default number of reference hops followed when inlining cross-document content
+pub const DEFAULT_MAX_TRANSCLUSION_DEPTH: usize = 3;
+
+/// A pending reference along with the chain of (document, section) pairs that led to it
+struct PendingRef {
+    reference: CrossDocumentRef,
+    base_dir: PathBuf,
+    depth: usize,
+    ancestors: Vec<(PathBuf, String)>,
+}
+
+/// Inline cross-document references of each section as read-only transclusions
+///
+/// References are followed depth-first up to `max_depth` hops. A reference that
+/// points back at a (document, section) pair already on the current chain is a
+/// cycle and is skipped.
+pub async fn transclude_sections(
+    sections: &mut [Section],
+    document_path: &str,
+    max_depth: usize,
+) -> Result<()> {
+    let document_path = canonical_path(Path::new(document_path)).await?;
+    let base_dir = parent_dir(&document_path);
+    let mut loaded: HashMap<PathBuf, ContextDocument> = HashMap::new();
+
+    for section in sections.iter_mut() {
+        let ancestors = vec![(document_path.clone(), section.id.clone())];
+        let mut stack: Vec<PendingRef> = section
+            .cross_document_refs()
+            .into_iter()
+            .rev()
+            .map(|reference| PendingRef {
+                reference,
+                base_dir: base_dir.clone(),
+                depth: 1,
+                ancestors: ancestors.clone(),
+            })
+            .collect();
+
+        let mut transclusions = Vec::new();
+
+        while let Some(pending) = stack.pop() {
+            if pending.depth > max_depth {
+                continue;
+            }
+
+            let source_path = canonical_path(&pending.base_dir.join(platform_path(&pending.reference.path))).await?;
+            let key = (source_path.clone(), pending.reference.section_id.clone());
+            if pending.ancestors.contains(&key) {
+                continue;
+            }
+
+            if !loaded.contains_key(&source_path) {
+                let doc = flow_service::load_context_document(&source_path.to_string_lossy()).await?;
+                loaded.insert(source_path.clone(), doc);
+            }
+            let source_doc = &loaded[&source_path];
+
+            let target = source_doc
+                .sections
+                .iter()
+                .find(|s| s.id == pending.reference.section_id)
+                .ok_or_else(|| {
+                    ContextError::ReferenceError(format!(
+                        "Section '{}' not found in '{}'",
+                        pending.reference.section_id,
+                        pending.reference.path
+                    ))
+                })?;
+
+            transclusions.push(Transclusion {
+                source_path: source_path.to_string_lossy().to_string(),
+                section_id: target.id.clone(),
+                section_type: target.section_type.clone(),
+                content: target.content.clone(),
+                depth: pending.depth,
+                read_only: true,
+            });
+
+            let mut ancestors = pending.ancestors.clone();
+            ancestors.push(key);
+            let next_base_dir = parent_dir(&source_path);
+            for reference in target.cross_document_refs().into_iter().rev() {
+                stack.push(PendingRef {
+                    reference,
+                    base_dir: next_base_dir.clone(),
+                    depth: pending.depth + 1,
+                    ancestors: ancestors.clone(),
+                });
+            }
+        }
+
+        section.transclusions = transclusions;
+    }
+
+    Ok(())
+}
+
+async fn canonical_path(path: &Path) -> Result<PathBuf> {
+    tokio::fs::canonicalize(path)
+        .await
+        .map_err(|_| ContextError::FileNotFound(path.to_string_lossy().to_string()))
+}
+
+fn parent_dir(path: &Path) -> PathBuf {
+    path.parent().map(Path::to_path_buf).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn write_doc(dir: &TempDir, name: &str, variables: &str, sections: &str) {
+        let xml = format!(
+            r#"
+<context version="1.0">
+    <meta>
+        <title>{name}</title>
+        <author>Author</author>
+        <created>2025-10-09</created>
+        <app name="CEC" version="0.1.0"/>
+        <tags>test</tags>
+        <description>Test</description>
+    </meta>
+    <variables>{variables}</variables>
+    <sections>{sections}</sections>
+</context>
+            "#
+        );
+        std::fs::write(dir.path().join(name), xml).unwrap();
+    }
+
+    fn main_path(dir: &TempDir) -> String {
+        dir.path().join("main.xml").to_str().unwrap().to_string()
+    }
+
+    #[tokio::test]
+    async fn test_transclude_direct_reference() {
+        let dir = TempDir::new().unwrap();
+        write_doc(
+            &dir,
+            "shared.xml",
+            r#"<var name="goal">Ship v1</var>"#,
+            r#"<section id="intent-1" type="intent"><content>Goal: ${goal}</content></section>"#,
+        );
+        write_doc(
+            &dir,
+            "main.xml",
+            "",
+            r#"<section id="proc-1" type="process" refTarget="shared.xml#intent-1"><content>Process</content></section>"#,
+        );
+
+        let path = main_path(&dir);
+        let mut doc = flow_service::load_context_document(&path).await.unwrap();
+        transclude_sections(&mut doc.sections, &path, DEFAULT_MAX_TRANSCLUSION_DEPTH).await.unwrap();
+
+        let transclusions = &doc.sections[0].transclusions;
+        assert_eq!(transclusions.len(), 1);
+        assert_eq!(transclusions[0].section_id, "intent-1");
+        assert_eq!(transclusions[0].content, "Goal: Ship v1");
+        assert_eq!(transclusions[0].depth, 1);
+        assert!(transclusions[0].read_only);
+    }
+
+    #[tokio::test]
+    async fn test_transclude_stops_at_cycle() {
+        let dir = TempDir::new().unwrap();
+        write_doc(
+            &dir,
+            "main.xml",
+            "",
+            r#"<section id="a" type="intent" refTarget="other.xml#b"><content>A</content></section>"#,
+        );
+        write_doc(
+            &dir,
+            "other.xml",
+            "",
+            r#"<section id="b" type="intent" refTarget="main.xml#a"><content>B</content></section>"#,
+        );
+
+        let path = main_path(&dir);
+        let mut doc = flow_service::load_context_document(&path).await.unwrap();
+        transclude_sections(&mut doc.sections, &path, 10).await.unwrap();
+
+        let transclusions = &doc.sections[0].transclusions;
+        assert_eq!(transclusions.len(), 1);
+        assert_eq!(transclusions[0].section_id, "b");
+    }
+
+    #[tokio::test]
+    async fn test_transclude_respects_max_depth() {
+        let dir = TempDir::new().unwrap();
+        write_doc(
+            &dir,
+            "main.xml",
+            "",
+            r#"<section id="a" type="intent" refTarget="b.xml#b"><content>A</content></section>"#,
+        );
+        write_doc(
+            &dir,
+            "b.xml",
+            "",
+            r#"<section id="b" type="intent" refTarget="c.xml#c"><content>B</content></section>"#,
+        );
+        write_doc(
+            &dir,
+            "c.xml",
+            "",
+            r#"<section id="c" type="intent"><content>C</content></section>"#,
+        );
+
+        let path = main_path(&dir);
+        let mut doc = flow_service::load_context_document(&path).await.unwrap();
+        transclude_sections(&mut doc.sections, &path, 1).await.unwrap();
+        assert_eq!(doc.sections[0].transclusions.len(), 1);
+
+        transclude_sections(&mut doc.sections, &path, 2).await.unwrap();
+        let transclusions = &doc.sections[0].transclusions;
+        assert_eq!(transclusions.len(), 2);
+        assert_eq!(transclusions[1].section_id, "c");
+        assert_eq!(transclusions[1].depth, 2);
+    }
+
+    #[tokio::test]
+    async fn test_transclude_missing_section() {
+        let dir = TempDir::new().unwrap();
+        write_doc(&dir, "shared.xml", "", "");
+        write_doc(
+            &dir,
+            "main.xml",
+            "",
+            r#"<section id="a" type="intent" refTarget="shared.xml#missing"><content>A</content></section>"#,
+        );
+
+        let path = main_path(&dir);
+        let mut doc = flow_service::load_context_document(&path).await.unwrap();
+        let result = transclude_sections(&mut doc.sections, &path, 3).await;
+
+        match result {
+            Err(ContextError::ReferenceError(msg)) => assert!(msg.contains("missing")),
+            other => panic!("Expected ReferenceError, got: {:?}", other),
+        }
+    }
+}
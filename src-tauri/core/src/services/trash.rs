@@ -0,0 +1,298 @@
+use crate::error::{ContextError, Result};
+use crate::models::*;
+use crate::processors::document_edits::{collect_section_ids, DocumentEdit};
+use crate::processors::section_import::unique_section_id;
+use crate::services::{binary_cache, flow_service};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+/// Folder of the trash inside the app data directory
+pub const TRASH_FOLDER: &str = "trash";
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum TrashKind {
+    Document,
+    Section,
+}
+
+/// A deleted document or section that can be restored
+///
+/// Stored as `<id>.json` in the trash folder; a deleted document's file is
+/// moved next to it as `<id>.xml`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct TrashEntry {
+    pub id: String,
+    pub kind: TrashKind,
+    /// The deleted document, or the document the section was deleted from
+    pub file_path: String,
+    /// RFC 3339 timestamp
+    pub deleted_at: String,
+    /// The deleted section with its subsections
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub section: Option<Section>,
+    /// Where the section was: its parent (None at the top level) and position among its siblings
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parent_id: Option<String>,
+    #[serde(default)]
+    pub index: usize,
+}
+
+/// Move a document file to the trash, discarding unsaved edits to it
+pub async fn trash_document(trash_dir: &Path, file_path: &str) -> Result<TrashEntry> {
+    if !Path::new(file_path).is_file() {
+        return Err(ContextError::FileNotFound(file_path.to_string()));
+    }
+    let entry = new_entry(TrashKind::Document, file_path, file_path);
+    tokio::fs::create_dir_all(trash_dir).await?;
+    move_file(Path::new(file_path), &document_copy(trash_dir, &entry.id)).await?;
+    write_entry(trash_dir, &entry).await?;
+    flow_service::close_document(file_path);
+    Ok(entry)
+}
+
+/// Delete a section (with its subsections) from the document, keeping a copy in the trash
+///
+/// The document is saved, as with `apply_edits`.
+pub async fn trash_section(trash_dir: &Path, file_path: &str, section_id: &str) -> Result<TrashEntry> {
+    let doc = flow_service::read_context_document(file_path).await?;
+    let (section, parent_id, index) =
+        locate_section(&doc.sections, None, section_id).ok_or_else(|| ContextError::SectionNotFound(section_id.to_string()))?;
+
+    let mut entry = new_entry(TrashKind::Section, file_path, &format!("{}#{}", file_path, section_id));
+    entry.section = Some(section.clone());
+    entry.parent_id = parent_id;
+    entry.index = index;
+    // Keep the copy before deleting so a failed write never loses the section
+    write_entry(trash_dir, &entry).await?;
+
+    let delete = DocumentEdit::DeleteSection { id: section_id.to_string() };
+    if let Err(e) = flow_service::apply_edits(file_path, &[delete]).await {
+        let _ = tokio::fs::remove_file(entry_path(trash_dir, &entry.id)).await;
+        return Err(e);
+    }
+    Ok(entry)
+}
+
+/// Everything in the trash, most recently deleted first
+pub async fn list_trash(trash_dir: &Path) -> Result<Vec<TrashEntry>> {
+    let mut read_dir = match tokio::fs::read_dir(trash_dir).await {
+        Ok(read_dir) => read_dir,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(vec![]),
+        Err(e) => return Err(e.into()),
+    };
+
+    let mut entries = Vec::new();
+    while let Some(file) = read_dir.next_entry().await? {
+        let path = file.path();
+        if path.extension().is_some_and(|ext| ext == "json") {
+            entries.push(read_entry(&path).await?);
+        }
+    }
+    entries.sort_by(|a, b| b.deleted_at.cmp(&a.deleted_at).then_with(|| b.id.cmp(&a.id)));
+    Ok(entries)
+}
+
+/// Put an item back where it was deleted from and drop it from the trash
+///
+/// A document is not restored over a file that has since been created at its
+/// path. A section goes back under its old parent at its old position, or at
+/// the end of the top level when the parent is gone; it gets a new ID if its
+/// old one has been reused. The returned entry carries the restored section.
+pub async fn restore_from_trash(trash_dir: &Path, id: &str) -> Result<TrashEntry> {
+    let mut entry = read_entry(&entry_path(trash_dir, id)).await?;
+
+    match entry.kind {
+        TrashKind::Document => {
+            let target = Path::new(&entry.file_path);
+            if target.exists() {
+                return Err(ContextError::InvalidArgument(format!(
+                    "Cannot restore {}: a file already exists there",
+                    entry.file_path
+                )));
+            }
+            if let Some(dir) = target.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+                tokio::fs::create_dir_all(dir).await?;
+            }
+            move_file(&document_copy(trash_dir, id), target).await?;
+        }
+        TrashKind::Section => {
+            let mut section = entry
+                .section
+                .clone()
+                .ok_or_else(|| ContextError::MissingRequiredField("section".to_string()))?;
+            let doc = flow_service::read_context_document(&entry.file_path).await?;
+            let mut taken = HashSet::new();
+            collect_section_ids(&doc.sections, &mut taken);
+            if taken.contains(&section.id) {
+                section.id = unique_section_id(&section.id, &taken);
+            }
+            let parent_id = entry.parent_id.clone().filter(|parent| taken.contains(parent));
+            let index = if parent_id == entry.parent_id { Some(entry.index) } else { None };
+
+            let create = DocumentEdit::CreateSection { section: section.clone(), parent_id, index };
+            flow_service::apply_edits(&entry.file_path, &[create]).await?;
+            entry.section = Some(section);
+        }
+    }
+
+    tokio::fs::remove_file(entry_path(trash_dir, id)).await?;
+    Ok(entry)
+}
+
+/// Permanently delete everything in the trash; returns the number of items removed
+pub async fn empty_trash(trash_dir: &Path) -> Result<usize> {
+    let entries = list_trash(trash_dir).await?;
+    for entry in &entries {
+        if entry.kind == TrashKind::Document {
+            match tokio::fs::remove_file(document_copy(trash_dir, &entry.id)).await {
+                Err(e) if e.kind() != std::io::ErrorKind::NotFound => return Err(e.into()),
+                _ => {}
+            }
+        }
+        tokio::fs::remove_file(entry_path(trash_dir, &entry.id)).await?;
+    }
+    Ok(entries.len())
+}
+
+fn new_entry(kind: TrashKind, file_path: &str, subject: &str) -> TrashEntry {
+    let now = chrono::Utc::now();
+    let unique = format!("{}{}", subject, now.timestamp_nanos_opt().unwrap_or_default());
+    TrashEntry {
+        id: format!("{}-{}", now.format("%Y%m%dT%H%M%S%3f"), &binary_cache::content_hash(unique.as_bytes())[..8]),
+        kind,
+        file_path: file_path.to_string(),
+        deleted_at: now.to_rfc3339(),
+        section: None,
+        parent_id: None,
+        index: 0,
+    }
+}
+
+/// The section, its parent's ID and its index among its siblings
+fn locate_section(sections: &[Section], parent_id: Option<&str>, id: &str) -> Option<(Section, Option<String>, usize)> {
+    if let Some(index) = sections.iter().position(|section| section.id == id) {
+        return Some((sections[index].clone(), parent_id.map(str::to_string), index));
+    }
+    sections
+        .iter()
+        .find_map(|section| locate_section(&section.children, Some(&section.id), id))
+}
+
+fn entry_path(trash_dir: &Path, id: &str) -> PathBuf {
+    trash_dir.join(format!("{}.json", id))
+}
+
+fn document_copy(trash_dir: &Path, id: &str) -> PathBuf {
+    trash_dir.join(format!("{}.xml", id))
+}
+
+async fn read_entry(path: &Path) -> Result<TrashEntry> {
+    let json = match tokio::fs::read_to_string(path).await {
+        Ok(json) => json,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            return Err(ContextError::InvalidArgument(format!("Not in the trash: {}", path.display())))
+        }
+        Err(e) => return Err(e.into()),
+    };
+    serde_json::from_str(&json).map_err(|e| ContextError::SerializationError(format!("{}: {}", path.display(), e)))
+}
+
+async fn write_entry(trash_dir: &Path, entry: &TrashEntry) -> Result<()> {
+    tokio::fs::create_dir_all(trash_dir).await?;
+    let json = serde_json::to_string_pretty(entry).map_err(|e| ContextError::SerializationError(e.to_string()))?;
+    let path = entry_path(trash_dir, &entry.id);
+    let tmp = path.with_extension("json.tmp");
+    tokio::fs::write(&tmp, json).await?;
+    tokio::fs::rename(&tmp, &path).await?;
+    Ok(())
+}
+
+/// Rename, falling back to copy and delete when the trash is on another file system
+async fn move_file(from: &Path, to: &Path) -> Result<()> {
+    if tokio::fs::rename(from, to).await.is_err() {
+        tokio::fs::copy(from, to).await?;
+        tokio::fs::remove_file(from).await?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    async fn write_document(dir: &TempDir) -> String {
+        let doc = ContextDocument::builder()
+            .title("Plan")
+            .section("intent-1", "intent", "Goal")
+            .section("process-1", "process", "Steps")
+            .section("evaluation-1", "evaluation", "Checks")
+            .build()
+            .unwrap();
+        let path = dir.path().join("plan.xml");
+        flow_service::save_context_document(path.to_str().unwrap(), &doc).await.unwrap();
+        path.to_str().unwrap().to_string()
+    }
+
+    #[tokio::test]
+    async fn test_trash_and_restore_document() {
+        let dir = TempDir::new().unwrap();
+        let trash_dir = dir.path().join("trash");
+        let file_path = write_document(&dir).await;
+
+        let entry = trash_document(&trash_dir, &file_path).await.unwrap();
+        assert!(!Path::new(&file_path).exists());
+        assert_eq!(list_trash(&trash_dir).await.unwrap(), vec![entry.clone()]);
+
+        restore_from_trash(&trash_dir, &entry.id).await.unwrap();
+        assert!(Path::new(&file_path).exists());
+        assert!(list_trash(&trash_dir).await.unwrap().is_empty());
+        assert!(restore_from_trash(&trash_dir, &entry.id).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_trash_and_restore_section() {
+        let dir = TempDir::new().unwrap();
+        let trash_dir = dir.path().join("trash");
+        let file_path = write_document(&dir).await;
+
+        let entry = trash_section(&trash_dir, &file_path, "process-1").await.unwrap();
+        assert_eq!((entry.parent_id.as_deref(), entry.index), (None, 1));
+        let doc = flow_service::read_context_document(&file_path).await.unwrap();
+        assert_eq!(doc.sections.len(), 2);
+
+        let restored = restore_from_trash(&trash_dir, &entry.id).await.unwrap();
+        assert_eq!(restored.section.unwrap().id, "process-1");
+        let doc = flow_service::read_context_document(&file_path).await.unwrap();
+        assert_eq!(doc.sections[1].id, "process-1");
+        assert_eq!(doc.sections[1].content, "Steps");
+        flow_service::close_document(&file_path);
+    }
+
+    #[tokio::test]
+    async fn test_restored_section_gets_free_id_and_empty_trash() {
+        let dir = TempDir::new().unwrap();
+        let trash_dir = dir.path().join("trash");
+        let file_path = write_document(&dir).await;
+
+        let entry = trash_section(&trash_dir, &file_path, "process-1").await.unwrap();
+        let reused = DocumentEdit::CreateSection {
+            section: Section::new("process-1", "process", "New"),
+            parent_id: None,
+            index: None,
+        };
+        flow_service::apply_edits(&file_path, &[reused]).await.unwrap();
+
+        let restored = restore_from_trash(&trash_dir, &entry.id).await.unwrap();
+        assert_ne!(restored.section.unwrap().id, "process-1");
+        assert_eq!(flow_service::read_context_document(&file_path).await.unwrap().sections.len(), 4);
+
+        trash_section(&trash_dir, &file_path, "intent-1").await.unwrap();
+        trash_document(&trash_dir, &file_path).await.unwrap();
+        assert_eq!(empty_trash(&trash_dir).await.unwrap(), 2);
+        assert_eq!(std::fs::read_dir(&trash_dir).unwrap().count(), 0);
+    }
+}
@@ -0,0 +1,157 @@
+use crate::error::{ContextError, Result};
+use crate::services::settings;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::RwLock;
+use tokio::fs;
+
+/// File name of the usage metrics inside the app data directory
+pub const METRICS_FILE: &str = "metrics.json";
+
+/// What happened on one day
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase", default)]
+pub struct MetricCounts {
+    pub saves: u64,
+    /// By kind of export, e.g. `html` or `docx`
+    pub exports: BTreeMap<String, u64>,
+    /// Errors reported by publish checks
+    pub validation_errors: u64,
+}
+
+/// Counts by local date (`YYYY-MM-DD`), as stored in the metrics file
+pub type UsageMetrics = BTreeMap<String, MetricCounts>;
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum MetricsFormat {
+    #[default]
+    Json,
+    /// One `date,metric,count` row per count
+    Csv,
+}
+
+static METRICS_PATH: RwLock<Option<PathBuf>> = RwLock::new(None);
+
+// Commands run concurrently; each update reads and rewrites the whole file
+static WRITE_LOCK: tokio::sync::Mutex<()> = tokio::sync::Mutex::const_new(());
+
+/// Where the app keeps its metrics; nothing is recorded until this is set at startup
+pub fn set_metrics_path(path: PathBuf) {
+    *METRICS_PATH.write().unwrap() = Some(path);
+}
+
+/// Count a save of any document
+pub async fn record_save() {
+    record(|counts| counts.saves += 1).await;
+}
+
+/// Count an export, by its kind
+pub async fn record_export(kind: &str) {
+    record(|counts| *counts.exports.entry(kind.to_string()).or_default() += 1).await;
+}
+
+/// Add the errors of a publish check
+pub async fn record_validation_errors(count: usize) {
+    if count > 0 {
+        record(|counts| counts.validation_errors += count as u64).await;
+    }
+}
+
+/// Update today's counts when the `usageMetrics` setting is on
+///
+/// Metrics are a side record of work that already happened, so a file that
+/// cannot be written is reported on stderr rather than failing the command.
+async fn record(change: impl FnOnce(&mut MetricCounts)) {
+    if !settings::current_settings().usage_metrics {
+        return;
+    }
+    let Some(path) = METRICS_PATH.read().unwrap().clone() else {
+        return;
+    };
+    let date = chrono::Local::now().format("%Y-%m-%d").to_string();
+    if let Err(e) = update_metrics(&path, &date, change).await {
+        eprintln!("Failed to record usage metrics in {}: {}", path.display(), e);
+    }
+}
+
+/// Change the counts of `date` in the metrics file, creating it if needed
+pub async fn update_metrics(path: &Path, date: &str, change: impl FnOnce(&mut MetricCounts)) -> Result<()> {
+    let _guard = WRITE_LOCK.lock().await;
+    let mut metrics = read_metrics(path).await?;
+    change(metrics.entry(date.to_string()).or_default());
+
+    let json = serde_json::to_string_pretty(&metrics).map_err(|e| ContextError::SerializationError(e.to_string()))?;
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).await?;
+    }
+    let temp_path = path.with_extension("json.tmp");
+    fs::write(&temp_path, json).await?;
+    fs::rename(&temp_path, path).await?;
+    Ok(())
+}
+
+/// Recorded counts, oldest day first; empty when nothing was recorded
+pub async fn read_metrics(path: &Path) -> Result<UsageMetrics> {
+    match fs::read_to_string(path).await {
+        Ok(json) => serde_json::from_str(&json).map_err(|e| ContextError::SerializationError(e.to_string())),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(UsageMetrics::new()),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// The recorded counts as JSON, or as CSV for a spreadsheet
+///
+/// CSV rows are `date,metric,count`, with exports as `export:<kind>` and
+/// zero counts left out.
+pub async fn export_metrics(path: &Path, format: MetricsFormat) -> Result<String> {
+    let metrics = read_metrics(path).await?;
+    match format {
+        MetricsFormat::Json => serde_json::to_string_pretty(&metrics).map_err(|e| ContextError::SerializationError(e.to_string())),
+        MetricsFormat::Csv => Ok(metrics_csv(&metrics)),
+    }
+}
+
+fn metrics_csv(metrics: &UsageMetrics) -> String {
+    let mut csv = String::from("date,metric,count\n");
+    for (date, counts) in metrics {
+        let rows = [("saves".to_string(), counts.saves), ("validation_errors".to_string(), counts.validation_errors)]
+            .into_iter()
+            .chain(counts.exports.iter().map(|(kind, count)| (format!("export:{}", kind), *count)));
+        for (metric, count) in rows.filter(|(_, count)| *count > 0) {
+            csv.push_str(&format!("{},{},{}\n", date, metric, count));
+        }
+    }
+    csv
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_update_and_export() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(METRICS_FILE);
+
+        assert!(read_metrics(&path).await.unwrap().is_empty());
+        update_metrics(&path, "2025-10-02", |counts| counts.saves += 1).await.unwrap();
+        update_metrics(&path, "2025-10-01", |counts| *counts.exports.entry("html".to_string()).or_default() += 1).await.unwrap();
+        update_metrics(&path, "2025-10-02", |counts| {
+            counts.saves += 1;
+            counts.validation_errors += 3;
+        })
+        .await
+        .unwrap();
+
+        let metrics = read_metrics(&path).await.unwrap();
+        assert_eq!(metrics["2025-10-02"].saves, 2);
+        assert_eq!(metrics["2025-10-01"].exports["html"], 1);
+
+        let csv = export_metrics(&path, MetricsFormat::Csv).await.unwrap();
+        assert_eq!(csv, "date,metric,count\n2025-10-01,export:html,1\n2025-10-02,saves,2\n2025-10-02,validation_errors,3\n");
+        let json: UsageMetrics = serde_json::from_str(&export_metrics(&path, MetricsFormat::Json).await.unwrap()).unwrap();
+        assert_eq!(json, metrics);
+    }
+}
@@ -0,0 +1,423 @@
+use crate::error::{ContextError, Result};
+use crate::processors::section_import::unique_section_id;
+use crate::validators::publish_check::{is_valid_date, Diagnostic, Severity};
+use regex::Regex;
+use std::collections::{HashMap, HashSet};
+use std::ops::Range;
+use std::sync::LazyLock;
+
+// These work on raw text rather than a parsed tree: the problems they repair
+// are exactly the ones that stop the document from parsing.
+static SECTION_ELEMENT: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?s)<section\b([^>]*?)(?:/>|>(.*?)</section>)").unwrap());
+static CONTENT_ELEMENT: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"(?s)<content\b([^>]*)>(.*?)</content>").unwrap());
+static CREATED_ELEMENT: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"(?s)<created>(.*?)</created>").unwrap());
+static ID_ATTRIBUTE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r#"\bid\s*=\s*(?:"([^"]*)"|'([^']*)')"#).unwrap());
+static LANG_ATTRIBUTE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r#"\blang\s*=\s*(?:"([^"]*)"|'([^']*)')"#).unwrap());
+static CHILD_ELEMENT: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"<[A-Za-z/]").unwrap());
+static CDATA_BLOCK: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"(?s)<!\[CDATA\[.*?\]\]>").unwrap());
+static ENTITY: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"^&(?:#[0-9]+|#x[0-9a-fA-F]+|[A-Za-z]+);").unwrap());
+static YEAR_FIRST_DATE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^(\d{4})[-/.](\d{1,2})[-/.](\d{1,2})(?:[ T](\d{1,2}):(\d{2})(?::(\d{2}))?)?$").unwrap()
+});
+static DAY_FIRST_DATE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"^(\d{1,2})\.(\d{1,2})\.(\d{4})$").unwrap());
+
+/// A text replacement in the raw XML
+#[derive(Debug, Clone, PartialEq)]
+struct Edit {
+    range: Range<usize>,
+    replacement: String,
+}
+
+struct Fix {
+    diagnostic: Diagnostic,
+    edits: Vec<Edit>,
+}
+
+/// Problems in the raw XML that have a deterministic repair
+///
+/// Detects duplicate section IDs, sections without a `<content>` element,
+/// content with unescaped markup outside CDATA and malformed created dates
+/// that can be normalized. Every returned diagnostic is marked fixable.
+pub fn find_fixable(xml: &str) -> Vec<Diagnostic> {
+    collect_fixes(xml).into_iter().map(|fix| fix.diagnostic).collect()
+}
+
+/// Apply the repairs for the selected diagnostic IDs and return the new XML
+///
+/// IDs that do not name a fixable diagnostic of this XML are rejected so a
+/// stale selection cannot silently do nothing.
+pub fn apply_fixes(xml: &str, diagnostic_ids: &[String]) -> Result<String> {
+    let mut fixes: HashMap<String, Vec<Edit>> = collect_fixes(xml)
+        .into_iter()
+        .map(|fix| (fix.diagnostic.id, fix.edits))
+        .collect();
+
+    let mut edits = Vec::new();
+    for id in diagnostic_ids {
+        let fix_edits = fixes
+            .remove(id)
+            .ok_or_else(|| ContextError::InvalidArgument(format!("No fix available for diagnostic '{}'", id)))?;
+        edits.extend(fix_edits);
+    }
+
+    // Apply back to front so earlier ranges stay valid
+    edits.sort_by_key(|edit| std::cmp::Reverse(edit.range.start));
+    let mut fixed = xml.to_string();
+    for edit in edits {
+        fixed.replace_range(edit.range, &edit.replacement);
+    }
+    Ok(fixed)
+}
+
+fn collect_fixes(xml: &str) -> Vec<Fix> {
+    let mut fixes = Vec::new();
+    fix_duplicate_ids(xml, &mut fixes);
+    fix_missing_content(xml, &mut fixes);
+    fix_unescaped_content(xml, &mut fixes);
+    fix_created_date(xml, &mut fixes);
+    fixes
+}
+
+fn fixable(check: &str, code: &str, subject: &str, message: String) -> Diagnostic {
+    Diagnostic::new(check, code, subject, Severity::Error, message).fixable()
+}
+
+/// Value and byte range of an `id`/`lang` style attribute within `attributes`
+fn attribute(pattern: &Regex, attributes: &str, offset: usize) -> Option<(String, Range<usize>)> {
+    let caps = pattern.captures(attributes)?;
+    let value = caps.get(1).or_else(|| caps.get(2))?;
+    Some((value.as_str().to_string(), offset + value.start()..offset + value.end()))
+}
+
+fn fix_duplicate_ids(xml: &str, fixes: &mut Vec<Fix>) {
+    let mut occurrences: Vec<(String, Range<usize>)> = Vec::new();
+    for caps in SECTION_ELEMENT.captures_iter(xml) {
+        let attributes = caps.get(1).unwrap();
+        if let Some(id) = attribute(&ID_ATTRIBUTE, attributes.as_str(), attributes.start()) {
+            occurrences.push(id);
+        }
+    }
+
+    let mut taken: HashSet<String> = occurrences.iter().map(|(id, _)| id.clone()).collect();
+    let mut seen: HashSet<&str> = HashSet::new();
+    let mut renames: Vec<(String, Vec<Edit>)> = Vec::new();
+
+    for (id, range) in &occurrences {
+        if seen.insert(id.as_str()) {
+            continue;
+        }
+        let new_id = unique_section_id(id, &taken);
+        taken.insert(new_id.clone());
+        let edit = Edit { range: range.clone(), replacement: new_id };
+        match renames.iter_mut().find(|(existing, _)| existing == id) {
+            Some((_, edits)) => edits.push(edit),
+            None => renames.push((id.clone(), vec![edit])),
+        }
+    }
+
+    for (id, edits) in renames {
+        let message = format!("Duplicate section ID '{}'; later sections get a numeric suffix", id);
+        fixes.push(Fix { diagnostic: fixable("schema", "duplicate-section-id", &id, message), edits });
+    }
+}
+
+fn fix_missing_content(xml: &str, fixes: &mut Vec<Fix>) {
+    for (index, caps) in SECTION_ELEMENT.captures_iter(xml).enumerate() {
+        let attributes = caps.get(1).unwrap();
+        let subject = attribute(&ID_ATTRIBUTE, attributes.as_str(), 0)
+            .map(|(id, _)| id)
+            .unwrap_or_else(|| format!("#{}", index + 1));
+
+        let edit = match caps.get(2) {
+            // Self-closing `<section ... />`
+            None => {
+                let whole = caps.get(0).unwrap();
+                Edit {
+                    range: whole.end() - 2..whole.end(),
+                    replacement: "><content><![CDATA[]]></content></section>".to_string(),
+                }
+            }
+            Some(body) if CONTENT_ELEMENT.is_match(body.as_str()) => continue,
+            Some(body) => {
+                let without_cdata = CDATA_BLOCK.replace_all(body.as_str(), "");
+                if CHILD_ELEMENT.is_match(&without_cdata) {
+                    // Other elements present; add an empty content element before them
+                    Edit {
+                        range: body.start()..body.start(),
+                        replacement: "<content><![CDATA[]]></content>".to_string(),
+                    }
+                } else {
+                    // Bare text or CDATA directly inside the section becomes its content
+                    Edit {
+                        range: body.range(),
+                        replacement: format!("<content>{}</content>", as_cdata(body.as_str())),
+                    }
+                }
+            }
+        };
+
+        let message = format!("Section '{}' has no content element", subject);
+        fixes.push(Fix { diagnostic: fixable("schema", "missing-content", &subject, message), edits: vec![edit] });
+    }
+}
+
+fn fix_unescaped_content(xml: &str, fixes: &mut Vec<Fix>) {
+    for section in SECTION_ELEMENT.captures_iter(xml) {
+        let Some(body) = section.get(2) else {
+            continue;
+        };
+        let section_id = attribute(&ID_ATTRIBUTE, section.get(1).unwrap().as_str(), 0)
+            .map(|(id, _)| id)
+            .unwrap_or_default();
+
+        for content in CONTENT_ELEMENT.captures_iter(body.as_str()) {
+            let inner = content.get(2).unwrap();
+            if !needs_cdata(inner.as_str()) {
+                continue;
+            }
+            let subject = match attribute(&LANG_ATTRIBUTE, content.get(1).unwrap().as_str(), 0) {
+                Some((lang, _)) => format!("{}:{}", section_id, lang),
+                None => section_id.clone(),
+            };
+            let edit = Edit {
+                range: body.start() + inner.start()..body.start() + inner.end(),
+                replacement: as_cdata(inner.as_str()),
+            };
+            let message = format!("Content of section '{}' has unescaped markup; it will be wrapped in CDATA", subject);
+            fixes.push(Fix { diagnostic: fixable("schema", "unescaped-content", &subject, message), edits: vec![edit] });
+        }
+    }
+}
+
+fn fix_created_date(xml: &str, fixes: &mut Vec<Fix>) {
+    let Some(caps) = CREATED_ELEMENT.captures(xml) else {
+        return;
+    };
+    let value = caps.get(1).unwrap();
+    if is_valid_date(value.as_str()) {
+        return;
+    }
+    let Some(normalized) = normalize_date(value.as_str()) else {
+        return;
+    };
+
+    let message = format!("Created date '{}' is not ISO 8601; it will become '{}'", value.as_str(), normalized);
+    fixes.push(Fix {
+        diagnostic: fixable("dates", "invalid-date", "created", message),
+        edits: vec![Edit { range: value.range(), replacement: normalized }],
+    });
+}
+
+/// Rewrite unambiguous date spellings (`2025/10/9`, `9.10.2025`, `2025-10-09 08:30`) as ISO 8601
+pub fn normalize_date(value: &str) -> Option<String> {
+    let value = value.trim();
+    let number = |m: Option<regex::Match>| m.map(|m| m.as_str().parse::<u32>().unwrap_or(u32::MAX));
+
+    let normalized = if let Some(caps) = YEAR_FIRST_DATE.captures(value) {
+        let date = format!("{}-{:02}-{:02}", &caps[1], number(caps.get(2))?, number(caps.get(3))?);
+        match number(caps.get(4)) {
+            Some(hour) => format!(
+                "{}T{:02}:{:02}:{:02}",
+                date,
+                hour,
+                number(caps.get(5))?,
+                number(caps.get(6)).unwrap_or(0)
+            ),
+            None => date,
+        }
+    } else if let Some(caps) = DAY_FIRST_DATE.captures(value) {
+        format!("{}-{:02}-{:02}", &caps[3], number(caps.get(2))?, number(caps.get(1))?)
+    } else {
+        return None;
+    };
+
+    is_valid_date(&normalized).then_some(normalized)
+}
+
+/// Whether content text outside CDATA contains characters that break the XML
+fn needs_cdata(inner: &str) -> bool {
+    if inner.trim_start().starts_with("<![CDATA[") {
+        return false;
+    }
+    inner.contains('<')
+        || inner
+            .match_indices('&')
+            .any(|(i, _)| !ENTITY.is_match(&inner[i..]))
+}
+
+/// Wrap text in CDATA, decoding entities first so the content reads the same
+fn as_cdata(text: &str) -> String {
+    if text.trim_start().starts_with("<![CDATA[") && !has_text_outside_cdata(text) {
+        return text.to_string();
+    }
+    let decoded = decode_entities(text);
+    format!("<![CDATA[{}]]>", decoded.replace("]]>", "]]]]><![CDATA[>"))
+}
+
+fn has_text_outside_cdata(text: &str) -> bool {
+    let rest = CDATA_BLOCK.replace_all(text, "");
+    !rest.trim().is_empty()
+}
+
+fn decode_entities(text: &str) -> String {
+    let mut decoded = String::with_capacity(text.len());
+    let mut rest = text;
+
+    while let Some(i) = rest.find('&') {
+        decoded.push_str(&rest[..i]);
+        rest = &rest[i..];
+        let entity_len = ENTITY.find(rest).map(|m| m.end());
+        let replacement = entity_len.and_then(|len| decode_entity(&rest[1..len - 1]));
+        match (entity_len, replacement) {
+            (Some(len), Some(c)) => {
+                decoded.push(c);
+                rest = &rest[len..];
+            }
+            _ => {
+                decoded.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+
+    decoded.push_str(rest);
+    decoded
+}
+
+fn decode_entity(name: &str) -> Option<char> {
+    match name {
+        "amp" => Some('&'),
+        "lt" => Some('<'),
+        "gt" => Some('>'),
+        "quot" => Some('"'),
+        "apos" => Some('\''),
+        _ => {
+            let code = match name.strip_prefix("#x") {
+                Some(hex) => u32::from_str_radix(hex, 16).ok()?,
+                None => name.strip_prefix('#')?.parse().ok()?,
+            };
+            char::from_u32(code)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parsers::parse_xml;
+    use crate::validators::schema_validator::validate_schema;
+
+    fn document(created: &str, sections: &str) -> String {
+        format!(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<context version="1.0">
+  <meta>
+    <title>Fixable</title>
+    <author>Test</author>
+    <created>{}</created>
+    <app name="CEC" version="0.1.0"/>
+    <tags></tags>
+    <description></description>
+  </meta>
+  <variables></variables>
+  <sections>
+{}
+  </sections>
+</context>"#,
+            created, sections
+        )
+    }
+
+    fn ids(xml: &str) -> Vec<String> {
+        find_fixable(xml).into_iter().map(|d| d.id).collect()
+    }
+
+    fn fix_all(xml: &str) -> String {
+        apply_fixes(xml, &ids(xml)).unwrap()
+    }
+
+    #[test]
+    fn test_valid_document_has_nothing_to_fix() {
+        let xml = document("2025-10-09", r#"<section id="a" type="intent"><content><![CDATA[A & <b>]]></content></section>"#);
+        assert!(find_fixable(&xml).is_empty());
+    }
+
+    #[test]
+    fn test_renames_duplicate_section_ids() {
+        let xml = document(
+            "2025-10-09",
+            r#"<section id="a" type="intent"><content>One</content></section>
+<section id="a" type="process"><content>Two</content></section>
+<section id="a-2" type="process"><content>Three</content></section>
+<section id="a" type="process"><content>Four</content></section>"#,
+        );
+        assert_eq!(ids(&xml), vec!["duplicate-section-id:a"]);
+
+        let fixed = fix_all(&xml);
+        validate_schema(&fixed).unwrap();
+        let doc = parse_xml(&fixed).unwrap();
+        let section_ids: Vec<&str> = doc.sections.iter().map(|s| s.id.as_str()).collect();
+        assert_eq!(section_ids, vec!["a", "a-3", "a-2", "a-4"]);
+    }
+
+    #[test]
+    fn test_adds_missing_content_elements() {
+        let xml = document(
+            "2025-10-09",
+            r#"<section id="empty" type="intent"/>
+<section id="bare" type="intent">Plain text &amp; more</section>
+<section id="cdata" type="intent"><![CDATA[Already <raw>]]></section>"#,
+        );
+        assert_eq!(
+            ids(&xml),
+            vec!["missing-content:empty", "missing-content:bare", "missing-content:cdata"]
+        );
+
+        let fixed = fix_all(&xml);
+        validate_schema(&fixed).unwrap();
+        let doc = parse_xml(&fixed).unwrap();
+        assert_eq!(doc.sections[0].content, "");
+        assert_eq!(doc.sections[1].content, "Plain text & more");
+        assert_eq!(doc.sections[2].content, "Already <raw>");
+    }
+
+    #[test]
+    fn test_wraps_unescaped_content_in_cdata() {
+        let xml = document(
+            "2025-10-09",
+            r#"<section id="a" type="intent"><content>Use <br> & keep &lt;tags&gt;</content><content lang="de">Tom & Jerry</content></section>"#,
+        );
+        assert_eq!(ids(&xml), vec!["unescaped-content:a", "unescaped-content:a:de"]);
+
+        let fixed = apply_fixes(&xml, &["unescaped-content:a".to_string()]).unwrap();
+        assert!(fixed.contains("<![CDATA[Use <br> & keep <tags>]]>"));
+        assert!(fixed.contains("Tom & Jerry</content>"));
+
+        let doc = parse_xml(&fix_all(&xml)).unwrap();
+        assert_eq!(doc.sections[0].content, "Use <br> & keep <tags>");
+        assert_eq!(doc.sections[0].translations["de"], "Tom & Jerry");
+    }
+
+    #[test]
+    fn test_normalizes_malformed_dates() {
+        let xml = document("2025/10/9", r#"<section id="a" type="intent"><content>A</content></section>"#);
+        assert_eq!(ids(&xml), vec!["invalid-date:created"]);
+        assert!(fix_all(&xml).contains("<created>2025-10-09</created>"));
+
+        assert_eq!(normalize_date("9.10.2025").as_deref(), Some("2025-10-09"));
+        assert_eq!(normalize_date("2025-10-09 8:30").as_deref(), Some("2025-10-09T08:30:00"));
+        assert_eq!(normalize_date("10/09/2025"), None);
+        assert_eq!(normalize_date("2025/13/01"), None);
+    }
+
+    #[test]
+    fn test_rejects_unknown_diagnostic_ids() {
+        let xml = document("2025-10-09", r#"<section id="a" type="intent"><content>A</content></section>"#);
+        assert!(matches!(
+            apply_fixes(&xml, &["duplicate-section-id:a".to_string()]),
+            Err(ContextError::InvalidArgument(_))
+        ));
+    }
+}
@@ -0,0 +1,101 @@
+use crate::error::Result;
+use crate::models::*;
+use crate::parsers::mermaid_parser;
+use crate::validators::publish_check::{Diagnostic, Severity};
+
+/// Compare a regenerated diagram with the current one before it replaces it
+///
+/// Reports an error when the new diagram has no nodes, and one per section
+/// that a node linked to through a click action but no node links to any
+/// more, so saving a canvas edit cannot silently drop the node↔section
+/// mapping. Fails when either diagram does not parse.
+pub fn check_flow_update(previous_code: Option<&str>, next_code: &str) -> Result<Vec<Diagnostic>> {
+    let mut diagnostics = Vec::new();
+    let (next_graph, next_refs) = live_links(next_code)?;
+    if next_graph.nodes.is_empty() {
+        diagnostics.push(Diagnostic::new(
+            "flow",
+            "flow-empty",
+            "flow",
+            Severity::Error,
+            "The new diagram has no nodes; saving it would remove the whole flow and its section links",
+        ));
+    }
+
+    let Some(previous_code) = previous_code else {
+        return Ok(diagnostics);
+    };
+    let (_, previous_refs) = live_links(previous_code)?;
+    let mut reported: Vec<&str> = Vec::new();
+    for node_ref in &previous_refs {
+        let section_id = node_ref.section_id.as_str();
+        if reported.contains(&section_id) || next_refs.iter().any(|next| next.section_id == section_id) {
+            continue;
+        }
+        reported.push(section_id);
+        diagnostics.push(
+            Diagnostic::new(
+                "flow",
+                "flow-lost-link",
+                section_id,
+                Severity::Error,
+                format!(
+                    "Section '{}' was linked from node '{}' but no node links to it in the new diagram",
+                    section_id, node_ref.node_id
+                ),
+            )
+            .in_section(section_id),
+        );
+    }
+    Ok(diagnostics)
+}
+
+/// The parsed diagram with the click actions of nodes that are actually in it
+fn live_links(code: &str) -> Result<(GraphStructure, Vec<NodeReference>)> {
+    let graph = mermaid_parser::parse_mermaid(code)?;
+    let node_refs = mermaid_parser::parse_click_actions(code)?
+        .into_iter()
+        .filter(|node_ref| graph.nodes.iter().any(|node| node.id == node_ref.node_id))
+        .collect();
+    Ok((graph, node_refs))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CURRENT: &str = "flowchart TD\n  A[Intent] --> B[Process]\n  click A \"#intent-1\"\n  click B \"#process-1\"";
+
+    fn ids(diagnostics: &[Diagnostic]) -> Vec<&str> {
+        diagnostics.iter().map(|d| d.id.as_str()).collect()
+    }
+
+    #[test]
+    fn test_unchanged_links_are_clean() {
+        let moved = "flowchart LR\n  X[Intent] --> B[Process]\n  click X \"#intent-1\"\n  click B \"#process-1\"";
+        assert!(check_flow_update(Some(CURRENT), CURRENT).unwrap().is_empty());
+        assert!(check_flow_update(Some(moved), CURRENT).unwrap().is_empty());
+        assert!(check_flow_update(None, CURRENT).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_lost_links() {
+        let dropped_click = "flowchart TD\n  A[Intent] --> B[Process]\n  click A \"#intent-1\"";
+        let dropped_node = "flowchart TD\n  A[Intent]\n  click A \"#intent-1\"\n  click B \"#process-1\"";
+
+        let diagnostics = check_flow_update(Some(CURRENT), dropped_click).unwrap();
+        assert_eq!(ids(&diagnostics), vec!["flow-lost-link:process-1"]);
+        assert_eq!(diagnostics[0].section_id.as_deref(), Some("process-1"));
+        assert_eq!(ids(&check_flow_update(Some(CURRENT), dropped_node).unwrap()), vec!["flow-lost-link:process-1"]);
+    }
+
+    #[test]
+    fn test_empty_diagram() {
+        let diagnostics = check_flow_update(Some(CURRENT), "flowchart TD").unwrap();
+        assert_eq!(
+            ids(&diagnostics),
+            vec!["flow-empty:flow", "flow-lost-link:intent-1", "flow-lost-link:process-1"]
+        );
+        assert!(diagnostics.iter().all(|d| d.severity == Severity::Error));
+    }
+}
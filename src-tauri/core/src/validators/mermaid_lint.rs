@@ -0,0 +1,145 @@
+use std::sync::LazyLock;
+use regex::Regex;
+use crate::parsers::mermaid_parser;
+use crate::validators::publish_check::{Diagnostic, Severity};
+
+static QUOTED: LazyLock<Regex> = LazyLock::new(|| Regex::new(r#""[^"]*""#).unwrap());
+/// Any bracketed label or `|edge label|`, removed before looking at arrows
+static LABELS: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\[[^\]]*\]+|\(+[^)]*\)+|\{+[^}]*\}+|\|[^|]*\|").unwrap());
+/// A node ID directly followed by the double circle shape, which the parser records as a plain circle
+static DOUBLE_CIRCLE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"(?:^|[\s&;|])(\w+)\(\(\(").unwrap());
+static ARROW: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"<?(?:-\.+->?|={2,}>?|~{3,}|-{2,}[ox]\b|-{2,}>?)").unwrap());
+
+/// Diagram types Mermaid renders that are not flowcharts
+const OTHER_DIAGRAMS: &[&str] = &[
+    "sequenceDiagram", "classDiagram", "stateDiagram", "erDiagram", "gantt", "pie", "journey", "gitGraph",
+    "mindmap", "timeline", "quadrantChart", "requirementDiagram", "C4Context", "C4Container", "C4Component",
+    "sankey", "xychart", "block", "packet", "kanban", "architecture",
+];
+
+/// Find Mermaid syntax that renders in the UI but is lost or simplified in `parsed_graph`
+///
+/// The internal parser models flowcharts with the classic node shapes
+/// (`[rect]`, `(round)`, `{rhombus}`, `((circle))` and so on), `-->` edges
+/// (optionally `-->|labelled|`) and click actions. Other diagram types,
+/// double circles, arrow styles and chained or `&`-joined edges are reported
+/// as warnings so missing nodes in the flow canvas are not a surprise.
+pub fn lint_mermaid(mermaid_code: &str) -> Vec<Diagnostic> {
+    let code = mermaid_parser::extract_mermaid_from_markdown(mermaid_code).unwrap_or_else(|_| mermaid_code.to_string());
+    let mut diagnostics = Vec::new();
+
+    let lines = code
+        .lines()
+        .enumerate()
+        .map(|(index, line)| (index + 1, line.trim()))
+        .filter(|(_, line)| !line.is_empty() && !line.starts_with("%%"));
+
+    if let Some((_, header)) = lines.clone().next() {
+        let keyword = header.split_whitespace().next().unwrap_or("");
+        if let Some(kind) = OTHER_DIAGRAMS.iter().find(|kind| keyword.starts_with(**kind)) {
+            diagnostics.push(warning(
+                "mermaid-unsupported-diagram",
+                kind,
+                format!("'{}' diagrams render in the UI but won't appear in parsed_graph; only flowcharts are parsed", kind),
+            ));
+            return diagnostics;
+        }
+    }
+
+    for (number, line) in lines {
+        if line.starts_with("flowchart") || line.starts_with("graph") || line.starts_with("click ") {
+            continue;
+        }
+        if line.starts_with("subgraph") || line == "end" {
+            continue;
+        }
+
+        let unquoted = QUOTED.replace_all(line, "\"\"");
+        for caps in DOUBLE_CIRCLE.captures_iter(&unquoted) {
+            diagnostics.push(warning(
+                "mermaid-unsupported-shape",
+                &caps[1],
+                format!("Line {}: node '{}' uses the double circle shape, which parsed_graph records as a plain circle", number, &caps[1]),
+            ));
+        }
+
+        let structure = LABELS.replace_all(&unquoted, "");
+        let arrows: Vec<&str> = ARROW.find_iter(&structure).map(|m| m.as_str()).collect();
+        if let Some(arrow) = arrows.iter().find(|arrow| **arrow != "-->") {
+            diagnostics.push(warning(
+                "mermaid-unsupported-edge",
+                &number.to_string(),
+                format!(
+                    "Line {}: '{}' links render in the UI but won't appear in parsed_graph; only '-->' edges are parsed",
+                    number, arrow
+                ),
+            ));
+        } else if arrows.len() > 1 {
+            diagnostics.push(warning(
+                "mermaid-chained-edge",
+                &number.to_string(),
+                format!("Line {}: only the first link of a chain is parsed; put each edge on its own line", number),
+            ));
+        }
+        if !arrows.is_empty() && structure.contains('&') {
+            diagnostics.push(warning(
+                "mermaid-multi-node-edge",
+                &number.to_string(),
+                format!("Line {}: '&' links several nodes at once, but only one edge is parsed", number),
+            ));
+        }
+    }
+
+    diagnostics
+}
+
+fn warning(code: &str, subject: &str, message: String) -> Diagnostic {
+    Diagnostic::new("mermaid", code, subject, Severity::Warning, message)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn codes(code: &str) -> Vec<String> {
+        lint_mermaid(code).into_iter().map(|d| d.id).collect()
+    }
+
+    #[test]
+    fn test_supported_syntax_is_clean() {
+        let code = "```mermaid\nflowchart TD\n  %% comment\n  A[Intent] --> B(Evaluate)\n  B -->|cond: score > 3| C{Done?}\n  C --> D((End))\n  click A \"#intent-1\"\n```";
+        assert!(lint_mermaid(code).is_empty());
+    }
+
+    #[test]
+    fn test_unsupported_diagram_type() {
+        let diagnostics = lint_mermaid("sequenceDiagram\n  Alice->>Bob: Hi");
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].id, "mermaid-unsupported-diagram:sequenceDiagram");
+        assert_eq!(diagnostics[0].severity, Severity::Warning);
+        assert!(diagnostics[0].message.contains("won't appear in parsed_graph"));
+    }
+
+    #[test]
+    fn test_double_circle() {
+        let code = "flowchart TD\n  A[Start] --> B{{Prep}}\n  B --> C(((Done)))\n  D[\"Use (((x\"] --> E[[Sub]]";
+        assert_eq!(codes(code), vec!["mermaid-unsupported-shape:C"]);
+    }
+
+    #[test]
+    fn test_edges() {
+        let code = "flowchart LR\n  A -.-> B\n  B ==> C\n  C --> D --> E\n  E & F --> G\n  G[Step -- two] --> H\n  subgraph Review\n  H --- I\n  end";
+        assert_eq!(
+            codes(code),
+            vec![
+                "mermaid-unsupported-edge:2",
+                "mermaid-unsupported-edge:3",
+                "mermaid-chained-edge:4",
+                "mermaid-multi-node-edge:5",
+                "mermaid-unsupported-edge:8",
+            ]
+        );
+    }
+}
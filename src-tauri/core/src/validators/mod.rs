@@ -0,0 +1,5 @@
+pub mod auto_fix;
+pub mod flow_connectivity;
+pub mod mermaid_lint;
+pub mod publish_check;
+pub mod schema_validator;
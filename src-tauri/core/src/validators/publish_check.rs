@@ -0,0 +1,423 @@
+use crate::models::*;
+use crate::parsers::mermaid_parser;
+use crate::processors::{budget_report, citations, locale_format, variable_resolver};
+use crate::validators::mermaid_lint;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::sync::LazyLock;
+
+/// `YYYY-MM-DD` with an optional RFC 3339 time and offset
+static DATE_PATTERN: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^(\d{4})-(\d{2})-(\d{2})(?:T(\d{2}):(\d{2})(?::(\d{2})(?:\.\d+)?)?(?:Z|[+-]\d{2}:\d{2})?)?$").unwrap()
+});
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+/// A single finding of the publish check
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Diagnostic {
+    /// Stable identifier (`code:subject`) so a diagnostic can be selected across runs
+    pub id: String,
+    /// Which check produced it: schema, refs, variables, mermaid, links, dates or budget
+    pub check: String,
+    pub code: String,
+    pub severity: Severity,
+    pub message: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub section_id: Option<String>,
+    /// Whether `apply_fixes` can repair it
+    #[serde(default)]
+    pub fixable: bool,
+}
+
+impl Diagnostic {
+    pub fn new(check: &str, code: &str, subject: &str, severity: Severity, message: impl Into<String>) -> Self {
+        Diagnostic {
+            id: format!("{}:{}", code, subject),
+            check: check.to_string(),
+            code: code.to_string(),
+            severity,
+            message: message.into(),
+            section_id: None,
+            fixable: false,
+        }
+    }
+
+    pub fn fixable(mut self) -> Self {
+        self.fixable = true;
+        self
+    }
+
+    pub fn in_section(mut self, section_id: &str) -> Self {
+        self.section_id = Some(section_id.to_string());
+        self
+    }
+}
+
+/// Publish-readiness of a document
+///
+/// The check is strict: warnings block publishing just like errors, so a
+/// document is only ready when there are no diagnostics at all.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct PublishReport {
+    pub ready: bool,
+    pub error_count: usize,
+    pub warning_count: usize,
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+impl PublishReport {
+    pub fn from_diagnostics(diagnostics: Vec<Diagnostic>) -> Self {
+        let error_count = diagnostics.iter().filter(|d| d.severity == Severity::Error).count();
+        PublishReport {
+            ready: diagnostics.is_empty(),
+            error_count,
+            warning_count: diagnostics.len() - error_count,
+            diagnostics,
+        }
+    }
+}
+
+/// Run the document-level checks (refs, variables, mermaid, links, dates, budgets)
+///
+/// Schema validation and checks that need the filesystem (cross-document
+/// references) are run by the caller, since this works on a parsed document.
+pub fn check_document(doc: &ContextDocument) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+    let mut sections = Vec::new();
+    collect_sections(&doc.sections, &mut sections);
+
+    check_refs(doc, &sections, &mut diagnostics);
+    check_variables(doc, &sections, &mut diagnostics);
+    check_flow(doc, &sections, &mut diagnostics);
+    check_links(doc, &mut diagnostics);
+    check_dates(doc, &mut diagnostics);
+    check_budgets(doc, &mut diagnostics);
+
+    diagnostics
+}
+
+fn collect_sections<'a>(sections: &'a [Section], out: &mut Vec<&'a Section>) {
+    for section in sections {
+        out.push(section);
+        collect_sections(&section.children, out);
+    }
+}
+
+fn check_refs(doc: &ContextDocument, sections: &[&Section], diagnostics: &mut Vec<Diagnostic>) {
+    let ids: HashSet<&str> = sections.iter().map(|s| s.id.as_str()).collect();
+
+    for section in sections {
+        let local_refs = section
+            .ref_target
+            .as_deref()
+            .unwrap_or("")
+            .split_whitespace()
+            .filter(|token| !token.contains('#'));
+        for target in local_refs {
+            if !ids.contains(target) {
+                diagnostics.push(
+                    Diagnostic::new(
+                        "refs",
+                        "missing-ref-target",
+                        &format!("{}:{}", section.id, target),
+                        Severity::Error,
+                        format!("Section '{}' references missing section '{}'", section.id, target),
+                    )
+                    .in_section(&section.id),
+                );
+            }
+        }
+
+        if section.content.trim().is_empty() {
+            diagnostics.push(
+                Diagnostic::new(
+                    "refs",
+                    "empty-section",
+                    &section.id,
+                    Severity::Warning,
+                    format!("Section '{}' has no content", section.id),
+                )
+                .in_section(&section.id),
+            );
+        }
+    }
+
+    for unresolved in citations::find_unresolved_citations(doc) {
+        diagnostics.push(
+            Diagnostic::new(
+                "refs",
+                "unresolved-citation",
+                &format!("{}:{}", unresolved.section_id, unresolved.reference_id),
+                Severity::Error,
+                format!(
+                    "Section '{}' cites unknown reference '{}'",
+                    unresolved.section_id, unresolved.reference_id
+                ),
+            )
+            .in_section(&unresolved.section_id),
+        );
+    }
+}
+
+fn check_variables(doc: &ContextDocument, sections: &[&Section], diagnostics: &mut Vec<Diagnostic>) {
+    let defined: HashSet<&str> = doc.variables.iter().map(|v| v.name.as_str()).collect();
+    let mut used: HashSet<String> = HashSet::new();
+
+    for section in sections {
+        let mut names = variable_resolver::find_variable_references(&section.content);
+        for content in section.translations.values() {
+            names.extend(variable_resolver::find_variable_references(content));
+        }
+
+        let mut reported = HashSet::new();
+        for name in names {
+            let undefined = !defined.contains(name.as_str()) && !locale_format::is_builtin_variable(&name);
+            if undefined && reported.insert(name.clone()) {
+                diagnostics.push(
+                    Diagnostic::new(
+                        "variables",
+                        "undefined-variable",
+                        &format!("{}:{}", section.id, name),
+                        Severity::Error,
+                        format!("Section '{}' uses undefined variable '${{{}}}'", section.id, name),
+                    )
+                    .in_section(&section.id),
+                );
+            }
+            used.insert(name);
+        }
+    }
+
+    for variable in &doc.variables {
+        if variable.value.trim().is_empty() {
+            diagnostics.push(Diagnostic::new(
+                "variables",
+                "empty-variable",
+                &variable.name,
+                Severity::Warning,
+                format!("Variable '{}' has no value", variable.name),
+            ));
+        }
+        if !used.contains(&variable.name) {
+            diagnostics.push(Diagnostic::new(
+                "variables",
+                "unused-variable",
+                &variable.name,
+                Severity::Warning,
+                format!("Variable '{}' is never used", variable.name),
+            ));
+        }
+    }
+}
+
+fn check_flow(doc: &ContextDocument, sections: &[&Section], diagnostics: &mut Vec<Diagnostic>) {
+    let Some(flow) = &doc.flow_graph else {
+        return;
+    };
+
+    let graph = match mermaid_parser::parse_mermaid(&flow.mermaid_code) {
+        Ok(graph) => graph,
+        Err(e) => {
+            diagnostics.push(Diagnostic::new("mermaid", "mermaid-syntax", &flow.id, Severity::Error, e.to_string()));
+            return;
+        }
+    };
+    let node_refs = match mermaid_parser::parse_click_actions(&flow.mermaid_code) {
+        Ok(node_refs) => node_refs,
+        Err(e) => {
+            diagnostics.push(Diagnostic::new("mermaid", "mermaid-syntax", &flow.id, Severity::Error, e.to_string()));
+            return;
+        }
+    };
+
+    diagnostics.extend(mermaid_lint::lint_mermaid(&flow.mermaid_code));
+
+    let ids: HashSet<&str> = sections.iter().map(|s| s.id.as_str()).collect();
+    let nodes: HashSet<&str> = graph.nodes.iter().map(|n| n.id.as_str()).collect();
+
+    for node_ref in &node_refs {
+        if !nodes.contains(node_ref.node_id.as_str()) {
+            diagnostics.push(Diagnostic::new(
+                "mermaid",
+                "click-unknown-node",
+                &node_ref.node_id,
+                Severity::Error,
+                format!("Click action targets node '{}' which is not in the flow", node_ref.node_id),
+            ));
+        }
+        if !ids.contains(node_ref.section_id.as_str()) {
+            diagnostics.push(Diagnostic::new(
+                "links",
+                "broken-click-target",
+                &format!("{}:{}", node_ref.node_id, node_ref.section_id),
+                Severity::Error,
+                format!(
+                    "Node '{}' links to missing section '{}'",
+                    node_ref.node_id, node_ref.section_id
+                ),
+            ));
+        }
+    }
+}
+
+fn check_links(doc: &ContextDocument, diagnostics: &mut Vec<Diagnostic>) {
+    for reference in &doc.references {
+        let Some(url) = &reference.url else {
+            continue;
+        };
+        let has_scheme = ["http://", "https://", "doi:", "mailto:"]
+            .iter()
+            .any(|scheme| url.starts_with(scheme));
+        if !has_scheme || url.contains(char::is_whitespace) {
+            diagnostics.push(Diagnostic::new(
+                "links",
+                "invalid-url",
+                &reference.id,
+                Severity::Error,
+                format!("Reference '{}' has an invalid URL '{}'", reference.id, url),
+            ));
+        }
+    }
+}
+
+fn check_dates(doc: &ContextDocument, diagnostics: &mut Vec<Diagnostic>) {
+    if !is_valid_date(&doc.meta.created) {
+        diagnostics.push(Diagnostic::new(
+            "dates",
+            "invalid-date",
+            "created",
+            Severity::Error,
+            format!("Created date '{}' is not an ISO 8601 date", doc.meta.created),
+        ));
+    }
+}
+
+fn check_budgets(doc: &ContextDocument, diagnostics: &mut Vec<Diagnostic>) {
+    for usage in budget_report::budget_report(doc).into_iter().filter(|u| u.over_budget) {
+        diagnostics.push(
+            Diagnostic::new(
+                "budget",
+                "over-budget",
+                &usage.section_id,
+                Severity::Error,
+                format!(
+                    "Section '{}' uses {} of its {} budget",
+                    usage.section_id, usage.used, usage.budget
+                ),
+            )
+            .in_section(&usage.section_id),
+        );
+    }
+}
+
+/// Whether the value is an ISO 8601 / RFC 3339 date with in-range fields
+pub fn is_valid_date(value: &str) -> bool {
+    let Some(caps) = DATE_PATTERN.captures(value.trim()) else {
+        return false;
+    };
+    let field = |i: usize| caps.get(i).map_or(0, |m| m.as_str().parse::<u32>().unwrap_or(u32::MAX));
+
+    (1..=12).contains(&field(2))
+        && (1..=31).contains(&field(3))
+        && field(4) <= 23
+        && field(5) <= 59
+        && field(6) <= 60
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn clean_document() -> ContextDocument {
+        ContextDocument::builder()
+            .title("Ready")
+            .created("2025-10-09T20:20:32.902425+00:00")
+            .variable("goal", "Ship v1")
+            .section("intent-1", "intent", "Goal: ${goal} [@spec]")
+            .reference(Reference {
+                id: "spec".to_string(),
+                title: "Spec".to_string(),
+                url: Some("https://example.com/spec".to_string()),
+                citation: String::new(),
+            })
+            .flow("graph TD\n    A[Intent]\n    click A \"#intent-1\"")
+            .build()
+            .unwrap()
+    }
+
+    fn codes(doc: &ContextDocument) -> Vec<String> {
+        check_document(doc).into_iter().map(|d| d.code).collect()
+    }
+
+    #[test]
+    fn test_clean_document_is_ready() {
+        let report = PublishReport::from_diagnostics(check_document(&clean_document()));
+        assert!(report.ready, "{:?}", report.diagnostics);
+    }
+
+    #[test]
+    fn test_reports_variable_problems() {
+        let mut doc = clean_document();
+        doc.sections[0].content = "Goal: ${missing} as of ${date.today}".to_string();
+
+        assert_eq!(codes(&doc), vec!["undefined-variable", "unused-variable"]);
+    }
+
+    #[test]
+    fn test_reports_broken_links_and_citations() {
+        let mut doc = clean_document();
+        doc.sections[0].content = "Goal: ${goal} [@other]".to_string();
+        doc.sections[0].ref_target = Some("intent-9".to_string());
+        doc.references[0].url = Some("example.com".to_string());
+        doc.flow_graph.as_mut().unwrap().mermaid_code = "graph TD\n    A[Intent]\n    click A \"#gone\"".to_string();
+
+        let diagnostics = check_document(&doc);
+        let ids: Vec<&str> = diagnostics.iter().map(|d| d.id.as_str()).collect();
+
+        assert_eq!(
+            ids,
+            vec![
+                "missing-ref-target:intent-1:intent-9",
+                "unresolved-citation:intent-1:other",
+                "broken-click-target:A:gone",
+                "invalid-url:spec",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_warnings_block_publishing() {
+        let mut doc = clean_document();
+        doc.variables.push(Variable { name: "spare".to_string(), value: "x".to_string(), description: None, sensitive: false, resolve_in: vec![], due: None });
+
+        let report = PublishReport::from_diagnostics(check_document(&doc));
+
+        assert!(!report.ready);
+        assert_eq!((report.error_count, report.warning_count), (0, 1));
+    }
+
+    #[test]
+    fn test_reports_sections_over_budget() {
+        let mut doc = clean_document();
+        doc.sections[0].budget = Budget::parse("5 chars");
+
+        assert_eq!(codes(&doc), vec!["over-budget"]);
+    }
+
+    #[test]
+    fn test_date_validation() {
+        assert!(is_valid_date("2025-10-09"));
+        assert!(is_valid_date("2025-10-09T20:20:32Z"));
+        assert!(is_valid_date("2025-10-09T20:20:32.902425+00:00"));
+        assert!(!is_valid_date("2025-13-09"));
+        assert!(!is_valid_date("09/10/2025"));
+        assert!(!is_valid_date("2025-10-09T25:00"));
+    }
+}
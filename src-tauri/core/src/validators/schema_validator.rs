@@ -1,9 +1,7 @@
 use crate::error::{ContextError, Result};
+use crate::models::{Budget, ResolutionContext, SECTION_TYPES as VALID_SECTION_TYPES};
 use std::collections::HashSet;
 
-/// Valid section types according to schema
-const VALID_SECTION_TYPES: &[&str] = &["intent", "evaluation", "process", "alternatives"];
-
 /// Validate XML content against context document schema
 ///
 /// Validates:
@@ -11,6 +9,8 @@ const VALID_SECTION_TYPES: &[&str] = &["intent", "evaluation", "process", "alter
 /// 2. Required elements present (meta, variables, sections)
 /// 3. Valid section types
 /// 4. Unique section IDs
+/// 5. Unique reference IDs
+/// 6. Known `resolveIn` contexts and valid `due` dates on variables
 pub fn validate_schema(xml_content: &str) -> Result<()> {
     // Parse XML for validation
     let doc = roxmltree::Document::parse(xml_content)
@@ -28,6 +28,14 @@ pub fn validate_schema(xml_content: &str) -> Result<()> {
     // Validate required elements
     validate_required_elements(&root)?;
 
+    // Validate variables
+    if let Some(variables_elem) = root
+        .children()
+        .find(|n| n.is_element() && n.tag_name().name() == "variables")
+    {
+        validate_variables(&variables_elem)?;
+    }
+
     // Validate sections
     if let Some(sections_elem) = root
         .children()
@@ -36,6 +44,14 @@ pub fn validate_schema(xml_content: &str) -> Result<()> {
         validate_sections(&sections_elem)?;
     }
 
+    // Validate references
+    if let Some(references_elem) = root
+        .children()
+        .find(|n| n.is_element() && n.tag_name().name() == "references")
+    {
+        validate_references(&references_elem)?;
+    }
+
     Ok(())
 }
 
@@ -104,6 +120,70 @@ fn validate_meta(meta: &roxmltree::Node) -> Result<()> {
     Ok(())
 }
 
+/// Validate `resolveIn` lists only known resolution contexts and `due` is a date
+fn validate_variables(variables_elem: &roxmltree::Node) -> Result<()> {
+    for var in variables_elem
+        .children()
+        .filter(|n| n.is_element() && n.tag_name().name() == "var")
+    {
+        let name = var.attribute("name").unwrap_or_default();
+        if let Some(resolve_in) = var.attribute("resolveIn") {
+            let invalid = resolve_in.split(',').find(|context| ResolutionContext::parse(context).is_none());
+            if let Some(invalid) = invalid {
+                return Err(ContextError::SchemaValidationError(format!(
+                    "Variable '{}' has invalid resolveIn context '{}'. Use preview, export or assembly",
+                    name,
+                    invalid.trim()
+                )));
+            }
+        }
+        if let Some(due) = var.attribute("due").filter(|due| !due.trim().is_empty()) {
+            if chrono::NaiveDate::parse_from_str(due.trim(), "%Y-%m-%d").is_err() {
+                return Err(ContextError::SchemaValidationError(format!(
+                    "Variable '{}' has invalid due date '{}'. Use YYYY-MM-DD",
+                    name, due
+                )));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Validate references have unique IDs and a title
+fn validate_references(references_elem: &roxmltree::Node) -> Result<()> {
+    let mut reference_ids = HashSet::new();
+
+    for reference in references_elem
+        .children()
+        .filter(|n| n.is_element() && n.tag_name().name() == "reference")
+    {
+        let id = reference.attribute("id").ok_or_else(|| {
+            ContextError::SchemaValidationError("Reference must have 'id' attribute".to_string())
+        })?;
+
+        if !reference_ids.insert(id.to_string()) {
+            return Err(ContextError::SchemaValidationError(format!(
+                "Duplicate reference ID '{}' found. Reference IDs must be unique.",
+                id
+            )));
+        }
+
+        let has_title = reference
+            .children()
+            .any(|n| n.is_element() && n.tag_name().name() == "title");
+
+        if !has_title {
+            return Err(ContextError::SchemaValidationError(format!(
+                "Reference '{}' must have a 'title' element",
+                id
+            )));
+        }
+    }
+
+    Ok(())
+}
+
 /// Validate sections structure
 fn validate_sections(sections_elem: &roxmltree::Node) -> Result<()> {
     let mut section_ids = HashSet::new();
@@ -140,6 +220,26 @@ fn validate_sections(sections_elem: &roxmltree::Node) -> Result<()> {
             )));
         }
 
+        if let Some(budget) = section.attribute("budget") {
+            if Budget::parse(budget).is_none() {
+                return Err(ContextError::SchemaValidationError(format!(
+                    "Section '{}' has invalid budget '{}'. Use e.g. '500 tokens' or '2000 chars'",
+                    id, budget
+                )));
+            }
+        }
+
+        for flag in ["sensitive", "encrypted"] {
+            if let Some(value) = section.attribute(flag) {
+                if !["true", "false", "1", "0"].contains(&value.trim()) {
+                    return Err(ContextError::SchemaValidationError(format!(
+                        "Section '{}' has invalid {} value '{}'. Use 'true' or 'false'",
+                        id, flag, value
+                    )));
+                }
+            }
+        }
+
         // Check for duplicate IDs
         if !section_ids.insert(id.to_string()) {
             return Err(ContextError::SchemaValidationError(format!(
@@ -249,6 +349,82 @@ mod tests {
         assert!(err_msg.contains("intent, evaluation, process, alternatives"));
     }
 
+    #[test]
+    fn test_invalid_section_budget() {
+        let xml = r#"
+        <context version="1.0">
+            <meta>
+                <title>Test</title>
+                <author>Author</author>
+                <created>2025-10-09T20:20:32+00:00</created>
+                <app name="CEC" version="0.1.0"/>
+                <tags>test</tags>
+                <description>Test</description>
+            </meta>
+            <variables></variables>
+            <sections>
+                <section id="test-1" type="intent" budget="500 tokens">
+                    <content>Content</content>
+                </section>
+                <section id="test-2" type="intent" budget="a lot">
+                    <content>Content</content>
+                </section>
+            </sections>
+        </context>
+        "#;
+
+        let err_msg = validate_schema(xml).unwrap_err().to_string();
+        assert!(err_msg.contains("Section 'test-2' has invalid budget 'a lot'"));
+    }
+
+    #[test]
+    fn test_invalid_variable_resolution_context() {
+        let xml = r#"
+        <context version="1.0">
+            <meta>
+                <title>Test</title>
+                <author>Author</author>
+                <created>2025-10-09T20:20:32+00:00</created>
+                <app name="CEC" version="0.1.0"/>
+                <tags>test</tags>
+                <description>Test</description>
+            </meta>
+            <variables>
+                <var name="apiKey" resolveIn="export, assembly">secret</var>
+                <var name="userName" resolveIn="preview,exprt">Ana</var>
+            </variables>
+            <sections></sections>
+        </context>
+        "#;
+
+        let err_msg = validate_schema(xml).unwrap_err().to_string();
+        assert!(err_msg.contains("Variable 'userName' has invalid resolveIn context 'exprt'"));
+    }
+
+    #[test]
+    fn test_invalid_variable_due_date() {
+        let xml = r#"
+        <context version="1.0">
+            <meta>
+                <title>Test</title>
+                <author>Author</author>
+                <created>2025-10-09T20:20:32+00:00</created>
+                <app name="CEC" version="0.1.0"/>
+                <tags>test</tags>
+                <description>Test</description>
+            </meta>
+            <variables>
+                <var name="launchDate" due="2025-11-01">TBD</var>
+                <var name="owner" due="next week">Ana</var>
+            </variables>
+            <sections></sections>
+        </context>
+        "#;
+
+        let err_msg = validate_schema(xml).unwrap_err().to_string();
+        assert!(err_msg.contains("Variable 'owner' has invalid due date 'next week'"));
+    }
+
     #[test]
     fn test_duplicate_section_ids() {
         let xml = r#"
@@ -281,6 +457,35 @@ mod tests {
             .contains("Duplicate section ID 'test-1'"));
     }
 
+    #[test]
+    fn test_duplicate_reference_ids() {
+        let xml = r#"
+        <context version="1.0">
+            <meta>
+                <title>Test</title>
+                <author>Author</author>
+                <created>2025-10-09T20:20:32+00:00</created>
+                <app name="CEC" version="0.1.0"/>
+                <tags>test</tags>
+                <description>Test</description>
+            </meta>
+            <variables></variables>
+            <sections></sections>
+            <references>
+                <reference id="ref-1"><title>One</title></reference>
+                <reference id="ref-1"><title>Two</title></reference>
+            </references>
+        </context>
+        "#;
+
+        let result = validate_schema(xml);
+        assert!(result.is_err());
+        assert!(result
+            .unwrap_err()
+            .to_string()
+            .contains("Duplicate reference ID 'ref-1'"));
+    }
+
     #[test]
     fn test_nested_section_rejected() {
         let xml = r#"
@@ -1,4 +1,4 @@
-use flow_writer_lib::services::flow_service;
+use flow_writer_core::services::flow_service;
 use std::io::Write;
 use tempfile::NamedTempFile;
 
@@ -0,0 +1,57 @@
+//! Load-time budget for large documents
+//!
+//! Timing is only meaningful in optimized builds, so the test is ignored by default:
+//! `cargo test -p flow-writer-core --release --test performance_budget -- --ignored`
+
+use flow_writer_core::models::{ContextDocument, SECTION_TYPES};
+use flow_writer_core::serializers::serialize_xml;
+use flow_writer_core::services::flow_service;
+use std::time::{Duration, Instant};
+
+/// Validate + parse + resolve of a ~10MB document
+const LOAD_BUDGET: Duration = Duration::from_millis(500);
+
+fn generate_xml(section_count: usize) -> String {
+    let paragraph = "The ${productName} team reviews **${goal}** every sprint.\n\n- [ ] Draft\n- [x] Ship\n\n";
+
+    let doc = (0..section_count)
+        .fold(
+            ContextDocument::builder()
+                .title("Budget Document")
+                .variable("productName", "Flow Writer")
+                .variable("goal", "Ship v1"),
+            |builder, i| {
+                let content = format!("# Section {}\n\n{}", i, paragraph.repeat(12));
+                builder.section(format!("section-{}", i), SECTION_TYPES[i % SECTION_TYPES.len()], content)
+            },
+        )
+        .build()
+        .unwrap();
+
+    serialize_xml(&doc)
+}
+
+#[tokio::test]
+#[ignore]
+async fn test_ten_megabyte_document_loads_within_budget() {
+    let xml = generate_xml(10_000);
+    assert!(xml.len() >= 10 * 1024 * 1024, "generated {} bytes", xml.len());
+
+    let file = tempfile::NamedTempFile::new().unwrap();
+    std::fs::write(file.path(), &xml).unwrap();
+    let file_path = file.path().to_str().unwrap();
+
+    let start = Instant::now();
+    let doc = flow_service::load_context_document(file_path).await.unwrap();
+    let elapsed = start.elapsed();
+
+    assert_eq!(doc.sections.len(), 10_000);
+    assert!(!doc.sections[0].content.contains("${goal}"));
+    assert!(
+        elapsed < LOAD_BUDGET,
+        "loading {} bytes took {:?} (budget {:?})",
+        xml.len(),
+        elapsed,
+        LOAD_BUDGET
+    );
+}
@@ -0,0 +1,265 @@
+//! Property-based tests for the document format
+//!
+//! Generated documents must survive `parse_xml(serialize_xml(doc))` unchanged and
+//! pass schema validation, generated flow graphs must survive
+//! `parse_mermaid(generate_mermaid(graph))`; arbitrary or truncated input must never panic.
+
+use flow_writer_core::models::*;
+use flow_writer_core::parsers::{extract_mermaid_from_markdown, parse_condition, parse_mermaid, parse_xml};
+use flow_writer_core::serializers::{generate_mermaid, serialize_xml};
+use flow_writer_core::validators::schema_validator::validate_schema;
+use proptest::collection::{btree_map, vec};
+use proptest::option;
+use proptest::prelude::*;
+use std::collections::BTreeMap;
+
+/// Single-line text with markup-significant characters; the parser trims text,
+/// so generated values never start or end with whitespace
+fn text() -> impl Strategy<Value = String> {
+    "[a-zA-Z0-9 .,;:!?'\"&<>()#*_/éü日本-]{0,30}".prop_map(|s| s.trim().to_string())
+}
+
+fn non_empty_text() -> impl Strategy<Value = String> {
+    text().prop_filter("non-empty", |s| !s.is_empty())
+}
+
+/// Multi-line markdown-ish content including CDATA terminators and variables
+fn content() -> impl Strategy<Value = String> {
+    "[a-zA-Z0-9 #*>\\[\\]{}$&<\\n-]{0,80}".prop_map(|s| s.trim().to_string())
+}
+
+fn identifier() -> impl Strategy<Value = String> {
+    "[a-zA-Z_][a-zA-Z0-9_]{0,12}"
+}
+
+fn tag() -> impl Strategy<Value = String> {
+    "[a-zA-Z&][a-zA-Z0-9 &-]{0,10}[a-zA-Z0-9]"
+}
+
+fn meta() -> impl Strategy<Value = MetaData> {
+    (
+        text(),
+        text(),
+        "[0-9]{4}-[0-9]{2}-[0-9]{2}",
+        (identifier(), "[0-9]\\.[0-9]\\.[0-9]"),
+        vec(tag(), 0..4),
+        text(),
+        btree_map(identifier(), text(), 0..3),
+    )
+        .prop_map(|(title, author, created, (app_name, app_version), tags, description, extra)| MetaData {
+            title,
+            author,
+            created,
+            app_info: AppInfo {
+                name: app_name,
+                version: app_version,
+            },
+            tags,
+            description,
+            extra,
+        })
+}
+
+fn section_body() -> impl Strategy<Value = (String, String, Vec<String>, BTreeMap<String, String>)> {
+    (
+        prop::sample::select(SECTION_TYPES),
+        content(),
+        vec(tag(), 0..3),
+        btree_map("[a-z]{2}", content(), 0..3),
+    )
+        .prop_map(|(section_type, content, tags, translations)| (section_type.to_string(), content, tags, translations))
+}
+
+fn budget() -> impl Strategy<Value = Budget> {
+    (0..100_000usize, prop::bool::ANY).prop_map(|(limit, tokens)| Budget {
+        limit,
+        unit: if tokens { BudgetUnit::Tokens } else { BudgetUnit::Chars },
+    })
+}
+
+fn sections() -> impl Strategy<Value = Vec<Section>> {
+    vec((section_body(), any::<bool>(), option::of(budget()), any::<bool>(), any::<bool>()), 0..6).prop_map(|bodies| {
+        bodies
+            .into_iter()
+            .enumerate()
+            .map(|(i, ((section_type, content, tags, translations), references_previous, budget, sensitive, encrypted))| Section {
+                ref_target: (references_previous && i > 0).then(|| format!("section-{} other.xml#shared", i - 1)),
+                tags,
+                budget,
+                translations,
+                sensitive,
+                encrypted,
+                ..Section::new(format!("section-{}", i), section_type, content)
+            })
+            .collect()
+    })
+}
+
+fn references() -> impl Strategy<Value = Vec<Reference>> {
+    vec((text(), option::of(non_empty_text()), content()), 0..3).prop_map(|entries| {
+        entries
+            .into_iter()
+            .enumerate()
+            .map(|(i, (title, url, citation))| Reference {
+                id: format!("ref-{}", i),
+                title,
+                url,
+                citation,
+            })
+            .collect()
+    })
+}
+
+fn layout() -> impl Strategy<Value = FlowLayout> {
+    (
+        0.1f64..4.0,
+        -1000.0f64..1000.0,
+        -1000.0f64..1000.0,
+        btree_map("[A-Z][0-9]?", (-1e4f64..1e4, -1e4f64..1e4), 0..4),
+    )
+        .prop_map(|(zoom, pan_x, pan_y, nodes)| FlowLayout {
+            zoom,
+            pan_x,
+            pan_y,
+            positions: nodes
+                .into_iter()
+                .map(|(id, (x, y))| (id, NodePosition { x, y }))
+                .collect(),
+        })
+}
+
+fn node_metadata() -> impl Strategy<Value = BTreeMap<String, NodeMetadata>> {
+    btree_map(
+        "[A-Z][0-9]?",
+        (option::of(non_empty_text()), option::of(identifier()), option::of(0.0f64..1000.0)),
+        0..4,
+    )
+    .prop_map(|entries| {
+        entries
+            .into_iter()
+            .map(|(id, (owner, status, estimated_effort))| (id, NodeMetadata { owner, status, estimated_effort }))
+            .collect()
+    })
+}
+
+fn flow() -> impl Strategy<Value = FlowGraph> {
+    (option::of(non_empty_text()), content(), option::of(layout()), node_metadata()).prop_map(|(title, mermaid_code, layout, node_metadata)| FlowGraph {
+        id: "flow-1".to_string(),
+        version: "1.0".to_string(),
+        title,
+        mermaid_code,
+        parsed_graph: GraphStructure {
+            nodes: vec![],
+            edges: vec![],
+            subgraphs: vec![],
+        },
+        node_refs: vec![],
+        layout,
+        node_metadata,
+    })
+}
+
+/// `YYYY-MM-DD`; days stop at 28 so every month has them
+fn date() -> impl Strategy<Value = String> {
+    (2000u32..2100, 1u32..=12, 1u32..=28).prop_map(|(year, month, day)| format!("{:04}-{:02}-{:02}", year, month, day))
+}
+
+fn document() -> impl Strategy<Value = ContextDocument> {
+    (
+        meta(),
+        btree_map(
+            identifier(),
+            (text(), option::of(non_empty_text()), any::<bool>(), proptest::sample::subsequence(ResolutionContext::ALL.to_vec(), 0..=3), option::of(date())),
+            0..4,
+        ),
+        sections(),
+        references(),
+        option::of(flow()),
+    )
+        .prop_map(|(meta, variables, sections, references, flow_graph)| ContextDocument {
+            meta,
+            variables: variables
+                .into_iter()
+                .map(|(name, (value, description, sensitive, resolve_in, due))| Variable { name, value, description, sensitive, resolve_in, due })
+                .collect(),
+            sections,
+            references,
+            flow_graph,
+        })
+}
+
+/// Flow graph with rectangle and round nodes (the shapes the parser models) and
+/// labels full of pipes, quotes, brackets, entity-like text and unicode
+fn graph() -> impl Strategy<Value = GraphStructure> {
+    let label = "[a-zA-Z0-9 |\"#;&<>\\[\\](){}:=éü日本🚀-]{1,20}".prop_map(|s| s.trim().to_string());
+    (
+        vec((label.clone(), any::<bool>()), 1..6),
+        vec((0..6usize, 0..6usize, option::of(label)), 0..8),
+    )
+        .prop_map(|(nodes, edges)| {
+            let nodes: Vec<GraphNode> = nodes
+                .into_iter()
+                .enumerate()
+                .map(|(i, (label, round))| GraphNode {
+                    id: format!("N{}", i),
+                    label: if label.is_empty() { "node".to_string() } else { label },
+                    node_type: if round { NodeType::RoundEdges } else { NodeType::Rectangle },
+                    ref_section_id: None,
+                    metadata: None,
+                })
+                .collect();
+            let count = nodes.len();
+            let edges = edges
+                .into_iter()
+                .map(|(from, to, label)| GraphEdge {
+                    from: format!("N{}", from % count),
+                    to: format!("N{}", to % count),
+                    condition: label.as_deref().and_then(parse_condition),
+                    label,
+                })
+                .collect();
+            GraphStructure { nodes, edges, subgraphs: vec![] }
+        })
+}
+
+proptest! {
+    #[test]
+    fn generated_mermaid_round_trips(graph in graph()) {
+        let code = generate_mermaid(&graph, FlowDirection::TopDown, &[]);
+
+        prop_assert_eq!(parse_mermaid(&code).unwrap(), graph, "{}", code);
+    }
+
+    #[test]
+    fn serialized_documents_round_trip(doc in document()) {
+        let xml = serialize_xml(&doc);
+
+        prop_assert!(validate_schema(&xml).is_ok(), "schema rejected:\n{}", xml);
+        prop_assert_eq!(parse_xml(&xml).unwrap(), doc);
+    }
+
+    #[test]
+    fn truncated_documents_never_panic(doc in document(), cut in 0.0f64..1.0) {
+        let xml = serialize_xml(&doc);
+        let mut end = (xml.len() as f64 * cut) as usize;
+        while !xml.is_char_boundary(end) {
+            end -= 1;
+        }
+
+        let truncated = &xml[..end];
+        let _ = validate_schema(truncated);
+        let _ = parse_xml(truncated);
+    }
+
+    #[test]
+    fn arbitrary_xml_never_panics(input in "(<[a-z/!?\\[]{0,3}|[a-zA-Z0-9 =\"'&;#\\]>]{0,5}){0,40}") {
+        let _ = validate_schema(&input);
+        let _ = parse_xml(&input);
+    }
+
+    #[test]
+    fn arbitrary_mermaid_never_panics(input in "(flowchart TD\\n)?([A-Za-z0-9\\[\\](){}<>|:%\"/\\\\ -]|-->|\\n){0,120}") {
+        let _ = parse_mermaid(&input);
+        let _ = extract_mermaid_from_markdown(&format!("```mermaid\n{}\n```", input));
+    }
+}
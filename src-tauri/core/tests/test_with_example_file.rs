@@ -1,11 +1,12 @@
-use flow_writer_lib::services::flow_service;
+use flow_writer_core::services::flow_service;
 use std::path::PathBuf;
 
 /// Test with the actual context-example.xml file
 #[tokio::test]
 async fn test_context_example_xml() {
-    // Get the path to context-example.xml
+    // Get the path to context-example.xml (sample documents live in the app crate)
     let mut file_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    file_path.push("..");
     file_path.push("context-docs");
     file_path.push("context-example.xml");
 
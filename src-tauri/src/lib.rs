@@ -1,46 +1,1744 @@
-pub mod error;
-pub mod models;
-pub mod parsers;
-pub mod processors;
-pub mod services;
-pub mod validators;
+//! Tauri shell: thin command wrappers over `flow-writer-core`
 
-use models::{MetaData, Section, FlowGraph};
-use services::flow_service;
+pub use flow_writer_core::{error, exporters, models, parsers, plugins, processors, serializers, services, validators};
+
+mod secrets;
+
+use exporters::{ExportFormat, PageSize, ReadingOrderOptions};
+use models::{ContextDocument, MetaData, Section, FlowGraph, FlowLayout, GraphNode, NodeMetadata, Reference};
+use parsers::{InputQuirk, SalvagedDocument};
+use plugins::PluginInfo;
+use processors::{
+    BudgetUsage, ClickLink, ClickSuggestion, ContentBlock, DependencyReport, DocumentComparison, DocumentEdit, DuplicateParagraphs, EffortRollup, FilterSpec,
+    FocusSection, GraphFormat, ImportResult, MergeResult, MergeSide, NodeNavigation, NodeTypeSuggestion, RedactionProfile, Reminder, SectionIndexPage, SectionIndexQuery,
+    SheetFormat, SimulationResult, TagLocation, TagUsage, TypeSuggestion, UnresolvedCitation, VariableLocation, WorkspaceEdge,
+    WorkspaceGraph, WorkspaceMatch,
+};
+use std::collections::{BTreeMap, HashMap};
+use validators::mermaid_lint;
+use validators::publish_check::{Diagnostic, PublishReport};
+use services::app_config::{self, AppConfig};
+use services::conflict_copies::ConflictCopy;
+use services::assembly_history::{self, AssemblySnapshot, SnapshotComparison, SnapshotSummary};
+use services::backup_service::{self, BackupInfo};
+use services::change_journal::{self, JournalCompaction, JournalEntry};
+use services::default_documents;
+use services::dependency_watch::{self, DependencyChange, ExternalDependency};
+use services::document_store;
+use services::dry_run::Mutation;
+use services::flow_service::{self, DocumentChanges, LoadOptions, WorkspaceDocument};
+use services::quick_capture::{self, CapturedNote};
+use services::reminders::{self, OverdueDocument};
+use services::remote_documents::{self, RemoteDocument, MAX_REMOTE_DOCUMENT_BYTES};
+use services::section_activity::{self, SectionActivity};
+use services::settings::{self, Settings};
+use services::snippets::{self, Snippet};
+use services::stats_history::{self, DailyStats};
+use services::template_library::{self, TemplateInfo};
+use services::trash::{self, TrashEntry};
+use services::usage_metrics::{self, MetricsFormat};
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use std::sync::{LazyLock, Mutex, OnceLock};
+use std::time::Duration;
+use tauri::webview::PageLoadEvent;
+use tauri::{Emitter, Manager};
+
+/// Event emitted with the new [`AppConfig`] when env-driven settings change
+const CONFIG_CHANGED_EVENT: &str = "config-changed";
+
+/// Label of the window that shows print previews
+const PRINT_WINDOW: &str = "print";
+
+/// How often the env file is checked when watching is enabled
+const ENV_WATCH_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Give up on a document download after this long
+const DOWNLOAD_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Event emitted with a [`DependencyChange`] when a watched external file changes
+const DEPENDENCY_CHANGED_EVENT: &str = "dependency-changed";
+
+/// How often the external files of watched documents are checked
+const DEPENDENCY_WATCH_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Running dependency watchers, keyed by document path
+static DEPENDENCY_WATCHERS: LazyLock<Mutex<HashMap<String, tauri::async_runtime::JoinHandle<()>>>> =
+    LazyLock::new(Default::default);
+
+/// Event emitted with the file path when the OS opens a document with the running app
+const OPEN_DOCUMENT_EVENT: &str = "open-document";
+
+/// Document the app was launched to open, e.g. by double-clicking a `.cec` file
+static OPENED_DOCUMENT: OnceLock<String> = OnceLock::new();
+
+/// Platform features the frontend can rely on, as found at startup
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Capabilities {
+    /// Opening URLs and files in other apps, through the opener plugin
+    opener: bool,
+    /// Storing secrets in the OS keychain
+    keychain: bool,
+}
+
+/// Whether the opener plugin started; unset until setup has run
+static OPENER_AVAILABLE: OnceLock<bool> = OnceLock::new();
+
+/// Whether the keychain answered the first `get_capabilities` call
+static KEYCHAIN_AVAILABLE: OnceLock<bool> = OnceLock::new();
+
+/// Event emitted at startup with the [`OverdueDocument`]s among the startup and default documents
+const REMINDERS_OVERDUE_EVENT: &str = "reminders-overdue";
+
+/// Open a document and return an ID that every command accepts in place of its path
+#[tauri::command]
+async fn open_document(file_path: String) -> Result<String, String> {
+    flow_service::open_document(&file_path)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Path of a document given by its path or by an `open_document` ID
+fn resolve_document(file_path: &str) -> Result<String, String> {
+    document_store::resolve(file_path).map_err(|e| e.to_string())
+}
 
 /// Load all sections from the context document
 #[tauri::command]
-async fn load_sections(file_path: String) -> Result<Vec<Section>, String> {
-    flow_service::load_sections(&file_path)
+async fn load_sections(file_path: String, options: Option<LoadOptions>) -> Result<Vec<Section>, String> {
+    let file_path = resolve_document(&file_path)?;
+    flow_service::load_sections_with_options(&file_path, &options.unwrap_or_default())
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Content hash and byte/word length of sections, so the UI can reload only changed sections; `query` pages and filters the list
+#[tauri::command]
+async fn get_section_index(file_path: String, query: Option<SectionIndexQuery>) -> Result<SectionIndexPage, String> {
+    let file_path = resolve_document(&file_path)?;
+    flow_service::get_section_index_page(&file_path, &query.unwrap_or_default())
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Sections, metadata and flow changed after a document version, so the UI can sync without reloading everything
+#[tauri::command]
+async fn get_changes_since(file_path: String, document_version: u64) -> Result<DocumentChanges, String> {
+    let file_path = resolve_document(&file_path)?;
+    flow_service::get_changes_since(&file_path, document_version)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// refTarget dependencies per section with transitive closure, plus refTargets contradicting the flow order
+#[tauri::command]
+async fn get_section_dependencies(file_path: String) -> Result<DependencyReport, String> {
+    let file_path = resolve_document(&file_path)?;
+    flow_service::get_section_dependencies(&file_path)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Near-duplicate paragraphs across sections; `threshold` is the shingle similarity (0-1) to report
+#[tauri::command]
+async fn find_duplicate_paragraphs(file_path: String, threshold: Option<f64>) -> Result<Vec<DuplicateParagraphs>, String> {
+    let file_path = resolve_document(&file_path)?;
+    flow_service::find_duplicate_paragraphs(&file_path, threshold.unwrap_or(processors::DEFAULT_DUPLICATE_THRESHOLD))
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Assemble the document sections into a single context string, with optional
+/// call-time variable overrides that leave the document unchanged
+#[tauri::command]
+async fn assemble_context(
+    file_path: String,
+    options: Option<LoadOptions>,
+    overrides: Option<HashMap<String, String>>,
+) -> Result<String, String> {
+    let file_path = resolve_document(&file_path)?;
+    flow_service::assemble_context(&file_path, &options.unwrap_or_default(), &overrides.unwrap_or_default())
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Assemble the context and keep the exact output, with its variables and hash, in the document's history
+#[tauri::command]
+async fn save_assembly_snapshot(
+    file_path: String,
+    options: Option<LoadOptions>,
+    overrides: Option<HashMap<String, String>>,
+    label: Option<String>,
+) -> Result<AssemblySnapshot, String> {
+    let file_path = resolve_document(&file_path)?;
+    assembly_history::save_assembly_snapshot(&file_path, &options.unwrap_or_default(), &overrides.unwrap_or_default(), label)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// List the saved assembly snapshots of the document, newest first
+#[tauri::command]
+async fn list_assembly_snapshots(file_path: String) -> Result<Vec<SnapshotSummary>, String> {
+    let file_path = resolve_document(&file_path)?;
+    assembly_history::list_assembly_snapshots(&file_path)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Load a saved assembly snapshot with its full output
+#[tauri::command]
+async fn load_assembly_snapshot(file_path: String, snapshot_id: String) -> Result<AssemblySnapshot, String> {
+    let file_path = resolve_document(&file_path)?;
+    assembly_history::load_assembly_snapshot(&file_path, &snapshot_id)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Compare two assembly snapshots' variables and output
+#[tauri::command]
+async fn compare_assembly_snapshots(file_path: String, before_id: String, after_id: String) -> Result<SnapshotComparison, String> {
+    let file_path = resolve_document(&file_path)?;
+    assembly_history::compare_assembly_snapshots(&file_path, &before_id, &after_id)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Frontmatter fields of the sections that have them, keyed by section ID
+#[tauri::command]
+async fn get_section_frontmatter(file_path: String) -> Result<BTreeMap<String, BTreeMap<String, String>>, String> {
+    let file_path = resolve_document(&file_path)?;
+    flow_service::get_section_frontmatter(&file_path)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Load the bibliography entries of the document
+#[tauri::command]
+async fn load_references(file_path: String) -> Result<Vec<Reference>, String> {
+    let file_path = resolve_document(&file_path)?;
+    flow_service::load_references(&file_path)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// List `[@ref-id]` citations that do not match any reference
+#[tauri::command]
+async fn validate_citations(file_path: String) -> Result<Vec<UnresolvedCitation>, String> {
+    let file_path = resolve_document(&file_path)?;
+    flow_service::validate_citations(&file_path)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// List all tags used in the document with usage counts
+#[tauri::command]
+async fn get_tags(file_path: String) -> Result<Vec<TagUsage>, String> {
+    let file_path = resolve_document(&file_path)?;
+    flow_service::get_tags(&file_path)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Load the flow graph from the context document
+#[tauri::command]
+async fn load_flow_graph(file_path: String) -> Result<Option<FlowGraph>, String> {
+    let file_path = resolve_document(&file_path)?;
+    flow_service::load_flow_graph(&file_path)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Load per-node hover-card data (linked section, heading, preview) for the flow canvas
+#[tauri::command]
+async fn get_flow_navigation(file_path: String) -> Result<Vec<NodeNavigation>, String> {
+    let file_path = resolve_document(&file_path)?;
+    flow_service::get_flow_navigation(&file_path)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Suggest click actions for unlinked flow nodes by matching labels against sections
+#[tauri::command]
+async fn suggest_click_actions(file_path: String) -> Result<Vec<ClickSuggestion>, String> {
+    let file_path = resolve_document(&file_path)?;
+    flow_service::suggest_click_actions(&file_path)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Suggest a section type from a heading or node label and content; None when nothing hints at one
+#[tauri::command]
+fn suggest_section_type(title: String, content: Option<String>) -> Option<TypeSuggestion> {
+    processors::infer_section_type(&title, content.as_deref().unwrap_or_default())
+}
+
+/// Suggest section types for flow nodes that have no section yet, from their labels
+#[tauri::command]
+async fn suggest_node_section_types(file_path: String) -> Result<Vec<NodeTypeSuggestion>, String> {
+    let file_path = resolve_document(&file_path)?;
+    flow_service::suggest_node_section_types(&file_path)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Insert accepted click actions into the flow's Mermaid code (in memory until saved); `dry_run` returns a diff preview instead
+#[tauri::command]
+async fn apply_click_actions(
+    file_path: String,
+    links: Vec<ClickLink>,
+    dry_run: Option<bool>,
+) -> Result<Mutation<FlowGraph>, String> {
+    let file_path = resolve_document(&file_path)?;
+    if dry_run.unwrap_or(false) {
+        return flow_service::preview_click_actions(&file_path, &links)
+            .await
+            .map(Mutation::Preview)
+            .map_err(|e| e.to_string());
+    }
+    let _lock = document_store::lock(&file_path).await;
+    flow_service::apply_click_actions(&file_path, &links)
+        .await
+        .map(Mutation::Applied)
+        .map_err(|e| e.to_string())
+}
+
+/// Replace the flow diagram with a Mermaid flowchart found in pasted text (in memory until saved); `dry_run` returns a diff preview instead
+#[tauri::command]
+async fn import_mermaid_from_text(
+    file_path: String,
+    text: String,
+    dry_run: Option<bool>,
+) -> Result<Mutation<FlowGraph>, String> {
+    let file_path = resolve_document(&file_path)?;
+    if dry_run.unwrap_or(false) {
+        return flow_service::preview_mermaid_import(&file_path, &text)
+            .await
+            .map(Mutation::Preview)
+            .map_err(|e| e.to_string());
+    }
+    let _lock = document_store::lock(&file_path).await;
+    flow_service::import_mermaid_from_text(&file_path, &text)
+        .await
+        .map(Mutation::Applied)
+        .map_err(|e| e.to_string())
+}
+
+/// Check Mermaid code regenerated by the canvas for lost section links or an empty diagram before saving it
+#[tauri::command]
+async fn check_flow_graph(file_path: String, mermaid_code: String) -> Result<Vec<Diagnostic>, String> {
+    let file_path = resolve_document(&file_path)?;
+    flow_service::check_flow_graph(&file_path, &mermaid_code)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Replace the flow diagram with Mermaid code regenerated by the canvas (in memory until saved); `force` skips the connectivity check
+#[tauri::command]
+async fn save_flow_graph(file_path: String, mermaid_code: String, force: Option<bool>) -> Result<FlowGraph, String> {
+    let file_path = resolve_document(&file_path)?;
+    let _lock = document_store::lock(&file_path).await;
+    flow_service::save_flow_graph(&file_path, &mermaid_code, force.unwrap_or(false))
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Append the flow as a markdown task list to a process section, creating it if needed (in memory until saved)
+#[tauri::command]
+async fn generate_checklist_from_flow(file_path: String, section_id: Option<String>) -> Result<Section, String> {
+    let file_path = resolve_document(&file_path)?;
+    let _lock = document_store::lock(&file_path).await;
+    flow_service::generate_checklist_from_flow(&file_path, section_id.as_deref())
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Load the saved node positions and viewport of the flow canvas
+#[tauri::command]
+async fn load_flow_layout(file_path: String) -> Result<Option<FlowLayout>, String> {
+    let file_path = resolve_document(&file_path)?;
+    flow_service::load_flow_layout(&file_path)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Compute an automatic layered layout for the flow graph, respecting its direction
+#[tauri::command]
+async fn compute_flow_layout(file_path: String) -> Result<Option<FlowLayout>, String> {
+    let file_path = resolve_document(&file_path)?;
+    flow_service::compute_flow_layout(&file_path)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Store node positions and viewport arranged on the flow canvas (in memory until saved)
+#[tauri::command]
+async fn save_flow_layout(file_path: String, layout: FlowLayout) -> Result<(), String> {
+    let file_path = resolve_document(&file_path)?;
+    let _lock = document_store::lock(&file_path).await;
+    flow_service::save_flow_layout(&file_path, layout)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Sections relevant to a flow node (linked and related through refTarget), for focus mode
+#[tauri::command]
+async fn get_sections_for_node(file_path: String, node_id: String) -> Result<Vec<FocusSection>, String> {
+    let file_path = resolve_document(&file_path)?;
+    flow_service::get_sections_for_node(&file_path, &node_id)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// The flow node a section belongs to in focus mode, if any
+#[tauri::command]
+async fn get_node_for_section(file_path: String, section_id: String) -> Result<Option<GraphNode>, String> {
+    let file_path = resolve_document(&file_path)?;
+    flow_service::get_node_for_section(&file_path, &section_id)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Mutations recorded in the document's change journal, oldest first
+#[tauri::command]
+async fn get_change_journal(file_path: String) -> Result<Vec<JournalEntry>, String> {
+    let file_path = resolve_document(&file_path)?;
+    change_journal::read_journal(&file_path)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Fold runs of identical mutations in the change journal into single entries
+#[tauri::command]
+async fn compact_journal(file_path: String) -> Result<JournalCompaction, String> {
+    let file_path = resolve_document(&file_path)?;
+    change_journal::compact_journal(&file_path)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Daily snapshots of the document's word and token counts, oldest first, for progress charts
+#[tauri::command]
+async fn get_stats_history(file_path: String) -> Result<Vec<DailyStats>, String> {
+    let file_path = resolve_document(&file_path)?;
+    stats_history::read_stats_history(&file_path)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Set the owner, status and estimated effort of a flow node (in memory until saved)
+#[tauri::command]
+async fn set_node_metadata(file_path: String, node_id: String, metadata: NodeMetadata) -> Result<FlowGraph, String> {
+    let file_path = resolve_document(&file_path)?;
+    let _lock = document_store::lock(&file_path).await;
+    flow_service::set_node_metadata(&file_path, &node_id, metadata)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Walk the flow from its start node, choosing branches by evaluating edge conditions
+/// against document variables (optionally overridden for a "what if" scenario)
+#[tauri::command]
+async fn simulate_flow(
+    file_path: String,
+    start_node: Option<String>,
+    overrides: Option<HashMap<String, String>>,
+) -> Result<SimulationResult, String> {
+    let file_path = resolve_document(&file_path)?;
+    flow_service::simulate_flow(&file_path, start_node.as_deref(), &overrides.unwrap_or_default())
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Effort estimates summed along each flow path and per subgraph
+#[tauri::command]
+async fn get_effort_rollup(file_path: String) -> Result<EffortRollup, String> {
+    let file_path = resolve_document(&file_path)?;
+    flow_service::get_effort_rollup(&file_path).await.map_err(|e| e.to_string())
+}
+
+/// Budget usage of sections that declare a `budget`, flagging those over their limit
+#[tauri::command]
+async fn get_budget_report(file_path: String) -> Result<Vec<BudgetUsage>, String> {
+    let file_path = resolve_document(&file_path)?;
+    flow_service::get_budget_report(&file_path)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Load metadata from the context document
+#[tauri::command]
+async fn load_metadata(file_path: String) -> Result<MetaData, String> {
+    let file_path = resolve_document(&file_path)?;
+    flow_service::load_metadata(&file_path)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Apply a batch of section, variable and metadata edits atomically and save once; `dry_run` returns a diff preview instead
+#[tauri::command]
+async fn apply_edits(
+    file_path: String,
+    edits: Vec<DocumentEdit>,
+    dry_run: Option<bool>,
+) -> Result<Mutation<ContextDocument>, String> {
+    let file_path = resolve_document(&file_path)?;
+    if dry_run.unwrap_or(false) {
+        return flow_service::preview_edits(&file_path, &edits)
+            .await
+            .map(Mutation::Preview)
+            .map_err(|e| e.to_string());
+    }
+    let _lock = document_store::lock(&file_path).await;
+    flow_service::apply_edits(&file_path, &edits)
+        .await
+        .map(Mutation::Applied)
+        .map_err(|e| e.to_string())
+}
+
+/// Replace one section by ID, keeping the rest of the document as stored, and save; `dry_run` returns a diff preview instead
+#[tauri::command]
+async fn update_section(file_path: String, section: Section, dry_run: Option<bool>) -> Result<Mutation<Section>, String> {
+    let file_path = resolve_document(&file_path)?;
+    if dry_run.unwrap_or(false) {
+        return flow_service::preview_update_section(&file_path, &section)
+            .await
+            .map(Mutation::Preview)
+            .map_err(|e| e.to_string());
+    }
+    let _lock = document_store::lock(&file_path).await;
+    flow_service::update_section(&file_path, &section)
+        .await
+        .map(Mutation::Applied)
+        .map_err(|e| e.to_string())
+}
+
+/// Generate a "fill these in" sheet of all variables as JSON (default) or a markdown table
+#[tauri::command]
+async fn get_variable_sheet(file_path: String, format: Option<SheetFormat>) -> Result<String, String> {
+    let file_path = resolve_document(&file_path)?;
+    flow_service::get_variable_sheet(&file_path, format.unwrap_or_default())
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Set variables from a filled-in sheet and save; blank values are skipped
+#[tauri::command]
+async fn apply_variable_sheet(file_path: String, sheet: String) -> Result<ContextDocument, String> {
+    let file_path = resolve_document(&file_path)?;
+    let _lock = document_store::lock(&file_path).await;
+    flow_service::apply_variable_sheet(&file_path, &sheet)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Replace document metadata, including custom fields (in memory until saved)
+#[tauri::command]
+async fn save_metadata(file_path: String, meta: MetaData) -> Result<(), String> {
+    let file_path = resolve_document(&file_path)?;
+    let _lock = document_store::lock(&file_path).await;
+    flow_service::save_metadata(&file_path, meta)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Export a single section as markdown, html, or plain text with variables resolved, `filter` and `redaction` applied
+#[tauri::command]
+async fn export_section(
+    file_path: String,
+    section_id: String,
+    format: ExportFormat,
+    filter: Option<FilterSpec>,
+    redaction: Option<RedactionProfile>,
+) -> Result<String, String> {
+    let file_path = resolve_document(&file_path)?;
+    flow_service::export_section(&file_path, &section_id, format, &filter.unwrap_or_default(), &redaction.unwrap_or_default())
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Export the document as paginated, print-optimized HTML, with `filter` and `redaction` applied
+#[tauri::command]
+async fn export_print_html(
+    file_path: String,
+    page_size: PageSize,
+    filter: Option<FilterSpec>,
+    redaction: Option<RedactionProfile>,
+) -> Result<String, String> {
+    let file_path = resolve_document(&file_path)?;
+    flow_service::export_print_html(&file_path, page_size, &filter.unwrap_or_default(), &redaction.unwrap_or_default())
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Export the document as a standalone, shareable HTML page with the flow rendered by mermaid.js, with `filter` and `redaction` applied
+#[tauri::command]
+async fn export_html(file_path: String, filter: Option<FilterSpec>, redaction: Option<RedactionProfile>) -> Result<String, String> {
+    let file_path = resolve_document(&file_path)?;
+    flow_service::export_html(&file_path, &filter.unwrap_or_default(), &redaction.unwrap_or_default())
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Export the sections in flow-graph reading order, with edge labels as transition notes
+#[tauri::command]
+async fn export_reading_order(
+    file_path: String,
+    options: Option<ReadingOrderOptions>,
+    filter: Option<FilterSpec>,
+    redaction: Option<RedactionProfile>,
+) -> Result<String, String> {
+    let file_path = resolve_document(&file_path)?;
+    flow_service::export_reading_order(
+        &file_path,
+        &options.unwrap_or_default(),
+        &filter.unwrap_or_default(),
+        &redaction.unwrap_or_default(),
+    )
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Export the flow graph as an Excalidraw scene (`.excalidraw` JSON)
+#[tauri::command]
+async fn export_excalidraw(file_path: String) -> Result<String, String> {
+    let file_path = resolve_document(&file_path)?;
+    flow_service::export_excalidraw(&file_path)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Export the parsed flow graph with click links, positions and styling as standalone JSON for external tools
+#[tauri::command]
+async fn export_flow_json(file_path: String) -> Result<String, String> {
+    let file_path = resolve_document(&file_path)?;
+    flow_service::export_flow_json(&file_path)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Export a JSON map of sections, headings, flow nodes and internal/external links for audits and publishing pipelines
+#[tauri::command]
+async fn export_link_map(file_path: String) -> Result<String, String> {
+    let file_path = resolve_document(&file_path)?;
+    flow_service::export_link_map(&file_path)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Export the document as JSON with raw content and the parsed flow, for tools without an XML parser
+#[tauri::command]
+async fn export_json(file_path: String) -> Result<String, String> {
+    let file_path = resolve_document(&file_path)?;
+    flow_service::export_json(&file_path)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Replace the document with one given as JSON in the `export_json` layout; the result is an unsaved edit
+#[tauri::command]
+async fn import_json(file_path: String, json: String) -> Result<ContextDocument, String> {
+    let file_path = resolve_document(&file_path)?;
+    let _lock = document_store::lock(&file_path).await;
+    flow_service::import_json(&file_path, &json)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Replace the flow with a graph from another tool, given as flow JSON or Graphviz DOT
+#[tauri::command]
+async fn import_flow(file_path: String, source: String, format: GraphFormat) -> Result<FlowGraph, String> {
+    let file_path = resolve_document(&file_path)?;
+    let _lock = document_store::lock(&file_path).await;
+    flow_service::import_flow(&file_path, &source, format)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Overdue sections and variables and those due in the next `within_days` days (default 7), soonest first
+#[tauri::command]
+async fn get_reminders(file_path: String, within_days: Option<i64>) -> Result<Vec<Reminder>, String> {
+    let file_path = resolve_document(&file_path)?;
+    reminders::get_reminders(&file_path, within_days)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Append a timestamped note to the inbox named by the `inbox` setting and save it, without opening the editor
+#[tauri::command]
+async fn quick_capture(app: tauri::AppHandle, text: String) -> Result<CapturedNote, String> {
+    let (file_path, section_id) = quick_capture::inbox_target(settings::current_settings().inbox.as_deref(), &documents_dir(&app)?);
+    let file_path = file_path.to_string_lossy();
+    let _lock = document_store::lock(&file_path).await;
+    quick_capture::quick_capture(&file_path, &section_id, &text)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Count a view of a section for the activity heat map; does nothing unless the `sectionActivity` setting is on
+#[tauri::command]
+async fn record_section_view(file_path: String, section_id: String) -> Result<(), String> {
+    let file_path = resolve_document(&file_path)?;
+    section_activity::record_view(&file_path, &section_id)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Views, edits, heat and staleness of every section, for a heat map of the canvas
+#[tauri::command]
+async fn get_section_activity(file_path: String, stale_after_days: Option<i64>) -> Result<Vec<SectionActivity>, String> {
+    let file_path = resolve_document(&file_path)?;
+    section_activity::get_section_activity(&file_path, stale_after_days)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Export dated variables and `Milestone:` lines as an iCalendar (.ics) file
+#[tauri::command]
+async fn export_calendar(file_path: String) -> Result<String, String> {
+    let file_path = resolve_document(&file_path)?;
+    flow_service::export_calendar(&file_path)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Export the document as a Word file with the flow diagram embedded as an image, with `filter` and `redaction` applied
+#[tauri::command]
+async fn export_docx(
+    file_path: String,
+    destination: String,
+    filter: Option<FilterSpec>,
+    redaction: Option<RedactionProfile>,
+) -> Result<(), String> {
+    let file_path = resolve_document(&file_path)?;
+    flow_service::export_docx(&file_path, &destination, &filter.unwrap_or_default(), &redaction.unwrap_or_default())
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Open the print-ready export in a preview window and show the OS print dialog
+#[tauri::command]
+async fn print_document(
+    app: tauri::AppHandle,
+    file_path: String,
+    page_size: PageSize,
+    filter: Option<FilterSpec>,
+    redaction: Option<RedactionProfile>,
+) -> Result<(), String> {
+    let file_path = resolve_document(&file_path)?;
+    let html = flow_service::export_print_html(&file_path, page_size, &filter.unwrap_or_default(), &redaction.unwrap_or_default())
+        .await
+        .map_err(|e| e.to_string())?;
+    let dir = app.path().app_cache_dir().map_err(|e| e.to_string())?.join("print");
+    std::fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+    let html_path = dir.join("document.html");
+    std::fs::write(&html_path, html).map_err(|e| e.to_string())?;
+
+    // One preview at a time; a new print request replaces the previous one
+    if let Some(window) = app.get_webview_window(PRINT_WINDOW) {
+        window.close().map_err(|e| e.to_string())?;
+    }
+    let url = tauri::Url::from_file_path(&html_path).map_err(|_| format!("Invalid path: {}", html_path.display()))?;
+    tauri::WebviewWindowBuilder::new(&app, PRINT_WINDOW, tauri::WebviewUrl::External(url))
+        .title("Print")
+        .on_page_load(|window, payload| {
+            if payload.event() == PageLoadEvent::Finished {
+                let _ = window.print();
+            }
+        })
+        .build()
+        .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// Split a section's raw content into `---`-separated blocks with stable indices
+#[tauri::command]
+async fn get_section_blocks(file_path: String, section_id: String) -> Result<Vec<ContentBlock>, String> {
+    let file_path = resolve_document(&file_path)?;
+    flow_service::get_section_blocks(&file_path, &section_id)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Replace a single block of a section's content, keeping the rest untouched (in memory until saved)
+#[tauri::command]
+async fn update_section_block(
+    file_path: String,
+    section_id: String,
+    index: usize,
+    content: String,
+) -> Result<Vec<ContentBlock>, String> {
+    let file_path = resolve_document(&file_path)?;
+    let _lock = document_store::lock(&file_path).await;
+    flow_service::update_section_block(&file_path, &section_id, index, &content)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Break a long section into several at markdown headings of the given level (in memory until saved)
+#[tauri::command]
+async fn split_section(file_path: String, section_id: String, heading_level: usize) -> Result<Vec<String>, String> {
+    let file_path = resolve_document(&file_path)?;
+    let _lock = document_store::lock(&file_path).await;
+    flow_service::split_section(&file_path, &section_id, heading_level)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Encrypt a section's content with a passphrase (in memory until saved)
+#[tauri::command]
+async fn encrypt_section(file_path: String, section_id: String, passphrase: String) -> Result<(), String> {
+    let file_path = resolve_document(&file_path)?;
+    let _lock = document_store::lock(&file_path).await;
+    flow_service::encrypt_section(&file_path, &section_id, &passphrase)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Decrypted copy of an encrypted section for viewing; the document stays encrypted
+#[tauri::command]
+async fn unlock_section(file_path: String, section_id: String, passphrase: String) -> Result<Section, String> {
+    let file_path = resolve_document(&file_path)?;
+    flow_service::unlock_section(&file_path, &section_id, &passphrase)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Remove a section's encryption for good (in memory until saved)
+#[tauri::command]
+async fn decrypt_section(file_path: String, section_id: String, passphrase: String) -> Result<(), String> {
+    let file_path = resolve_document(&file_path)?;
+    let _lock = document_store::lock(&file_path).await;
+    flow_service::decrypt_section(&file_path, &section_id, &passphrase)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Merge sections into the first given ID, joining content with the separator (in memory until saved); `dry_run` returns a diff preview instead
+#[tauri::command]
+async fn merge_sections(
+    file_path: String,
+    ids: Vec<String>,
+    separator: Option<String>,
+    dry_run: Option<bool>,
+) -> Result<Mutation<()>, String> {
+    let file_path = resolve_document(&file_path)?;
+    if dry_run.unwrap_or(false) {
+        return flow_service::preview_merge_sections(&file_path, &ids, separator.as_deref())
+            .await
+            .map(Mutation::Preview)
+            .map_err(|e| e.to_string());
+    }
+    let _lock = document_store::lock(&file_path).await;
+    flow_service::merge_sections(&file_path, &ids, separator.as_deref())
+        .await
+        .map(Mutation::Applied)
+        .map_err(|e| e.to_string())
+}
+
+/// Copy sections from another document, remapping IDs on collision (in memory until saved)
+#[tauri::command]
+async fn import_sections(
+    target_path: String,
+    source_path: String,
+    section_ids: Vec<String>,
+    carry_variables: Option<bool>,
+) -> Result<ImportResult, String> {
+    let target_path = resolve_document(&target_path)?;
+    let source_path = resolve_document(&source_path)?;
+    let _lock = document_store::lock(&target_path).await;
+    flow_service::import_sections(&target_path, &source_path, &section_ids, carry_variables.unwrap_or(true))
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Write the open document, including all unsaved edits, to disk; `dry_run` returns a diff preview instead
+#[tauri::command]
+async fn save_document(file_path: String, dry_run: Option<bool>) -> Result<Mutation<()>, String> {
+    let file_path = resolve_document(&file_path)?;
+    if dry_run.unwrap_or(false) {
+        return flow_service::preview_save(&file_path)
+            .await
+            .map(Mutation::Preview)
+            .map_err(|e| e.to_string());
+    }
+    let _lock = document_store::lock(&file_path).await;
+    flow_service::save_document(&file_path)
         .await
+        .map(Mutation::Applied)
         .map_err(|e| e.to_string())
 }
 
-/// Load the flow graph from the context document
+/// Write the document, e.g. a scratch document, to `new_path` and keep editing it there
 #[tauri::command]
-async fn load_flow_graph(file_path: String) -> Result<Option<FlowGraph>, String> {
-    flow_service::load_flow_graph(&file_path)
+async fn save_document_as(file_path: String, new_path: String) -> Result<(), String> {
+    let file_path = resolve_document(&file_path)?;
+    // Both documents are locked, always in the same order
+    let (first, second) = if file_path <= new_path { (&file_path, &new_path) } else { (&new_path, &file_path) };
+    let _first = document_store::lock(first).await;
+    let _second = if second != first { Some(document_store::lock(second).await) } else { None };
+    flow_service::save_document_as(&file_path, &new_path)
         .await
         .map_err(|e| e.to_string())
 }
 
-/// Load metadata from the context document
+/// Copies of the document kept from before each save, newest first
 #[tauri::command]
-async fn load_metadata(file_path: String) -> Result<MetaData, String> {
-    flow_service::load_metadata(&file_path)
+async fn list_backups(file_path: String) -> Result<Vec<BackupInfo>, String> {
+    let file_path = resolve_document(&file_path)?;
+    backup_service::list_backups(&file_path)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Replace the document with one of its backups and save it; the replaced version is backed up too
+#[tauri::command]
+async fn restore_backup(file_path: String, backup_id: String) -> Result<ContextDocument, String> {
+    let file_path = resolve_document(&file_path)?;
+    let _lock = document_store::lock(&file_path).await;
+    backup_service::restore_backup(&file_path, &backup_id)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Whether the open document has edits that have not been saved
+#[tauri::command]
+fn is_document_dirty(file_path: String) -> bool {
+    resolve_document(&file_path).is_ok_and(|file_path| flow_service::is_document_dirty(&file_path))
+}
+
+/// Close the document, discarding unsaved edits; returns whether any were discarded
+#[tauri::command]
+fn close_document(file_path: String) -> bool {
+    resolve_document(&file_path).is_ok_and(|file_path| flow_service::close_document(&file_path))
+}
+
+/// Problems in the file (byte order mark, leading whitespace) corrected when it was opened
+#[tauri::command]
+async fn get_load_warnings(file_path: String) -> Result<Vec<InputQuirk>, String> {
+    let file_path = resolve_document(&file_path)?;
+    flow_service::get_load_warnings(&file_path)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// List sync-service conflict copies (Dropbox, OneDrive, ...) of the document
+#[tauri::command]
+async fn list_conflict_copies(file_path: String) -> Result<Vec<ConflictCopy>, String> {
+    let file_path = resolve_document(&file_path)?;
+    flow_service::list_conflict_copies(&file_path)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Show how a conflict copy would merge into the document, with its conflicts
+#[tauri::command]
+async fn preview_conflict_merge(
+    file_path: String,
+    copy_path: String,
+    base_path: Option<String>,
+    resolutions: Option<HashMap<String, MergeSide>>,
+) -> Result<MergeResult, String> {
+    let file_path = resolve_document(&file_path)?;
+    flow_service::preview_conflict_merge(&file_path, &copy_path, base_path.as_deref(), &resolutions.unwrap_or_default())
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Merge a conflict copy into the document, picking a side per conflict (in memory until saved); `dry_run` returns a diff preview instead
+#[tauri::command]
+async fn merge_conflict_copy(
+    file_path: String,
+    copy_path: String,
+    base_path: Option<String>,
+    resolutions: Option<HashMap<String, MergeSide>>,
+    dry_run: Option<bool>,
+) -> Result<Mutation<MergeResult>, String> {
+    let file_path = resolve_document(&file_path)?;
+    let resolutions = resolutions.unwrap_or_default();
+    if dry_run.unwrap_or(false) {
+        return flow_service::preview_merge_conflict_copy(&file_path, &copy_path, base_path.as_deref(), &resolutions)
+            .await
+            .map(Mutation::Preview)
+            .map_err(|e| e.to_string());
+    }
+    let _lock = document_store::lock(&file_path).await;
+    flow_service::merge_conflict_copy(&file_path, &copy_path, base_path.as_deref(), &resolutions)
+        .await
+        .map(Mutation::Applied)
+        .map_err(|e| e.to_string())
+}
+
+/// Metadata, section, variable and flow differences between two documents for the compare screen
+#[tauri::command]
+async fn compare_documents(path_a: String, path_b: String) -> Result<DocumentComparison, String> {
+    let path_a = resolve_document(&path_a)?;
+    let path_b = resolve_document(&path_b)?;
+    flow_service::compare_documents(&path_a, &path_b)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Run every validator in strict mode; gate exporting or sharing on the report being ready
+#[tauri::command]
+async fn publish_check(file_path: String) -> Result<PublishReport, String> {
+    let file_path = resolve_document(&file_path)?;
+    flow_service::publish_check(&file_path)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Warn about Mermaid syntax that renders in the UI but is missing from the parsed graph
+#[tauri::command]
+async fn lint_mermaid(mermaid_code: String) -> Result<Vec<Diagnostic>, String> {
+    Ok(mermaid_lint::lint_mermaid(&mermaid_code))
+}
+
+/// XML text of the document (with unsaved edits) for the source view
+#[tauri::command]
+async fn get_raw_document(file_path: String) -> Result<String, String> {
+    let file_path = resolve_document(&file_path)?;
+    flow_service::get_raw_document(&file_path)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Replace the document with XML edited in the source view, validating it before saving
+#[tauri::command]
+async fn save_raw_document(file_path: String, xml: String) -> Result<ContextDocument, String> {
+    let file_path = resolve_document(&file_path)?;
+    let _lock = document_store::lock(&file_path).await;
+    flow_service::save_raw_document(&file_path, &xml)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Open a document that fails to parse, salvaging its sections, variables and diagram as an unsaved edit flagged `recovered`
+#[tauri::command]
+async fn open_document_safe_mode(file_path: String) -> Result<SalvagedDocument, String> {
+    let file_path = resolve_document(&file_path)?;
+    let _lock = document_store::lock(&file_path).await;
+    flow_service::open_document_safe_mode(&file_path)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Repair the selected fixable diagnostics; the result is an unsaved edit returned for review; `dry_run` returns a diff preview instead
+#[tauri::command]
+async fn apply_fixes(
+    file_path: String,
+    diagnostic_ids: Vec<String>,
+    dry_run: Option<bool>,
+) -> Result<Mutation<ContextDocument>, String> {
+    let file_path = resolve_document(&file_path)?;
+    if dry_run.unwrap_or(false) {
+        return flow_service::preview_fixes(&file_path, &diagnostic_ids)
+            .await
+            .map(Mutation::Preview)
+            .map_err(|e| e.to_string());
+    }
+    let _lock = document_store::lock(&file_path).await;
+    flow_service::apply_fixes(&file_path, &diagnostic_ids)
+        .await
+        .map(Mutation::Applied)
+        .map_err(|e| e.to_string())
+}
+
+/// List metadata of workspace documents, reusing the parsed-document cache for unchanged files
+#[tauri::command]
+async fn load_workspace_metadata(app: tauri::AppHandle, file_paths: Vec<String>) -> Result<Vec<WorkspaceDocument>, String> {
+    flow_service::load_workspace_metadata(&file_paths, &document_cache_dir(&app)?)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Workspace documents carrying the tag in their metadata or on a section, with the matching sections
+#[tauri::command]
+async fn find_documents_by_tag(
+    app: tauri::AppHandle,
+    file_paths: Vec<String>,
+    tag: String,
+) -> Result<Vec<WorkspaceMatch<TagLocation>>, String> {
+    let index = flow_service::build_workspace_index(&file_paths, &document_cache_dir(&app)?)
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(index.documents_by_tag(&tag))
+}
+
+/// Workspace documents declaring or referencing the variable, with its value and the sections using it
+#[tauri::command]
+async fn find_variable_usages_workspace(
+    app: tauri::AppHandle,
+    file_paths: Vec<String>,
+    name: String,
+) -> Result<Vec<WorkspaceMatch<VariableLocation>>, String> {
+    let index = flow_service::build_workspace_index(&file_paths, &document_cache_dir(&app)?)
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(index.variable_usages(&name))
+}
+
+/// Workspace documents as nodes and their refTargets and markdown links to each other as edges
+#[tauri::command]
+async fn get_workspace_graph(app: tauri::AppHandle, file_paths: Vec<String>) -> Result<WorkspaceGraph, String> {
+    flow_service::build_workspace_graph(&file_paths, &document_cache_dir(&app)?)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// References to `file_path` from the other workspace documents
+#[tauri::command]
+async fn get_backlinks(app: tauri::AppHandle, file_paths: Vec<String>, file_path: String) -> Result<Vec<WorkspaceEdge>, String> {
+    let file_path = resolve_document(&file_path)?;
+    let graph = flow_service::build_workspace_graph(&file_paths, &document_cache_dir(&app)?)
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(graph.backlinks(&file_path).into_iter().cloned().collect())
+}
+
+/// Move a document file to the trash
+#[tauri::command]
+async fn delete_document(app: tauri::AppHandle, file_path: String) -> Result<TrashEntry, String> {
+    let file_path = resolve_document(&file_path)?;
+    let _lock = document_store::lock(&file_path).await;
+    trash::trash_document(&trash_dir(&app)?, &file_path).await.map_err(|e| e.to_string())
+}
+
+/// Delete a section and its subsections, keeping a copy in the trash
+#[tauri::command]
+async fn delete_section(app: tauri::AppHandle, file_path: String, section_id: String) -> Result<TrashEntry, String> {
+    let file_path = resolve_document(&file_path)?;
+    let _lock = document_store::lock(&file_path).await;
+    trash::trash_section(&trash_dir(&app)?, &file_path, &section_id)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Deleted documents and sections, most recent first
+#[tauri::command]
+async fn list_trash(app: tauri::AppHandle) -> Result<Vec<TrashEntry>, String> {
+    trash::list_trash(&trash_dir(&app)?).await.map_err(|e| e.to_string())
+}
+
+/// Put a trashed document or section back where it was
+#[tauri::command]
+async fn restore_from_trash(app: tauri::AppHandle, id: String) -> Result<TrashEntry, String> {
+    trash::restore_from_trash(&trash_dir(&app)?, &id).await.map_err(|e| e.to_string())
+}
+
+/// Permanently delete everything in the trash
+#[tauri::command]
+async fn empty_trash(app: tauri::AppHandle) -> Result<usize, String> {
+    trash::empty_trash(&trash_dir(&app)?).await.map_err(|e| e.to_string())
+}
+
+fn trash_dir(app: &tauri::AppHandle) -> Result<PathBuf, String> {
+    Ok(app
+        .path()
+        .app_data_dir()
+        .map_err(|e| e.to_string())?
+        .join(trash::TRASH_FOLDER))
+}
+
+/// Parsed-document cache shared by the workspace commands
+fn document_cache_dir(app: &tauri::AppHandle) -> Result<PathBuf, String> {
+    Ok(app.path().app_cache_dir().map_err(|e| e.to_string())?.join("documents"))
+}
+
+/// Env-driven settings as of the last load
+#[tauri::command]
+fn get_config() -> AppConfig {
+    app_config::current_config()
+}
+
+/// Re-read `.env` and the environment, emitting `config-changed` if anything changed
+#[tauri::command]
+fn reload_config(app: tauri::AppHandle) -> Result<AppConfig, String> {
+    let (config, changed) = app_config::reload_config(Path::new(app_config::DEFAULT_ENV_FILE)).map_err(|e| e.to_string())?;
+    if changed {
+        app.emit(CONFIG_CHANGED_EVENT, &config).map_err(|e| e.to_string())?;
+    }
+    Ok(config)
+}
+
+/// `FLOW_WRITER_DOCS_DIR`, else a folder in the user's documents directory
+fn documents_dir(app: &tauri::AppHandle) -> Result<PathBuf, String> {
+    match app_config::current_config().docs_dir {
+        Some(dir) => Ok(PathBuf::from(dir)),
+        None => Ok(app
+            .path()
+            .document_dir()
+            .map_err(|e| e.to_string())?
+            .join(default_documents::DEFAULT_DOCS_FOLDER)),
+    }
+}
+
+/// Emit `reminders-overdue` when the startup document or a default document has overdue items
+async fn emit_overdue_reminders(app: &tauri::AppHandle) -> Result<(), String> {
+    let dir = documents_dir(app)?;
+    default_documents::ensure_documents_dir(&dir)
+        .await
+        .map_err(|e| e.to_string())?;
+    let mut file_paths: Vec<String> = default_documents::list_documents(&dir)
+        .await
+        .map_err(|e| e.to_string())?
+        .into_iter()
+        .map(|p| p.to_string_lossy().into_owned())
+        .collect();
+    if let Some(path) = OPENED_DOCUMENT.get().cloned().or(app_config::current_config().doc_path) {
+        if !file_paths.contains(&path) {
+            file_paths.insert(0, path);
+        }
+    }
+
+    let overdue: Vec<OverdueDocument> = reminders::find_overdue(&file_paths, &document_cache_dir(app)?, reminders::today()).await;
+    if !overdue.is_empty() {
+        app.emit(REMINDERS_OVERDUE_EVENT, &overdue).map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+/// Document to open at startup: the one the app was launched with, else
+/// `FLOW_WRITER_DOC_PATH`, else one from the default documents directory, which is
+/// created with an example document on first run
+#[tauri::command]
+async fn get_document_path(app: tauri::AppHandle) -> Result<Option<String>, String> {
+    let configured = OPENED_DOCUMENT.get().cloned().or(app_config::current_config().doc_path);
+    let path = default_documents::default_document_path(configured.as_deref(), &documents_dir(&app)?)
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(path.map(|p| p.to_string_lossy().into_owned()))
+}
+
+/// List the context documents in the default documents directory
+#[tauri::command]
+async fn list_default_documents(app: tauri::AppHandle) -> Result<Vec<String>, String> {
+    let dir = documents_dir(&app)?;
+    default_documents::ensure_documents_dir(&dir)
+        .await
+        .map_err(|e| e.to_string())?;
+    let documents = default_documents::list_documents(&dir)
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(documents.into_iter().map(|p| p.to_string_lossy().into_owned()).collect())
+}
+
+fn settings_path(app: &tauri::AppHandle) -> Result<PathBuf, String> {
+    Ok(app
+        .path()
+        .app_data_dir()
+        .map_err(|e| e.to_string())?
+        .join(settings::SETTINGS_FILE))
+}
+
+/// Load all app settings, with defaults for any not yet set
+#[tauri::command]
+async fn get_settings(app: tauri::AppHandle) -> Result<Settings, String> {
+    settings::load_settings(&settings_path(&app)?)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Read one app setting by its camelCase key
+#[tauri::command]
+async fn get_setting(app: tauri::AppHandle, key: String) -> Result<serde_json::Value, String> {
+    settings::get_setting(&settings_path(&app)?, &key)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Change one app setting; the value is checked against the settings schema
+#[tauri::command]
+async fn set_setting(app: tauri::AppHandle, key: String, value: serde_json::Value) -> Result<Settings, String> {
+    settings::set_setting(&settings_path(&app)?, &key, value)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+fn remote_dir(app: &tauri::AppHandle) -> Result<PathBuf, String> {
+    Ok(app
+        .path()
+        .app_data_dir()
+        .map_err(|e| e.to_string())?
+        .join(remote_documents::REMOTE_FOLDER))
+}
+
+fn metrics_path(app: &tauri::AppHandle) -> Result<PathBuf, String> {
+    Ok(app
+        .path()
+        .app_data_dir()
+        .map_err(|e| e.to_string())?
+        .join(usage_metrics::METRICS_FILE))
+}
+
+/// Daily counts of saves, exports and publish check errors, recorded while the `usageMetrics` setting is on; JSON unless `format` is `csv`
+#[tauri::command]
+async fn export_metrics(app: tauri::AppHandle, format: Option<MetricsFormat>) -> Result<String, String> {
+    usage_metrics::export_metrics(&metrics_path(&app)?, format.unwrap_or_default())
+        .await
+        .map_err(|e| e.to_string())
+}
+
+fn snippets_path(app: &tauri::AppHandle) -> Result<PathBuf, String> {
+    Ok(app
+        .path()
+        .app_data_dir()
+        .map_err(|e| e.to_string())?
+        .join(snippets::SNIPPETS_FILE))
+}
+
+/// List the snippets in the snippet library
+#[tauri::command]
+async fn list_snippets(app: tauri::AppHandle) -> Result<Vec<Snippet>, String> {
+    snippets::load_snippets(&snippets_path(&app)?)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Add a snippet to the library, replacing one with the same name
+#[tauri::command]
+async fn save_snippet(app: tauri::AppHandle, snippet: Snippet) -> Result<Vec<Snippet>, String> {
+    snippets::save_snippet(&snippets_path(&app)?, snippet)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Remove a snippet from the library
+#[tauri::command]
+async fn delete_snippet(app: tauri::AppHandle, name: String) -> Result<Vec<Snippet>, String> {
+    snippets::delete_snippet(&snippets_path(&app)?, &name)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Insert a snippet into a section with its placeholders resolved; returns the new section content
+#[tauri::command]
+async fn insert_snippet(
+    app: tauri::AppHandle,
+    file_path: String,
+    section_id: String,
+    name: String,
+    values: Option<HashMap<String, String>>,
+    position: Option<usize>,
+) -> Result<String, String> {
+    let file_path = resolve_document(&file_path)?;
+    let _lock = document_store::lock(&file_path).await;
+    let snippet = snippets::find_snippet(&snippets_path(&app)?, &name)
+        .await
+        .map_err(|e| e.to_string())?;
+    flow_service::insert_snippet(&file_path, &section_id, &snippet, &values.unwrap_or_default(), position)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Which platform features work here, so the frontend can hide the ones that don't
+#[tauri::command]
+fn get_capabilities() -> Capabilities {
+    Capabilities {
+        opener: OPENER_AVAILABLE.get().copied().unwrap_or(false),
+        keychain: *KEYCHAIN_AVAILABLE.get_or_init(secrets::is_available),
+    }
+}
+
+/// Store a secret in the OS keychain under `name`
+#[tauri::command]
+fn store_secret(name: String, value: String) -> Result<(), String> {
+    secrets::store(&name, &value)
+}
+
+/// Read a secret from the OS keychain; None if nothing is stored under `name`
+#[tauri::command]
+fn get_secret(name: String) -> Result<Option<String>, String> {
+    secrets::get(&name)
+}
+
+/// Remove a secret from the OS keychain; returns whether one was stored
+#[tauri::command]
+fn delete_secret(name: String) -> Result<bool, String> {
+    secrets::delete(&name)
+}
+
+fn plugins_dir(app: &tauri::AppHandle) -> Result<PathBuf, String> {
+    Ok(app.path().app_data_dir().map_err(|e| e.to_string())?.join("plugins"))
+}
+
+/// List installed content processor plugins and whether they are enabled
+#[tauri::command]
+fn list_plugins(app: tauri::AppHandle) -> Result<Vec<PluginInfo>, String> {
+    plugins::discover_plugins(&plugins_dir(&app)?).map_err(|e| e.to_string())
+}
+
+/// Enable or disable a plugin and rebuild the processor pipeline
+#[tauri::command]
+fn set_plugin_enabled(app: tauri::AppHandle, plugin_id: String, enabled: bool) -> Result<Vec<PluginInfo>, String> {
+    let dir = plugins_dir(&app)?;
+    plugins::set_plugin_enabled(&dir, &plugin_id, enabled).map_err(|e| e.to_string())?;
+    plugins::load_plugins(&dir).map_err(|e| e.to_string())
+}
+
+/// Recompile the enabled plugins, picking up newly installed or updated ones
+#[tauri::command]
+fn reload_plugins(app: tauri::AppHandle) -> Result<Vec<PluginInfo>, String> {
+    plugins::load_plugins(&plugins_dir(&app)?).map_err(|e| e.to_string())
+}
+
+fn templates_dir(app: &tauri::AppHandle) -> Result<PathBuf, String> {
+    Ok(app
+        .path()
+        .app_data_dir()
+        .map_err(|e| e.to_string())?
+        .join(template_library::TEMPLATES_FOLDER))
+}
+
+/// Create a new document from the default sections, or from `template_name` in the template library; never overwrites a file
+#[tauri::command]
+async fn create_document(
+    app: tauri::AppHandle,
+    file_path: String,
+    title: String,
+    author: Option<String>,
+    template_name: Option<String>,
+    include_flow: Option<bool>,
+) -> Result<ContextDocument, String> {
+    let _lock = document_store::lock(&file_path).await;
+    flow_service::create_document(
+        &file_path,
+        &title,
+        author.as_deref().unwrap_or_default(),
+        template_name.as_deref(),
+        &templates_dir(&app)?,
+        include_flow.unwrap_or(true),
+    )
+    .await
+    .map_err(|e| e.to_string())
+}
+
+/// Create a document in memory only, like `create_document`; the returned handle works in place of a file path until `save_document_as`
+#[tauri::command]
+async fn create_scratch_document(
+    app: tauri::AppHandle,
+    title: String,
+    author: Option<String>,
+    template_name: Option<String>,
+    include_flow: Option<bool>,
+) -> Result<String, String> {
+    flow_service::create_scratch_document(
+        &title,
+        author.as_deref().unwrap_or_default(),
+        template_name.as_deref(),
+        &templates_dir(&app)?,
+        include_flow.unwrap_or(true),
+    )
+    .await
+    .map_err(|e| e.to_string())
+}
+
+/// Strip the document to its structure and save it in the template library as `template_name`
+#[tauri::command]
+async fn save_as_template(app: tauri::AppHandle, file_path: String, template_name: String) -> Result<TemplateInfo, String> {
+    let file_path = resolve_document(&file_path)?;
+    flow_service::save_as_template(&file_path, &templates_dir(&app)?, &template_name)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// List the templates in the template library
+#[tauri::command]
+async fn list_templates(app: tauri::AppHandle) -> Result<Vec<TemplateInfo>, String> {
+    template_library::list_templates(&templates_dir(&app)?)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Download a document over https, refusing responses that are too large or not XML
+async fn download_document(url: &str) -> Result<Vec<u8>, String> {
+    remote_documents::validate_url(url).map_err(|e| e.to_string())?;
+    let client = reqwest::Client::builder()
+        .timeout(DOWNLOAD_TIMEOUT)
+        .https_only(true)
+        .build()
+        .map_err(|e| e.to_string())?;
+    let mut response = client
+        .get(url)
+        .send()
+        .await
+        .and_then(|r| r.error_for_status())
+        .map_err(|e| e.to_string())?;
+
+    let content_type = response
+        .headers()
+        .get(reqwest::header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok());
+    remote_documents::check_content_type(content_type).map_err(|e| e.to_string())?;
+
+    let too_large = || format!("Document is larger than {} bytes", MAX_REMOTE_DOCUMENT_BYTES);
+    if response.content_length().is_some_and(|len| len > MAX_REMOTE_DOCUMENT_BYTES as u64) {
+        return Err(too_large());
+    }
+    // The header can be missing or wrong, so enforce the limit while reading too
+    let mut bytes = Vec::new();
+    while let Some(chunk) = response.chunk().await.map_err(|e| e.to_string())? {
+        if bytes.len() + chunk.len() > MAX_REMOTE_DOCUMENT_BYTES {
+            return Err(too_large());
+        }
+        bytes.extend_from_slice(&chunk);
+    }
+    Ok(bytes)
+}
+
+/// Download a context document over https and open it read-only
+#[tauri::command]
+async fn open_document_url(app: tauri::AppHandle, url: String) -> Result<RemoteDocument, String> {
+    let bytes = download_document(&url).await?;
+    remote_documents::store_remote_document(&remote_dir(&app)?, &url, &bytes)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Copy a downloaded document to an editable local file (default: the documents directory)
+#[tauri::command]
+async fn fork_remote_document(app: tauri::AppHandle, file_path: String, destination: Option<String>) -> Result<String, String> {
+    let file_path = resolve_document(&file_path)?;
+    let destination = match destination {
+        Some(destination) => destination,
+        None => remote_documents::default_fork_path(&file_path, &documents_dir(&app)?)
+            .to_string_lossy()
+            .into_owned(),
+    };
+    remote_documents::fork_remote_document(&file_path, &destination)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Files outside the document that its sections transclude or link to
+#[tauri::command]
+async fn get_external_dependencies(file_path: String) -> Result<Vec<ExternalDependency>, String> {
+    let file_path = resolve_document(&file_path)?;
+    dependency_watch::external_dependencies(&file_path)
         .await
         .map_err(|e| e.to_string())
 }
 
+/// Watch the document's external files, emitting `dependency-changed` with a stale-content warning when one changes
+#[tauri::command]
+fn watch_dependencies(app: tauri::AppHandle, file_path: String) -> Result<(), String> {
+    let file_path = resolve_document(&file_path)?;
+    let path = file_path.clone();
+    let task = tauri::async_runtime::spawn(async move {
+        dependency_watch::watch_dependencies(&path, DEPENDENCY_WATCH_INTERVAL, |change: DependencyChange| {
+            let _ = app.emit(DEPENDENCY_CHANGED_EVENT, &change);
+        })
+        .await;
+    });
+    // Watching the same document again restarts its watcher
+    if let Some(previous) = DEPENDENCY_WATCHERS.lock().unwrap().insert(file_path, task) {
+        previous.abort();
+    }
+    Ok(())
+}
+
+/// Stop watching the document's external files; returns whether a watcher was running
+#[tauri::command]
+fn unwatch_dependencies(file_path: String) -> bool {
+    let Ok(file_path) = resolve_document(&file_path) else {
+        return false;
+    };
+    match DEPENDENCY_WATCHERS.lock().unwrap().remove(&file_path) {
+        Some(task) => {
+            task.abort();
+            true
+        }
+        None => false,
+    }
+}
+
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
     tauri::Builder::default()
-        .plugin(tauri_plugin_opener::init())
+        .setup(|app| {
+            // Registered here rather than on the builder, where a plugin that fails to
+            // start (as on some Linux setups) aborts the app; its features are hidden instead
+            let opener = match app.handle().plugin(tauri_plugin_opener::init()) {
+                Ok(()) => true,
+                Err(e) => {
+                    eprintln!("Opener plugin unavailable: {}", e);
+                    false
+                }
+            };
+            let _ = OPENER_AVAILABLE.set(opener);
+            if let Some(path) = default_documents::document_from_args(std::env::args().skip(1)) {
+                let _ = OPENED_DOCUMENT.set(path.to_string_lossy().into_owned());
+            }
+            let env_file = Path::new(app_config::DEFAULT_ENV_FILE);
+            let (config, _) = app_config::reload_config(env_file)?;
+            usage_metrics::set_metrics_path(metrics_path(app.handle())?);
+            // Downloaded copies stay read-only across restarts
+            document_store::set_read_only_dir(&remote_dir(app.handle())?);
+            // A broken plugin setup should not keep the app from starting
+            if let Err(e) = plugins::load_plugins(&plugins_dir(app.handle())?) {
+                eprintln!("Failed to load plugins: {}", e);
+            }
+            if config.watch_env {
+                let handle = app.handle().clone();
+                tauri::async_runtime::spawn(async move {
+                    app_config::watch_config(Path::new(app_config::DEFAULT_ENV_FILE), ENV_WATCH_INTERVAL, |config| {
+                        let _ = handle.emit(CONFIG_CHANGED_EVENT, &config);
+                    })
+                    .await;
+                });
+            }
+            let handle = app.handle().clone();
+            tauri::async_runtime::spawn(async move {
+                if let Err(e) = emit_overdue_reminders(&handle).await {
+                    eprintln!("Failed to check for overdue reminders: {}", e);
+                }
+            });
+            Ok(())
+        })
         .invoke_handler(tauri::generate_handler![
             load_sections,
+            assemble_context,
+            save_assembly_snapshot,
+            list_assembly_snapshots,
+            load_assembly_snapshot,
+            compare_assembly_snapshots,
+            get_tags,
+            load_references,
+            validate_citations,
             load_flow_graph,
-            load_metadata
+            get_flow_navigation,
+            suggest_click_actions,
+            apply_click_actions,
+            load_flow_layout,
+            save_flow_layout,
+            compute_flow_layout,
+            simulate_flow,
+            get_budget_report,
+            load_metadata,
+            save_metadata,
+            export_section,
+            get_section_blocks,
+            update_section_block,
+            import_sections,
+            split_section,
+            merge_sections,
+            save_document,
+            is_document_dirty,
+            close_document,
+            load_workspace_metadata,
+            get_load_warnings,
+            publish_check,
+            apply_fixes,
+            get_config,
+            reload_config,
+            get_document_path,
+            list_default_documents,
+            get_settings,
+            get_setting,
+            set_setting,
+            store_secret,
+            get_secret,
+            delete_secret,
+            list_plugins,
+            set_plugin_enabled,
+            reload_plugins,
+            open_document_url,
+            fork_remote_document,
+            list_conflict_copies,
+            preview_conflict_merge,
+            merge_conflict_copy,
+            export_print_html,
+            print_document,
+            export_docx,
+            export_excalidraw,
+            export_calendar,
+            lint_mermaid,
+            set_node_metadata,
+            get_sections_for_node,
+            get_node_for_section,
+            apply_edits,
+            get_section_index,
+            get_change_journal,
+            compact_journal,
+            get_stats_history,
+            export_reading_order,
+            save_as_template,
+            list_templates,
+            get_variable_sheet,
+            apply_variable_sheet,
+            list_snippets,
+            save_snippet,
+            delete_snippet,
+            insert_snippet,
+            import_mermaid_from_text,
+            generate_checklist_from_flow,
+            get_section_dependencies,
+            suggest_section_type,
+            suggest_node_section_types,
+            find_duplicate_paragraphs,
+            get_external_dependencies,
+            watch_dependencies,
+            unwatch_dependencies,
+            find_documents_by_tag,
+            find_variable_usages_workspace,
+            get_workspace_graph,
+            get_backlinks,
+            delete_document,
+            delete_section,
+            list_trash,
+            restore_from_trash,
+            empty_trash,
+            encrypt_section,
+            unlock_section,
+            decrypt_section,
+            get_section_frontmatter,
+            get_effort_rollup,
+            check_flow_graph,
+            save_flow_graph,
+            open_document_safe_mode,
+            get_raw_document,
+            save_raw_document,
+            get_changes_since,
+            export_flow_json,
+            import_flow,
+            get_reminders,
+            update_section,
+            quick_capture,
+            record_section_view,
+            get_section_activity,
+            export_link_map,
+            export_html,
+            export_json,
+            import_json,
+            create_document,
+            create_scratch_document,
+            save_document_as,
+            open_document,
+            list_backups,
+            restore_backup,
+            compare_documents,
+            export_metrics,
+            get_capabilities
         ])
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .build(tauri::generate_context!())
+        .expect("error while building tauri application")
+        .run(handle_run_event);
+}
+
+/// macOS hands over documents opened with the app as events rather than arguments
+#[cfg(any(target_os = "macos", target_os = "ios"))]
+fn handle_run_event(app: &tauri::AppHandle, event: tauri::RunEvent) {
+    if let tauri::RunEvent::Opened { urls } = event {
+        let paths = urls.iter().filter_map(|url| url.to_file_path().ok());
+        for path in paths.filter(|path| default_documents::is_document_path(path)) {
+            let path = path.to_string_lossy().into_owned();
+            // Before the window asks for its document this is the startup document;
+            // afterwards the frontend opens it from the event
+            let _ = OPENED_DOCUMENT.set(path.clone());
+            let _ = app.emit(OPEN_DOCUMENT_EVENT, path);
+        }
+    }
 }
+
+#[cfg(not(any(target_os = "macos", target_os = "ios")))]
+fn handle_run_event(_app: &tauri::AppHandle, _event: tauri::RunEvent) {}
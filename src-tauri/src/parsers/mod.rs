@@ -1,5 +0,0 @@
-pub mod xml_parser;
-pub mod mermaid_parser;
-
-pub use xml_parser::*;
-pub use mermaid_parser::*;
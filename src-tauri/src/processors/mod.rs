@@ -1,3 +0,0 @@
-pub mod variable_resolver;
-
-pub use variable_resolver::*;
@@ -0,0 +1,50 @@
+//! Secrets (LLM keys, webhook tokens, encryption passwords) kept in the OS keychain
+//!
+//! Secrets are never written into documents or config files; documents and
+//! settings refer to them by name only.
+
+use keyring::Entry;
+
+/// Keychain service all secrets are stored under
+const SERVICE: &str = "flow-writer";
+
+/// Name looked up to find out whether the keychain answers at all
+const PROBE: &str = "flow-writer-probe";
+
+fn entry(name: &str) -> Result<Entry, String> {
+    let name = name.trim();
+    if name.is_empty() || name.chars().any(char::is_control) {
+        return Err(format!("Invalid secret name '{}'", name.escape_default()));
+    }
+    Entry::new(SERVICE, name).map_err(|e| e.to_string())
+}
+
+pub fn store(name: &str, value: &str) -> Result<(), String> {
+    if value.is_empty() {
+        return Err("Secret value must not be empty".to_string());
+    }
+    entry(name)?.set_password(value).map_err(|e| e.to_string())
+}
+
+/// The secret, or None if nothing is stored under the name
+pub fn get(name: &str) -> Result<Option<String>, String> {
+    match entry(name)?.get_password() {
+        Ok(value) => Ok(Some(value)),
+        Err(keyring::Error::NoEntry) => Ok(None),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+/// Remove the secret; returns whether one was stored
+pub fn delete(name: &str) -> Result<bool, String> {
+    match entry(name)?.delete_credential() {
+        Ok(()) => Ok(true),
+        Err(keyring::Error::NoEntry) => Ok(false),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+/// Whether the OS keychain can be reached; some Linux setups run no secret service
+pub fn is_available() -> bool {
+    matches!(Entry::new(SERVICE, PROBE).and_then(|entry| entry.get_password()), Ok(_) | Err(keyring::Error::NoEntry))
+}
@@ -1,3 +0,0 @@
-pub mod flow_service;
-
-pub use flow_service::*;
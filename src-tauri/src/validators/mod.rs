@@ -1 +0,0 @@
-pub mod schema_validator;